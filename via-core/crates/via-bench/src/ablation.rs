@@ -0,0 +1,114 @@
+//! Per-Detector Ablation
+//!
+//! Runs a scenario once with every built-in detector and once more with each
+//! detector switched off (`ProfileConfig::disabled_detectors`). The drop in
+//! F1 without a detector is its marginal contribution to accuracy; the drop
+//! in per-event latency is what it costs. A negative F1 contribution means
+//! the ensemble does better without the detector on this scenario.
+
+use crate::{BenchmarkConfig, BenchmarkResults, BenchmarkRunner};
+use serde::{Deserialize, Serialize};
+use via_core::signal::DetectorId;
+
+/// Accuracy and latency of one run
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AblationRun {
+    pub precision: f64,
+    pub recall: f64,
+    pub f1_score: f64,
+    pub avg_micros: f64,
+    pub p99_micros: f64,
+    pub throughput_eps: f64,
+}
+
+impl AblationRun {
+    fn from_results(r: &BenchmarkResults) -> Self {
+        Self {
+            precision: r.precision,
+            recall: r.recall,
+            f1_score: r.f1_score,
+            avg_micros: r.latency_micros.avg_micros,
+            p99_micros: r.latency_micros.p99_micros,
+            throughput_eps: r.throughput_eps,
+        }
+    }
+}
+
+/// One detector's run and its contribution relative to the full ensemble
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DetectorAblation {
+    pub detector: DetectorId,
+    pub name: String,
+    /// The run with this detector disabled
+    pub without: AblationRun,
+    /// Full-ensemble F1 minus F1 without the detector
+    pub f1_contribution: f64,
+    /// Full-ensemble mean latency minus mean latency without the detector (µs)
+    pub latency_contribution_micros: f64,
+}
+
+/// Full ablation output, detectors by descending F1 contribution
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AblationResults {
+    pub config: String,
+    pub baseline: AblationRun,
+    pub detectors: Vec<DetectorAblation>,
+}
+
+/// Run `base` with all detectors, then once without each built-in detector
+pub fn run_ablation(base: &BenchmarkConfig) -> Result<AblationResults, String> {
+    let run = |disabled: Vec<DetectorId>| {
+        let mut config = base.clone();
+        config.disabled_detectors = disabled;
+        Ok::<_, String>(AblationRun::from_results(
+            &BenchmarkRunner::new().run(config)?,
+        ))
+    };
+
+    let baseline = run(base.disabled_detectors.clone())?;
+    let mut detectors = DetectorId::ALL
+        .into_iter()
+        .filter(|detector| !base.disabled_detectors.contains(detector))
+        .map(|detector| {
+            let mut disabled = base.disabled_detectors.clone();
+            disabled.push(detector);
+            let without = run(disabled)?;
+            Ok(DetectorAblation {
+                detector,
+                name: detector.name().to_string(),
+                f1_contribution: baseline.f1_score - without.f1_score,
+                latency_contribution_micros: baseline.avg_micros - without.avg_micros,
+                without,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    detectors.sort_by(|a, b| b.f1_contribution.total_cmp(&a.f1_contribution));
+
+    Ok(AblationResults {
+        config: base.name.clone(),
+        baseline,
+        detectors,
+    })
+}
+
+/// Print each detector's marginal F1 and latency as a table
+pub fn print_ablation(results: &AblationResults) {
+    println!("\n╔══════════════════════════════════════════════════════════════╗");
+    println!("║                    PER-DETECTOR ABLATION                     ║");
+    println!("╠══════════════════════════════════════════════════════════════╣");
+    println!("║ Config: {:52} ║", results.config);
+    println!(
+        "║ All detectors: F1 {:>5.3} | avg {:>7.2}µs | p99 {:>7.2}µs      ║",
+        results.baseline.f1_score, results.baseline.avg_micros, results.baseline.p99_micros
+    );
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    println!("║ Without                |  F1   | ΔF1 contrib | Δavg µs  cost ║");
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    for d in &results.detectors {
+        println!(
+            "║ {:<22} | {:>5.3} | {:>+11.4} | {:>+13.3} ║",
+            d.name, d.without.f1_score, d.f1_contribution, d.latency_contribution_micros
+        );
+    }
+    println!("╚══════════════════════════════════════════════════════════════╝");
+}
@@ -0,0 +1,102 @@
+//! Registry Eviction Under Entity Churn
+//!
+//! Re-runs a per-service benchmark (normally the `entity_churn` preset) at
+//! several registry capacities. Each step records how often lookups hit a
+//! live profile, how many profiles were evicted and later re-created from
+//! scratch, and what that did to accuracy, so capacity can be sized against
+//! the churn a deployment actually sees.
+
+use crate::{BenchmarkConfig, BenchmarkResults, BenchmarkRunner};
+use serde::{Deserialize, Serialize};
+
+/// Registry behavior and accuracy at one capacity
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChurnSweepPoint {
+    pub max_profiles: usize,
+    /// Distinct entities seen (`service.name`s)
+    pub entities: usize,
+    pub creations: u64,
+    pub evictions: u64,
+    pub recreations: u64,
+    pub hit_rate: f64,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1_score: f64,
+    pub throughput_eps: f64,
+}
+
+impl ChurnSweepPoint {
+    fn from_results(max_profiles: usize, r: &BenchmarkResults) -> Self {
+        let registry = r.registry.clone().unwrap_or_default();
+        Self {
+            max_profiles,
+            entities: r.service_metrics.len(),
+            creations: registry.creations,
+            evictions: registry.evictions,
+            recreations: registry.recreations,
+            hit_rate: registry.hit_rate,
+            precision: r.precision,
+            recall: r.recall,
+            f1_score: r.f1_score,
+            throughput_eps: r.throughput_eps,
+        }
+    }
+}
+
+/// Full sweep output
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChurnSweepResults {
+    pub config: String,
+    pub points: Vec<ChurnSweepPoint>,
+}
+
+/// Run `base` in per-service mode once per registry capacity
+pub fn run_churn_sweep(
+    base: &BenchmarkConfig,
+    capacities: &[usize],
+) -> Result<ChurnSweepResults, String> {
+    let points = capacities
+        .iter()
+        .map(|&max_profiles| {
+            let mut config = base.clone();
+            config.name = format!("{} @ {} profiles", base.name, max_profiles);
+            config.per_service = true;
+            config.max_profiles = max_profiles;
+
+            let results = BenchmarkRunner::new().run(config)?;
+            Ok(ChurnSweepPoint::from_results(max_profiles, &results))
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(ChurnSweepResults {
+        config: base.name.clone(),
+        points,
+    })
+}
+
+/// Print eviction behavior and accuracy per capacity as a table
+pub fn print_churn_sweep(results: &ChurnSweepResults) {
+    println!("\n╔══════════════════════════════════════════════════════════════╗");
+    println!("║                 ENTITY CHURN / REGISTRY EVICTION             ║");
+    println!("╠══════════════════════════════════════════════════════════════╣");
+    println!("║ Config: {:52} ║", results.config);
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    println!("║ Capacity | Evictions | Re-created |  Hit %  | Recall |  F1   ║");
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    for p in &results.points {
+        println!(
+            "║ {:>8} | {:>9} | {:>10} | {:>6.2}% | {:>5.1}% | {:>5.3} ║",
+            p.max_profiles,
+            p.evictions,
+            p.recreations,
+            p.hit_rate * 100.0,
+            p.recall * 100.0,
+            p.f1_score
+        );
+    }
+    if let Some(entities) = results.points.first().map(|p| p.entities) {
+        println!("╠──────────────────────────────────────────────────────────────╣");
+        println!("║ Distinct entities: {:>10} {:>30} ║", entities, "");
+    }
+    println!("╚══════════════════════════════════════════════════════════════╝");
+}
@@ -0,0 +1,505 @@
+//! Result Comparison
+//!
+//! Diffs a candidate `BenchmarkResults` against a baseline metric by metric and
+//! flags regressions beyond a tolerance, so two runs (or two releases) can be
+//! compared without reading raw JSON.
+//!
+//! When both sides were run with `--repeats`, accuracy compares the means
+//! across seeds and a drop only regresses if it is statistically
+//! distinguishable (see `repeats::difference`).
+
+use crate::BenchmarkResults;
+use crate::repeats::{self, MetricSummary, RepeatSummary};
+use serde::{Deserialize, Serialize};
+
+/// How much a metric may worsen before it counts as a regression
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct CompareTolerance {
+    /// Absolute drop allowed in precision, recall and F1
+    pub max_accuracy_drop: f64,
+    /// Relative increase allowed in latency percentiles (%)
+    pub max_latency_increase_pct: f64,
+    /// Relative drop allowed in throughput (%)
+    pub max_throughput_drop_pct: f64,
+    /// Latency increases below this are never regressions (µs); latencies are
+    /// recorded in whole microseconds, so 1 → 2 µs is quantization, not +100%
+    pub min_latency_delta_micros: f64,
+    /// Absolute increase allowed in heap allocations per event, compared only
+    /// when both runs counted allocations
+    pub max_allocation_increase: f64,
+}
+
+impl Default for CompareTolerance {
+    fn default() -> Self {
+        Self {
+            max_accuracy_drop: 0.02,
+            max_latency_increase_pct: 20.0,
+            max_throughput_drop_pct: 20.0,
+            min_latency_delta_micros: 1.0,
+            max_allocation_increase: 0.05,
+        }
+    }
+}
+
+/// Change in one metric
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MetricDelta {
+    pub metric: String,
+    pub baseline: f64,
+    pub candidate: f64,
+    pub delta: f64,
+    /// Relative change (%), absent when the baseline is zero
+    pub delta_pct: Option<f64>,
+    pub higher_is_better: bool,
+    pub regression: bool,
+    /// Whether the change exceeds seed-to-seed noise, known only when both
+    /// sides have repeated runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distinguishable: Option<bool>,
+}
+
+/// Candidate vs baseline diff
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Comparison {
+    pub baseline: String,
+    pub candidate: String,
+    pub deltas: Vec<MetricDelta>,
+    pub regressed: bool,
+}
+
+impl Comparison {
+    pub fn regressions(&self) -> impl Iterator<Item = &MetricDelta> {
+        self.deltas.iter().filter(|d| d.regression)
+    }
+}
+
+enum Rule {
+    /// Higher is better, absolute drop limit
+    Accuracy,
+    /// Lower is better, relative increase limit
+    Latency,
+    /// Higher is better, relative drop limit
+    Throughput,
+    /// Lower is better, absolute increase limit
+    Allocations,
+}
+
+fn delta(
+    metric: &str,
+    baseline: f64,
+    candidate: f64,
+    rule: Rule,
+    tol: &CompareTolerance,
+) -> MetricDelta {
+    let change = candidate - baseline;
+    let delta_pct = (baseline != 0.0).then(|| change / baseline.abs() * 100.0);
+    let (higher_is_better, regression) = match rule {
+        Rule::Accuracy => (true, -change > tol.max_accuracy_drop),
+        Rule::Latency => (
+            false,
+            change >= tol.min_latency_delta_micros
+                && delta_pct.is_none_or(|pct| pct > tol.max_latency_increase_pct),
+        ),
+        Rule::Throughput => (
+            true,
+            delta_pct.is_some_and(|pct| -pct > tol.max_throughput_drop_pct),
+        ),
+        Rule::Allocations => (false, change > tol.max_allocation_increase),
+    };
+    MetricDelta {
+        metric: metric.to_string(),
+        baseline,
+        candidate,
+        delta: change,
+        delta_pct,
+        higher_is_better,
+        regression,
+        distinguishable: None,
+    }
+}
+
+/// Compare `candidate` against `baseline`
+pub fn compare(
+    baseline: &BenchmarkResults,
+    candidate: &BenchmarkResults,
+    tol: &CompareTolerance,
+) -> Comparison {
+    let (b, c) = (baseline, candidate);
+    let (bl, cl) = (&b.latency_micros, &c.latency_micros);
+    let repeated = b.repeats.as_ref().zip(c.repeats.as_ref());
+    type Pick = fn(&RepeatSummary) -> &MetricSummary;
+    let accuracy = |metric: &str, (base, cand): (f64, f64), pick: Pick| {
+        let Some((br, cr)) = repeated else {
+            return delta(metric, base, cand, Rule::Accuracy, tol);
+        };
+        let (bs, cs) = (pick(br), pick(cr));
+        let mut d = delta(metric, bs.mean, cs.mean, Rule::Accuracy, tol);
+        if let Some(diff) = repeats::difference(bs, cs) {
+            d.distinguishable = Some(diff.distinguishable);
+            d.regression &= diff.distinguishable;
+        }
+        d
+    };
+    let accuracy_deltas = [
+        accuracy("precision", (b.precision, c.precision), |r| &r.precision),
+        accuracy("recall", (b.recall, c.recall), |r| &r.recall),
+        accuracy("f1_score", (b.f1_score, c.f1_score), |r| &r.f1_score),
+    ];
+    let metrics = [
+        ("avg_micros", bl.avg_micros, cl.avg_micros, Rule::Latency),
+        ("p50_micros", bl.p50_micros, cl.p50_micros, Rule::Latency),
+        ("p95_micros", bl.p95_micros, cl.p95_micros, Rule::Latency),
+        ("p99_micros", bl.p99_micros, cl.p99_micros, Rule::Latency),
+        (
+            "throughput_eps",
+            b.throughput_eps,
+            c.throughput_eps,
+            Rule::Throughput,
+        ),
+    ];
+    let allocations = b
+        .allocations_per_event
+        .zip(c.allocations_per_event)
+        .map(|(base, cand)| ("allocations_per_event", base, cand, Rule::Allocations));
+    let deltas: Vec<MetricDelta> = accuracy_deltas
+        .into_iter()
+        .chain(
+            metrics
+                .into_iter()
+                .chain(allocations)
+                .map(|(metric, base, cand, rule)| delta(metric, base, cand, rule, tol)),
+        )
+        .collect();
+    Comparison {
+        baseline: b.config.clone(),
+        candidate: c.config.clone(),
+        regressed: deltas.iter().any(|d| d.regression),
+        deltas,
+    }
+}
+
+/// Pair each candidate with the baseline of the same config name, falling back
+/// to the only baseline when there is exactly one
+pub fn compare_all(
+    baselines: &[BenchmarkResults],
+    candidates: &[BenchmarkResults],
+    tol: &CompareTolerance,
+) -> Result<Vec<Comparison>, String> {
+    candidates
+        .iter()
+        .map(|candidate| {
+            let baseline = baselines
+                .iter()
+                .find(|b| b.config == candidate.config)
+                .or(if baselines.len() == 1 {
+                    baselines.first()
+                } else {
+                    None
+                })
+                .ok_or_else(|| format!("no baseline result for config '{}'", candidate.config))?;
+            Ok(compare(baseline, candidate, tol))
+        })
+        .collect()
+}
+
+/// Parse a percentage given as `20%` or `20`
+pub fn parse_percent(s: &str) -> Result<f64, String> {
+    let value: f64 = s
+        .trim()
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| format!("invalid percentage '{s}'"))?;
+    if value < 0.0 {
+        return Err(format!("percentage must not be negative: '{s}'"));
+    }
+    Ok(value)
+}
+
+/// Gate exit codes
+pub const GATE_PASS: i32 = 0;
+pub const GATE_REGRESSION: i32 = 1;
+pub const GATE_ERROR: i32 = 2;
+
+/// Verdict of a CI gate run
+#[derive(Clone, Debug)]
+pub enum GateOutcome {
+    Pass,
+    /// The gated metrics that regressed
+    Regression(Vec<MetricDelta>),
+    /// The gate could not run (unreadable baseline, failed run, ...)
+    Error(String),
+}
+
+impl GateOutcome {
+    /// Only F1 and P99 (plus throughput when `gate_throughput`) decide the
+    /// outcome; other regressions in `comparison` are context
+    pub fn from_comparison(comparison: &Comparison, gate_throughput: bool) -> Self {
+        let failures: Vec<_> = comparison
+            .regressions()
+            .filter(|d| {
+                d.metric == "f1_score"
+                    || d.metric == "p99_micros"
+                    || (gate_throughput && d.metric == "throughput_eps")
+            })
+            .cloned()
+            .collect();
+        if failures.is_empty() {
+            Self::Pass
+        } else {
+            Self::Regression(failures)
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Pass => GATE_PASS,
+            Self::Regression(_) => GATE_REGRESSION,
+            Self::Error(_) => GATE_ERROR,
+        }
+    }
+}
+
+/// Render comparisons as markdown tables
+pub fn comparison_markdown(comparisons: &[Comparison]) -> String {
+    let mut md = String::new();
+    for cmp in comparisons {
+        let status = if cmp.regressed { "REGRESSED" } else { "OK" };
+        md.push_str(&format!(
+            "### {} → {} ({})\n\n",
+            cmp.baseline, cmp.candidate, status
+        ));
+        md.push_str("| Metric | Baseline | Candidate | Delta | Delta % | |\n");
+        md.push_str("|--------|---------:|----------:|------:|--------:|-|\n");
+        for d in &cmp.deltas {
+            let pct = d
+                .delta_pct
+                .map(|p| format!("{:+.1}%", p))
+                .unwrap_or_else(|| "n/a".to_string());
+            md.push_str(&format!(
+                "| {} | {:.4} | {:.4} | {:+.4} | {} | {} |\n",
+                d.metric,
+                d.baseline,
+                d.candidate,
+                d.delta,
+                pct,
+                match (d.regression, d.distinguishable) {
+                    (true, _) => "❌",
+                    (false, Some(false)) => "≈",
+                    _ => "",
+                }
+            ));
+        }
+        if cmp.deltas.iter().any(|d| d.distinguishable == Some(false)) {
+            md.push_str(
+                "\n≈ within seed-to-seed noise (bootstrap CI of the difference includes 0)\n",
+            );
+        }
+        md.push('\n');
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LatencyMetrics;
+
+    fn result(config: &str, f1: f64, p99: f64, eps: f64) -> BenchmarkResults {
+        BenchmarkResults {
+            config: config.to_string(),
+            precision: f1,
+            recall: f1,
+            f1_score: f1,
+            latency_micros: LatencyMetrics {
+                p99_micros: p99,
+                ..Default::default()
+            },
+            throughput_eps: eps,
+            ..Default::default()
+        }
+    }
+
+    fn regressed_metrics(cmp: &Comparison) -> Vec<&str> {
+        cmp.regressions().map(|d| d.metric.as_str()).collect()
+    }
+
+    #[test]
+    fn test_within_tolerance_is_not_a_regression() {
+        let tol = CompareTolerance::default();
+        let base = result("quick", 0.80, 50.0, 10_000.0);
+        let cand = result("quick", 0.79, 55.0, 9_000.0);
+        let cmp = compare(&base, &cand, &tol);
+        assert!(!cmp.regressed, "{:?}", regressed_metrics(&cmp));
+    }
+
+    #[test]
+    fn test_flags_each_regressed_metric() {
+        let tol = CompareTolerance::default();
+        let base = result("quick", 0.80, 50.0, 10_000.0);
+        let cand = result("quick", 0.70, 80.0, 5_000.0);
+        let cmp = compare(&base, &cand, &tol);
+        assert!(cmp.regressed);
+        assert_eq!(
+            regressed_metrics(&cmp),
+            vec![
+                "precision",
+                "recall",
+                "f1_score",
+                "p99_micros",
+                "throughput_eps"
+            ]
+        );
+        let p99 = cmp
+            .deltas
+            .iter()
+            .find(|d| d.metric == "p99_micros")
+            .unwrap();
+        assert_eq!(p99.delta_pct, Some(60.0));
+    }
+
+    #[test]
+    fn test_repeated_drop_within_noise_is_not_a_regression() {
+        let tol = CompareTolerance::default();
+        let repeated = |config: &str, f1: &[f64]| {
+            let summary = MetricSummary::from_samples(f1.to_vec());
+            BenchmarkResults {
+                repeats: Some(RepeatSummary {
+                    runs: f1.len(),
+                    precision: summary.clone(),
+                    recall: summary.clone(),
+                    f1_score: summary,
+                    ..Default::default()
+                }),
+                ..result(config, f1[0], 50.0, 10_000.0)
+            }
+        };
+        let base = repeated("quick", &[0.80, 0.70, 0.85, 0.72, 0.78]);
+
+        // Single runs would flag the 0.10 drop in the first seed
+        let noisy = repeated("quick", &[0.70, 0.82, 0.74, 0.80, 0.71]);
+        assert!(
+            compare(
+                &result("quick", 0.80, 50.0, 1e4),
+                &result("quick", 0.70, 50.0, 1e4),
+                &tol
+            )
+            .regressed
+        );
+        let cmp = compare(&base, &noisy, &tol);
+        assert!(!cmp.regressed, "{:?}", regressed_metrics(&cmp));
+        assert_eq!(cmp.deltas[2].distinguishable, Some(false));
+        assert!(comparison_markdown(&[cmp]).contains("≈"));
+
+        let worse = repeated("quick", &[0.60, 0.58, 0.62, 0.61, 0.59]);
+        let cmp = compare(&base, &worse, &tol);
+        assert_eq!(cmp.deltas[2].distinguishable, Some(true));
+        assert!(cmp.regressed);
+    }
+
+    #[test]
+    fn test_latency_quantization_is_ignored() {
+        let tol = CompareTolerance::default();
+        // 0 → 0.9 µs is below the absolute floor
+        let cmp = compare(
+            &result("q", 0.8, 0.0, 1.0),
+            &result("q", 0.8, 0.9, 1.0),
+            &tol,
+        );
+        assert!(!cmp.regressed);
+        // Starting from zero, a real increase still regresses
+        let cmp = compare(
+            &result("q", 0.8, 0.0, 1.0),
+            &result("q", 0.8, 5.0, 1.0),
+            &tol,
+        );
+        assert_eq!(regressed_metrics(&cmp), vec!["p99_micros"]);
+    }
+
+    #[test]
+    fn test_allocations_compared_only_when_both_counted() {
+        let tol = CompareTolerance::default();
+        let base = result("q", 0.8, 10.0, 1.0);
+        let mut cand = result("q", 0.8, 10.0, 1.0);
+        cand.allocations_per_event = Some(3.0);
+        let cmp = compare(&base, &cand, &tol);
+        assert!(
+            cmp.deltas
+                .iter()
+                .all(|d| d.metric != "allocations_per_event")
+        );
+
+        let base = BenchmarkResults {
+            allocations_per_event: Some(0.0),
+            ..base
+        };
+        assert_eq!(
+            regressed_metrics(&compare(&base, &cand, &tol)),
+            vec!["allocations_per_event"]
+        );
+        cand.allocations_per_event = Some(0.01);
+        assert!(!compare(&base, &cand, &tol).regressed);
+    }
+
+    #[test]
+    fn test_compare_all_pairs_by_config() {
+        let tol = CompareTolerance::default();
+        let baselines = vec![
+            result("quick", 0.8, 10.0, 1.0),
+            result("mixed", 0.5, 10.0, 1.0),
+        ];
+        let candidates = vec![result("mixed", 0.5, 10.0, 1.0)];
+        let cmps = compare_all(&baselines, &candidates, &tol).unwrap();
+        assert_eq!(cmps[0].baseline, "mixed");
+        assert!(!cmps[0].regressed);
+
+        let unknown = vec![result("security", 0.5, 10.0, 1.0)];
+        assert!(compare_all(&baselines, &unknown, &tol).is_err());
+    }
+
+    #[test]
+    fn test_gate_counts_only_f1_p99_and_opted_in_throughput() {
+        let tol = CompareTolerance::default();
+        let base = result("quick", 0.80, 50.0, 10_000.0);
+        let gate = |cand: &BenchmarkResults, throughput: bool| {
+            GateOutcome::from_comparison(&compare(&base, cand, &tol), throughput)
+        };
+        let failed = |outcome: GateOutcome| match outcome {
+            GateOutcome::Regression(deltas) => deltas.into_iter().map(|d| d.metric).collect(),
+            _ => Vec::new(),
+        };
+
+        let same = gate(&base, true);
+        assert!(matches!(same, GateOutcome::Pass));
+        assert_eq!(same.exit_code(), GATE_PASS);
+
+        // Precision, recall and p50 alone never fail the gate
+        let mut cand = result("quick", 0.80, 50.0, 10_000.0);
+        cand.precision = 0.5;
+        cand.recall = 0.5;
+        cand.latency_micros.p50_micros = 500.0;
+        assert!(compare(&base, &cand, &tol).regressed);
+        assert_eq!(gate(&cand, true).exit_code(), GATE_PASS);
+
+        let worse = result("quick", 0.70, 80.0, 10_000.0);
+        assert_eq!(gate(&worse, false).exit_code(), GATE_REGRESSION);
+        assert_eq!(failed(gate(&worse, false)), ["f1_score", "p99_micros"]);
+
+        let slower = result("quick", 0.80, 50.0, 5_000.0);
+        assert_eq!(gate(&slower, false).exit_code(), GATE_PASS);
+        assert_eq!(failed(gate(&slower, true)), ["throughput_eps"]);
+
+        let error = GateOutcome::Error("no baseline".to_string());
+        assert_eq!(error.exit_code(), GATE_ERROR);
+    }
+
+    #[test]
+    fn test_parse_percent() {
+        assert_eq!(parse_percent("20%"), Ok(20.0));
+        assert_eq!(parse_percent(" 20 "), Ok(20.0));
+        assert_eq!(parse_percent("0.5%"), Ok(0.5));
+        assert!(parse_percent("-5%").unwrap_err().contains("negative"));
+        assert!(parse_percent("lots").unwrap_err().contains("invalid"));
+        assert!(parse_percent("%").is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! Cost Estimation
+//!
+//! Platform teams pick detectors on cost as well as accuracy. Every run
+//! reports [`CostMetrics`]: the CPU-seconds detection took, summed over the
+//! timed spans behind the per-event latency (warmup included, simulation
+//! left out), and per event, which in µs is also CPU-seconds per million
+//! events. A [`CostModel`] prices them: the cost of a CPU-hour gives the
+//! cost per million events, and the power draw of a busy core the energy per
+//! million events. Detection runs on one thread, so its time is CPU time.
+
+use serde::{Deserialize, Serialize};
+
+/// Price and power of a core, each optional
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct CostModel {
+    /// Cost of one CPU-hour, in any currency (e.g. 0.04 for a cloud vCPU in
+    /// USD)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_hour_cost: Option<f64>,
+    /// Power draw of one busy core (W)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watts_per_core: Option<f64>,
+}
+
+/// Estimated detection cost of a run
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct CostMetrics {
+    pub cpu_seconds: f64,
+    /// Also CPU-seconds per million events
+    pub cpu_micros_per_event: f64,
+    /// With `CostModel::cpu_hour_cost`, in its currency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_per_million_events: Option<f64>,
+    /// With `CostModel::watts_per_core`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub joules_per_million_events: Option<f64>,
+}
+
+impl CostMetrics {
+    /// Cost of `events` events detected in `busy_ns` of CPU time
+    pub fn estimate(busy_ns: u64, events: u64, model: &CostModel) -> Self {
+        let cpu_seconds = busy_ns as f64 / 1e9;
+        // µs per event = CPU-seconds per million events
+        let per_million = cpu_seconds * 1e6 / events.max(1) as f64;
+        Self {
+            cpu_seconds,
+            cpu_micros_per_event: per_million,
+            cost_per_million_events: model.cpu_hour_cost.map(|cost| per_million / 3600.0 * cost),
+            joules_per_million_events: model.watts_per_core.map(|watts| per_million * watts),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BenchmarkConfig, BenchmarkRunner};
+
+    #[test]
+    fn test_estimate_prices_cpu_time() {
+        let model = CostModel {
+            cpu_hour_cost: Some(0.036),
+            watts_per_core: Some(10.0),
+        };
+        // 2 s for 400k events: 5 µs each, 5 CPU-seconds per million
+        let cost = CostMetrics::estimate(2_000_000_000, 400_000, &model);
+        assert_eq!(cost.cpu_seconds, 2.0);
+        assert_eq!(cost.cpu_micros_per_event, 5.0);
+        assert!((cost.cost_per_million_events.unwrap() - 0.00005).abs() < 1e-12);
+        assert_eq!(cost.joules_per_million_events, Some(50.0));
+
+        let unpriced = CostMetrics::estimate(2_000_000_000, 400_000, &CostModel::default());
+        assert_eq!(unpriced.cost_per_million_events, None);
+        assert_eq!(CostMetrics::estimate(0, 0, &model).cpu_seconds, 0.0);
+    }
+
+    #[test]
+    fn test_run_reports_cost_from_detection_time() {
+        let results = BenchmarkRunner::new()
+            .run(BenchmarkConfig {
+                duration_secs: 10,
+                cost_model: CostModel {
+                    cpu_hour_cost: Some(0.05),
+                    watts_per_core: None,
+                },
+                batch_size: 100,
+                quiet: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let cost = &results.cost;
+        assert!(cost.cpu_seconds > 0.0);
+        assert!(cost.cpu_micros_per_event > 0.0);
+        assert!(cost.cost_per_million_events.unwrap() > 0.0);
+        assert!(cost.joules_per_million_events.is_none());
+    }
+}
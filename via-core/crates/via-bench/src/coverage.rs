@@ -0,0 +1,226 @@
+//! Per-Scenario Coverage Matrix
+//!
+//! Runs one benchmark per via-sim anomaly scenario, each injected once into
+//! the middle of the same baseline traffic, and tabulates the F1 of every
+//! detector per anomaly type next to the ensemble's. Rows show which anomaly
+//! types the ensemble misses; columns show which detectors carry which
+//! types, and which carry none. Absence scenarios (`service_silence`) emit
+//! no anomalous events, so their F1 is 0 by construction; their time to
+//! detect is the one to read.
+
+use crate::{AnomalySpec, BenchmarkConfig, BenchmarkResults, BenchmarkRunner};
+use serde::{Deserialize, Serialize};
+use via_core::signal::DetectorId;
+use via_sim::scenarios::{create_scenario, list_scenarios};
+
+/// Detector F1 below this is shown as `·` in the matrix
+pub const COVERED_F1: f64 = 0.05;
+
+/// Accuracy on one anomaly scenario
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScenarioCoverage {
+    pub scenario: String,
+    pub anomaly_events: u64,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1_score: f64,
+    /// Mean time from the anomaly's start to its first detection, absent
+    /// when it went undetected
+    pub time_to_detect_ms: Option<f64>,
+    /// F1 of each detector, in `CoverageResults::detectors` order
+    pub detector_f1: Vec<f64>,
+    /// Detector with the highest F1, absent when none reached `COVERED_F1`
+    pub best_detector: Option<DetectorId>,
+}
+
+impl ScenarioCoverage {
+    fn from_results(scenario: &str, detectors: &[DetectorId], r: &BenchmarkResults) -> Self {
+        let detector_f1: Vec<f64> = detectors
+            .iter()
+            .map(|id| {
+                r.detector_metrics
+                    .get(id.name())
+                    .map_or(0.0, |m| m.f1_score)
+            })
+            .collect();
+        let best_detector = detectors
+            .iter()
+            .zip(&detector_f1)
+            .filter(|(_, f1)| **f1 >= COVERED_F1)
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(id, _)| *id);
+        let ttd = r.absence.as_ref().unwrap_or(&r.time_to_detect);
+        Self {
+            scenario: scenario.to_string(),
+            anomaly_events: r.total_anomaly_events,
+            precision: r.precision,
+            recall: r.recall,
+            f1_score: r.f1_score,
+            time_to_detect_ms: (ttd.detected > 0).then_some(ttd.mean_ms),
+            detector_f1,
+            best_detector,
+        }
+    }
+}
+
+/// Detector F1 per anomaly scenario
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CoverageResults {
+    pub base_scenario: String,
+    pub duration_secs: u64,
+    pub anomaly_secs: u64,
+    /// Matrix columns: the detectors that ran
+    pub detectors: Vec<DetectorId>,
+    pub scenarios: Vec<ScenarioCoverage>,
+    /// Mean F1 of each detector over the scenarios
+    pub mean_detector_f1: Vec<f64>,
+    pub mean_f1: f64,
+}
+
+/// Every via-sim scenario that injects anomalies, in `via-sim list` order
+pub fn anomaly_scenarios() -> Vec<String> {
+    list_scenarios()
+        .into_iter()
+        .filter(|(name, _)| create_scenario(name).is_some_and(|s| !s.is_baseline()))
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Run `base` once per scenario, each injected for `anomaly_secs` in the
+/// middle of the run in place of `base`'s anomalies
+pub fn run_coverage(
+    base: &BenchmarkConfig,
+    scenarios: &[String],
+    anomaly_secs: u64,
+) -> Result<CoverageResults, String> {
+    let duration_secs = base.duration_ns() / 1_000_000_000;
+    let anomaly_secs = anomaly_secs.clamp(1, duration_secs.max(1));
+    let detectors = base.enabled_detectors();
+
+    let mut rows = Vec::new();
+    for (i, scenario) in scenarios.iter().enumerate() {
+        let mut config = base.clone();
+        config.name = format!("{} + {}", base.base_scenario, scenario);
+        config.anomalies = vec![AnomalySpec {
+            scenario: scenario.clone(),
+            start_time_sec: (duration_secs - anomaly_secs) / 2,
+            duration_sec: anomaly_secs,
+        }];
+        config.quiet = true;
+        let results = BenchmarkRunner::new().run(config)?;
+        let row = ScenarioCoverage::from_results(scenario, &detectors, &results);
+        if !base.quiet {
+            println!(
+                "  [{:>2}/{}] {:<24} F1 {:.3}",
+                i + 1,
+                scenarios.len(),
+                scenario,
+                row.f1_score
+            );
+        }
+        rows.push(row);
+    }
+
+    let mean = |f1: &dyn Fn(&ScenarioCoverage) -> f64| {
+        rows.iter().map(f1).sum::<f64>() / rows.len().max(1) as f64
+    };
+    let mean_detector_f1 = (0..detectors.len())
+        .map(|d| mean(&|row| row.detector_f1[d]))
+        .collect();
+    Ok(CoverageResults {
+        base_scenario: base.base_scenario.clone(),
+        duration_secs,
+        anomaly_secs,
+        mean_f1: mean(&|row| row.f1_score),
+        detectors,
+        scenarios: rows,
+        mean_detector_f1,
+    })
+}
+
+/// Coverage matrix as a markdown table: a row per scenario, the ensemble
+/// then a column per detector
+pub fn coverage_markdown(results: &CoverageResults) -> String {
+    let cell = |f1: f64| {
+        if f1 < COVERED_F1 {
+            "·".to_string()
+        } else {
+            format!("{:.2}", f1)
+        }
+    };
+    let mut md = format!(
+        "### Detector F1 per anomaly scenario ({} + each, {}s of {}s)\n\n",
+        results.base_scenario, results.anomaly_secs, results.duration_secs
+    );
+    md.push_str("| Scenario | F1 | TTD (ms) |");
+    for id in &results.detectors {
+        md.push_str(&format!(" {} |", id));
+    }
+    md.push_str(" Best |\n|----------|---:|---------:|");
+    md.push_str(&"----:|".repeat(results.detectors.len()));
+    md.push_str("------|\n");
+
+    for row in &results.scenarios {
+        let ttd = row
+            .time_to_detect_ms
+            .map_or("-".to_string(), |ms| format!("{:.0}", ms));
+        md.push_str(&format!(
+            "| {} | {:.3} | {} |",
+            row.scenario, row.f1_score, ttd
+        ));
+        for f1 in &row.detector_f1 {
+            md.push_str(&format!(" {} |", cell(*f1)));
+        }
+        let best = row
+            .best_detector
+            .map_or("-".to_string(), |id| id.to_string());
+        md.push_str(&format!(" {} |\n", best));
+    }
+
+    md.push_str(&format!("| **mean** | {:.3} | |", results.mean_f1));
+    for f1 in &results.mean_detector_f1 {
+        md.push_str(&format!(" {} |", cell(*f1)));
+    }
+    md.push_str(" |\n");
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anomaly_scenarios_leave_out_baselines() {
+        let scenarios = anomaly_scenarios();
+        assert!(scenarios.iter().any(|s| s == "ddos"));
+        assert!(scenarios.iter().any(|s| s == "service_silence"));
+        assert!(!scenarios.iter().any(|s| s == "normal_traffic"));
+        assert!(!scenarios.iter().any(|s| s == "checkout_funnel"));
+    }
+
+    #[test]
+    fn test_matrix_has_a_row_per_scenario() {
+        let mut base = BenchmarkConfig {
+            duration_secs: 60,
+            quiet: true,
+            ..Default::default()
+        };
+        base.select_detectors(&[DetectorId::Volume, DetectorId::Burst]);
+        let scenarios = ["traffic_spike".to_string(), "error_spike".to_string()];
+        let results = run_coverage(&base, &scenarios, 20).unwrap();
+
+        assert_eq!(results.detectors, [DetectorId::Volume, DetectorId::Burst]);
+        assert_eq!(results.scenarios.len(), 2);
+        for row in &results.scenarios {
+            assert!(row.anomaly_events > 0, "{}", row.scenario);
+            assert_eq!(row.detector_f1.len(), 2);
+        }
+        let spike = &results.scenarios[0];
+        assert!(spike.f1_score > 0.0);
+        assert!(spike.best_detector.is_some());
+
+        let md = coverage_markdown(&results);
+        assert!(md.contains("| Scenario | F1 | TTD (ms) | volume | burst | Best |"));
+        assert_eq!(md.lines().filter(|l| l.starts_with("| ")).count(), 4);
+    }
+}
@@ -0,0 +1,313 @@
+//! ROC, Precision-Recall and Calibration Curves
+//!
+//! The engine's boolean decision hides how well the ensemble score separates
+//! anomalous from normal events. Sweeping the decision threshold over the
+//! recorded scores offline yields the full ROC and PR curves, summarized by
+//! AUC and AUPRC, independent of the threshold the engine happens to use.
+//!
+//! The calibration curve shows how far the score is from a probability: it
+//! fits the engine's feedback calibrator to the run's ground truth and
+//! compares Brier scores before and after.
+//!
+//! A run does not keep its scores: it counts them in a [`ScoreHistogram`] of
+//! `SCORE_BINS` bins over [0, 1], so its curves sweep thresholds at bin edges.
+//! `compute_curves` and `compute_calibration` take exact `(score, label)`
+//! pairs instead.
+
+use serde::{Deserialize, Serialize};
+use via_core::algo::calibration::{CalibrationCurve, CalibrationMethod, ScoreCalibrator};
+
+/// Default number of curve points kept in exported results
+pub const DEFAULT_CURVE_POINTS: usize = 101;
+
+/// Bins of a run's `ScoreHistogram` (a multiple of the calibrator's bins)
+pub const SCORE_BINS: usize = 1000;
+
+/// Operating point at one decision threshold (`score >= threshold` fires)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct CurvePoint {
+    pub threshold: f64,
+    /// True positive rate (= recall)
+    pub tpr: f64,
+    pub fpr: f64,
+    pub precision: f64,
+}
+
+/// ROC / PR summary over all thresholds
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ThresholdCurves {
+    /// Area under the ROC curve
+    pub auc: f64,
+    /// Area under the precision-recall curve (average precision)
+    pub auprc: f64,
+    /// Operating points, highest threshold first (downsampled)
+    pub points: Vec<CurvePoint>,
+}
+
+/// Ensemble score read as a probability, before and after calibration
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CalibrationReport {
+    /// Mean squared error of the raw score against the 0/1 label
+    pub brier_raw: f64,
+    /// Same for the calibrated probability; fit and scored on the same run,
+    /// so optimistic
+    pub brier_calibrated: f64,
+    pub curve: CalibrationCurve,
+}
+
+/// Fit a calibrator to every `(score, label)` pair.
+///
+/// Returns `None` when the labels are too few or contain only one class.
+pub fn compute_calibration(scored: &[(f64, bool)]) -> Option<CalibrationReport> {
+    let weighted: Vec<(f64, bool, f64)> = scored
+        .iter()
+        .filter(|(s, _)| !s.is_nan())
+        .map(|&(score, label)| (score, label, 1.0))
+        .collect();
+    calibrate(&weighted)
+}
+
+/// Calibration over `(score, label, weight)` triples
+fn calibrate(weighted: &[(f64, bool, f64)]) -> Option<CalibrationReport> {
+    let calibrator = ScoreCalibrator::fit_weighted(weighted.iter().copied());
+    if calibrator.method() == CalibrationMethod::Uncalibrated {
+        return None;
+    }
+
+    let total: f64 = weighted.iter().map(|&(_, _, weight)| weight).sum();
+    let brier = |probability: &dyn Fn(f64) -> f64| {
+        weighted
+            .iter()
+            .map(|&(score, label, weight)| {
+                weight * (probability(score) - label as u8 as f64).powi(2)
+            })
+            .sum::<f64>()
+            / total
+    };
+    Some(CalibrationReport {
+        brier_raw: brier(&|score| score.clamp(0.0, 1.0)),
+        brier_calibrated: brier(&|score| calibrator.probability(score).unwrap_or(score)),
+        curve: calibrator.curve(),
+    })
+}
+
+/// Sweep every distinct score as a threshold.
+///
+/// Returns `None` when the labels contain only one class, where neither curve
+/// is defined. At most `max_points` points are kept; the areas are always
+/// computed over every threshold.
+pub fn compute_curves(scored: &[(f64, bool)], max_points: usize) -> Option<ThresholdCurves> {
+    let mut sorted: Vec<(f64, bool)> = scored
+        .iter()
+        .filter(|(s, _)| !s.is_nan())
+        .copied()
+        .collect();
+    sorted.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    // Tied scores cross the threshold together
+    let groups: Vec<(f64, u64, u64)> = sorted
+        .chunk_by(|a, b| a.0 == b.0)
+        .map(|tied| {
+            let positives = tied.iter().filter(|(_, label)| *label).count() as u64;
+            (tied[0].0, positives, tied.len() as u64 - positives)
+        })
+        .collect();
+    sweep(&groups, max_points)
+}
+
+/// Sweep `(threshold, positives, negatives)` groups, highest threshold
+/// first, each holding the events that start firing at its threshold
+fn sweep(groups: &[(f64, u64, u64)], max_points: usize) -> Option<ThresholdCurves> {
+    let positives = groups.iter().map(|g| g.1).sum::<u64>() as f64;
+    let negatives = groups.iter().map(|g| g.2).sum::<u64>() as f64;
+    if positives == 0.0 || negatives == 0.0 {
+        return None;
+    }
+
+    let mut all_points = Vec::new();
+    let (mut tp, mut fp) = (0.0, 0.0);
+    let (mut auc, mut auprc) = (0.0, 0.0);
+    let (mut prev_tpr, mut prev_fpr) = (0.0, 0.0);
+
+    for &(threshold, group_tp, group_fp) in groups {
+        tp += group_tp as f64;
+        fp += group_fp as f64;
+
+        let tpr = tp / positives;
+        let fpr = fp / negatives;
+        let precision = tp / (tp + fp);
+        auc += (fpr - prev_fpr) * (tpr + prev_tpr) / 2.0;
+        auprc += (tpr - prev_tpr) * precision;
+        prev_tpr = tpr;
+        prev_fpr = fpr;
+
+        all_points.push(CurvePoint {
+            threshold,
+            tpr,
+            fpr,
+            precision,
+        });
+    }
+
+    Some(ThresholdCurves {
+        auc,
+        auprc,
+        points: downsample(all_points, max_points),
+    })
+}
+
+/// Ensemble scores counted per label in `SCORE_BINS` equal bins over [0, 1]
+#[derive(Clone, Debug)]
+pub struct ScoreHistogram {
+    positives: Vec<u64>,
+    negatives: Vec<u64>,
+}
+
+impl Default for ScoreHistogram {
+    fn default() -> Self {
+        Self {
+            positives: vec![0; SCORE_BINS],
+            negatives: vec![0; SCORE_BINS],
+        }
+    }
+}
+
+impl ScoreHistogram {
+    /// Count one score (NaN is skipped; out-of-range scores are clamped)
+    pub fn add(&mut self, score: f64, label: bool) {
+        if score.is_nan() {
+            return;
+        }
+        let bin = ((score.clamp(0.0, 1.0) * SCORE_BINS as f64) as usize).min(SCORE_BINS - 1);
+        if label {
+            self.positives[bin] += 1;
+        } else {
+            self.negatives[bin] += 1;
+        }
+    }
+
+    /// `compute_curves` with each bin's lower edge as its threshold
+    pub fn curves(&self, max_points: usize) -> Option<ThresholdCurves> {
+        let groups: Vec<(f64, u64, u64)> = (0..SCORE_BINS)
+            .rev()
+            .filter(|&i| self.positives[i] + self.negatives[i] > 0)
+            .map(|i| {
+                let threshold = i as f64 / SCORE_BINS as f64;
+                (threshold, self.positives[i], self.negatives[i])
+            })
+            .collect();
+        sweep(&groups, max_points)
+    }
+
+    /// `compute_calibration` with each bin's scores at its center
+    pub fn calibration(&self) -> Option<CalibrationReport> {
+        let weighted: Vec<(f64, bool, f64)> = (0..SCORE_BINS)
+            .flat_map(|i| {
+                let center = (i as f64 + 0.5) / SCORE_BINS as f64;
+                [
+                    (center, true, self.positives[i] as f64),
+                    (center, false, self.negatives[i] as f64),
+                ]
+            })
+            .filter(|&(_, _, weight)| weight > 0.0)
+            .collect();
+        calibrate(&weighted)
+    }
+}
+
+/// Keep `max_points` evenly spaced points, always including both ends
+fn downsample(points: Vec<CurvePoint>, max_points: usize) -> Vec<CurvePoint> {
+    let max_points = max_points.max(2);
+    if points.len() <= max_points {
+        return points;
+    }
+    let last = points.len() - 1;
+    (0..max_points)
+        .map(|k| points[k * last / (max_points - 1)])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perfect_separation() {
+        let scored = vec![(0.9, true), (0.8, true), (0.3, false), (0.1, false)];
+        let curves = compute_curves(&scored, DEFAULT_CURVE_POINTS).unwrap();
+        assert!((curves.auc - 1.0).abs() < 1e-12);
+        assert!((curves.auprc - 1.0).abs() < 1e-12);
+        assert_eq!(curves.points.len(), 4);
+        assert_eq!(curves.points[0].threshold, 0.9);
+        assert_eq!(curves.points[3].fpr, 1.0);
+    }
+
+    #[test]
+    fn test_inverted_and_tied_scores() {
+        let inverted = vec![(0.1, true), (0.2, true), (0.8, false), (0.9, false)];
+        let curves = compute_curves(&inverted, DEFAULT_CURVE_POINTS).unwrap();
+        assert!(curves.auc.abs() < 1e-12);
+        // Recall 0.5 at precision 1/3, then recall 1.0 at precision 1/2
+        assert!((curves.auprc - 5.0 / 12.0).abs() < 1e-12);
+
+        // A constant score carries no information: the ROC is the diagonal
+        let tied = vec![(0.5, true), (0.5, false), (0.5, false), (0.5, false)];
+        let curves = compute_curves(&tied, DEFAULT_CURVE_POINTS).unwrap();
+        assert!((curves.auc - 0.5).abs() < 1e-12);
+        assert!((curves.auprc - 0.25).abs() < 1e-12);
+        assert_eq!(curves.points.len(), 1);
+    }
+
+    #[test]
+    fn test_single_class_has_no_curve() {
+        assert!(compute_curves(&[(0.4, false), (0.6, false)], 10).is_none());
+        assert!(compute_curves(&[], 10).is_none());
+    }
+
+    #[test]
+    fn test_calibration_improves_overconfident_scores() {
+        // Scores near 1.0 that are anomalous only a fifth of the time
+        let scored: Vec<(f64, bool)> = (0..1000)
+            .map(|i| (0.8 + (i % 20) as f64 / 100.0, i % 5 == 0))
+            .chain((0..1000).map(|i| ((i % 20) as f64 / 100.0, false)))
+            .collect();
+        let report = compute_calibration(&scored).unwrap();
+        assert!(report.brier_calibrated < report.brier_raw / 2.0);
+        let top = report.curve.points.last().unwrap();
+        assert!((top.probability - 0.2).abs() < 0.05, "{:?}", top);
+
+        assert!(compute_calibration(&[(0.9, true), (0.1, false)]).is_none());
+    }
+
+    #[test]
+    fn test_downsampling_keeps_ends_and_exact_area() {
+        let scored: Vec<(f64, bool)> = (0..1000).map(|i| (i as f64 / 1000.0, i % 3 == 0)).collect();
+        let full = compute_curves(&scored, usize::MAX).unwrap();
+        let small = compute_curves(&scored, 11).unwrap();
+        assert_eq!(small.points.len(), 11);
+        assert_eq!(small.points.first(), full.points.first());
+        assert_eq!(small.points.last(), full.points.last());
+        assert_eq!(small.auc, full.auc);
+    }
+
+    #[test]
+    fn test_histogram_matches_exact_curves_at_bin_edges() {
+        // Scores already on bin edges lose nothing to binning
+        let scored: Vec<(f64, bool)> = (0..1000).map(|i| (i as f64 / 1000.0, i % 3 == 0)).collect();
+        let mut histogram = ScoreHistogram::default();
+        for &(score, label) in &scored {
+            histogram.add(score, label);
+        }
+        let exact = compute_curves(&scored, DEFAULT_CURVE_POINTS).unwrap();
+        let binned = histogram.curves(DEFAULT_CURVE_POINTS).unwrap();
+        assert!((exact.auc - binned.auc).abs() < 1e-12);
+        assert!((exact.auprc - binned.auprc).abs() < 1e-12);
+        assert_eq!(exact.points, binned.points);
+
+        let exact = compute_calibration(&scored).unwrap();
+        let binned = histogram.calibration().unwrap();
+        assert_eq!(exact.curve.method, binned.curve.method);
+        assert!((exact.brier_raw - binned.brier_raw).abs() < 1e-3);
+        assert!(ScoreHistogram::default().curves(10).is_none());
+    }
+}
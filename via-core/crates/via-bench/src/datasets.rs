@@ -0,0 +1,383 @@
+//! Public Benchmark Datasets
+//!
+//! Loads labeled public anomaly detection datasets as `LogRecord` streams with
+//! ground truth, so `via-bench run --dataset <name>` scores the detectors on
+//! real data next to the synthetic scenarios:
+//! - `nab`, `nab/<category>`: Numenta Anomaly Benchmark checkout
+//!   (`data/<category>/*.csv`, `labels/combined_windows.json`)
+//! - `yahoo-a1` .. `yahoo-a4`: Yahoo Webscope S5 (`A1Benchmark/*.csv`, ...)
+//! - `smd`, `smd/<machine>`: Server Machine Dataset test split
+//!   (`test/<machine>.txt`, `test_label/<machine>.txt`)
+//!
+//! Every series becomes one `service.name` with its samples in the `value`
+//! attribute; SMD rows also carry each metric as `dim.<i>`. A contiguous run
+//! of labeled samples is one ground-truth window.
+
+use chrono::NaiveDateTime;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use via_sim::{KeyValue, LogRecord};
+
+/// Attribute holding each sample's value
+pub const VALUE_ATTR: &str = "value";
+
+/// Timestamp format of NAB data files and label windows
+const NAB_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+
+/// SMD samples are one minute apart
+const SMD_STEP_NS: u64 = 60_000_000_000;
+
+/// A public dataset, or a slice of one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dataset {
+    /// Numenta Anomaly Benchmark, optionally one data category
+    Nab { category: Option<String> },
+    /// Yahoo S5 benchmark 1 to 4 (A1 real traffic, A2-A4 synthetic)
+    Yahoo { benchmark: u8 },
+    /// Server Machine Dataset, optionally one machine
+    Smd { machine: Option<String> },
+}
+
+impl Dataset {
+    /// Directory looked up under `datasets/` when no data dir is given
+    pub fn default_dir(&self) -> PathBuf {
+        let name = match self {
+            Self::Nab { .. } => "nab",
+            Self::Yahoo { .. } => "yahoo",
+            Self::Smd { .. } => "smd",
+        };
+        Path::new("datasets").join(name)
+    }
+
+    /// Every labeled sample under `root`
+    pub fn load(&self, root: &Path) -> Result<Vec<LogRecord>, String> {
+        match self {
+            Self::Nab { category } => load_nab(root, category.as_deref()),
+            Self::Yahoo { benchmark } => load_yahoo(root, *benchmark),
+            Self::Smd { machine } => load_smd(root, machine.as_deref()),
+        }
+    }
+}
+
+impl FromStr for Dataset {
+    type Err = String;
+
+    /// `nab`, `nab/<category>`, `yahoo-a1` .. `yahoo-a4`, `smd`, `smd/<machine>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, part) = match s.split_once('/') {
+            Some((name, part)) => (name, Some(part.to_string())),
+            None => (s, None),
+        };
+        // Category and machine names are case-sensitive file names
+        match (name.to_ascii_lowercase().as_str(), part) {
+            ("nab", category) => Ok(Self::Nab { category }),
+            ("smd", machine) => Ok(Self::Smd { machine }),
+            (yahoo, None) if yahoo.starts_with("yahoo-a") => match &yahoo["yahoo-a".len()..] {
+                n @ ("1" | "2" | "3" | "4") => Ok(Self::Yahoo {
+                    benchmark: n.parse().unwrap(),
+                }),
+                _ => Err(format!(
+                    "unknown Yahoo benchmark '{s}', expected yahoo-a1..a4"
+                )),
+            },
+            _ => Err(format!(
+                "unknown dataset '{s}', expected nab[/category], yahoo-a1..a4 or smd[/machine]"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Dataset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nab { category: None } => f.write_str("nab"),
+            Self::Nab { category: Some(c) } => write!(f, "nab/{c}"),
+            Self::Yahoo { benchmark } => write!(f, "yahoo-a{benchmark}"),
+            Self::Smd { machine: None } => f.write_str("smd"),
+            Self::Smd { machine: Some(m) } => write!(f, "smd/{m}"),
+        }
+    }
+}
+
+/// Turns one series' samples into records, numbering labeled runs
+struct SeriesBuilder<'a> {
+    series: &'a str,
+    runs: usize,
+    in_run: bool,
+    records: Vec<LogRecord>,
+}
+
+impl<'a> SeriesBuilder<'a> {
+    fn new(series: &'a str) -> Self {
+        Self {
+            series,
+            runs: 0,
+            in_run: false,
+            records: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, ts_ns: u64, value: f64, anomalous: bool, extra: Vec<KeyValue>) {
+        let anomaly_id = if anomalous {
+            if !self.in_run {
+                self.runs += 1;
+            }
+            Some(format!("{}#{}", self.series, self.runs))
+        } else {
+            None
+        };
+        self.in_run = anomalous;
+
+        let mut attributes = vec![
+            KeyValue::string("service.name", self.series),
+            KeyValue::double(VALUE_ATTR, value),
+        ];
+        attributes.extend(extra);
+        self.records.push(LogRecord {
+            timeUnixNano: ts_ns.to_string(),
+            traceId: self.series.to_string(),
+            attributes,
+            isGroundTruthAnomaly: anomalous,
+            anomalyId: anomaly_id,
+            ..Default::default()
+        });
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Files in `dir` with extension `ext`, sorted
+fn files(dir: &Path, ext: &str) -> Result<Vec<PathBuf>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == ext))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn parse_nab_time(text: &str) -> Result<u64, String> {
+    let time = NaiveDateTime::parse_from_str(text.trim(), NAB_TIME_FORMAT)
+        .map_err(|e| format!("bad timestamp '{text}': {e}"))?;
+    time.and_utc()
+        .timestamp_nanos_opt()
+        .and_then(|ns| u64::try_from(ns).ok())
+        .ok_or_else(|| format!("timestamp '{text}' out of range"))
+}
+
+fn parse_f64(text: &str, path: &Path, line: usize) -> Result<f64, String> {
+    text.trim()
+        .parse()
+        .map_err(|_| format!("{}:{}: bad number '{}'", path.display(), line, text))
+}
+
+fn load_nab(root: &Path, category: Option<&str>) -> Result<Vec<LogRecord>, String> {
+    let labels_path = root.join("labels").join("combined_windows.json");
+    let windows: BTreeMap<String, Vec<[String; 2]>> = serde_json::from_str(&read(&labels_path)?)
+        .map_err(|e| format!("{}: {}", labels_path.display(), e))?;
+
+    let mut records = Vec::new();
+    for (key, windows) in &windows {
+        if category.is_some_and(|c| !key.starts_with(&format!("{c}/"))) {
+            continue;
+        }
+        let ranges = windows
+            .iter()
+            .map(|[start, end]| Ok((parse_nab_time(start)?, parse_nab_time(end)?)))
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(|e| format!("{}: {}: {}", labels_path.display(), key, e))?;
+
+        let path = root.join("data").join(key);
+        let series = key.trim_end_matches(".csv");
+        let mut builder = SeriesBuilder::new(series);
+        for (i, line) in read(&path)?.lines().enumerate().skip(1) {
+            let Some((time, value)) = line.split_once(',') else {
+                continue;
+            };
+            let ts =
+                parse_nab_time(time).map_err(|e| format!("{}:{}: {}", path.display(), i + 1, e))?;
+            let anomalous = ranges
+                .iter()
+                .any(|&(start, end)| (start..=end).contains(&ts));
+            builder.push(ts, parse_f64(value, &path, i + 1)?, anomalous, Vec::new());
+        }
+        records.append(&mut builder.records);
+    }
+    if records.is_empty() {
+        return Err(format!("no NAB series found under {}", root.display()));
+    }
+    Ok(records)
+}
+
+fn load_yahoo(root: &Path, benchmark: u8) -> Result<Vec<LogRecord>, String> {
+    let dir = root.join(format!("A{benchmark}Benchmark"));
+    let mut records = Vec::new();
+    for path in files(&dir, "csv")? {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        // A3/A4 ship a concatenation of every series next to the series
+        if stem.ends_with("_all") {
+            continue;
+        }
+        let content = read(&path)?;
+        let mut lines = content.lines();
+        let header: Vec<&str> = lines.next().unwrap_or("").split(',').collect();
+        let column = |names: &[&str]| {
+            header
+                .iter()
+                .position(|h| names.contains(&h.trim()))
+                .ok_or_else(|| format!("{}: missing column {}", path.display(), names[0]))
+        };
+        let time_col = column(&["timestamp", "timestamps"])?;
+        let value_col = column(&["value"])?;
+        let label_col = column(&["is_anomaly", "anomaly"])?;
+
+        let series = format!("A{benchmark}/{stem}");
+        let mut builder = SeriesBuilder::new(&series);
+        for (i, line) in lines.enumerate() {
+            let fields: Vec<&str> = line.split(',').collect();
+            if fields.len() <= time_col.max(value_col).max(label_col) {
+                continue;
+            }
+            // Unix seconds (A2-A4) or sample index (A1)
+            let ts = parse_f64(fields[time_col], &path, i + 2)? as u64 * 1_000_000_000;
+            let value = parse_f64(fields[value_col], &path, i + 2)?;
+            let anomalous = parse_f64(fields[label_col], &path, i + 2)? != 0.0;
+            builder.push(ts, value, anomalous, Vec::new());
+        }
+        records.append(&mut builder.records);
+    }
+    if records.is_empty() {
+        return Err(format!("no Yahoo A{benchmark} series in {}", dir.display()));
+    }
+    Ok(records)
+}
+
+fn load_smd(root: &Path, machine: Option<&str>) -> Result<Vec<LogRecord>, String> {
+    let test_dir = root.join("test");
+    let machines: Vec<PathBuf> = match machine {
+        Some(m) => vec![test_dir.join(format!("{m}.txt"))],
+        None => files(&test_dir, "txt")?,
+    };
+
+    let mut records = Vec::new();
+    for path in machines {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        let label_path = root.join("test_label").join(format!("{name}.txt"));
+        let labels = read(&label_path)?;
+        let mut labels = labels.lines();
+
+        let mut builder = SeriesBuilder::new(&name);
+        for (i, line) in read(&path)?.lines().enumerate() {
+            let dims = line
+                .split(',')
+                .map(|v| parse_f64(v, &path, i + 1))
+                .collect::<Result<Vec<_>, String>>()?;
+            let label = labels
+                .next()
+                .ok_or_else(|| format!("{}: fewer labels than samples", label_path.display()))?;
+            let anomalous = parse_f64(label, &label_path, i + 1)? != 0.0;
+            let mean = dims.iter().sum::<f64>() / dims.len().max(1) as f64;
+            let extra = dims
+                .iter()
+                .enumerate()
+                .map(|(d, &v)| KeyValue::double(format!("dim.{d}"), v))
+                .collect();
+            builder.push(i as u64 * SMD_STEP_NS, mean, anomalous, extra);
+        }
+        records.append(&mut builder.records);
+    }
+    if records.is_empty() {
+        return Err(format!("no SMD machines in {}", test_dir.display()));
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use via_sim::ReplaySource;
+
+    fn write(root: &Path, file: &str, content: &str) {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn temp_root(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("via-bench-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn test_parse_dataset_names() {
+        for name in ["nab", "nab/realKnownCause", "yahoo-a3", "smd/machine-1-1"] {
+            let dataset: Dataset = name.parse().unwrap();
+            assert_eq!(dataset.to_string(), name);
+        }
+        assert_eq!(
+            "Yahoo-A1".parse::<Dataset>().unwrap(),
+            Dataset::Yahoo { benchmark: 1 }
+        );
+        assert!("yahoo-a5".parse::<Dataset>().is_err());
+        assert!("kdd".parse::<Dataset>().is_err());
+    }
+
+    #[test]
+    fn test_nab_windows_label_samples() {
+        let root = temp_root("nab");
+        write(
+            &root,
+            "data/realKnownCause/cpu.csv",
+            "timestamp,value\n2014-04-01 00:00:00,1.5\n2014-04-01 00:05:00,9.0\n\
+             2014-04-01 00:10:00,8.5\n2014-04-01 00:15:00,1.0\n",
+        );
+        write(
+            &root,
+            "labels/combined_windows.json",
+            r#"{"realKnownCause/cpu.csv": [["2014-04-01 00:05:00.000000", "2014-04-01 00:10:00.000000"]]}"#,
+        );
+        let records = Dataset::Nab { category: None }.load(&root).unwrap();
+        let labels: Vec<bool> = records.iter().map(|r| r.isGroundTruthAnomaly).collect();
+        assert_eq!(labels, [false, true, true, false]);
+        assert_eq!(records[1].service_name(), Some("realKnownCause/cpu"));
+
+        let source = ReplaySource::from_records(records);
+        assert_eq!(source.ground_truth().len(), 1);
+        assert_eq!(source.ground_truth()[0].log_count, 2);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_yahoo_and_smd_layouts() {
+        let root = temp_root("yahoo");
+        write(
+            &root,
+            "A3Benchmark/A3Benchmark-TS1.csv",
+            "timestamps,value,anomaly,changepoint\n1416726000,10,0,0\n1416729600,50,1,0\n\
+             1416733200,11,0,0\n1416736800,60,1,0\n",
+        );
+        write(&root, "A3Benchmark/A3Benchmark_all.csv", "ignored\n");
+        let records = Dataset::Yahoo { benchmark: 3 }.load(&root).unwrap();
+        assert_eq!(records.len(), 4);
+        // Two separate labeled runs are two windows
+        assert_eq!(ReplaySource::from_records(records).ground_truth().len(), 2);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let root = temp_root("smd");
+        write(&root, "test/machine-1-1.txt", "0.1,0.3\n0.9,0.7\n");
+        write(&root, "test_label/machine-1-1.txt", "0\n1\n");
+        let records = Dataset::Smd { machine: None }.load(&root).unwrap();
+        assert_eq!(records[1].timeUnixNano, SMD_STEP_NS.to_string());
+        assert!(records[1].isGroundTruthAnomaly);
+        let value = records[1].get_attribute(VALUE_ATTR).unwrap().as_f64();
+        assert!((value.unwrap() - 0.8).abs() < 1e-9);
+        assert!(records[1].get_attribute("dim.1").is_some());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,322 @@
+//! Field Extraction
+//!
+//! Maps a `LogRecord` to the inputs detection consumes: the entity the event
+//! belongs to and the value it measures. The default keys events by
+//! `traceId` and takes `LogRecord::metric_value`; an `ExtractionSpec` can
+//! instead key on any attribute (`service.name`, `client.ip`, `user.id`) or
+//! a composite `EntityKey` (`service.name + client.ip`), and measure a
+//! numeric attribute, the HTTP status class or the body size. Filters
+//! restrict a run to matching logs; logs they reject, or that lack the value
+//! field, are skipped before detection and scoring.
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+use via_sim::{AnyValue, EntityKey, LogRecord};
+
+/// Attribute holding the HTTP status code for `ValueSource::StatusClass`
+const STATUS_CODE_ATTR: &str = "http.status_code";
+
+/// Field of a log (see `LogRecord::field`): `traceId`, `spanId`,
+/// `severityText`, `body`, or else an attribute key
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct AttributePath(pub String);
+
+impl AttributePath {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self(path.into())
+    }
+
+    /// The field as text, `None` when the log lacks it
+    pub fn resolve<'a>(&self, log: &'a LogRecord) -> Option<Cow<'a, str>> {
+        log.field(&self.0)
+    }
+
+    /// The field as a number; strings are parsed
+    pub fn resolve_f64(&self, log: &LogRecord) -> Option<f64> {
+        match self.0.as_str() {
+            "body" => number(&log.body),
+            key => log.get_attribute(key).and_then(number),
+        }
+    }
+}
+
+impl fmt::Display for AttributePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn number(value: &AnyValue) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+/// Where the detected value comes from
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueSource {
+    /// First known metric attribute (`LogRecord::metric_value`)
+    #[default]
+    Metric,
+    /// A numeric field, e.g. `http.duration_ms` for latency
+    Attribute(AttributePath),
+    /// Hundreds digit of `http.status_code` (5 for a 503)
+    StatusClass,
+    /// Byte length of the body as text
+    BodySize,
+}
+
+impl ValueSource {
+    /// The value of `log`, `None` when the log lacks the field
+    pub fn resolve(&self, log: &LogRecord) -> Option<f64> {
+        match self {
+            Self::Metric => Some(log.metric_value()),
+            Self::Attribute(path) => path.resolve_f64(log),
+            Self::StatusClass => log
+                .get_attribute(STATUS_CODE_ATTR)
+                .and_then(number)
+                .map(|code| (code / 100.0).floor()),
+            Self::BodySize => Some(log.body.as_text().len() as f64),
+        }
+    }
+}
+
+impl FromStr for ValueSource {
+    type Err = String;
+
+    /// `metric`, `status-class`, `body-size`, or an attribute key
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "" => Err("value source must not be empty".to_string()),
+            "metric" => Ok(Self::Metric),
+            "status-class" | "status_class" => Ok(Self::StatusClass),
+            "body-size" | "body_size" => Ok(Self::BodySize),
+            key => Ok(Self::Attribute(AttributePath::new(key))),
+        }
+    }
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Metric => f.write_str("metric"),
+            Self::Attribute(path) => path.fmt(f),
+            Self::StatusClass => f.write_str("status-class"),
+            Self::BodySize => f.write_str("body-size"),
+        }
+    }
+}
+
+/// Condition a log must meet to be detected
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LogFilter {
+    pub path: AttributePath,
+    /// Required text of the field; any value matches when absent
+    #[serde(default)]
+    pub equals: Option<String>,
+    /// Keep the logs that do not match instead
+    #[serde(default)]
+    pub negate: bool,
+}
+
+impl LogFilter {
+    pub fn matches(&self, log: &LogRecord) -> bool {
+        let found = self
+            .path
+            .resolve(log)
+            .is_some_and(|v| self.equals.as_deref().is_none_or(|want| v == want));
+        found != self.negate
+    }
+}
+
+impl FromStr for LogFilter {
+    type Err = String;
+
+    /// `path` (present), `path=value`, or `path!=value`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (path, equals, negate) = match s.split_once("!=") {
+            Some((path, value)) => (path, Some(value), true),
+            None => match s.split_once('=') {
+                Some((path, value)) => (path, Some(value), false),
+                None => (s, None, false),
+            },
+        };
+        let path = path.trim();
+        if path.is_empty() {
+            return Err(format!(
+                "expected path, path=value or path!=value, got '{s}'"
+            ));
+        }
+        Ok(Self {
+            path: AttributePath::new(path),
+            equals: equals.map(|v| v.trim().to_string()),
+            negate,
+        })
+    }
+}
+
+/// How logs become detection inputs
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ExtractionSpec {
+    /// Key hashed into the event's entity
+    pub entity_key: EntityKey,
+    pub value: ValueSource,
+    /// Every filter must match for a log to be detected
+    pub filters: Vec<LogFilter>,
+    /// Key of the profiles in per-service mode, `service.name` when unset
+    pub profile_key: Option<EntityKey>,
+}
+
+impl ExtractionSpec {
+    /// Whether `log` passes the filters and carries the value field
+    pub fn admits(&self, log: &LogRecord) -> bool {
+        self.filters.iter().all(|f| f.matches(log)) && self.value.resolve(log).is_some()
+    }
+
+    /// Entity id of `log`; logs without the key share `unknown`
+    pub fn entity<'a>(&self, log: &'a LogRecord) -> Cow<'a, str> {
+        self.entity_key.entity(log)
+    }
+
+    /// Entity hash of `log`, as `via_hash_string` computes it
+    pub fn entity_hash(&self, log: &LogRecord) -> u64 {
+        self.entity_key.hash(log)
+    }
+
+    /// Detected value of an admitted `log`
+    pub fn value(&self, log: &LogRecord) -> f64 {
+        self.value.resolve(log).unwrap_or(0.0)
+    }
+
+    /// Short description for run headers, `None` for the default
+    pub fn label(&self) -> Option<String> {
+        if *self == Self::default() {
+            return None;
+        }
+        let mut label = format!("{} -> {}", self.entity_key, self.value);
+        if !self.filters.is_empty() {
+            label.push_str(&format!(", {} filters", self.filters.len()));
+        }
+        if let Some(key) = &self.profile_key {
+            label.push_str(&format!(", profiles by {key}"));
+        }
+        Some(label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AnomalySpec, BenchmarkConfig, BenchmarkRunner};
+    use via_sim::KeyValue;
+
+    fn log(attributes: &[(&str, AnyValue)], body: &str) -> LogRecord {
+        LogRecord {
+            traceId: "trace-1".to_string(),
+            body: AnyValue::string(body),
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| KeyValue {
+                    key: key.to_string(),
+                    value: value.clone(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_default_spec_matches_builtin_extraction() {
+        let log = log(&[("http.duration_ms", AnyValue::double(120.0))], "ok");
+        let spec = ExtractionSpec::default();
+        assert!(spec.admits(&log));
+        assert_eq!(spec.value(&log), log.metric_value());
+        assert_eq!(
+            spec.entity_hash(&log),
+            xxhash_rust::xxh3::xxh3_64(log.traceId.as_bytes())
+        );
+        assert_eq!(spec.label(), None);
+    }
+
+    #[test]
+    fn test_custom_entity_and_values() {
+        let log = log(
+            &[
+                ("client.ip", AnyValue::string("10.0.0.7")),
+                ("http.status_code", AnyValue::int(503)),
+                ("http.duration_ms", AnyValue::string("42.5")),
+            ],
+            "hello",
+        );
+        let spec = ExtractionSpec {
+            entity_key: EntityKey::field("client.ip"),
+            value: ValueSource::StatusClass,
+            ..Default::default()
+        };
+        assert_eq!(spec.entity(&log), "10.0.0.7");
+        assert_eq!(spec.value(&log), 5.0);
+
+        let latency: ValueSource = "http.duration_ms".parse().unwrap();
+        assert_eq!(latency.resolve(&log), Some(42.5));
+        let size: ValueSource = "body-size".parse().unwrap();
+        assert_eq!(size.resolve(&log), Some(5.0));
+        // Logs without the value field are not detected
+        let missing: ValueSource = "user.id".parse().unwrap();
+        assert!(
+            !ExtractionSpec {
+                value: missing,
+                ..Default::default()
+            }
+            .admits(&log)
+        );
+    }
+
+    #[test]
+    fn test_filters() {
+        let log = log(&[("service.name", AnyValue::string("checkout"))], "");
+        let only = |s: &str| ExtractionSpec {
+            filters: vec![s.parse().unwrap()],
+            ..Default::default()
+        };
+        assert!(only("service.name").admits(&log));
+        assert!(only("service.name=checkout").admits(&log));
+        assert!(!only("service.name=auth").admits(&log));
+        assert!(only("service.name!=auth").admits(&log));
+        assert!(!only("user.id").admits(&log));
+        assert!("=x".parse::<LogFilter>().is_err());
+    }
+
+    #[test]
+    fn test_composite_key_reaches_ground_truth_entities() {
+        let config = BenchmarkConfig {
+            duration_secs: 10,
+            anomalies: vec![AnomalySpec {
+                scenario: "credential_stuffing".to_string(),
+                start_time_sec: 2,
+                duration_sec: 5,
+            }],
+            per_service: true,
+            quiet: true,
+            extraction: ExtractionSpec {
+                entity_key: "service.name + source.ip".parse().unwrap(),
+                profile_key: Some(EntityKey::field("source.ip")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let results = BenchmarkRunner::new().run(config).unwrap();
+
+        let entities = results.entities.unwrap();
+        assert_eq!(entities.entity_key, "service.name + source.ip");
+        assert!(entities.target_entities > 0);
+        assert!(entities.detected_entities <= entities.target_entities);
+        // One profile per attacking IP, plus one for logs without an IP
+        let registry = results.registry.unwrap();
+        assert!(registry.profiles > results.service_metrics.len());
+    }
+}
@@ -0,0 +1,325 @@
+//! Delayed-Label Feedback Loop
+//!
+//! Exercises the adaptive ensemble's learning path, which plain benchmark
+//! runs never touch. Every event the engine flags is labeled from the ground
+//! truth, and the label is applied to the profile that scored it `delay_ms`
+//! of simulated time later, the way Tier-2 confirmations trickle back in
+//! production. A `label_noise` share of labels is flipped to model reviewer
+//! error.
+//!
+//! `run_feedback_loop` runs a benchmark with and without the loop and
+//! reports accuracy per time window next to how far the mean ensemble weights
+//! moved since the previous window, showing how fast the Thompson-sampling
+//! weights converge and what they buy.
+
+use crate::online::OnlineMetrics;
+use crate::{BenchmarkConfig, BenchmarkRunner, calculate_metrics};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use via_core::feedback::{FeedbackEvent, FeedbackSource};
+use via_core::signal::{AnomalySignal, NUM_DETECTORS};
+
+/// Time windows the run is split into for accuracy over time
+pub const FEEDBACK_WINDOWS: usize = 10;
+
+/// Label delay when `--label-noise` turns the loop on (ms)
+pub const DEFAULT_LABEL_DELAY_MS: u64 = 1000;
+
+/// Ground-truth labels fed back into detection
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct FeedbackLoopConfig {
+    pub enabled: bool,
+    /// Simulated time between a detection and its label (ms)
+    pub delay_ms: u64,
+    /// Share of labels that are flipped (0.0 - 1.0)
+    pub label_noise: f64,
+}
+
+/// Label traffic of one run
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct FeedbackLoopStats {
+    pub delay_ms: u64,
+    pub label_noise: f64,
+    /// Flagged events a label was produced for
+    pub labels_sent: u64,
+    /// Labels that reached their profile before the run ended
+    pub labels_applied: u64,
+    /// Labels flipped by the noise model
+    pub labels_flipped: u64,
+}
+
+/// Labels waiting for their delivery time
+pub(crate) struct LabelQueue {
+    delay_ns: u64,
+    label_noise: f64,
+    rng: StdRng,
+    /// (due time, sequence) -> (profile key, label)
+    pending: BTreeMap<(u64, u64), (u64, FeedbackEvent)>,
+    stats: FeedbackLoopStats,
+}
+
+impl LabelQueue {
+    pub(crate) fn new(config: &FeedbackLoopConfig, seed: u64) -> Self {
+        Self {
+            delay_ns: config.delay_ms * 1_000_000,
+            label_noise: config.label_noise.clamp(0.0, 1.0),
+            rng: StdRng::seed_from_u64(seed),
+            pending: BTreeMap::new(),
+            stats: FeedbackLoopStats {
+                delay_ms: config.delay_ms,
+                label_noise: config.label_noise,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Queue the label for a flagged event; other events are not reviewed
+    pub(crate) fn push(&mut self, key: u64, signal: &AnomalySignal, is_anomaly: bool) {
+        if !signal.is_anomaly {
+            return;
+        }
+        let flipped = self.label_noise > 0.0 && self.rng.random_bool(self.label_noise);
+        let scores: [f32; NUM_DETECTORS] = signal.detector_scores.map(|s| s.score);
+        let (entity, timestamp) = (signal.entity_hash, signal.timestamp);
+        let source = FeedbackSource::AutoCorrelation;
+        let mut event = if is_anomaly != flipped {
+            FeedbackEvent::true_positive(entity, timestamp, scores, source, 1.0)
+        } else {
+            FeedbackEvent::false_positive(entity, timestamp, scores, source, 1.0)
+        };
+        event.feedback_latency_ms = self.stats.delay_ms;
+
+        let due = timestamp.saturating_add(self.delay_ns);
+        self.pending.insert(
+            (due, self.stats.labels_sent),
+            (key, event.with_ensemble_score(signal.ensemble_score)),
+        );
+        self.stats.labels_sent += 1;
+        self.stats.labels_flipped += flipped as u64;
+    }
+
+    /// Pop the next label due by `now_ns`, with its profile key
+    pub(crate) fn release(&mut self, now_ns: u64) -> Option<(u64, FeedbackEvent)> {
+        let entry = self.pending.first_entry()?;
+        if entry.key().0 > now_ns {
+            return None;
+        }
+        self.stats.labels_applied += 1;
+        Some(entry.remove())
+    }
+
+    pub(crate) fn stats(&self) -> FeedbackLoopStats {
+        self.stats.clone()
+    }
+}
+
+/// Accuracy and weight movement over one slice of simulated time
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct AccuracyWindow {
+    /// Offsets from the first event (seconds)
+    pub start_sec: f64,
+    pub end_sec: f64,
+    pub events: u64,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1_score: f64,
+    /// L1 distance between this window's mean ensemble weights and the
+    /// previous window's (0 for the first)
+    pub weight_shift: f64,
+}
+
+/// Split the scored events into `windows` equal spans of event time, each
+/// time slot of the run landing whole in the span holding its middle
+pub(crate) fn accuracy_windows(metrics: &OnlineMetrics, windows: usize) -> Vec<AccuracyWindow> {
+    let windows = windows.max(1);
+    let Some((first, last)) = metrics.span_ns else {
+        return Vec::new();
+    };
+    let span = (last - first).max(1) as f64 / windows as f64;
+
+    // (tp, fp, fn, events, summed weights) per window
+    let mut slots = vec![(0u64, 0u64, 0u64, 0u64, [0.0f64; NUM_DETECTORS]); windows];
+    let half_slot = metrics.slots.width_ns() / 2;
+    for (slot_start, time_slot) in metrics.slots.iter() {
+        let middle = (slot_start + half_slot).clamp(first, last);
+        let index = (((middle - first) as f64 / span) as usize).min(windows - 1);
+        let slot = &mut slots[index];
+        let counts = time_slot.tally.counts;
+        slot.0 += counts.true_positives;
+        slot.1 += counts.false_positives;
+        slot.2 += counts.false_negatives;
+        slot.3 += time_slot.tally.events;
+        for (sum, weights) in slot.4.iter_mut().zip(time_slot.weight_sums) {
+            *sum += weights;
+        }
+    }
+
+    let mut previous: Option<[f64; NUM_DETECTORS]> = None;
+    slots
+        .iter()
+        .enumerate()
+        .map(|(index, &(tp, fp, fn_, count, sums))| {
+            let (precision, recall, f1_score) = calculate_metrics(tp, fp, fn_);
+            let weight_shift = if count == 0 {
+                0.0
+            } else {
+                let mean = sums.map(|s| s / count as f64);
+                let shift = previous.map_or(0.0, |prev| {
+                    prev.iter().zip(&mean).map(|(a, b)| (a - b).abs()).sum()
+                });
+                previous = Some(mean);
+                shift
+            };
+            AccuracyWindow {
+                start_sec: index as f64 * span / 1e9,
+                end_sec: (index + 1) as f64 * span / 1e9,
+                events: count,
+                precision,
+                recall,
+                f1_score,
+                weight_shift,
+            }
+        })
+        .collect()
+}
+
+/// One run with the feedback loop against the same run without it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FeedbackLoopResults {
+    pub config: String,
+    pub stats: FeedbackLoopStats,
+    pub f1_score: f64,
+    pub baseline_f1_score: f64,
+    pub windows: Vec<AccuracyWindow>,
+    pub baseline_windows: Vec<AccuracyWindow>,
+}
+
+/// Run `base` with `feedback` applied and once without any feedback
+pub fn run_feedback_loop(
+    base: &BenchmarkConfig,
+    feedback: &FeedbackLoopConfig,
+) -> Result<FeedbackLoopResults, String> {
+    let run = |feedback: FeedbackLoopConfig| {
+        let mut config = base.clone();
+        config.feedback = feedback;
+        let mut runner = BenchmarkRunner::new();
+        let results = runner.run(config)?;
+        let windows = accuracy_windows(&runner.metrics, FEEDBACK_WINDOWS);
+        Ok::<_, String>((results, windows))
+    };
+
+    let (results, windows) = run(FeedbackLoopConfig {
+        enabled: true,
+        ..feedback.clone()
+    })?;
+    let (baseline, baseline_windows) = run(FeedbackLoopConfig::default())?;
+
+    Ok(FeedbackLoopResults {
+        config: base.name.clone(),
+        stats: results.feedback.unwrap_or_default(),
+        f1_score: results.f1_score,
+        baseline_f1_score: baseline.f1_score,
+        windows,
+        baseline_windows,
+    })
+}
+
+/// Print accuracy over time with and without feedback as a table
+pub fn print_feedback_loop(results: &FeedbackLoopResults) {
+    let stats = &results.stats;
+    println!("\n╔══════════════════════════════════════════════════════════════╗");
+    println!("║                 FEEDBACK LOOP (DELAYED LABELS)               ║");
+    println!("╠══════════════════════════════════════════════════════════════╣");
+    println!("║ Config: {:52} ║", results.config);
+    println!(
+        "║ Delay: {:>8}ms | Noise: {:>5.1}% | Labels: {:>7} / {:<7}║",
+        stats.delay_ms,
+        stats.label_noise * 100.0,
+        stats.labels_applied,
+        stats.labels_sent
+    );
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    println!("║ Window s      | Events  |   F1   | No feedback | Weight shift ║");
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    for (w, base) in results.windows.iter().zip(&results.baseline_windows) {
+        println!(
+            "║ {:>5.0} - {:<5.0} | {:>7} | {:>6.3} | {:>11.3} | {:>12.4} ║",
+            w.start_sec, w.end_sec, w.events, w.f1_score, base.f1_score, w.weight_shift
+        );
+    }
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    println!(
+        "║ Overall F1:   {:>6.3} (no feedback {:>6.3}) {:>18} ║",
+        results.f1_score, results.baseline_f1_score, ""
+    );
+    println!("╚══════════════════════════════════════════════════════════════╝");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flagged(timestamp: u64) -> AnomalySignal {
+        AnomalySignal {
+            timestamp,
+            is_anomaly: true,
+            ensemble_score: 0.7,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_labels_arrive_after_delay() {
+        let config = FeedbackLoopConfig {
+            enabled: true,
+            delay_ms: 100,
+            label_noise: 0.0,
+        };
+        let mut queue = LabelQueue::new(&config, 7);
+        queue.push(1, &flagged(0), true);
+        queue.push(2, &flagged(50_000_000), false);
+        queue.push(
+            3,
+            &AnomalySignal {
+                is_anomaly: false,
+                ..flagged(0)
+            },
+            true,
+        );
+
+        assert!(queue.release(99_000_000).is_none());
+        let (key, event) = queue.release(100_000_000).unwrap();
+        assert_eq!(key, 1);
+        assert!(event.was_true_positive);
+        assert_eq!(event.ensemble_score, Some(0.7));
+        let (key, event) = queue.release(u64::MAX).unwrap();
+        assert_eq!(key, 2);
+        assert!(!event.was_true_positive);
+        assert!(queue.release(u64::MAX).is_none());
+
+        let stats = queue.stats();
+        assert_eq!((stats.labels_sent, stats.labels_applied), (2, 2));
+    }
+
+    #[test]
+    fn test_label_noise_flips_share_of_labels() {
+        let config = FeedbackLoopConfig {
+            enabled: true,
+            delay_ms: 0,
+            label_noise: 0.2,
+        };
+        let mut queue = LabelQueue::new(&config, 7);
+        for i in 0..1000 {
+            queue.push(0, &flagged(i), true);
+        }
+        let mut negatives = 0;
+        while let Some((_, event)) = queue.release(u64::MAX) {
+            negatives += !event.was_true_positive as u64;
+        }
+        assert_eq!(negatives, queue.stats().labels_flipped);
+        assert!((150..250).contains(&negatives), "{} flipped", negatives);
+    }
+}
@@ -0,0 +1,212 @@
+//! FFI-Path Benchmarking
+//!
+//! The Bun integration never calls `AnomalyProfile` directly: every event goes
+//! through the exported C ABI, which hashes a C string, heap-allocates the
+//! signal, serializes it to JSON and frees both. This module reproduces that
+//! call sequence so its cost shows up in benchmark latency, and runs the same
+//! workload down both paths to report the overhead delta.
+
+use crate::{BenchmarkConfig, BenchmarkResults, BenchmarkRunner};
+use serde::{Deserialize, Serialize};
+use std::ffi::CString;
+use via_core::engine::{AnomalyProfile, ExogenousContext};
+use via_core::signal::AnomalySignal;
+
+/// Run one event through the C ABI exactly as the Bun host does.
+///
+/// Returns the entity hash and a copy of the signal. Entity IDs containing an
+/// interior NUL cannot cross the boundary and hash to 0, matching the host.
+/// A non-default `exogenous` context goes through `via_process_event_with_context`.
+pub fn process_event(
+    profile: &mut AnomalyProfile,
+    timestamp: u64,
+    entity_id: &str,
+    value: f64,
+    exogenous: ExogenousContext,
+) -> (u64, AnomalySignal) {
+    let entity_hash = CString::new(entity_id)
+        .map(|id| via_core::via_hash_string(id.as_ptr()))
+        .unwrap_or(0);
+
+    let signal_ptr = if exogenous == ExogenousContext::default() {
+        via_core::via_process_event(profile, timestamp, entity_hash, value)
+    } else {
+        let business_hours = exogenous.business_hours.map_or(-1, i32::from);
+        via_core::via_process_event_with_context(
+            profile,
+            timestamp,
+            entity_hash,
+            value,
+            business_hours,
+            exogenous.deploy_in_progress,
+        )
+    };
+    let json_ptr = via_core::via_signal_to_json(signal_ptr);
+    via_core::via_free_string(json_ptr);
+
+    // SAFETY: `via_process_event` returns a valid, uniquely owned signal for a
+    // non-null profile; it is copied out before being released below.
+    let signal = unsafe { (*signal_ptr).clone() };
+    via_core::via_free_signal(signal_ptr);
+
+    (entity_hash, signal)
+}
+
+/// Latency and throughput of one call path
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PathMetrics {
+    pub avg_micros: f64,
+    pub p50_micros: f64,
+    pub p99_micros: f64,
+    pub throughput_eps: f64,
+    pub f1_score: f64,
+}
+
+impl From<&BenchmarkResults> for PathMetrics {
+    fn from(r: &BenchmarkResults) -> Self {
+        Self {
+            avg_micros: r.latency_micros.avg_micros,
+            p50_micros: r.latency_micros.p50_micros,
+            p99_micros: r.latency_micros.p99_micros,
+            throughput_eps: r.throughput_eps,
+            f1_score: r.f1_score,
+        }
+    }
+}
+
+/// Direct-call vs FFI-path comparison of the same workload
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FfiOverhead {
+    pub config: String,
+    pub direct: PathMetrics,
+    pub ffi: PathMetrics,
+    /// FFI minus direct average per-event latency (µs)
+    pub avg_delta_micros: f64,
+    /// FFI minus direct P99 per-event latency (µs)
+    pub p99_delta_micros: f64,
+    /// Relative throughput change of the FFI path (negative = slower)
+    pub throughput_delta_pct: f64,
+    /// FFI minus direct F1; RRCF and Thompson sampling are unseeded, so small
+    /// values are run-to-run noise rather than an FFI effect
+    pub f1_delta: f64,
+}
+
+impl FfiOverhead {
+    pub fn from_results(direct: &BenchmarkResults, ffi: &BenchmarkResults) -> Self {
+        let throughput_delta_pct = if direct.throughput_eps > 0.0 {
+            (ffi.throughput_eps / direct.throughput_eps - 1.0) * 100.0
+        } else {
+            0.0
+        };
+        Self {
+            config: direct.config.clone(),
+            direct: direct.into(),
+            ffi: ffi.into(),
+            avg_delta_micros: ffi.latency_micros.avg_micros - direct.latency_micros.avg_micros,
+            p99_delta_micros: ffi.latency_micros.p99_micros - direct.latency_micros.p99_micros,
+            throughput_delta_pct,
+            f1_delta: ffi.f1_score - direct.f1_score,
+        }
+    }
+}
+
+/// Run `base` once through direct Rust calls and once through the C ABI
+pub fn run_ffi_overhead(
+    base: &BenchmarkConfig,
+) -> Result<(BenchmarkResults, BenchmarkResults, FfiOverhead), String> {
+    let mut direct_config = base.clone();
+    direct_config.ffi_path = false;
+    let direct = BenchmarkRunner::new().run(direct_config)?;
+
+    let mut ffi_config = base.clone();
+    ffi_config.ffi_path = true;
+    ffi_config.name = format!("{} (FFI)", base.name);
+    let ffi = BenchmarkRunner::new().run(ffi_config)?;
+
+    let overhead = FfiOverhead::from_results(&direct, &ffi);
+    Ok((direct, ffi, overhead))
+}
+
+/// Print the side-by-side comparison
+pub fn print_ffi_overhead(overhead: &FfiOverhead) {
+    println!("\n╔══════════════════════════════════════════════════════════════╗");
+    println!("║                    FFI OVERHEAD                              ║");
+    println!("╠══════════════════════════════════════════════════════════════╣");
+    println!("║ Config: {:52} ║", overhead.config);
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    println!("║                      Direct |        FFI |      Delta        ║");
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    println!(
+        "║ Avg (µs):        {:>10.2} | {:>10.2} | {:>+10.2}        ║",
+        overhead.direct.avg_micros, overhead.ffi.avg_micros, overhead.avg_delta_micros
+    );
+    println!(
+        "║ P99 (µs):        {:>10.2} | {:>10.2} | {:>+10.2}        ║",
+        overhead.direct.p99_micros, overhead.ffi.p99_micros, overhead.p99_delta_micros
+    );
+    println!(
+        "║ Throughput:      {:>10.0} | {:>10.0} | {:>+9.1}%        ║",
+        overhead.direct.throughput_eps, overhead.ffi.throughput_eps, overhead.throughput_delta_pct
+    );
+    println!(
+        "║ F1-Score:        {:>10.3} | {:>10.3} | {:>+10.3}        ║",
+        overhead.direct.f1_score, overhead.ffi.f1_score, overhead.f1_delta
+    );
+    println!("╚══════════════════════════════════════════════════════════════╝");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use via_core::signal::DetectorId;
+
+    #[test]
+    fn test_ffi_path_hashes_and_returns_engine_signal() {
+        let mut direct = AnomalyProfile::default();
+        let mut ffi = AnomalyProfile::default();
+
+        // Warm-up followed by a spike, with varied trace ids; the tail of the
+        // spike runs during a deploy, off hours
+        let deploy = ExogenousContext {
+            business_hours: Some(false),
+            deploy_in_progress: true,
+        };
+        for i in 0..400u64 {
+            let trace_id = format!("trace-{:04x}", i % 37);
+            let value = if i >= 380 {
+                5_000.0
+            } else {
+                50.0 + (i % 7) as f64
+            };
+            let ts = i * 100_000_000;
+            let exogenous = if i >= 390 {
+                deploy
+            } else {
+                ExogenousContext::default()
+            };
+
+            let hash = xxhash_rust::xxh3::xxh3_64(trace_id.as_bytes());
+            let expected = direct.process_with_context(ts, hash, value, exogenous);
+            let (ffi_hash, actual) = process_event(&mut ffi, ts, &trace_id, value, exogenous);
+
+            assert_eq!(ffi_hash, hash);
+            assert_eq!(actual.entity_hash, expected.entity_hash, "event {i}");
+            assert_eq!(actual.sequence, expected.sequence, "event {i}");
+            assert_eq!(actual.timestamp, ts);
+            assert_eq!(actual.raw_value, value);
+            let distribution = DetectorId::Distribution as usize;
+            assert_eq!(
+                actual.detector_scores[distribution].score,
+                expected.detector_scores[distribution].score,
+                "event {i}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_interior_nul_hashes_to_zero_without_panicking() {
+        let mut profile = AnomalyProfile::default();
+        let (hash, _) = process_event(&mut profile, 0, "bad\0id", 1.0, ExogenousContext::default());
+        assert_eq!(hash, 0);
+    }
+}
@@ -0,0 +1,261 @@
+//! Hyperparameter Grid Search
+//!
+//! Runs a benchmark once per combination of `ProfileConfig` values and ranks
+//! the combinations by F1. Parameters are named by their `ProfileConfig`
+//! field, with dots for nested fields (`suppression.cooldown_ms`), and take
+//! either an inclusive range with a step (`hw_alpha=0.1..0.5:0.1`) or a list
+//! (`period=12,24,48`).
+//!
+//! Combinations can run on several threads. Accuracy is unaffected, but
+//! concurrent runs share cores, so latency and throughput of a parallel
+//! search are only comparable within that search.
+
+use crate::{BenchmarkConfig, BenchmarkRunner};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use via_core::engine::ProfileConfig;
+
+/// Upper bound on values per parameter, against typos like a zero-ish step
+const MAX_VALUES: usize = 1_000;
+
+/// Values to try for one `ProfileConfig` field
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ParamRange {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+impl FromStr for ParamRange {
+    type Err = String;
+
+    /// `name=start..end:step` or `name=v1,v2,...`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, spec) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected name=start..end:step or name=a,b,c, got '{s}'"))?;
+        let number = |v: &str| {
+            v.trim()
+                .parse::<f64>()
+                .map_err(|_| format!("invalid number '{v}' in '{s}'"))
+        };
+
+        let values = match spec.split_once("..") {
+            Some((start, rest)) => {
+                let (end, step) = rest.split_once(':').unwrap_or((rest, "1"));
+                let (start, end, step) = (number(start)?, number(end)?, number(step)?);
+                if step <= 0.0 || end < start {
+                    return Err(format!(
+                        "range in '{s}' must have start <= end and step > 0"
+                    ));
+                }
+                let steps = ((end - start) / step + 1e-9).floor() as usize;
+                if steps >= MAX_VALUES {
+                    return Err(format!("'{s}' expands to more than {MAX_VALUES} values"));
+                }
+                // Rounded so 0.1 + 2 * 0.1 prints as 0.3
+                (0..=steps)
+                    .map(|i| ((start + i as f64 * step) * 1e9).round() / 1e9)
+                    .collect()
+            }
+            None => spec.split(',').map(number).collect::<Result<Vec<_>, _>>()?,
+        };
+
+        Ok(Self {
+            name: name.trim().to_string(),
+            values,
+        })
+    }
+}
+
+/// Every combination of the parameters' values, first parameter slowest
+pub fn combinations(params: &[ParamRange]) -> Vec<BTreeMap<String, f64>> {
+    params.iter().fold(vec![BTreeMap::new()], |combos, param| {
+        combos
+            .iter()
+            .flat_map(|combo| {
+                param.values.iter().map(move |&value| {
+                    let mut combo = combo.clone();
+                    combo.insert(param.name.clone(), value);
+                    combo
+                })
+            })
+            .collect()
+    })
+}
+
+/// `base` with numeric fields replaced, keeping each field's integer or
+/// float type
+pub fn apply_overrides(
+    base: &ProfileConfig,
+    overrides: &BTreeMap<String, f64>,
+) -> Result<ProfileConfig, String> {
+    let mut config = serde_json::to_value(base).map_err(|e| e.to_string())?;
+    for (path, &value) in overrides {
+        let field = path
+            .split('.')
+            .try_fold(&mut config, |node, key| node.get_mut(key))
+            .ok_or_else(|| format!("unknown ProfileConfig field '{path}'"))?;
+        *field = match field {
+            Value::Number(n) if n.is_f64() => {
+                serde_json::Number::from_f64(value).map(Value::Number)
+            }
+            Value::Number(_) if value >= 0.0 && value.fract() == 0.0 => {
+                Some(Value::from(value as u64))
+            }
+            Value::Bool(_) => Some(Value::Bool(value != 0.0)),
+            _ => None,
+        }
+        .ok_or_else(|| format!("'{path}' cannot be set to {value}"))?;
+    }
+    serde_json::from_value(config).map_err(|e| e.to_string())
+}
+
+/// Accuracy and cost of one combination
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GridPoint {
+    pub params: BTreeMap<String, f64>,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1_score: f64,
+    pub p99_micros: f64,
+    pub throughput_eps: f64,
+}
+
+/// Full search output, points by descending F1
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GridSearchResults {
+    pub config: String,
+    pub params: Vec<ParamRange>,
+    pub points: Vec<GridPoint>,
+}
+
+/// Run `base` once per combination of `params` on up to `threads` threads
+pub fn run_grid_search(
+    base: &BenchmarkConfig,
+    params: &[ParamRange],
+    threads: usize,
+) -> Result<GridSearchResults, String> {
+    let combos = combinations(params);
+    // Reject bad names before spending any runs
+    for combo in &combos {
+        let mut config = base.clone();
+        config.profile_overrides.extend(combo.clone());
+        config.profile_config()?;
+    }
+
+    let next = AtomicUsize::new(0);
+    let run_next = || {
+        let mut points = Vec::new();
+        loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(combo) = combos.get(index) else {
+                break;
+            };
+            let mut config = base.clone();
+            config.quiet = true;
+            config.profile_overrides.extend(combo.clone());
+            let r = BenchmarkRunner::new()
+                .run(config)
+                .expect("combinations are validated before any run");
+            let point = GridPoint {
+                params: combo.clone(),
+                precision: r.precision,
+                recall: r.recall,
+                f1_score: r.f1_score,
+                p99_micros: r.latency_micros.p99_micros,
+                throughput_eps: r.throughput_eps,
+            };
+            points.push((index, point));
+        }
+        points
+    };
+
+    let mut points: Vec<(usize, GridPoint)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.clamp(1, combos.len().max(1)))
+            .map(|_| scope.spawn(run_next))
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("grid search worker panicked"))
+            .collect()
+    });
+    // Ties keep grid order whatever thread finished first
+    points.sort_by(|(ia, a), (ib, b)| b.f1_score.total_cmp(&a.f1_score).then(ia.cmp(ib)));
+
+    Ok(GridSearchResults {
+        config: base.name.clone(),
+        params: params.to_vec(),
+        points: points.into_iter().map(|(_, point)| point).collect(),
+    })
+}
+
+/// Print the `top` combinations by F1 as a table
+pub fn print_grid_search(results: &GridSearchResults, top: usize) {
+    println!("\n╔══════════════════════════════════════════════════════════════╗");
+    println!("║                 HYPERPARAMETER GRID SEARCH                   ║");
+    println!("╠══════════════════════════════════════════════════════════════╣");
+    println!("║ Config: {:52} ║", results.config);
+    println!(
+        "║ Combinations: {:>6} | Parameters: {:>3} {:>21} ║",
+        results.points.len(),
+        results.params.len(),
+        ""
+    );
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    println!("║ #  |  F1   | Prec  | Recall |  P99 µs | Parameters          ║");
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    for (rank, p) in results.points.iter().take(top).enumerate() {
+        let params: Vec<String> = p.params.iter().map(|(k, v)| format!("{k}={v}")).collect();
+        println!(
+            "║ {:>2} | {:>5.3} | {:>5.3} | {:>6.3} | {:>7.1} | {:<19} ║",
+            rank + 1,
+            p.f1_score,
+            p.precision,
+            p.recall,
+            p.p99_micros,
+            params.join(" ")
+        );
+    }
+    println!("╚══════════════════════════════════════════════════════════════╝");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_param_ranges_expand() {
+        let range: ParamRange = "hw_alpha=0.1..0.5:0.1".parse().unwrap();
+        assert_eq!(range.values, vec![0.1, 0.2, 0.3, 0.4, 0.5]);
+        let list: ParamRange = "period=12,24".parse().unwrap();
+        assert_eq!(list.values, vec![12.0, 24.0]);
+        assert!("hw_alpha=0.5..0.1:0.1".parse::<ParamRange>().is_err());
+        assert!("hw_alpha".parse::<ParamRange>().is_err());
+
+        let combos = combinations(&[range, list]);
+        assert_eq!(combos.len(), 10);
+        assert_eq!(combos[1]["hw_alpha"], 0.1);
+        assert_eq!(combos[1]["period"], 24.0);
+    }
+
+    #[test]
+    fn test_overrides_keep_field_types() {
+        let overrides = BTreeMap::from([
+            ("hw_alpha".to_string(), 0.25),
+            ("period".to_string(), 48.0),
+            ("suppression.cooldown_ms".to_string(), 5000.0),
+        ]);
+        let config = apply_overrides(&ProfileConfig::default(), &overrides).unwrap();
+        assert_eq!(config.hw_alpha, 0.25);
+        assert_eq!(config.period, 48);
+        assert_eq!(config.suppression.cooldown_ms, 5000);
+
+        let typo = BTreeMap::from([("hw_alfa".to_string(), 0.1)]);
+        assert!(apply_overrides(&ProfileConfig::default(), &typo).is_err());
+        let fractional = BTreeMap::from([("period".to_string(), 1.5)]);
+        assert!(apply_overrides(&ProfileConfig::default(), &fractional).is_err());
+    }
+}
@@ -0,0 +1,273 @@
+//! Run History Store (feature `history`)
+//!
+//! Appends run summaries to an embedded SQLite database (`--history <db>`)
+//! and reads them back as per-metric time series, so detection quality can
+//! be tracked across months of runs with `via-bench history`.
+//!
+//! Each run stores its full results JSON plus one row per headline metric;
+//! metric names match the ones `compare` reports.
+
+use crate::BenchmarkResults;
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    recorded_at TEXT NOT NULL,
+    scenario TEXT NOT NULL,
+    config TEXT NOT NULL,
+    results TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS metrics (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    name TEXT NOT NULL,
+    value REAL NOT NULL,
+    PRIMARY KEY (run_id, name)
+);
+CREATE INDEX IF NOT EXISTS metrics_by_name ON metrics(name, run_id);
+";
+
+/// `(name, short alias)` of every recorded metric
+pub const METRICS: [(&str, &str); 13] = [
+    ("precision", "precision"),
+    ("recall", "recall"),
+    ("f1_score", "f1"),
+    ("composite_score", "score"),
+    ("avg_micros", "avg"),
+    ("p50_micros", "p50"),
+    ("p95_micros", "p95"),
+    ("p99_micros", "p99"),
+    ("throughput_eps", "throughput"),
+    ("peak_rss_bytes", "rss"),
+    ("allocations_per_event", "allocations"),
+    ("cpu_micros_per_event", "cpu"),
+    ("cost_per_million_events", "cost"),
+];
+
+/// Full metric name for a name or alias from [`METRICS`]
+pub fn metric_name(metric: &str) -> Option<&'static str> {
+    METRICS
+        .iter()
+        .find(|(name, alias)| *name == metric || *alias == metric)
+        .map(|(name, _)| *name)
+}
+
+fn metric_values(r: &BenchmarkResults) -> impl Iterator<Item = (&'static str, f64)> {
+    let l = &r.latency_micros;
+    [
+        ("precision", Some(r.precision)),
+        ("recall", Some(r.recall)),
+        ("f1_score", Some(r.f1_score)),
+        ("composite_score", Some(r.composite_score)),
+        ("avg_micros", Some(l.avg_micros)),
+        ("p50_micros", Some(l.p50_micros)),
+        ("p95_micros", Some(l.p95_micros)),
+        ("p99_micros", Some(l.p99_micros)),
+        ("throughput_eps", Some(r.throughput_eps)),
+        (
+            "peak_rss_bytes",
+            (r.peak_rss_bytes > 0).then_some(r.peak_rss_bytes as f64),
+        ),
+        ("allocations_per_event", r.allocations_per_event),
+        (
+            "cpu_micros_per_event",
+            (r.cost.cpu_seconds > 0.0).then_some(r.cost.cpu_micros_per_event),
+        ),
+        ("cost_per_million_events", r.cost.cost_per_million_events),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| value.map(|v| (name, v)))
+}
+
+/// One run's value of a metric
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HistoryPoint {
+    pub run_id: i64,
+    /// RFC 3339 UTC
+    pub recorded_at: String,
+    pub scenario: String,
+    pub config: String,
+    pub value: f64,
+}
+
+pub struct HistoryStore {
+    conn: Connection,
+}
+
+impl HistoryStore {
+    /// Open (creating if needed) the database at `path`
+    pub fn open(path: &str) -> Result<Self, String> {
+        let conn = Connection::open(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("{}: failed to create schema: {}", path, e))?;
+        Ok(Self { conn })
+    }
+
+    /// Record a run of `scenario`; returns its run id
+    pub fn append(&mut self, scenario: &str, results: &BenchmarkResults) -> Result<i64, String> {
+        let json = serde_json::to_string(results).map_err(|e| e.to_string())?;
+        let recorded_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+
+        let tx = self.conn.transaction().map_err(db_error)?;
+        tx.execute(
+            "INSERT INTO runs (recorded_at, scenario, config, results) VALUES (?1, ?2, ?3, ?4)",
+            params![recorded_at, scenario, results.config, json],
+        )
+        .map_err(db_error)?;
+        let run_id = tx.last_insert_rowid();
+        for (name, value) in metric_values(results) {
+            tx.execute(
+                "INSERT INTO metrics (run_id, name, value) VALUES (?1, ?2, ?3)",
+                params![run_id, name, value],
+            )
+            .map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)?;
+        Ok(run_id)
+    }
+
+    /// The last `limit` values of `metric`, oldest first, optionally only
+    /// for runs whose scenario or config name is `scenario`
+    pub fn series(
+        &self,
+        metric: &str,
+        scenario: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<HistoryPoint>, String> {
+        let name = metric_name(metric).ok_or_else(|| unknown_metric(metric))?;
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT r.id, r.recorded_at, r.scenario, r.config, m.value
+                 FROM runs r JOIN metrics m ON m.run_id = r.id
+                 WHERE m.name = ?1 AND (?2 IS NULL OR r.scenario = ?2 OR r.config = ?2)
+                 ORDER BY r.id DESC LIMIT ?3",
+            )
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map(params![name, scenario, limit as i64], |row| {
+                Ok(HistoryPoint {
+                    run_id: row.get(0)?,
+                    recorded_at: row.get(1)?,
+                    scenario: row.get(2)?,
+                    config: row.get(3)?,
+                    value: row.get(4)?,
+                })
+            })
+            .map_err(db_error)?;
+        let mut points = rows.collect::<Result<Vec<_>, _>>().map_err(db_error)?;
+        points.reverse();
+        Ok(points)
+    }
+}
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("History database error: {}", e)
+}
+
+fn unknown_metric(metric: &str) -> String {
+    let names: Vec<&str> = METRICS.iter().map(|(_, alias)| *alias).collect();
+    format!(
+        "Unknown metric '{}' (expected one of: {})",
+        metric,
+        names.join(", ")
+    )
+}
+
+/// Trend table for one metric: each run with its change from the previous
+/// run of the same scenario, then min / max / first-to-last change
+pub fn trend_markdown(metric: &str, points: &[HistoryPoint]) -> String {
+    let mut md = format!("### {} ({} runs)\n\n", metric, points.len());
+    if points.is_empty() {
+        md.push_str("No recorded runs.\n");
+        return md;
+    }
+
+    md.push_str(&format!(
+        "| Run | Recorded | Scenario | {} | Delta |\n",
+        metric
+    ));
+    md.push_str("|----:|----------|----------|------:|------:|\n");
+    let mut previous: HashMap<&str, f64> = HashMap::new();
+    for p in points {
+        let delta = previous
+            .insert(&p.scenario, p.value)
+            .map(|prev| format!("{:+.4}", p.value - prev))
+            .unwrap_or_default();
+        md.push_str(&format!(
+            "| {} | {} | {} | {:.4} | {} |\n",
+            p.run_id, p.recorded_at, p.scenario, p.value, delta
+        ));
+    }
+
+    let min = points.iter().map(|p| p.value).fold(f64::INFINITY, f64::min);
+    let max = points
+        .iter()
+        .map(|p| p.value)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let change = points[points.len() - 1].value - points[0].value;
+    md.push_str(&format!(
+        "\nMin {:.4} | Max {:.4} | First → last {:+.4}\n",
+        min, max, change
+    ));
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LatencyMetrics;
+
+    fn result(config: &str, f1: f64, p99: f64) -> BenchmarkResults {
+        BenchmarkResults {
+            config: config.to_string(),
+            f1_score: f1,
+            latency_micros: LatencyMetrics {
+                p99_micros: p99,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_series_filters_by_scenario_and_limits() {
+        let mut store = HistoryStore::open(":memory:").unwrap();
+        store
+            .append("mixed", &result("Mixed Workload", 0.80, 40.0))
+            .unwrap();
+        store
+            .append("quick", &result("Quick Validation", 0.95, 10.0))
+            .unwrap();
+        store
+            .append("mixed", &result("Mixed Workload", 0.82, 38.0))
+            .unwrap();
+        store
+            .append("mixed", &result("Mixed Workload", 0.85, 41.0))
+            .unwrap();
+
+        let f1 = store.series("f1", Some("mixed"), 10).unwrap();
+        let values: Vec<f64> = f1.iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![0.80, 0.82, 0.85]);
+        // Config names match too, and the limit keeps the newest runs
+        let recent = store
+            .series("p99_micros", Some("Mixed Workload"), 2)
+            .unwrap();
+        assert_eq!(
+            recent.iter().map(|p| p.run_id).collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert_eq!(store.series("f1", None, 10).unwrap().len(), 4);
+
+        // Allocations were not counted, so nothing is recorded for them
+        assert!(store.series("allocations", None, 10).unwrap().is_empty());
+        assert!(store.series("latency", None, 10).is_err());
+
+        let md = trend_markdown("f1_score", &f1);
+        assert!(md.contains("| 3 |"));
+        assert!(md.contains("+0.0200"));
+        assert!(md.contains("First → last +0.0500"));
+    }
+}
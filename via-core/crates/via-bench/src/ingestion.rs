@@ -0,0 +1,290 @@
+//! Simulated Ingestion Delay
+//!
+//! Models collection pipeline lag between a log's simulated timestamp and
+//! the moment it reaches the detector: every log is held for a fixed delay
+//! plus uniform jitter, then released in arrival order. Jitter larger than
+//! the gap between logs reorders them, as a real collector would. The delay
+//! sweep re-runs a benchmark at growing delays to show how time-to-detect
+//! and accuracy degrade.
+
+use crate::{BenchmarkConfig, BenchmarkResults, BenchmarkRunner};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use via_sim::LogRecord;
+
+/// Delay/jitter model applied between generation and detection
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct IngestionDelay {
+    /// Fixed delay added to every log (ms)
+    pub delay_ms: u64,
+    /// Upper bound of the uniform extra delay per log (ms)
+    pub jitter_ms: u64,
+}
+
+impl IngestionDelay {
+    pub fn is_enabled(&self) -> bool {
+        self.delay_ms > 0 || self.jitter_ms > 0
+    }
+}
+
+/// How the delay line shaped the run
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct IngestionMetrics {
+    pub delay_ms: u64,
+    pub jitter_ms: u64,
+    pub mean_delay_ms: f64,
+    pub max_delay_ms: f64,
+    /// Logs handed to the detector with an older timestamp than one before them
+    pub reordered_events: u64,
+}
+
+/// Log waiting in the delay line, ordered by arrival then generation order
+struct Pending {
+    arrival_ns: u64,
+    seq: u64,
+    log: LogRecord,
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        (self.arrival_ns, self.seq) == (other.arrival_ns, other.seq)
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.arrival_ns, self.seq).cmp(&(other.arrival_ns, other.seq))
+    }
+}
+
+/// Holds logs until their simulated arrival time
+pub(crate) struct DelayLine {
+    delay_ns: u64,
+    jitter_ns: u64,
+    rng: StdRng,
+    queue: BinaryHeap<Reverse<Pending>>,
+    seq: u64,
+    last_released_ns: u64,
+    total_delay_ns: u64,
+    max_delay_ns: u64,
+    released: u64,
+    reordered: u64,
+}
+
+impl DelayLine {
+    pub(crate) fn new(config: &IngestionDelay, seed: u64) -> Self {
+        Self {
+            delay_ns: config.delay_ms * 1_000_000,
+            jitter_ns: config.jitter_ms * 1_000_000,
+            rng: StdRng::seed_from_u64(seed),
+            queue: BinaryHeap::new(),
+            seq: 0,
+            last_released_ns: 0,
+            total_delay_ns: 0,
+            max_delay_ns: 0,
+            released: 0,
+            reordered: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, log: LogRecord) {
+        let timestamp: u64 = log.timeUnixNano.parse().unwrap_or(0);
+        let jitter = if self.jitter_ns > 0 {
+            self.rng.random_range(0..=self.jitter_ns)
+        } else {
+            0
+        };
+        self.queue.push(Reverse(Pending {
+            arrival_ns: timestamp + self.delay_ns + jitter,
+            seq: self.seq,
+            log,
+        }));
+        self.seq += 1;
+    }
+
+    /// Pop the next log that has arrived by `now_ns`, with its delay (ns)
+    pub(crate) fn release(&mut self, now_ns: u64) -> Option<(LogRecord, u64)> {
+        if self.queue.peek()?.0.arrival_ns > now_ns {
+            return None;
+        }
+        self.pop()
+    }
+
+    /// Pop the next log regardless of arrival time (end of run)
+    pub(crate) fn pop(&mut self) -> Option<(LogRecord, u64)> {
+        let Reverse(pending) = self.queue.pop()?;
+        let timestamp: u64 = pending.log.timeUnixNano.parse().unwrap_or(0);
+        let delay = pending.arrival_ns - timestamp;
+
+        if timestamp < self.last_released_ns {
+            self.reordered += 1;
+        }
+        self.last_released_ns = self.last_released_ns.max(timestamp);
+        self.total_delay_ns += delay;
+        self.max_delay_ns = self.max_delay_ns.max(delay);
+        self.released += 1;
+        Some((pending.log, delay))
+    }
+
+    pub(crate) fn metrics(&self, config: &IngestionDelay) -> IngestionMetrics {
+        IngestionMetrics {
+            delay_ms: config.delay_ms,
+            jitter_ms: config.jitter_ms,
+            mean_delay_ms: self.total_delay_ns as f64 / self.released.max(1) as f64 / 1e6,
+            max_delay_ms: self.max_delay_ns as f64 / 1e6,
+            reordered_events: self.reordered,
+        }
+    }
+}
+
+/// Accuracy and time-to-detect at one delay level
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DelaySweepPoint {
+    pub delay_ms: u64,
+    pub jitter_ms: u64,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1_score: f64,
+    pub median_time_to_detect_ms: f64,
+    pub p95_time_to_detect_ms: f64,
+    pub reordered_events: u64,
+}
+
+impl DelaySweepPoint {
+    fn from_results(delay: &IngestionDelay, r: &BenchmarkResults) -> Self {
+        Self {
+            delay_ms: delay.delay_ms,
+            jitter_ms: delay.jitter_ms,
+            precision: r.precision,
+            recall: r.recall,
+            f1_score: r.f1_score,
+            median_time_to_detect_ms: r.time_to_detect.median_ms,
+            p95_time_to_detect_ms: r.time_to_detect.p95_ms,
+            reordered_events: r.ingestion.as_ref().map_or(0, |i| i.reordered_events),
+        }
+    }
+}
+
+/// Full sweep output
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DelaySweepResults {
+    pub config: String,
+    pub points: Vec<DelaySweepPoint>,
+}
+
+/// Run `base` once per delay (ms), with jitter of `jitter_ratio` × delay
+///
+/// A zero delay runs without the delay line and serves as the baseline.
+pub fn run_delay_sweep(
+    base: &BenchmarkConfig,
+    delays_ms: &[u64],
+    jitter_ratio: f64,
+) -> Result<DelaySweepResults, String> {
+    let points = delays_ms
+        .iter()
+        .map(|&delay_ms| {
+            let delay = IngestionDelay {
+                delay_ms,
+                jitter_ms: (delay_ms as f64 * jitter_ratio.max(0.0)).round() as u64,
+            };
+            let mut config = base.clone();
+            config.name = format!("{} @ {}ms", base.name, delay_ms);
+            config.ingestion_delay = delay.clone();
+
+            let results = BenchmarkRunner::new().run(config)?;
+            Ok(DelaySweepPoint::from_results(&delay, &results))
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(DelaySweepResults {
+        config: base.name.clone(),
+        points,
+    })
+}
+
+/// Print accuracy and time-to-detect against ingestion delay as a table
+pub fn print_delay_sweep(results: &DelaySweepResults) {
+    println!("\n╔══════════════════════════════════════════════════════════════╗");
+    println!("║                 INGESTION DELAY SWEEP                        ║");
+    println!("╠══════════════════════════════════════════════════════════════╣");
+    println!("║ Config: {:52} ║", results.config);
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    println!("║ Delay ms | Jitter |   F1   | Recall | TTD p50 ms | Reordered ║");
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    for p in &results.points {
+        println!(
+            "║ {:>8} | {:>6} | {:>6.3} | {:>5.1}% | {:>10.1} | {:>9} ║",
+            p.delay_ms,
+            p.jitter_ms,
+            p.f1_score,
+            p.recall * 100.0,
+            p.median_time_to_detect_ms,
+            p.reordered_events
+        );
+    }
+    println!("╚══════════════════════════════════════════════════════════════╝");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_at(ts_ns: u64) -> LogRecord {
+        LogRecord {
+            timeUnixNano: ts_ns.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_delay_line_holds_and_reorders() {
+        let config = IngestionDelay {
+            delay_ms: 100,
+            jitter_ms: 50,
+        };
+        let mut line = DelayLine::new(&config, 7);
+        for i in 0..200u64 {
+            line.push(log_at(i * 1_000_000));
+        }
+
+        // Nothing arrives before the fixed delay
+        assert!(line.release(99_000_000).is_none());
+
+        let mut released = 0;
+        while let Some((_, delay)) = line.release(u64::MAX) {
+            assert!((100_000_000..=150_000_000).contains(&delay));
+            released += 1;
+        }
+        assert_eq!(released, 200);
+
+        let metrics = line.metrics(&config);
+        assert!(metrics.reordered_events > 0);
+        assert!(metrics.mean_delay_ms > 100.0 && metrics.max_delay_ms <= 150.0);
+    }
+
+    #[test]
+    fn test_fixed_delay_keeps_order() {
+        let config = IngestionDelay {
+            delay_ms: 20,
+            jitter_ms: 0,
+        };
+        let mut line = DelayLine::new(&config, 7);
+        for i in 0..50u64 {
+            line.push(log_at(i * 1_000_000));
+        }
+        while line.pop().is_some() {}
+        assert_eq!(line.metrics(&config).reordered_events, 0);
+    }
+}
@@ -1,19 +1,132 @@
 //! Benchmark Suite for VIA Detection
 //!
-//! Comprehensive evaluation of all 10 SOTA detectors with proper ground truth tracking:
+//! Comprehensive evaluation of all 13 SOTA detectors with proper ground truth tracking:
 //! - Precision, Recall, F1-Score per detector
+//! - TP / FP / FN per injected scenario and per time bucket, next to the
+//!   per-service and per-severity breakdowns
 //! - Latency measurements (p50, p95, p99)
 //! - Throughput (EPS)
-//! - Detection latency (time to detect)
-
+//! - Detection latency (time to detect), per scenario and per detector
+//! - Optional per-service profiles backed by `ProfileRegistry`
+//! - Weighted composite score for tracking progress across releases
+//! - Window-based (point-adjust / NAB-style) scoring with tolerance
+//! - Warmup events (profile warmup or `warmup_secs`) reported apart from the
+//!   confusion matrix
+//! - Rate-distortion sweeps (accuracy vs offered load)
+//! - Startup canary (`canary()`) checking a short seeded run against expected ranges
+//! - Baseline comparison with per-metric deltas and regression flags
+//! - ROC / PR curves (AUC, AUPRC) from a threshold sweep over ensemble scores
+//! - Calibration curve and Brier scores of the ensemble score as a probability
+//! - FFI-path runs measuring the C ABI overhead seen by the Bun host
+//! - Replay of recorded OTLP / JSON-lines captures (`run_replay`) and streamed
+//!   batches such as a Kafka topic (`run_stream`, feature `kafka`)
+//! - TOML benchmark suites and rate sweep specs (`suite`)
+//! - Embedded SQLite run history with per-metric trends (`history`, feature
+//!   `history`)
+//! - Simulated ingestion delay and jitter, with a delay sweep (`ingestion`)
+//! - Registry eviction under entity churn: hit rate, re-creations and
+//!   accuracy across registry capacities (`churn`)
+//! - Delayed, optionally noisy ground-truth feedback into the adaptive
+//!   ensemble, with F1 over time against a run without it (`feedback_loop`)
+//! - Per-detector ablation: marginal F1 and latency of each detector
+//!   (`ablation`)
+//! - Sensitivity to flipped feedback labels and to a warmup period
+//!   contaminated with unlabeled anomalies (`robustness`)
+//! - Repeated runs over seeds with bootstrap confidence intervals, and
+//!   significance of differences between configs (`repeats`)
+//! - Grid search over `ProfileConfig` values, ranked by F1 (`grid`)
+//! - Configurable entity key, value and filters per log (`extraction`)
+//! - NAB, Yahoo S5 and SMD dataset loaders for runs on real labeled data
+//!   (`datasets`)
+//! - Per-event detection traces as CSV or Parquet for slicing false positives
+//!   in external tools (`trace`)
+//! - Self-contained HTML reports with score timelines against ground truth,
+//!   per-detector bars and latency histograms (`report`)
+//! - Long soak runs with recurring anomalies, streaming rolling metric and
+//!   memory snapshots instead of keeping every event (`soak`)
+//! - Metrics folded in as events are scored, so memory does not grow with
+//!   run length (`online`); full events are only kept spilled to disk
+//! - CPU flamegraphs of a run with pprof (`profiling`, feature `profiling`)
+//! - Detection CPU-seconds per event, priced per million events by a cost
+//!   model (`cost`)
+//! - Multi-threaded throughput: producers sharing a sharded registry, with
+//!   aggregate EPS and scaling efficiency per core (`parallel`)
+//! - Coverage matrix of detector F1 per anomaly type, one run per via-sim
+//!   anomaly scenario (`coverage`)
+//! - Live terminal dashboard of a running scenario: EPS, active anomalies,
+//!   per-detector fire rates and rolling F1 (`watch`, feature `tui`)
+
+use cost::{CostMetrics, CostModel};
+use extraction::ExtractionSpec;
+use feedback_loop::LabelQueue;
+use ingestion::DelayLine;
+use online::{OnlineMetrics, Tally};
+use scoring::ConfusionCounts;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::time::Instant;
-use via_core::engine::AnomalyProfile;
+use via_core::alloc_counter;
+use via_core::engine::{AnomalyProfile, ExogenousContext, ProfileConfig};
+use via_core::registry::{ProfileRegistry, RegistryConfig};
 use via_core::signal::{AnomalySignal, DetectorId, NUM_DETECTORS};
-use via_sim::{LogRecord, SimulationEngine};
-
+use via_sim::{
+    DeliveryConfig, EntityKey, GroundTruth, LogRecord, ReplaySource, SimulationBatch,
+    SimulationEngine,
+};
+
+pub mod ablation;
+pub mod canary;
+pub mod churn;
+pub mod compare;
+pub mod cost;
+pub mod coverage;
+pub mod curves;
+pub mod datasets;
+pub mod extraction;
+pub mod feedback_loop;
+pub mod ffi;
+pub mod grid;
+#[cfg(feature = "history")]
+pub mod history;
+pub mod ingestion;
+mod online;
+pub mod parallel;
 pub mod pipeline;
+#[cfg(feature = "profiling")]
+pub mod profiling;
+pub mod rate_sweep;
+pub mod repeats;
+pub mod report;
+pub mod robustness;
+pub mod score;
+pub mod scoring;
+pub mod soak;
+pub mod suite;
+pub mod trace;
+#[cfg(feature = "tui")]
+pub mod watch;
+
+pub use canary::{CanaryCheck, CanaryReport, canary};
+pub use curves::{CalibrationReport, CurvePoint, ThresholdCurves};
+pub use feedback_loop::{FeedbackLoopConfig, FeedbackLoopStats};
+pub use ingestion::{IngestionDelay, IngestionMetrics};
+pub use repeats::{MetricSummary, RepeatSummary};
+pub use report::{Timeline, TimelineBucket, TimelineWindow};
+pub use score::ScoreWeights;
+pub use scoring::{ScoringConfig, ScoringMode, TimeToDetect, WindowedMetrics};
+pub use soak::{SoakConfig, SoakSnapshot, SoakSummary};
+
+/// Breakdown rows printed before the rest are summarized (e.g. per-device fleets)
+const MAX_BREAKDOWN_ROWS: usize = 20;
+
+/// Equal spans of the run in `time_metrics`
+pub const TIME_BREAKDOWN_BUCKETS: usize = 10;
+
+/// `scenario_metrics` slice of events outside every ground-truth window
+pub const OUTSIDE_WINDOWS: &str = "(no anomaly)";
+
+/// Longest contamination burst; longer totals are split across the warmup
+const CONTAMINATION_BURST_SECS: u64 = 5;
 
 /// Benchmark configuration
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -21,6 +134,13 @@ pub struct BenchmarkConfig {
     pub name: String,
     pub base_scenario: String,
     pub duration_minutes: u64,
+    /// Sub-minute duration; overrides `duration_minutes` when non-zero
+    #[serde(default)]
+    pub duration_secs: u64,
+    /// Run start no anomaly is scheduled into; its events are reported apart
+    /// from the confusion matrix, as are events a profile scores in warmup
+    #[serde(default)]
+    pub warmup_secs: u64,
     pub tick_ms: u64,
     #[serde(default = "default_simulation_seed")]
     pub simulation_seed: u64,
@@ -28,10 +148,114 @@ pub struct BenchmarkConfig {
     /// Batch size for batch processing mode (0 = single event mode)
     #[serde(default)]
     pub batch_size: usize,
+    /// Keep one profile per `service.name` instead of a single global profile
+    #[serde(default)]
+    pub per_service: bool,
+    /// Registry capacity in per-service mode (0 = registry default)
+    #[serde(default)]
+    pub max_profiles: usize,
+    /// Weights for the composite score
+    #[serde(default)]
+    pub score_weights: ScoreWeights,
+    /// Window-based scoring mode and tolerance
+    #[serde(default)]
+    pub scoring: ScoringConfig,
+    /// Multiplier on every scenario's emission rate
+    #[serde(default = "default_rate_scale")]
+    pub rate_scale: f64,
+    /// Drive detection through the exported C ABI instead of direct calls
+    #[serde(default)]
+    pub ffi_path: bool,
+    /// Suppress progress output (for programmatic runs such as the canary)
+    #[serde(default)]
+    pub quiet: bool,
+    /// Tag logs with business-hours/deploy context and pass it to detection
+    #[serde(default)]
+    pub exogenous_context: bool,
+    /// Deploy rollouts to simulate; implies `exogenous_context`
+    #[serde(default)]
+    pub deploys: Vec<DeploySpec>,
+    /// Collection lag between a log's timestamp and its detection
+    #[serde(default)]
+    pub ingestion_delay: IngestionDelay,
+    /// Clock skew, late batches and duplicates between simulator and ingest
+    #[serde(default)]
+    pub delivery: DeliveryConfig,
+    /// Ground-truth labels applied back to the profiles during the run
+    #[serde(default)]
+    pub feedback: FeedbackLoopConfig,
+    /// Share of the warmup period (before the first anomaly) seeded with
+    /// unlabeled anomalies (0.0 - 1.0)
+    #[serde(default)]
+    pub contamination: f64,
+    /// Built-in detectors switched off in every profile
+    #[serde(default)]
+    pub disabled_detectors: Vec<DetectorId>,
+    /// Numeric `ProfileConfig` fields to override, dotted for nested fields
+    /// (see `grid::apply_overrides`)
+    #[serde(default)]
+    pub profile_overrides: BTreeMap<String, f64>,
+    /// Entity key, value and filters taken from each log
+    #[serde(default)]
+    pub extraction: ExtractionSpec,
+    /// Write every scored event to this file (CSV, or Parquet by extension;
+    /// see `trace`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<String>,
+    /// Keep every scored event in a temporary file for `scored_events` and
+    /// `write_trace` after the run; implied by `trace`
+    #[serde(default)]
+    pub spill_events: bool,
+    /// Price and power of a core for `BenchmarkResults::cost`
+    #[serde(default)]
+    pub cost_model: CostModel,
 }
 
+impl BenchmarkConfig {
+    /// Detection settings of every profile in the run
+    pub fn profile_config(&self) -> Result<ProfileConfig, String> {
+        let config = ProfileConfig {
+            disabled_detectors: self.disabled_detectors.clone(),
+            ..Default::default()
+        };
+        grid::apply_overrides(&config, &self.profile_overrides)
+    }
+
+    /// Built-in detectors not in `disabled_detectors`
+    pub fn enabled_detectors(&self) -> Vec<DetectorId> {
+        DetectorId::ALL
+            .into_iter()
+            .filter(|id| !self.disabled_detectors.contains(id))
+            .collect()
+    }
+
+    /// Run only `detectors`, switching every other built-in detector off
+    pub fn select_detectors(&mut self, detectors: &[DetectorId]) {
+        self.disabled_detectors = DetectorId::ALL
+            .into_iter()
+            .filter(|id| !detectors.contains(id))
+            .collect();
+    }
+
+    /// Simulated run length
+    pub fn duration_ns(&self) -> u64 {
+        if self.duration_secs > 0 {
+            self.duration_secs * 1_000_000_000
+        } else {
+            self.duration_minutes * 60 * 1_000_000_000
+        }
+    }
+}
+
+/// Seed of configs that don't set `simulation_seed`
+pub const DEFAULT_SEED: u64 = 42;
+
 fn default_simulation_seed() -> u64 {
-    42
+    DEFAULT_SEED
+}
+
+fn default_rate_scale() -> f64 {
+    1.0
 }
 
 impl Default for BenchmarkConfig {
@@ -40,10 +264,31 @@ impl Default for BenchmarkConfig {
             name: "Default Benchmark".to_string(),
             base_scenario: "normal_traffic".to_string(),
             duration_minutes: 5,
+            duration_secs: 0,
+            warmup_secs: 0,
             tick_ms: 100,
             simulation_seed: default_simulation_seed(),
             anomalies: Vec::new(),
             batch_size: 0, // Single event mode by default
+            per_service: false,
+            max_profiles: 0,
+            score_weights: ScoreWeights::default(),
+            scoring: ScoringConfig::default(),
+            rate_scale: default_rate_scale(),
+            ffi_path: false,
+            quiet: false,
+            exogenous_context: false,
+            deploys: Vec::new(),
+            ingestion_delay: IngestionDelay::default(),
+            delivery: DeliveryConfig::default(),
+            feedback: FeedbackLoopConfig::default(),
+            contamination: 0.0,
+            disabled_detectors: Vec::new(),
+            profile_overrides: BTreeMap::new(),
+            extraction: ExtractionSpec::default(),
+            trace: None,
+            spill_events: false,
+            cost_model: CostModel::default(),
         }
     }
 }
@@ -59,8 +304,16 @@ pub struct AnomalySpec {
     pub duration_sec: u64,
 }
 
-/// Benchmark results with proper metrics
+/// Deploy rollout for benchmarks: shifts detection sensitivity, not ground truth
 #[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeploySpec {
+    /// Offset from simulation start (seconds)
+    pub start_time_sec: u64,
+    pub duration_sec: u64,
+}
+
+/// Benchmark results with proper metrics
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct BenchmarkResults {
     pub config: String,
     pub total_events: u64,
@@ -77,12 +330,88 @@ pub struct BenchmarkResults {
     pub recall: f64,
     pub f1_score: f64,
 
+    // Threshold-independent accuracy of the ensemble score
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curves: Option<ThresholdCurves>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration: Option<CalibrationReport>,
+    /// Scores and detections over time against the ground-truth windows
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeline: Option<Timeline>,
+
     // Per-detector breakdown
     pub detector_metrics: HashMap<String, DetectorMetrics>,
 
     // Performance
     pub latency_micros: LatencyMetrics,
     pub throughput_eps: f64,
+    /// Process peak resident set size (0 when unavailable)
+    #[serde(default)]
+    pub peak_rss_bytes: u64,
+    /// Heap allocations per detected event (built with feature `alloc-counter`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocations_per_event: Option<f64>,
+    /// Detection CPU time, priced by `BenchmarkConfig::cost_model`
+    #[serde(default)]
+    pub cost: CostMetrics,
+
+    // Time from anomaly window start to first true-positive detection
+    #[serde(default)]
+    pub time_to_detect: TimeToDetect,
+    #[serde(default)]
+    pub time_to_detect_by_scenario: HashMap<String, TimeToDetect>,
+    /// Time-to-detect over missing-data windows only (absent without any)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub absence: Option<TimeToDetect>,
+
+    // Window-based scoring (absent in per-event mode)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub windowed: Option<WindowedMetrics>,
+
+    // Headline number (0-100), see `score::composite_score`
+    #[serde(default)]
+    pub composite_score: f64,
+
+    // Breakdowns by `service.name` and by generated log severity
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub service_metrics: HashMap<String, BreakdownMetrics>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub severity_metrics: HashMap<String, BreakdownMetrics>,
+    // By the injected scenario whose window an event falls in, and by time
+    // since the run started
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub scenario_metrics: HashMap<String, BreakdownMetrics>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub time_metrics: Vec<BreakdownMetrics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<RegistryMetrics>,
+    // Ground-truth entities reached (absent with the default entity key)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entities: Option<EntityMetrics>,
+    // Delay line statistics (absent without an ingestion delay)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ingestion: Option<IngestionMetrics>,
+    // Label traffic of the feedback loop (absent when it is off)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback: Option<FeedbackLoopStats>,
+    // Warmup events left out of every metric above
+    #[serde(default)]
+    pub warmup: WarmupMetrics,
+    // Detectors that ran (absent when all of them did)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub detectors: Vec<DetectorId>,
+    // Accuracy across seeds (absent for a single run)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeats: Option<RepeatSummary>,
+}
+
+/// Events scored while a profile was warming up or inside `warmup_secs`,
+/// excluded from accuracy metrics
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct WarmupMetrics {
+    pub events: u64,
+    pub anomaly_events: u64,
+    pub detections: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -98,6 +427,50 @@ pub struct DetectorMetrics {
     pub avg_score: f64,
     pub trigger_count: u64,
     pub total_score: f64,
+    #[serde(default)]
+    pub time_to_detect: TimeToDetect,
+}
+
+/// Accuracy over one slice of the events (a service, log severity, scenario
+/// window or time bucket)
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct BreakdownMetrics {
+    pub name: String,
+    pub events: u64,
+    pub anomaly_events: u64,
+    pub true_positives: u64,
+    pub false_positives: u64,
+    pub true_negatives: u64,
+    pub false_negatives: u64,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1_score: f64,
+}
+
+/// Ground-truth entities under the run's entity key reached by detection
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct EntityMetrics {
+    pub entity_key: String,
+    /// Distinct entities across the anomaly windows' `target_entities`
+    pub target_entities: usize,
+    /// Target entities with at least one true-positive detection
+    pub detected_entities: usize,
+    pub entity_recall: f64,
+}
+
+/// Profile registry usage at the end of a per-service run
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RegistryMetrics {
+    pub profiles: usize,
+    pub capacity: usize,
+    pub creations: u64,
+    pub evictions: u64,
+    /// Share of lookups that found the entity's profile already in the registry
+    #[serde(default)]
+    pub hit_rate: f64,
+    /// Profiles created again for an entity whose profile had been evicted
+    #[serde(default)]
+    pub recreations: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -106,114 +479,198 @@ pub struct LatencyMetrics {
     pub p95_micros: f64,
     pub p99_micros: f64,
     pub avg_micros: f64,
+    /// Event counts in power-of-two latency buckets
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub histogram: Vec<LatencyBucket>,
+}
+
+/// Events whose latency is below `upper_micros` (and at least half of it)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct LatencyBucket {
+    pub upper_micros: u64,
+    pub count: u64,
 }
 
 /// Detection event for tracking
+#[derive(Serialize, Deserialize)]
 struct DetectionEvent {
+    service_hash: u64,
+    severity: u32,
+    anomaly_id: Option<String>,
     is_ground_truth_anomaly: bool,
     detected_as_anomaly: bool,
+    /// Time between the log's timestamp and its arrival at the detector
+    ingest_delay_ns: u64,
     signal: AnomalySignal,
 }
 
 /// Main benchmark runner with proper ground truth tracking
 pub struct BenchmarkRunner {
     profile: AnomalyProfile,
-    detection_events: Vec<DetectionEvent>,
-    latencies: Vec<u64>,
+    /// Configuration of `profile` and of every registry profile
+    profile_config: ProfileConfig,
+    /// Per-service profiles, present only when `per_service` is enabled
+    registry: Option<ProfileRegistry<AnomalyProfile>>,
+    /// Entities that ever had a registry profile, to spot re-creations
+    profiled: HashSet<u64>,
+    recreations: u64,
+    /// Route events through the C ABI (see `ffi::process_event`)
+    ffi_path: bool,
+    /// Entity key, value and filters taken from each log
+    extraction: ExtractionSpec,
+    service_names: HashMap<u64, String>,
+    severity_names: HashMap<u32, String>,
+    /// Anomaly windows reported by the simulator
+    ground_truth: Vec<GroundTruth>,
+    /// Contamination anomalies, detected over but scored as normal
+    unlabeled: HashSet<String>,
+    /// Events before this log time are warmup (`warmup_secs` after the
+    /// first event), set on the first recorded event
+    warmup_end_ns: Option<u64>,
+    warmup_secs: u64,
+    warmup: WarmupMetrics,
+    /// Scored events of the run so far, folded into counts
+    metrics: OnlineMetrics,
+    /// The scored events themselves, with `spill_events`
+    spill: Option<trace::Spill>,
+    /// Heap allocations made by detection (always 0 without `alloc-counter`)
+    allocations: u64,
+    /// Ground-truth labels in flight, present only with the feedback loop
+    labels: Option<LabelQueue>,
+    /// Scratch for `AnomalyProfile::process_batch_into`, kept across batches
+    batch_events: Vec<(u64, u64, f64)>,
+    batch_signals: Vec<AnomalySignal>,
 }
 
 impl BenchmarkRunner {
     pub fn new() -> Self {
         Self {
             profile: AnomalyProfile::default(),
-            detection_events: Vec::new(),
-            latencies: Vec::new(),
+            profile_config: ProfileConfig::default(),
+            registry: None,
+            profiled: HashSet::new(),
+            recreations: 0,
+            ffi_path: false,
+            extraction: ExtractionSpec::default(),
+            service_names: HashMap::new(),
+            severity_names: HashMap::new(),
+            ground_truth: Vec::new(),
+            unlabeled: HashSet::new(),
+            warmup_end_ns: None,
+            warmup_secs: 0,
+            warmup: WarmupMetrics::default(),
+            metrics: OnlineMetrics::new(&ScoringConfig::default()),
+            spill: None,
+            allocations: 0,
+            labels: None,
+            batch_events: Vec::new(),
+            batch_signals: Vec::new(),
         }
     }
 
-    pub fn run(&mut self, config: BenchmarkConfig) -> BenchmarkResults {
-        let batch_mode = if config.batch_size > 0 {
-            format!("Batch Size: {}", config.batch_size)
-        } else {
-            "Single Event Mode".to_string()
-        };
-
-        println!("╔══════════════════════════════════════════════════════════════╗");
-        println!("║           VIA Benchmark Suite - Ground Truth Mode            ║");
-        println!("╠══════════════════════════════════════════════════════════════╣");
-        println!("║ Config: {:50} ║", config.name);
-        println!(
-            "║ Duration: {} min | Base: {:35} ║",
-            config.duration_minutes, config.base_scenario
-        );
-        println!(
-            "║ Anomalies: {:3} scheduled {:>34} ║",
-            config.anomalies.len(),
-            ""
-        );
-        println!("║ Mode: {:52} ║", batch_mode);
-        println!("╚══════════════════════════════════════════════════════════════╝");
+    /// Run `config`'s scenario through detection; fails before the run
+    /// starts if the profile settings are invalid
+    pub fn run(&mut self, config: BenchmarkConfig) -> Result<BenchmarkResults, String> {
+        let batch_mode = self.configure(&config)?;
+
+        let quiet = config.quiet;
+        if !quiet {
+            println!("╔══════════════════════════════════════════════════════════════╗");
+            println!("║           VIA Benchmark Suite - Ground Truth Mode            ║");
+            println!("╠══════════════════════════════════════════════════════════════╣");
+            println!("║ Config: {:50} ║", config.name);
+            println!(
+                "║ Duration: {} min | Base: {:35} ║",
+                config.duration_minutes, config.base_scenario
+            );
+            println!(
+                "║ Anomalies: {:3} scheduled {:>34} ║",
+                config.anomalies.len(),
+                ""
+            );
+            println!("║ Mode: {:52} ║", batch_mode);
+            println!("╚══════════════════════════════════════════════════════════════╝");
+        }
 
         let start_time = Instant::now();
 
         // Create simulation engine
         let mut engine = SimulationEngine::new_deterministic(config.simulation_seed);
+        engine.set_rate_scale(config.rate_scale);
         engine.start(&config.base_scenario);
 
-        // Schedule all anomalies
+        engine.set_emit_context(config.exogenous_context);
+        engine.set_delivery(config.delivery.clone());
+        // Ground truth names the entities detection is keyed on
+        if config.extraction.entity_key != EntityKey::default() {
+            engine.set_entity_key(Some(config.extraction.entity_key.clone()));
+        }
+        for deploy in &config.deploys {
+            engine.schedule_deploy(
+                deploy.start_time_sec * 1_000_000_000,
+                deploy.duration_sec * 1_000_000_000,
+            );
+        }
+
+        // Schedule all anomalies, none before the warmup ends
         for anomaly in &config.anomalies {
-            let start_offset_ns = anomaly.start_time_sec * 1_000_000_000;
+            if anomaly.start_time_sec < config.warmup_secs && !quiet {
+                println!(
+                    "  Warning: '{}' starts inside the {}s warmup; moved to {}s",
+                    anomaly.scenario, config.warmup_secs, config.warmup_secs
+                );
+            }
+            let start_offset_ns = anomaly.start_time_sec.max(config.warmup_secs) * 1_000_000_000;
             let duration_ns = anomaly.duration_sec * 1_000_000_000;
-            if let Some(id) =
-                engine.schedule_anomaly(&anomaly.scenario, start_offset_ns, duration_ns)
-            {
-                println!("  Scheduled anomaly '{}' (id: {})", anomaly.scenario, id);
-            } else {
-                println!("  Warning: Unknown scenario '{}'", anomaly.scenario);
+            match engine.schedule_anomaly(&anomaly.scenario, start_offset_ns, duration_ns) {
+                Some(id) if !quiet => {
+                    println!("  Scheduled anomaly '{}' (id: {})", anomaly.scenario, id)
+                }
+                None if !quiet => println!("  Warning: Unknown scenario '{}'", anomaly.scenario),
+                _ => {}
             }
         }
+        self.schedule_contamination(&mut engine, &config);
 
-        let duration_ns = config.duration_minutes * 60 * 1_000_000_000;
+        let duration_ns = config.duration_ns();
         let tick_ns = config.tick_ms * 1_000_000;
         let total_ticks = duration_ns / tick_ns;
         let batch_size = config.batch_size;
 
-        println!("\n🔄 Running benchmark... ({} ticks)\n", total_ticks);
+        if !quiet {
+            println!("\n🔄 Running benchmark... ({} ticks)\n", total_ticks);
+        }
 
         let mut total_events = 0u64;
         let mut _elapsed_ns = 0u64;
 
         // For batched processing, collect logs first
-        let mut pending_logs: Vec<(LogRecord, bool)> = Vec::new();
+        let mut pending_logs: Vec<(LogRecord, u64)> = Vec::new();
+        let mut delay_line = config
+            .ingestion_delay
+            .is_enabled()
+            .then(|| DelayLine::new(&config.ingestion_delay, config.simulation_seed));
 
         for tick in 0..total_ticks {
-            let batch = engine.tick(tick_ns);
+            let mut batch = engine.tick(tick_ns);
             _elapsed_ns += tick_ns;
-
-            // Process each log through detection
-            for resource_log in &batch.logs.resourceLogs {
-                for scope_log in &resource_log.scopeLogs {
-                    for log in &scope_log.logRecords {
-                        if batch_size > 0 {
-                            // Batch mode: collect logs
-                            pending_logs.push((log.clone(), log.isGroundTruthAnomaly));
-
-                            // Process batch when full
-                            if pending_logs.len() >= batch_size {
-                                self.process_batch(&pending_logs);
-                                pending_logs.clear();
-                            }
-                        } else {
-                            // Single event mode
-                            self.process_log(log);
-                        }
-                    }
-                    total_events += scope_log.logRecords.len() as u64;
-                }
+            if tick + 1 == total_ticks
+                && let Some(scope_log) = batch
+                    .logs
+                    .resourceLogs
+                    .first_mut()
+                    .and_then(|r| r.scopeLogs.first_mut())
+            {
+                // Batches the delivery model still holds arrive at the end
+                scope_log.logRecords.extend(engine.flush_delivery());
             }
+            total_events += match delay_line.as_mut() {
+                Some(line) => self.ingest_delayed(&batch, line, batch_size, &mut pending_logs),
+                None => self.ingest(&batch, batch_size, &mut pending_logs),
+            };
 
             // Progress update every 10% or 100 ticks
-            if tick % (total_ticks / 10).max(100) == 0 {
+            if !quiet && tick % (total_ticks / 10).max(100) == 0 {
                 let progress = ((tick + 1) as f64 / total_ticks as f64 * 100.0) as u32;
                 print!(
                     "\r  [{:>3}%] Tick {:>6}/{} | {:>8} events",
@@ -225,148 +682,652 @@ impl BenchmarkRunner {
             }
         }
 
+        // Logs still in flight arrive after the run ends
+        if let Some(line) = delay_line.as_mut() {
+            while let Some((log, delay_ns)) = line.pop() {
+                self.dispatch(log, delay_ns, batch_size, &mut pending_logs);
+            }
+        }
+
         // Process remaining logs in batch mode
         if !pending_logs.is_empty() {
             self.process_batch(&pending_logs);
         }
 
-        println!(
-            "\n\n✅ Benchmark completed in {:.2}s",
-            start_time.elapsed().as_secs_f64()
-        );
+        if !quiet {
+            println!(
+                "\n\n✅ Benchmark completed in {:.2}s",
+                start_time.elapsed().as_secs_f64()
+            );
+        }
 
         // Calculate results
-        self.calculate_results(&config, total_events, start_time.elapsed())
+        let mut results = self.calculate_results(&config, total_events, start_time.elapsed());
+        results.ingestion = delay_line.map(|line| line.metrics(&config.ingestion_delay));
+        Ok(results)
     }
 
-    /// Process a batch of logs (amortizes overhead)
-    fn process_batch(&mut self, logs: &[(LogRecord, bool)]) {
-        let start = Instant::now();
+    /// Replay a recorded capture through detection, `config.tick_ms` of
+    /// capture time per batch. Simulation settings in `config` are ignored.
+    pub fn run_replay(
+        &mut self,
+        config: BenchmarkConfig,
+        source: &mut ReplaySource,
+    ) -> Result<BenchmarkResults, String> {
+        let batch_mode = self.configure(&config)?;
+        let quiet = config.quiet;
+        if !quiet {
+            println!("╔══════════════════════════════════════════════════════════════╗");
+            println!("║           VIA Benchmark Suite - Replay Mode                  ║");
+            println!("╠══════════════════════════════════════════════════════════════╣");
+            println!("║ Config: {:52} ║", config.name);
+            println!(
+                "║ Records: {:>9} | Span: {:>10.1}s | Windows: {:>10} ║",
+                source.len(),
+                source.span_ns() as f64 / 1e9,
+                source.ground_truth().len()
+            );
+            println!("║ Mode: {:54} ║", batch_mode);
+            println!("╚══════════════════════════════════════════════════════════════╝");
+            println!("\n🔄 Replaying capture...\n");
+        }
 
-        for (log, is_anomaly) in logs {
-            let value = log.metric_value();
-            let timestamp: u64 = log.timeUnixNano.parse().unwrap_or(0);
-            let entity_hash = xxhash_rust::xxh3::xxh3_64(log.traceId.as_bytes());
+        let tick_ns = config.tick_ms.max(1) * 1_000_000;
+        Ok(self.consume(config, std::iter::from_fn(|| source.next_batch(tick_ns))))
+    }
 
-            let signal = self
-                .profile
-                .process_with_hash(timestamp, entity_hash, value);
+    /// Run externally sourced batches (e.g. consumed from Kafka) through
+    /// detection until the iterator ends. Throughput includes time spent
+    /// waiting on the source.
+    pub fn run_stream(
+        &mut self,
+        config: BenchmarkConfig,
+        batches: impl IntoIterator<Item = SimulationBatch>,
+    ) -> Result<BenchmarkResults, String> {
+        let batch_mode = self.configure(&config)?;
+        if !config.quiet {
+            println!("╔══════════════════════════════════════════════════════════════╗");
+            println!("║           VIA Benchmark Suite - Stream Mode                  ║");
+            println!("╠══════════════════════════════════════════════════════════════╣");
+            println!("║ Config: {:52} ║", config.name);
+            println!("║ Mode: {:54} ║", batch_mode);
+            println!("╚══════════════════════════════════════════════════════════════╝");
+            println!("\n🔄 Consuming stream...\n");
+        }
+        Ok(self.consume(config, batches))
+    }
 
-            self.detection_events.push(DetectionEvent {
-                is_ground_truth_anomaly: *is_anomaly,
-                detected_as_anomaly: signal.is_anomaly,
-                signal,
-            });
+    /// Seed the warmup period with `contamination` of its length in unlabeled
+    /// anomalies: bursts of the configured scenarios, spread evenly
+    fn schedule_contamination(&mut self, engine: &mut SimulationEngine, config: &BenchmarkConfig) {
+        let Some(warmup_secs) = config.anomalies.iter().map(|a| a.start_time_sec).min() else {
+            return;
+        };
+        let contaminated_secs = (warmup_secs as f64 * config.contamination.clamp(0.0, 1.0)) as u64;
+        let bursts = contaminated_secs.div_ceil(CONTAMINATION_BURST_SECS);
+        if bursts == 0 {
+            return;
         }
+        let slot_secs = warmup_secs / bursts;
+        for burst in 0..bursts {
+            let scenario = &config.anomalies[burst as usize % config.anomalies.len()].scenario;
+            let duration_secs =
+                CONTAMINATION_BURST_SECS.min(contaminated_secs - burst * CONTAMINATION_BURST_SECS);
+            let start_secs = burst * slot_secs + slot_secs.saturating_sub(duration_secs) / 2;
+            if let Some(id) = engine.schedule_anomaly(
+                scenario,
+                start_secs * 1_000_000_000,
+                duration_secs * 1_000_000_000,
+            ) {
+                self.unlabeled.insert(id);
+            }
+        }
+    }
 
-        // Record batch latency (divided by batch size for per-event latency)
-        let elapsed_per_event = start.elapsed().as_micros() as u64 / logs.len().max(1) as u64;
-        self.latencies.push(elapsed_per_event);
+    /// Take the batch's ground truth, minus contamination windows, and match
+    /// the events scored so far against it
+    fn update_ground_truth(&mut self, batch: &SimulationBatch) {
+        if !batch.ground_truth.is_empty() {
+            self.ground_truth = batch
+                .ground_truth
+                .iter()
+                .filter(|gt| !self.unlabeled.contains(&gt.anomaly_id))
+                .cloned()
+                .collect();
+        }
+        self.metrics
+            .advance(&self.ground_truth, batch.metadata.timestamp_ns);
+    }
+
+    /// Detect over every batch, then score against the ground truth they carry
+    fn consume(
+        &mut self,
+        config: BenchmarkConfig,
+        batches: impl IntoIterator<Item = SimulationBatch>,
+    ) -> BenchmarkResults {
+        let start_time = Instant::now();
+        let mut total_events = 0u64;
+        let mut pending_logs: Vec<(LogRecord, u64)> = Vec::new();
+        for batch in batches {
+            total_events += self.ingest(&batch, config.batch_size, &mut pending_logs);
+        }
+        if !pending_logs.is_empty() {
+            self.process_batch(&pending_logs);
+        }
+
+        if !config.quiet {
+            println!(
+                "✅ Completed in {:.2}s ({} events)",
+                start_time.elapsed().as_secs_f64(),
+                total_events
+            );
+        }
+
+        let mut results = self.calculate_results(&config, total_events, start_time.elapsed());
+        results.total_anomalies_injected = self.ground_truth.len();
+        results
+    }
+
+    /// Apply profile settings from the config, returning the mode label, or
+    /// why the profile settings are invalid
+    fn configure(&mut self, config: &BenchmarkConfig) -> Result<String, String> {
+        let mut batch_mode = if config.batch_size > 0 {
+            format!("Batch Size: {}", config.batch_size)
+        } else {
+            "Single Event Mode".to_string()
+        };
+
+        if !config.disabled_detectors.is_empty() || !config.profile_overrides.is_empty() {
+            self.profile_config = config
+                .profile_config()
+                .map_err(|e| format!("Invalid profile settings: {e}"))?;
+            self.profile = AnomalyProfile::with_config(self.profile_config.clone());
+        }
+        if !config.disabled_detectors.is_empty() {
+            batch_mode.push_str(&format!(
+                " | {} Detectors Off",
+                config.disabled_detectors.len()
+            ));
+        }
+        if !config.profile_overrides.is_empty() {
+            batch_mode.push_str(&format!(" | {} Overrides", config.profile_overrides.len()));
+        }
+        if config.per_service {
+            let mut registry_config = RegistryConfig::default();
+            if config.max_profiles > 0 {
+                registry_config.max_profiles = config.max_profiles;
+            }
+            self.registry = Some(ProfileRegistry::with_config(registry_config));
+            self.profiled.clear();
+            self.recreations = 0;
+            batch_mode.push_str(" | Per-Service Profiles");
+        }
+        self.ffi_path = config.ffi_path;
+        if config.ffi_path {
+            batch_mode.push_str(" | FFI Path");
+        }
+        self.extraction = config.extraction.clone();
+        if let Some(label) = self.extraction.label() {
+            batch_mode.push_str(&format!(" | {label}"));
+        }
+        if config.exogenous_context || !config.deploys.is_empty() {
+            batch_mode.push_str(" | Exogenous Context");
+        }
+        if config.ingestion_delay.is_enabled() {
+            batch_mode.push_str(&format!(
+                " | Delay {}±{}ms",
+                config.ingestion_delay.delay_ms, config.ingestion_delay.jitter_ms
+            ));
+        }
+        if config.delivery.is_enabled() {
+            batch_mode.push_str(&format!(
+                " | Skew ±{}ms, Reorder {}, Dup {}",
+                config.delivery.max_skew_ms,
+                config.delivery.reorder_rate,
+                config.delivery.duplicate_rate
+            ));
+        }
+        self.metrics = OnlineMetrics::new(&config.scoring);
+        self.spill = None;
+        if config.spill_events || config.trace.is_some() {
+            match trace::Spill::create() {
+                Ok(spill) => self.spill = Some(spill),
+                Err(e) => eprintln!("  Warning: cannot spill events: {}", e),
+            }
+        }
+        self.unlabeled.clear();
+        self.warmup_end_ns = None;
+        self.warmup_secs = config.warmup_secs;
+        self.warmup = WarmupMetrics::default();
+        if config.warmup_secs > 0 {
+            batch_mode.push_str(&format!(" | Warmup {}s", config.warmup_secs));
+        }
+        if config.contamination > 0.0 {
+            batch_mode.push_str(&format!(
+                " | {:.0}% Contaminated",
+                config.contamination * 100.0
+            ));
+        }
+        self.labels = config
+            .feedback
+            .enabled
+            .then(|| LabelQueue::new(&config.feedback, config.simulation_seed));
+        if config.feedback.enabled {
+            batch_mode.push_str(&format!(
+                " | Feedback {}ms, {:.0}% noise",
+                config.feedback.delay_ms,
+                config.feedback.label_noise * 100.0
+            ));
+        }
+        Ok(batch_mode)
+    }
+
+    /// Run every admitted log of a batch through detection, returning the
+    /// number of logs
+    fn ingest(
+        &mut self,
+        batch: &SimulationBatch,
+        batch_size: usize,
+        pending_logs: &mut Vec<(LogRecord, u64)>,
+    ) -> u64 {
+        self.update_ground_truth(batch);
+
+        let mut events = 0u64;
+        for resource_log in &batch.logs.resourceLogs {
+            for scope_log in &resource_log.scopeLogs {
+                for log in &scope_log.logRecords {
+                    if !self.extraction.admits(log) {
+                        continue;
+                    }
+                    events += 1;
+                    if batch_size > 0 {
+                        // Batch mode: collect logs
+                        pending_logs.push((log.clone(), 0));
+
+                        // Process batch when full
+                        if pending_logs.len() >= batch_size {
+                            self.process_batch(pending_logs);
+                            pending_logs.clear();
+                        }
+                    } else {
+                        // Single event mode
+                        self.process_log(log, 0);
+                    }
+                }
+            }
+        }
+        events
+    }
+
+    /// Queue a batch's logs in the delay line, then detect over every log
+    /// that has arrived by the batch's simulation time
+    fn ingest_delayed(
+        &mut self,
+        batch: &SimulationBatch,
+        line: &mut DelayLine,
+        batch_size: usize,
+        pending_logs: &mut Vec<(LogRecord, u64)>,
+    ) -> u64 {
+        self.update_ground_truth(batch);
+
+        let mut events = 0u64;
+        for resource_log in &batch.logs.resourceLogs {
+            for scope_log in &resource_log.scopeLogs {
+                for log in &scope_log.logRecords {
+                    if self.extraction.admits(log) {
+                        line.push(log.clone());
+                        events += 1;
+                    }
+                }
+            }
+        }
+        while let Some((log, delay_ns)) = line.release(batch.metadata.timestamp_ns) {
+            self.dispatch(log, delay_ns, batch_size, pending_logs);
+        }
+        events
     }
 
-    fn process_log(&mut self, log: &LogRecord) {
+    /// Detect over one delayed log, in single event or batch mode
+    fn dispatch(
+        &mut self,
+        log: LogRecord,
+        delay_ns: u64,
+        batch_size: usize,
+        pending_logs: &mut Vec<(LogRecord, u64)>,
+    ) {
+        if batch_size == 0 {
+            self.process_log(&log, delay_ns);
+            return;
+        }
+        pending_logs.push((log, delay_ns));
+        if pending_logs.len() >= batch_size {
+            self.process_batch(pending_logs);
+            pending_logs.clear();
+        }
+    }
+
+    /// Process a batch of logs (amortizes overhead)
+    fn process_batch(&mut self, logs: &[(LogRecord, u64)]) {
+        let service_hashes: Vec<u64> = logs.iter().map(|(log, _)| self.service_key(log)).collect();
+        let profile_hashes: Vec<u64> = logs
+            .iter()
+            .zip(&service_hashes)
+            .map(|((log, _), &service_hash)| self.profile_key(log, service_hash))
+            .collect();
+        if let Some((log, _)) = logs.first() {
+            self.deliver_labels(log);
+        }
+        // The C ABI and context-carrying logs have no batch entry point
+        let batchable = !self.ffi_path
+            && logs
+                .iter()
+                .all(|(log, _)| log.exogenous_context() == ExogenousContext::default());
         let start = Instant::now();
 
-        // Extract value for detection
-        let value = log.metric_value();
-        let timestamp: u64 = log.timeUnixNano.parse().unwrap_or(0);
-        let entity_hash = xxhash_rust::xxh3::xxh3_64(log.traceId.as_bytes());
+        if batchable {
+            let signals = self.detect_batch(logs, &profile_hashes);
+            for ((((log, delay_ns), service_hash), profile_hash), signal) in logs
+                .iter()
+                .zip(service_hashes)
+                .zip(profile_hashes)
+                .zip(signals)
+            {
+                self.record(log, service_hash, profile_hash, *delay_ns, signal);
+            }
+        } else {
+            for (((log, delay_ns), service_hash), profile_hash) in
+                logs.iter().zip(service_hashes).zip(profile_hashes)
+            {
+                let signal = self.detect(log, profile_hash);
+                self.record(log, service_hash, profile_hash, *delay_ns, signal);
+            }
+        }
+
+        // Record batch latency (divided by batch size for per-event latency)
+        self.metrics
+            .latency
+            .record_span(start.elapsed(), logs.len());
+    }
+
+    fn process_log(&mut self, log: &LogRecord, delay_ns: u64) {
+        let service_hash = self.service_key(log);
+        let profile_hash = self.profile_key(log, service_hash);
+        self.deliver_labels(log);
+        let start = Instant::now();
 
         // Run detection - get full AnomalySignal
-        let signal = self
-            .profile
-            .process_with_hash(timestamp, entity_hash, value);
+        let signal = self.detect(log, profile_hash);
 
-        let elapsed = start.elapsed();
-        self.latencies.push(elapsed.as_micros() as u64);
+        self.metrics.latency.record_span(start.elapsed(), 1);
 
         // Store detection event - ground truth comes from the log itself
-        self.detection_events.push(DetectionEvent {
-            is_ground_truth_anomaly: log.isGroundTruthAnomaly,
+        self.record(log, service_hash, profile_hash, delay_ns, signal);
+    }
+
+    /// Hash of the log's `service.name`, remembering the name for reporting
+    fn service_key(&mut self, log: &LogRecord) -> u64 {
+        let service = log.service_name().unwrap_or("unknown");
+        let service_hash = xxhash_rust::xxh3::xxh3_64(service.as_bytes());
+        self.service_names
+            .entry(service_hash)
+            .or_insert_with(|| service.to_string());
+        service_hash
+    }
+
+    /// Key of the log's profile in per-service mode: `service_hash` unless
+    /// the extraction spec sets a profile key
+    fn profile_key(&self, log: &LogRecord, service_hash: u64) -> u64 {
+        match &self.extraction.profile_key {
+            Some(key) => key.hash(log),
+            None => service_hash,
+        }
+    }
+
+    fn record(
+        &mut self,
+        log: &LogRecord,
+        service_hash: u64,
+        profile_hash: u64,
+        delay_ns: u64,
+        signal: AnomalySignal,
+    ) {
+        // Contamination is unlabeled: reviewed and scored as normal traffic
+        let labeled = log.isGroundTruthAnomaly
+            && !log
+                .anomalyId
+                .as_ref()
+                .is_some_and(|id| self.unlabeled.contains(id));
+        if let Some(labels) = self.labels.as_mut() {
+            labels.push(profile_hash, &signal, labeled);
+        }
+        self.severity_names
+            .entry(log.severityNumber)
+            .or_insert_with(|| log.severityText.clone());
+
+        let timestamp = signal.timestamp;
+        let warmup_end = *self
+            .warmup_end_ns
+            .get_or_insert(timestamp.saturating_add(self.warmup_secs * 1_000_000_000));
+        if signal.baseline.is_warmup || timestamp < warmup_end {
+            self.warmup.events += 1;
+            self.warmup.anomaly_events += labeled as u64;
+            self.warmup.detections += signal.is_anomaly as u64;
+            return;
+        }
+        let event = DetectionEvent {
+            service_hash,
+            severity: log.severityNumber,
+            anomaly_id: log.anomalyId.clone().filter(|_| labeled),
+            is_ground_truth_anomaly: labeled,
             detected_as_anomaly: signal.is_anomaly,
+            ingest_delay_ns: delay_ns,
             signal,
-        });
+        };
+        self.metrics.observe(&event);
+        if let Some(spill) = self.spill.as_mut()
+            && let Err(e) = spill.push(&event)
+        {
+            eprintln!("  Warning: stopped spilling events: {}", e);
+            self.spill = None;
+        }
+    }
+
+    /// Apply every label due by the log's timestamp to the profile that
+    /// scored it (outside the timed section)
+    fn deliver_labels(&mut self, log: &LogRecord) {
+        let Some(labels) = self.labels.as_mut() else {
+            return;
+        };
+        let now: u64 = log.timeUnixNano.parse().unwrap_or(0);
+        while let Some((profile_hash, event)) = labels.release(now) {
+            let profile = match self.registry.as_mut() {
+                Some(registry) => registry.get_mut(profile_hash),
+                None => Some(&mut self.profile),
+            };
+            // Labels for an evicted profile are lost with it
+            if let Some(profile) = profile {
+                profile.apply_feedback(&[event]);
+            }
+        }
+    }
+
+    /// Route a log to its profile (by `profile_key` in per-service mode) and run detection
+    fn detect(&mut self, log: &LogRecord, profile_hash: u64) -> AnomalySignal {
+        // Extract value for detection
+        let value = self.extraction.value(log);
+        let entity = self.extraction.entity(log);
+        let timestamp: u64 = log.timeUnixNano.parse().unwrap_or(0);
+        // Default (no effect) unless the log carries context attributes
+        let exogenous = log.exogenous_context();
+        let ffi_path = self.ffi_path;
+        self.with_profile(profile_hash, |profile| {
+            if ffi_path {
+                ffi::process_event(profile, timestamp, &entity, value, exogenous).1
+            } else {
+                let entity_hash = xxhash_rust::xxh3::xxh3_64(entity.as_bytes());
+                profile.process_with_context(timestamp, entity_hash, value, exogenous)
+            }
+        })
+    }
+
+    /// Detect a batch with one `process_batch_into` call per profile,
+    /// returning signals in log order
+    fn detect_batch(
+        &mut self,
+        logs: &[(LogRecord, u64)],
+        profile_hashes: &[u64],
+    ) -> Vec<AnomalySignal> {
+        let mut signals = vec![AnomalySignal::default(); logs.len()];
+        let mut done = vec![false; logs.len()];
+        for first in 0..logs.len() {
+            if done[first] {
+                continue;
+            }
+            // Without a registry every service shares one profile
+            let profile_hash = profile_hashes[first];
+            let shared = self.registry.is_none();
+            let indices: Vec<usize> = (first..logs.len())
+                .filter(|&i| shared || profile_hashes[i] == profile_hash)
+                .collect();
+
+            let mut events = std::mem::take(&mut self.batch_events);
+            let mut out = std::mem::take(&mut self.batch_signals);
+            events.clear();
+            out.clear();
+            let extraction = &self.extraction;
+            events.extend(indices.iter().map(|&i| {
+                let log = &logs[i].0;
+                (
+                    log.timeUnixNano.parse().unwrap_or(0),
+                    extraction.entity_hash(log),
+                    extraction.value(log),
+                )
+            }));
+            self.with_profile(profile_hash, |profile| {
+                profile.process_batch_into(&events, &mut out, false)
+            });
+
+            for (&i, signal) in indices.iter().zip(out.drain(..)) {
+                signals[i] = signal;
+                done[i] = true;
+            }
+            self.batch_events = events;
+            self.batch_signals = out;
+        }
+        signals
+    }
+
+    /// Run `detect` on the profile for `profile_hash`, counting its heap
+    /// allocations and re-created profiles
+    fn with_profile<R>(
+        &mut self,
+        profile_hash: u64,
+        detect: impl FnOnce(&mut AnomalyProfile) -> R,
+    ) -> R {
+        // A missing profile for an entity seen before was evicted
+        let missing = self
+            .registry
+            .as_ref()
+            .is_some_and(|registry| !registry.contains(profile_hash));
+        if missing && !self.profiled.insert(profile_hash) {
+            self.recreations += 1;
+        }
+
+        let profile_config = &self.profile_config;
+        let (result, allocations) =
+            alloc_counter::count_allocations(|| match self.registry.as_mut() {
+                Some(registry) => detect(registry.get_or_create(profile_hash, || {
+                    AnomalyProfile::with_config(profile_config.clone())
+                })),
+                None => detect(&mut self.profile),
+            });
+        self.allocations += allocations;
+        result
     }
 
     fn calculate_results(
-        &self,
+        &mut self,
         config: &BenchmarkConfig,
         total_events: u64,
         elapsed: std::time::Duration,
     ) -> BenchmarkResults {
-        // Calculate overall TP/FP/TN/FN
-        let mut tp = 0u64;
-        let mut fp = 0u64;
-        let mut tn = 0u64;
-        let mut fn_ = 0u64;
-        let mut anomaly_events = 0u64;
-
-        for event in &self.detection_events {
-            if event.is_ground_truth_anomaly {
-                anomaly_events += 1;
-            }
-
-            match (event.detected_as_anomaly, event.is_ground_truth_anomaly) {
-                (true, true) => tp += 1,
-                (true, false) => fp += 1,
-                (false, true) => fn_ += 1,
-                (false, false) => tn += 1,
-            }
+        self.metrics.finish(&self.ground_truth);
+        if let Some(Err(e)) = self.spill.as_mut().map(trace::Spill::flush) {
+            eprintln!("  Warning: cannot spill events: {}", e);
+            self.spill = None;
         }
 
+        let overall = self.metrics.overall;
+        let ConfusionCounts {
+            true_positives: tp,
+            false_positives: fp,
+            true_negatives: tn,
+            false_negatives: fn_,
+        } = overall.counts;
         let (precision, recall, f1) = calculate_metrics(tp, fp, fn_);
 
         // Calculate per-detector metrics
         let mut detector_metrics = HashMap::new();
-
         for detector_id in 0..NUM_DETECTORS {
             if let Some(id) = DetectorId::from_u8(detector_id as u8) {
                 let name = id.name().to_string();
-                let mut dm = DetectorMetrics {
+                let tally = &self.metrics.detectors[detector_id];
+                let counts = tally.counts;
+                let (p, r, f) = counts.metrics();
+                let delays = self
+                    .metrics
+                    .detection_delays(1 + detector_id, &self.ground_truth);
+                let dm = DetectorMetrics {
                     name: name.clone(),
-                    ..Default::default()
+                    true_positives: counts.true_positives,
+                    false_positives: counts.false_positives,
+                    true_negatives: counts.true_negatives,
+                    false_negatives: counts.false_negatives,
+                    precision: p,
+                    recall: r,
+                    f1_score: f,
+                    trigger_count: counts.true_positives + counts.false_positives,
+                    total_score: tally.score_sum,
+                    avg_score: tally.score_sum / overall.events.max(1) as f64,
+                    time_to_detect: TimeToDetect::from_delays(
+                        delays.len(),
+                        delays.into_iter().flatten().collect(),
+                    ),
                 };
-
-                // Calculate per-detector TP/FP/TN/FN based on which detector fired
-                for event in &self.detection_events {
-                    let detector_fired = event.signal.detector_scores[detector_id].fired;
-
-                    match (detector_fired, event.is_ground_truth_anomaly) {
-                        (true, true) => dm.true_positives += 1,
-                        (true, false) => dm.false_positives += 1,
-                        (false, true) => dm.false_negatives += 1,
-                        (false, false) => dm.true_negatives += 1,
-                    }
-
-                    if detector_fired {
-                        dm.trigger_count += 1;
-                    }
-                    dm.total_score += event.signal.detector_scores[detector_id].score as f64;
-                }
-
-                let (p, r, f) =
-                    calculate_metrics(dm.true_positives, dm.false_positives, dm.false_negatives);
-                dm.precision = p;
-                dm.recall = r;
-                dm.f1_score = f;
-                dm.avg_score = if self.detection_events.is_empty() {
-                    0.0
-                } else {
-                    dm.total_score / self.detection_events.len() as f64
-                };
-
                 detector_metrics.insert(name, dm);
             }
         }
 
         // Calculate latency metrics
-        let latency_micros = self.calculate_latency_metrics();
-
-        BenchmarkResults {
+        let latency_micros = self.metrics.latency.metrics();
+        let service_metrics = self.calculate_breakdown(&self.metrics.services, |hash| {
+            self.service_names
+                .get(hash)
+                .cloned()
+                .unwrap_or_else(|| format!("{:016x}", hash))
+        });
+        let severity_metrics = self.calculate_breakdown(&self.metrics.severities, |severity| {
+            self.severity_names
+                .get(severity)
+                .cloned()
+                .unwrap_or_else(|| severity.to_string())
+        });
+        let scenario_metrics = self.calculate_scenario_breakdown();
+        let time_metrics = self.calculate_time_breakdown(TIME_BREAKDOWN_BUCKETS);
+        let registry = self.calculate_registry_metrics();
+        let entities = self.calculate_entity_metrics(&config.extraction.entity_key);
+        let windowed = self.calculate_windowed_metrics(&config.scoring);
+        let (time_to_detect, time_to_detect_by_scenario, absence) = self.calculate_time_to_detect();
+        let curves = self.metrics.scores.curves(curves::DEFAULT_CURVE_POINTS);
+        let calibration = self.metrics.scores.calibration();
+        let timeline =
+            report::compute_timeline(&self.metrics, &self.ground_truth, report::TIMELINE_BUCKETS);
+
+        let mut results = BenchmarkResults {
             config: config.name.clone(),
             total_events,
             total_anomalies_injected: config.anomalies.len(),
-            total_anomaly_events: anomaly_events,
+            total_anomaly_events: overall.anomaly_events,
             total_detections: tp + fp,
             true_positives: tp,
             false_positives: fp,
@@ -375,32 +1336,231 @@ impl BenchmarkRunner {
             precision,
             recall,
             f1_score: f1,
+            curves,
+            calibration,
+            timeline,
             detector_metrics,
             latency_micros,
             throughput_eps: total_events as f64 / elapsed.as_secs_f64(),
+            peak_rss_bytes: peak_rss_bytes(),
+            cost: self.metrics.latency.cost(&config.cost_model),
+            allocations_per_event: alloc_counter::ENABLED
+                .then(|| self.allocations as f64 / overall.events.max(1) as f64),
+            time_to_detect,
+            time_to_detect_by_scenario,
+            absence,
+            windowed,
+            composite_score: 0.0,
+            service_metrics,
+            severity_metrics,
+            scenario_metrics,
+            time_metrics,
+            registry,
+            entities,
+            ingestion: None,
+            feedback: self.labels.as_ref().map(LabelQueue::stats),
+            warmup: self.warmup.clone(),
+            detectors: if config.disabled_detectors.is_empty() {
+                Vec::new()
+            } else {
+                config.enabled_detectors()
+            },
+            repeats: None,
+        };
+        results.composite_score = score::composite_score(&results, &config.score_weights);
+
+        if let Some(path) = &config.trace {
+            match self.write_trace(path) {
+                Ok(rows) if !config.quiet => println!("  Trace: {} events -> {}", rows, path),
+                Err(e) => eprintln!("  Warning: {}", e),
+                _ => {}
+            }
+        }
+        results
+    }
+
+    /// Time-to-detect overall, grouped by anomaly scenario and over absence
+    /// windows
+    fn calculate_time_to_detect(
+        &self,
+    ) -> (
+        TimeToDetect,
+        HashMap<String, TimeToDetect>,
+        Option<TimeToDetect>,
+    ) {
+        let delays = self.metrics.detection_delays(0, &self.ground_truth);
+
+        let mut by_scenario: HashMap<String, (usize, Vec<u64>)> = HashMap::new();
+        for (gt, delay) in self.ground_truth.iter().zip(&delays) {
+            let entry = by_scenario.entry(gt.anomaly_type.clone()).or_default();
+            entry.0 += 1;
+            entry.1.extend(*delay);
+        }
+
+        let absent: Vec<Option<u64>> = self
+            .ground_truth
+            .iter()
+            .zip(&delays)
+            .filter(|(gt, _)| gt.absence)
+            .map(|(_, d)| *d)
+            .collect();
+        let absence = (!absent.is_empty()).then(|| {
+            TimeToDetect::from_delays(absent.len(), absent.iter().flatten().copied().collect())
+        });
+
+        let overall =
+            TimeToDetect::from_delays(delays.len(), delays.into_iter().flatten().collect());
+        let by_scenario = by_scenario
+            .into_iter()
+            .map(|(scenario, (n, d))| (scenario, TimeToDetect::from_delays(n, d)))
+            .collect();
+        (overall, by_scenario, absence)
+    }
+
+    fn calculate_windowed_metrics(&self, scoring: &ScoringConfig) -> Option<WindowedMetrics> {
+        let (counts, windows_detected) = self.metrics.windowed(0)?;
+        let (precision, recall, f1_score) = counts.metrics();
+
+        let mut detector_f1 = HashMap::new();
+        for detector_id in 0..NUM_DETECTORS {
+            if let Some(id) = DetectorId::from_u8(detector_id as u8)
+                && let Some((dc, _)) = self.metrics.windowed(1 + detector_id)
+            {
+                detector_f1.insert(id.name().to_string(), dc.metrics().2);
+            }
         }
+
+        Some(WindowedMetrics {
+            mode: scoring.mode,
+            tolerance_before_ms: scoring.tolerance_before_ms,
+            tolerance_after_ms: scoring.tolerance_after_ms,
+            windows_total: self.ground_truth.len(),
+            windows_detected,
+            counts,
+            precision,
+            recall,
+            f1_score,
+            detector_f1,
+        })
     }
 
-    fn calculate_latency_metrics(&self) -> LatencyMetrics {
-        if self.latencies.is_empty() {
-            return LatencyMetrics::default();
+    /// Accuracy per slice of events, named by `name`
+    fn calculate_breakdown<K>(
+        &self,
+        tallies: &HashMap<K, Tally>,
+        name: impl Fn(&K) -> String,
+    ) -> HashMap<String, BreakdownMetrics> {
+        let mut merged: HashMap<String, Tally> = HashMap::new();
+        for (key, tally) in tallies {
+            merged.entry(name(key)).or_default().merge(tally);
         }
+        merged
+            .into_iter()
+            .map(|(name, tally)| (name.clone(), tally.breakdown(name)))
+            .collect()
+    }
 
-        let mut sorted = self.latencies.clone();
-        sorted.sort();
+    /// Confusion per scenario: anomaly events by their window, other events
+    /// by the window they fall in (collateral false positives), else
+    /// `OUTSIDE_WINDOWS`
+    fn calculate_scenario_breakdown(&self) -> HashMap<String, BreakdownMetrics> {
+        if self.ground_truth.is_empty() {
+            return HashMap::new();
+        }
+        self.calculate_breakdown(&self.metrics.scenarios, String::clone)
+    }
 
-        let len = sorted.len();
-        let avg = sorted.iter().sum::<u64>() as f64 / len as f64;
-        let p50 = sorted[len / 2] as f64;
-        let p95 = sorted[len * 95 / 100] as f64;
-        let p99 = sorted[len * 99 / 100] as f64;
+    /// Confusion over `buckets` equal spans from the run's first event to
+    /// its last scored one, named by their offset ("12-24s"), in time order
+    fn calculate_time_breakdown(&self, buckets: usize) -> Vec<BreakdownMetrics> {
+        let Some((_, end)) = self.metrics.span_ns else {
+            return Vec::new();
+        };
+        // Warmup events are unscored but the run starts with them
+        let start = self
+            .warmup_end_ns
+            .map_or(end, |w| w.saturating_sub(self.warmup_secs * 1_000_000_000))
+            .min(end);
+        let buckets = buckets.max(1) as u64;
+        let width = (end - start) / buckets + 1;
+        let mut tallies = vec![Tally::default(); buckets as usize];
+        let half_slot = self.metrics.slots.width_ns() / 2;
+        for (slot_start, slot) in self.metrics.slots.iter() {
+            let middle = (slot_start + half_slot).clamp(start, end);
+            let index = ((middle - start) / width).min(buckets - 1);
+            tallies[index as usize].merge(&slot.tally);
+        }
+        tallies
+            .into_iter()
+            .zip(0..)
+            .filter(|(tally, _)| tally.events > 0)
+            .map(|(tally, i)| {
+                tally.breakdown(format!(
+                    "{:.0}-{:.0}s",
+                    (i * width) as f64 / 1e9,
+                    ((i + 1) * width) as f64 / 1e9
+                ))
+            })
+            .collect()
+    }
 
-        LatencyMetrics {
-            p50_micros: p50,
-            p95_micros: p95,
-            p99_micros: p99,
-            avg_micros: avg,
+    /// How many ground-truth entities got a true positive; `None` when the
+    /// windows record no entities
+    fn calculate_entity_metrics(&self, entity_key: &EntityKey) -> Option<EntityMetrics> {
+        let targets: HashSet<u64> = self
+            .ground_truth
+            .iter()
+            .flat_map(|gt| gt.target_entities.iter().copied())
+            .collect();
+        if targets.is_empty() {
+            return None;
         }
+        let detected = self
+            .metrics
+            .detected_entities
+            .iter()
+            .filter(|hash| targets.contains(hash))
+            .count();
+        Some(EntityMetrics {
+            entity_key: entity_key.to_string(),
+            target_entities: targets.len(),
+            detected_entities: detected,
+            entity_recall: detected as f64 / targets.len() as f64,
+        })
+    }
+
+    fn calculate_registry_metrics(&self) -> Option<RegistryMetrics> {
+        let registry = self.registry.as_ref()?;
+        let stats = registry.stats();
+        Some(RegistryMetrics {
+            profiles: registry.len(),
+            capacity: stats.capacity,
+            creations: stats.total_creations,
+            evictions: stats.total_evictions,
+            hit_rate: stats.total_accesses as f64
+                / (stats.total_accesses + stats.total_creations).max(1) as f64,
+            recreations: self.recreations,
+        })
+    }
+
+    /// Signal and ground-truth label of every event of the last run, in
+    /// detection order; empty unless the run spilled its events
+    /// (`spill_events`)
+    pub fn scored_events(&self) -> impl Iterator<Item = (AnomalySignal, bool)> + '_ {
+        self.spill
+            .iter()
+            .flat_map(trace::Spill::events)
+            .map(|e| (e.signal, e.is_ground_truth_anomaly))
+    }
+
+    /// Write every scored event of the last run to `path` (see `trace`),
+    /// returning the row count; needs the events spilled
+    pub fn write_trace(&self, path: &str) -> Result<u64, String> {
+        let spill = self
+            .spill
+            .as_ref()
+            .ok_or("trace: the run did not spill its events (spill_events)")?;
+        trace::write(path, spill.events(), &self.service_names)
     }
 
     pub fn print_results(&self, results: &BenchmarkResults) {
@@ -408,6 +1568,14 @@ impl BenchmarkRunner {
         println!("║                    BENCHMARK RESULTS                         ║");
         println!("╠══════════════════════════════════════════════════════════════╣");
         println!("║ Configuration: {:44} ║", results.config);
+        if !results.detectors.is_empty() {
+            let keys: Vec<String> = results.detectors.iter().map(ToString::to_string).collect();
+            let mut detectors = keys.join(",");
+            if detectors.len() > 44 {
+                detectors = format!("{} of {}", keys.len(), NUM_DETECTORS);
+            }
+            println!("║ Detectors:     {:44} ║", detectors);
+        }
         println!("╠══════════════════════════════════════════════════════════════╣");
         println!("║ OVERALL METRICS                                              ║");
         println!("╠──────────────────────────────────────────────────────────────╣");
@@ -423,10 +1591,20 @@ impl BenchmarkRunner {
             "║ Detections:         {:>10}                              ║",
             results.total_detections
         );
+        if results.warmup.events > 0 {
+            println!(
+                "║ Warmup (unscored):  {:>10} events, {:>6} detections    ║",
+                results.warmup.events, results.warmup.detections
+            );
+        }
         println!(
             "║ Throughput:         {:>10.0} EPS                          ║",
             results.throughput_eps
         );
+        println!(
+            "║ Composite Score:    {:>10.1} / 100                        ║",
+            results.composite_score
+        );
         println!("╠──────────────────────────────────────────────────────────────╣");
         println!("║ ACCURACY                                                     ║");
         println!("╠──────────────────────────────────────────────────────────────╣");
@@ -459,6 +1637,98 @@ impl BenchmarkRunner {
             "║ F1-Score:           {:>10.3}                              ║",
             results.f1_score
         );
+        if let Some(curves) = &results.curves {
+            println!(
+                "║ ROC AUC / AUPRC:    {:>10.3} / {:<10.3}                 ║",
+                curves.auc, curves.auprc
+            );
+        }
+        if let Some(calibration) = &results.calibration {
+            println!(
+                "║ Brier raw / calib.: {:>10.4} / {:<10.4}                 ║",
+                calibration.brier_raw, calibration.brier_calibrated
+            );
+        }
+        if let Some(r) = &results.repeats {
+            println!("╠──────────────────────────────────────────────────────────────╣");
+            let header = format!(
+                "REPEATED RUNS ({} seeds, {:.0}% bootstrap CI)",
+                r.runs,
+                repeats::CONFIDENCE * 100.0
+            );
+            println!("║ {:60} ║", header);
+            println!("╠──────────────────────────────────────────────────────────────╣");
+            for (name, m) in [
+                ("Precision", &r.precision),
+                ("Recall", &r.recall),
+                ("F1-Score", &r.f1_score),
+            ] {
+                let row = format!(
+                    "{:<19} {:.3} ± {:.3}  [{:.3}, {:.3}]",
+                    format!("{name}:"),
+                    m.mean,
+                    m.stddev,
+                    m.ci_low,
+                    m.ci_high
+                );
+                println!("║ {:60} ║", row);
+            }
+        }
+        let ttd = &results.time_to_detect;
+        if ttd.windows > 0 {
+            println!("╠──────────────────────────────────────────────────────────────╣");
+            println!("║ TIME TO DETECT (ms)                                          ║");
+            println!("╠──────────────────────────────────────────────────────────────╣");
+            println!(
+                "║ Windows Detected:   {:>10} / {:<10}                 ║",
+                ttd.detected, ttd.windows
+            );
+            println!(
+                "║ Mean / Median / P95: {:>9.0} / {:>9.0} / {:>9.0}      ║",
+                ttd.mean_ms, ttd.median_ms, ttd.p95_ms
+            );
+            let mut scenarios: Vec<_> = results.time_to_detect_by_scenario.iter().collect();
+            scenarios.sort_by(|a, b| a.0.cmp(b.0));
+            for (scenario, t) in scenarios {
+                println!(
+                    "║   {:22} {:>2}/{:<2} | median {:>9.0} ms        ║",
+                    scenario, t.detected, t.windows, t.median_ms
+                );
+            }
+            if let Some(a) = &results.absence {
+                println!(
+                    "║   {:22} {:>2}/{:<2} | median {:>9.0} ms        ║",
+                    "(absence windows)", a.detected, a.windows, a.median_ms
+                );
+            }
+        }
+        if let Some(w) = &results.windowed {
+            println!("╠──────────────────────────────────────────────────────────────╣");
+            let header = format!(
+                "WINDOWED ({}, -{}ms/+{}ms)",
+                w.mode.as_str(),
+                w.tolerance_before_ms,
+                w.tolerance_after_ms
+            );
+            println!("║ {:60} ║", header);
+            println!("╠──────────────────────────────────────────────────────────────╣");
+            println!(
+                "║ Windows Detected:   {:>10} / {:<10}                 ║",
+                w.windows_detected, w.windows_total
+            );
+            println!(
+                "║ Precision:          {:>10.2}%                             ║",
+                w.precision * 100.0
+            );
+            println!(
+                "║ Recall:             {:>10.2}%                             ║",
+                w.recall * 100.0
+            );
+            println!(
+                "║ F1-Score:           {:>10.3}                              ║",
+                w.f1_score
+            );
+        }
         println!("╠──────────────────────────────────────────────────────────────╣");
         println!("║ LATENCY (microseconds)                                       ║");
         println!("╠──────────────────────────────────────────────────────────────╣");
@@ -478,6 +1748,26 @@ impl BenchmarkRunner {
             "║ P99:                {:>10.2} µs                           ║",
             results.latency_micros.p99_micros
         );
+        if let Some(allocations) = results.allocations_per_event {
+            println!(
+                "║ Allocations/event:  {:>10.3}                              ║",
+                allocations
+            );
+        }
+        let cost = &results.cost;
+        println!(
+            "║ CPU: {:>10.3} s | {:>8.3} µs/event (CPU-s per M) {:>7} ║",
+            cost.cpu_seconds, cost.cpu_micros_per_event, ""
+        );
+        if let Some(price) = cost.cost_per_million_events {
+            println!("║ Cost per million events: {:>12.6} {:>21} ║", price, "");
+        }
+        if let Some(joules) = cost.joules_per_million_events {
+            println!(
+                "║ Energy per million events: {:>10.1} J {:>19} ║",
+                joules, ""
+            );
+        }
         println!("╠══════════════════════════════════════════════════════════════╣");
         println!("║ PER-DETECTOR BREAKDOWN                                       ║");
         println!("╠──────────────────────────────────────────────────────────────╣");
@@ -494,6 +1784,96 @@ impl BenchmarkRunner {
             }
         }
 
+        let breakdowns = [
+            ("PER-SERVICE BREAKDOWN", &results.service_metrics),
+            ("PER-SEVERITY BREAKDOWN", &results.severity_metrics),
+        ];
+        for (title, breakdown) in breakdowns {
+            if breakdown.is_empty() {
+                continue;
+            }
+            println!("╠══════════════════════════════════════════════════════════════╣");
+            println!("║ {:60} ║", title);
+            println!("╠──────────────────────────────────────────────────────────────╣");
+
+            // Large fleets: show the slices with the most anomaly events
+            let mut slices: Vec<_> = breakdown.values().collect();
+            if slices.len() > MAX_BREAKDOWN_ROWS {
+                slices.sort_by_key(|b| std::cmp::Reverse(b.anomaly_events));
+                slices.truncate(MAX_BREAKDOWN_ROWS);
+            }
+            slices.sort_by(|a, b| a.name.cmp(&b.name));
+            let hidden = breakdown.len() - slices.len();
+            for bm in slices {
+                println!(
+                    "║ {:24} | P: {:5.1}% | R: {:5.1}% | F1: {:5.3} ║",
+                    bm.name,
+                    bm.precision * 100.0,
+                    bm.recall * 100.0,
+                    bm.f1_score
+                );
+            }
+            if hidden > 0 {
+                println!("║ {:60} ║", format!("... {} more", hidden));
+            }
+        }
+
+        let mut scenarios: Vec<_> = results.scenario_metrics.values().collect();
+        scenarios.sort_by(|a, b| a.name.cmp(&b.name));
+        let confusions = [
+            ("PER-SCENARIO CONFUSION", scenarios),
+            (
+                "PER-TIME-BUCKET CONFUSION",
+                results.time_metrics.iter().collect(),
+            ),
+        ];
+        for (title, slices) in confusions {
+            if slices.is_empty() {
+                continue;
+            }
+            println!("╠══════════════════════════════════════════════════════════════╣");
+            println!("║ {:60} ║", title);
+            println!("╠──────────────────────────────────────────────────────────────╣");
+            for bm in slices {
+                println!(
+                    "║ {:21.21} | TP {:>7} | FP {:>7} | FN {:>7} ║",
+                    bm.name, bm.true_positives, bm.false_positives, bm.false_negatives
+                );
+            }
+        }
+
+        if let Some(registry) = &results.registry {
+            println!("╠──────────────────────────────────────────────────────────────╣");
+            println!(
+                "║ Profiles: {:>6}/{:<8} | Created: {:>6} | Evicted: {:>5} ║",
+                registry.profiles, registry.capacity, registry.creations, registry.evictions
+            );
+            println!(
+                "║ Hit rate: {:>5.1}% | Re-created after eviction: {:>14} ║",
+                registry.hit_rate * 100.0,
+                registry.recreations
+            );
+        }
+
+        if let Some(entities) = &results.entities {
+            println!("╠──────────────────────────────────────────────────────────────╣");
+            println!("║ Entity key: {:48} ║", entities.entity_key);
+            println!(
+                "║ Entities detected: {:>8}/{:<8} | Entity recall: {:>6.1}% ║",
+                entities.detected_entities,
+                entities.target_entities,
+                entities.entity_recall * 100.0
+            );
+        }
+
+        if let Some(ingestion) = &results.ingestion {
+            println!("╠──────────────────────────────────────────────────────────────╣");
+            println!(
+                "║ Delay: mean {:>7.1} ms | max {:>7.1} ms | {:>8} reordered ║",
+                ingestion.mean_delay_ms, ingestion.max_delay_ms, ingestion.reordered_events
+            );
+        }
+
         println!("╚══════════════════════════════════════════════════════════════╝");
     }
 
@@ -508,6 +1888,43 @@ impl Default for BenchmarkRunner {
     }
 }
 
+/// Peak resident set size of this process in bytes.
+///
+/// Reads `VmHWM` from procfs, so it covers everything the process has done so
+/// far (including earlier runs in `run-all`). Returns 0 where unavailable.
+fn peak_rss_bytes() -> u64 {
+    proc_status_bytes("VmHWM:")
+}
+
+/// A `kB` field of `/proc/self/status` in bytes, 0 where unavailable
+fn proc_status_bytes(field: &str) -> u64 {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find(|l| l.starts_with(field))
+                .and_then(|l| l.split_whitespace().nth(1))
+                .and_then(|kb| kb.parse::<u64>().ok())
+        })
+        .map(|kb| kb * 1024)
+        .unwrap_or(0)
+}
+
+/// Load a result file written by `run-all` (array) or a single benchmark (object)
+pub fn load_results(path: &str) -> Result<Vec<BenchmarkResults>, String> {
+    let content = via_sim::compression::read_to_string(path)
+        .map_err(|e| format!("failed to read {path}: {e}"))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| format!("failed to parse {path}: {e}"))?;
+    if value.is_array() {
+        serde_json::from_value(value)
+    } else {
+        serde_json::from_value(value).map(|r| vec![r])
+    }
+    .map_err(|e| format!("{path} is not a benchmark result file: {e}"))
+}
+
 /// Calculate precision, recall, f1 from confusion matrix values
 pub fn calculate_metrics(tp: u64, fp: u64, fn_: u64) -> (f64, f64, f64) {
     let precision = if tp + fp > 0 {
@@ -586,6 +2003,11 @@ pub mod scenarios {
                     start_time_sec: 30,
                     duration_sec: 60,
                 },
+                AnomalySpec {
+                    scenario: "ransomware".to_string(),
+                    start_time_sec: 95,
+                    duration_sec: 20,
+                },
                 AnomalySpec {
                     scenario: "sql_injection".to_string(),
                     start_time_sec: 120,
@@ -647,6 +2069,72 @@ pub mod scenarios {
         }
     }
 
+    /// E-commerce checkout funnel with business-impacting incidents
+    pub fn checkout_funnel() -> BenchmarkConfig {
+        BenchmarkConfig {
+            name: "Checkout Funnel - Payments & Fraud".to_string(),
+            base_scenario: "checkout_funnel".to_string(),
+            duration_minutes: 4,
+            tick_ms: 100,
+            anomalies: vec![
+                AnomalySpec {
+                    scenario: "payment_outage".to_string(),
+                    start_time_sec: 60,
+                    duration_sec: 45,
+                },
+                AnomalySpec {
+                    scenario: "fraud_burst".to_string(),
+                    start_time_sec: 150,
+                    duration_sec: 40,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    /// IoT fleet with one registry profile per device
+    pub fn iot_fleet() -> BenchmarkConfig {
+        BenchmarkConfig {
+            name: "IoT Fleet - Per-Device Profiles".to_string(),
+            base_scenario: "iot_fleet".to_string(),
+            duration_minutes: 5,
+            tick_ms: 100,
+            per_service: true,
+            anomalies: vec![
+                AnomalySpec {
+                    scenario: "firmware_regression".to_string(),
+                    start_time_sec: 150,
+                    duration_sec: 60,
+                },
+                AnomalySpec {
+                    scenario: "sensor_drift".to_string(),
+                    start_time_sec: 220,
+                    duration_sec: 60,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    /// Entity churn: short-lived workers around a core that regresses,
+    /// with a registry too small to hold every entity
+    pub fn entity_churn() -> BenchmarkConfig {
+        BenchmarkConfig {
+            name: "Entity Churn - Registry Eviction".to_string(),
+            base_scenario: "entity_churn".to_string(),
+            duration_minutes: 3,
+            tick_ms: 100,
+            per_service: true,
+            max_profiles: 300,
+            anomalies: vec![AnomalySpec {
+                scenario: "core_latency_regression".to_string(),
+                start_time_sec: 90,
+                duration_sec: 45,
+            }],
+            ..Default::default()
+        }
+    }
+
     /// Quick validation benchmark
     pub fn quick_validation() -> BenchmarkConfig {
         BenchmarkConfig {
@@ -663,3 +2151,192 @@ pub mod scenarios {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// TP, FP, FN and TN summed over `slices`
+    fn confusion<'a>(slices: impl IntoIterator<Item = &'a BreakdownMetrics>) -> [u64; 4] {
+        slices.into_iter().fold([0; 4], |acc, b| {
+            [
+                acc[0] + b.true_positives,
+                acc[1] + b.false_positives,
+                acc[2] + b.false_negatives,
+                acc[3] + b.true_negatives,
+            ]
+        })
+    }
+
+    fn global_confusion(results: &BenchmarkResults) -> [u64; 4] {
+        [
+            results.true_positives,
+            results.false_positives,
+            results.false_negatives,
+            results.true_negatives,
+        ]
+    }
+
+    #[test]
+    fn test_warmup_is_unscored_and_anomaly_free() {
+        let config = BenchmarkConfig {
+            duration_secs: 30,
+            warmup_secs: 10,
+            anomalies: vec![AnomalySpec {
+                scenario: "error_spike".to_string(),
+                start_time_sec: 5,
+                duration_sec: 10,
+            }],
+            quiet: true,
+            ..Default::default()
+        };
+        let mut runner = BenchmarkRunner::new();
+        let results = runner.run(config).unwrap();
+
+        // The anomaly waits for the warmup to end
+        assert_eq!(runner.ground_truth[0].start_time_ns, 10_000_000_000);
+        let warmup = &results.warmup;
+        assert!(warmup.events > 500, "{warmup:?}");
+        assert_eq!(warmup.anomaly_events, 0);
+        let scored = results.true_positives
+            + results.false_positives
+            + results.true_negatives
+            + results.false_negatives;
+        assert_eq!(scored + warmup.events, results.total_events);
+    }
+
+    #[test]
+    fn test_invalid_profile_override_is_an_error() {
+        let config = BenchmarkConfig {
+            duration_secs: 10,
+            profile_overrides: [("hw_alphaa".to_string(), 0.3)].into(),
+            quiet: true,
+            ..Default::default()
+        };
+        let err = BenchmarkRunner::new().run(config).unwrap_err();
+        assert!(
+            err.contains("unknown ProfileConfig field 'hw_alphaa'"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_selected_detectors_alone_run() {
+        let mut config = BenchmarkConfig {
+            duration_secs: 20,
+            quiet: true,
+            ..Default::default()
+        };
+        assert_eq!(config.enabled_detectors().len(), NUM_DETECTORS);
+        let selected = [DetectorId::Volume, DetectorId::RRCF];
+        config.select_detectors(&selected);
+        assert_eq!(config.disabled_detectors.len(), NUM_DETECTORS - 2);
+        assert_eq!(config.enabled_detectors(), selected);
+
+        let results = BenchmarkRunner::new().run(config).unwrap();
+        assert_eq!(results.detectors, selected);
+        let json = serde_json::to_value(&results).unwrap();
+        assert_eq!(json["detectors"], serde_json::json!(["volume", "rrcf"]));
+        for id in DetectorId::ALL {
+            let fired = &results.detector_metrics[id.name()];
+            if !selected.contains(&id) {
+                assert_eq!(fired.true_positives + fired.false_positives, 0, "{id}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_per_service_profiles_and_breakdown() {
+        let config = BenchmarkConfig {
+            duration_secs: 30,
+            per_service: true,
+            anomalies: vec![AnomalySpec {
+                scenario: "error_spike".to_string(),
+                start_time_sec: 10,
+                duration_sec: 10,
+            }],
+            quiet: true,
+            ..Default::default()
+        };
+        let results = BenchmarkRunner::new().run(config).unwrap();
+
+        // One profile per service, each created once
+        let registry = results.registry.as_ref().unwrap();
+        let services = &results.service_metrics;
+        assert!(services.len() > 1, "{:?}", services.keys());
+        assert_eq!(registry.profiles, services.len());
+        assert_eq!(registry.creations, registry.profiles as u64);
+        assert_eq!(registry.evictions, 0);
+
+        let events: u64 = services.values().map(|s| s.events).sum();
+        assert_eq!(events + results.warmup.events, results.total_events);
+        let payment = &services["payment-service"];
+        assert!(payment.anomaly_events > 0);
+        assert_eq!(payment.anomaly_events, results.total_anomaly_events);
+    }
+
+    #[test]
+    fn test_service_and_severity_breakdowns_sum_to_global() {
+        let config = BenchmarkConfig {
+            duration_secs: 30,
+            warmup_secs: 5,
+            anomalies: vec![AnomalySpec {
+                scenario: "error_spike".to_string(),
+                start_time_sec: 10,
+                duration_sec: 10,
+            }],
+            quiet: true,
+            ..Default::default()
+        };
+        let results = BenchmarkRunner::new().run(config).unwrap();
+
+        let global = global_confusion(&results);
+        assert!(global[0] + global[2] > 0, "{global:?}");
+        assert!(results.service_metrics.len() > 1);
+        assert!(results.severity_metrics.len() > 1);
+        assert_eq!(confusion(results.service_metrics.values()), global);
+        assert_eq!(confusion(results.severity_metrics.values()), global);
+    }
+
+    #[test]
+    fn test_confusion_by_scenario_and_time() {
+        let config = BenchmarkConfig {
+            duration_secs: 40,
+            warmup_secs: 10,
+            anomalies: vec![
+                AnomalySpec {
+                    scenario: "error_spike".to_string(),
+                    start_time_sec: 15,
+                    duration_sec: 5,
+                },
+                AnomalySpec {
+                    scenario: "ddos".to_string(),
+                    start_time_sec: 30,
+                    duration_sec: 5,
+                },
+            ],
+            quiet: true,
+            ..Default::default()
+        };
+        let results = BenchmarkRunner::new().run(config).unwrap();
+
+        let global = global_confusion(&results);
+        assert_eq!(confusion(results.scenario_metrics.values()), global);
+        assert_eq!(confusion(&results.time_metrics), global);
+
+        let outside = &results.scenario_metrics[OUTSIDE_WINDOWS];
+        assert_eq!(outside.anomaly_events, 0);
+        let anomalies: u64 = results
+            .scenario_metrics
+            .values()
+            .map(|b| b.anomaly_events)
+            .sum();
+        assert_eq!(anomalies, results.total_anomaly_events);
+        assert_eq!(results.scenario_metrics.len(), 3);
+
+        // Buckets count from the first (warmup) event; the warmup's hold no
+        // scored events and are left out
+        assert_eq!(results.time_metrics[0].name, "8-12s");
+        assert_eq!(results.time_metrics.len(), TIME_BREAKDOWN_BUCKETS - 2);
+    }
+}
@@ -2,15 +2,106 @@
 //!
 //! Usage:
 //!   via-bench run-all                    # Run all benchmark scenarios
+//!   via-bench run-all --suite bench-suite.toml     # Run a TOML benchmark suite
+//!   via-bench init                       # Scaffold example suite and sweep spec
+//!   via-bench completions zsh            # Shell completions (also: man)
 //!   via-bench mixed-workload             # Run mixed anomaly test
 //!   via-bench security-audit             # Run security-focused test
 //!   via-bench performance-stress         # Run performance test
 //!   via-bench throughput                 # Maximum throughput test
-//!   via-bench compare results1.json results2.json  # Compare results
+//!   via-bench throughput --threads 8     # Aggregate EPS scaling over 1-8 threads
+//!   via-bench rate-sweep --budget-us 100 # Accuracy vs offered load
+//!   via-bench rate-sweep --spec sweep.toml
+//!   via-bench ffi-overhead               # Direct calls vs C ABI path
+//!   via-bench churn --capacities 100,300,1000
+//!                                        # Registry eviction under entity churn
+//!   via-bench soak --hours 24 --stream soak.jsonl
+//!                                        # Recurring anomalies, snapshot every 10 min
+//!   via-bench watch --scenario mixed --speed 10x
+//!                                        # Live dashboard of the run (feature `tui`)
+//!   via-bench mixed-workload --ingest-delay-ms 500 --ingest-jitter-ms 200
+//!                                        # Simulate collection pipeline lag
+//!   via-bench mixed-workload --skew-ms 500 --reorder-rate 0.1 --duplicate-rate 0.01
+//!                                        # Skewed clocks, late batches, resent logs
+//!   via-bench delay-sweep --delays-ms 0,100,1000,5000
+//!                                        # Accuracy and time-to-detect vs delay
+//!   via-bench sweep --param hw_alpha=0.1..0.5:0.1 --param confidence_threshold=0.3..0.7:0.1
+//!                                        # Grid search over ProfileConfig, ranked by F1
+//!   via-bench ablation --scenario mixed  # Marginal F1/latency of each detector
+//!   via-bench per-scenario --output coverage.json
+//!                                        # Detector F1 matrix, one run per anomaly type
+//!   via-bench mixed-workload --detectors volume,rrcf
+//!                                        # Only these detectors (see list-detectors)
+//!   via-bench feedback-loop --delay-ms 5000 --label-noise 0.1
+//!                                        # F1 over time with delayed ground-truth feedback
+//!   via-bench mixed-workload --label-noise 0.05 --contamination 0.1
+//!                                        # Noisy feedback labels, unlabeled warmup anomalies
+//!   via-bench robustness --noise-levels 0.05,0.1,0.2
+//!                                        # F1 sensitivity to label noise and contamination
+//!   via-bench quick --cpu-hour-cost 0.04 --watts-per-core 12
+//!                                        # Detection cost and energy per million events
+//!   via-bench mixed-workload --warmup-secs 30
+//!                                        # Anomaly-free, unscored warmup period
+//!   via-bench quick --repeats 10 --output new.json
+//!                                        # Mean ± stddev and bootstrap CIs over 10 seeds
+//!   via-bench mixed-workload --trace trace.csv.zst
+//!                                        # Every scored event with detector scores
+//!                                        # (.parquet with feature `parquet`)
+//!   via-bench replay capture.jsonl --label-attribute anomaly
+//!                                        # Detect over recorded OTel logs
+//!   via-bench run --dataset yahoo-a1     # Public labeled dataset (nab, yahoo-a1..a4, smd)
+//!   via-bench init-config bench-config.yaml        # Scaffold a benchmark config (.json, .toml)
+//!   via-bench run --config bench-config.yaml       # Run it; global flags override its fields
+//!   via-bench kafka --brokers localhost:9092 --topic via-logs
+//!                                        # Detect over a Kafka topic (feature `kafka`)
+//!   via-bench compare base.json new.json           # Diff results against a baseline
+//!   via-bench mixed-workload --output results.json.zst
+//!                                        # Compressed by extension (.gz, .zst)
+//!   via-bench gate --baseline base.json --max-f1-drop 0.02 --max-p99-increase 20%
+//!                                        # CI gate: exit 1 on regression, 2 on error
+//!   via-bench leaderboard results/*.json           # Rank results by composite score
+//!   via-bench mixed-workload --history   # Append the run to the history store
+//!   via-bench mixed-workload --entity-key service.name --value http.duration_ms
+//!                                        # Key detection on any attribute
+//!   via-bench security-audit --entity-key "service.name + source.ip" --profile-key source.ip
+//!                                        # Composite keys, one profile per source IP
+//!   via-bench history --metric f1 --scenario mixed
+//!                                        # Trend table from the store (feature `history`)
+//!   via-bench mixed-workload --profile --output results.json
+//!                                        # Flamegraph to results.flamegraph.svg (feature `profiling`)
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use via_bench::ablation;
+use via_bench::churn;
+use via_bench::compare::{self, CompareTolerance, GateOutcome, parse_percent};
+use via_bench::cost::CostModel;
+use via_bench::coverage;
+use via_bench::datasets::{self, Dataset};
+use via_bench::extraction::{AttributePath, LogFilter, ValueSource};
+use via_bench::feedback_loop::{self, FeedbackLoopConfig};
+use via_bench::ffi;
+use via_bench::grid::{self, ParamRange};
+#[cfg(feature = "history")]
+use via_bench::history::{self, HistoryStore};
+use via_bench::ingestion;
+use via_bench::parallel;
 use via_bench::pipeline::{PipelineBenchmarkConfig, PipelineBenchmarkRunner, scenario_by_name};
-use via_bench::{BenchmarkConfig, BenchmarkRunner, scenarios};
+#[cfg(feature = "profiling")]
+use via_bench::profiling;
+use via_bench::rate_sweep::{self, RateSweepConfig};
+use via_bench::repeats;
+use via_bench::robustness;
+use via_bench::soak::{self, SoakConfig};
+use via_bench::suite::{self, BenchmarkSuite, SweepSpec};
+use via_bench::trace;
+#[cfg(feature = "tui")]
+use via_bench::watch::WatchConfig;
+use via_bench::{
+    BenchmarkConfig, BenchmarkResults, BenchmarkRunner, DEFAULT_SEED, IngestionDelay, ScoreWeights,
+    ScoringConfig, ScoringMode, scenarios, score,
+};
+use via_core::signal::{DetectorId, NUM_DETECTORS};
+use via_sim::{DeliveryConfig, EntityKey, ReplayConfig, ReplaySource, compression};
 
 #[derive(Parser)]
 #[command(name = "via-bench")]
@@ -27,13 +118,247 @@ struct Cli {
     #[arg(short, long, global = true)]
     verbose: bool,
 
-    /// Batch size for batch processing mode (0 = single event, default)
-    #[arg(short, long, global = true, default_value = "0")]
-    batch: usize,
+    /// Batch size for batch processing mode (0 = single event; default: the
+    /// config's, else 0)
+    #[arg(short, long, global = true)]
+    batch: Option<usize>,
 
-    /// Deterministic simulation seed
-    #[arg(long, global = true, default_value = "42")]
-    seed: u64,
+    /// Deterministic simulation seed (default: the config's, else 42)
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+
+    /// Keep a separate profile per service.name (registry-backed)
+    #[arg(long, global = true)]
+    per_service: bool,
+
+    /// Registry capacity in per-service mode (0 = registry default)
+    #[arg(long, global = true, default_value = "0")]
+    max_profiles: usize,
+
+    /// Scoring mode: event, point-adjust, window (default: the config's,
+    /// else event)
+    #[arg(long, global = true)]
+    scoring: Option<ScoringMode>,
+
+    /// Credit detections up to this long before an anomaly window (ms)
+    #[arg(long, global = true, default_value = "0")]
+    tolerance_before_ms: u64,
+
+    /// Credit detections up to this long after an anomaly window (ms)
+    #[arg(long, global = true, default_value = "0")]
+    tolerance_after_ms: u64,
+
+    /// Drive detection through the exported C ABI (as the Bun host does)
+    #[arg(long, global = true)]
+    ffi: bool,
+
+    /// Emit business-hours/deploy context and pass it to detection
+    #[arg(long, global = true)]
+    exogenous: bool,
+
+    /// Hold each log this long before detection (simulated collection lag, ms)
+    #[arg(long, global = true, default_value = "0")]
+    ingest_delay_ms: u64,
+
+    /// Extra uniform random delay per log on top of --ingest-delay-ms (ms)
+    #[arg(long, global = true, default_value = "0")]
+    ingest_jitter_ms: u64,
+
+    /// Offset each service's clock by up to this many ms, ahead or behind
+    #[arg(long, global = true, default_value = "0")]
+    skew_ms: u64,
+
+    /// Chance a tick's logs arrive late (up to 5 ticks), behind newer ones
+    #[arg(long, global = true, default_value = "0")]
+    reorder_rate: f64,
+
+    /// Chance a log is delivered twice
+    #[arg(long, global = true, default_value = "0")]
+    duplicate_rate: f64,
+
+    /// Share of feedback labels flipped to model reviewer error; turns the
+    /// feedback loop on (0.0 - 1.0)
+    #[arg(long, global = true, default_value = "0")]
+    label_noise: f64,
+
+    /// Share of the warmup period seeded with unlabeled anomalies (0.0 - 1.0)
+    #[arg(long, global = true, default_value = "0")]
+    contamination: f64,
+
+    /// Keep anomalies out of the first N seconds and leave their events unscored
+    #[arg(long, global = true, default_value = "0")]
+    warmup_secs: u64,
+
+    /// Cost of one CPU-hour, to report detection cost per million events
+    #[arg(long, global = true)]
+    cpu_hour_cost: Option<f64>,
+
+    /// Power draw of one busy core (W), to report energy per million events
+    #[arg(long, global = true)]
+    watts_per_core: Option<f64>,
+
+    /// Run each benchmark with N consecutive seeds and report mean ± stddev
+    /// with bootstrap confidence intervals
+    #[arg(long, global = true, default_value = "1")]
+    repeats: usize,
+
+    /// Write every scored event (scores per detector, ground truth) to this
+    /// CSV file, or Parquet for .parquet; run-all writes one file per config
+    #[arg(long, global = true)]
+    trace: Option<String>,
+
+    /// Log fields hashed into the entity: traceId (default), an attribute such
+    /// as client.ip, or a composite like "service.name + client.ip" or
+    /// "{service.name}/{user.id}"
+    #[arg(long, global = true)]
+    entity_key: Option<EntityKey>,
+
+    /// Key per-service profiles by these fields instead of service.name
+    /// (same syntax as --entity-key; implies --per-service)
+    #[arg(long, global = true)]
+    profile_key: Option<EntityKey>,
+
+    /// Detected value: metric (default), status-class, body-size or a numeric
+    /// attribute such as http.duration_ms
+    #[arg(long, global = true)]
+    value: Option<ValueSource>,
+
+    /// Only detect logs matching path, path=value or path!=value (repeatable)
+    #[arg(long = "filter", global = true)]
+    filters: Vec<LogFilter>,
+
+    /// Only run these detectors, e.g. volume,rrcf (see list-detectors)
+    #[arg(long, global = true, value_delimiter = ',')]
+    detectors: Vec<DetectorId>,
+
+    /// Append run summaries to this SQLite history store
+    #[cfg(feature = "history")]
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = DEFAULT_HISTORY_DB)]
+    history: Option<String>,
+
+    /// Sample the CPU during the run and write a flamegraph SVG next to the
+    /// results (`results.flamegraph.svg`)
+    #[cfg(feature = "profiling")]
+    #[arg(long, global = true)]
+    profile: bool,
+}
+
+/// History store used when `--history` / `--db` is given without a path
+#[cfg(feature = "history")]
+const DEFAULT_HISTORY_DB: &str = "via-bench-history.db";
+
+/// Global CLI overrides applied to every benchmark config
+/// Global flags applied to every config; unset ones leave its values
+struct RunOptions {
+    batch_size: Option<usize>,
+    seed: Option<u64>,
+    per_service: bool,
+    max_profiles: usize,
+    scoring: Option<ScoringConfig>,
+    ffi_path: bool,
+    exogenous_context: bool,
+    ingestion_delay: IngestionDelay,
+    delivery: DeliveryConfig,
+    label_noise: f64,
+    contamination: f64,
+    warmup_secs: u64,
+    cost_model: CostModel,
+    repeats: usize,
+    trace: Option<String>,
+    entity_key: Option<EntityKey>,
+    profile_key: Option<EntityKey>,
+    value: Option<ValueSource>,
+    filters: Vec<LogFilter>,
+    detectors: Vec<DetectorId>,
+    #[cfg(feature = "history")]
+    history: Option<String>,
+}
+
+impl RunOptions {
+    fn apply(&self, config: &mut BenchmarkConfig) {
+        if let Some(batch_size) = self.batch_size {
+            config.batch_size = batch_size;
+        }
+        if let Some(seed) = self.seed {
+            config.simulation_seed = seed;
+        }
+        // Presets that need per-service profiles keep them
+        config.per_service |= self.per_service;
+        if self.max_profiles > 0 {
+            config.max_profiles = self.max_profiles;
+        }
+        if let Some(scoring) = &self.scoring {
+            config.scoring = scoring.clone();
+        }
+        config.ffi_path |= self.ffi_path;
+        config.exogenous_context |= self.exogenous_context;
+        if self.ingestion_delay.is_enabled() {
+            config.ingestion_delay = self.ingestion_delay.clone();
+        }
+        if self.delivery.is_enabled() {
+            config.delivery = self.delivery.clone();
+        }
+        if self.label_noise > 0.0 {
+            if !config.feedback.enabled {
+                config.feedback.enabled = true;
+                config.feedback.delay_ms = feedback_loop::DEFAULT_LABEL_DELAY_MS;
+            }
+            config.feedback.label_noise = self.label_noise;
+        }
+        if self.contamination > 0.0 {
+            config.contamination = self.contamination;
+        }
+        if self.warmup_secs > 0 {
+            config.warmup_secs = self.warmup_secs;
+        }
+        if self.cost_model != CostModel::default() {
+            config.cost_model = self.cost_model.clone();
+        }
+        if let Some(entity_key) = &self.entity_key {
+            config.extraction.entity_key = entity_key.clone();
+        }
+        if let Some(profile_key) = &self.profile_key {
+            config.extraction.profile_key = Some(profile_key.clone());
+            config.per_service = true;
+        }
+        if let Some(value) = &self.value {
+            config.extraction.value = value.clone();
+        }
+        config
+            .extraction
+            .filters
+            .extend(self.filters.iter().cloned());
+        if !self.detectors.is_empty() {
+            config.select_detectors(&self.detectors);
+        }
+    }
+
+    /// Run `config` once, or once per seed with `--repeats`
+    fn run(&self, mut config: BenchmarkConfig) -> Result<BenchmarkResults, String> {
+        config.trace = config.trace.or_else(|| self.trace.clone());
+        if self.repeats > 1 {
+            repeats::run_repeats(&config, self.repeats)
+        } else {
+            BenchmarkRunner::new().run(config)
+        }
+    }
+
+    fn batch_label(&self) -> String {
+        batch_label(self.batch_size.unwrap_or(0))
+    }
+
+    /// Seed of commands that build their config here
+    fn seed(&self) -> u64 {
+        self.seed.unwrap_or(DEFAULT_SEED)
+    }
+}
+
+fn batch_label(batch_size: usize) -> String {
+    if batch_size > 0 {
+        format!("{}", batch_size)
+    } else {
+        "single".to_string()
+    }
 }
 
 #[derive(Subcommand)]
@@ -43,6 +368,10 @@ enum Commands {
         /// Export format
         #[arg(short, long, default_value = "json")]
         format: String,
+
+        /// TOML benchmark suite to run instead of the built-in scenarios
+        #[arg(long)]
+        suite: Option<String>,
     },
 
     /// Run mixed workload benchmark
@@ -58,11 +387,26 @@ enum Commands {
     /// Run performance stress test
     PerformanceStress,
 
+    /// Run e-commerce checkout funnel benchmark
+    Checkout,
+
+    /// Run IoT fleet benchmark (one profile per device)
+    Iot,
+
     /// Run maximum throughput test
     Throughput {
         /// Duration in minutes
         #[arg(short, long, default_value = "2")]
         duration: u64,
+
+        /// Producer threads on a shared registry; above 1, report aggregate
+        /// EPS and scaling efficiency from 1 thread up
+        #[arg(long, default_value = "1")]
+        threads: usize,
+
+        /// Shards of the shared registry
+        #[arg(long, default_value = "64")]
+        shards: usize,
     },
 
     /// Quick validation test
@@ -74,239 +418,1468 @@ enum Commands {
         #[arg(long, default_value = "http://127.0.0.1:3000")]
         tier2_url: String,
 
-        /// Scenario profile: quick, mixed, security, performance, throughput
+        /// Scenario profile: quick, mixed, security, performance, checkout, iot, churn, throughput
+        #[arg(long, default_value = "quick")]
+        scenario: String,
+
+        /// Duration override (minutes)
+        #[arg(short, long)]
+        duration: Option<u64>,
+
+        /// Anomaly send batch size to Tier-2
+        #[arg(long, default_value = "256")]
+        send_batch: usize,
+    },
+
+    /// Sweep offered load until the per-event latency budget is exceeded
+    RateSweep {
+        /// Scenario profile: quick, mixed, security, performance, checkout, iot, churn, throughput
+        #[arg(long, default_value = "quick")]
+        scenario: String,
+
+        /// P99 per-event latency budget (µs)
+        #[arg(long, default_value = "100")]
+        budget_us: f64,
+
+        /// Rate multiplier of the first step
+        #[arg(long, default_value = "1")]
+        start_scale: f64,
+
+        /// Multiplier between steps
+        #[arg(long, default_value = "2")]
+        step_factor: f64,
+
+        /// Maximum number of steps
+        #[arg(long, default_value = "8")]
+        max_steps: usize,
+
+        /// TOML sweep spec (see `via-bench init`); replaces the flags above
+        #[arg(long)]
+        spec: Option<String>,
+    },
+
+    /// Run per-service detection under entity churn at several registry capacities
+    Churn {
+        /// Scenario profile: quick, mixed, security, performance, checkout, iot, churn, throughput
+        #[arg(long, default_value = "churn")]
+        scenario: String,
+
+        /// Registry capacities to run (profiles)
+        #[arg(long, value_delimiter = ',', default_value = "100,300,1000,5000")]
+        capacities: Vec<usize>,
+    },
+
+    /// Run a scenario for hours with its anomalies recurring, appending
+    /// rolling metric and memory snapshots to a JSON-lines stream
+    Soak {
+        /// Scenario profile: quick, mixed, security, performance, checkout, iot, churn, throughput
+        #[arg(long, default_value = "quick")]
+        scenario: String,
+
+        /// Simulated run length (hours)
+        #[arg(long, default_value = "24")]
+        hours: f64,
+
+        /// Simulated minutes between snapshots
+        #[arg(long, default_value = "10")]
+        snapshot_minutes: u64,
+
+        /// File the snapshots are appended to
+        #[arg(long, default_value = "soak.jsonl")]
+        stream: String,
+    },
+
+    /// Run a scenario under a live terminal dashboard: EPS, active
+    /// anomalies, per-detector fire rates and rolling F1
+    #[cfg(feature = "tui")]
+    Watch {
+        /// Scenario profile: quick, mixed, security, performance, checkout, iot, churn, throughput
+        #[arg(long, default_value = "quick")]
+        scenario: String,
+
+        /// Wall-clock pacing as a multiple of real time (e.g. 10x, 0.5x);
+        /// as fast as detection runs when omitted
+        #[arg(long, value_parser = parse_speed)]
+        speed: Option<f64>,
+
+        /// Simulated time the rolling metrics cover (s)
+        #[arg(long, default_value = "60")]
+        window_secs: u64,
+
+        /// Wall time between redraws (ms)
+        #[arg(long, default_value = "250")]
+        refresh_ms: u64,
+
+        /// Stretch the run to this many hours, anomalies recurring as in `soak`
+        #[arg(long)]
+        hours: Option<f64>,
+    },
+
+    /// Re-run a scenario at growing ingestion delays
+    DelaySweep {
+        /// Scenario profile: quick, mixed, security, performance, checkout, iot, churn, throughput
+        #[arg(long, default_value = "quick")]
+        scenario: String,
+
+        /// Delays to run (ms); 0 is the undelayed baseline
+        #[arg(long, value_delimiter = ',', default_value = "0,100,500,1000,5000")]
+        delays_ms: Vec<u64>,
+
+        /// Jitter as a fraction of each delay
+        #[arg(long, default_value = "0.5")]
+        jitter_ratio: f64,
+    },
+
+    /// Grid search over ProfileConfig values, ranked by F1
+    Sweep {
+        /// Scenario profile: quick, mixed, security, performance, checkout, iot, churn, throughput
+        #[arg(long, default_value = "quick")]
+        scenario: String,
+
+        /// Field and values: name=start..end:step or name=a,b,c (repeatable;
+        /// dotted names reach nested fields, e.g. suppression.cooldown_ms)
+        #[arg(long = "param", required = true)]
+        params: Vec<ParamRange>,
+
+        /// Combinations run concurrently
+        #[arg(long, default_value = "1")]
+        threads: usize,
+
+        /// Rows of the ranked table
+        #[arg(long, default_value = "10")]
+        top: usize,
+    },
+
+    /// Inject each via-sim anomaly scenario into the middle of its own run
+    /// and tabulate detector F1 per anomaly type
+    PerScenario {
+        /// Baseline traffic every anomaly is injected into
+        #[arg(long, default_value = "normal_traffic")]
+        base: String,
+
+        /// Simulated length of each run (s)
+        #[arg(long, default_value = "240")]
+        duration_secs: u64,
+
+        /// How long each anomaly stays active, centered in the run (s)
+        #[arg(long, default_value = "60")]
+        anomaly_secs: u64,
+
+        /// Anomaly scenarios to run (default: every one in `via-sim list`)
+        #[arg(long, value_delimiter = ',')]
+        scenarios: Vec<String>,
+    },
+
+    /// Re-run a scenario without each detector to measure its contribution
+    Ablation {
+        /// Scenario profile: quick, mixed, security, performance, checkout, iot, churn, throughput
+        #[arg(long, default_value = "quick")]
+        scenario: String,
+    },
+
+    /// Feed delayed ground-truth labels back into the profiles and track F1 over time
+    FeedbackLoop {
+        /// Scenario profile: quick, mixed, security, performance, checkout, iot, churn, throughput
+        #[arg(long, default_value = "quick")]
+        scenario: String,
+
+        /// Simulated time between a detection and its label (ms)
+        #[arg(long, default_value = "1000")]
+        delay_ms: u64,
+    },
+
+    /// Re-run with flipped feedback labels and a contaminated warmup, reporting
+    /// how far F1 moves from the clean run
+    Robustness {
+        /// Scenario profile: quick, mixed, security, performance, checkout, iot, churn, throughput
+        #[arg(long, default_value = "quick")]
+        scenario: String,
+
+        /// Simulated time between a detection and its label (ms)
+        #[arg(long, default_value = "1000")]
+        delay_ms: u64,
+
+        /// Label-noise shares to run (0.0 - 1.0)
+        #[arg(long, value_delimiter = ',', default_value = "0.05,0.1,0.2")]
+        noise_levels: Vec<f64>,
+
+        /// Warmup shares seeded with unlabeled anomalies (0.0 - 1.0)
+        #[arg(long, value_delimiter = ',', default_value = "0.05,0.1,0.25")]
+        contamination_levels: Vec<f64>,
+    },
+
+    /// Write a commented example benchmark suite and rate sweep spec
+    Init {
+        /// Directory to write into
+        #[arg(default_value = ".")]
+        dir: String,
+
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Write an example benchmark config for `run --config`
+    InitConfig {
+        /// Config to write; the extension picks JSON, YAML or TOML
+        #[arg(default_value = suite::CONFIG_FILE)]
+        path: String,
+
+        /// Overwrite an existing file
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Print shell completions
+    Completions {
+        /// Target shell
+        shell: clap_complete::Shell,
+    },
+
+    /// Print the man page (roff)
+    Man,
+
+    /// Run a scenario through direct calls and the C ABI, reporting the overhead
+    FfiOverhead {
+        /// Scenario profile: quick, mixed, security, performance, checkout, iot, churn, throughput
+        #[arg(long, default_value = "quick")]
+        scenario: String,
+    },
+
+    /// Run a benchmark config file, or a public labeled dataset through
+    /// detection with one profile per series
+    Run {
+        /// Benchmark config (.json, .yaml or .toml; see init-config)
+        #[arg(long, conflicts_with_all = ["dataset", "data_dir"])]
+        config: Option<String>,
+
+        /// nab, nab/<category>, yahoo-a1 .. yahoo-a4, smd or smd/<machine>
+        #[arg(long, required_unless_present = "config")]
+        dataset: Option<Dataset>,
+
+        /// Dataset root (default: datasets/nab, datasets/yahoo or datasets/smd)
+        #[arg(long)]
+        data_dir: Option<String>,
+
+        /// Dataset time per batch (ms)
+        #[arg(long, default_value = "100")]
+        tick_ms: u64,
+    },
+
+    /// Replay recorded OTLP JSON / JSON-lines logs through detection
+    Replay {
+        /// Capture file
+        file: String,
+
+        /// Attribute marking ground-truth anomalous records
+        #[arg(long)]
+        label_attribute: Option<String>,
+
+        /// Capture time per batch (ms)
+        #[arg(long, default_value = "100")]
+        tick_ms: u64,
+    },
+
+    /// Consume logs from a Kafka topic through detection until it goes idle
+    #[cfg(feature = "kafka")]
+    Kafka {
+        /// Kafka bootstrap servers
+        #[arg(long, default_value = "localhost:9092")]
+        brokers: String,
+
+        /// Topic to consume
+        #[arg(long, default_value = "via-logs")]
+        topic: String,
+
+        /// Consumer group
+        #[arg(long, default_value = "via-bench")]
+        group_id: String,
+
+        /// Attribute marking ground-truth anomalous records
+        #[arg(long)]
+        label_attribute: Option<String>,
+
+        /// Maximum messages per batch
+        #[arg(long, default_value = "1000")]
+        max_batch: usize,
+
+        /// Stop after no message arrives for this long (ms)
+        #[arg(long, default_value = "5000")]
+        idle_timeout_ms: u64,
+    },
+
+    /// Compare benchmark results against a baseline (the first file)
+    Compare {
+        /// Result files to compare
+        files: Vec<String>,
+
+        /// Output comparison to file
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Output format: markdown, json
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+
+        /// Absolute drop allowed in precision, recall and F1
+        #[arg(long, default_value = "0.02")]
+        max_accuracy_drop: f64,
+
+        /// Relative latency increase allowed (%)
+        #[arg(long, default_value = "20")]
+        max_latency_increase_pct: f64,
+
+        /// Relative throughput drop allowed (%)
+        #[arg(long, default_value = "20")]
+        max_throughput_drop_pct: f64,
+
+        /// Increase allowed in heap allocations per event (when both runs
+        /// were built with feature `alloc-counter`)
+        #[arg(long, default_value = "0.05")]
+        max_allocation_increase: f64,
+    },
+
+    /// Run a benchmark and fail (exit 1) if it regressed against a baseline
+    Gate {
+        /// Baseline results file
+        #[arg(long)]
+        baseline: String,
+
+        /// Scenario profile: quick, mixed, security, performance, checkout, iot, churn, throughput
         #[arg(long, default_value = "quick")]
         scenario: String,
 
-        /// Duration override (minutes)
-        #[arg(short, long)]
-        duration: Option<u64>,
+        /// Absolute F1 drop allowed
+        #[arg(long, default_value = "0.02")]
+        max_f1_drop: f64,
+
+        /// P99 latency increase allowed, e.g. 20%
+        #[arg(long, default_value = "20%", value_parser = parse_percent)]
+        max_p99_increase: f64,
+
+        /// Throughput drop allowed, e.g. 15% (not gated when omitted)
+        #[arg(long, value_parser = parse_percent)]
+        max_throughput_drop: Option<f64>,
+    },
+
+    /// Rank result files by composite score
+    Leaderboard {
+        /// Result files to rank
+        files: Vec<String>,
+
+        /// JSON file with ScoreWeights to rescore every result with
+        #[arg(long)]
+        weights: Option<String>,
+
+        /// Output format: markdown, json
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+    },
+
+    /// Show how a metric trended across runs in the history store
+    #[cfg(feature = "history")]
+    History {
+        /// History store to read
+        #[arg(long, default_value = DEFAULT_HISTORY_DB)]
+        db: String,
+
+        /// Metric name or alias: f1, precision, recall, score, avg, p50, p95,
+        /// p99, throughput, rss, allocations, cpu, cost
+        #[arg(long, default_value = "f1")]
+        metric: String,
+
+        /// Only runs of this scenario (or benchmark config name)
+        #[arg(long)]
+        scenario: Option<String>,
+
+        /// Most recent runs to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+
+        /// Output format: markdown, json
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+    },
+
+    /// List available detectors
+    ListDetectors,
+
+    /// Export results in various formats
+    Export {
+        /// Input result file
+        input: String,
+
+        /// Output format
+        #[arg(short, long, default_value = "html")]
+        format: String,
+
+        /// Output file
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let opts = RunOptions {
+        batch_size: cli.batch,
+        seed: cli.seed,
+        per_service: cli.per_service,
+        max_profiles: cli.max_profiles,
+        scoring: (cli.scoring.is_some()
+            || cli.tolerance_before_ms > 0
+            || cli.tolerance_after_ms > 0)
+            .then(|| ScoringConfig {
+                mode: cli.scoring.unwrap_or_default(),
+                tolerance_before_ms: cli.tolerance_before_ms,
+                tolerance_after_ms: cli.tolerance_after_ms,
+            }),
+        ffi_path: cli.ffi,
+        exogenous_context: cli.exogenous,
+        ingestion_delay: IngestionDelay {
+            delay_ms: cli.ingest_delay_ms,
+            jitter_ms: cli.ingest_jitter_ms,
+        },
+        delivery: DeliveryConfig {
+            max_skew_ms: cli.skew_ms,
+            reorder_rate: cli.reorder_rate,
+            max_delay_ticks: 5,
+            duplicate_rate: cli.duplicate_rate,
+        },
+        label_noise: cli.label_noise,
+        contamination: cli.contamination,
+        warmup_secs: cli.warmup_secs,
+        cost_model: CostModel {
+            cpu_hour_cost: cli.cpu_hour_cost,
+            watts_per_core: cli.watts_per_core,
+        },
+        repeats: cli.repeats,
+        trace: cli.trace,
+        entity_key: cli.entity_key,
+        profile_key: cli.profile_key,
+        value: cli.value,
+        filters: cli.filters,
+        detectors: cli.detectors,
+        #[cfg(feature = "history")]
+        history: cli.history,
+    };
+
+    #[cfg(feature = "profiling")]
+    let profiler = cli.profile.then(|| {
+        let path = profiling::flamegraph_path(cli.output.as_deref());
+        match profiling::CpuProfiler::start() {
+            Ok(profiler) => (profiler, path),
+            Err(e) => exit_with(&e),
+        }
+    });
+
+    match cli.command {
+        Commands::RunAll { format, suite } => {
+            let configs = match suite {
+                Some(path) => match BenchmarkSuite::load(&path) {
+                    Ok(suite) => suite.benchmarks,
+                    Err(e) => exit_with(&e),
+                },
+                None => vec![
+                    scenarios::mixed_workload(),
+                    scenarios::security_audit(),
+                    scenarios::performance_stress(),
+                    scenarios::checkout_funnel(),
+                    scenarios::throughput_test(),
+                ],
+            };
+            run_all_benchmarks(configs, &format, cli.output, cli.verbose, &opts);
+        }
+        Commands::MixedWorkload { duration } => {
+            run_single_benchmark("mixed", duration, cli.output, &opts);
+        }
+        Commands::SecurityAudit => {
+            run_single_benchmark("security", None, cli.output, &opts);
+        }
+        Commands::PerformanceStress => {
+            run_single_benchmark("performance", None, cli.output, &opts);
+        }
+        Commands::Checkout => {
+            run_single_benchmark("checkout", None, cli.output, &opts);
+        }
+        Commands::Iot => {
+            run_single_benchmark("iot", None, cli.output, &opts);
+        }
+        Commands::Throughput {
+            duration,
+            threads,
+            shards,
+        } => {
+            run_throughput_benchmark(duration, threads, shards, cli.output, &opts);
+        }
+        Commands::Quick => {
+            run_single_benchmark("quick", None, cli.output, &opts);
+        }
+        Commands::Pipeline {
+            tier2_url,
+            scenario,
+            duration,
+            send_batch,
+        } => {
+            run_pipeline_benchmark(
+                &tier2_url,
+                &scenario,
+                duration,
+                send_batch,
+                cli.output,
+                opts.seed(),
+            );
+        }
+        Commands::RateSweep {
+            scenario,
+            budget_us,
+            start_scale,
+            step_factor,
+            max_steps,
+            spec: None,
+        } => {
+            let sweep = RateSweepConfig {
+                start_scale,
+                step_factor,
+                max_steps,
+                latency_budget_micros: budget_us,
+            };
+            run_rate_sweep_benchmark(&scenario, &sweep, cli.output, &opts);
+        }
+        Commands::RateSweep {
+            spec: Some(path), ..
+        } => match SweepSpec::load(&path) {
+            Ok(spec) => run_rate_sweep_benchmark(&spec.scenario, &spec.sweep, cli.output, &opts),
+            Err(e) => exit_with(&e),
+        },
+        Commands::Churn {
+            scenario,
+            capacities,
+        } => {
+            run_churn_benchmark(&scenario, &capacities, cli.output, &opts);
+        }
+        Commands::Soak {
+            scenario,
+            hours,
+            snapshot_minutes,
+            stream,
+        } => {
+            let soak = SoakConfig {
+                hours,
+                snapshot_minutes,
+            };
+            run_soak_benchmark(&scenario, &soak, &stream, cli.output, &opts);
+        }
+        #[cfg(feature = "tui")]
+        Commands::Watch {
+            scenario,
+            speed,
+            window_secs,
+            refresh_ms,
+            hours,
+        } => {
+            let watch = WatchConfig {
+                speed,
+                refresh_ms,
+                window_secs,
+                hours,
+            };
+            run_watch_benchmark(&scenario, &watch, cli.output, &opts);
+        }
+        Commands::DelaySweep {
+            scenario,
+            delays_ms,
+            jitter_ratio,
+        } => {
+            run_delay_sweep_benchmark(&scenario, &delays_ms, jitter_ratio, cli.output, &opts);
+        }
+        Commands::Sweep {
+            scenario,
+            params,
+            threads,
+            top,
+        } => {
+            run_grid_search_benchmark(&scenario, &params, threads, top, cli.output, &opts);
+        }
+        Commands::PerScenario {
+            base,
+            duration_secs,
+            anomaly_secs,
+            scenarios,
+        } => {
+            run_coverage_benchmark(
+                &base,
+                duration_secs,
+                anomaly_secs,
+                scenarios,
+                cli.output,
+                &opts,
+            );
+        }
+        Commands::Ablation { scenario } => {
+            run_ablation_benchmark(&scenario, cli.output, &opts);
+        }
+        Commands::FeedbackLoop { scenario, delay_ms } => {
+            let feedback = FeedbackLoopConfig {
+                enabled: true,
+                delay_ms,
+                label_noise: opts.label_noise,
+            };
+            run_feedback_loop_benchmark(&scenario, &feedback, cli.output, &opts);
+        }
+        Commands::Robustness {
+            scenario,
+            delay_ms,
+            noise_levels,
+            contamination_levels,
+        } => {
+            let feedback = FeedbackLoopConfig {
+                enabled: true,
+                delay_ms,
+                label_noise: 0.0,
+            };
+            run_robustness_benchmark(
+                &scenario,
+                &feedback,
+                &noise_levels,
+                &contamination_levels,
+                cli.output,
+                &opts,
+            );
+        }
+        Commands::Init { dir, force } => {
+            let files = [
+                (suite::SUITE_FILE, suite::SUITE_TEMPLATE),
+                (suite::SWEEP_FILE, suite::SWEEP_TEMPLATE),
+            ];
+            match via_sim::config::write_scaffold(std::path::Path::new(&dir), &files, force) {
+                Ok(paths) => {
+                    for path in paths {
+                        println!("Wrote {}", path.display());
+                    }
+                    println!("Edit them, then run: via-bench run-all --suite <file>");
+                    println!("                 or: via-bench rate-sweep --spec <file>");
+                }
+                Err(e) => exit_with(&e),
+            }
+        }
+        Commands::InitConfig { path, force } => {
+            let content = suite::config_template(&path).unwrap_or_else(|e| exit_with(&e));
+            let path = std::path::Path::new(&path);
+            let (dir, file) = (path.parent().unwrap_or(path), path.file_name());
+            let file = file.and_then(|f| f.to_str()).unwrap_or(suite::CONFIG_FILE);
+            match via_sim::config::write_scaffold(dir, &[(file, &content)], force) {
+                Ok(paths) => {
+                    for path in paths {
+                        println!("Wrote {}", path.display());
+                        println!(
+                            "Edit it, then run: via-bench run --config {}",
+                            path.display()
+                        );
+                    }
+                }
+                Err(e) => exit_with(&e),
+            }
+        }
+        Commands::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut Cli::command(),
+                "via-bench",
+                &mut std::io::stdout(),
+            );
+        }
+        Commands::Man => {
+            clap_mangen::Man::new(Cli::command())
+                .render(&mut std::io::stdout())
+                .unwrap_or_else(|e| exit_with(&format!("Failed to render man page: {}", e)));
+        }
+        Commands::Run {
+            config,
+            dataset,
+            data_dir,
+            tick_ms,
+        } => match (config, dataset) {
+            (Some(path), _) => run_config_file(&path, cli.output, &opts),
+            (None, Some(dataset)) => {
+                run_dataset_benchmark(&dataset, data_dir, tick_ms, cli.output, &opts)
+            }
+            (None, None) => unreachable!("clap requires --config or --dataset"),
+        },
+        Commands::Replay {
+            file,
+            label_attribute,
+            tick_ms,
+        } => {
+            let replay = ReplayConfig { label_attribute };
+            run_replay_benchmark(&file, &replay, tick_ms, cli.output, &opts);
+        }
+        #[cfg(feature = "kafka")]
+        Commands::Kafka {
+            brokers,
+            topic,
+            group_id,
+            label_attribute,
+            max_batch,
+            idle_timeout_ms,
+        } => {
+            let kafka = via_sim::KafkaConfig {
+                brokers,
+                topic,
+                group_id,
+                label_attribute,
+            };
+            let idle = std::time::Duration::from_millis(idle_timeout_ms);
+            run_kafka_benchmark(&kafka, max_batch, idle, cli.output, &opts);
+        }
+        Commands::Compare {
+            files,
+            output,
+            format,
+            max_accuracy_drop,
+            max_latency_increase_pct,
+            max_throughput_drop_pct,
+            max_allocation_increase,
+        } => {
+            let tolerance = CompareTolerance {
+                max_accuracy_drop,
+                max_latency_increase_pct,
+                max_throughput_drop_pct,
+                max_allocation_increase,
+                ..Default::default()
+            };
+            compare_results(&files, &tolerance, &format, output);
+        }
+        Commands::FfiOverhead { scenario } => {
+            run_ffi_overhead_benchmark(&scenario, cli.output, &opts);
+        }
+        Commands::Gate {
+            baseline,
+            scenario,
+            max_f1_drop,
+            max_p99_increase,
+            max_throughput_drop,
+        } => {
+            let tolerance = CompareTolerance {
+                max_accuracy_drop: max_f1_drop,
+                max_latency_increase_pct: max_p99_increase,
+                max_throughput_drop_pct: max_throughput_drop.unwrap_or(f64::INFINITY),
+                ..Default::default()
+            };
+            let gated = max_throughput_drop.is_some();
+            let outcome = run_gate(&baseline, &scenario, &tolerance, gated, cli.output, &opts);
+            match &outcome {
+                GateOutcome::Pass => println!("✅ Gate passed"),
+                GateOutcome::Regression(failures) => {
+                    for d in failures {
+                        eprintln!(
+                            "❌ {} regressed: {:.4} → {:.4}",
+                            d.metric, d.baseline, d.candidate
+                        );
+                    }
+                }
+                GateOutcome::Error(e) => eprintln!("{e}"),
+            }
+            std::process::exit(outcome.exit_code());
+        }
+        Commands::Leaderboard {
+            files,
+            weights,
+            format,
+        } => {
+            print_leaderboard(&files, weights.as_deref(), &format, cli.output);
+        }
+        #[cfg(feature = "history")]
+        Commands::History {
+            db,
+            metric,
+            scenario,
+            limit,
+            format,
+        } => {
+            print_history(
+                &db,
+                &metric,
+                scenario.as_deref(),
+                limit,
+                &format,
+                cli.output,
+            );
+        }
+        Commands::ListDetectors => {
+            list_detectors();
+        }
+        Commands::Export {
+            input,
+            format,
+            output,
+        } => {
+            export_results(&input, &format, output);
+        }
+    }
+
+    #[cfg(feature = "profiling")]
+    if let Some((profiler, path)) = profiler {
+        match profiler.write_flamegraph(&path) {
+            Ok(()) => println!("\nFlamegraph saved to: {}", path.display()),
+            Err(e) => eprintln!("Warning: {}", e),
+        }
+    }
+}
+
+fn exit_with(msg: &str) -> ! {
+    eprintln!("Error: {}", msg);
+    std::process::exit(1);
+}
+
+fn run_all_benchmarks(
+    configs: Vec<BenchmarkConfig>,
+    format: &str,
+    output: Option<String>,
+    verbose: bool,
+    opts: &RunOptions,
+) {
+    println!(
+        "Running all benchmarks... (batch_size: {})\n",
+        opts.batch_label()
+    );
+
+    let several = configs.len() > 1;
+    let configs: Vec<BenchmarkConfig> = configs
+        .into_iter()
+        .map(|mut c| {
+            opts.apply(&mut c);
+            if several && let Some(path) = &opts.trace {
+                c.trace = Some(trace::path_for(path, &c.name));
+            }
+            c
+        })
+        .collect();
+
+    let mut all_results = Vec::new();
+
+    for config in configs {
+        if verbose {
+            println!("Running: {}", config.name);
+        }
+
+        let results = opts.run(config).unwrap_or_else(|e| exit_with(&e));
+
+        if verbose {
+            BenchmarkRunner::new().print_results(&results);
+            println!();
+        }
+
+        record_history(opts, &results.config, &results);
+        all_results.push(results);
+    }
+
+    // Export results
+    let json = serde_json::to_string_pretty(&all_results).unwrap();
+
+    if let Some(output_file) = output {
+        compression::write(&output_file, json).expect("Failed to write results");
+        println!("Results saved to: {}", output_file);
+    } else {
+        match format {
+            "json" => println!("{}", json),
+            _ => println!("Results generated ({} scenarios)", all_results.len()),
+        }
+    }
+}
+
+fn run_single_benchmark(
+    name: &str,
+    duration_override: Option<u64>,
+    output: Option<String>,
+    opts: &RunOptions,
+) {
+    let mut config = match name {
+        "mixed" => scenarios::mixed_workload(),
+        "security" => scenarios::security_audit(),
+        "performance" => scenarios::performance_stress(),
+        "checkout" => scenarios::checkout_funnel(),
+        "iot" => scenarios::iot_fleet(),
+        "quick" => scenarios::quick_validation(),
+        _ => scenarios::mixed_workload(),
+    };
+
+    // Apply global CLI overrides
+    opts.apply(&mut config);
+
+    // Apply duration override if specified
+    let config = if let Some(duration) = duration_override {
+        BenchmarkConfig {
+            duration_minutes: duration,
+            ..config
+        }
+    } else {
+        config
+    };
+
+    run_benchmark(config, name, output, opts);
+}
+
+/// Run the benchmark config in `path` with the global flags applied
+fn run_config_file(path: &str, output: Option<String>, opts: &RunOptions) {
+    let mut config = suite::load_config(path).unwrap_or_else(|e| exit_with(&e));
+    opts.apply(&mut config);
+    // A bad override is the file's fault: report it before running anything
+    if let Err(e) = config.profile_config() {
+        eprintln!("Error: {}: invalid profile settings: {}", path, e);
+        std::process::exit(2);
+    }
+    let stem = compression::strip_extension(std::path::Path::new(path));
+    let name = stem.file_stem().and_then(|s| s.to_str()).unwrap_or(path);
+    run_benchmark(config, name, output, opts);
+}
+
+fn run_benchmark(config: BenchmarkConfig, name: &str, output: Option<String>, opts: &RunOptions) {
+    println!(
+        "Running benchmark: {} (batch_size: {}, seed: {})\n",
+        config.name,
+        batch_label(config.batch_size),
+        config.simulation_seed
+    );
+
+    let results = opts.run(config).unwrap_or_else(|e| exit_with(&e));
+    BenchmarkRunner::new().print_results(&results);
+
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        compression::write(&output_file, json).expect("Failed to write results");
+        println!("\nResults saved to: {}", output_file);
+    }
+    record_history(opts, name, &results);
+}
+
+fn run_throughput_benchmark(
+    duration: u64,
+    threads: usize,
+    shards: usize,
+    output: Option<String>,
+    opts: &RunOptions,
+) {
+    if threads > 1 {
+        println!(
+            "Running throughput test ({} minutes, 1-{} threads, {} shards, seed: {})...\n",
+            duration,
+            threads,
+            shards,
+            opts.seed()
+        );
+    } else {
+        println!(
+            "Running throughput test ({} minutes, batch_size: {}, seed: {})...\n",
+            duration,
+            opts.batch_label(),
+            opts.seed()
+        );
+    }
+
+    let mut config = BenchmarkConfig {
+        name: "Throughput Test".to_string(),
+        base_scenario: "normal_traffic".to_string(),
+        duration_minutes: duration,
+        tick_ms: 10, // Small tick for high throughput
+        anomalies: vec![],
+        ..Default::default()
+    };
+    opts.apply(&mut config);
+
+    if threads > 1 {
+        let results =
+            parallel::run_scaling(&config, threads, shards).unwrap_or_else(|e| exit_with(&e));
+        parallel::print_parallel_throughput(&results);
+        if let Some(output_file) = output {
+            let json = serde_json::to_string_pretty(&results).unwrap();
+            compression::write(&output_file, json).expect("Failed to write results");
+        }
+        return;
+    }
+
+    let mut runner = BenchmarkRunner::new();
+    let results = runner.run(config).unwrap_or_else(|e| exit_with(&e));
+    runner.print_results(&results);
+
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        compression::write(&output_file, json).expect("Failed to write results");
+    }
+    record_history(opts, "throughput", &results);
+}
+
+fn run_rate_sweep_benchmark(
+    scenario: &str,
+    sweep: &RateSweepConfig,
+    output: Option<String>,
+    opts: &RunOptions,
+) {
+    let mut config = scenario_by_name(scenario);
+    opts.apply(&mut config);
+
+    println!(
+        "Running rate sweep: {} (budget: P99 ≤ {} µs, x{} per step)\n",
+        config.name, sweep.latency_budget_micros, sweep.step_factor
+    );
+
+    let results = rate_sweep::run_rate_sweep(&config, sweep).unwrap_or_else(|e| exit_with(&e));
+    rate_sweep::print_rate_sweep(&results);
+
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        compression::write(&output_file, json).expect("Failed to write sweep results");
+        println!("\nSweep results saved to: {}", output_file);
+    }
+}
+
+fn run_churn_benchmark(
+    scenario: &str,
+    capacities: &[usize],
+    output: Option<String>,
+    opts: &RunOptions,
+) {
+    let mut config = scenario_by_name(scenario);
+    opts.apply(&mut config);
+
+    println!(
+        "Running entity churn sweep: {} ({} registry capacities)\n",
+        config.name,
+        capacities.len()
+    );
+
+    let results = churn::run_churn_sweep(&config, capacities).unwrap_or_else(|e| exit_with(&e));
+    churn::print_churn_sweep(&results);
+
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        compression::write(&output_file, json).expect("Failed to write sweep results");
+        println!("\nSweep results saved to: {}", output_file);
+    }
+}
+
+fn run_soak_benchmark(
+    scenario: &str,
+    soak: &SoakConfig,
+    stream: &str,
+    output: Option<String>,
+    opts: &RunOptions,
+) {
+    let mut config = scenario_by_name(scenario);
+    opts.apply(&mut config);
+
+    // Append-only: a restarted soak adds to the stream instead of replacing it
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(stream)
+        .unwrap_or_else(|e| exit_with(&format!("Failed to open {}: {}", stream, e)));
+    let mut out = std::io::BufWriter::new(file);
+    let summary = BenchmarkRunner::new()
+        .run_soak(config, soak, &mut out)
+        .unwrap_or_else(|e| match e.kind() {
+            std::io::ErrorKind::InvalidInput => exit_with(&e.to_string()),
+            _ => exit_with(&format!("Failed to write {}: {}", stream, e)),
+        });
+    soak::print_soak_summary(&summary);
+    println!("\nSnapshots appended to: {}", stream);
+
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&summary).unwrap();
+        compression::write(&output_file, json).expect("Failed to write soak summary");
+        println!("Soak summary saved to: {}", output_file);
+    }
+}
+
+#[cfg(feature = "tui")]
+fn run_watch_benchmark(
+    scenario: &str,
+    watch: &WatchConfig,
+    output: Option<String>,
+    opts: &RunOptions,
+) {
+    let mut config = scenario_by_name(scenario);
+    opts.apply(&mut config);
+    config.quiet = true;
+
+    let results = via_bench::watch::watch(config, watch).unwrap_or_else(|e| match e.kind() {
+        std::io::ErrorKind::InvalidInput => exit_with(&e.to_string()),
+        _ => exit_with(&format!("Terminal error: {}", e)),
+    });
+    BenchmarkRunner::new().print_results(&results);
+
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        compression::write(&output_file, json).expect("Failed to write results");
+        println!("\nResults saved to: {}", output_file);
+    }
+    record_history(opts, scenario, &results);
+}
+
+/// `10x`, `0.5x` or a bare factor
+#[cfg(feature = "tui")]
+fn parse_speed(s: &str) -> Result<f64, String> {
+    let factor = s.trim().trim_end_matches(['x', 'X']);
+    match factor.parse::<f64>() {
+        Ok(speed) if speed > 0.0 && speed.is_finite() => Ok(speed),
+        _ => Err(format!(
+            "expected a positive speed such as 10x or 0.5x, got '{}'",
+            s
+        )),
+    }
+}
+
+fn run_delay_sweep_benchmark(
+    scenario: &str,
+    delays_ms: &[u64],
+    jitter_ratio: f64,
+    output: Option<String>,
+    opts: &RunOptions,
+) {
+    let mut config = scenario_by_name(scenario);
+    opts.apply(&mut config);
+
+    println!(
+        "Running ingestion delay sweep: {} ({} delays, jitter {:.0}% of delay)\n",
+        config.name,
+        delays_ms.len(),
+        jitter_ratio * 100.0
+    );
+
+    let results = ingestion::run_delay_sweep(&config, delays_ms, jitter_ratio)
+        .unwrap_or_else(|e| exit_with(&e));
+    ingestion::print_delay_sweep(&results);
+
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        compression::write(&output_file, json).expect("Failed to write sweep results");
+        println!("\nSweep results saved to: {}", output_file);
+    }
+}
+
+fn run_grid_search_benchmark(
+    scenario: &str,
+    params: &[ParamRange],
+    threads: usize,
+    top: usize,
+    output: Option<String>,
+    opts: &RunOptions,
+) {
+    let mut config = scenario_by_name(scenario);
+    opts.apply(&mut config);
+
+    println!(
+        "Running grid search: {} ({} combinations, {} threads)\n",
+        config.name,
+        grid::combinations(params).len(),
+        threads
+    );
+
+    let results = match grid::run_grid_search(&config, params, threads) {
+        Ok(results) => results,
+        Err(e) => exit_with(&e),
+    };
+    grid::print_grid_search(&results, top);
+
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        compression::write(&output_file, json).expect("Failed to write grid search results");
+        println!("\nGrid search results saved to: {}", output_file);
+    }
+}
 
-        /// Anomaly send batch size to Tier-2
-        #[arg(long, default_value = "256")]
-        send_batch: usize,
-    },
+fn run_ablation_benchmark(scenario: &str, output: Option<String>, opts: &RunOptions) {
+    let mut config = scenario_by_name(scenario);
+    opts.apply(&mut config);
 
-    /// Compare benchmark results
-    Compare {
-        /// Result files to compare
-        files: Vec<String>,
+    println!(
+        "Running detector ablation: {} ({} runs)\n",
+        config.name,
+        NUM_DETECTORS + 1
+    );
 
-        /// Output comparison to file
-        #[arg(short, long)]
-        output: Option<String>,
-    },
+    let results = ablation::run_ablation(&config).unwrap_or_else(|e| exit_with(&e));
+    ablation::print_ablation(&results);
 
-    /// List available detectors
-    ListDetectors,
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        compression::write(&output_file, json).expect("Failed to write ablation results");
+        println!("\nAblation results saved to: {}", output_file);
+    }
+}
 
-    /// Export results in various formats
-    Export {
-        /// Input result file
-        input: String,
+fn run_coverage_benchmark(
+    base: &str,
+    duration_secs: u64,
+    anomaly_secs: u64,
+    scenarios: Vec<String>,
+    output: Option<String>,
+    opts: &RunOptions,
+) {
+    let scenarios = if scenarios.is_empty() {
+        coverage::anomaly_scenarios()
+    } else {
+        scenarios
+    };
+    let unknown = std::iter::once(base)
+        .chain(scenarios.iter().map(String::as_str))
+        .find(|name| via_sim::create_scenario(name).is_none());
+    if let Some(name) = unknown {
+        exit_with(&format!("Unknown scenario '{}' (see via-sim list)", name));
+    }
 
-        /// Output format
-        #[arg(short, long, default_value = "html")]
-        format: String,
+    let mut config = BenchmarkConfig {
+        name: "Per-Scenario Coverage".to_string(),
+        base_scenario: base.to_string(),
+        duration_secs,
+        tick_ms: 100,
+        anomalies: Vec::new(),
+        ..Default::default()
+    };
+    opts.apply(&mut config);
 
-        /// Output file
-        #[arg(short, long)]
-        output: Option<String>,
-    },
-}
+    println!(
+        "Running per-scenario coverage: {} anomaly scenarios in {} ({}s runs, batch_size: {})\n",
+        scenarios.len(),
+        base,
+        duration_secs,
+        batch_label(config.batch_size)
+    );
 
-fn main() {
-    let cli = Cli::parse();
-    let batch_size = cli.batch;
-    let seed = cli.seed;
+    let results =
+        coverage::run_coverage(&config, &scenarios, anomaly_secs).unwrap_or_else(|e| exit_with(&e));
+    println!("\n{}", coverage::coverage_markdown(&results));
 
-    match cli.command {
-        Commands::RunAll { format } => {
-            run_all_benchmarks(&format, cli.output, cli.verbose, batch_size, seed);
-        }
-        Commands::MixedWorkload { duration } => {
-            run_single_benchmark("mixed", duration, cli.output, batch_size, seed);
-        }
-        Commands::SecurityAudit => {
-            run_single_benchmark("security", None, cli.output, batch_size, seed);
-        }
-        Commands::PerformanceStress => {
-            run_single_benchmark("performance", None, cli.output, batch_size, seed);
-        }
-        Commands::Throughput { duration } => {
-            run_throughput_benchmark(duration, cli.output, batch_size, seed);
-        }
-        Commands::Quick => {
-            run_single_benchmark("quick", None, cli.output, batch_size, seed);
-        }
-        Commands::Pipeline {
-            tier2_url,
-            scenario,
-            duration,
-            send_batch,
-        } => {
-            run_pipeline_benchmark(
-                &tier2_url, &scenario, duration, send_batch, cli.output, seed,
-            );
-        }
-        Commands::Compare { files, output } => {
-            compare_results(&files, output);
-        }
-        Commands::ListDetectors => {
-            list_detectors();
-        }
-        Commands::Export {
-            input,
-            format,
-            output,
-        } => {
-            export_results(&input, &format, output);
-        }
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        compression::write(&output_file, json).expect("Failed to write coverage results");
+        println!("Coverage results saved to: {}", output_file);
     }
 }
 
-fn run_all_benchmarks(
-    format: &str,
+fn run_feedback_loop_benchmark(
+    scenario: &str,
+    feedback: &FeedbackLoopConfig,
     output: Option<String>,
-    verbose: bool,
-    batch_size: usize,
-    seed: u64,
+    opts: &RunOptions,
 ) {
+    let mut config = scenario_by_name(scenario);
+    opts.apply(&mut config);
+
     println!(
-        "Running all benchmarks... (batch_size: {})\n",
-        if batch_size > 0 {
-            format!("{}", batch_size)
-        } else {
-            "single".to_string()
-        }
+        "Running feedback loop: {} (labels after {}ms, {:.0}% noise)\n",
+        config.name,
+        feedback.delay_ms,
+        feedback.label_noise * 100.0
     );
 
-    let configs: Vec<BenchmarkConfig> = vec![
-        scenarios::mixed_workload(),
-        scenarios::security_audit(),
-        scenarios::performance_stress(),
-        scenarios::throughput_test(),
-    ]
-    .into_iter()
-    .map(|mut c| {
-        c.batch_size = batch_size;
-        c.simulation_seed = seed;
-        c
-    })
-    .collect();
+    let results =
+        feedback_loop::run_feedback_loop(&config, feedback).unwrap_or_else(|e| exit_with(&e));
+    feedback_loop::print_feedback_loop(&results);
 
-    let mut all_results = Vec::new();
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        compression::write(&output_file, json).expect("Failed to write feedback loop results");
+        println!("\nFeedback loop results saved to: {}", output_file);
+    }
+}
 
-    for config in configs {
-        if verbose {
-            println!("Running: {}", config.name);
-        }
+fn run_robustness_benchmark(
+    scenario: &str,
+    feedback: &FeedbackLoopConfig,
+    noise_levels: &[f64],
+    contamination_levels: &[f64],
+    output: Option<String>,
+    opts: &RunOptions,
+) {
+    let mut config = scenario_by_name(scenario);
+    opts.apply(&mut config);
 
-        let mut runner = BenchmarkRunner::new();
-        let results = runner.run(config);
+    println!(
+        "Running robustness: {} ({} noise levels, {} contamination levels, labels after {}ms)\n",
+        config.name,
+        noise_levels.len(),
+        contamination_levels.len(),
+        feedback.delay_ms
+    );
 
-        if verbose {
-            runner.print_results(&results);
-            println!();
-        }
+    let results = robustness::run_robustness(&config, feedback, noise_levels, contamination_levels)
+        .unwrap_or_else(|e| exit_with(&e));
+    robustness::print_robustness(&results);
 
-        all_results.push(results);
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        compression::write(&output_file, json).expect("Failed to write robustness results");
+        println!("\nRobustness results saved to: {}", output_file);
     }
+}
 
-    // Export results
-    let json = serde_json::to_string_pretty(&all_results).unwrap();
+fn run_ffi_overhead_benchmark(scenario: &str, output: Option<String>, opts: &RunOptions) {
+    let mut config = scenario_by_name(scenario);
+    opts.apply(&mut config);
+
+    println!("Running FFI overhead comparison: {}\n", config.name);
+
+    let (_, _, overhead) = ffi::run_ffi_overhead(&config).unwrap_or_else(|e| exit_with(&e));
+    ffi::print_ffi_overhead(&overhead);
 
     if let Some(output_file) = output {
-        std::fs::write(&output_file, json).expect("Failed to write results");
-        println!("Results saved to: {}", output_file);
-    } else {
-        match format {
-            "json" => println!("{}", json),
-            _ => println!("Results generated ({} scenarios)", all_results.len()),
-        }
+        let json = serde_json::to_string_pretty(&overhead).unwrap();
+        compression::write(&output_file, json).expect("Failed to write FFI overhead results");
+        println!("\nFFI overhead results saved to: {}", output_file);
     }
 }
 
-fn run_single_benchmark(
-    name: &str,
-    duration_override: Option<u64>,
+fn run_replay_benchmark(
+    file: &str,
+    replay: &ReplayConfig,
+    tick_ms: u64,
     output: Option<String>,
-    batch_size: usize,
-    seed: u64,
+    opts: &RunOptions,
 ) {
-    let mut config = match name {
-        "mixed" => scenarios::mixed_workload(),
-        "security" => scenarios::security_audit(),
-        "performance" => scenarios::performance_stress(),
-        "quick" => scenarios::quick_validation(),
-        _ => scenarios::mixed_workload(),
+    let mut source = match ReplaySource::load(file, replay) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
     };
 
-    // Apply batch_size
-    config.batch_size = batch_size;
-    config.simulation_seed = seed;
-
-    // Apply duration override if specified
-    let config = if let Some(duration) = duration_override {
-        BenchmarkConfig {
-            duration_minutes: duration,
-            ..config
-        }
-    } else {
-        config
+    let mut config = BenchmarkConfig {
+        name: format!("Replay: {}", file),
+        base_scenario: "replay".to_string(),
+        tick_ms,
+        anomalies: vec![],
+        ..Default::default()
     };
+    opts.apply(&mut config);
+    config.trace = opts.trace.clone();
 
     println!(
-        "Running benchmark: {} (batch_size: {}, seed: {})\n",
-        config.name,
-        if batch_size > 0 {
-            format!("{}", batch_size)
-        } else {
-            "single".to_string()
-        },
-        config.simulation_seed
+        "Replaying {} ({} records, batch_size: {})\n",
+        file,
+        source.len(),
+        opts.batch_label()
     );
+    if source.ground_truth().is_empty() {
+        println!("  Note: no labelled records; accuracy metrics are not meaningful\n");
+    }
 
     let mut runner = BenchmarkRunner::new();
-    let results = runner.run(config);
+    let results = runner
+        .run_replay(config, &mut source)
+        .unwrap_or_else(|e| exit_with(&e));
     runner.print_results(&results);
 
     if let Some(output_file) = output {
         let json = serde_json::to_string_pretty(&results).unwrap();
-        std::fs::write(&output_file, json).expect("Failed to write results");
+        compression::write(&output_file, json).expect("Failed to write results");
         println!("\nResults saved to: {}", output_file);
     }
 }
 
-fn run_throughput_benchmark(duration: u64, output: Option<String>, batch_size: usize, seed: u64) {
+fn run_dataset_benchmark(
+    dataset: &Dataset,
+    data_dir: Option<String>,
+    tick_ms: u64,
+    output: Option<String>,
+    opts: &RunOptions,
+) {
+    let root = data_dir
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| dataset.default_dir());
+    let records = dataset.load(&root).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    let mut source = ReplaySource::from_records(records);
+
+    let mut config = BenchmarkConfig {
+        name: format!("Dataset: {}", dataset),
+        base_scenario: dataset.to_string(),
+        tick_ms,
+        anomalies: vec![],
+        // One entity and one profile per series
+        per_service: true,
+        ..Default::default()
+    };
+    config.extraction.entity_key = EntityKey::field("service.name");
+    config.extraction.value = ValueSource::Attribute(AttributePath::new(datasets::VALUE_ATTR));
+    opts.apply(&mut config);
+    config.trace = opts.trace.clone();
+
     println!(
-        "Running throughput test ({} minutes, batch_size: {}, seed: {})...\n",
-        duration,
-        if batch_size > 0 {
-            format!("{}", batch_size)
-        } else {
-            "single".to_string()
-        },
-        seed
+        "Loaded {} from {} ({} samples, {} windows, batch_size: {})\n",
+        dataset,
+        root.display(),
+        source.len(),
+        source.ground_truth().len(),
+        opts.batch_label()
     );
 
-    let config = BenchmarkConfig {
-        name: "Throughput Test".to_string(),
-        base_scenario: "normal_traffic".to_string(),
-        duration_minutes: duration,
-        tick_ms: 10, // Small tick for high throughput
-        simulation_seed: seed,
+    let mut runner = BenchmarkRunner::new();
+    let results = runner
+        .run_replay(config, &mut source)
+        .unwrap_or_else(|e| exit_with(&e));
+    runner.print_results(&results);
+
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        compression::write(&output_file, json).expect("Failed to write results");
+        println!("\nResults saved to: {}", output_file);
+    }
+}
+
+#[cfg(feature = "kafka")]
+fn run_kafka_benchmark(
+    kafka: &via_sim::KafkaConfig,
+    max_batch: usize,
+    idle: std::time::Duration,
+    output: Option<String>,
+    opts: &RunOptions,
+) {
+    let mut source = match via_sim::KafkaSource::new(kafka) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut config = BenchmarkConfig {
+        name: format!("Kafka: {}", kafka.topic),
+        base_scenario: "kafka".to_string(),
         anomalies: vec![],
-        batch_size,
+        ..Default::default()
     };
+    opts.apply(&mut config);
+    config.trace = opts.trace.clone();
+
+    println!(
+        "Consuming {} from {} (batch_size: {}, idle timeout: {:?})\n",
+        kafka.topic,
+        kafka.brokers,
+        opts.batch_label(),
+        idle
+    );
 
+    let batches = std::iter::from_fn(|| match source.next_batch(max_batch, idle) {
+        Ok(batch) => batch,
+        Err(e) => {
+            eprintln!("{e}");
+            None
+        }
+    });
     let mut runner = BenchmarkRunner::new();
-    let results = runner.run(config);
+    let results = runner
+        .run_stream(config, batches)
+        .unwrap_or_else(|e| exit_with(&e));
     runner.print_results(&results);
 
     if let Some(output_file) = output {
         let json = serde_json::to_string_pretty(&results).unwrap();
-        std::fs::write(&output_file, json).expect("Failed to write results");
+        compression::write(&output_file, json).expect("Failed to write results");
+        println!("\nResults saved to: {}", output_file);
     }
 }
 
@@ -356,7 +1929,7 @@ fn run_pipeline_benchmark(
             let json = serde_json::to_string_pretty(&results).unwrap();
             println!("{json}");
             if let Some(output_file) = output {
-                std::fs::write(&output_file, json).expect("Failed to write pipeline results");
+                compression::write(&output_file, json).expect("Failed to write pipeline results");
                 println!("Pipeline results saved to: {}", output_file);
             }
         }
@@ -367,18 +1940,164 @@ fn run_pipeline_benchmark(
     }
 }
 
-fn compare_results(files: &[String], output: Option<String>) {
-    println!("Comparing {} benchmark results...\n", files.len());
+fn compare_results(
+    files: &[String],
+    tolerance: &CompareTolerance,
+    format: &str,
+    output: Option<String>,
+) {
+    if files.len() < 2 {
+        eprintln!("compare needs a baseline and at least one candidate file");
+        std::process::exit(1);
+    }
+
+    let load = |file: &String| {
+        via_bench::load_results(file).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        })
+    };
+    let baselines = load(&files[0]);
+    let candidates: Vec<_> = files[1..].iter().flat_map(load).collect();
+
+    let comparisons = match compare::compare_all(&baselines, &candidates, tolerance) {
+        Ok(comparisons) => comparisons,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    let rendered = match format {
+        "json" => serde_json::to_string_pretty(&comparisons).unwrap(),
+        _ => compare::comparison_markdown(&comparisons),
+    };
+
+    if let Some(output_file) = output {
+        compression::write(&output_file, rendered).expect("Failed to write comparison");
+        println!("Comparison saved to: {}", output_file);
+    } else {
+        println!("{}", rendered);
+    }
+}
+
+/// Run `scenario` and compare it with the baseline; the full comparison is
+/// printed for context
+fn run_gate(
+    baseline: &str,
+    scenario: &str,
+    tolerance: &CompareTolerance,
+    gate_throughput: bool,
+    output: Option<String>,
+    opts: &RunOptions,
+) -> GateOutcome {
+    let baselines = match via_bench::load_results(baseline) {
+        Ok(baselines) => baselines,
+        Err(e) => return GateOutcome::Error(e),
+    };
+
+    let mut config = scenario_by_name(scenario);
+    opts.apply(&mut config);
+    let results = match opts.run(config) {
+        Ok(results) => results,
+        Err(e) => return GateOutcome::Error(e),
+    };
+
+    if let Some(output_file) = output {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        if let Err(e) = compression::write(&output_file, json) {
+            return GateOutcome::Error(format!(
+                "Failed to write results to {}: {}",
+                output_file, e
+            ));
+        }
+        println!("\nResults saved to: {}", output_file);
+    }
+
+    let comparison =
+        match compare::compare_all(&baselines, std::slice::from_ref(&results), tolerance) {
+            Ok(mut comparisons) => comparisons.remove(0),
+            Err(e) => return GateOutcome::Error(e),
+        };
+    println!(
+        "\n{}",
+        compare::comparison_markdown(std::slice::from_ref(&comparison))
+    );
+    GateOutcome::from_comparison(&comparison, gate_throughput)
+}
+
+/// Append `results` to the `--history` store, if one was given
+#[cfg(feature = "history")]
+fn record_history(opts: &RunOptions, scenario: &str, results: &BenchmarkResults) {
+    let Some(path) = &opts.history else {
+        return;
+    };
+    match HistoryStore::open(path).and_then(|mut store| store.append(scenario, results)) {
+        Ok(run_id) => println!("Recorded run {} in {}", run_id, path),
+        Err(e) => exit_with(&e),
+    }
+}
+
+#[cfg(not(feature = "history"))]
+fn record_history(_opts: &RunOptions, _scenario: &str, _results: &BenchmarkResults) {}
+
+#[cfg(feature = "history")]
+fn print_history(
+    db: &str,
+    metric: &str,
+    scenario: Option<&str>,
+    limit: usize,
+    format: &str,
+    output: Option<String>,
+) {
+    let points = HistoryStore::open(db)
+        .and_then(|store| store.series(metric, scenario, limit))
+        .unwrap_or_else(|e| exit_with(&e));
+    let rendered = match format {
+        "json" => serde_json::to_string_pretty(&points).unwrap(),
+        _ => history::trend_markdown(history::metric_name(metric).unwrap_or(metric), &points),
+    };
+
+    if let Some(output_file) = output {
+        compression::write(&output_file, rendered).expect("Failed to write history");
+        println!("History saved to: {}", output_file);
+    } else {
+        println!("{}", rendered);
+    }
+}
+
+fn print_leaderboard(
+    files: &[String],
+    weights: Option<&str>,
+    format: &str,
+    output: Option<String>,
+) {
+    let weights: Option<ScoreWeights> = weights.map(|path| {
+        let content = std::fs::read_to_string(path).expect("Failed to read weights file");
+        serde_json::from_str(&content).expect("Failed to parse weights file")
+    });
 
-    for (i, file) in files.iter().enumerate() {
-        println!("{}. {}", i + 1, file);
+    let mut results = Vec::new();
+    for file in files {
+        match via_bench::load_results(file) {
+            Ok(loaded) => results.extend(loaded.into_iter().map(|r| (file.clone(), r))),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
     }
 
-    println!("\nComparison feature not yet implemented.");
-    println!("Load the JSON results and compare metrics manually.");
+    let entries = score::leaderboard(&results, weights.as_ref());
+    let rendered = match format {
+        "json" => serde_json::to_string_pretty(&entries).unwrap(),
+        _ => score::leaderboard_markdown(&entries),
+    };
 
     if let Some(output_file) = output {
-        println!("Comparison would be saved to: {}", output_file);
+        compression::write(&output_file, rendered).expect("Failed to write leaderboard");
+        println!("Leaderboard saved to: {}", output_file);
+    } else {
+        println!("{}", rendered);
     }
 }
 
@@ -386,64 +2105,52 @@ fn list_detectors() {
     println!("Available SOTA Detectors:");
     println!();
 
-    let detectors = vec![
-        (
-            "Volume/RPS",
-            "Holt-Winters forecasting for request rate anomalies",
-        ),
-        (
-            "Distribution/Latency",
-            "Fading histogram for latency distribution shifts",
-        ),
-        (
-            "Cardinality/Velocity",
-            "HyperLogLog for new entity velocity detection",
-        ),
-        ("Burst/IAT", "Inter-arrival time analysis for micro-bursts"),
-        (
-            "Spectral/FFT",
-            "Fast Fourier Transform for frequency-domain anomalies",
-        ),
-        (
-            "ChangePoint/Trend",
-            "CUSUM for trend and level shift detection",
-        ),
-        (
-            "RRCF/Multivariate",
-            "Robust Random Cut Forest for multi-dimensional outliers",
-        ),
-        (
-            "MultiScale/Temporal",
-            "Multi-resolution analysis (second/minute/hour/day)",
-        ),
-        ("Behavioral/Fingerprint", "Per-entity behavioral profiling"),
-        (
-            "Drift/Concept",
-            "ADWIN and Page-Hinkley for distribution drift",
-        ),
-    ];
-
-    for (i, (name, desc)) in detectors.iter().enumerate() {
-        println!("{:2}. {:25} - {}", i + 1, name, desc);
+    for id in DetectorId::ALL {
+        println!(
+            "{:2}. {:12} {:23} - {}",
+            id as u8,
+            id.key(),
+            id.name(),
+            detector_description(id)
+        );
     }
 
     println!();
     println!("Use 'via-bench <scenario>' to run a benchmark.");
+    println!("Use '--detectors volume,rrcf' to run only some detectors.");
+}
+
+fn detector_description(id: DetectorId) -> &'static str {
+    match id {
+        DetectorId::Volume => "Holt-Winters forecasting for request rate anomalies",
+        DetectorId::Distribution => "Fading histogram for latency distribution shifts",
+        DetectorId::Cardinality => "HyperLogLog for new entity velocity detection",
+        DetectorId::Burst => "Inter-arrival time analysis for micro-bursts",
+        DetectorId::Spectral => "Fast Fourier Transform for frequency-domain anomalies",
+        DetectorId::ChangePoint => "CUSUM for trend and level shift detection",
+        DetectorId::RRCF => "Robust Random Cut Forest for multi-dimensional outliers",
+        DetectorId::MultiScale => "Multi-resolution analysis (second/minute/hour/day)",
+        DetectorId::Behavioral => "Per-entity behavioral profiling",
+        DetectorId::Drift => "ADWIN and Page-Hinkley for distribution drift",
+        DetectorId::Quantile => "DDSketch p95/p99 shifts for latency SLO breaches",
+        DetectorId::Evt => "Peaks-over-threshold extreme values (DSPOT) for heavy tails",
+        DetectorId::Discord => "Streaming matrix profile for repeated-pattern breaks",
+    }
 }
 
 fn export_results(input: &str, format: &str, output: Option<String>) {
     println!("Exporting {} to {} format", input, format);
 
     // Load results
-    let content = std::fs::read_to_string(input).expect("Failed to read input file");
+    let content = compression::read_to_string(input).expect("Failed to read input file");
     let results: via_bench::BenchmarkResults =
         serde_json::from_str(&content).expect("Failed to parse results");
 
     match format {
         "html" => {
-            let html = generate_html_report(&results);
+            let html = via_bench::report::html_report(&results);
             if let Some(output_file) = output {
-                std::fs::write(&output_file, html).expect("Failed to write HTML");
+                compression::write(&output_file, html).expect("Failed to write HTML");
                 println!("HTML report saved to: {}", output_file);
             } else {
                 println!("{}", html);
@@ -452,7 +2159,7 @@ fn export_results(input: &str, format: &str, output: Option<String>) {
         "csv" => {
             let csv = generate_csv_report(&results);
             if let Some(output_file) = output {
-                std::fs::write(&output_file, csv).expect("Failed to write CSV");
+                compression::write(&output_file, csv).expect("Failed to write CSV");
                 println!("CSV report saved to: {}", output_file);
             } else {
                 println!("{}", csv);
@@ -464,72 +2171,7 @@ fn export_results(input: &str, format: &str, output: Option<String>) {
     }
 }
 
-fn generate_html_report(results: &via_bench::BenchmarkResults) -> String {
-    format!(
-        r#"<!DOCTYPE html>
-<html>
-<head>
-    <title>VIA Benchmark Results</title>
-    <style>
-        body {{ font-family: Arial, sans-serif; margin: 40px; }}
-        h1 {{ color: #333; }}
-        .metric {{ margin: 20px 0; padding: 15px; background: #f5f5f5; border-radius: 5px; }}
-        .metric-label {{ font-weight: bold; color: #666; }}
-        .metric-value {{ font-size: 24px; color: #2196F3; }}
-        table {{ width: 100%; border-collapse: collapse; margin-top: 20px; }}
-        th, td {{ padding: 10px; text-align: left; border-bottom: 1px solid #ddd; }}
-        th {{ background-color: #2196F3; color: white; }}
-    </style>
-</head>
-<body>
-    <h1>VIA Detection Benchmark Results</h1>
-    
-    <div class="metric">
-        <div class="metric-label">Total Events</div>
-        <div class="metric-value">{}</div>
-    </div>
-    
-    <div class="metric">
-        <div class="metric-label">Throughput</div>
-        <div class="metric-value">{:.0} EPS</div>
-    </div>
-    
-    <div class="metric">
-        <div class="metric-label">P99 Latency</div>
-        <div class="metric-value">{:.2} μs</div>
-    </div>
-    
-    <h2>Detector Performance</h2>
-    <table>
-        <tr>
-            <th>Detector</th>
-            <th>Precision</th>
-            <th>Recall</th>
-            <th>F1-Score</th>
-        </tr>
-{}    </table>
-</body>
-</html>"#,
-        results.total_events,
-        results.throughput_eps,
-        results.latency_micros.p99_micros,
-        results
-            .detector_metrics
-            .iter()
-            .map(|(name, m)| {
-                format!(
-                    "        <tr><td>{}</td><td>{:.1}%</td><td>{:.1}%</td><td>{:.2}</td></tr>\n",
-                    name,
-                    m.precision * 100.0,
-                    m.recall * 100.0,
-                    m.f1_score
-                )
-            })
-            .collect::<String>()
-    )
-}
-
-fn generate_csv_report(results: &via_bench::BenchmarkResults) -> String {
+fn generate_csv_report(results: &BenchmarkResults) -> String {
     let mut csv = String::new();
     csv.push_str("Metric,Value\n");
     csv.push_str(&format!("Total Events,{}\n", results.total_events));
@@ -553,6 +2195,13 @@ fn generate_csv_report(results: &via_bench::BenchmarkResults) -> String {
     csv.push_str(&format!("Precision,{:.4}\n", results.precision));
     csv.push_str(&format!("Recall,{:.4}\n", results.recall));
     csv.push_str(&format!("F1-Score,{:.4}\n", results.f1_score));
+    if let Some(calibration) = &results.calibration {
+        csv.push_str(&format!("Brier Raw,{:.4}\n", calibration.brier_raw));
+        csv.push_str(&format!(
+            "Brier Calibrated,{:.4}\n",
+            calibration.brier_calibrated
+        ));
+    }
 
     csv.push_str("\nDetector,TP,FP,TN,FN,Precision,Recall,F1\n");
     for (name, m) in &results.detector_metrics {
@@ -0,0 +1,625 @@
+//! Online Run Metrics
+//!
+//! Every scored event is folded into [`OnlineMetrics`] as it is scored and
+//! then dropped, so a run's memory does not grow with its event count:
+//!
+//! - confusion counts overall, per detector, service, severity and scenario;
+//! - per-event latency in a `DDSketch` (percentiles within 1%) next to the
+//!   power-of-two histogram;
+//! - ensemble scores in a `ScoreHistogram` for the ROC / PR and calibration
+//!   curves;
+//! - time slots for the time breakdown, timeline and feedback windows. Slots
+//!   start `FIRST_SLOT_NS` wide and double, merging neighbours, whenever the
+//!   run outgrows `MAX_TIME_SLOTS`, so those are exact to within one slot;
+//! - first detections and window scoring per ground-truth window.
+//!
+//! Window matching needs every window an event could fall in. The simulator
+//! starts a window during the tick that first reports it and ends it during
+//! the tick it notices the end, so an event is matched once a batch starting
+//! after it (and after its arrival, and its tolerance before windows) has
+//! been seen. Until then it waits in a queue holding about one tick of
+//! traffic.
+//!
+//! Events are only kept when spilled to disk (see `trace`).
+
+use crate::cost::{CostMetrics, CostModel};
+use crate::curves::ScoreHistogram;
+use crate::scoring::{
+    ConfusionCounts, EventOutcome, FirstDetections, ScoringConfig, ScoringMode, Window,
+    WindowScorer,
+};
+use crate::{BreakdownMetrics, DetectionEvent, LatencyBucket, LatencyMetrics, OUTSIDE_WINDOWS};
+use std::collections::{HashMap, HashSet, VecDeque};
+use via_core::algo::DDSketch;
+use via_core::signal::NUM_DETECTORS;
+use via_sim::GroundTruth;
+
+/// Time slots kept before they double in width
+const MAX_TIME_SLOTS: usize = 1024;
+/// Width of a time slot at the start of a run
+const FIRST_SLOT_NS: u64 = 1_000_000;
+/// Relative error of the latency percentiles
+const LATENCY_ACCURACY: f64 = 0.01;
+const LATENCY_SKETCH_BINS: usize = 2048;
+
+/// Events and confusion counts of one slice of a run
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Tally {
+    pub events: u64,
+    pub anomaly_events: u64,
+    pub counts: ConfusionCounts,
+}
+
+impl Tally {
+    pub(crate) fn add(&mut self, detected: bool, labeled: bool) {
+        self.events += 1;
+        self.anomaly_events += labeled as u64;
+        let counts = &mut self.counts;
+        match (detected, labeled) {
+            (true, true) => counts.true_positives += 1,
+            (true, false) => counts.false_positives += 1,
+            (false, true) => counts.false_negatives += 1,
+            (false, false) => counts.true_negatives += 1,
+        }
+    }
+
+    pub(crate) fn merge(&mut self, other: &Tally) {
+        self.events += other.events;
+        self.anomaly_events += other.anomaly_events;
+        self.counts.true_positives += other.counts.true_positives;
+        self.counts.false_positives += other.counts.false_positives;
+        self.counts.true_negatives += other.counts.true_negatives;
+        self.counts.false_negatives += other.counts.false_negatives;
+    }
+
+    pub(crate) fn breakdown(&self, name: String) -> BreakdownMetrics {
+        let (precision, recall, f1_score) = self.counts.metrics();
+        BreakdownMetrics {
+            name,
+            events: self.events,
+            anomaly_events: self.anomaly_events,
+            true_positives: self.counts.true_positives,
+            false_positives: self.counts.false_positives,
+            true_negatives: self.counts.true_negatives,
+            false_negatives: self.counts.false_negatives,
+            precision,
+            recall,
+            f1_score,
+        }
+    }
+}
+
+/// Confusion counts of one detector's own firing decision
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct DetectorTally {
+    pub counts: ConfusionCounts,
+    pub score_sum: f64,
+}
+
+/// Scored events in one span of log time
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TimeSlot {
+    pub tally: Tally,
+    pub score_sum: f64,
+    pub max_score: f64,
+    /// Firings per detector
+    pub fires: [u32; NUM_DETECTORS],
+    /// Ensemble weights per detector, summed over the slot's events
+    pub weight_sums: [f64; NUM_DETECTORS],
+}
+
+impl TimeSlot {
+    pub(crate) fn merge(&mut self, other: &TimeSlot) {
+        self.tally.merge(&other.tally);
+        self.score_sum += other.score_sum;
+        self.max_score = self.max_score.max(other.max_score);
+        for (fires, other) in self.fires.iter_mut().zip(other.fires) {
+            *fires += other;
+        }
+        for (sum, other) in self.weight_sums.iter_mut().zip(other.weight_sums) {
+            *sum += other;
+        }
+    }
+}
+
+/// Equal spans of log time from the first scored event
+#[derive(Clone, Debug)]
+pub(crate) struct TimeSlots {
+    origin_ns: Option<u64>,
+    width_ns: u64,
+    slots: Vec<TimeSlot>,
+}
+
+impl Default for TimeSlots {
+    fn default() -> Self {
+        Self {
+            origin_ns: None,
+            width_ns: FIRST_SLOT_NS,
+            slots: Vec::new(),
+        }
+    }
+}
+
+impl TimeSlots {
+    /// Slot holding `timestamp_ns`; earlier timestamps land in the first
+    fn slot_mut(&mut self, timestamp_ns: u64) -> &mut TimeSlot {
+        let origin = *self.origin_ns.get_or_insert(timestamp_ns);
+        let offset = timestamp_ns.saturating_sub(origin);
+        while offset / self.width_ns >= MAX_TIME_SLOTS as u64 {
+            self.coarsen();
+        }
+        let index = (offset / self.width_ns) as usize;
+        if index >= self.slots.len() {
+            self.slots.resize_with(index + 1, TimeSlot::default);
+        }
+        &mut self.slots[index]
+    }
+
+    /// Merge neighbouring slots, doubling their width
+    fn coarsen(&mut self) {
+        self.slots = self
+            .slots
+            .chunks(2)
+            .map(|pair| {
+                let mut slot = pair[0].clone();
+                if let Some(next) = pair.get(1) {
+                    slot.merge(next);
+                }
+                slot
+            })
+            .collect();
+        self.width_ns *= 2;
+    }
+
+    pub(crate) fn width_ns(&self) -> u64 {
+        self.width_ns
+    }
+
+    /// Start time and contents of every slot holding events
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (u64, &TimeSlot)> {
+        let origin = self.origin_ns.unwrap_or(0);
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.tally.events > 0)
+            .map(move |(i, slot)| (origin + i as u64 * self.width_ns, slot))
+    }
+}
+
+/// Per-event detection latency
+#[derive(Clone, Debug)]
+pub(crate) struct LatencySketch {
+    sketch: DDSketch,
+    count: u64,
+    sum_micros: u64,
+    /// Bucket i counts latencies in [2^i / 2, 2^i), with 0 in the first
+    histogram: Vec<u64>,
+    /// Summed timed spans and the events they covered
+    busy_ns: u64,
+    timed_events: u64,
+}
+
+impl Default for LatencySketch {
+    fn default() -> Self {
+        Self {
+            sketch: DDSketch::new(LATENCY_ACCURACY, LATENCY_SKETCH_BINS),
+            count: 0,
+            sum_micros: 0,
+            histogram: Vec::new(),
+            busy_ns: 0,
+            timed_events: 0,
+        }
+    }
+}
+
+impl LatencySketch {
+    /// Record `events` detected in `elapsed`, as one sample of their mean
+    /// latency
+    pub(crate) fn record_span(&mut self, elapsed: std::time::Duration, events: usize) {
+        let events = events.max(1) as u64;
+        self.busy_ns += elapsed.as_nanos() as u64;
+        self.timed_events += events;
+        self.record(elapsed.as_micros() as u64 / events);
+    }
+
+    /// Detection cost of the recorded spans under `model`
+    pub(crate) fn cost(&self, model: &CostModel) -> CostMetrics {
+        CostMetrics::estimate(self.busy_ns, self.timed_events, model)
+    }
+
+    pub(crate) fn record(&mut self, micros: u64) {
+        self.sketch.add(micros as f64);
+        self.count += 1;
+        self.sum_micros += micros;
+        let index = (u64::BITS - micros.leading_zeros()) as usize;
+        if index >= self.histogram.len() {
+            self.histogram.resize(index + 1, 0);
+        }
+        self.histogram[index] += 1;
+    }
+
+    pub(crate) fn metrics(&self) -> LatencyMetrics {
+        if self.count == 0 {
+            return LatencyMetrics::default();
+        }
+        let quantile = |q: f64| self.sketch.quantile(q).unwrap_or(0.0);
+        LatencyMetrics {
+            p50_micros: quantile(0.50),
+            p95_micros: quantile(0.95),
+            p99_micros: quantile(0.99),
+            avg_micros: self.sum_micros as f64 / self.count as f64,
+            histogram: self
+                .histogram
+                .iter()
+                .enumerate()
+                .map(|(i, &count)| LatencyBucket {
+                    upper_micros: 1 << i,
+                    count,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// What window matching needs of a scored event
+#[derive(Clone, Copy, Debug)]
+struct Unmatched {
+    timestamp_ns: u64,
+    /// Arrival at the detector, which time-to-detect is measured at
+    arrival_ns: u64,
+    /// Index of the event's ground-truth window
+    window: Option<usize>,
+    labeled: bool,
+    detected: bool,
+    fired: [bool; NUM_DETECTORS],
+}
+
+/// Everything a run reports about its scored events
+#[derive(Clone, Debug)]
+pub(crate) struct OnlineMetrics {
+    pub overall: Tally,
+    pub detectors: [DetectorTally; NUM_DETECTORS],
+    pub services: HashMap<u64, Tally>,
+    pub severities: HashMap<u32, Tally>,
+    /// By the anomaly type of the window an event falls in
+    pub scenarios: HashMap<String, Tally>,
+    pub slots: TimeSlots,
+    pub scores: ScoreHistogram,
+    pub latency: LatencySketch,
+    /// Entities of true-positive events
+    pub detected_entities: HashSet<u64>,
+    /// First and last scored log timestamps
+    pub span_ns: Option<(u64, u64)>,
+    /// Window index by anomaly id, in the order windows were first seen
+    window_index: HashMap<String, usize>,
+    windows: Vec<(Window, String)>,
+    /// Ensemble first, then one per detector
+    first_detections: Vec<FirstDetections>,
+    /// Same order; empty in event scoring mode
+    scorers: Vec<WindowScorer>,
+    before_ns: u64,
+    /// Time by which every window starting or ending earlier is known
+    horizon_ns: u64,
+    last_batch_ns: Option<u64>,
+    unmatched: VecDeque<Unmatched>,
+}
+
+impl OnlineMetrics {
+    pub(crate) fn new(scoring: &ScoringConfig) -> Self {
+        let streams = 1 + NUM_DETECTORS;
+        let scorers = if scoring.mode == ScoringMode::Event {
+            Vec::new()
+        } else {
+            vec![WindowScorer::new(scoring); streams]
+        };
+        Self {
+            overall: Tally::default(),
+            detectors: [DetectorTally::default(); NUM_DETECTORS],
+            services: HashMap::new(),
+            severities: HashMap::new(),
+            scenarios: HashMap::new(),
+            slots: TimeSlots::default(),
+            scores: ScoreHistogram::default(),
+            latency: LatencySketch::default(),
+            detected_entities: HashSet::new(),
+            span_ns: None,
+            window_index: HashMap::new(),
+            windows: Vec::new(),
+            first_detections: vec![FirstDetections::default(); streams],
+            scorers,
+            before_ns: scoring.tolerance_before_ms * 1_000_000,
+            horizon_ns: 0,
+            last_batch_ns: None,
+            unmatched: VecDeque::new(),
+        }
+    }
+
+    /// Take a batch's ground truth, then match the events no window can
+    /// still start or end around
+    pub(crate) fn advance(&mut self, ground_truth: &[GroundTruth], batch_ns: u64) {
+        self.sync_windows(ground_truth);
+        // Windows starting during the batch's tick are reported by the next
+        if let Some(previous) = self.last_batch_ns.replace(batch_ns) {
+            self.horizon_ns = self.horizon_ns.max(previous);
+        }
+        let mut pending = std::mem::take(&mut self.unmatched);
+        pending.retain(|event| {
+            let ready = self.is_ready(event);
+            if ready {
+                self.match_windows(event);
+            }
+            !ready
+        });
+        self.unmatched = pending;
+    }
+
+    /// Match every waiting event against the final ground truth
+    pub(crate) fn finish(&mut self, ground_truth: &[GroundTruth]) {
+        self.sync_windows(ground_truth);
+        while let Some(event) = self.unmatched.pop_front() {
+            self.match_windows(&event);
+        }
+    }
+
+    fn sync_windows(&mut self, ground_truth: &[GroundTruth]) {
+        for gt in ground_truth {
+            let next = self.windows.len();
+            let index = *self
+                .window_index
+                .entry(gt.anomaly_id.clone())
+                .or_insert(next);
+            let window = Window {
+                start_ns: gt.start_time_ns,
+                end_ns: gt.end_time_ns,
+                absence: gt.absence,
+            };
+            if index == next {
+                self.windows.push((window, gt.anomaly_type.clone()));
+            } else {
+                self.windows[index].0 = window;
+            }
+            for first in &mut self.first_detections {
+                first.set_window(index, window);
+            }
+            for scorer in &mut self.scorers {
+                scorer.set_window(index, &window);
+            }
+        }
+    }
+
+    pub(crate) fn observe(&mut self, event: &DetectionEvent) {
+        let signal = &event.signal;
+        let (detected, labeled) = (event.detected_as_anomaly, event.is_ground_truth_anomaly);
+        let timestamp = signal.timestamp;
+        self.overall.add(detected, labeled);
+        self.services
+            .entry(event.service_hash)
+            .or_default()
+            .add(detected, labeled);
+        self.severities
+            .entry(event.severity)
+            .or_default()
+            .add(detected, labeled);
+        self.scores.add(signal.ensemble_score, labeled);
+        if detected && labeled {
+            self.detected_entities.insert(signal.entity_hash);
+        }
+        self.span_ns = Some(match self.span_ns {
+            Some((first, last)) => (first.min(timestamp), last.max(timestamp)),
+            None => (timestamp, timestamp),
+        });
+
+        let slot = self.slots.slot_mut(timestamp);
+        slot.tally.add(detected, labeled);
+        slot.score_sum += signal.ensemble_score;
+        slot.max_score = slot.max_score.max(signal.ensemble_score);
+        for (i, score) in signal.detector_scores.iter().enumerate() {
+            let tally = &mut self.detectors[i];
+            tally.score_sum += score.score as f64;
+            let counts = &mut tally.counts;
+            match (score.fired, labeled) {
+                (true, true) => counts.true_positives += 1,
+                (true, false) => counts.false_positives += 1,
+                (false, true) => counts.false_negatives += 1,
+                (false, false) => counts.true_negatives += 1,
+            }
+            slot.fires[i] += score.fired as u32;
+            slot.weight_sums[i] += signal.detector_weights[i] as f64;
+        }
+
+        let unmatched = Unmatched {
+            timestamp_ns: timestamp,
+            arrival_ns: timestamp + event.ingest_delay_ns,
+            window: event
+                .anomaly_id
+                .as_deref()
+                .and_then(|id| self.window_index.get(id).copied()),
+            labeled,
+            detected,
+            fired: signal.detector_scores.map(|s| s.fired),
+        };
+        if self.is_ready(&unmatched) {
+            self.match_windows(&unmatched);
+        } else {
+            self.unmatched.push_back(unmatched);
+        }
+    }
+
+    fn is_ready(&self, event: &Unmatched) -> bool {
+        let latest = event
+            .timestamp_ns
+            .saturating_add(self.before_ns)
+            .max(event.arrival_ns);
+        latest < self.horizon_ns
+    }
+
+    fn match_windows(&mut self, event: &Unmatched) {
+        let ts = event.timestamp_ns;
+        let window = event.window.or_else(|| {
+            self.windows
+                .iter()
+                .position(|(w, _)| w.start_ns <= ts && ts < w.end_ns)
+        });
+        let scenario = window.map_or(OUTSIDE_WINDOWS, |i| self.windows[i].1.as_str());
+        match self.scenarios.get_mut(scenario) {
+            Some(tally) => tally.add(event.detected, event.labeled),
+            None => {
+                let mut tally = Tally::default();
+                tally.add(event.detected, event.labeled);
+                self.scenarios.insert(scenario.to_string(), tally);
+            }
+        }
+
+        let fired = std::iter::once(event.detected).chain(event.fired);
+        for (stream, detected) in fired.enumerate() {
+            let outcome = EventOutcome {
+                timestamp_ns: ts,
+                window: event.window,
+                detected,
+            };
+            if let Some(scorer) = self.scorers.get_mut(stream) {
+                scorer.observe(&outcome);
+            }
+            self.first_detections[stream].observe(&EventOutcome {
+                timestamp_ns: event.arrival_ns,
+                ..outcome
+            });
+        }
+    }
+
+    /// Time from each window's start to its first detection by `stream`
+    /// (0 for the ensemble, 1 + detector id), in `ground_truth` order
+    pub(crate) fn detection_delays(
+        &self,
+        stream: usize,
+        ground_truth: &[GroundTruth],
+    ) -> Vec<Option<u64>> {
+        let delays = self.first_detections[stream].delays();
+        ground_truth
+            .iter()
+            .map(|gt| {
+                self.window_index
+                    .get(&gt.anomaly_id)
+                    .and_then(|&i| delays[i])
+            })
+            .collect()
+    }
+
+    /// Windowed counts and detected windows of `stream`, `None` in event
+    /// scoring mode
+    pub(crate) fn windowed(&self, stream: usize) -> Option<(ConfusionCounts, usize)> {
+        self.scorers.get(stream).map(WindowScorer::finish)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scoring::{first_detection_delays, score_windows};
+    use crate::{AnomalySpec, BenchmarkConfig, BenchmarkRunner};
+
+    #[test]
+    fn test_online_window_scoring_matches_spilled_events() {
+        let scoring = ScoringConfig {
+            mode: ScoringMode::Window,
+            tolerance_before_ms: 2_000,
+            tolerance_after_ms: 3_000,
+        };
+        let mut runner = BenchmarkRunner::new();
+        let results = runner
+            .run(BenchmarkConfig {
+                duration_secs: 60,
+                anomalies: vec![
+                    AnomalySpec {
+                        scenario: "error_spike".to_string(),
+                        start_time_sec: 15,
+                        duration_sec: 10,
+                    },
+                    AnomalySpec {
+                        scenario: "traffic_spike".to_string(),
+                        start_time_sec: 40,
+                        duration_sec: 10,
+                    },
+                ],
+                scoring: scoring.clone(),
+                spill_events: true,
+                quiet: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let ground_truth = &runner.ground_truth;
+        let windows: Vec<Window> = ground_truth
+            .iter()
+            .map(|gt| Window {
+                start_ns: gt.start_time_ns,
+                end_ns: gt.end_time_ns,
+                absence: gt.absence,
+            })
+            .collect();
+        let events: Vec<DetectionEvent> = runner.spill.as_ref().unwrap().events().collect();
+        let scored = results.true_positives
+            + results.false_positives
+            + results.true_negatives
+            + results.false_negatives;
+        assert_eq!(events.len() as u64, scored);
+        let outcomes = |arrival: bool| -> Vec<EventOutcome> {
+            events
+                .iter()
+                .map(|e| EventOutcome {
+                    timestamp_ns: e.signal.timestamp + if arrival { e.ingest_delay_ns } else { 0 },
+                    window: e
+                        .anomaly_id
+                        .as_deref()
+                        .and_then(|id| ground_truth.iter().position(|gt| gt.anomaly_id == id)),
+                    detected: e.detected_as_anomaly,
+                })
+                .collect()
+        };
+
+        let delays: Vec<u64> = first_detection_delays(&windows, &outcomes(true))
+            .into_iter()
+            .flatten()
+            .collect();
+        assert!(!delays.is_empty());
+        let time_to_detect = crate::TimeToDetect::from_delays(windows.len(), delays);
+        assert_eq!(results.time_to_detect, time_to_detect);
+        let (counts, detected) = score_windows(&scoring, &windows, &outcomes(false));
+        let windowed = results.windowed.unwrap();
+        assert_eq!(windowed.counts, counts);
+        assert_eq!(windowed.windows_detected, detected);
+    }
+
+    #[test]
+    fn test_time_slots_coarsen_and_keep_counts() {
+        let mut slots = TimeSlots::default();
+        let span = 10 * MAX_TIME_SLOTS as u64 * FIRST_SLOT_NS;
+        for i in 0..1000u64 {
+            slots
+                .slot_mut(i * span / 1000)
+                .tally
+                .add(i % 10 == 0, false);
+        }
+        assert!(slots.slots.len() <= MAX_TIME_SLOTS);
+        assert_eq!(slots.width_ns, 16 * FIRST_SLOT_NS);
+        let events: u64 = slots.iter().map(|(_, slot)| slot.tally.events).sum();
+        assert_eq!(events, 1000);
+        let (start, _) = slots.iter().last().unwrap();
+        assert!(start <= span && span - start <= slots.width_ns * 2);
+    }
+
+    #[test]
+    fn test_latency_sketch_percentiles() {
+        let mut latency = LatencySketch::default();
+        for micros in 1..=1000 {
+            latency.record(micros);
+        }
+        let metrics = latency.metrics();
+        assert!((metrics.p50_micros - 500.0).abs() <= 10.0, "{metrics:?}");
+        assert!((metrics.p99_micros - 990.0).abs() <= 20.0, "{metrics:?}");
+        assert_eq!(metrics.avg_micros, 500.5);
+        let counted: u64 = metrics.histogram.iter().map(|b| b.count).sum();
+        assert_eq!(counted, 1000);
+        assert_eq!(LatencySketch::default().metrics().p99_micros, 0.0);
+    }
+}
@@ -0,0 +1,241 @@
+//! Multi-Threaded Throughput
+//!
+//! A single-threaded run measures one core, not what a deployment can
+//! ingest. `run_scaling` starts N producer threads, each generating the
+//! scenario's traffic with its own `SimulationEngine` (seeded apart) and
+//! detecting one event at a time on a `ShardedRegistry` shared by all of
+//! them, one profile per service as in per-service mode. Producers double
+//! from 1 up to N and each simulates the full duration, so the aggregate EPS
+//! at n producers against n times the single-producer EPS is the scaling
+//! efficiency per core. Generation runs on the producer threads as parsing
+//! would in a collector, so both are in the EPS.
+
+use crate::BenchmarkConfig;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use via_core::engine::AnomalyProfile;
+use via_core::registry::{RegistryConfig, ShardedRegistry};
+use via_sim::SimulationEngine;
+
+/// Aggregate throughput at one producer count
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScalingPoint {
+    pub threads: usize,
+    pub events: u64,
+    pub detections: u64,
+    pub wall_secs: f64,
+    /// Events of all producers per wall-clock second
+    pub throughput_eps: f64,
+    /// Over the single-producer throughput
+    pub speedup: f64,
+    /// `speedup / threads`: 1.0 is linear scaling
+    pub efficiency: f64,
+}
+
+/// Scaling of aggregate throughput with the producer count
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ParallelThroughputResults {
+    pub config: String,
+    pub shards: usize,
+    /// Cores available to the process
+    pub cores: usize,
+    pub points: Vec<ScalingPoint>,
+    /// Profiles in the shared registry at the end of the largest run
+    pub profiles: usize,
+}
+
+/// Producer counts of a scaling run: powers of two below `max`, then `max`
+pub fn thread_counts(max: usize) -> Vec<usize> {
+    let max = max.max(1);
+    let mut counts: Vec<usize> = std::iter::successors(Some(1usize), |n| n.checked_mul(2))
+        .take_while(|&n| n < max)
+        .collect();
+    counts.push(max);
+    counts
+}
+
+/// Run `threads` producers for `config`'s duration on a registry of `shards`
+/// shards, returning the point (unscaled) and the registry's profile count,
+/// or why the profile settings are invalid
+pub fn run_parallel(
+    config: &BenchmarkConfig,
+    threads: usize,
+    shards: usize,
+) -> Result<(ScalingPoint, usize), String> {
+    let threads = threads.max(1);
+    let profile_config = config
+        .profile_config()
+        .map_err(|e| format!("Invalid profile settings: {e}"))?;
+    let mut registry_config = RegistryConfig::default();
+    if config.max_profiles > 0 {
+        registry_config.max_profiles = config.max_profiles;
+    }
+    let registry = ShardedRegistry::<AnomalyProfile>::new(shards, registry_config);
+    let tick_ns = config.tick_ms.max(1) * 1_000_000;
+    let total_ticks = config.duration_ns() / tick_ns;
+    let (events, detections) = (AtomicU64::new(0), AtomicU64::new(0));
+
+    let start = Instant::now();
+    std::thread::scope(|scope| {
+        for producer in 0..threads {
+            let (registry, profile_config) = (&registry, &profile_config);
+            let (events, detections) = (&events, &detections);
+            scope.spawn(move || {
+                let mut engine = SimulationEngine::new_deterministic(
+                    config.simulation_seed.wrapping_add(producer as u64),
+                );
+                engine.set_rate_scale(config.rate_scale);
+                engine.start(&config.base_scenario);
+                let extraction = &config.extraction;
+                let (mut produced, mut detected) = (0u64, 0u64);
+                for _ in 0..total_ticks {
+                    let batch = engine.tick(tick_ns);
+                    let logs = batch.logs.resourceLogs.iter().flat_map(|r| &r.scopeLogs);
+                    for log in logs.flat_map(|s| &s.logRecords) {
+                        if !extraction.admits(log) {
+                            continue;
+                        }
+                        let profile_hash = match &extraction.profile_key {
+                            Some(key) => key.hash(log),
+                            None => xxhash_rust::xxh3::xxh3_64(
+                                log.service_name().unwrap_or("unknown").as_bytes(),
+                            ),
+                        };
+                        let timestamp = log.timeUnixNano.parse().unwrap_or(0);
+                        let (entity_hash, value) =
+                            (extraction.entity_hash(log), extraction.value(log));
+                        let signal = registry.with_profile(
+                            profile_hash,
+                            || AnomalyProfile::with_config(profile_config.clone()),
+                            |profile| profile.process_with_hash(timestamp, entity_hash, value),
+                        );
+                        produced += 1;
+                        detected += signal.is_anomaly as u64;
+                    }
+                }
+                events.fetch_add(produced, Ordering::Relaxed);
+                detections.fetch_add(detected, Ordering::Relaxed);
+            });
+        }
+    });
+    let wall_secs = start.elapsed().as_secs_f64();
+
+    let events = events.into_inner();
+    let point = ScalingPoint {
+        threads,
+        events,
+        detections: detections.into_inner(),
+        wall_secs,
+        throughput_eps: events as f64 / wall_secs.max(f64::MIN_POSITIVE),
+        speedup: 1.0,
+        efficiency: 1.0,
+    };
+    Ok((point, registry.len()))
+}
+
+/// Run `config` at every count of `thread_counts(max_threads)`, scaling each
+/// point against the single producer
+pub fn run_scaling(
+    config: &BenchmarkConfig,
+    max_threads: usize,
+    shards: usize,
+) -> Result<ParallelThroughputResults, String> {
+    let mut points: Vec<ScalingPoint> = Vec::new();
+    let mut profiles = 0;
+    for threads in thread_counts(max_threads) {
+        let (mut point, registry_len) = run_parallel(config, threads, shards)?;
+        if let Some(single) = points.first() {
+            point.speedup = point.throughput_eps / single.throughput_eps.max(f64::MIN_POSITIVE);
+            point.efficiency = point.speedup / threads as f64;
+        }
+        if !config.quiet {
+            println!(
+                "  {:>3} threads: {:>12.0} EPS ({:.2}x, {:>5.1}% efficient)",
+                threads,
+                point.throughput_eps,
+                point.speedup,
+                point.efficiency * 100.0
+            );
+        }
+        points.push(point);
+        profiles = registry_len;
+    }
+    Ok(ParallelThroughputResults {
+        config: config.name.clone(),
+        shards: shards.max(1),
+        cores: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        points,
+        profiles,
+    })
+}
+
+/// Print aggregate EPS and scaling efficiency per producer count
+pub fn print_parallel_throughput(results: &ParallelThroughputResults) {
+    println!("\n╔══════════════════════════════════════════════════════════════╗");
+    println!("║              MULTI-THREADED THROUGHPUT                       ║");
+    println!("╠══════════════════════════════════════════════════════════════╣");
+    println!("║ Config: {:52} ║", results.config);
+    println!(
+        "║ Shards: {:>5} | Cores: {:>4} | Profiles: {:>8} {:>11} ║",
+        results.shards, results.cores, results.profiles, ""
+    );
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    println!("║ Threads |       Events |   Aggregate EPS | Speedup | Eff.    ║");
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    for p in &results.points {
+        println!(
+            "║ {:>7} | {:>12} | {:>15.0} | {:>6.2}x | {:>5.1}%  ║",
+            p.threads,
+            p.events,
+            p.throughput_eps,
+            p.speedup,
+            p.efficiency * 100.0
+        );
+    }
+    println!("╚══════════════════════════════════════════════════════════════╝");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_counts_double_up_to_max() {
+        assert_eq!(thread_counts(1), [1]);
+        assert_eq!(thread_counts(6), [1, 2, 4, 6]);
+        assert_eq!(thread_counts(8), [1, 2, 4, 8]);
+    }
+
+    #[test]
+    fn test_producers_share_the_registry() {
+        let config = BenchmarkConfig {
+            duration_secs: 5,
+            quiet: true,
+            ..Default::default()
+        };
+        let (single, single_profiles) = run_parallel(&config, 1, 4).unwrap();
+        let (double, profiles) = run_parallel(&config, 2, 4).unwrap();
+        assert!(single.events > 0);
+        // Producers differ only in seed, so they hit the same services
+        assert!(double.events > single.events);
+        assert_eq!(profiles, single_profiles);
+
+        let results = run_scaling(&config, 2, 4).unwrap();
+        assert_eq!(results.points.len(), 2);
+        assert_eq!(results.points[0].efficiency, 1.0);
+        assert!(results.points[1].speedup > 0.0);
+    }
+
+    #[test]
+    fn test_invalid_override_is_an_error() {
+        let config = BenchmarkConfig {
+            duration_secs: 1,
+            profile_overrides: [("hw_alphaa".to_string(), 0.5)].into(),
+            quiet: true,
+            ..Default::default()
+        };
+        let err = run_scaling(&config, 2, 4).unwrap_err();
+        assert!(err.contains("hw_alphaa"), "{err}");
+    }
+}
@@ -1,7 +1,8 @@
-use crate::{calculate_metrics, scenarios, AnomalySpec, BenchmarkConfig};
+use crate::extraction::ExtractionSpec;
+use crate::{AnomalySpec, BenchmarkConfig, calculate_metrics, scenarios};
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::{Value, json};
 use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 use via_core::engine::AnomalyProfile;
@@ -124,6 +125,8 @@ struct ScheduledAnomalyWindow {
 pub struct PipelineBenchmarkRunner {
     profile: AnomalyProfile,
     client: Client,
+    /// Entity key, value and filters taken from each log
+    extraction: ExtractionSpec,
 }
 
 impl PipelineBenchmarkRunner {
@@ -136,6 +139,7 @@ impl PipelineBenchmarkRunner {
         Ok(Self {
             profile: AnomalyProfile::default(),
             client,
+            extraction: ExtractionSpec::default(),
         })
     }
 
@@ -262,9 +266,9 @@ impl PipelineBenchmarkRunner {
     ) {
         let start = Instant::now();
 
-        let value = log.metric_value();
+        let value = self.extraction.value(log);
         let timestamp: u64 = log.timeUnixNano.parse().unwrap_or(0);
-        let entity_hash = xxhash_rust::xxh3::xxh3_64(log.traceId.as_bytes());
+        let entity_hash = self.extraction.entity_hash(log);
         let ground_truth_id = resolve_ground_truth_id(log, timestamp, windows);
 
         let signal = self
@@ -392,6 +396,7 @@ impl PipelineBenchmarkRunner {
         cfg: PipelineBenchmarkConfig,
     ) -> Result<PipelineBenchmarkResults, String> {
         let run_id = format!("pipeline_{}", chrono::Utc::now().format("%Y%m%d%H%M%S"));
+        self.extraction = cfg.benchmark.extraction.clone();
 
         let mut engine = SimulationEngine::new_deterministic(cfg.simulation_seed);
         engine.start(&cfg.benchmark.base_scenario);
@@ -426,7 +431,7 @@ impl PipelineBenchmarkRunner {
             .map(|d| d.as_nanos() as u64)
             .unwrap_or(0);
 
-        let duration_ns = cfg.benchmark.duration_minutes * 60 * 1_000_000_000;
+        let duration_ns = cfg.benchmark.duration_ns();
         let tick_ns = cfg.benchmark.tick_ms * 1_000_000;
         let total_ticks = duration_ns / tick_ns;
 
@@ -447,6 +452,9 @@ impl PipelineBenchmarkRunner {
             for resource_log in &batch.logs.resourceLogs {
                 for scope_log in &resource_log.scopeLogs {
                     for log in &scope_log.logRecords {
+                        if !self.extraction.admits(log) {
+                            continue;
+                        }
                         counts.total_events += 1;
                         self.process_log(
                             &run_id,
@@ -694,6 +702,9 @@ pub fn scenario_by_name(name: &str) -> BenchmarkConfig {
         "mixed" => scenarios::mixed_workload(),
         "security" => scenarios::security_audit(),
         "performance" => scenarios::performance_stress(),
+        "checkout" => scenarios::checkout_funnel(),
+        "iot" => scenarios::iot_fleet(),
+        "churn" => scenarios::entity_churn(),
         "quick" => scenarios::quick_validation(),
         "throughput" => BenchmarkConfig {
             name: "Pipeline Throughput".to_string(),
@@ -702,7 +713,7 @@ pub fn scenario_by_name(name: &str) -> BenchmarkConfig {
             tick_ms: 50,
             simulation_seed: 42,
             anomalies: Vec::<AnomalySpec>::new(),
-            batch_size: 0,
+            ..Default::default()
         },
         _ => scenarios::quick_validation(),
     }
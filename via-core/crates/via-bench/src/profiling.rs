@@ -0,0 +1,91 @@
+//! CPU Profiling
+//!
+//! With feature `profiling`, `--profile` samples the CPU with pprof while the
+//! command runs and writes a flamegraph SVG next to the results:
+//! `--output results.json` gives `results.flamegraph.svg`, and without
+//! `--output` it is `flamegraph.svg` in the working directory. A regression
+//! the benchmark surfaces can then be read off the flamegraph of the same
+//! run instead of re-running under perf.
+
+use std::path::{Path, PathBuf};
+use via_sim::compression;
+
+/// Stack samples per second
+pub const SAMPLE_HZ: i32 = 997;
+
+/// Suffix replacing the results file's extension
+pub const FLAMEGRAPH_SUFFIX: &str = "flamegraph.svg";
+
+/// Frames of these libraries are left out of the stacks
+const BLOCKLIST: [&str; 4] = ["libc", "libgcc", "pthread", "vdso"];
+
+/// Flamegraph path for a results file: `results.json` or
+/// `results.json.zst` -> `results.flamegraph.svg`
+pub fn flamegraph_path(output: Option<&str>) -> PathBuf {
+    match output {
+        Some(output) => {
+            compression::strip_extension(Path::new(output)).with_extension(FLAMEGRAPH_SUFFIX)
+        }
+        None => PathBuf::from(FLAMEGRAPH_SUFFIX),
+    }
+}
+
+/// Sampling profiler running from `start` until the flamegraph is written
+pub struct CpuProfiler {
+    guard: pprof::ProfilerGuard<'static>,
+}
+
+impl CpuProfiler {
+    pub fn start() -> Result<Self, String> {
+        let guard = pprof::ProfilerGuardBuilder::default()
+            .frequency(SAMPLE_HZ)
+            .blocklist(&BLOCKLIST)
+            .build()
+            .map_err(|e| format!("Cannot start the CPU profiler: {}", e))?;
+        Ok(Self { guard })
+    }
+
+    /// Stop sampling and write the samples so far to `path` as a flamegraph
+    pub fn write_flamegraph(self, path: &Path) -> Result<(), String> {
+        let fail = |e: &dyn std::fmt::Display| {
+            format!("Failed to write flamegraph {}: {}", path.display(), e)
+        };
+        let report = self.guard.report().build().map_err(|e| fail(&e))?;
+        let file = std::fs::File::create(path).map_err(|e| fail(&e))?;
+        report.flamegraph(file).map_err(|e| fail(&e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BenchmarkConfig, BenchmarkRunner};
+
+    #[test]
+    fn test_flamegraph_path_replaces_extension() {
+        assert_eq!(
+            flamegraph_path(Some("out/results.json.zst")),
+            Path::new("out/results.flamegraph.svg")
+        );
+        assert_eq!(flamegraph_path(None), Path::new("flamegraph.svg"));
+    }
+
+    #[test]
+    fn test_profiled_run_writes_flamegraph() {
+        let path = std::env::temp_dir().join(format!("via-flamegraph-{}.svg", std::process::id()));
+        let profiler = CpuProfiler::start().unwrap();
+        BenchmarkRunner::new()
+            .run(BenchmarkConfig {
+                duration_secs: 20,
+                quiet: true,
+                ..Default::default()
+            })
+            .unwrap();
+        profiler.write_flamegraph(&path).unwrap();
+
+        let svg = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(svg.contains("<svg"));
+        assert!(svg.contains("BenchmarkRunner"));
+    }
+}
@@ -0,0 +1,154 @@
+//! Rate-Distortion Sweep
+//!
+//! Re-runs a benchmark at increasing offered load (scenario emission rates
+//! multiplied by a growing factor) until per-event P99 latency exceeds the
+//! budget. Each step records accuracy at that load, giving an accuracy-vs-EPS
+//! curve for capacity planning instead of a single throughput number.
+
+use crate::{BenchmarkConfig, BenchmarkResults, BenchmarkRunner};
+use serde::{Deserialize, Serialize};
+
+/// Sweep configuration
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct RateSweepConfig {
+    /// Rate multiplier of the first step
+    pub start_scale: f64,
+    /// Multiplier applied between steps (must be > 1)
+    pub step_factor: f64,
+    /// Upper bound on steps, in case the budget is never exceeded
+    pub max_steps: usize,
+    /// Per-event P99 latency budget (µs)
+    pub latency_budget_micros: f64,
+}
+
+impl Default for RateSweepConfig {
+    fn default() -> Self {
+        Self {
+            start_scale: 1.0,
+            step_factor: 2.0,
+            max_steps: 8,
+            latency_budget_micros: 100.0,
+        }
+    }
+}
+
+/// Accuracy and latency at one load level
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RateSweepPoint {
+    pub rate_scale: f64,
+    /// Simulated events per second of simulation time
+    pub offered_eps: f64,
+    /// Events processed per wall-clock second
+    pub throughput_eps: f64,
+    pub p99_micros: f64,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1_score: f64,
+    pub within_budget: bool,
+}
+
+impl RateSweepPoint {
+    fn from_results(
+        rate_scale: f64,
+        config: &BenchmarkConfig,
+        r: &BenchmarkResults,
+        budget: f64,
+    ) -> Self {
+        let sim_seconds = (config.duration_ns() as f64 / 1e9).max(1.0);
+        Self {
+            rate_scale,
+            offered_eps: r.total_events as f64 / sim_seconds,
+            throughput_eps: r.throughput_eps,
+            p99_micros: r.latency_micros.p99_micros,
+            precision: r.precision,
+            recall: r.recall,
+            f1_score: r.f1_score,
+            within_budget: r.latency_micros.p99_micros <= budget,
+        }
+    }
+}
+
+/// Full sweep output
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RateSweepResults {
+    pub config: String,
+    pub sweep: RateSweepConfig,
+    pub points: Vec<RateSweepPoint>,
+    /// Highest offered EPS that stayed within the latency budget
+    pub max_sustainable_eps: Option<f64>,
+}
+
+/// Run the sweep, stopping at the first step that exceeds the latency budget
+pub fn run_rate_sweep(
+    base: &BenchmarkConfig,
+    sweep: &RateSweepConfig,
+) -> Result<RateSweepResults, String> {
+    let step_factor = sweep.step_factor.max(1.01);
+    let mut scale = sweep.start_scale.max(f64::MIN_POSITIVE);
+    let mut points = Vec::new();
+
+    for _ in 0..sweep.max_steps.max(1) {
+        let mut config = base.clone();
+        config.rate_scale = base.rate_scale * scale;
+        config.name = format!("{} @ {:.2}x", base.name, scale);
+
+        let mut runner = BenchmarkRunner::new();
+        let results = runner.run(config.clone())?;
+        let point =
+            RateSweepPoint::from_results(scale, &config, &results, sweep.latency_budget_micros);
+        let exceeded = !point.within_budget;
+        points.push(point);
+
+        if exceeded {
+            break;
+        }
+        scale *= step_factor;
+    }
+
+    let max_sustainable_eps = points
+        .iter()
+        .filter(|p| p.within_budget)
+        .map(|p| p.offered_eps)
+        .reduce(f64::max);
+
+    Ok(RateSweepResults {
+        config: base.name.clone(),
+        sweep: sweep.clone(),
+        points,
+        max_sustainable_eps,
+    })
+}
+
+/// Print the accuracy-vs-load curve as a table
+pub fn print_rate_sweep(results: &RateSweepResults) {
+    println!("\n╔══════════════════════════════════════════════════════════════╗");
+    println!("║                 RATE-DISTORTION SWEEP                        ║");
+    println!("╠══════════════════════════════════════════════════════════════╣");
+    println!("║ Config: {:52} ║", results.config);
+    println!(
+        "║ Budget: P99 ≤ {:>8.1} µs {:>34} ║",
+        results.sweep.latency_budget_micros, ""
+    );
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    println!("║  Scale | Offered EPS |    P99 µs |  Prec  | Recall |   F1    ║");
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    for p in &results.points {
+        println!(
+            "║ {:>5.2}x | {:>11.0} | {:>9.1} | {:>5.1}% | {:>5.1}% | {:>5.3} {} ║",
+            p.rate_scale,
+            p.offered_eps,
+            p.p99_micros,
+            p.precision * 100.0,
+            p.recall * 100.0,
+            p.f1_score,
+            if p.within_budget { " " } else { "!" }
+        );
+    }
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    match results.max_sustainable_eps {
+        Some(eps) => println!("║ Max sustainable offered EPS: {:>10.0} {:>20} ║", eps, ""),
+        None => println!("║ Latency budget exceeded at the first step {:>18} ║", ""),
+    }
+    println!("╚══════════════════════════════════════════════════════════════╝");
+}
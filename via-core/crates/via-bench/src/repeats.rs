@@ -0,0 +1,205 @@
+//! Repeated Runs and Significance
+//!
+//! A single seeded run is one draw: a different seed moves F1 by more than
+//! many tuning changes do. `run_repeats` runs a config once per seed and
+//! summarizes precision, recall and F1 as mean ± standard deviation with a
+//! percentile bootstrap confidence interval. `difference` bootstraps the
+//! change in mean between two such summaries; when its interval contains
+//! zero the two configurations are not statistically distinguishable.
+
+use crate::{BenchmarkConfig, BenchmarkResults, BenchmarkRunner};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Bootstrap resamples per interval
+pub const BOOTSTRAP_RESAMPLES: usize = 2000;
+/// Two-sided confidence level of every interval
+pub const CONFIDENCE: f64 = 0.95;
+/// Seed of the bootstrap itself, so intervals are reproducible
+const BOOTSTRAP_SEED: u64 = 0x5eed;
+
+/// One metric across repeated runs
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct MetricSummary {
+    pub mean: f64,
+    /// Sample standard deviation (0 for a single run)
+    pub stddev: f64,
+    /// Bootstrap confidence interval of the mean
+    pub ci_low: f64,
+    pub ci_high: f64,
+    /// Per-run values, in seed order
+    pub samples: Vec<f64>,
+}
+
+impl MetricSummary {
+    pub fn from_samples(samples: Vec<f64>) -> Self {
+        let mean = mean(&samples);
+        let stddev = if samples.len() > 1 {
+            let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>()
+                / (samples.len() - 1) as f64;
+            var.sqrt()
+        } else {
+            0.0
+        };
+        let mut rng = StdRng::seed_from_u64(BOOTSTRAP_SEED);
+        let (ci_low, ci_high) = interval(
+            (0..BOOTSTRAP_RESAMPLES)
+                .map(|_| resample_mean(&samples, &mut rng))
+                .collect(),
+        );
+        Self {
+            mean,
+            stddev,
+            ci_low,
+            ci_high,
+            samples,
+        }
+    }
+}
+
+/// Accuracy across runs of one config with consecutive seeds
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RepeatSummary {
+    pub runs: usize,
+    pub seeds: Vec<u64>,
+    pub precision: MetricSummary,
+    pub recall: MetricSummary,
+    pub f1_score: MetricSummary,
+}
+
+/// Change in a metric's mean between two repeated configs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Difference {
+    /// Candidate mean minus baseline mean
+    pub delta: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+    /// The interval excludes zero
+    pub distinguishable: bool,
+}
+
+/// Run `base` with `repeats` consecutive seeds starting at its own.
+///
+/// Returns the first run's results with the summary in `repeats`; later runs
+/// are quiet and write no trace.
+pub fn run_repeats(base: &BenchmarkConfig, repeats: usize) -> Result<BenchmarkResults, String> {
+    let seeds: Vec<u64> = (0..repeats.max(1) as u64)
+        .map(|i| base.simulation_seed.wrapping_add(i))
+        .collect();
+    let mut first: Option<BenchmarkResults> = None;
+    let (mut precision, mut recall, mut f1) = (Vec::new(), Vec::new(), Vec::new());
+    for (i, &seed) in seeds.iter().enumerate() {
+        let mut config = base.clone();
+        config.simulation_seed = seed;
+        config.quiet |= i > 0;
+        if i > 0 {
+            config.trace = None;
+        }
+        let results = BenchmarkRunner::new().run(config)?;
+        if !base.quiet {
+            println!(
+                "  Run {:>3}/{} (seed {}): F1 {:.4}",
+                i + 1,
+                seeds.len(),
+                seed,
+                results.f1_score
+            );
+        }
+        precision.push(results.precision);
+        recall.push(results.recall);
+        f1.push(results.f1_score);
+        first.get_or_insert(results);
+    }
+
+    let mut results = first.unwrap_or_default();
+    results.repeats = Some(RepeatSummary {
+        runs: seeds.len(),
+        seeds,
+        precision: MetricSummary::from_samples(precision),
+        recall: MetricSummary::from_samples(recall),
+        f1_score: MetricSummary::from_samples(f1),
+    });
+    Ok(results)
+}
+
+/// Bootstrap the change in mean from `baseline` to `candidate`; `None`
+/// unless both have at least two samples
+pub fn difference(baseline: &MetricSummary, candidate: &MetricSummary) -> Option<Difference> {
+    if baseline.samples.len() < 2 || candidate.samples.len() < 2 {
+        return None;
+    }
+    let mut rng = StdRng::seed_from_u64(BOOTSTRAP_SEED);
+    let (ci_low, ci_high) = interval(
+        (0..BOOTSTRAP_RESAMPLES)
+            .map(|_| {
+                resample_mean(&candidate.samples, &mut rng)
+                    - resample_mean(&baseline.samples, &mut rng)
+            })
+            .collect(),
+    );
+    Some(Difference {
+        delta: mean(&candidate.samples) - mean(&baseline.samples),
+        ci_low,
+        ci_high,
+        distinguishable: ci_low > 0.0 || ci_high < 0.0,
+    })
+}
+
+fn mean(samples: &[f64]) -> f64 {
+    samples.iter().sum::<f64>() / samples.len().max(1) as f64
+}
+
+/// Mean of `samples.len()` draws with replacement
+fn resample_mean(samples: &[f64], rng: &mut StdRng) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = (0..samples.len())
+        .map(|_| samples[rng.random_range(0..samples.len())])
+        .sum();
+    sum / samples.len() as f64
+}
+
+/// Percentile interval at `CONFIDENCE` of the bootstrap statistics
+fn interval(mut stats: Vec<f64>) -> (f64, f64) {
+    stats.sort_by(f64::total_cmp);
+    let tail = (1.0 - CONFIDENCE) / 2.0;
+    let at = |q: f64| stats[((stats.len() - 1) as f64 * q).round() as usize];
+    (at(tail), at(1.0 - tail))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_interval_brackets_mean() {
+        let summary = MetricSummary::from_samples(vec![0.60, 0.62, 0.64, 0.61, 0.63]);
+        assert!((summary.mean - 0.62).abs() < 1e-9);
+        assert!((summary.stddev - 0.0158).abs() < 1e-3);
+        assert!(summary.ci_low < summary.mean && summary.mean < summary.ci_high);
+        assert!(summary.ci_low >= 0.60 && summary.ci_high <= 0.64);
+
+        let single = MetricSummary::from_samples(vec![0.5]);
+        assert_eq!(
+            (single.stddev, single.ci_low, single.ci_high),
+            (0.0, 0.5, 0.5)
+        );
+    }
+
+    #[test]
+    fn test_difference_needs_separated_runs() {
+        let base = MetricSummary::from_samples(vec![0.60, 0.62, 0.64, 0.61, 0.63]);
+        let overlapping = MetricSummary::from_samples(vec![0.61, 0.63, 0.65, 0.60, 0.62]);
+        let better = MetricSummary::from_samples(vec![0.70, 0.72, 0.71, 0.73, 0.69]);
+
+        let diff = difference(&base, &overlapping).unwrap();
+        assert!(!diff.distinguishable, "{diff:?}");
+        let diff = difference(&base, &better).unwrap();
+        assert!(diff.distinguishable && diff.ci_low > 0.0, "{diff:?}");
+        assert!((diff.delta - 0.09).abs() < 1e-9);
+
+        assert!(difference(&base, &MetricSummary::from_samples(vec![0.7])).is_none());
+    }
+}
@@ -0,0 +1,618 @@
+//! HTML Report
+//!
+//! `html_report` renders a `BenchmarkResults` file as a self-contained page
+//! with inline SVG charts, no scripts or external assets:
+//!
+//! - a timeline of the ensemble score against the ground-truth windows, with
+//!   detections per time bucket and the detector that fired most (hover a
+//!   bucket for details), showing when and why detections happened
+//! - precision / recall / F1 bars per detector
+//! - a per-event latency histogram
+//! - ROC, precision-recall and calibration curves
+//!
+//! The timeline is computed during the run (`compute_timeline`) and stored in
+//! the results, so reports can be rendered from saved files. It is built from
+//! the run's online time slots, so each slot lands whole in the bucket
+//! holding its middle.
+
+use crate::online::{OnlineMetrics, TimeSlot};
+use crate::{BenchmarkResults, LatencyBucket};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use via_core::signal::{DetectorId, NUM_DETECTORS};
+use via_sim::GroundTruth;
+
+/// Time buckets kept in exported results
+pub const TIMELINE_BUCKETS: usize = 200;
+
+const TIMELINE_WIDTH: f64 = 900.0;
+const TIMELINE_HEIGHT: f64 = 200.0;
+const BAR_HEIGHT: f64 = 180.0;
+
+/// Detection activity over the run in equal time buckets
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Timeline {
+    /// Log time of the first bucket's start
+    pub start_ns: u64,
+    pub bucket_ns: u64,
+    pub buckets: Vec<TimelineBucket>,
+    /// Ground-truth windows as offsets from `start_ns`
+    pub windows: Vec<TimelineWindow>,
+}
+
+/// Events scored in one time bucket
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TimelineBucket {
+    pub events: u64,
+    pub anomaly_events: u64,
+    pub detections: u64,
+    pub mean_score: f64,
+    pub max_score: f64,
+    /// Detector that fired most often in the bucket, if any did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_detector: Option<String>,
+}
+
+/// Ground-truth window on the timeline
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TimelineWindow {
+    pub anomaly_type: String,
+    pub start_sec: f64,
+    pub end_sec: f64,
+    /// Missing-data window (no anomalous logs)
+    #[serde(default)]
+    pub absence: bool,
+}
+
+/// Bucket the scored events into `buckets` equal spans of log time covering
+/// the events and the ground-truth windows; `None` without events
+pub(crate) fn compute_timeline(
+    metrics: &OnlineMetrics,
+    ground_truth: &[GroundTruth],
+    buckets: usize,
+) -> Option<Timeline> {
+    let buckets = buckets.max(1);
+    let (start, end) = metrics.span_ns?;
+    let end = end.max(
+        ground_truth
+            .iter()
+            .map(|gt| gt.end_time_ns)
+            .max()
+            .unwrap_or(0),
+    );
+    let start = start.min(
+        ground_truth
+            .iter()
+            .map(|gt| gt.start_time_ns)
+            .min()
+            .unwrap_or(start),
+    );
+    let bucket_ns = ((end - start) / buckets as u64).max(1) + 1;
+
+    let mut slots = vec![TimeSlot::default(); buckets];
+    for (slot_start, slot) in metrics.slots.iter() {
+        let middle = slot_start + metrics.slots.width_ns() / 2;
+        let index = ((middle.clamp(start, end) - start) / bucket_ns) as usize;
+        slots[index.min(buckets - 1)].merge(slot);
+    }
+
+    let to_sec = |ns: u64| ns.saturating_sub(start) as f64 / 1e9;
+    Some(Timeline {
+        start_ns: start,
+        bucket_ns,
+        buckets: slots
+            .into_iter()
+            .map(|slot| {
+                let fires = slot.fires;
+                let top = (0..NUM_DETECTORS)
+                    .filter(|&i| fires[i] > 0)
+                    .max_by_key(|&i| (fires[i], std::cmp::Reverse(i)));
+                let tally = slot.tally;
+                TimelineBucket {
+                    events: tally.events,
+                    anomaly_events: tally.anomaly_events,
+                    detections: tally.counts.true_positives + tally.counts.false_positives,
+                    mean_score: if tally.events > 0 {
+                        slot.score_sum / tally.events as f64
+                    } else {
+                        0.0
+                    },
+                    max_score: slot.max_score,
+                    top_detector: top
+                        .and_then(|i| DetectorId::from_u8(i as u8))
+                        .map(|id| id.name().to_string()),
+                }
+            })
+            .collect(),
+        windows: ground_truth
+            .iter()
+            .map(|gt| TimelineWindow {
+                anomaly_type: gt.anomaly_type.clone(),
+                start_sec: to_sec(gt.start_time_ns),
+                end_sec: to_sec(gt.end_time_ns),
+                absence: gt.absence,
+            })
+            .collect(),
+    })
+}
+
+/// Render `results` as a self-contained HTML page
+pub fn html_report(results: &BenchmarkResults) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <title>VIA Benchmark Results - {title}</title>
+    <style>
+        body {{ font-family: Arial, sans-serif; margin: 40px; color: #333; }}
+        .cards {{ display: flex; flex-wrap: wrap; gap: 16px; }}
+        .metric {{ padding: 15px; background: #f5f5f5; border-radius: 5px; min-width: 140px; }}
+        .metric-label {{ font-weight: bold; color: #666; }}
+        .metric-value {{ font-size: 24px; color: #2196F3; }}
+        table {{ width: 100%; border-collapse: collapse; margin-top: 20px; }}
+        th, td {{ padding: 10px; text-align: left; border-bottom: 1px solid #ddd; }}
+        th {{ background-color: #2196F3; color: white; }}
+        svg text {{ font-size: 11px; fill: #555; }}
+        .legend span {{ margin-right: 16px; }}
+    </style>
+</head>
+<body>
+    <h1>VIA Detection Benchmark Results</h1>
+    <p>{title}</p>
+    <div class="cards">
+"#,
+        title = escape(&results.config)
+    );
+    let cards = [
+        ("Total Events", results.total_events.to_string()),
+        ("Anomaly Events", results.total_anomaly_events.to_string()),
+        ("Precision", format!("{:.1}%", results.precision * 100.0)),
+        ("Recall", format!("{:.1}%", results.recall * 100.0)),
+        ("F1-Score", format!("{:.3}", results.f1_score)),
+        ("Throughput", format!("{:.0} EPS", results.throughput_eps)),
+        (
+            "P99 Latency",
+            format!("{:.2} μs", results.latency_micros.p99_micros),
+        ),
+        ("Composite", format!("{:.1}", results.composite_score)),
+    ];
+    for (label, value) in cards {
+        let _ = writeln!(
+            html,
+            r#"        <div class="metric"><div class="metric-label">{label}</div><div class="metric-value">{value}</div></div>"#
+        );
+    }
+    html.push_str("    </div>\n");
+
+    if let Some(timeline) = &results.timeline {
+        html.push_str(&timeline_html(timeline));
+    }
+    html.push_str(&detector_bars_html(results));
+    if !results.latency_micros.histogram.is_empty() {
+        html.push_str(&latency_histogram_html(
+            &results.latency_micros.histogram,
+            results.latency_micros.p50_micros,
+            results.latency_micros.p99_micros,
+        ));
+    }
+    html.push_str(&curves_html(results));
+    html.push_str(&detector_table_html(results));
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Score timeline with shaded ground-truth windows and detection bars
+fn timeline_html(timeline: &Timeline) -> String {
+    let n = timeline.buckets.len().max(1) as f64;
+    let width = TIMELINE_WIDTH / n;
+    let span_sec = n * timeline.bucket_ns as f64 / 1e9;
+    let x_of = |sec: f64| sec / span_sec * TIMELINE_WIDTH;
+    let y_of = |score: f64| TIMELINE_HEIGHT - score.clamp(0.0, 1.0) * TIMELINE_HEIGHT;
+
+    let mut svg = String::new();
+    for w in &timeline.windows {
+        let (x0, x1) = (x_of(w.start_sec), x_of(w.end_sec));
+        let fill = if w.absence { "#FFE0B2" } else { "#FFCDD2" };
+        let _ = write!(
+            svg,
+            r#"<rect x="{:.1}" y="0" width="{:.1}" height="{}" fill="{}"><title>{} ({:.1}s - {:.1}s{})</title></rect>"#,
+            x0,
+            (x1 - x0).max(1.0),
+            TIMELINE_HEIGHT,
+            fill,
+            escape(&w.anomaly_type),
+            w.start_sec,
+            w.end_sec,
+            if w.absence { ", missing data" } else { "" }
+        );
+    }
+    for (i, b) in timeline.buckets.iter().enumerate() {
+        if b.detections == 0 {
+            continue;
+        }
+        let share = b.detections as f64 / b.events.max(1) as f64;
+        let height = share * TIMELINE_HEIGHT * 0.5;
+        let _ = write!(
+            svg,
+            r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="#F44336" opacity="0.6"><title>{:.1}s: {} of {} events flagged ({} anomalous), mostly {}</title></rect>"##,
+            i as f64 * width,
+            TIMELINE_HEIGHT - height,
+            width.max(1.0),
+            height,
+            i as f64 * timeline.bucket_ns as f64 / 1e9,
+            b.detections,
+            b.events,
+            b.anomaly_events,
+            b.top_detector.as_deref().unwrap_or("-")
+        );
+    }
+    let line = |value: fn(&TimelineBucket) -> f64| {
+        timeline
+            .buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.events > 0)
+            .map(|(i, b)| format!("{:.1},{:.1}", (i as f64 + 0.5) * width, y_of(value(b))))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let _ = write!(
+        svg,
+        r##"<polyline fill="none" stroke="#90CAF9" stroke-width="1" points="{}"/><polyline fill="none" stroke="#1565C0" stroke-width="2" points="{}"/>"##,
+        line(|b| b.max_score),
+        line(|b| b.mean_score)
+    );
+    for tick in 0..=4 {
+        let sec = span_sec * tick as f64 / 4.0;
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{}" text-anchor="middle">{:.0}s</text>"#,
+            x_of(sec),
+            TIMELINE_HEIGHT + 15.0,
+            sec
+        );
+    }
+
+    format!(
+        r##"
+    <h2>Timeline</h2>
+    <div class="legend"><span style="color:#1565C0">&#9632; mean score</span><span style="color:#90CAF9">&#9632; max score</span><span style="color:#F44336">&#9632; share flagged</span><span style="color:#E57373">&#9632; anomaly window</span><span style="color:#FFB74D">&#9632; missing-data window</span></div>
+    <svg width="{}" height="{}" style="border:1px solid #ddd; overflow:visible;">{}</svg>
+"##,
+        TIMELINE_WIDTH, TIMELINE_HEIGHT, svg
+    )
+}
+
+/// Grouped precision / recall / F1 bars per detector, best F1 first
+fn detector_bars_html(results: &BenchmarkResults) -> String {
+    let mut detectors: Vec<_> = results.detector_metrics.iter().collect();
+    if detectors.is_empty() {
+        return String::new();
+    }
+    detectors.sort_by(|a, b| b.1.f1_score.total_cmp(&a.1.f1_score).then(a.0.cmp(b.0)));
+
+    let group = 60.0;
+    let bar = 16.0;
+    let mut svg = String::new();
+    for (i, (name, m)) in detectors.iter().enumerate() {
+        let x = i as f64 * group;
+        for (j, (value, color, label)) in [
+            (m.precision, "#2196F3", "precision"),
+            (m.recall, "#4CAF50", "recall"),
+            (m.f1_score, "#FF9800", "F1"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let height = value.clamp(0.0, 1.0) * BAR_HEIGHT;
+            let _ = write!(
+                svg,
+                r#"<rect x="{:.1}" y="{:.1}" width="{}" height="{:.1}" fill="{}"><title>{} {}: {:.3}</title></rect>"#,
+                x + 4.0 + j as f64 * bar,
+                BAR_HEIGHT - height,
+                bar - 2.0,
+                height,
+                color,
+                escape(name),
+                label,
+                value
+            );
+        }
+        let _ = write!(
+            svg,
+            r#"<text transform="translate({:.1},{}) rotate(40)">{}</text>"#,
+            x + 8.0,
+            BAR_HEIGHT + 12.0,
+            escape(name)
+        );
+    }
+
+    format!(
+        r##"
+    <h2>Detector Performance</h2>
+    <div class="legend"><span style="color:#2196F3">&#9632; precision</span><span style="color:#4CAF50">&#9632; recall</span><span style="color:#FF9800">&#9632; F1</span></div>
+    <svg width="{:.0}" height="{}" style="overflow:visible; margin-bottom:90px;">{}</svg>
+"##,
+        detectors.len() as f64 * group,
+        BAR_HEIGHT,
+        svg
+    )
+}
+
+/// Per-event latency histogram over power-of-two buckets
+fn latency_histogram_html(histogram: &[LatencyBucket], p50: f64, p99: f64) -> String {
+    let total: u64 = histogram.iter().map(|b| b.count).sum();
+    let width = 40.0;
+    let mut svg = String::new();
+    for (i, b) in histogram.iter().enumerate() {
+        let share = b.count as f64 / total.max(1) as f64;
+        let height = share * BAR_HEIGHT;
+        let lower = b.upper_micros / 2;
+        let _ = write!(
+            svg,
+            r##"<rect x="{:.1}" y="{:.1}" width="{}" height="{:.1}" fill="#7E57C2"><title>{}-{} μs: {} events ({:.1}%)</title></rect><text x="{:.1}" y="{}" text-anchor="middle">&lt;{}</text>"##,
+            i as f64 * width,
+            BAR_HEIGHT - height,
+            width - 4.0,
+            height,
+            lower,
+            b.upper_micros,
+            b.count,
+            share * 100.0,
+            i as f64 * width + (width - 4.0) / 2.0,
+            BAR_HEIGHT + 15.0,
+            b.upper_micros
+        );
+    }
+
+    format!(
+        r#"
+    <h2>Latency Distribution</h2>
+    <p>Per-event detection latency (μs), p50 {:.1} / p99 {:.1}</p>
+    <svg width="{:.0}" height="{}" style="overflow:visible; margin-bottom:20px;">{}</svg>
+"#,
+        p50,
+        p99,
+        histogram.len() as f64 * width,
+        BAR_HEIGHT,
+        svg
+    )
+}
+
+/// ROC, PR and calibration curves (empty when no curves were recorded)
+fn curves_html(results: &BenchmarkResults) -> String {
+    let Some(curves) = &results.curves else {
+        return String::new();
+    };
+
+    // Unit-square coordinates scaled to a 300x300 plot with y pointing up
+    let polyline = |points: Vec<(f64, f64)>, color: &str| {
+        let coords: Vec<String> = points
+            .iter()
+            .map(|(x, y)| format!("{:.1},{:.1}", x * 300.0, 300.0 - y * 300.0))
+            .collect();
+        format!(
+            r#"<polyline fill="none" stroke="{}" stroke-width="2" points="{}"/>"#,
+            color,
+            coords.join(" ")
+        )
+    };
+    let plot = |title: &str, x_label: &str, y_label: &str, line: String, diagonal: bool| {
+        let baseline = if diagonal {
+            r##"<line x1="0" y1="300" x2="300" y2="0" stroke="#ccc" stroke-dasharray="4"/>"##
+        } else {
+            ""
+        };
+        format!(
+            r##"        <figure style="display:inline-block; margin-right:40px;">
+            <figcaption>{}</figcaption>
+            <svg width="300" height="300" style="border:1px solid #ddd; overflow:visible;">
+                {}
+                {}
+                <text x="150" y="330" text-anchor="middle">{}</text>
+                <text x="-150" y="-10" transform="rotate(-90)" text-anchor="middle">{}</text>
+            </svg>
+        </figure>
+"##,
+            title, baseline, line, x_label, y_label
+        )
+    };
+
+    let roc: Vec<(f64, f64)> = std::iter::once((0.0, 0.0))
+        .chain(curves.points.iter().map(|p| (p.fpr, p.tpr)))
+        .collect();
+    let pr: Vec<(f64, f64)> = curves.points.iter().map(|p| (p.tpr, p.precision)).collect();
+    let reliability = results
+        .calibration
+        .as_ref()
+        .map_or(String::new(), |calibration| {
+            let observed: Vec<(f64, f64)> = calibration
+                .curve
+                .points
+                .iter()
+                .filter_map(|p| Some((p.score, p.observed?)))
+                .collect();
+            let fitted: Vec<(f64, f64)> = calibration
+                .curve
+                .points
+                .iter()
+                .map(|p| (p.score, p.probability))
+                .collect();
+            plot(
+                &format!(
+                    "Calibration (Brier {:.4} -> {:.4})",
+                    calibration.brier_raw, calibration.brier_calibrated
+                ),
+                "Ensemble score",
+                "Anomaly rate",
+                polyline(observed, "#9E9E9E") + &polyline(fitted, "#4CAF50"),
+                true,
+            )
+        });
+
+    format!(
+        r#"
+    <h2>Threshold Sweep</h2>
+    <div>
+{}{}{}    </div>
+"#,
+        plot(
+            &format!("ROC (AUC {:.3})", curves.auc),
+            "False positive rate",
+            "True positive rate",
+            polyline(roc, "#2196F3"),
+            true,
+        ),
+        plot(
+            &format!("Precision-Recall (AUPRC {:.3})", curves.auprc),
+            "Recall",
+            "Precision",
+            polyline(pr, "#F44336"),
+            false,
+        ),
+        reliability
+    )
+}
+
+fn detector_table_html(results: &BenchmarkResults) -> String {
+    let mut detectors: Vec<_> = results.detector_metrics.iter().collect();
+    detectors.sort_by(|a, b| a.0.cmp(b.0));
+    let rows: String = detectors
+        .iter()
+        .map(|(name, m)| {
+            format!(
+                "        <tr><td>{}</td><td>{:.1}%</td><td>{:.1}%</td><td>{:.2}</td><td>{}</td><td>{:.0}</td></tr>\n",
+                escape(name),
+                m.precision * 100.0,
+                m.recall * 100.0,
+                m.f1_score,
+                m.trigger_count,
+                m.time_to_detect.median_ms
+            )
+        })
+        .collect();
+    format!(
+        r#"
+    <h2>Detectors</h2>
+    <table>
+        <tr><th>Detector</th><th>Precision</th><th>Recall</th><th>F1-Score</th><th>Fired</th><th>TTD p50 ms</th></tr>
+{}    </table>
+"#,
+        rows
+    )
+}
+
+/// Escape text for HTML element content and attribute values
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BenchmarkConfig, BenchmarkRunner};
+
+    #[test]
+    fn test_timeline_marks_window_and_detections() {
+        let config = BenchmarkConfig {
+            duration_secs: 40,
+            anomalies: vec![crate::AnomalySpec {
+                scenario: "error_spike".to_string(),
+                start_time_sec: 20,
+                duration_sec: 10,
+            }],
+            quiet: true,
+            ..Default::default()
+        };
+        let results = BenchmarkRunner::new().run(config).unwrap();
+        let timeline = results.timeline.as_ref().unwrap();
+
+        assert_eq!(timeline.buckets.len(), TIMELINE_BUCKETS);
+        let events: u64 = timeline.buckets.iter().map(|b| b.events).sum();
+        let scored = results.true_positives
+            + results.false_positives
+            + results.true_negatives
+            + results.false_negatives;
+        assert_eq!(events, scored);
+        let anomalous: Vec<usize> = (0..TIMELINE_BUCKETS)
+            .filter(|&i| timeline.buckets[i].anomaly_events > 0)
+            .collect();
+        let window = &timeline.windows[0];
+        let bucket_sec = timeline.bucket_ns as f64 / 1e9;
+        for i in anomalous {
+            let t = i as f64 * bucket_sec;
+            assert!(t + bucket_sec >= window.start_sec && t <= window.end_sec);
+        }
+        assert!(timeline.buckets.iter().any(|b| b.top_detector.is_some()));
+
+        let histogram = &results.latency_micros.histogram;
+        let latencies: u64 = histogram.iter().map(|b| b.count).sum();
+        assert!(latencies > 0);
+        assert!(
+            histogram
+                .windows(2)
+                .all(|w| w[1].upper_micros == w[0].upper_micros * 2)
+        );
+    }
+
+    #[test]
+    fn test_report_embeds_charts_and_escapes() {
+        let mut results = BenchmarkResults {
+            config: "a <b> & c".to_string(),
+            timeline: Some(Timeline {
+                start_ns: 0,
+                bucket_ns: 1_000_000_000,
+                buckets: vec![
+                    TimelineBucket {
+                        events: 10,
+                        mean_score: 0.2,
+                        max_score: 0.4,
+                        ..Default::default()
+                    },
+                    TimelineBucket {
+                        events: 10,
+                        anomaly_events: 8,
+                        detections: 6,
+                        mean_score: 0.8,
+                        max_score: 0.9,
+                        top_detector: Some("Spectral".to_string()),
+                    },
+                ],
+                windows: vec![TimelineWindow {
+                    anomaly_type: "Error Spike".to_string(),
+                    start_sec: 1.0,
+                    end_sec: 2.0,
+                    absence: false,
+                }],
+            }),
+            ..Default::default()
+        };
+        results.latency_micros.histogram = vec![
+            LatencyBucket {
+                upper_micros: 1,
+                count: 3,
+            },
+            LatencyBucket {
+                upper_micros: 2,
+                count: 1,
+            },
+        ];
+
+        let html = html_report(&results);
+        assert!(html.contains("a &lt;b&gt; &amp; c"));
+        assert!(!html.contains("a <b>"));
+        assert!(html.contains("<h2>Timeline</h2>"));
+        assert!(html.contains("6 of 10 events flagged (8 anomalous), mostly Spectral"));
+        assert!(html.contains("Error Spike (1.0s - 2.0s)"));
+        assert!(html.contains("<h2>Latency Distribution</h2>"));
+        assert!(html.contains("3 events (75.0%)"));
+        // No scripts or external assets
+        assert!(!html.contains("<script") && !html.contains("http"));
+    }
+}
@@ -0,0 +1,216 @@
+//! Label Noise and Contamination Sensitivity
+//!
+//! Benchmark labels are perfect; production ones are not. Reviewers mislabel
+//! events, and the data a profile warms up on already contains incidents
+//! nobody labeled. `run_robustness` re-runs a scenario with the feedback loop
+//! on and
+//!
+//! - a share of feedback labels flipped (`FeedbackLoopConfig::label_noise`)
+//! - a share of the warmup period seeded with unlabeled anomalies
+//!   (`BenchmarkConfig::contamination`)
+//!
+//! and reports each run's accuracy against the clean run, with the F1 lost
+//! per unit of noise as a least-squares slope over the levels.
+
+use crate::feedback_loop::FeedbackLoopConfig;
+use crate::{BenchmarkConfig, BenchmarkResults, BenchmarkRunner};
+use serde::{Deserialize, Serialize};
+
+/// Accuracy at one noise level
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RobustnessPoint {
+    pub label_noise: f64,
+    pub contamination: f64,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1_score: f64,
+    /// F1 minus the clean run's F1
+    pub f1_delta: f64,
+    /// Feedback labels flipped by the noise model
+    pub labels_flipped: u64,
+}
+
+impl RobustnessPoint {
+    fn from_results(config: &BenchmarkConfig, r: &BenchmarkResults, clean_f1: f64) -> Self {
+        Self {
+            label_noise: config.feedback.label_noise,
+            contamination: config.contamination,
+            precision: r.precision,
+            recall: r.recall,
+            f1_score: r.f1_score,
+            f1_delta: r.f1_score - clean_f1,
+            labels_flipped: r.feedback.as_ref().map_or(0, |f| f.labels_flipped),
+        }
+    }
+}
+
+/// Full robustness output
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RobustnessResults {
+    pub config: String,
+    pub feedback_delay_ms: u64,
+    /// Feedback on, no label noise, no contamination
+    pub clean: RobustnessPoint,
+    pub label_noise: Vec<RobustnessPoint>,
+    pub contamination: Vec<RobustnessPoint>,
+    /// Change in F1 per unit of label noise (negative when noise hurts)
+    pub label_noise_sensitivity: f64,
+    /// Change in F1 per unit of contamination
+    pub contamination_sensitivity: f64,
+}
+
+/// Run `base` clean, then once per label-noise and per contamination level,
+/// with `feedback` labels after `feedback.delay_ms`
+pub fn run_robustness(
+    base: &BenchmarkConfig,
+    feedback: &FeedbackLoopConfig,
+    noise_levels: &[f64],
+    contamination_levels: &[f64],
+) -> Result<RobustnessResults, String> {
+    let run = |label_noise: f64, contamination: f64, clean_f1: f64| {
+        let mut config = base.clone();
+        config.feedback = FeedbackLoopConfig {
+            enabled: true,
+            label_noise,
+            ..feedback.clone()
+        };
+        config.contamination = contamination;
+        let results = BenchmarkRunner::new().run(config.clone())?;
+        Ok::<_, String>(RobustnessPoint::from_results(&config, &results, clean_f1))
+    };
+
+    let mut clean = run(0.0, 0.0, 0.0)?;
+    clean.f1_delta = 0.0;
+    let clean_f1 = clean.f1_score;
+    let label_noise: Vec<RobustnessPoint> = noise_levels
+        .iter()
+        .filter(|&&level| level > 0.0)
+        .map(|&level| run(level, 0.0, clean_f1))
+        .collect::<Result<_, String>>()?;
+    let contamination: Vec<RobustnessPoint> = contamination_levels
+        .iter()
+        .filter(|&&level| level > 0.0)
+        .map(|&level| run(0.0, level, clean_f1))
+        .collect::<Result<_, String>>()?;
+
+    let sensitivity = |points: &[RobustnessPoint], level: fn(&RobustnessPoint) -> f64| {
+        let mut xy = vec![(0.0, clean_f1)];
+        xy.extend(points.iter().map(|p| (level(p), p.f1_score)));
+        slope(&xy)
+    };
+    Ok(RobustnessResults {
+        config: base.name.clone(),
+        feedback_delay_ms: feedback.delay_ms,
+        label_noise_sensitivity: sensitivity(&label_noise, |p| p.label_noise),
+        contamination_sensitivity: sensitivity(&contamination, |p| p.contamination),
+        clean,
+        label_noise,
+        contamination,
+    })
+}
+
+/// Least-squares slope of y over x (0 with fewer than two distinct x)
+fn slope(points: &[(f64, f64)]) -> f64 {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for &(x, y) in points {
+        cov += (x - mean_x) * (y - mean_y);
+        var += (x - mean_x) * (x - mean_x);
+    }
+    if var > 0.0 { cov / var } else { 0.0 }
+}
+
+/// Print accuracy per noise level against the clean run as a table
+pub fn print_robustness(results: &RobustnessResults) {
+    println!("\n╔══════════════════════════════════════════════════════════════╗");
+    println!("║             LABEL NOISE / CONTAMINATION SENSITIVITY          ║");
+    println!("╠══════════════════════════════════════════════════════════════╣");
+    println!("║ Config: {:52} ║", results.config);
+    println!(
+        "║ Feedback delay: {:>8}ms {:>33} ║",
+        results.feedback_delay_ms, ""
+    );
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    println!("║ Noise  | Contam. |   F1   |  ΔF1   |  Prec.  | Recall | Flip ║");
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    let rows = std::iter::once(&results.clean)
+        .chain(&results.label_noise)
+        .chain(&results.contamination);
+    for p in rows {
+        println!(
+            "║ {:>5.1}% | {:>6.1}% | {:>6.3} | {:>+6.3} | {:>6.1}% | {:>5.1}% | {:>4} ║",
+            p.label_noise * 100.0,
+            p.contamination * 100.0,
+            p.f1_score,
+            p.f1_delta,
+            p.precision * 100.0,
+            p.recall * 100.0,
+            p.labels_flipped.min(9999)
+        );
+    }
+    println!("╠──────────────────────────────────────────────────────────────╣");
+    println!(
+        "║ ΔF1 per unit label noise:   {:>+8.3} {:>23} ║",
+        results.label_noise_sensitivity, ""
+    );
+    println!(
+        "║ ΔF1 per unit contamination: {:>+8.3} {:>23} ║",
+        results.contamination_sensitivity, ""
+    );
+    println!("╚══════════════════════════════════════════════════════════════╝");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AnomalySpec;
+
+    #[test]
+    fn test_slope_fits_line() {
+        assert!((slope(&[(0.0, 0.9), (0.1, 0.8), (0.2, 0.7)]) + 1.0).abs() < 1e-9);
+        assert_eq!(slope(&[(0.0, 0.9)]), 0.0);
+    }
+
+    #[test]
+    fn test_contamination_is_detected_over_but_unlabeled() {
+        let config = BenchmarkConfig {
+            name: "contaminated".to_string(),
+            duration_secs: 60,
+            anomalies: vec![AnomalySpec {
+                scenario: "error_spike".to_string(),
+                start_time_sec: 40,
+                duration_sec: 10,
+            }],
+            contamination: 0.25,
+            quiet: true,
+            ..Default::default()
+        };
+        let mut runner = BenchmarkRunner::new();
+        let results = runner.run(config.clone()).unwrap();
+
+        // 10s of the 40s warmup carry anomalies, but only the scheduled
+        // window is ground truth
+        assert_eq!(runner.ground_truth.len(), 1);
+        assert_eq!(runner.ground_truth[0].start_time_ns, 40_000_000_000);
+        let window_start = runner.ground_truth[0].start_time_ns;
+        let warmup_anomalies: u64 = runner
+            .metrics
+            .slots
+            .iter()
+            .filter(|(start, _)| start + runner.metrics.slots.width_ns() <= window_start)
+            .map(|(_, slot)| slot.tally.anomaly_events)
+            .sum();
+        assert_eq!(warmup_anomalies, 0);
+
+        let clean = BenchmarkRunner::new()
+            .run(BenchmarkConfig {
+                contamination: 0.0,
+                ..config
+            })
+            .unwrap();
+        assert!(results.total_events > clean.total_events);
+        assert_eq!(results.total_anomaly_events, clean.total_anomaly_events);
+    }
+}
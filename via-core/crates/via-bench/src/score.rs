@@ -0,0 +1,246 @@
+//! Composite Benchmark Score and Leaderboard
+//!
+//! Collapses accuracy, time-to-detect, per-event latency, throughput and memory
+//! into one 0-100 number so releases can be tracked with a single headline
+//! figure. Each component is normalized to `[0, 1]` against a target, then
+//! combined by configurable weights.
+
+use crate::BenchmarkResults;
+use serde::{Deserialize, Serialize};
+
+/// Weights and targets for the composite score
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ScoreWeights {
+    pub f1: f64,
+    pub precision: f64,
+    pub recall: f64,
+    pub latency: f64,
+    pub throughput: f64,
+    pub memory: f64,
+    pub time_to_detect: f64,
+    /// P99 per-event latency at or below this scores 1.0
+    pub latency_target_micros: f64,
+    /// Throughput at or above this scores 1.0
+    pub throughput_target_eps: f64,
+    /// Peak RSS at or below this scores 1.0
+    pub memory_budget_bytes: u64,
+    /// Mean time-to-detect at or below this scores 1.0
+    pub time_to_detect_target_ms: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            f1: 0.4,
+            precision: 0.0,
+            recall: 0.0,
+            latency: 0.15,
+            throughput: 0.15,
+            memory: 0.1,
+            time_to_detect: 0.2,
+            latency_target_micros: 50.0,
+            throughput_target_eps: 100_000.0,
+            memory_budget_bytes: 256 * 1024 * 1024,
+            time_to_detect_target_ms: 1_000.0,
+        }
+    }
+}
+
+/// Ratio of `target` to `value`, capped at 1.0 (lower values are better)
+fn at_most(value: f64, target: f64) -> f64 {
+    if value <= target || value <= 0.0 {
+        1.0
+    } else {
+        target / value
+    }
+}
+
+/// Ratio of `value` to `target`, capped at 1.0 (higher values are better)
+fn at_least(value: f64, target: f64) -> f64 {
+    if target <= 0.0 {
+        1.0
+    } else {
+        (value / target).clamp(0.0, 1.0)
+    }
+}
+
+/// Compute the weighted composite score (0-100) for a result.
+///
+/// Components that were not measured (e.g. peak RSS on non-Linux hosts) are
+/// dropped and the remaining weights renormalized.
+pub fn composite_score(results: &BenchmarkResults, weights: &ScoreWeights) -> f64 {
+    // Window-based F1 reflects the configured scoring mode when present
+    let f1 = results
+        .windowed
+        .as_ref()
+        .map(|w| w.f1_score)
+        .unwrap_or(results.f1_score);
+    let mut components = vec![
+        (weights.f1, f1),
+        (weights.precision, results.precision),
+        (weights.recall, results.recall),
+        (
+            weights.latency,
+            at_most(
+                results.latency_micros.p99_micros,
+                weights.latency_target_micros,
+            ),
+        ),
+        (
+            weights.throughput,
+            at_least(results.throughput_eps, weights.throughput_target_eps),
+        ),
+    ];
+    if results.peak_rss_bytes > 0 {
+        components.push((
+            weights.memory,
+            at_most(
+                results.peak_rss_bytes as f64,
+                weights.memory_budget_bytes as f64,
+            ),
+        ));
+    }
+
+    // Missed windows score zero; runs without anomalies drop the component
+    let ttd = &results.time_to_detect;
+    if ttd.windows > 0 {
+        let detected_share = ttd.detected as f64 / ttd.windows as f64;
+        let speed = if ttd.detected > 0 {
+            at_most(ttd.mean_ms, weights.time_to_detect_target_ms)
+        } else {
+            0.0
+        };
+        components.push((weights.time_to_detect, detected_share * speed));
+    }
+
+    let total_weight: f64 = components.iter().map(|(w, _)| w.max(0.0)).sum();
+    if total_weight <= 0.0 {
+        return 0.0;
+    }
+    let weighted: f64 = components.iter().map(|(w, s)| w.max(0.0) * s).sum();
+    100.0 * weighted / total_weight
+}
+
+// ============================================================================
+// Leaderboard
+// ============================================================================
+
+/// One ranked row of the leaderboard
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub source: String,
+    pub config: String,
+    pub composite_score: f64,
+    pub f1_score: f64,
+    pub p99_micros: f64,
+    pub throughput_eps: f64,
+    pub peak_rss_bytes: u64,
+}
+
+/// Rank results by composite score (highest first).
+///
+/// With `weights`, every result is rescored so files produced under different
+/// weight settings are compared on equal terms; otherwise stored scores are used.
+pub fn leaderboard(
+    results: &[(String, BenchmarkResults)],
+    weights: Option<&ScoreWeights>,
+) -> Vec<LeaderboardEntry> {
+    let mut entries: Vec<LeaderboardEntry> = results
+        .iter()
+        .map(|(source, r)| LeaderboardEntry {
+            rank: 0,
+            source: source.clone(),
+            config: r.config.clone(),
+            composite_score: weights
+                .map(|w| composite_score(r, w))
+                .unwrap_or(r.composite_score),
+            f1_score: r.f1_score,
+            p99_micros: r.latency_micros.p99_micros,
+            throughput_eps: r.throughput_eps,
+            peak_rss_bytes: r.peak_rss_bytes,
+        })
+        .collect();
+
+    entries.sort_by(|a, b| b.composite_score.total_cmp(&a.composite_score));
+    for (i, entry) in entries.iter_mut().enumerate() {
+        entry.rank = i + 1;
+    }
+    entries
+}
+
+/// Render the leaderboard as a markdown table
+pub fn leaderboard_markdown(entries: &[LeaderboardEntry]) -> String {
+    let mut md = String::new();
+    md.push_str("| Rank | Score | Config | Source | F1 | P99 (µs) | EPS | Peak RSS (MiB) |\n");
+    md.push_str("|-----:|------:|--------|--------|---:|---------:|----:|---------------:|\n");
+    for e in entries {
+        md.push_str(&format!(
+            "| {} | {:.1} | {} | {} | {:.3} | {:.1} | {:.0} | {:.1} |\n",
+            e.rank,
+            e.composite_score,
+            e.config,
+            e.source,
+            e.f1_score,
+            e.p99_micros,
+            e.throughput_eps,
+            e.peak_rss_bytes as f64 / (1024.0 * 1024.0)
+        ));
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LatencyMetrics;
+
+    fn result(name: &str, f1: f64, p99: f64, eps: f64) -> BenchmarkResults {
+        BenchmarkResults {
+            config: name.to_string(),
+            f1_score: f1,
+            latency_micros: LatencyMetrics {
+                p99_micros: p99,
+                ..Default::default()
+            },
+            throughput_eps: eps,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_perfect_run_scores_100() {
+        let weights = ScoreWeights::default();
+        let r = result("perfect", 1.0, 10.0, 1_000_000.0);
+        assert!((composite_score(&r, &weights) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_slow_run_is_penalized_proportionally() {
+        let weights = ScoreWeights {
+            f1: 0.0,
+            throughput: 0.0,
+            latency: 1.0,
+            ..Default::default()
+        };
+        // 4x over the latency target scores a quarter
+        let r = result("slow", 1.0, weights.latency_target_micros * 4.0, 0.0);
+        assert!((composite_score(&r, &weights) - 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_leaderboard_rescores_and_ranks() {
+        let files = vec![
+            ("a.json".to_string(), result("a", 0.2, 10.0, 100_000.0)),
+            ("b.json".to_string(), result("b", 0.9, 10.0, 100_000.0)),
+        ];
+
+        // Stored scores are zero, so only rescoring separates them
+        let ranked = leaderboard(&files, Some(&ScoreWeights::default()));
+        assert_eq!(ranked[0].config, "b");
+        assert_eq!(ranked[0].rank, 1);
+        assert_eq!(ranked[1].config, "a");
+        assert!(ranked[0].composite_score > ranked[1].composite_score);
+    }
+}
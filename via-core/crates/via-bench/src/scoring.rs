@@ -0,0 +1,525 @@
+//! Window-Based Scoring
+//!
+//! Per-event scoring counts every anomalous log a detector stays silent on as a
+//! false negative, which punishes detectors that (correctly) alert once per
+//! incident. This module scores against ground-truth *windows* instead:
+//!
+//! - **point-adjust**: if any detection lands in a window (plus tolerance), every
+//!   anomalous event of that window counts as detected
+//! - **window** (NAB-style): each window is a single positive; every detection
+//!   outside all windows is a false positive
+//!
+//! Absence windows (a service going silent) have no anomalous events to
+//! label, so they are matched purely by time: a detection on any event inside
+//! the window counts for it.
+//!
+//! `FirstDetections` and `WindowScorer` do the same one event at a time, for
+//! runs that score online; the slice functions wrap them.
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// How detections are matched against ground truth
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoringMode {
+    /// Per-event labels only (no windowed metrics)
+    #[default]
+    Event,
+    /// Point-adjusted event scoring
+    PointAdjust,
+    /// One positive per window
+    Window,
+}
+
+impl ScoringMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScoringMode::Event => "event",
+            ScoringMode::PointAdjust => "point_adjust",
+            ScoringMode::Window => "window",
+        }
+    }
+}
+
+impl FromStr for ScoringMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "event" => Ok(ScoringMode::Event),
+            "point_adjust" | "pa" => Ok(ScoringMode::PointAdjust),
+            "window" | "nab" => Ok(ScoringMode::Window),
+            other => Err(format!(
+                "unknown scoring mode '{other}' (expected event, point-adjust, window)"
+            )),
+        }
+    }
+}
+
+/// Scoring configuration
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ScoringConfig {
+    pub mode: ScoringMode,
+    /// Detections this long before a window starts still count for it
+    pub tolerance_before_ms: u64,
+    /// Detections this long after a window ends still count for it
+    pub tolerance_after_ms: u64,
+}
+
+/// Ground-truth anomaly window in simulation time
+#[derive(Clone, Copy, Debug)]
+pub struct Window {
+    pub start_ns: u64,
+    pub end_ns: u64,
+    /// Missing-data window, matched by time rather than by labeled events
+    pub absence: bool,
+}
+
+/// Outcome of one processed event
+#[derive(Clone, Copy, Debug)]
+pub struct EventOutcome {
+    pub timestamp_ns: u64,
+    /// Index into the window list for ground-truth anomalous events
+    pub window: Option<usize>,
+    pub detected: bool,
+}
+
+/// Confusion matrix counts
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ConfusionCounts {
+    pub true_positives: u64,
+    pub false_positives: u64,
+    pub true_negatives: u64,
+    pub false_negatives: u64,
+}
+
+impl ConfusionCounts {
+    pub fn metrics(&self) -> (f64, f64, f64) {
+        crate::calculate_metrics(
+            self.true_positives,
+            self.false_positives,
+            self.false_negatives,
+        )
+    }
+}
+
+/// Windowed metrics reported alongside the per-event confusion matrix
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct WindowedMetrics {
+    pub mode: ScoringMode,
+    pub tolerance_before_ms: u64,
+    pub tolerance_after_ms: u64,
+    pub windows_total: usize,
+    pub windows_detected: usize,
+    pub counts: ConfusionCounts,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1_score: f64,
+    /// Per-detector F1 under the same mode
+    pub detector_f1: std::collections::HashMap<String, f64>,
+}
+
+/// Time from anomaly window start to its first true-positive detection
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TimeToDetect {
+    pub windows: usize,
+    pub detected: usize,
+    pub mean_ms: f64,
+    pub median_ms: f64,
+    pub p95_ms: f64,
+}
+
+impl TimeToDetect {
+    /// Summarize detection delays (ns) for `windows` windows, of which
+    /// `delays_ns.len()` were detected
+    pub fn from_delays(windows: usize, mut delays_ns: Vec<u64>) -> Self {
+        if delays_ns.is_empty() {
+            return Self {
+                windows,
+                ..Default::default()
+            };
+        }
+        delays_ns.sort_unstable();
+        let len = delays_ns.len();
+        let to_ms = |ns: u64| ns as f64 / 1_000_000.0;
+        Self {
+            windows,
+            detected: len,
+            mean_ms: to_ms(delays_ns.iter().sum::<u64>()) / len as f64,
+            median_ms: to_ms(delays_ns[len / 2]),
+            p95_ms: to_ms(delays_ns[(len * 95 / 100).min(len - 1)]),
+        }
+    }
+}
+
+/// Delay (ns) from each window's start to its first detected anomalous event
+/// (any detection inside an absence window), `None` for windows that were
+/// never detected
+pub fn first_detection_delays(windows: &[Window], events: &[EventOutcome]) -> Vec<Option<u64>> {
+    let mut first = FirstDetections::default();
+    for (index, window) in windows.iter().enumerate() {
+        first.set_window(index, *window);
+    }
+    for event in events {
+        first.observe(event);
+    }
+    first.delays()
+}
+
+/// `first_detection_delays` fed one event at a time
+///
+/// Windows may be added while events arrive, but a window must be set before
+/// any event it could match.
+#[derive(Clone, Debug, Default)]
+pub struct FirstDetections {
+    windows: Vec<Window>,
+    first: Vec<Option<u64>>,
+}
+
+impl FirstDetections {
+    /// Add window `index` (the next one) or update it
+    pub fn set_window(&mut self, index: usize, window: Window) {
+        if index < self.windows.len() {
+            self.windows[index] = window;
+        } else {
+            self.windows.push(window);
+            self.first.push(None);
+        }
+    }
+
+    pub fn observe(&mut self, event: &EventOutcome) {
+        if !event.detected {
+            return;
+        }
+        let ts = event.timestamp_ns;
+        let absent = self
+            .windows
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| w.absence && ts >= w.start_ns && ts <= w.end_ns)
+            .map(|(i, _)| i);
+        for w in event.window.into_iter().chain(absent) {
+            if self.first[w].is_none_or(|f| ts < f) {
+                self.first[w] = Some(ts);
+            }
+        }
+    }
+
+    /// Delay per window, in window order
+    pub fn delays(&self) -> Vec<Option<u64>> {
+        self.first
+            .iter()
+            .zip(&self.windows)
+            .map(|(ts, w)| ts.map(|ts| ts.saturating_sub(w.start_ns)))
+            .collect()
+    }
+}
+
+/// Score events against windows.
+///
+/// Returns the confusion counts and the number of windows with at least one
+/// detection inside their tolerance range.
+pub fn score_windows(
+    config: &ScoringConfig,
+    windows: &[Window],
+    events: &[EventOutcome],
+) -> (ConfusionCounts, usize) {
+    let mut scorer = WindowScorer::new(config);
+    for (index, window) in windows.iter().enumerate() {
+        scorer.set_window(index, window);
+    }
+    for event in events {
+        scorer.observe(event);
+    }
+    scorer.finish()
+}
+
+/// `score_windows` fed one event at a time
+///
+/// Windows may be added while events arrive, but a window must be set before
+/// any event inside its tolerance range.
+#[derive(Clone, Debug)]
+pub struct WindowScorer {
+    mode: ScoringMode,
+    before_ns: u64,
+    after_ns: u64,
+    /// Tolerance range per window
+    ranges: Vec<(u64, u64)>,
+    detected: Vec<bool>,
+    /// Anomalous events per window, credited in full under point-adjust
+    anomalous: Vec<u64>,
+    counts: ConfusionCounts,
+}
+
+impl WindowScorer {
+    pub fn new(config: &ScoringConfig) -> Self {
+        Self {
+            mode: config.mode,
+            before_ns: config.tolerance_before_ms * 1_000_000,
+            after_ns: config.tolerance_after_ms * 1_000_000,
+            ranges: Vec::new(),
+            detected: Vec::new(),
+            anomalous: Vec::new(),
+            counts: ConfusionCounts::default(),
+        }
+    }
+
+    /// Add window `index` (the next one) or update it
+    pub fn set_window(&mut self, index: usize, window: &Window) {
+        let range = (
+            window.start_ns.saturating_sub(self.before_ns),
+            window.end_ns.saturating_add(self.after_ns),
+        );
+        if index < self.ranges.len() {
+            self.ranges[index] = range;
+        } else {
+            self.ranges.push(range);
+            self.detected.push(false);
+            self.anomalous.push(0);
+        }
+    }
+
+    pub fn observe(&mut self, event: &EventOutcome) {
+        let ts = event.timestamp_ns;
+        let mut in_any_range = false;
+        for (i, &(lo, hi)) in self.ranges.iter().enumerate() {
+            if ts >= lo && ts <= hi {
+                in_any_range = true;
+                self.detected[i] |= event.detected;
+            }
+        }
+
+        let counts = &mut self.counts;
+        match event.window {
+            Some(w) => {
+                self.detected[w] |= event.detected;
+                match self.mode {
+                    ScoringMode::PointAdjust => self.anomalous[w] += 1,
+                    ScoringMode::Event if event.detected => counts.true_positives += 1,
+                    ScoringMode::Event => counts.false_negatives += 1,
+                    // Window-mode positives are counted per window
+                    ScoringMode::Window => {}
+                }
+            }
+            None => {
+                if !event.detected {
+                    counts.true_negatives += 1;
+                } else if !in_any_range {
+                    counts.false_positives += 1;
+                }
+                // Detections on normal events inside a tolerant window are
+                // attributed to that window and neither rewarded nor penalized
+            }
+        }
+    }
+
+    /// Confusion counts and the number of detected windows
+    pub fn finish(&self) -> (ConfusionCounts, usize) {
+        let mut counts = self.counts;
+        let detected_count = self.detected.iter().filter(|d| **d).count();
+        match self.mode {
+            ScoringMode::Window => {
+                counts.true_positives = detected_count as u64;
+                counts.false_negatives = (self.ranges.len() - detected_count) as u64;
+            }
+            ScoringMode::PointAdjust => {
+                for (&detected, &anomalous) in self.detected.iter().zip(&self.anomalous) {
+                    if detected {
+                        counts.true_positives += anomalous;
+                    } else {
+                        counts.false_negatives += anomalous;
+                    }
+                }
+            }
+            ScoringMode::Event => {}
+        }
+        (counts, detected_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEC: u64 = 1_000_000_000;
+
+    fn event(ts_sec: u64, window: Option<usize>, detected: bool) -> EventOutcome {
+        EventOutcome {
+            timestamp_ns: ts_sec * SEC,
+            window,
+            detected,
+        }
+    }
+
+    fn one_window() -> Vec<Window> {
+        vec![Window {
+            start_ns: 10 * SEC,
+            end_ns: 20 * SEC,
+            absence: false,
+        }]
+    }
+
+    fn config(mode: ScoringMode, before_ms: u64, after_ms: u64) -> ScoringConfig {
+        ScoringConfig {
+            mode,
+            tolerance_before_ms: before_ms,
+            tolerance_after_ms: after_ms,
+        }
+    }
+
+    #[test]
+    fn test_point_adjust_credits_whole_window_for_single_alert() {
+        // Detector fires once out of five anomalous events
+        let events = vec![
+            event(5, None, false),
+            event(11, Some(0), true),
+            event(12, Some(0), false),
+            event(13, Some(0), false),
+            event(14, Some(0), false),
+            event(15, Some(0), false),
+            event(30, None, false),
+        ];
+
+        let (event_counts, _) =
+            score_windows(&config(ScoringMode::Event, 0, 0), &one_window(), &events);
+        assert_eq!(event_counts.true_positives, 1);
+        assert_eq!(event_counts.false_negatives, 4);
+
+        let (pa, detected) = score_windows(
+            &config(ScoringMode::PointAdjust, 0, 0),
+            &one_window(),
+            &events,
+        );
+        assert_eq!(detected, 1);
+        assert_eq!(pa.true_positives, 5);
+        assert_eq!(pa.false_negatives, 0);
+        assert_eq!(pa.true_negatives, 2);
+        assert_eq!(pa.metrics().2, 1.0);
+    }
+
+    #[test]
+    fn test_late_detection_needs_after_tolerance() {
+        // Anomaly is missed in-window but caught 3s after it ends
+        let events = vec![
+            event(12, Some(0), false),
+            event(18, Some(0), false),
+            event(23, None, true),
+        ];
+
+        let (strict, detected) =
+            score_windows(&config(ScoringMode::Window, 0, 0), &one_window(), &events);
+        assert_eq!(detected, 0);
+        assert_eq!(strict.false_negatives, 1);
+        assert_eq!(strict.false_positives, 1);
+
+        let (tolerant, detected) = score_windows(
+            &config(ScoringMode::Window, 0, 5_000),
+            &one_window(),
+            &events,
+        );
+        assert_eq!(detected, 1);
+        assert_eq!(tolerant.true_positives, 1);
+        assert_eq!(tolerant.false_positives, 0);
+    }
+
+    #[test]
+    fn test_window_mode_counts_each_false_alarm() {
+        let events = vec![
+            event(1, None, true),
+            event(2, None, true),
+            event(12, Some(0), true),
+            event(13, Some(0), true),
+        ];
+        let (counts, _) = score_windows(&config(ScoringMode::Window, 0, 0), &one_window(), &events);
+        assert_eq!(counts.true_positives, 1);
+        assert_eq!(counts.false_positives, 2);
+        let (p, r, _) = counts.metrics();
+        assert!((p - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(r, 1.0);
+    }
+
+    #[test]
+    fn test_time_to_detect_uses_first_true_positive() {
+        let windows = vec![
+            Window {
+                start_ns: 10 * SEC,
+                end_ns: 20 * SEC,
+                absence: false,
+            },
+            Window {
+                start_ns: 40 * SEC,
+                end_ns: 50 * SEC,
+                absence: false,
+            },
+            Window {
+                start_ns: 60 * SEC,
+                end_ns: 70 * SEC,
+                absence: false,
+            },
+        ];
+        let events = vec![
+            // False alarm before the window must not count as detection
+            event(9, None, true),
+            event(14, Some(0), true),
+            event(12, Some(0), true),
+            event(41, Some(1), true),
+            event(65, Some(2), false),
+        ];
+
+        let delays = first_detection_delays(&windows, &events);
+        assert_eq!(delays, vec![Some(2 * SEC), Some(SEC), None]);
+
+        let ttd = TimeToDetect::from_delays(3, delays.into_iter().flatten().collect());
+        assert_eq!(ttd.windows, 3);
+        assert_eq!(ttd.detected, 2);
+        assert_eq!(ttd.mean_ms, 1500.0);
+        assert_eq!(ttd.median_ms, 2000.0);
+        assert_eq!(ttd.p95_ms, 2000.0);
+    }
+
+    #[test]
+    fn test_absence_window_is_matched_by_time() {
+        // A service goes silent from 10s to 20s: there are no anomalous events
+        // to label, only normal traffic from other services
+        let windows = vec![Window {
+            start_ns: 10 * SEC,
+            end_ns: 20 * SEC,
+            absence: true,
+        }];
+        let events = vec![
+            event(5, None, false),
+            event(12, None, false),
+            event(16, None, true),
+            event(25, None, false),
+        ];
+
+        let delays = first_detection_delays(&windows, &events);
+        assert_eq!(delays, vec![Some(6 * SEC)]);
+
+        let (counts, detected) =
+            score_windows(&config(ScoringMode::Window, 0, 0), &windows, &events);
+        assert_eq!(detected, 1);
+        assert_eq!(counts.true_positives, 1);
+        assert_eq!(counts.false_positives, 0);
+
+        // A gap noticed only once traffic resumes needs after-tolerance
+        let late = vec![event(12, None, false), event(22, None, true)];
+        assert_eq!(first_detection_delays(&windows, &late), vec![None]);
+        let (counts, detected) =
+            score_windows(&config(ScoringMode::Window, 0, 5_000), &windows, &late);
+        assert_eq!(detected, 1);
+        assert_eq!(counts.false_positives, 0);
+    }
+
+    #[test]
+    fn test_mode_parsing() {
+        assert_eq!(
+            "point-adjust".parse::<ScoringMode>(),
+            Ok(ScoringMode::PointAdjust)
+        );
+        assert_eq!("NAB".parse::<ScoringMode>(), Ok(ScoringMode::Window));
+        assert!("bogus".parse::<ScoringMode>().is_err());
+    }
+}
@@ -0,0 +1,351 @@
+//! Long-Soak Runs
+//!
+//! A regular run reports once, when it ends. A soak run repeats a scenario's
+//! anomaly plan for hours (each anomaly recurring once per scenario
+//! duration) and every `snapshot_minutes` of simulated time reports the
+//! events scored since the last snapshot as a [`SoakSnapshot`]: rolling and
+//! cumulative precision / recall, latency percentiles and resident memory.
+//! Events are folded into the run's online counts as they are scored and
+//! never kept, so memory stays flat unless detection itself grows.
+//! Snapshots are written as JSON lines as they are taken, so an interrupted
+//! run keeps everything up to its last snapshot.
+
+use crate::{BenchmarkConfig, BenchmarkRunner, LatencyMetrics, calculate_metrics};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::time::Instant;
+use via_sim::{LogRecord, Recurrence, SimulationEngine};
+
+/// Soak length and snapshot interval
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct SoakConfig {
+    /// Simulated run length
+    pub hours: f64,
+    /// Simulated time between snapshots
+    pub snapshot_minutes: u64,
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self {
+            hours: 24.0,
+            snapshot_minutes: 10,
+        }
+    }
+}
+
+/// Detection over one snapshot interval
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SoakSnapshot {
+    pub index: u64,
+    /// Simulated time at the end of the interval
+    pub sim_elapsed_secs: f64,
+    pub wall_elapsed_secs: f64,
+    /// Events scored in the interval
+    pub events: u64,
+    /// Events scored per wall-clock second in the interval
+    pub throughput_eps: f64,
+    pub true_positives: u64,
+    pub false_positives: u64,
+    pub false_negatives: u64,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1_score: f64,
+    /// Over every interval so far
+    pub cumulative_precision: f64,
+    pub cumulative_recall: f64,
+    pub cumulative_f1: f64,
+    pub latency_micros: LatencyMetrics,
+    /// Resident set size at the end of the interval (0 where unavailable)
+    pub rss_bytes: u64,
+    /// `rss_bytes` minus that of the first snapshot
+    pub rss_growth_bytes: i64,
+    /// Live registry profiles in per-service mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profiles: Option<usize>,
+}
+
+/// Whole-run summary
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SoakSummary {
+    pub config: String,
+    pub soak: SoakConfig,
+    pub snapshots: u64,
+    pub total_events: u64,
+    /// Anomaly instances scheduled over the run
+    pub anomaly_instances: usize,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1_score: f64,
+    /// Worst interval P99
+    pub max_p99_micros: f64,
+    pub first_rss_bytes: u64,
+    pub last_rss_bytes: u64,
+    pub peak_rss_bytes: u64,
+    /// RSS growth from the first to the last snapshot per simulated hour
+    pub rss_growth_bytes_per_hour: f64,
+}
+
+/// Confusion counts since the run started
+#[derive(Default)]
+struct Totals {
+    events: u64,
+    tp: u64,
+    fp: u64,
+    fn_: u64,
+    max_p99: f64,
+}
+
+impl BenchmarkRunner {
+    /// Run `config`'s scenario for `soak.hours` with its anomalies recurring,
+    /// writing a JSON line per snapshot to `out`. Contamination and the
+    /// per-run reports (curves, breakdowns, trace) are not computed.
+    pub fn run_soak(
+        &mut self,
+        config: BenchmarkConfig,
+        soak: &SoakConfig,
+        out: &mut dyn Write,
+    ) -> std::io::Result<SoakSummary> {
+        let batch_mode = self
+            .configure(&config)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        let quiet = config.quiet;
+        let duration_ns = (soak.hours.max(0.0) * 3600.0 * 1e9) as u64;
+        let snapshot_ns = soak.snapshot_minutes.max(1) * 60 * 1_000_000_000;
+        if !quiet {
+            println!("╔══════════════════════════════════════════════════════════════╗");
+            println!("║           VIA Benchmark Suite - Soak Mode                    ║");
+            println!("╠══════════════════════════════════════════════════════════════╣");
+            println!("║ Config: {:52} ║", config.name);
+            println!(
+                "║ Length: {:>6.1}h | Snapshot every {:>4} min {:>18} ║",
+                soak.hours, soak.snapshot_minutes, ""
+            );
+            println!("║ Mode: {:54} ║", batch_mode);
+            println!("╚══════════════════════════════════════════════════════════════╝");
+        }
+
+        let mut engine = SimulationEngine::new_deterministic(config.simulation_seed);
+        engine.set_rate_scale(config.rate_scale);
+        engine.start(&config.base_scenario);
+        engine.set_emit_context(config.exogenous_context);
+        engine.set_delivery(config.delivery.clone());
+
+        // The scenario's anomaly plan repeats once per scenario duration
+        let every = Recurrence::Every {
+            period_ns: config.duration_ns().max(1),
+        };
+        let mut anomaly_instances = 0;
+        for anomaly in &config.anomalies {
+            anomaly_instances += engine
+                .schedule_recurring(
+                    &anomaly.scenario,
+                    &every,
+                    anomaly.start_time_sec.max(config.warmup_secs) * 1_000_000_000,
+                    anomaly.duration_sec * 1_000_000_000,
+                    duration_ns,
+                )
+                .len();
+        }
+
+        let tick_ns = config.tick_ms.max(1) * 1_000_000;
+        let total_ticks = duration_ns / tick_ns;
+        let start = Instant::now();
+        let mut pending_logs: Vec<(LogRecord, u64)> = Vec::new();
+        let mut totals = Totals::default();
+        let mut snapshots = 0u64;
+        let mut last_wall = 0.0;
+        let mut first_rss = None;
+        let mut rss = 0;
+
+        for tick in 0..total_ticks {
+            let batch = engine.tick(tick_ns);
+            self.ingest(&batch, config.batch_size, &mut pending_logs);
+
+            let elapsed_ns = (tick + 1) * tick_ns;
+            let last = tick + 1 == total_ticks;
+            if !elapsed_ns.is_multiple_of(snapshot_ns) && !last {
+                continue;
+            }
+            if last && !pending_logs.is_empty() {
+                self.process_batch(&pending_logs);
+                pending_logs.clear();
+            }
+
+            let wall = start.elapsed().as_secs_f64();
+            let mut snapshot =
+                self.take_snapshot(snapshots, elapsed_ns, wall - last_wall, &mut totals);
+            last_wall = wall;
+            rss = crate::proc_status_bytes("VmRSS:");
+            snapshot.wall_elapsed_secs = wall;
+            snapshot.rss_bytes = rss;
+            snapshot.rss_growth_bytes = rss as i64 - *first_rss.get_or_insert(rss) as i64;
+
+            serde_json::to_writer(&mut *out, &snapshot)?;
+            writeln!(out)?;
+            out.flush()?;
+            if !quiet {
+                println!(
+                    "  [{:>6.2}h] {:>9} events | P {:>5.1}% R {:>5.1}% | P99 {:>7.1} µs | RSS {:>7.1} MiB",
+                    snapshot.sim_elapsed_secs / 3600.0,
+                    snapshot.events,
+                    snapshot.precision * 100.0,
+                    snapshot.recall * 100.0,
+                    snapshot.latency_micros.p99_micros,
+                    snapshot.rss_bytes as f64 / (1024.0 * 1024.0)
+                );
+            }
+            snapshots += 1;
+        }
+
+        let (precision, recall, f1_score) = calculate_metrics(totals.tp, totals.fp, totals.fn_);
+        let first_rss = first_rss.unwrap_or(0);
+        let hours = (duration_ns as f64 / 3.6e12).max(f64::MIN_POSITIVE);
+        Ok(SoakSummary {
+            config: config.name.clone(),
+            soak: soak.clone(),
+            snapshots,
+            total_events: totals.events,
+            anomaly_instances,
+            precision,
+            recall,
+            f1_score,
+            max_p99_micros: totals.max_p99,
+            first_rss_bytes: first_rss,
+            last_rss_bytes: rss,
+            peak_rss_bytes: crate::peak_rss_bytes(),
+            rss_growth_bytes_per_hour: (rss as f64 - first_rss as f64) / hours,
+        })
+    }
+
+    /// Report the counts since the last snapshot and restart the latency
+    /// sketch; the caller fills in wall time and memory
+    fn take_snapshot(
+        &mut self,
+        index: u64,
+        elapsed_ns: u64,
+        interval_wall_secs: f64,
+        totals: &mut Totals,
+    ) -> SoakSnapshot {
+        let overall = self.metrics.overall;
+        let events = overall.events - totals.events;
+        let tp = overall.counts.true_positives - totals.tp;
+        let fp = overall.counts.false_positives - totals.fp;
+        let fn_ = overall.counts.false_negatives - totals.fn_;
+        let latency_micros = LatencyMetrics {
+            histogram: Vec::new(),
+            ..std::mem::take(&mut self.metrics.latency).metrics()
+        };
+
+        totals.events = overall.events;
+        totals.tp = overall.counts.true_positives;
+        totals.fp = overall.counts.false_positives;
+        totals.fn_ = overall.counts.false_negatives;
+        totals.max_p99 = totals.max_p99.max(latency_micros.p99_micros);
+        let (precision, recall, f1_score) = calculate_metrics(tp, fp, fn_);
+        let (cumulative_precision, cumulative_recall, cumulative_f1) =
+            calculate_metrics(totals.tp, totals.fp, totals.fn_);
+
+        SoakSnapshot {
+            index,
+            sim_elapsed_secs: elapsed_ns as f64 / 1e9,
+            wall_elapsed_secs: 0.0,
+            events,
+            throughput_eps: events as f64 / interval_wall_secs.max(f64::MIN_POSITIVE),
+            true_positives: tp,
+            false_positives: fp,
+            false_negatives: fn_,
+            precision,
+            recall,
+            f1_score,
+            cumulative_precision,
+            cumulative_recall,
+            cumulative_f1,
+            latency_micros,
+            rss_bytes: 0,
+            rss_growth_bytes: 0,
+            profiles: self.registry.as_ref().map(|r| r.len()),
+        }
+    }
+}
+
+/// Print the run summary as a table
+pub fn print_soak_summary(summary: &SoakSummary) {
+    let mib = |bytes: f64| bytes / (1024.0 * 1024.0);
+    println!("\n╔══════════════════════════════════════════════════════════════╗");
+    println!("║                       SOAK SUMMARY                           ║");
+    println!("╠══════════════════════════════════════════════════════════════╣");
+    println!("║ Config: {:52} ║", summary.config);
+    println!(
+        "║ Snapshots: {:>6} | Events: {:>12} | Anomalies: {:>6} ║",
+        summary.snapshots, summary.total_events, summary.anomaly_instances
+    );
+    println!(
+        "║ Precision: {:>5.1}% | Recall: {:>5.1}% | F1: {:>5.3} {:>13} ║",
+        summary.precision * 100.0,
+        summary.recall * 100.0,
+        summary.f1_score,
+        ""
+    );
+    println!(
+        "║ Worst interval P99: {:>10.1} µs {:>26} ║",
+        summary.max_p99_micros, ""
+    );
+    println!(
+        "║ RSS: {:>8.1} -> {:>8.1} MiB ({:>+8.2} MiB/h) {:>13} ║",
+        mib(summary.first_rss_bytes as f64),
+        mib(summary.last_rss_bytes as f64),
+        mib(summary.rss_growth_bytes_per_hour),
+        ""
+    );
+    println!("╚══════════════════════════════════════════════════════════════╝");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AnomalySpec;
+
+    #[test]
+    fn test_soak_snapshots_without_keeping_events() {
+        let config = BenchmarkConfig {
+            duration_secs: 60,
+            anomalies: vec![AnomalySpec {
+                scenario: "traffic_spike".to_string(),
+                start_time_sec: 20,
+                duration_sec: 15,
+            }],
+            quiet: true,
+            ..Default::default()
+        };
+        let soak = SoakConfig {
+            hours: 0.05,
+            snapshot_minutes: 1,
+        };
+        let mut runner = BenchmarkRunner::new();
+        let mut stream = Vec::new();
+        let summary = runner.run_soak(config, &soak, &mut stream).unwrap();
+
+        assert_eq!(summary.snapshots, 3);
+        assert_eq!(summary.anomaly_instances, 3);
+        assert!(runner.spill.is_none());
+        assert_eq!(runner.metrics.overall.events, summary.total_events);
+
+        let snapshots: Vec<SoakSnapshot> = String::from_utf8(stream)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(snapshots[2].sim_elapsed_secs, 180.0);
+        let events: u64 = snapshots.iter().map(|s| s.events).sum();
+        assert_eq!(events, summary.total_events);
+        // Every interval has an anomaly instance to find
+        assert!(
+            snapshots
+                .iter()
+                .all(|s| s.true_positives + s.false_negatives > 0)
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! Benchmark Suite and Sweep Spec Files
+//!
+//! TOML inputs for `run-all --suite` (a list of [`BenchmarkConfig`]s) and
+//! `rate-sweep --spec` (a scenario profile plus [`RateSweepConfig`]).
+//! `via-bench init` writes commented examples of both.
+//!
+//! `run --config` takes a single [`BenchmarkConfig`] as JSON, YAML or TOML,
+//! picked by extension; `via-bench init-config` writes an example.
+
+use crate::BenchmarkConfig;
+use crate::rate_sweep::RateSweepConfig;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Commented example benchmark suite
+pub const SUITE_TEMPLATE: &str = include_str!("../templates/suite.toml");
+pub const SUITE_FILE: &str = "bench-suite.toml";
+/// Commented example rate sweep spec
+pub const SWEEP_TEMPLATE: &str = include_str!("../templates/sweep.toml");
+pub const SWEEP_FILE: &str = "sweep.toml";
+/// Commented example single benchmark config
+pub const CONFIG_TEMPLATE: &str = include_str!("../templates/config.yaml");
+pub const CONFIG_FILE: &str = "bench-config.yaml";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BenchmarkSuite {
+    pub benchmarks: Vec<BenchmarkConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SweepSpec {
+    /// Scenario profile (see `pipeline::scenario_by_name`)
+    pub scenario: String,
+    #[serde(default)]
+    pub sweep: RateSweepConfig,
+}
+
+impl BenchmarkSuite {
+    pub fn load(path: &str) -> Result<Self, String> {
+        parse(&read(path)?).map_err(|e| format!("{}: invalid suite: {}", path, e))
+    }
+}
+
+impl SweepSpec {
+    pub fn load(path: &str) -> Result<Self, String> {
+        parse(&read(path)?).map_err(|e| format!("{}: invalid sweep spec: {}", path, e))
+    }
+}
+
+/// Format of a single benchmark config, by file extension
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &str) -> Result<Self, String> {
+        match Path::new(path).extension().and_then(|e| e.to_str()) {
+            Some("json") => Ok(Self::Json),
+            Some("yaml" | "yml") => Ok(Self::Yaml),
+            Some("toml") => Ok(Self::Toml),
+            _ => Err(format!("{}: expected a .json, .yaml or .toml config", path)),
+        }
+    }
+}
+
+/// Load one benchmark config from JSON, YAML or TOML
+pub fn load_config(path: &str) -> Result<BenchmarkConfig, String> {
+    let format = ConfigFormat::from_path(path)?;
+    let content = read(path)?;
+    let config = match format {
+        ConfigFormat::Json => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        ConfigFormat::Yaml => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+        ConfigFormat::Toml => parse(&content).map_err(|e| e.to_string()),
+    };
+    config.map_err(|e| format!("{}: invalid config: {}", path, e))
+}
+
+/// Example config for `path`: the commented template as YAML, its values
+/// with every other field at its default as JSON or TOML
+pub fn config_template(path: &str) -> Result<String, String> {
+    let config =
+        || serde_yaml::from_str::<BenchmarkConfig>(CONFIG_TEMPLATE).map_err(|e| e.to_string());
+    match ConfigFormat::from_path(path)? {
+        ConfigFormat::Yaml => Ok(CONFIG_TEMPLATE.to_string()),
+        ConfigFormat::Json => serde_json::to_string_pretty(&config()?)
+            .map(|json| json + "\n")
+            .map_err(|e| e.to_string()),
+        ConfigFormat::Toml => toml::to_string_pretty(&config()?).map_err(|e| e.to_string()),
+    }
+}
+
+fn read(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(content: &str) -> Result<T, toml::de::Error> {
+    toml::from_str(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use via_core::signal::DetectorId;
+    use via_sim::create_scenario;
+
+    #[test]
+    fn test_templates_parse() {
+        let suite: BenchmarkSuite = parse(SUITE_TEMPLATE).unwrap();
+        assert_eq!(suite.benchmarks.len(), 2);
+        for config in &suite.benchmarks {
+            assert!(create_scenario(&config.base_scenario).is_some());
+            assert!(!config.anomalies.is_empty());
+            for anomaly in &config.anomalies {
+                assert!(create_scenario(&anomaly.scenario).is_some());
+                assert!(
+                    anomaly.start_time_sec + anomaly.duration_sec
+                        <= config.duration_ns() / 1_000_000_000
+                );
+            }
+        }
+        // Omitted fields take their defaults
+        assert_eq!(suite.benchmarks[0].simulation_seed, 42);
+        assert!(suite.benchmarks[1].per_service);
+        assert!(!suite.benchmarks[0].ingestion_delay.is_enabled());
+        // Detectors are named by key or variant
+        let named = SUITE_TEMPLATE.replace(
+            "disabled_detectors = []",
+            "disabled_detectors = [\"spectral\", \"RRCF\"]",
+        );
+        let suite: BenchmarkSuite = parse(&named).unwrap();
+        assert_eq!(
+            suite.benchmarks[0].disabled_detectors,
+            [DetectorId::Spectral, DetectorId::RRCF]
+        );
+
+        let spec: SweepSpec = parse(SWEEP_TEMPLATE).unwrap();
+        assert_eq!(spec.scenario, "quick");
+        assert_eq!(spec.sweep.max_steps, 8);
+        let minimal: SweepSpec = parse("scenario = \"mixed\"").unwrap();
+        assert_eq!(minimal.sweep.latency_budget_micros, 100.0);
+    }
+
+    #[test]
+    fn test_config_loads_in_every_format() {
+        let dir = std::env::temp_dir().join(format!("via-bench-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["config.yaml", "config.json", "config.toml"] {
+            let path = dir.join(name).to_string_lossy().into_owned();
+            std::fs::write(&path, config_template(&path).unwrap()).unwrap();
+            let config = load_config(&path).unwrap();
+            assert_eq!(config.base_scenario, "checkout_funnel", "{name}");
+            assert_eq!(config.anomalies.len(), 2, "{name}");
+            assert_eq!(config.batch_size, 0, "{name}");
+            assert!(create_scenario(&config.anomalies[1].scenario).is_some());
+        }
+
+        let path = dir.join("batched.yaml").to_string_lossy().into_owned();
+        let batched = CONFIG_TEMPLATE.replace("batch_size: 0", "batch_size: 500");
+        std::fs::write(&path, batched).unwrap();
+        assert_eq!(load_config(&path).unwrap().batch_size, 500);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(config_template("config.ini").is_err());
+        assert!(
+            load_config("missing.json")
+                .unwrap_err()
+                .contains("missing.json")
+        );
+    }
+}
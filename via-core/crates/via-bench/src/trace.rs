@@ -0,0 +1,429 @@
+//! Per-event Detection Trace
+//!
+//! Results keep aggregate counts only. With `BenchmarkConfig::trace` set (or
+//! `--trace <file>`), every scored event is also written as one row, so false
+//! positives can be sliced by detector, entity and time in DuckDB, Polars or
+//! a spreadsheet. Warmup events are unscored and left out.
+//!
+//! Runs do not keep their events in memory: a trace is written after the run
+//! from a [`Spill`], a temporary JSON-lines file the events are appended to
+//! as they are scored (`BenchmarkConfig::spill_events`, implied by `trace`).
+//!
+//! Files ending in `.parquet` are Parquet (feature `parquet`); anything else
+//! is CSV, compressed by extension (`.csv.gz`, `.csv.zst`). Columns:
+//!
+//! | Column              | Type    | Source                                   |
+//! |---------------------|---------|------------------------------------------|
+//! | `timestamp_ns`      | uint64  | log timestamp                            |
+//! | `service`           | utf8    | `service.name`                           |
+//! | `entity_hash`       | uint64  | hash of the entity key                   |
+//! | `value`             | float64 | detected value                           |
+//! | `severity`          | uint32  | log severity number                      |
+//! | `ingest_delay_ns`   | uint64  | simulated collection lag                 |
+//! | `ensemble_score`    | float64 | combined score                           |
+//! | `detected`          | bool    | raised as an anomaly                     |
+//! | `suppressed`        | bool    | anomalous but inside the cooldown        |
+//! | `ground_truth`      | bool    | labeled anomalous                        |
+//! | `anomaly_id`        | utf8?   | ground-truth window                      |
+//! | `primary_detector`  | utf8    | top attribution                          |
+//! | `<detector>_score`  | float32 | per detector, e.g. `spectral_score`      |
+//! | `<detector>_fired`  | bool    | per detector, e.g. `spectral_fired`      |
+
+use crate::DetectionEvent;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use via_core::signal::DetectorId;
+use via_sim::compression;
+
+/// `path` with `name` before its extension, for one trace per config:
+/// `trace.csv` + "Mixed Workload" -> `trace.mixed-workload.csv`
+pub fn path_for(path: &str, name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let file = Path::new(path)
+        .file_name()
+        .map_or(path, |f| f.to_str().unwrap_or(path));
+    let dir = &path[..path.len() - file.len()];
+    match file.split_once('.') {
+        Some((stem, ext)) => format!("{dir}{stem}.{slug}.{ext}"),
+        None => format!("{dir}{file}.{slug}"),
+    }
+}
+
+/// Rows per Parquet row group
+#[cfg(feature = "parquet")]
+const PARQUET_BATCH_ROWS: usize = 65_536;
+
+/// Spills created by this process, naming their files apart
+static SPILLS: AtomicU64 = AtomicU64::new(0);
+
+/// Scored events of a run appended to a temporary file, which is removed on
+/// drop
+pub(crate) struct Spill {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl Spill {
+    pub(crate) fn create() -> std::io::Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "via-bench-events-{}-{}.jsonl",
+            std::process::id(),
+            SPILLS.fetch_add(1, Ordering::Relaxed)
+        ));
+        let writer = BufWriter::new(File::create(&path)?);
+        Ok(Self { path, writer })
+    }
+
+    pub(crate) fn push(&mut self, event: &DetectionEvent) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, event)?;
+        self.writer.write_all(b"\n")
+    }
+
+    pub(crate) fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Events pushed before the last flush, in order
+    pub(crate) fn events(&self) -> impl Iterator<Item = DetectionEvent> + use<> {
+        let lines = File::open(&self.path)
+            .map(|file| BufReader::new(file).lines())
+            .into_iter()
+            .flatten();
+        lines.filter_map(|line| serde_json::from_str(&line.ok()?).ok())
+    }
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Write `events` to `path`, returning the row count
+pub(crate) fn write(
+    path: &str,
+    events: impl Iterator<Item = DetectionEvent>,
+    service_names: &HashMap<u64, String>,
+) -> Result<u64, String> {
+    if path.ends_with(".parquet") {
+        return write_parquet(path, events, service_names);
+    }
+    write_csv(path, events, service_names)
+        .map_err(|e| format!("Failed to write trace {}: {}", path, e))
+}
+
+fn service<'a>(event: &DetectionEvent, service_names: &'a HashMap<u64, String>) -> &'a str {
+    service_names
+        .get(&event.service_hash)
+        .map_or("", String::as_str)
+}
+
+fn write_csv(
+    path: &str,
+    events: impl Iterator<Item = DetectionEvent>,
+    service_names: &HashMap<u64, String>,
+) -> std::io::Result<u64> {
+    let mut out = compression::create(Path::new(path))?;
+    let mut header = String::from(
+        "timestamp_ns,service,entity_hash,value,severity,ingest_delay_ns,ensemble_score,\
+         detected,suppressed,ground_truth,anomaly_id,primary_detector",
+    );
+    for id in DetectorId::ALL {
+        let column = id.key();
+        header.push_str(&format!(",{column}_score,{column}_fired"));
+    }
+    writeln!(out, "{header}")?;
+
+    let mut rows = 0;
+    for event in events {
+        let event = &event;
+        let s = &event.signal;
+        write!(
+            out,
+            "{},{},{},{},{},{},{:.6},{},{},{},{},{}",
+            s.timestamp,
+            csv_field(service(event, service_names)),
+            s.entity_hash,
+            s.raw_value,
+            event.severity,
+            event.ingest_delay_ns,
+            s.ensemble_score,
+            event.detected_as_anomaly,
+            s.suppressed_by_cooldown,
+            event.is_ground_truth_anomaly,
+            csv_field(event.anomaly_id.as_deref().unwrap_or("")),
+            csv_field(s.primary_detector_name())
+        )?;
+        for score in &s.detector_scores {
+            write!(out, ",{:.6},{}", score.score, score.fired)?;
+        }
+        writeln!(out)?;
+        rows += 1;
+    }
+    out.finish()?;
+    Ok(rows)
+}
+
+/// Quote a CSV field holding a separator, quote or line break
+fn csv_field(text: &str) -> std::borrow::Cow<'_, str> {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\"")).into()
+    } else {
+        text.into()
+    }
+}
+
+#[cfg(not(feature = "parquet"))]
+fn write_parquet(
+    path: &str,
+    _events: impl Iterator<Item = DetectionEvent>,
+    _service_names: &HashMap<u64, String>,
+) -> Result<u64, String> {
+    Err(format!(
+        "Cannot write {}: Parquet traces need feature `parquet`",
+        path
+    ))
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet(
+    path: &str,
+    mut events: impl Iterator<Item = DetectionEvent>,
+    service_names: &HashMap<u64, String>,
+) -> Result<u64, String> {
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+
+    let fail = |e: &dyn std::fmt::Display| format!("Failed to write trace {}: {}", path, e);
+    let schema = parquet_schema();
+    let file = File::create(path).map_err(|e| fail(&e))?;
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer =
+        ArrowWriter::try_new(file, schema.clone(), Some(props)).map_err(|e| fail(&e))?;
+    let mut rows = 0;
+    loop {
+        let chunk: Vec<DetectionEvent> = events.by_ref().take(PARQUET_BATCH_ROWS).collect();
+        if chunk.is_empty() {
+            break;
+        }
+        let batch = record_batch(schema.clone(), &chunk, service_names).map_err(|e| fail(&e))?;
+        writer.write(&batch).map_err(|e| fail(&e))?;
+        rows += chunk.len() as u64;
+    }
+    writer.close().map_err(|e| fail(&e))?;
+    Ok(rows)
+}
+
+#[cfg(feature = "parquet")]
+fn parquet_schema() -> std::sync::Arc<arrow_schema::Schema> {
+    use arrow_schema::{DataType, Field, Schema};
+
+    let mut fields = vec![
+        Field::new("timestamp_ns", DataType::UInt64, false),
+        Field::new("service", DataType::Utf8, false),
+        Field::new("entity_hash", DataType::UInt64, false),
+        Field::new("value", DataType::Float64, false),
+        Field::new("severity", DataType::UInt32, false),
+        Field::new("ingest_delay_ns", DataType::UInt64, false),
+        Field::new("ensemble_score", DataType::Float64, false),
+        Field::new("detected", DataType::Boolean, false),
+        Field::new("suppressed", DataType::Boolean, false),
+        Field::new("ground_truth", DataType::Boolean, false),
+        Field::new("anomaly_id", DataType::Utf8, true),
+        Field::new("primary_detector", DataType::Utf8, false),
+    ];
+    for id in DetectorId::ALL {
+        let name = id.key();
+        fields.push(Field::new(
+            format!("{name}_score"),
+            DataType::Float32,
+            false,
+        ));
+        fields.push(Field::new(
+            format!("{name}_fired"),
+            DataType::Boolean,
+            false,
+        ));
+    }
+    std::sync::Arc::new(Schema::new(fields))
+}
+
+#[cfg(feature = "parquet")]
+fn record_batch(
+    schema: std::sync::Arc<arrow_schema::Schema>,
+    events: &[DetectionEvent],
+    service_names: &HashMap<u64, String>,
+) -> Result<arrow_array::RecordBatch, arrow_schema::ArrowError> {
+    use arrow_array::{
+        ArrayRef, BooleanArray, Float32Array, Float64Array, RecordBatch, StringArray, UInt32Array,
+        UInt64Array,
+    };
+    use std::sync::Arc;
+
+    let column = |f: fn(&DetectionEvent) -> u64| -> ArrayRef {
+        Arc::new(events.iter().map(f).collect::<UInt64Array>())
+    };
+    let flag = |f: fn(&DetectionEvent) -> bool| -> ArrayRef {
+        Arc::new(events.iter().map(|e| Some(f(e))).collect::<BooleanArray>())
+    };
+    let mut columns: Vec<ArrayRef> = vec![
+        column(|e| e.signal.timestamp),
+        Arc::new(
+            events
+                .iter()
+                .map(|e| Some(service(e, service_names)))
+                .collect::<StringArray>(),
+        ),
+        column(|e| e.signal.entity_hash),
+        Arc::new(
+            events
+                .iter()
+                .map(|e| e.signal.raw_value)
+                .collect::<Float64Array>(),
+        ),
+        Arc::new(events.iter().map(|e| e.severity).collect::<UInt32Array>()),
+        column(|e| e.ingest_delay_ns),
+        Arc::new(
+            events
+                .iter()
+                .map(|e| e.signal.ensemble_score)
+                .collect::<Float64Array>(),
+        ),
+        flag(|e| e.detected_as_anomaly),
+        flag(|e| e.signal.suppressed_by_cooldown),
+        flag(|e| e.is_ground_truth_anomaly),
+        Arc::new(
+            events
+                .iter()
+                .map(|e| e.anomaly_id.as_deref())
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            events
+                .iter()
+                .map(|e| Some(e.signal.primary_detector_name()))
+                .collect::<StringArray>(),
+        ),
+    ];
+    for i in 0..DetectorId::ALL.len() {
+        columns.push(Arc::new(
+            events
+                .iter()
+                .map(|e| e.signal.detector_scores[i].score)
+                .collect::<Float32Array>(),
+        ));
+        columns.push(Arc::new(
+            events
+                .iter()
+                .map(|e| Some(e.signal.detector_scores[i].fired))
+                .collect::<BooleanArray>(),
+        ));
+    }
+    RecordBatch::try_new(schema, columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AnomalySpec, BenchmarkConfig, BenchmarkRunner};
+    use via_core::signal::NUM_DETECTORS;
+
+    #[test]
+    fn test_path_for_inserts_config_name() {
+        assert_eq!(
+            path_for("out/trace.csv.gz", "Mixed Workload"),
+            "out/trace.mixed-workload.csv.gz"
+        );
+        assert_eq!(path_for("trace", "Quick"), "trace.quick");
+    }
+
+    #[test]
+    fn test_csv_trace_has_row_per_scored_event() {
+        let path = std::env::temp_dir().join(format!("via-trace-{}.csv", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let config = BenchmarkConfig {
+            duration_secs: 20,
+            anomalies: vec![AnomalySpec {
+                scenario: "error_spike".to_string(),
+                start_time_sec: 10,
+                duration_sec: 5,
+            }],
+            trace: Some(path.clone()),
+            quiet: true,
+            ..Default::default()
+        };
+        let results = BenchmarkRunner::new().run(config).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let mut lines = content.lines();
+        let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(header.len(), 12 + 2 * NUM_DETECTORS);
+        assert!(header.contains(&"spectral_fired"));
+        let rows: Vec<Vec<&str>> = lines.map(|l| l.split(',').collect()).collect();
+        let scored = results.true_positives
+            + results.false_positives
+            + results.true_negatives
+            + results.false_negatives;
+        assert_eq!(rows.len() as u64, scored);
+        assert!(rows.iter().all(|r| r.len() == header.len()));
+
+        let column = |name: &str| header.iter().position(|h| *h == name).unwrap();
+        let (truth, detected) = (column("ground_truth"), column("detected"));
+        let count = |f: &dyn Fn(&Vec<&str>) -> bool| rows.iter().filter(|r| f(r)).count() as u64;
+        assert_eq!(count(&|r| r[truth] == "true"), results.total_anomaly_events);
+        assert_eq!(
+            count(&|r| r[truth] == "false" && r[detected] == "true"),
+            results.false_positives
+        );
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_trace_reads_back() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let path = std::env::temp_dir().join(format!("via-trace-{}.parquet", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let mut runner = BenchmarkRunner::new();
+        let results = runner
+            .run(BenchmarkConfig {
+                duration_secs: 10,
+                spill_events: true,
+                quiet: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let rows = runner.write_trace(&path).unwrap();
+
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows() as u64, rows);
+        assert_eq!(rows, results.true_negatives + results.false_positives);
+        assert_eq!(
+            metadata.schema_descr().num_columns(),
+            12 + 2 * NUM_DETECTORS
+        );
+    }
+}
@@ -0,0 +1,676 @@
+//! Live Terminal Dashboard (feature `tui`)
+//!
+//! `via-bench watch` runs a scenario through detection and redraws a
+//! terminal dashboard every `refresh_ms` of wall time: events per second and
+//! their recent history, the anomalies active in the simulation, each
+//! detector's fire rate and F1, and the ensemble's precision / recall / F1
+//! over the last `window_secs` of simulated time next to the whole run's.
+//! Runs can be paced to a multiple of real time for demos, and stretched to
+//! `hours` with the anomaly plan recurring as in a soak run.
+//!
+//! [`BenchmarkRunner::run_watch`] drives the simulation and hands a
+//! [`WatchState`] to a callback at every refresh; [`watch`] is that loop
+//! drawing to the terminal. Events are scored once their ground truth is
+//! known, so the accuracy panels trail the event counts slightly.
+
+use crate::scoring::ConfusionCounts;
+use crate::{BenchmarkConfig, BenchmarkResults, BenchmarkRunner, calculate_metrics};
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Gauge, Paragraph, Row, Sparkline, Table};
+use std::collections::VecDeque;
+use std::io;
+use std::time::{Duration, Instant};
+use via_core::signal::{DetectorId, NUM_DETECTORS};
+use via_sim::{EntityKey, LogRecord, Recurrence, SimulationEngine};
+
+/// Refreshes of events-per-second history kept for the sparkline
+const EPS_HISTORY: usize = 512;
+
+/// Pacing and refresh of a watched run
+#[derive(Clone, Debug)]
+pub struct WatchConfig {
+    /// Simulated seconds per wall second; as fast as detection runs when unset
+    pub speed: Option<f64>,
+    /// Wall time between dashboard refreshes
+    pub refresh_ms: u64,
+    /// Simulated time the rolling metrics cover
+    pub window_secs: u64,
+    /// Run length with the anomaly plan recurring once per scenario
+    /// duration, as in a soak run; the scenario's own duration when unset
+    pub hours: Option<f64>,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            speed: None,
+            refresh_ms: 250,
+            window_secs: 60,
+            hours: None,
+        }
+    }
+}
+
+/// Run totals at one refresh; two of them bound an interval
+#[derive(Clone, Copy, Debug, Default)]
+struct Totals {
+    sim_secs: f64,
+    wall_secs: f64,
+    /// Events ingested
+    events: u64,
+    /// Events scored against ground truth
+    scored: u64,
+    counts: ConfusionCounts,
+    detectors: [ConfusionCounts; NUM_DETECTORS],
+}
+
+impl Totals {
+    fn since(&self, earlier: &Totals) -> Totals {
+        let minus = |a: &ConfusionCounts, b: &ConfusionCounts| ConfusionCounts {
+            true_positives: a.true_positives - b.true_positives,
+            false_positives: a.false_positives - b.false_positives,
+            true_negatives: a.true_negatives - b.true_negatives,
+            false_negatives: a.false_negatives - b.false_negatives,
+        };
+        Totals {
+            sim_secs: self.sim_secs - earlier.sim_secs,
+            wall_secs: self.wall_secs - earlier.wall_secs,
+            events: self.events - earlier.events,
+            scored: self.scored - earlier.scored,
+            counts: minus(&self.counts, &earlier.counts),
+            detectors: std::array::from_fn(|d| minus(&self.detectors[d], &earlier.detectors[d])),
+        }
+    }
+
+    /// Fired events over scored events
+    fn fire_rate(&self, counts: &ConfusionCounts) -> f64 {
+        (counts.true_positives + counts.false_positives) as f64 / self.scored.max(1) as f64
+    }
+}
+
+/// One detector's row on the dashboard
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DetectorActivity {
+    pub detector: DetectorId,
+    /// Share of the window's scored events the detector fired on
+    pub fire_rate: f64,
+    /// F1 over the window
+    pub f1_score: f64,
+}
+
+/// What the dashboard shows, updated at every refresh
+#[derive(Clone, Debug)]
+pub struct WatchState {
+    pub config: String,
+    pub speed: Option<f64>,
+    pub total_sim_secs: f64,
+    pub window_secs: f64,
+    /// Anomalies active at the last tick, with the simulated second each
+    /// became active
+    pub active_anomalies: Vec<(String, f64)>,
+    /// Events per wall second over each recent refresh, oldest first
+    pub eps_history: VecDeque<u64>,
+    /// Over the whole run
+    pub p99_micros: f64,
+    /// Set on the last refresh of a run that was not stopped early
+    pub finished: bool,
+    /// Totals at the refreshes spanning the rolling window, oldest first;
+    /// never empty
+    snapshots: VecDeque<Totals>,
+}
+
+impl WatchState {
+    fn new(config: &BenchmarkConfig, watch: &WatchConfig, total_sim_secs: f64) -> Self {
+        Self {
+            config: config.name.clone(),
+            speed: watch.speed,
+            total_sim_secs,
+            window_secs: watch.window_secs.max(1) as f64,
+            active_anomalies: Vec::new(),
+            eps_history: VecDeque::new(),
+            p99_micros: 0.0,
+            finished: false,
+            snapshots: VecDeque::from([Totals::default()]),
+        }
+    }
+
+    fn latest(&self) -> &Totals {
+        self.snapshots.back().expect("snapshots are never empty")
+    }
+
+    /// Add the totals at a refresh, dropping snapshots no longer needed to
+    /// span the window
+    fn record(&mut self, totals: Totals) {
+        let interval = totals.since(self.latest());
+        // No simulated time passed (the run's closing refresh): nothing to rate
+        if interval.sim_secs > 0.0 && interval.wall_secs > 0.0 {
+            if self.eps_history.len() == EPS_HISTORY {
+                self.eps_history.pop_front();
+            }
+            self.eps_history
+                .push_back((interval.events as f64 / interval.wall_secs) as u64);
+        }
+        self.snapshots.push_back(totals);
+        let window_start = totals.sim_secs - self.window_secs;
+        while self.snapshots.len() > 2 && self.snapshots[1].sim_secs <= window_start {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Track which anomalies `active_scenarios` (a tick's
+    /// `metadata.active_scenarios`) shows active at `sim_secs`
+    fn set_active(&mut self, active_scenarios: &[String], sim_secs: f64) {
+        let active: Vec<&str> = active_scenarios
+            .iter()
+            .filter_map(|name| name.strip_suffix("(anomaly)"))
+            .collect();
+        self.active_anomalies
+            .retain(|(name, _)| active.contains(&name.as_str()));
+        for name in active {
+            if !self.active_anomalies.iter().any(|(n, _)| n == name) {
+                self.active_anomalies.push((name.to_string(), sim_secs));
+            }
+        }
+    }
+
+    fn window(&self) -> Totals {
+        self.latest().since(&self.snapshots[0])
+    }
+
+    pub fn sim_secs(&self) -> f64 {
+        self.latest().sim_secs
+    }
+
+    pub fn wall_secs(&self) -> f64 {
+        self.latest().wall_secs
+    }
+
+    /// Events ingested so far
+    pub fn events(&self) -> u64 {
+        self.latest().events
+    }
+
+    /// Events per wall second over the last refresh
+    pub fn eps(&self) -> u64 {
+        self.eps_history.back().copied().unwrap_or(0)
+    }
+
+    /// Ensemble precision, recall and F1 over the window
+    pub fn rolling(&self) -> (f64, f64, f64) {
+        self.window().counts.metrics()
+    }
+
+    /// Ensemble precision, recall and F1 over the whole run
+    pub fn cumulative(&self) -> (f64, f64, f64) {
+        self.latest().counts.metrics()
+    }
+
+    /// Share of the window's scored events the ensemble fired on
+    pub fn fire_rate(&self) -> f64 {
+        let window = self.window();
+        window.fire_rate(&window.counts)
+    }
+
+    /// Fire rate and F1 of every detector over the window
+    pub fn detectors(&self) -> Vec<DetectorActivity> {
+        let window = self.window();
+        DetectorId::ALL
+            .iter()
+            .zip(&window.detectors)
+            .map(|(&detector, counts)| DetectorActivity {
+                detector,
+                fire_rate: window.fire_rate(counts),
+                f1_score: calculate_metrics(
+                    counts.true_positives,
+                    counts.false_positives,
+                    counts.false_negatives,
+                )
+                .2,
+            })
+            .collect()
+    }
+}
+
+impl BenchmarkRunner {
+    /// Run `config` paced by `watch`, calling `refresh` with the dashboard
+    /// state every `refresh_ms` of wall time and once more at the end.
+    /// Stops early when `refresh` returns false; the results then cover
+    /// the events run until that point. Contamination, deploys and the
+    /// ingestion delay line are not applied.
+    pub fn run_watch(
+        &mut self,
+        config: BenchmarkConfig,
+        watch: &WatchConfig,
+        refresh: &mut dyn FnMut(&WatchState) -> bool,
+    ) -> Result<BenchmarkResults, String> {
+        self.configure(&config)?;
+        let scenario_ns = config.duration_ns().max(1);
+        let duration_ns = watch
+            .hours
+            .map_or(scenario_ns, |hours| (hours.max(0.0) * 3.6e12) as u64);
+
+        let mut engine = SimulationEngine::new_deterministic(config.simulation_seed);
+        engine.set_rate_scale(config.rate_scale);
+        engine.start(&config.base_scenario);
+        engine.set_emit_context(config.exogenous_context);
+        engine.set_delivery(config.delivery.clone());
+        if config.extraction.entity_key != EntityKey::default() {
+            engine.set_entity_key(Some(config.extraction.entity_key.clone()));
+        }
+        // Within one scenario duration each anomaly runs once
+        let every = Recurrence::Every {
+            period_ns: scenario_ns,
+        };
+        for anomaly in &config.anomalies {
+            engine.schedule_recurring(
+                &anomaly.scenario,
+                &every,
+                anomaly.start_time_sec.max(config.warmup_secs) * 1_000_000_000,
+                anomaly.duration_sec * 1_000_000_000,
+                duration_ns,
+            );
+        }
+
+        let tick_ns = config.tick_ms.max(1) * 1_000_000;
+        let total_ticks = duration_ns / tick_ns;
+        let refresh_every = Duration::from_millis(watch.refresh_ms.max(1));
+        let mut state = WatchState::new(&config, watch, (total_ticks * tick_ns) as f64 / 1e9);
+        let start = Instant::now();
+        let mut next_refresh = start;
+        let mut pending_logs: Vec<(LogRecord, u64)> = Vec::new();
+        let mut total_events = 0u64;
+        let mut stopped = false;
+
+        for tick in 0..total_ticks {
+            let batch = engine.tick(tick_ns);
+            total_events += self.ingest(&batch, config.batch_size, &mut pending_logs);
+
+            let elapsed_ns = (tick + 1) * tick_ns;
+            state.set_active(&batch.metadata.active_scenarios, elapsed_ns as f64 / 1e9);
+            if let Some(speed) = watch.speed {
+                let due = Duration::from_secs_f64(elapsed_ns as f64 / 1e9 / speed);
+                if let Some(ahead) = due.checked_sub(start.elapsed()) {
+                    std::thread::sleep(ahead);
+                }
+            }
+            if Instant::now() >= next_refresh {
+                next_refresh = Instant::now() + refresh_every;
+                state.record(self.watch_totals(elapsed_ns, start, total_events));
+                state.p99_micros = self.metrics.latency.metrics().p99_micros;
+                if !refresh(&state) {
+                    stopped = true;
+                    break;
+                }
+            }
+        }
+        if !pending_logs.is_empty() {
+            self.process_batch(&pending_logs);
+        }
+
+        if !stopped {
+            state.record(self.watch_totals(total_ticks * tick_ns, start, total_events));
+            state.p99_micros = self.metrics.latency.metrics().p99_micros;
+            state.finished = true;
+            refresh(&state);
+        }
+        Ok(self.calculate_results(&config, total_events, start.elapsed()))
+    }
+
+    fn watch_totals(&self, elapsed_ns: u64, start: Instant, events: u64) -> Totals {
+        Totals {
+            sim_secs: elapsed_ns as f64 / 1e9,
+            wall_secs: start.elapsed().as_secs_f64(),
+            events,
+            scored: self.metrics.overall.events,
+            counts: self.metrics.overall.counts,
+            detectors: std::array::from_fn(|d| self.metrics.detectors[d].counts),
+        }
+    }
+}
+
+/// Run `config` under a live dashboard until it ends and `q` is pressed, or
+/// until `q` / Esc / Ctrl-C stops it early. The terminal is restored before
+/// this returns, so the results can be printed after.
+pub fn watch(config: BenchmarkConfig, watch: &WatchConfig) -> io::Result<BenchmarkResults> {
+    let mut terminal = ratatui::try_init()?;
+    let refresh_every = Duration::from_millis(watch.refresh_ms.max(1));
+    let mut failed = None;
+    let results = BenchmarkRunner::new().run_watch(config, watch, &mut |state| {
+        let shown = (|| {
+            terminal.draw(|frame| draw(frame, state))?;
+            if !state.finished {
+                return Ok(!quit_requested(Duration::ZERO)?);
+            }
+            // Keep the final frame up, redrawn on resize, until dismissed
+            while !quit_requested(refresh_every)? {
+                terminal.draw(|frame| draw(frame, state))?;
+            }
+            Ok(false)
+        })();
+        shown.unwrap_or_else(|e: io::Error| {
+            failed = Some(e);
+            false
+        })
+    });
+    ratatui::try_restore()?;
+    match failed {
+        Some(e) => Err(e),
+        None => results.map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e)),
+    }
+}
+
+/// Whether `q`, Esc or Ctrl-C was pressed within `timeout`
+fn quit_requested(timeout: Duration) -> io::Result<bool> {
+    if !event::poll(timeout)? {
+        return Ok(false);
+    }
+    while event::poll(Duration::ZERO)? {
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && (matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                || (key.code == KeyCode::Char('c')
+                    && key.modifiers.contains(KeyModifiers::CONTROL)))
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// `1h02m03s`, `2m05s` or `8.3s`
+fn clock(secs: f64) -> String {
+    let whole = secs as u64;
+    match whole {
+        0..60 => format!("{:.1}s", secs),
+        60..3600 => format!("{}m{:02}s", whole / 60, whole % 60),
+        _ => format!("{}h{:02}m{:02}s", whole / 3600, whole / 60 % 60, whole % 60),
+    }
+}
+
+fn f1_style(f1: f64) -> Style {
+    let color = if f1 >= 0.5 {
+        Color::Green
+    } else if f1 >= crate::coverage::COVERED_F1 {
+        Color::Yellow
+    } else {
+        Color::DarkGray
+    };
+    Style::new().fg(color)
+}
+
+/// Draw the dashboard for `state`
+pub fn draw(frame: &mut Frame, state: &WatchState) {
+    let [header, middle, bottom, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(8),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let pace = state
+        .speed
+        .map_or("unpaced".to_string(), |speed| format!("{}x", speed));
+    let progress = state.sim_secs() / state.total_sim_secs.max(f64::MIN_POSITIVE);
+    frame.render_widget(
+        Gauge::default()
+            .block(Block::bordered().title(format!(" via-bench watch | {} ", state.config)))
+            .gauge_style(Style::new().fg(Color::Cyan))
+            .ratio(progress.clamp(0.0, 1.0))
+            .label(format!(
+                "sim {} / {} | wall {} | {}",
+                clock(state.sim_secs()),
+                clock(state.total_sim_secs),
+                clock(state.wall_secs()),
+                pace
+            )),
+        header,
+    );
+
+    let [eps_area, accuracy_area] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(middle);
+    let width = eps_area.width.saturating_sub(2) as usize;
+    let eps: Vec<u64> = state
+        .eps_history
+        .iter()
+        .skip(state.eps_history.len().saturating_sub(width))
+        .copied()
+        .collect();
+    frame.render_widget(
+        Sparkline::default()
+            .block(Block::bordered().title(format!(" Events/s {} ", state.eps())))
+            .style(Style::new().fg(Color::Cyan))
+            .data(&eps),
+        eps_area,
+    );
+
+    let (p, r, f1) = state.rolling();
+    let (cp, cr, cf1) = state.cumulative();
+    let accuracy = vec![
+        Line::styled(
+            format!(
+                "Last {:>5}  P {:.3}  R {:.3}  F1 {:.3}",
+                clock(state.window_secs),
+                p,
+                r,
+                f1
+            ),
+            f1_style(f1).add_modifier(Modifier::BOLD),
+        ),
+        Line::raw(format!(
+            "Run        P {:.3}  R {:.3}  F1 {:.3}",
+            cp, cr, cf1
+        )),
+        Line::raw(""),
+        Line::raw(format!("Fire rate  {:.2}%", state.fire_rate() * 100.0)),
+        Line::raw(format!("Events     {}", state.events())),
+        Line::raw(format!("P99        {:.1} µs", state.p99_micros)),
+    ];
+    frame.render_widget(
+        Paragraph::new(accuracy).block(Block::bordered().title(" Accuracy ")),
+        accuracy_area,
+    );
+
+    let [anomalies_area, detectors_area] =
+        Layout::horizontal([Constraint::Length(36), Constraint::Min(0)]).areas(bottom);
+    let anomalies: Vec<Line> = if state.active_anomalies.is_empty() {
+        vec![Line::styled("none", Style::new().fg(Color::DarkGray))]
+    } else {
+        state
+            .active_anomalies
+            .iter()
+            .map(|(name, since)| {
+                Line::styled(
+                    format!("{:<22} {:>9}", name, clock(state.sim_secs() - since)),
+                    Style::new().fg(Color::Red),
+                )
+            })
+            .collect()
+    };
+    frame.render_widget(
+        Paragraph::new(anomalies).block(Block::bordered().title(" Active anomalies ")),
+        anomalies_area,
+    );
+
+    let rows = state.detectors().into_iter().map(|d| {
+        Row::new([
+            d.detector.key().to_string(),
+            format!("{:.2}%", d.fire_rate * 100.0),
+            format!("{:.3}", d.f1_score),
+        ])
+        .style(f1_style(d.f1_score))
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(14),
+                Constraint::Length(10),
+                Constraint::Length(8),
+            ],
+        )
+        .header(
+            Row::new(["Detector", "Fire rate", "F1"])
+                .style(Style::new().add_modifier(Modifier::BOLD)),
+        )
+        .block(Block::bordered().title(format!(" Detectors (last {}) ", clock(state.window_secs)))),
+        detectors_area,
+    );
+
+    let keys = if state.finished {
+        " Finished | q / Esc exit"
+    } else {
+        " q / Esc stop"
+    };
+    frame.render_widget(Line::styled(keys, Style::new().fg(Color::DarkGray)), footer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AnomalySpec;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn totals(sim_secs: f64, events: u64, tp: u64, fp: u64, fn_: u64) -> Totals {
+        let counts = ConfusionCounts {
+            true_positives: tp,
+            false_positives: fp,
+            true_negatives: events - tp - fp - fn_,
+            false_negatives: fn_,
+        };
+        let mut detectors = [ConfusionCounts::default(); NUM_DETECTORS];
+        detectors[DetectorId::Volume as usize] = counts;
+        Totals {
+            sim_secs,
+            wall_secs: sim_secs / 10.0,
+            events,
+            scored: events,
+            counts,
+            detectors,
+        }
+    }
+
+    #[test]
+    fn test_state_keeps_a_rolling_window() {
+        let watch = WatchConfig {
+            window_secs: 20,
+            ..Default::default()
+        };
+        let mut state = WatchState::new(&BenchmarkConfig::default(), &watch, 60.0);
+        // A perfect first 20s, then 20s where half the detections are wrong
+        state.record(totals(10.0, 1000, 50, 0, 0));
+        state.record(totals(20.0, 2000, 100, 0, 0));
+        state.record(totals(30.0, 3000, 150, 50, 0));
+        state.record(totals(40.0, 4000, 200, 100, 0));
+
+        assert_eq!(state.eps(), 1000);
+        assert_eq!(state.eps_history.len(), 4);
+        let (precision, recall, _) = state.rolling();
+        assert!((precision - 0.5).abs() < 1e-9);
+        assert!((recall - 1.0).abs() < 1e-9);
+        let (precision, _, _) = state.cumulative();
+        assert!((precision - 200.0 / 300.0).abs() < 1e-9);
+        assert!((state.fire_rate() - 0.1).abs() < 1e-9);
+        let volume = state.detectors()[DetectorId::Volume as usize];
+        assert!((volume.fire_rate - 0.1).abs() < 1e-9);
+        assert_eq!(state.detectors()[DetectorId::Burst as usize].fire_rate, 0.0);
+
+        state.set_active(
+            &["Normal Traffic".into(), "DDoS Attack(anomaly)".into()],
+            40.0,
+        );
+        state.set_active(
+            &[
+                "Normal Traffic".into(),
+                "DDoS Attack(anomaly)".into(),
+                "Error Rate Spike(anomaly)".into(),
+            ],
+            45.0,
+        );
+        assert_eq!(
+            state.active_anomalies,
+            [
+                ("DDoS Attack".to_string(), 40.0),
+                ("Error Rate Spike".to_string(), 45.0)
+            ]
+        );
+        state.set_active(
+            &["Normal Traffic".into(), "Error Rate Spike(anomaly)".into()],
+            50.0,
+        );
+        assert_eq!(
+            state.active_anomalies,
+            [("Error Rate Spike".to_string(), 45.0)]
+        );
+    }
+
+    #[test]
+    fn test_run_watch_refreshes_until_stopped() {
+        let config = BenchmarkConfig {
+            duration_secs: 40,
+            warmup_secs: 0,
+            anomalies: vec![AnomalySpec {
+                scenario: "traffic_spike".to_string(),
+                start_time_sec: 10,
+                duration_sec: 20,
+            }],
+            quiet: true,
+            ..Default::default()
+        };
+        let watch = WatchConfig {
+            refresh_ms: 1,
+            ..Default::default()
+        };
+
+        let mut refreshes = 0;
+        let mut saw_anomaly = false;
+        let mut last = None;
+        let results = BenchmarkRunner::new()
+            .run_watch(config.clone(), &watch, &mut |state| {
+                refreshes += 1;
+                saw_anomaly |= state
+                    .active_anomalies
+                    .iter()
+                    .any(|(n, _)| n == "Traffic Spike");
+                last = Some(state.clone());
+                true
+            })
+            .unwrap();
+        let last = last.unwrap();
+        assert!(refreshes > 1);
+        assert!(saw_anomaly);
+        assert!(last.finished);
+        assert_eq!(last.sim_secs(), 40.0);
+        assert_eq!(last.events(), results.total_events);
+        assert!(results.f1_score > 0.0);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| draw(frame, &last)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(screen.contains("Finished"));
+        assert!(screen.contains("changepoint"));
+
+        // Stopping early keeps the results of the events run so far
+        let mut calls = 0;
+        let partial = BenchmarkRunner::new()
+            .run_watch(config, &watch, &mut |state| {
+                calls += 1;
+                assert!(!state.finished);
+                calls < 3
+            })
+            .unwrap();
+        assert_eq!(calls, 3);
+        assert!(partial.total_events < results.total_events);
+    }
+}
@@ -0,0 +1,260 @@
+//! Per-event update cost of each detector and its algorithm
+//!
+//! Every benchmark warms its subject up on a latency-like stream first, so
+//! it measures the steady state the hot path spends its time in rather than
+//! warmup. Three groups:
+//! - `algo`: the raw algorithm modules (`algo::*`)
+//! - `detector`: the built-in detectors through the `Detector` trait, named
+//!   after the algorithm they wrap
+//! - `profile`: the full `process_with_hash` pipeline and its batch form,
+//!   for reference
+//!
+//! Run with `cargo bench -p via-core --bench detectors [-- <filter>]`.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use via_core::algo::ddsketch::DDSketch;
+use via_core::algo::drift_detector::EnsembleDriftDetector;
+use via_core::algo::ewma::EWMA;
+use via_core::algo::histogram::FadingHistogram;
+use via_core::algo::hll::HyperLogLog;
+use via_core::algo::holtwinters::HoltWinters;
+use via_core::algo::{
+    CountMinSketch, EnhancedCUSUM, MultiScaleDetector, SpectralResidual, Spot,
+    StreamingMatrixProfile, StreamingRRCF,
+};
+use via_core::engine::{
+    AnomalyProfile, BehavioralFingerprintDetectorV2, BurstDetectorV2, CardinalityDetectorV2,
+    ChangePointDetector, Detector, DiscordDetector, DistributionDetectorV2, DriftDetectorV2,
+    EvtDetector, ExogenousContext, FeatureVector, MultiScaleDetectorV2, QuantileDetector,
+    RRCFDetectorV2, SignalContext, SpectralDetector, VolumeDetectorV2,
+};
+
+/// Events fed before measuring
+const WARMUP_EVENTS: usize = 5_000;
+
+/// Latency-like stream: ~100ms values with jitter, ~20 events/s across a
+/// fixed pool of 50 users (same shape as `tests/zero_alloc.rs`)
+struct Traffic {
+    state: u64,
+    timestamp: u64,
+    sequence: u64,
+}
+
+impl Traffic {
+    fn new() -> Self {
+        Self {
+            state: 0x9E37_79B9_7F4A_7C15,
+            timestamp: 1_700_000_000_000_000_000,
+            sequence: 0,
+        }
+    }
+
+    fn next(&mut self) -> SignalContext {
+        // xorshift64
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        let value = 95.0 + (self.state % 1000) as f64 / 100.0;
+        self.timestamp += 49_500_000 + self.state % 1_000_000;
+        self.sequence += 1;
+        SignalContext {
+            timestamp: self.timestamp,
+            unique_id_hash: self.state % 50,
+            value,
+            features: FeatureVector::scalar(value),
+            is_warmup: false,
+            sequence: self.sequence,
+            exogenous: ExogenousContext::default(),
+        }
+    }
+}
+
+/// Time one `update` per event on a warmed-up `subject`
+///
+/// Events are generated as the benchmark runs so time keeps moving forward;
+/// generating one costs a few nanoseconds, the same for every benchmark.
+fn bench_updates<T>(
+    c: &mut Criterion,
+    name: &str,
+    mut subject: T,
+    mut update: impl FnMut(&mut T, &SignalContext),
+) {
+    let mut traffic = Traffic::new();
+    for _ in 0..WARMUP_EVENTS {
+        update(&mut subject, &traffic.next());
+    }
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            let ctx = traffic.next();
+            update(&mut subject, black_box(&ctx));
+        })
+    });
+}
+
+fn bench_algorithms(c: &mut Criterion) {
+    bench_updates(
+        c,
+        "algo/holtwinters",
+        HoltWinters::new(0.3, 0.1, 0.1, 24),
+        |d, ctx| {
+            black_box(d.update(ctx.value));
+        },
+    );
+    bench_updates(
+        c,
+        "algo/histogram",
+        FadingHistogram::new(50, 0.0, 10_000.0, 0.999),
+        |d, ctx| {
+            black_box(d.update(ctx.value));
+        },
+    );
+    bench_updates(c, "algo/hll", HyperLogLog::new(12), |d, ctx| {
+        d.add_hash(black_box(ctx.unique_id_hash));
+    });
+    bench_updates(c, "algo/cms", CountMinSketch::default_sketch(), |d, ctx| {
+        d.increment(black_box(ctx.unique_id_hash));
+    });
+    bench_updates(c, "algo/ddsketch", DDSketch::new(0.01, 2048), |d, ctx| {
+        d.add(black_box(ctx.value));
+    });
+    bench_updates(c, "algo/ewma", EWMA::new(100.0), |d, ctx| {
+        black_box(d.update(ctx.value));
+    });
+    bench_updates(
+        c,
+        "algo/enhanced_cusum",
+        EnhancedCUSUM::new(100.0, 0.5, 4.0),
+        |d, ctx| {
+            black_box(d.update(ctx.value));
+        },
+    );
+    bench_updates(
+        c,
+        "algo/spectral_residual",
+        SpectralResidual::new(64, 3.0),
+        |d, ctx| {
+            black_box(d.update(ctx.value));
+        },
+    );
+    // One transform per 16 values; time is per value
+    let mut batch = Vec::with_capacity(16);
+    bench_updates(
+        c,
+        "algo/spectral_residual_batch",
+        SpectralResidual::new(64, 3.0),
+        move |d, ctx| {
+            batch.push(ctx.value);
+            if batch.len() == 16 {
+                black_box(d.update_batch(&batch));
+                batch.clear();
+            }
+        },
+    );
+    bench_updates(
+        c,
+        "algo/multi_scale",
+        MultiScaleDetector::new(),
+        |d, ctx| {
+            black_box(d.update(ctx.value, ctx.timestamp));
+        },
+    );
+    bench_updates(
+        c,
+        "algo/rrcf",
+        StreamingRRCF::new(1, 40, 256, 4),
+        |d, ctx| {
+            black_box(d.update_univariate(ctx.value));
+        },
+    );
+    bench_updates(
+        c,
+        "algo/drift_detector",
+        EnsembleDriftDetector::new(),
+        |d, ctx| {
+            black_box(d.update(ctx.value));
+        },
+    );
+    bench_updates(
+        c,
+        "algo/matrix_profile",
+        StreamingMatrixProfile::new(16, 512),
+        |d, ctx| {
+            black_box(d.update(ctx.value));
+        },
+    );
+    bench_updates(
+        c,
+        "algo/spot",
+        Spot::new(1e-4, 0.98, 1_000, 1_000),
+        |d, ctx| {
+            black_box(d.update(ctx.value));
+        },
+    );
+}
+
+/// Time a built-in detector through the trait the profile calls
+fn bench_detector(c: &mut Criterion, algorithm: &str, detector: impl Detector) {
+    let name = format!("detector/{:?}/{}", detector.id(), algorithm);
+    bench_updates(c, &name, detector, |d, ctx| {
+        black_box(d.update(ctx));
+    });
+}
+
+fn bench_detectors(c: &mut Criterion) {
+    bench_detector(
+        c,
+        "holtwinters",
+        VolumeDetectorV2::new(0.3, 0.1, 0.1, 24).with_period_detection(),
+    );
+    bench_detector(
+        c,
+        "histogram",
+        DistributionDetectorV2::new(50, 0.0, 10_000.0, 0.999),
+    );
+    bench_detector(c, "hll", CardinalityDetectorV2::new());
+    bench_detector(c, "enhanced_cusum", BurstDetectorV2::new());
+    bench_detector(c, "spectral_residual", SpectralDetector::new());
+    bench_detector(c, "enhanced_cusum", ChangePointDetector::new());
+    bench_detector(c, "rrcf", RRCFDetectorV2::new());
+    bench_detector(c, "multi_scale", MultiScaleDetectorV2::new());
+    bench_detector(
+        c,
+        "behavioral_fingerprint",
+        BehavioralFingerprintDetectorV2::new(),
+    );
+    bench_detector(c, "drift_detector", DriftDetectorV2::new());
+    bench_detector(c, "ddsketch", QuantileDetector::new());
+    bench_detector(c, "spot", EvtDetector::new());
+    bench_detector(c, "matrix_profile", DiscordDetector::new());
+}
+
+fn bench_profile(c: &mut Criterion) {
+    bench_updates(
+        c,
+        "profile/process_with_hash",
+        AnomalyProfile::default(),
+        |profile, ctx| {
+            black_box(profile.process_with_hash(ctx.timestamp, ctx.unique_id_hash, ctx.value));
+        },
+    );
+    // One call per 64 events into a reused buffer; time is per event
+    let (mut events, mut signals) = (Vec::with_capacity(64), Vec::with_capacity(64));
+    bench_updates(
+        c,
+        "profile/process_batch",
+        AnomalyProfile::default(),
+        move |profile, ctx| {
+            events.push((ctx.timestamp, ctx.unique_id_hash, ctx.value));
+            if events.len() == 64 {
+                signals.clear();
+                profile.process_batch_into(&events, &mut signals, false);
+                black_box(&signals);
+                events.clear();
+            }
+        },
+    );
+}
+
+criterion_group!(benches, bench_algorithms, bench_detectors, bench_profile);
+criterion_main!(benches);
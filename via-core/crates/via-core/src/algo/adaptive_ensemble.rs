@@ -12,7 +12,6 @@
 //!
 //! Reference: Contextual Bandits for Online Learning
 
-use crate::signal::NUM_DETECTORS;
 use rand_distr::Distribution;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
@@ -344,6 +343,13 @@ impl ThompsonBandit {
             .collect()
     }
 
+    /// Add an arm with a uniform Beta(1, 1) prior
+    pub fn add_arm(&mut self) {
+        self.num_arms += 1;
+        self.alphas.push(1.0);
+        self.betas.push(1.0);
+    }
+
     /// Restore alpha/beta parameters from checkpointed state.
     pub fn set_params(&mut self, alphas: &[f64], betas: &[f64]) -> Result<(), &'static str> {
         if alphas.len() != self.num_arms || betas.len() != self.num_arms {
@@ -364,8 +370,8 @@ pub struct AdaptiveEnsemble {
     performance: Vec<DetectorPerformance>,
     /// Thompson sampling bandit for weight learning
     bandit: ThompsonBandit,
-    /// Current weights (updated periodically)
-    current_weights: [f64; NUM_DETECTORS],
+    /// Current weights (updated periodically), one per detector
+    current_weights: Vec<f64>,
     /// Whether to use Thompson sampling (exploration) or expected values (exploitation)
     exploration_rate: f64,
     /// Update counter
@@ -375,7 +381,6 @@ pub struct AdaptiveEnsemble {
     /// Detector names for reference
     detector_names: Vec<String>,
     /// P² estimator for O(1) percentile calculation
-    #[serde(skip)]
     p2_estimator: P2QuantileEstimator,
     /// Adaptive threshold
     adaptive_threshold: f64,
@@ -398,13 +403,9 @@ impl AdaptiveEnsemble {
     /// * `exploration_rate` - Probability of exploration vs exploitation (0.0-1.0)
     /// * `update_interval` - How often to update weights (in samples)
     pub fn new(detector_names: Vec<String>, exploration_rate: f64, update_interval: usize) -> Self {
-        let n = detector_names.len().clamp(1, NUM_DETECTORS);
+        let n = detector_names.len().max(1);
         let exploration = exploration_rate.clamp(0.0, 1.0);
-        let mut current_weights = [0.0; NUM_DETECTORS];
-        let uniform = 1.0 / n as f64;
-        for w in current_weights.iter_mut().take(n) {
-            *w = uniform;
-        }
+        let current_weights = vec![1.0 / n as f64; n];
 
         Self {
             num_detectors: n,
@@ -425,6 +426,25 @@ impl AdaptiveEnsemble {
         Self::new(detector_names, 0.1, 100)
     }
 
+    /// Add a detector after the existing ones; it starts from an uninformed
+    /// prior and takes an equal share of the weight, the others shrink
+    /// proportionally
+    pub fn add_detector(&mut self, name: String) {
+        let n = self.num_detectors as f64;
+        for w in &mut self.current_weights {
+            *w *= n / (n + 1.0);
+        }
+        self.current_weights.push(1.0 / (n + 1.0));
+        self.performance.push(DetectorPerformance::new(100));
+        self.bandit.add_arm();
+        self.detector_names.push(name);
+        self.num_detectors += 1;
+    }
+
+    pub fn num_detectors(&self) -> usize {
+        self.num_detectors
+    }
+
     /// Combine detector outputs into ensemble score
     pub fn combine(&mut self, outputs: &[DetectorOutput]) -> (f64, f64) {
         if outputs.is_empty() {
@@ -535,17 +555,10 @@ impl AdaptiveEnsemble {
         }
 
         // Renormalize
-        let sum: f64 = self.current_weights.iter().take(self.num_detectors).sum();
+        let sum: f64 = self.current_weights.iter().sum();
         if sum > 0.0 {
-            self.current_weights
-                .iter_mut()
-                .take(self.num_detectors)
-                .for_each(|w| *w /= sum);
+            self.current_weights.iter_mut().for_each(|w| *w /= sum);
         }
-        self.current_weights
-            .iter_mut()
-            .skip(self.num_detectors)
-            .for_each(|w| *w = 0.0);
     }
 
     /// Calculate ensemble confidence
@@ -562,7 +575,11 @@ impl AdaptiveEnsemble {
         let mut total_weight = 0.0;
 
         for output in outputs {
-            let weight = self.current_weights[output.detector_id];
+            let weight = self
+                .current_weights
+                .get(output.detector_id)
+                .copied()
+                .unwrap_or(0.0);
             total_confidence += output.confidence * weight;
             total_weight += weight;
         }
@@ -593,18 +610,13 @@ impl AdaptiveEnsemble {
             .iter()
             .take(self.num_detectors)
             .cloned()
-            .zip(
-                self.current_weights
-                    .iter()
-                    .take(self.num_detectors)
-                    .cloned(),
-            )
+            .zip(self.current_weights.iter().cloned())
             .collect()
     }
 
     /// Current normalized detector weights.
     pub fn current_weights(&self) -> &[f64] {
-        &self.current_weights[..self.num_detectors]
+        &self.current_weights
     }
 
     /// Restore full adaptive state from a checkpoint.
@@ -618,19 +630,12 @@ impl AdaptiveEnsemble {
         if weights.len() != self.num_detectors {
             return Err("invalid weight length");
         }
-        self.current_weights[..self.num_detectors].copy_from_slice(weights);
-        self.current_weights
-            .iter_mut()
-            .skip(self.num_detectors)
-            .for_each(|w| *w = 0.0);
-        let sum: f64 = self.current_weights.iter().take(self.num_detectors).sum();
+        let sum: f64 = weights.iter().sum();
         if sum <= 0.0 {
             return Err("invalid weight sum");
         }
-        self.current_weights
-            .iter_mut()
-            .take(self.num_detectors)
-            .for_each(|w| *w /= sum);
+        self.current_weights.copy_from_slice(weights);
+        self.current_weights.iter_mut().for_each(|w| *w /= sum);
         self.bandit.set_params(alphas, betas)?;
         self.update_count = total_samples;
         Ok(())
@@ -676,11 +681,7 @@ impl AdaptiveEnsemble {
             .map(|_| DetectorPerformance::new(100))
             .collect();
         self.bandit = ThompsonBandit::new(self.num_detectors);
-        self.current_weights = [0.0; NUM_DETECTORS];
-        let uniform = 1.0 / self.num_detectors as f64;
-        for w in self.current_weights.iter_mut().take(self.num_detectors) {
-            *w = uniform;
-        }
+        self.current_weights = vec![1.0 / self.num_detectors as f64; self.num_detectors];
         self.update_count = 0;
         self.p2_estimator = P2QuantileEstimator::new(0.95);
         self.adaptive_threshold = 0.5;
@@ -792,6 +793,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_add_detector_rebalances_weights() {
+        let names = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        let mut ensemble = AdaptiveEnsemble::new(names, 0.0, 10);
+        ensemble.add_detector("Custom".to_string());
+
+        assert_eq!(ensemble.num_detectors(), 4);
+        let weights = ensemble.get_weights();
+        assert_eq!(weights[3].0, "Custom");
+        for (_, w) in &weights {
+            assert!((w - 0.25).abs() < 1e-12);
+        }
+
+        // The new detector takes part in combining and learning
+        let outputs = [DetectorOutput {
+            detector_id: 3,
+            score: 0.9,
+            confidence: 1.0,
+            signal_type: 0,
+        }];
+        let (score, _) = ensemble.combine(&outputs);
+        assert!((score - 0.9).abs() < 1e-12);
+        ensemble.update_with_feedback(&outputs, true, true);
+        assert_eq!(ensemble.bandit_params().0.len(), 4);
+    }
+
     #[test]
     fn test_ensemble_combine() {
         let names = vec!["A".to_string(), "B".to_string()];
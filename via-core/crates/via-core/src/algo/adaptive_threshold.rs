@@ -36,6 +36,10 @@ pub struct AdaptiveThreshold {
     update_count: u64,
     min_threshold: f64,
     max_threshold: f64,
+
+    /// Reused sort buffer, so steady-state updates do not allocate
+    #[serde(skip)]
+    scratch: Vec<f64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
@@ -75,6 +79,7 @@ impl AdaptiveThreshold {
             update_count: 0,
             min_threshold: 0.001,
             max_threshold: f64::MAX,
+            scratch: Vec::with_capacity(ws),
         }
     }
 
@@ -183,7 +188,7 @@ impl AdaptiveThreshold {
 
         // Update MAD history (track deviations from median)
         if !self.percentile_window.is_empty() {
-            let median = self.calculate_median_deque(&self.percentile_window);
+            let median = median_of(&self.percentile_window, &mut self.scratch);
             let deviation = (value - median).abs();
             self.mad_history.push_back(deviation);
             if self.mad_history.len() > self.window_size {
@@ -199,13 +204,12 @@ impl AdaptiveThreshold {
     }
 
     /// Calculate threshold using percentile method
-    fn calculate_percentile_threshold(&self) -> f64 {
+    fn calculate_percentile_threshold(&mut self) -> f64 {
         if self.percentile_window.len() < 10 {
             return self.ewma_mean * 2.0; // Fallback during warm-up
         }
 
-        let mut sorted: Vec<f64> = self.percentile_window.iter().copied().collect();
-        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let sorted = sorted_into(&self.percentile_window, &mut self.scratch);
 
         let idx =
             ((self.target_percentile * (sorted.len() - 1) as f64) as usize).min(sorted.len() - 1);
@@ -214,13 +218,13 @@ impl AdaptiveThreshold {
     }
 
     /// Calculate threshold using MAD (robust statistic)
-    fn calculate_mad_threshold(&self) -> f64 {
+    fn calculate_mad_threshold(&mut self) -> f64 {
         if self.mad_history.len() < 10 {
             return self.ewma_mean * 2.0; // Fallback during warm-up
         }
 
-        let median = self.calculate_median_deque(&self.percentile_window);
-        let mad = self.calculate_median_deque(&self.mad_history);
+        let median = median_of(&self.percentile_window, &mut self.scratch);
+        let mad = median_of(&self.mad_history, &mut self.scratch);
 
         // MAD * 1.4826 ≈ standard deviation for normal distribution
         let robust_std = mad * 1.4826;
@@ -229,7 +233,7 @@ impl AdaptiveThreshold {
     }
 
     /// Calculate ensemble threshold (conservative combination)
-    fn calculate_ensemble_threshold(&self) -> f64 {
+    fn calculate_ensemble_threshold(&mut self) -> f64 {
         let ewma_thresh = self.calculate_ewma_threshold(3.0);
         let percentile_thresh = self.calculate_percentile_threshold();
         let mad_thresh = self.calculate_mad_threshold();
@@ -241,29 +245,6 @@ impl AdaptiveThreshold {
         thresholds[1] // Median
     }
 
-    /// Calculate median of a slice
-    fn calculate_median(&self, data: &[f64]) -> f64 {
-        if data.is_empty() {
-            return 0.0;
-        }
-
-        let mut sorted: Vec<f64> = data.iter().copied().collect();
-        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-        let n = sorted.len();
-        if n % 2 == 0 {
-            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
-        } else {
-            sorted[n / 2]
-        }
-    }
-
-    /// Calculate median from VecDeque
-    fn calculate_median_deque(&self, deque: &std::collections::VecDeque<f64>) -> f64 {
-        let slice: Vec<f64> = deque.iter().copied().collect();
-        self.calculate_median(&slice)
-    }
-
     /// Get current statistics
     pub fn get_stats(&self) -> (f64, f64, f64, u64) {
         (
@@ -295,6 +276,27 @@ impl AdaptiveThreshold {
     }
 }
 
+/// Copy `data` into `scratch` and sort it in place
+fn sorted_into<'a>(data: &VecDeque<f64>, scratch: &'a mut Vec<f64>) -> &'a [f64] {
+    scratch.clear();
+    scratch.extend(data.iter().copied());
+    scratch.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+    scratch
+}
+
+/// Median of `data`, using `scratch` as the sort buffer
+fn median_of(data: &VecDeque<f64>, scratch: &mut Vec<f64>) -> f64 {
+    let sorted = sorted_into(data, scratch);
+    let n = sorted.len();
+    if n == 0 {
+        0.0
+    } else if n.is_multiple_of(2) {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+    } else {
+        sorted[n / 2]
+    }
+}
+
 /// Pre-configured threshold presets for common use cases
 pub mod presets {
     use super::*;
@@ -81,6 +81,25 @@ impl BehavioralProfile {
         }
     }
 
+    /// Start over as a fresh profile for `entity_hash`, reusing the sketch
+    /// buffers of this one
+    pub fn reset(&mut self, entity_hash: u64, timestamp: u64) {
+        self.entity_hash = entity_hash;
+        self.first_seen = timestamp;
+        self.last_seen = timestamp;
+        self.normal_hours = [0; 24];
+        self.observation_count = 0;
+        self.velocity_ewma = 0.0;
+        self.iat_histogram.clear();
+        self.payload_histogram.clear();
+        self.service_access.clear();
+        self.service_diversity.clear();
+        self.geo_diversity.clear();
+        self.behavior_score = 0.0;
+        self.anomaly_count = 0;
+        self.is_mature = false;
+    }
+
     /// Update profile with new event
     pub fn update(
         &mut self,
@@ -136,43 +155,41 @@ impl BehavioralProfile {
             return 0.0; // Not enough data
         }
 
-        let mut deviations = vec![];
+        // Strongest single deviation (max for high sensitivity)
+        let mut score = 0.0_f64;
 
         // 1. Temporal deviation (unusual hour)
         let hour = ((timestamp_ns / 3_600_000_000_000u64) % 24) as usize;
         let hour_count = self.normal_hours[hour];
         let avg_hour_count = self.observation_count / 24;
         if hour_count < avg_hour_count / 2 {
-            deviations.push(0.3); // Unusual hour
+            score = score.max(0.3); // Unusual hour
         }
 
         // 2. Velocity deviation
         let instant_velocity = 1.0 / iat_ms.max(1.0);
         let velocity_ratio = instant_velocity / self.velocity_ewma.max(0.001);
         if velocity_ratio > 5.0 {
-            deviations.push(0.4 * (velocity_ratio.min(10.0) / 10.0));
+            score = score.max(0.4 * (velocity_ratio.min(10.0) / 10.0));
         }
 
         // 3. IAT distribution deviation (using histogram rarity)
         let iat_rarity = self.iat_histogram.rarity_score(iat_ms);
         if iat_rarity > 0.8 {
-            deviations.push(0.3 * iat_rarity);
+            score = score.max(0.3 * iat_rarity);
         }
 
         // 4. Payload size deviation
         let payload_rarity = self.payload_histogram.rarity_score(payload_size);
         if payload_rarity > 0.8 {
-            deviations.push(0.2 * payload_rarity);
+            score = score.max(0.2 * payload_rarity);
         }
 
         // 5. Service access deviation (new service)
         if !self.service_access.contains(service_hash) {
-            deviations.push(0.3); // Accessing new service
+            score = score.max(0.3); // Accessing new service
         }
 
-        // Combine deviations (max for high sensitivity, sum for accumulation)
-        let score: f64 = deviations.iter().cloned().fold(0.0_f64, f64::max);
-
         // Update behavior score with EWMA
         self.behavior_score = 0.1 * score + 0.9 * self.behavior_score;
 
@@ -256,12 +273,16 @@ impl ProfileStore {
         self.access_counter += 1;
 
         if !self.profiles.contains_key(&entity_hash) {
-            // Check if we need to evict
-            if self.profiles.len() >= self.max_profiles {
-                self.evict_lru();
-            }
-
-            let profile = BehavioralProfile::new(entity_hash, timestamp_ns);
+            // Check if we need to evict; the evicted profile's buffers are
+            // recycled so a full store does not allocate per new entity
+            let profile = if self.profiles.len() >= self.max_profiles
+                && let Some(mut evicted) = self.evict_lru()
+            {
+                evicted.reset(entity_hash, timestamp_ns);
+                evicted
+            } else {
+                BehavioralProfile::new(entity_hash, timestamp_ns)
+            };
             self.profiles.insert(entity_hash, profile);
         }
 
@@ -306,18 +327,16 @@ impl ProfileStore {
     }
 
     /// Evict least recently used profile
-    fn evict_lru(&mut self) {
+    fn evict_lru(&mut self) -> Option<BehavioralProfile> {
         // Find the oldest entry by collecting the key separately
         let oldest_hash = self
             .access_times
             .iter()
             .min_by_key(|(_key, time)| **time)
-            .map(|(hash, _)| *hash);
+            .map(|(hash, _)| *hash)?;
 
-        if let Some(hash) = oldest_hash {
-            self.profiles.remove(&hash);
-            self.access_times.remove(&hash);
-        }
+        self.access_times.remove(&oldest_hash);
+        self.profiles.remove(&oldest_hash)
     }
 
     /// Get store statistics
@@ -373,6 +392,7 @@ pub struct EntityEvent {
 }
 
 /// Behavioral fingerprinting detector (wrapper for engine integration)
+#[derive(Serialize, Deserialize, Clone)]
 pub struct BehavioralFingerprintDetector {
     store: ProfileStore,
     last_timestamp: u64,
@@ -396,6 +416,19 @@ impl BehavioralFingerprintDetector {
         payload_size: f64,
         service_hash: u64,
     ) -> (f64, bool, String) {
+        let (score, is_anomaly) =
+            self.observe(entity_hash, timestamp_ns, payload_size, service_hash);
+        (score, is_anomaly, self.explain(entity_hash, is_anomaly))
+    }
+
+    /// [`process`](Self::process) without building the reason string
+    pub fn observe(
+        &mut self,
+        entity_hash: u64,
+        timestamp_ns: u64,
+        payload_size: f64,
+        service_hash: u64,
+    ) -> (f64, bool) {
         // Calculate IAT
         let iat_ms = if self.last_entity == entity_hash && self.last_timestamp > 0 {
             (timestamp_ns.saturating_sub(self.last_timestamp)) as f64 / 1_000_000.0
@@ -418,9 +451,12 @@ impl BehavioralFingerprintDetector {
             geo_hash,
         );
 
-        // Generate reason
-        let profile = self.store.get_profile(entity_hash);
-        let reason = if let Some(p) = profile {
+        (score, is_anomaly)
+    }
+
+    /// Human-readable reason for the last result for `entity_hash`
+    pub fn explain(&mut self, entity_hash: u64, is_anomaly: bool) -> String {
+        if let Some(p) = self.store.get_profile(entity_hash) {
             if is_anomaly {
                 format!(
                     "Behavioral anomaly: entity {} has score {:.2} (observations: {})",
@@ -431,9 +467,7 @@ impl BehavioralFingerprintDetector {
             }
         } else {
             format!("New entity: {} (learning)", entity_hash)
-        };
-
-        (score, is_anomaly, reason)
+        }
     }
 
     pub fn get_stats(&self) -> (usize, u64, u64) {
@@ -0,0 +1,358 @@
+//! Score Calibration from Labeled Feedback
+//!
+//! Maps ensemble scores to empirical anomaly probabilities. Labels are
+//! accumulated in fixed score bins; once enough have arrived the mapping is
+//! the isotonic (non-decreasing) regression of the bins' hit rates, fit with
+//! pool-adjacent-violators. With fewer labels a Platt fit (a logistic curve
+//! in the score) over the same bins is used instead, as two parameters are
+//! far less noisy than one rate per bin.
+//!
+//! Probabilities are read off the fit at the bin centers, interpolated
+//! linearly, so a lookup is O(1). An optional half-life fades old labels out.
+
+use serde::{Deserialize, Serialize};
+
+/// Score bins over [0, 1]
+pub const CALIBRATION_BINS: usize = 20;
+/// Label weight needed before any calibration
+const MIN_PLATT_WEIGHT: f64 = 10.0;
+/// Label weight needed before the isotonic fit replaces Platt's
+const MIN_ISOTONIC_WEIGHT: f64 = 100.0;
+const PLATT_ITERATIONS: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CalibrationMethod {
+    /// Too few labels; no probability is reported
+    Uncalibrated,
+    Platt,
+    Isotonic,
+}
+
+/// One score bin of the calibration curve
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct CalibrationPoint {
+    /// Bin center
+    pub score: f64,
+    /// Fraction of positive labels in the bin, `None` when it has none
+    pub observed: Option<f64>,
+    /// Calibrated probability at the bin center
+    pub probability: f64,
+    /// Label weight in the bin
+    pub weight: f64,
+}
+
+/// Exportable state of a calibrator
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CalibrationCurve {
+    pub method: CalibrationMethod,
+    /// Total (decayed) label weight
+    pub label_weight: f64,
+    /// One point per score bin, lowest score first
+    pub points: Vec<CalibrationPoint>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScoreCalibrator {
+    positives: [f64; CALIBRATION_BINS],
+    totals: [f64; CALIBRATION_BINS],
+    /// Calibrated probability at each bin center
+    fitted: [f64; CALIBRATION_BINS],
+    method: CalibrationMethod,
+    /// Multiplier applied to existing labels per new label (1.0 = keep all)
+    decay: f64,
+}
+
+impl ScoreCalibrator {
+    pub fn new() -> Self {
+        Self {
+            positives: [0.0; CALIBRATION_BINS],
+            totals: [0.0; CALIBRATION_BINS],
+            fitted: [0.0; CALIBRATION_BINS],
+            method: CalibrationMethod::Uncalibrated,
+            decay: 1.0,
+        }
+    }
+
+    /// Fade old labels out: a label counts half as much `half_life` labels later
+    pub fn with_half_life(mut self, half_life: f64) -> Self {
+        self.decay = 0.5f64.powf(1.0 / half_life.max(1.0));
+        self
+    }
+
+    /// Fit on `(score, is_anomaly)` pairs without forgetting
+    pub fn fit(labeled: &[(f64, bool)]) -> Self {
+        Self::fit_weighted(
+            labeled
+                .iter()
+                .map(|&(score, positive)| (score, positive, 1.0)),
+        )
+    }
+
+    /// Fit on `(score, is_anomaly, weight)` triples, e.g. histogram bins
+    pub fn fit_weighted(labeled: impl IntoIterator<Item = (f64, bool, f64)>) -> Self {
+        let mut calibrator = Self::new();
+        for (score, positive, weight) in labeled {
+            calibrator.add(score, positive, weight);
+        }
+        calibrator.refit();
+        calibrator
+    }
+
+    /// Learn from one label on an event that scored `score`
+    pub fn observe(&mut self, score: f64, positive: bool, weight: f64) {
+        if self.decay < 1.0 {
+            for (p, t) in self.positives.iter_mut().zip(&mut self.totals) {
+                *p *= self.decay;
+                *t *= self.decay;
+            }
+        }
+        self.add(score, positive, weight);
+        self.refit();
+    }
+
+    /// Empirical probability that an event scoring `score` is anomalous,
+    /// `None` until enough labels have arrived
+    pub fn probability(&self, score: f64) -> Option<f64> {
+        if self.method == CalibrationMethod::Uncalibrated || score.is_nan() {
+            return None;
+        }
+        let position = (score.clamp(0.0, 1.0) * CALIBRATION_BINS as f64 - 0.5)
+            .clamp(0.0, (CALIBRATION_BINS - 1) as f64);
+        let lo = position.floor() as usize;
+        let hi = (lo + 1).min(CALIBRATION_BINS - 1);
+        let w = position - lo as f64;
+        Some((1.0 - w) * self.fitted[lo] + w * self.fitted[hi])
+    }
+
+    pub fn method(&self) -> CalibrationMethod {
+        self.method
+    }
+
+    /// Total (decayed) label weight
+    pub fn label_weight(&self) -> f64 {
+        self.totals.iter().sum()
+    }
+
+    /// Observed and calibrated rate per score bin
+    pub fn curve(&self) -> CalibrationCurve {
+        let points = (0..CALIBRATION_BINS)
+            .map(|bin| CalibrationPoint {
+                score: bin_center(bin),
+                observed: (self.totals[bin] > 0.0).then(|| self.positives[bin] / self.totals[bin]),
+                probability: self.fitted[bin],
+                weight: self.totals[bin],
+            })
+            .collect();
+        CalibrationCurve {
+            method: self.method,
+            label_weight: self.label_weight(),
+            points,
+        }
+    }
+
+    fn add(&mut self, score: f64, positive: bool, weight: f64) {
+        if score.is_nan() || weight <= 0.0 {
+            return;
+        }
+        let bin =
+            ((score.clamp(0.0, 1.0) * CALIBRATION_BINS as f64) as usize).min(CALIBRATION_BINS - 1);
+        self.totals[bin] += weight;
+        if positive {
+            self.positives[bin] += weight;
+        }
+    }
+
+    fn refit(&mut self) {
+        let total = self.label_weight();
+        let positives: f64 = self.positives.iter().sum();
+        self.method = if total < MIN_PLATT_WEIGHT || positives <= 0.0 || positives >= total {
+            CalibrationMethod::Uncalibrated
+        } else if total < MIN_ISOTONIC_WEIGHT {
+            self.fit_platt(positives, total - positives);
+            CalibrationMethod::Platt
+        } else {
+            self.fit_isotonic();
+            CalibrationMethod::Isotonic
+        };
+    }
+
+    /// Weighted logistic regression of the label on the score by Newton's
+    /// method, with Platt's smoothed targets against overconfidence
+    fn fit_platt(&mut self, positives: f64, negatives: f64) {
+        let hi = (positives + 1.0) / (positives + 2.0);
+        let lo = 1.0 / (negatives + 2.0);
+        let prior = positives / (positives + negatives);
+        let (mut a, mut b) = (0.0, (prior / (1.0 - prior)).ln());
+
+        for _ in 0..PLATT_ITERATIONS {
+            let (mut g_a, mut g_b) = (0.0, 0.0);
+            // Small ridge keeps the Hessian invertible on separable labels
+            let (mut h_aa, mut h_ab, mut h_bb) = (1e-6, 0.0, 1e-6);
+            for bin in 0..CALIBRATION_BINS {
+                let weight = self.totals[bin];
+                if weight <= 0.0 {
+                    continue;
+                }
+                let target =
+                    (self.positives[bin] * hi + (weight - self.positives[bin]) * lo) / weight;
+                let x = bin_center(bin);
+                let p = sigmoid(a * x + b);
+                let d = weight * p * (1.0 - p);
+                g_a += weight * (p - target) * x;
+                g_b += weight * (p - target);
+                h_aa += d * x * x;
+                h_ab += d * x;
+                h_bb += d;
+            }
+            let det = h_aa * h_bb - h_ab * h_ab;
+            if det.abs() < 1e-12 {
+                break;
+            }
+            let step_a = (h_bb * g_a - h_ab * g_b) / det;
+            let step_b = (h_aa * g_b - h_ab * g_a) / det;
+            a -= step_a;
+            b -= step_b;
+            if step_a.abs() + step_b.abs() < 1e-9 {
+                break;
+            }
+        }
+
+        for (bin, fitted) in self.fitted.iter_mut().enumerate() {
+            *fitted = sigmoid(a * bin_center(bin) + b);
+        }
+    }
+
+    /// Pool-adjacent-violators over the non-empty bins; empty bins take the
+    /// interpolation of their neighbors
+    fn fit_isotonic(&mut self) {
+        // (first bin, last bin, rate, weight) per pooled block
+        let mut blocks: Vec<(usize, usize, f64, f64)> = Vec::with_capacity(CALIBRATION_BINS);
+        for bin in 0..CALIBRATION_BINS {
+            let weight = self.totals[bin];
+            if weight <= 0.0 {
+                continue;
+            }
+            blocks.push((bin, bin, self.positives[bin] / weight, weight));
+            while blocks.len() > 1 {
+                let (start, _, rate_prev, weight_prev) = blocks[blocks.len() - 2];
+                let (_, end, rate, weight) = blocks[blocks.len() - 1];
+                if rate_prev <= rate {
+                    break;
+                }
+                blocks.truncate(blocks.len() - 2);
+                let pooled = weight_prev + weight;
+                blocks.push((
+                    start,
+                    end,
+                    (rate_prev * weight_prev + rate * weight) / pooled,
+                    pooled,
+                ));
+            }
+        }
+
+        let mut known: Vec<(usize, f64)> = Vec::with_capacity(CALIBRATION_BINS);
+        for &(start, end, rate, _) in &blocks {
+            for bin in start..=end {
+                if self.totals[bin] > 0.0 {
+                    known.push((bin, rate));
+                }
+            }
+        }
+        for bin in 0..CALIBRATION_BINS {
+            let after = known.partition_point(|&(b, _)| b < bin);
+            self.fitted[bin] = match (after.checked_sub(1).map(|i| known[i]), known.get(after)) {
+                (_, Some(&(b, rate))) if b == bin => rate,
+                (Some((b0, r0)), Some(&(b1, r1))) => {
+                    r0 + (r1 - r0) * (bin - b0) as f64 / (b1 - b0) as f64
+                }
+                (Some((_, rate)), None) | (None, Some(&(_, rate))) => rate,
+                (None, None) => 0.0,
+            };
+        }
+    }
+}
+
+impl Default for ScoreCalibrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn bin_center(bin: usize) -> f64 {
+    (bin as f64 + 0.5) / CALIBRATION_BINS as f64
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic labels whose true anomaly probability is `score^2`
+    fn labeled(n: usize) -> Vec<(f64, bool)> {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut uniform = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+        (0..n)
+            .map(|_| {
+                let score = uniform();
+                (score, uniform() < score * score)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_isotonic_recovers_probability() {
+        let calibrator = ScoreCalibrator::fit(&labeled(20_000));
+        assert_eq!(calibrator.method(), CalibrationMethod::Isotonic);
+        for score in [0.1, 0.5, 0.8, 0.95] {
+            let p = calibrator.probability(score).unwrap();
+            assert!((p - score * score).abs() < 0.06, "p({}) = {}", score, p);
+        }
+
+        let curve = calibrator.curve();
+        assert_eq!(curve.points.len(), CALIBRATION_BINS);
+        assert_eq!(curve.label_weight, 20_000.0);
+        assert!(
+            curve
+                .points
+                .windows(2)
+                .all(|w| w[0].probability <= w[1].probability)
+        );
+    }
+
+    #[test]
+    fn test_few_labels_use_platt() {
+        let mut calibrator = ScoreCalibrator::new();
+        assert_eq!(calibrator.probability(0.5), None);
+        for (score, positive) in labeled(60) {
+            calibrator.observe(score, positive, 1.0);
+        }
+        assert_eq!(calibrator.method(), CalibrationMethod::Platt);
+        let (low, high) = (
+            calibrator.probability(0.1).unwrap(),
+            calibrator.probability(0.9).unwrap(),
+        );
+        assert!(low < high && low > 0.0 && high < 1.0, "{} {}", low, high);
+    }
+
+    #[test]
+    fn test_half_life_follows_label_shift() {
+        let mut calibrator = ScoreCalibrator::new().with_half_life(100.0);
+        for i in 0..2_000 {
+            // High scores are confirmed at first, then mostly rejected
+            let positive = if i < 1_000 { i % 10 != 0 } else { i % 10 == 0 };
+            calibrator.observe(0.9, positive, 1.0);
+            calibrator.observe(0.1, false, 1.0);
+        }
+        let p = calibrator.probability(0.9).unwrap();
+        assert!(p < 0.2, "p = {}", p);
+    }
+}
@@ -0,0 +1,263 @@
+//! DDSketch: Quantile Sketch with Relative-Error Guarantees
+//!
+//! Values map to logarithmic buckets `ceil(ln(v) / ln(gamma))`, so every
+//! quantile is within `relative_accuracy` of the true value whatever the
+//! range of the stream; there is no min/max to configure. Sketches with the
+//! same accuracy merge by adding bucket counts.
+//!
+//! An optional half-life fades old values out: each new value is weighted
+//! `2^(1 / half_life)` times the previous one and counts are renormalized
+//! when weights grow large, so forgetting costs O(1) per value.
+
+use serde::{Deserialize, Serialize};
+
+/// Values at or below this land in the zero bucket (includes negatives)
+const MIN_INDEXABLE: f64 = 1e-9;
+/// Weight at which counts are renormalized
+const RESCALE_AT: f64 = 1e100;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DDSketch {
+    gamma: f64,
+    ln_gamma: f64,
+    /// Counts for keys `min_key..min_key + bins.len()`
+    bins: Vec<f64>,
+    min_key: i32,
+    zero_count: f64,
+    count: f64,
+    /// Buckets kept; beyond this the lowest ones collapse together
+    max_bins: usize,
+    /// Weight multiplier per value (1.0 = no forgetting)
+    growth: f64,
+    /// Weight of the next value
+    weight: f64,
+}
+
+impl DDSketch {
+    /// Sketch whose quantiles are within `relative_accuracy` (e.g. 0.01)
+    pub fn new(relative_accuracy: f64, max_bins: usize) -> Self {
+        let accuracy = relative_accuracy.clamp(1e-4, 0.5);
+        let gamma = (1.0 + accuracy) / (1.0 - accuracy);
+        let max_bins = max_bins.max(16);
+        Self {
+            gamma,
+            ln_gamma: gamma.ln(),
+            bins: Vec::with_capacity(max_bins),
+            min_key: 0,
+            zero_count: 0.0,
+            count: 0.0,
+            max_bins,
+            growth: 1.0,
+            weight: 1.0,
+        }
+    }
+
+    /// Fade old values out: a value counts half as much `half_life` values later
+    pub fn with_half_life(mut self, half_life: f64) -> Self {
+        self.growth = 2f64.powf(1.0 / half_life.max(1.0));
+        self
+    }
+
+    pub fn add(&mut self, value: f64) {
+        let weight = self.weight;
+        if value <= MIN_INDEXABLE || value.is_nan() {
+            self.zero_count += weight;
+        } else {
+            let key = (value.ln() / self.ln_gamma).ceil() as i32;
+            *self.bin_mut(key) += weight;
+        }
+        self.count += weight;
+
+        if self.growth > 1.0 {
+            self.weight *= self.growth;
+            if self.weight > RESCALE_AT {
+                self.rescale();
+            }
+        }
+    }
+
+    /// Value at quantile `q` (0..=1), `None` while empty
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count <= 0.0 {
+            return None;
+        }
+        let rank = q.clamp(0.0, 1.0) * self.count;
+        let mut cumulative = self.zero_count;
+        if cumulative > rank {
+            return Some(0.0);
+        }
+        let mut last = None;
+        for (offset, &count) in self.bins.iter().enumerate() {
+            if count <= 0.0 {
+                continue;
+            }
+            last = Some(offset);
+            cumulative += count;
+            if cumulative > rank {
+                break;
+            }
+        }
+        Some(last.map_or(0.0, |offset| self.key_value(self.min_key + offset as i32)))
+    }
+
+    /// Add `other`'s counts; both must have the same accuracy
+    ///
+    /// With forgetting, both sketches' most recent values count as "now".
+    pub fn merge(&mut self, other: &DDSketch) -> Result<(), &'static str> {
+        if (self.gamma - other.gamma).abs() > 1e-12 {
+            return Err("sketch accuracy mismatch");
+        }
+        let scale = self.weight / other.weight;
+        for (offset, &count) in other.bins.iter().enumerate() {
+            if count > 0.0 {
+                *self.bin_mut(other.min_key + offset as i32) += count * scale;
+            }
+        }
+        self.zero_count += other.zero_count * scale;
+        self.count += other.count * scale;
+        Ok(())
+    }
+
+    /// Total weight of the values seen, relative to the newest one
+    pub fn count(&self) -> f64 {
+        self.count / self.weight
+    }
+
+    /// Relative accuracy the sketch was built with
+    pub fn relative_accuracy(&self) -> f64 {
+        (self.gamma - 1.0) / (self.gamma + 1.0)
+    }
+
+    /// Representative value of bucket `key`: equidistant in relative terms
+    /// from both bucket bounds
+    fn key_value(&self, key: i32) -> f64 {
+        2.0 * self.gamma.powi(key) / (self.gamma + 1.0)
+    }
+
+    fn bin_mut(&mut self, key: i32) -> &mut f64 {
+        if self.bins.is_empty() {
+            self.min_key = key;
+            self.bins.push(0.0);
+        }
+
+        if key < self.min_key {
+            let missing = (self.min_key - key) as usize;
+            if self.bins.len() + missing > self.max_bins {
+                // Below the kept range: fold into the lowest bucket
+                return &mut self.bins[0];
+            }
+            let len = self.bins.len();
+            self.bins.resize(len + missing, 0.0);
+            self.bins.copy_within(0..len, missing);
+            self.bins[..missing].fill(0.0);
+            self.min_key = key;
+        }
+
+        let offset = (key - self.min_key) as usize;
+        if offset >= self.max_bins {
+            // Make room within capacity before extending upwards
+            self.collapse_lowest(offset + 1 - self.max_bins);
+        }
+        let offset = (key - self.min_key) as usize;
+        if offset >= self.bins.len() {
+            self.bins.resize(offset + 1, 0.0);
+        }
+        &mut self.bins[offset]
+    }
+
+    /// Raise the lowest kept key by `n`, folding the dropped buckets into the
+    /// new lowest one
+    fn collapse_lowest(&mut self, n: usize) {
+        if n >= self.bins.len() {
+            let total: f64 = self.bins.iter().sum();
+            self.bins.clear();
+            self.bins.push(total);
+        } else {
+            let folded: f64 = self.bins[..n].iter().sum();
+            self.bins[n] += folded;
+            self.bins.copy_within(n.., 0);
+            self.bins.truncate(self.bins.len() - n);
+        }
+        self.min_key += n as i32;
+    }
+
+    fn rescale(&mut self) {
+        let scale = 1.0 / self.weight;
+        for count in &mut self.bins {
+            *count *= scale;
+        }
+        self.zero_count *= scale;
+        self.count *= scale;
+        self.weight = 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantiles_within_relative_accuracy() {
+        let mut sketch = DDSketch::new(0.01, 1024);
+        for i in 1..=10_000 {
+            sketch.add(i as f64);
+        }
+        for (q, exact) in [(0.5, 5_000.0), (0.95, 9_500.0), (0.99, 9_900.0)] {
+            let estimate = sketch.quantile(q).unwrap();
+            assert!(
+                (estimate - exact).abs() / exact <= 0.011,
+                "q{} = {} (exact {})",
+                q,
+                estimate,
+                exact
+            );
+        }
+
+        // No configured range: far larger values are still tracked
+        sketch.add(1e9);
+        assert!(sketch.quantile(1.0).unwrap() > 0.98e9);
+    }
+
+    #[test]
+    fn test_merge_matches_single_sketch() {
+        let mut all = DDSketch::new(0.01, 1024);
+        let mut low = DDSketch::new(0.01, 1024);
+        let mut high = DDSketch::new(0.01, 1024);
+        for i in 1..=1_000 {
+            all.add(i as f64);
+            if i <= 500 {
+                low.add(i as f64)
+            } else {
+                high.add(i as f64)
+            }
+        }
+        low.merge(&high).unwrap();
+        assert_eq!(low.quantile(0.95), all.quantile(0.95));
+        assert!(low.merge(&DDSketch::new(0.05, 1024)).is_err());
+    }
+
+    #[test]
+    fn test_half_life_forgets_old_values() {
+        let mut sketch = DDSketch::new(0.01, 1024).with_half_life(50.0);
+        for _ in 0..5_000 {
+            sketch.add(100.0);
+        }
+        for _ in 0..500 {
+            sketch.add(400.0);
+        }
+        let p50 = sketch.quantile(0.5).unwrap();
+        assert!((p50 - 400.0).abs() < 8.0, "p50 {}", p50);
+        assert!(sketch.count() < 100.0);
+    }
+
+    #[test]
+    fn test_bins_stay_bounded() {
+        let mut sketch = DDSketch::new(0.01, 64);
+        for i in 0..2_000 {
+            sketch.add(1.05f64.powi(i));
+        }
+        assert!(sketch.bins.len() <= 64);
+        assert!(sketch.bins.capacity() <= 64);
+        assert!(sketch.quantile(0.0).is_some());
+    }
+}
@@ -0,0 +1,174 @@
+//! Online Seasonal Decomposition (STL-like)
+//!
+//! Splits a timestamped stream into `value = trend + seasonal + residual`.
+//! The season is a wall-clock cycle (a day, a week) cut into equal buckets;
+//! each bucket keeps an exponentially weighted estimate of how far values
+//! around its center sit from the trend, interpolated linearly between
+//! neighboring buckets, and the trend is a time-based EWMA of the
+//! deseasonalized value. The seasonal estimates are kept centered (zero
+//! mean across buckets) so the level stays in the trend.
+//!
+//! Unlike batch STL there is no LOESS smoothing, but an update is O(1) and
+//! needs no history.
+
+use serde::{Deserialize, Serialize};
+
+/// Weight of a new observation in its bucket's seasonal estimate
+const SEASONAL_ALPHA: f64 = 0.05;
+
+/// One event split into its components
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Decomposition {
+    pub trend: f64,
+    pub seasonal: f64,
+    pub residual: f64,
+}
+
+impl Decomposition {
+    /// The value with its seasonal swing removed (`trend + residual`)
+    pub fn adjusted(&self) -> f64 {
+        self.trend + self.residual
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SeasonalDecomposition {
+    season_ns: u64,
+    bucket_ns: u64,
+    /// Raw seasonal estimate per bucket; the effective component subtracts
+    /// their mean
+    seasonal: Vec<f64>,
+    seasonal_sum: f64,
+    trend: f64,
+    /// Time constant of the trend EWMA
+    trend_tau_ns: f64,
+    last_timestamp: u64,
+    initialized: bool,
+    last: Decomposition,
+}
+
+impl SeasonalDecomposition {
+    /// Decompose over a `season_ns` cycle cut into `buckets` slots; the trend
+    /// follows changes slower than one season
+    pub fn new(season_ns: u64, buckets: usize) -> Self {
+        let buckets = buckets.max(2);
+        let season_ns = season_ns.max(buckets as u64);
+        Self {
+            season_ns,
+            bucket_ns: season_ns / buckets as u64,
+            seasonal: vec![0.0; buckets],
+            seasonal_sum: 0.0,
+            trend: 0.0,
+            trend_tau_ns: season_ns as f64,
+            last_timestamp: 0,
+            initialized: false,
+            last: Decomposition::default(),
+        }
+    }
+
+    /// Decompose `value` observed at `timestamp` (ns) and learn from it
+    ///
+    /// The residual is taken against the trend and seasonal estimates from
+    /// before this value, so it is a one-step-ahead error.
+    pub fn update(&mut self, timestamp: u64, value: f64) -> Decomposition {
+        let (lo, hi, w) = self.position(timestamp);
+        if !self.initialized {
+            self.trend = value;
+            self.last_timestamp = timestamp;
+            self.initialized = true;
+        }
+
+        let seasonal = (1.0 - w) * self.seasonal_component(lo) + w * self.seasonal_component(hi);
+        let decomposition = Decomposition {
+            trend: self.trend,
+            seasonal,
+            residual: value - self.trend - seasonal,
+        };
+
+        let dt = timestamp.saturating_sub(self.last_timestamp) as f64;
+        let trend_alpha = 1.0 - (-dt / self.trend_tau_ns).exp();
+        self.trend += trend_alpha * (value - seasonal - self.trend);
+        self.last_timestamp = self.last_timestamp.max(timestamp);
+
+        let raw = (1.0 - w) * self.seasonal[lo] + w * self.seasonal[hi];
+        let error = SEASONAL_ALPHA * ((value - self.trend) - raw);
+        self.seasonal[lo] += (1.0 - w) * error;
+        self.seasonal[hi] += w * error;
+        self.seasonal_sum += error;
+
+        self.last = decomposition;
+        decomposition
+    }
+
+    /// Components of the last update
+    pub fn last(&self) -> Decomposition {
+        self.last
+    }
+
+    pub fn buckets(&self) -> usize {
+        self.seasonal.len()
+    }
+
+    /// Centered seasonal component of every bucket, in season order
+    pub fn seasonal_profile(&self) -> impl Iterator<Item = f64> + '_ {
+        (0..self.seasonal.len()).map(|b| self.seasonal_component(b))
+    }
+
+    /// Buckets whose centers surround `timestamp` and the weight of the
+    /// second one
+    fn position(&self, timestamp: u64) -> (usize, usize, f64) {
+        let n = self.seasonal.len();
+        let offset = (timestamp % self.season_ns) as f64 / self.bucket_ns as f64 - 0.5;
+        let offset = offset.rem_euclid(n as f64);
+        let lo = (offset.floor() as usize).min(n - 1);
+        (lo, (lo + 1) % n, offset - lo as f64)
+    }
+
+    fn seasonal_component(&self, bucket: usize) -> f64 {
+        self.seasonal[bucket] - self.seasonal_sum / self.seasonal.len() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: u64 = 3_600_000_000_000;
+
+    /// Daily cycle peaking at noon, one event per minute
+    fn daily(minute: u64) -> f64 {
+        let phase = 2.0 * std::f64::consts::PI * (minute % 1_440) as f64 / 1_440.0;
+        100.0 - 40.0 * phase.cos()
+    }
+
+    #[test]
+    fn test_learns_daily_cycle() {
+        let mut decomposition = SeasonalDecomposition::new(24 * HOUR, 24);
+        let mut last = Decomposition::default();
+        for minute in 0..10 * 1_440 {
+            last = decomposition.update(minute * HOUR / 60, daily(minute));
+        }
+
+        // Residuals are small once the cycle is learned...
+        assert!(last.residual.abs() < 10.0, "{:?}", last);
+        assert!((last.trend - 100.0).abs() < 5.0, "{:?}", last);
+
+        // ...and the profile peaks around noon
+        let profile: Vec<f64> = decomposition.seasonal_profile().collect();
+        let peak = (0..24).max_by(|&a, &b| profile[a].total_cmp(&profile[b]));
+        assert!(matches!(peak, Some(11..=12)), "{:?}", profile);
+        assert!(profile.iter().sum::<f64>().abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_level_shift_shows_in_residual() {
+        let mut decomposition = SeasonalDecomposition::new(24 * HOUR, 24);
+        for minute in 0..7 * 1_440 {
+            decomposition.update(minute * HOUR / 60, daily(minute));
+        }
+        let minute = 7 * 1_440 + 300;
+        let shifted = decomposition.update(minute * HOUR / 60, daily(minute) + 80.0);
+        assert!(shifted.residual > 60.0, "{:?}", shifted);
+        assert!((shifted.adjusted() - (shifted.trend + shifted.residual)).abs() < 1e-9);
+    }
+}
@@ -293,8 +293,8 @@ impl KLDivergenceDetector {
 
             if ref_sum == 0 {
                 // First batch - use as reference
-                self.reference_hist = self.current_hist.clone();
-                self.current_hist = vec![0; self.num_bins];
+                self.reference_hist.copy_from_slice(&self.current_hist);
+                self.current_hist.fill(0);
                 self.current_count = 0;
                 return (DriftType::None, 0.0);
             }
@@ -303,8 +303,8 @@ impl KLDivergenceDetector {
 
             if kl_div > self.threshold {
                 // Drift detected - reset current, make it reference
-                self.reference_hist = self.current_hist.clone();
-                self.current_hist = vec![0; self.num_bins];
+                self.reference_hist.copy_from_slice(&self.current_hist);
+                self.current_hist.fill(0);
                 self.current_count = 0;
                 return (DriftType::Incremental, kl_div);
             }
@@ -315,7 +315,7 @@ impl KLDivergenceDetector {
                     self.reference_hist[i] =
                         (self.reference_hist[i] / 2) + (self.current_hist[i] / 2);
                 }
-                self.current_hist = vec![0; self.num_bins];
+                self.current_hist.fill(0);
                 self.current_count = 0;
             }
 
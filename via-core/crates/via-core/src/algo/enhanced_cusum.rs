@@ -264,6 +264,23 @@ impl EnhancedCUSUM {
         self.v_mask_angle = (self.slack / 2.0).atan();
     }
 
+    /// Pool with `other`, giving this side `weight` (0..=1)
+    ///
+    /// Target, cumulative sums and adaptive threshold are averaged; sample
+    /// and alarm counts add up. The parameters and threshold history stay
+    /// this side's.
+    pub fn merge(&mut self, other: &EnhancedCUSUM, weight: f64) {
+        let w = weight.clamp(0.0, 1.0);
+        let mix = |a: f64, b: f64| w * a + (1.0 - w) * b;
+        self.target = mix(self.target, other.target);
+        self.c_pos = mix(self.c_pos, other.c_pos);
+        self.c_neg = mix(self.c_neg, other.c_neg);
+        self.adaptive_threshold = mix(self.adaptive_threshold, other.adaptive_threshold);
+        self.sample_count += other.sample_count;
+        self.samples_since_reset += other.samples_since_reset;
+        self.total_alarms += other.total_alarms;
+    }
+
     /// Get current statistics
     pub fn get_stats(&self) -> (f64, f64, f64, u64) {
         (
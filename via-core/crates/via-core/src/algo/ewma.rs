@@ -42,6 +42,30 @@ impl EWMA {
         self.mean
     }
 
+    /// Start from a learned mean instead of the first sample
+    pub fn seed(&mut self, mean: f64) {
+        self.mean = mean;
+        self.variance = 0.0;
+        self.initialized = true;
+    }
+
+    /// Pool with `other`, giving this side `weight` (0..=1): means mix
+    /// linearly and the variance includes the gap between them
+    pub fn merge(&mut self, other: &EWMA, weight: f64) {
+        if !other.initialized {
+            return;
+        }
+        if !self.initialized {
+            *self = other.clone();
+            return;
+        }
+        let w = weight.clamp(0.0, 1.0);
+        let mean = w * self.mean + (1.0 - w) * other.mean;
+        self.variance = w * (self.variance + (self.mean - mean).powi(2))
+            + (1.0 - w) * (other.variance + (other.mean - mean).powi(2));
+        self.mean = mean;
+    }
+
     pub fn get_std_dev(&self) -> f64 {
         self.variance.sqrt()
     }
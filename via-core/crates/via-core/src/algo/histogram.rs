@@ -22,6 +22,12 @@ impl FadingHistogram {
         }
     }
 
+    /// Forget all observations, keeping the bin layout
+    pub fn clear(&mut self) {
+        self.bins.fill(0.0);
+        self.total_weight = 0.0;
+    }
+
     fn get_bin_index(&self, value: f64) -> usize {
         // Log-linear binning for wide dynamic range (good for latency)
         // Log transform normalized to [0, num_bins]
@@ -67,6 +73,80 @@ impl FadingHistogram {
         if prob < 0.001 { 100.0 } else { 1.0 / prob }
     }
 
+    /// `(min, max)` the bins span
+    pub fn range(&self) -> (f64, f64) {
+        (self.min_val, self.max_val)
+    }
+
+    /// Decayed count per bin
+    pub fn bins(&self) -> &[f64] {
+        &self.bins
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.total_weight
+    }
+
+    /// Replace the counts with learned ones; ignored unless `bins` matches
+    /// the bin count
+    pub fn seed(&mut self, bins: &[f64], total_weight: f64) {
+        if bins.len() == self.num_bins {
+            self.bins.copy_from_slice(bins);
+            self.total_weight = total_weight;
+        }
+    }
+
+    /// Add `other`'s counts, as if both streams went into one histogram;
+    /// the bin layouts must match
+    pub fn merge(&mut self, other: &FadingHistogram) -> Result<(), &'static str> {
+        if self.num_bins != other.num_bins || self.range() != other.range() {
+            return Err("histogram layout mismatch");
+        }
+        for (a, b) in self.bins.iter_mut().zip(&other.bins) {
+            *a += b;
+        }
+        self.total_weight += other.total_weight;
+        Ok(())
+    }
+
+    /// Lower edge of bin `i`; the first bin is open below and `num_bins`
+    /// (one past the last) open above
+    fn bin_start(&self, i: usize) -> f64 {
+        if i == 0 {
+            f64::NEG_INFINITY
+        } else if i >= self.num_bins {
+            f64::INFINITY
+        } else {
+            self.min_val * (self.max_val / self.min_val).powf(i as f64 / self.num_bins as f64)
+        }
+    }
+
+    /// Span of the run of common bins nearest to `value`, other than its own
+    /// bin, where common means an `update` score of at most `max_score`
+    pub fn nearest_common_span(&self, value: f64, max_score: f64) -> Option<(f64, f64)> {
+        if self.total_weight <= 0.0 || max_score <= 0.0 {
+            return None;
+        }
+        let min_weight = self.total_weight / max_score;
+        let own = self.get_bin_index(value);
+        let common = |i: usize| i != own && self.bins[i] >= min_weight;
+
+        let nearest = (1..self.num_bins)
+            .flat_map(|d| [own.checked_sub(d), Some(own + d)])
+            .flatten()
+            .filter(|&i| i < self.num_bins)
+            .find(|&i| common(i))?;
+        let mut low = nearest;
+        while low > 0 && common(low - 1) {
+            low -= 1;
+        }
+        let mut high = nearest;
+        while high + 1 < self.num_bins && common(high + 1) {
+            high += 1;
+        }
+        Some((self.bin_start(low), self.bin_start(high + 1)))
+    }
+
     /// Get rarity score (0.0 = common, 1.0 = extremely rare)
     /// Normalized version of the update() return value
     pub fn rarity_score(&self, value: f64) -> f64 {
@@ -37,13 +37,30 @@ impl HyperLogLog {
     pub fn add_hash(&mut self, hash: u64) {
         let idx = (hash >> (64 - self.p)) as usize;
         let w = hash << self.p; // Remaining bits
-        let lz = (w.leading_zeros() as u8) + 1;
+        // Only 64 - p bits remain; cap the rank so an all-zero tail (e.g. the
+        // 0 hash FFI callers get for invalid strings) stays in range
+        let lz = (w.leading_zeros().min(64 - self.p as u32) as u8) + 1;
 
         if lz > self.registers[idx] {
             self.registers[idx] = lz;
         }
     }
 
+    pub fn clear(&mut self) {
+        self.registers.fill(0);
+    }
+
+    /// Union with `other`'s registers; both must have the same precision
+    pub fn merge(&mut self, other: &HyperLogLog) -> Result<(), &'static str> {
+        if self.p != other.p {
+            return Err("HLL precision mismatch");
+        }
+        for (a, &b) in self.registers.iter_mut().zip(&other.registers) {
+            *a = (*a).max(b);
+        }
+        Ok(())
+    }
+
     pub fn count(&self) -> f64 {
         let mut raw_sum = 0.0;
         let mut zeros = 0;
@@ -87,4 +87,282 @@ impl HoltWinters {
     pub fn get_seasonality(&self) -> &[f64] {
         &self.seasonals
     }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// `(level, trend)` once the first season was seen
+    pub fn components(&self) -> Option<(f64, f64)> {
+        self.initialized.then_some((self.level, self.trend))
+    }
+
+    /// Start from learned components instead of warming up; the season
+    /// length becomes `seasonals.len()`, with phase 0 at the next update
+    pub fn seed(&mut self, level: f64, trend: f64, seasonals: &[f64]) {
+        if seasonals.is_empty() {
+            return;
+        }
+        self.period = seasonals.len();
+        self.seasonals.clear();
+        self.seasonals.extend_from_slice(seasonals);
+        self.level = level;
+        self.trend = trend;
+        self.initialized = true;
+        self.step = 0;
+    }
+
+    /// Add `other`'s components, modelling the sum of both series (e.g.
+    /// the event rates of two shards of one stream)
+    ///
+    /// Seasonal phases are aligned on each side's current step. A side that
+    /// has not finished its first season contributes nothing; two different
+    /// season lengths cannot be added.
+    pub fn merge_sum(&mut self, other: &HoltWinters) -> Result<(), &'static str> {
+        if !other.initialized {
+            return Ok(());
+        }
+        if !self.initialized {
+            *self = other.clone();
+            return Ok(());
+        }
+        if self.period != other.period {
+            return Err("season length mismatch");
+        }
+        let p = self.period;
+        for k in 0..p {
+            self.seasonals[(self.step + k) % p] += other.seasonals[(other.step + k) % p];
+        }
+        self.level += other.level;
+        self.trend += other.trend;
+        Ok(())
+    }
+
+    /// Reserve room for seasons up to `max_period` so `reseed` does not
+    /// allocate
+    pub fn reserve_period(&mut self, max_period: usize) {
+        self.seasonals
+            .reserve(max_period.saturating_sub(self.seasonals.len()));
+    }
+
+    /// Switch to a new season length, re-seeding level and seasonal
+    /// components from `history` (oldest first, ending with the last value
+    /// passed to `update`) instead of warming up from scratch
+    pub fn reseed(&mut self, period: usize, history: impl Iterator<Item = f64> + Clone) {
+        let period = period.max(1);
+        let n = history.clone().count();
+        self.period = period;
+        self.seasonals.clear();
+        self.seasonals.resize(period, 0.0);
+        if n < period {
+            // Not a full season to learn from: warm up again
+            self.initialized = false;
+            self.step = 0;
+            return;
+        }
+
+        // Average deviation from the mean per phase; `history[j]` was seen at
+        // step `start + j`
+        let mean = history.clone().sum::<f64>() / n as f64;
+        let start = self.step.saturating_sub(n);
+        for (j, value) in history.clone().enumerate() {
+            self.seasonals[(start + j) % period] += value - mean;
+        }
+        for (phase, seasonal) in self.seasonals.iter_mut().enumerate() {
+            let first = (phase + period - start % period) % period;
+            let count = n / period + usize::from(first < n % period);
+            *seasonal /= count.max(1) as f64;
+        }
+
+        // Deseasonalized level of the last season, no trend
+        let recent = history.skip(n - period);
+        self.level = recent
+            .enumerate()
+            .map(|(j, value)| value - self.seasonals[(start + n - period + j) % period])
+            .sum::<f64>()
+            / period as f64;
+        self.trend = 0.0;
+        self.initialized = true;
+    }
+}
+
+/// Shortest season the estimator reports
+const MIN_PERIOD: usize = 4;
+/// Autocorrelation a lag needs to count as a season
+const MIN_AUTOCORRELATION: f64 = 0.3;
+
+/// Seasonal period estimation from the autocorrelation of recent values
+///
+/// Keeps the last `window` values in a ring buffer and, every `interval`
+/// values once it is full, picks the lag in `[4, window / 2]` whose
+/// autocorrelation is the highest local peak. Peaks rule out the slow decay
+/// of a merely smooth series; the biased estimator favors the fundamental
+/// over its multiples.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PeriodEstimator {
+    buffer: Vec<f64>,
+    head: usize,
+    seen: usize,
+    interval: usize,
+    period: Option<usize>,
+    strength: f64,
+}
+
+impl PeriodEstimator {
+    pub fn new(window: usize, interval: usize) -> Self {
+        let window = window.max(4 * MIN_PERIOD);
+        Self {
+            buffer: vec![0.0; window],
+            head: 0,
+            seen: 0,
+            interval: interval.max(1),
+            period: None,
+            strength: 0.0,
+        }
+    }
+
+    /// Record a value; returns true when the estimate was refreshed
+    pub fn update(&mut self, value: f64) -> bool {
+        let window = self.buffer.len();
+        self.buffer[self.head] = value;
+        self.head = (self.head + 1) % window;
+        self.seen += 1;
+
+        if self.seen < window || !(self.seen - window).is_multiple_of(self.interval) {
+            return false;
+        }
+        match self.estimate() {
+            Some((period, strength)) => {
+                self.period = Some(period);
+                self.strength = strength;
+            }
+            None => {
+                self.period = None;
+                self.strength = 0.0;
+            }
+        }
+        true
+    }
+
+    /// Last estimated period, `None` while the series shows no season
+    pub fn period(&self) -> Option<usize> {
+        self.period
+    }
+
+    /// Autocorrelation at the estimated period
+    pub fn strength(&self) -> f64 {
+        self.strength
+    }
+
+    /// Buffered values, oldest first
+    pub fn history(&self) -> impl Iterator<Item = f64> + Clone + '_ {
+        let filled = self.seen.min(self.buffer.len());
+        let (newer, older) = self.buffer.split_at(self.head);
+        older
+            .iter()
+            .chain(newer)
+            .skip(self.buffer.len() - filled)
+            .copied()
+    }
+
+    fn estimate(&self) -> Option<(usize, f64)> {
+        let n = self.buffer.len();
+        let at = |i: usize| self.buffer[(self.head + i) % n];
+        let mean = self.buffer.iter().sum::<f64>() / n as f64;
+        let variance: f64 = self.buffer.iter().map(|v| (v - mean).powi(2)).sum();
+        if variance < 1e-12 {
+            return None;
+        }
+        let acf = |lag: usize| {
+            (0..n - lag)
+                .map(|i| (at(i) - mean) * (at(i + lag) - mean))
+                .sum::<f64>()
+                / variance
+        };
+
+        let mut best: Option<(usize, f64)> = None;
+        let (mut before, mut current) = (acf(MIN_PERIOD - 1), acf(MIN_PERIOD));
+        for lag in MIN_PERIOD..=n / 2 {
+            let after = acf(lag + 1);
+            let is_peak = current > before && current >= after;
+            if is_peak
+                && current >= MIN_AUTOCORRELATION
+                && best.is_none_or(|(_, strength)| current > strength)
+            {
+                best = Some((lag, current));
+            }
+            (before, current) = (current, after);
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seasonal(step: usize, period: usize) -> f64 {
+        100.0 + 20.0 * (2.0 * std::f64::consts::PI * step as f64 / period as f64).sin()
+    }
+
+    #[test]
+    fn test_estimates_period() {
+        let mut estimator = PeriodEstimator::new(256, 64);
+        for step in 0..256 {
+            estimator.update(seasonal(step, 24) + (step % 3) as f64);
+        }
+        assert_eq!(estimator.period(), Some(24));
+        assert!(estimator.strength() > 0.8);
+
+        // A flat or steadily trending series has no season
+        let mut flat = PeriodEstimator::new(256, 64);
+        for step in 0..256 {
+            flat.update(step as f64);
+        }
+        assert_eq!(flat.period(), None);
+    }
+
+    #[test]
+    fn test_reseed_predicts_new_season() {
+        let mut hw = HoltWinters::new(0.3, 0.1, 0.1, 10);
+        let mut estimator = PeriodEstimator::new(128, 32);
+        for step in 0..128 {
+            let value = seasonal(step, 16);
+            hw.update(value);
+            estimator.update(value);
+        }
+        assert_eq!(estimator.period(), Some(16));
+
+        hw.reseed(16, estimator.history());
+        assert_eq!(hw.period(), 16);
+        let (predicted, deviation) = hw.update(seasonal(128, 16));
+        assert!(deviation.abs() < 2.0, "predicted {}", predicted);
+    }
+
+    #[test]
+    fn test_merge_sum_aligns_phases() {
+        let mut a = HoltWinters::new(0.3, 0.1, 0.1, 16);
+        let mut b = HoltWinters::new(0.3, 0.1, 0.1, 16);
+        // Two shards of one seasonal stream, `b` a quarter season behind
+        for step in 0..256 {
+            a.update(seasonal(step, 16) / 2.0);
+        }
+        for step in 0..252 {
+            b.update(seasonal(step + 4, 16) / 2.0);
+        }
+        a.merge_sum(&b).unwrap();
+        let (predicted, deviation) = a.update(seasonal(256, 16));
+        assert!(deviation.abs() < 2.0, "predicted {}", predicted);
+
+        let other = HoltWinters::new(0.3, 0.1, 0.1, 8);
+        assert!(
+            a.merge_sum(&other).is_ok(),
+            "a warming-up side adds nothing"
+        );
+        let mut other = other;
+        for step in 0..64 {
+            other.update(seasonal(step, 8));
+        }
+        assert!(a.merge_sum(&other).is_err());
+    }
 }
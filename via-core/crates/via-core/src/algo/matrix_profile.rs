@@ -0,0 +1,217 @@
+//! Streaming Left Matrix Profile for Discord Detection
+//!
+//! For every new subsequence of length `m` (the last `m` values), finds the
+//! z-normalized Euclidean distance to its nearest neighbor among the last
+//! `history` subsequences, skipping trivial matches that overlap it by more
+//! than half. A repeating pattern keeps that distance small; a break in the
+//! pattern (a discord) has no close match and stands out, whatever its
+//! amplitude.
+//!
+//! Dot products with the newest subsequence are updated along the diagonals
+//! as in STOMP: `QT[j] = QT_prev[j-1] - T[j-1]·T[s-1] + T[j+m-1]·T[t]`, so
+//! an update costs O(history + m) instead of O(history · m). They are
+//! recomputed directly once per `history` values so rounding errors do not
+//! accumulate along a diagonal.
+
+use serde::{Deserialize, Serialize};
+
+/// Standard deviation below which a subsequence counts as flat
+const FLAT_STD: f64 = 1e-8;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StreamingMatrixProfile {
+    /// Subsequence length
+    m: usize,
+    /// Past subsequences searched for a match
+    history: usize,
+    /// Matches starting within this many values of the query are trivial
+    exclusion: usize,
+    /// Last `history + m` values, by absolute index modulo the length
+    values: Vec<f64>,
+    /// Per subsequence start (modulo `history`): mean, std and dot product
+    /// with the newest subsequence
+    means: Vec<f64>,
+    stds: Vec<f64>,
+    qt: Vec<f64>,
+    /// Values seen
+    count: u64,
+}
+
+impl StreamingMatrixProfile {
+    pub fn new(m: usize, history: usize) -> Self {
+        let m = m.max(4);
+        let history = history.max(2 * m);
+        Self {
+            m,
+            history,
+            exclusion: m.div_ceil(2),
+            values: vec![0.0; history + m],
+            means: vec![0.0; history],
+            stds: vec![0.0; history],
+            qt: vec![0.0; history],
+            count: 0,
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.m
+    }
+
+    /// Whether the full history of subsequences is available
+    pub fn is_saturated(&self) -> bool {
+        self.count >= (self.history + self.m) as u64
+    }
+
+    /// Add a value; returns the distance of the newest subsequence to its
+    /// nearest non-trivial past match, once one exists
+    ///
+    /// Distances range from 0 (same shape) to `2 * sqrt(m)` (opposite shape).
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        let t = self.count as usize;
+        self.count += 1;
+        let len = self.values.len();
+        self.values[t % len] = value;
+
+        let m = self.m;
+        if t + 1 < m {
+            return None;
+        }
+        let s = t + 1 - m;
+        let slot = s % self.history;
+
+        let (mean, std) = self.moments(s);
+        let lo = (s + 1).saturating_sub(self.history);
+        if s.is_multiple_of(self.history) {
+            for j in lo..s {
+                self.qt[j % self.history] = self.dot(j, s);
+            }
+        } else {
+            let t_prev = self.values[(s + len - 1) % len];
+            for j in (lo..s).rev() {
+                self.qt[j % self.history] = if j == 0 {
+                    self.dot(0, s)
+                } else {
+                    self.qt[(j - 1) % self.history] - self.values[(j - 1) % len] * t_prev
+                        + self.values[(j + m - 1) % len] * value
+                };
+            }
+        }
+        self.qt[slot] = self.dot(s, s);
+        self.means[slot] = mean;
+        self.stds[slot] = std;
+
+        if s < lo + self.exclusion {
+            return None;
+        }
+        let mut best = f64::INFINITY;
+        for j in lo..=(s - self.exclusion) {
+            let k = j % self.history;
+            best = best.min(self.distance(self.qt[k], self.means[k], self.stds[k], mean, std));
+        }
+        Some(best)
+    }
+
+    fn moments(&self, start: usize) -> (f64, f64) {
+        let len = self.values.len();
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+        for i in start..start + self.m {
+            let v = self.values[i % len];
+            sum += v;
+            sum_sq += v * v;
+        }
+        let n = self.m as f64;
+        let mean = sum / n;
+        (mean, (sum_sq / n - mean * mean).max(0.0).sqrt())
+    }
+
+    fn dot(&self, a: usize, b: usize) -> f64 {
+        let len = self.values.len();
+        (0..self.m)
+            .map(|k| self.values[(a + k) % len] * self.values[(b + k) % len])
+            .sum()
+    }
+
+    /// z-normalized distance from the dot product of two subsequences
+    fn distance(&self, qt: f64, mean_a: f64, std_a: f64, mean_b: f64, std_b: f64) -> f64 {
+        let m = self.m as f64;
+        match (std_a < FLAT_STD, std_b < FLAT_STD) {
+            (true, true) => 0.0,
+            (true, false) | (false, true) => m.sqrt(),
+            (false, false) => {
+                let correlation = (qt - m * mean_a * mean_b) / (m * std_a * std_b);
+                (2.0 * m * (1.0 - correlation.clamp(-1.0, 1.0))).sqrt()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Brute-force nearest-neighbor distance of the last subsequence
+    fn naive(series: &[f64], m: usize, history: usize) -> f64 {
+        let znorm = |w: &[f64]| {
+            let mean = w.iter().sum::<f64>() / m as f64;
+            let std = (w.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / m as f64).sqrt();
+            w.iter().map(|v| (v - mean) / std).collect::<Vec<_>>()
+        };
+        let s = series.len() - m;
+        let query = znorm(&series[s..]);
+        (s.saturating_sub(history - 1)..=s - m.div_ceil(2))
+            .map(|j| {
+                let other = znorm(&series[j..j + m]);
+                query
+                    .iter()
+                    .zip(&other)
+                    .map(|(a, b)| (a - b).powi(2))
+                    .sum::<f64>()
+                    .sqrt()
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let series: Vec<f64> = (0..600)
+            .map(|i| (i as f64 * 0.37).sin() * 10.0 + (i * 7919 % 13) as f64)
+            .collect();
+        let mut mp = StreamingMatrixProfile::new(8, 64);
+        for (i, &v) in series.iter().enumerate() {
+            if let Some(d) = mp.update(v) {
+                let expected = naive(&series[..=i], 8, 64);
+                assert!(
+                    (d - expected).abs() < 1e-6,
+                    "at {}: {} vs {}",
+                    i,
+                    d,
+                    expected
+                );
+            }
+        }
+        assert!(mp.is_saturated());
+    }
+
+    #[test]
+    fn test_pattern_break_is_a_discord() {
+        let pattern = [1.0, 3.0, 7.0, 3.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let mut mp = StreamingMatrixProfile::new(10, 100);
+        let mut normal_max = 0.0f64;
+        for i in 0..500 {
+            let d = mp.update(pattern[i % pattern.len()]);
+            // The first cycles have no earlier occurrence to match
+            if i >= 2 * pattern.len() {
+                normal_max = normal_max.max(d.unwrap());
+            }
+        }
+        assert!(normal_max < 1e-3, "repeating pattern: {}", normal_max);
+
+        // Same values, but the peak arrives late once
+        let broken = [1.0, 0.0, 0.0, 3.0, 7.0, 3.0, 1.0, 0.0, 0.0, 0.0];
+        let discord = broken
+            .iter()
+            .filter_map(|&v| mp.update(v))
+            .fold(0.0f64, f64::max);
+        assert!(discord > 1.0, "discord distance {}", discord);
+    }
+}
@@ -1,24 +1,34 @@
 pub mod adaptive_ensemble;
 pub mod adaptive_threshold;
 pub mod behavioral_fingerprint;
+pub mod calibration;
 pub mod cms;
+pub mod ddsketch;
+pub mod decomposition;
 pub mod drift_detector;
 pub mod enhanced_cusum;
 pub mod ewma;
 pub mod histogram;
 pub mod hll;
 pub mod holtwinters;
+pub mod matrix_profile;
 pub mod multi_scale;
 pub mod rrcf;
 pub mod spectral_residual;
+pub mod spot;
 
 // Re-exports for convenience
 pub use adaptive_ensemble::{AdaptiveEnsemble, DetectorOutput};
 pub use adaptive_threshold::{AdaptiveThreshold, ThresholdMethod};
 pub use behavioral_fingerprint::{BehavioralFingerprintDetector, ProfileStore};
+pub use calibration::{CalibrationCurve, CalibrationMethod, CalibrationPoint, ScoreCalibrator};
 pub use cms::CountMinSketch;
+pub use ddsketch::DDSketch;
+pub use decomposition::{Decomposition, SeasonalDecomposition};
 pub use drift_detector::{DriftType, EnsembleDriftDetector};
 pub use enhanced_cusum::{CUSUM, EnhancedCUSUM};
+pub use matrix_profile::StreamingMatrixProfile;
 pub use multi_scale::MultiScaleDetector;
 pub use rrcf::{RRCFDetector, StreamingRRCF};
 pub use spectral_residual::SpectralResidual;
+pub use spot::{Spot, SpotOutcome};
@@ -11,6 +11,7 @@
 use crate::algo::ewma::EWMA;
 use crate::algo::holtwinters::HoltWinters;
 use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
 use std::collections::VecDeque;
 
 /// Time scale for analysis
@@ -84,7 +85,7 @@ impl ScaleDetector {
             window_sum: 0.0,
             window_count: 0,
             has_seasonality: false,
-            fourier_coeffs: vec![],
+            fourier_coeffs: Vec::with_capacity(8),
         }
     }
 
@@ -133,11 +134,11 @@ impl ScaleDetector {
             (ewma_val, (value - ewma_val).abs())
         };
 
-        // Update buffer for Fourier analysis
-        self.value_buffer.push_back(value);
-        if self.value_buffer.len() > self.buffer_size {
+        // Update buffer for Fourier analysis (evict first so it never grows)
+        if self.value_buffer.len() >= self.buffer_size {
             self.value_buffer.pop_front();
         }
+        self.value_buffer.push_back(value);
 
         // Periodically compute Fourier coefficients
         if self.value_buffer.len() >= self.buffer_size && self.fourier_coeffs.is_empty() {
@@ -205,21 +206,20 @@ impl ScaleDetector {
             return;
         }
 
-        let data: Vec<f64> = self.value_buffer.iter().copied().collect();
-        let mut coeffs = Vec::new();
+        // Reuse the coefficient buffer across recomputes
+        let coeffs = &mut self.fourier_coeffs;
+        coeffs.clear();
 
         // Compute first few DFT coefficients
         for k in 0..(n / 2).min(8) {
             let (mut re, mut im) = (0.0, 0.0);
-            for (i, &x) in data.iter().enumerate() {
+            for (i, &x) in self.value_buffer.iter().enumerate() {
                 let angle = -2.0 * std::f64::consts::PI * (k as f64) * (i as f64) / (n as f64);
                 re += x * angle.cos();
                 im += x * angle.sin();
             }
             coeffs.push((re, im));
         }
-
-        self.fourier_coeffs = coeffs;
     }
 
     fn get_stats(&self) -> (usize, f64, bool) {
@@ -254,7 +254,7 @@ pub struct MultiScaleDetector {
 pub struct MultiScaleResult {
     pub combined_score: f64,
     pub is_anomaly: bool,
-    pub active_scales: Vec<(TimeScale, f64, bool)>, // (scale, score, has_seasonality)
+    pub active_scales: SmallVec<[(TimeScale, f64, bool); 4]>, // (scale, score, has_seasonality)
     pub primary_scale: Option<TimeScale>,
     pub has_seasonality: bool,
 }
@@ -267,7 +267,7 @@ impl MultiScaleDetector {
             hour_level: ScaleDetector::new(TimeScale::Hour),
             day_level: ScaleDetector::new(TimeScale::Day),
             combined_score: 0.0,
-            active_scales: vec![],
+            active_scales: Vec::with_capacity(4),
             last_timestamp: 0,
             sample_count: 0,
         }
@@ -278,7 +278,7 @@ impl MultiScaleDetector {
         self.sample_count += 1;
         self.last_timestamp = timestamp_ns;
 
-        let mut scale_results = vec![];
+        let mut scale_results = SmallVec::new();
         let mut max_score = 0.0;
         let mut primary_scale = None;
         let mut any_seasonality = false;
@@ -333,7 +333,9 @@ impl MultiScaleDetector {
             self.combined_score = (self.combined_score * 1.2).min(1.0);
         }
 
-        self.active_scales = scale_results.iter().map(|(s, _, _)| *s).collect();
+        self.active_scales.clear();
+        self.active_scales
+            .extend(scale_results.iter().map(|(s, _, _)| *s));
 
         MultiScaleResult {
             combined_score: self.combined_score,
@@ -14,150 +14,76 @@
 //! (Guha et al., KDD 2016)
 
 use rand::Rng;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
-use std::sync::Arc;
 
-// --- Serde Helpers for Arc<[f64]> ---
-
-mod serde_arc {
-    use super::*;
-    use serde::ser::SerializeSeq;
-
-    pub fn serialize<S>(data: &Arc<[f64]>, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let mut seq = serializer.serialize_seq(Some(data.len()))?;
-        for e in data.iter() {
-            seq.serialize_element(e)?;
-        }
-        seq.end()
-    }
-
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<Arc<[f64]>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let vec: Vec<f64> = Vec::deserialize(deserializer)?;
-        Ok(vec.into())
-    }
-}
-
-mod serde_points {
-    use super::*;
-    use serde::ser::SerializeSeq;
-
-    pub fn serialize<S>(
-        data: &VecDeque<(u64, Arc<[f64]>)>,
-        serializer: S,
-    ) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let mut seq = serializer.serialize_seq(Some(data.len()))?;
-        for (id, point) in data {
-            // Helper struct to match the desired tuple format but with custom serialization
-            #[derive(Serialize)]
-            struct PointHelper<'a> {
-                id: u64,
-                #[serde(with = "serde_arc")]
-                point: &'a Arc<[f64]>,
-            }
-            // Serialize as a tuple (u64, Arc<[f64]>)
-            // Note: Tuple serialization in serde expects ordered elements.
-            // We can manually serialize a tuple variant.
-            let _helper = PointHelper { id: *id, point };
-            // To match Vec<(u64, Arc<[f64]>)> we need to serialize as a tuple.
-            // But helper struct serializes as a map/struct usually?
-            // No, strictly speaking we just want to serialize the elements.
-            // Let's simpler serialize as a tuple:
-            seq.serialize_element(&(*id, Helper(point)))?;
-        }
-        seq.end()
-    }
-
-    // Helper wrapper to apply serde_arc to the second element of the tuple
-    struct Helper<'a>(&'a Arc<[f64]>);
-    impl<'a> Serialize for Helper<'a> {
-        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-        where
-            S: Serializer,
-        {
-            serde_arc::serialize(self.0, serializer)
-        }
-    }
-
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<VecDeque<(u64, Arc<[f64]>)>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        // Helper struct to deserialize the tuple
-        struct TupleVisitor;
-
-        impl<'de> serde::de::Visitor<'de> for TupleVisitor {
-            type Value = VecDeque<(u64, Arc<[f64]>)>;
-
-            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("a sequence of (id, point) tuples")
-            }
-
-            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-            where
-                A: serde::de::SeqAccess<'de>,
-            {
-                let mut vec = VecDeque::new();
-                while let Some((id, point_wrapper)) = seq.next_element::<(u64, PointWrapper)>()? {
-                    vec.push_back((id, point_wrapper.0));
-                }
-                Ok(vec)
-            }
-        }
-
-        // Wrapper to use serde_arc for deserialization
-        struct PointWrapper(Arc<[f64]>);
-        impl<'de> Deserialize<'de> for PointWrapper {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-            where
-                D: Deserializer<'de>,
-            {
-                serde_arc::deserialize(deserializer).map(PointWrapper)
-            }
-        }
-
-        deserializer.deserialize_seq(TupleVisitor)
-    }
-}
-
-/// A node in the RRCF tree
+/// A node in an RRCF tree arena
+///
+/// Children are indices into [`RcTree::nodes`]; internal bounding boxes live
+/// in [`RcTree::bbox`] and leaf points in the forest's [`PointStore`], so a
+/// full tree recycles its slots instead of allocating per insert.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 enum RcNode {
     /// Internal node with cut dimension and value
     Internal {
         cut_dim: usize,
         cut_value: f64,
-        left: Box<RcNode>,
-        right: Box<RcNode>,
-        /// Bounding box for this subtree
-        bbox_min: Box<[f64]>,
-        bbox_max: Box<[f64]>,
+        left: usize,
+        right: usize,
         /// Number of points in subtree
         num_points: usize,
     },
-    /// Leaf node containing a point
+    /// Leaf node referencing a point in the store
     Leaf {
-        #[serde(with = "serde_arc")]
-        point: Arc<[f64]>,
         /// Unique identifier for this point
         point_id: u64,
     },
 }
 
+/// Ring buffer of the points the trees currently hold, keyed by point ID
+///
+/// Every tree holds the same `tree_size` most recent points, so one slot more
+/// than that keeps an evicted point readable while the new one is inserted.
+#[derive(Serialize, Deserialize, Clone)]
+struct PointStore {
+    dims: usize,
+    slots: usize,
+    data: Vec<f64>,
+}
+
+impl PointStore {
+    fn new(dims: usize, tree_size: usize) -> Self {
+        let slots = tree_size + 1;
+        Self {
+            dims,
+            slots,
+            data: vec![0.0; dims * slots],
+        }
+    }
+
+    fn get(&self, point_id: u64) -> &[f64] {
+        let start = (point_id % self.slots as u64) as usize * self.dims;
+        &self.data[start..start + self.dims]
+    }
+
+    /// Store `point`, truncated or zero-padded to `dims` values
+    fn put(&mut self, point_id: u64, point: impl IntoIterator<Item = f64>) {
+        let start = (point_id % self.slots as u64) as usize * self.dims;
+        let slot = &mut self.data[start..start + self.dims];
+        slot.fill(0.0);
+        for (dst, v) in slot.iter_mut().zip(point) {
+            *dst = v;
+        }
+    }
+}
+
 /// Robust Random Cut Forest
 #[derive(Serialize, Deserialize, Clone)]
 pub struct StreamingRRCF {
     /// Forest of trees
     trees: Vec<RcTree>,
+    /// Points held by the trees
+    store: PointStore,
     /// Window size for streaming (sliding window)
     window_size: usize,
     /// Shingle buffer for time series embedding
@@ -183,331 +109,309 @@ pub struct StreamingRRCF {
 /// A single RRCF tree
 #[derive(Serialize, Deserialize, Clone)]
 struct RcTree {
-    root: Option<RcNode>,
-    /// Points currently in this tree (id -> point)
-    #[serde(with = "serde_points")]
-    points: VecDeque<(u64, Arc<[f64]>)>,
+    /// Node arena
+    nodes: Vec<RcNode>,
+    /// Parent of each arena slot (`None` for the root)
+    parents: Vec<Option<usize>>,
+    /// Arena slots freed by deletions, reused before growing
+    free: Vec<usize>,
+    /// Per-slot bounding box: `dims` minimums then `dims` maximums
+    bbox: Vec<f64>,
+    dims: usize,
+    root: Option<usize>,
+    /// IDs of the points currently in this tree, oldest first
+    points: VecDeque<u64>,
+    /// Leaf slot of each point, indexed by `point_id % (max_size + 1)`
+    leaves: Vec<usize>,
     /// Maximum points this tree can hold
     max_size: usize,
 }
 
 impl RcTree {
-    fn new(max_size: usize) -> Self {
+    fn new(max_size: usize, dims: usize) -> Self {
+        // A full tree has `max_size` leaves and `max_size - 1` internal nodes
+        let capacity = 2 * max_size;
         Self {
+            nodes: Vec::with_capacity(capacity),
+            parents: Vec::with_capacity(capacity),
+            free: Vec::with_capacity(capacity),
+            bbox: Vec::with_capacity(capacity * 2 * dims),
+            dims,
             root: None,
             points: VecDeque::with_capacity(max_size),
+            leaves: vec![0; max_size + 1],
             max_size,
         }
     }
 
-    /// Insert a point into the tree
-    fn insert(&mut self, point_id: u64, point: Arc<[f64]>) -> Option<(u64, Arc<[f64]>)> {
+    /// Insert a stored point into the tree, evicting the oldest when full
+    fn insert(&mut self, store: &PointStore, point_id: u64) -> Option<u64> {
         // If tree is full, need to evict oldest
         let evicted = if self.points.len() >= self.max_size {
             // FIFO eviction (oldest point)
-            if let Some(evicted) = self.points.pop_front() {
-                self.delete_point(&evicted.1);
-                Some(evicted)
-            } else {
-                None
+            let evicted = self.points.pop_front();
+            if let Some(id) = evicted {
+                self.delete_point(id);
             }
+            evicted
         } else {
             None
         };
 
         // Insert new point
-        self.points.push_back((point_id, point.clone()));
-        self.root = Some(insert_recursive(self.root.take(), point_id, point));
+        self.points.push_back(point_id);
+        let point = store.get(point_id);
+        self.root = Some(match self.root {
+            None => self.alloc(RcNode::Leaf { point_id }, None),
+            Some(root) => self.insert_at(root, store, point_id, point),
+        });
 
         evicted
     }
 
-    /// Delete a point from the tree
-    fn delete_point(&mut self, point: &[f64]) {
-        self.root = delete_recursive(self.root.take(), point);
+    /// Delete a point from the tree; its sibling takes its parent's place
+    fn delete_point(&mut self, point_id: u64) {
+        let leaf = self.leaves[self.leaf_slot(point_id)];
+        self.free.push(leaf);
+
+        let Some(parent) = self.parents[leaf] else {
+            self.root = None;
+            return;
+        };
+        let RcNode::Internal { left, right, .. } = self.nodes[parent] else {
+            unreachable!("parent of a leaf is an internal node");
+        };
+        let sibling = if left == leaf { right } else { left };
+        let grandparent = self.parents[parent];
+        self.parents[sibling] = grandparent;
+        self.free.push(parent);
+
+        let Some(grandparent) = grandparent else {
+            self.root = Some(sibling);
+            return;
+        };
+        if let RcNode::Internal { left, right, .. } = &mut self.nodes[grandparent] {
+            if *left == parent {
+                *left = sibling;
+            } else {
+                *right = sibling;
+            }
+        }
+
+        // Every remaining ancestor lost one point
+        let mut ancestor = Some(grandparent);
+        while let Some(idx) = ancestor {
+            if let RcNode::Internal { num_points, .. } = &mut self.nodes[idx] {
+                *num_points = num_points.saturating_sub(1);
+            }
+            ancestor = self.parents[idx];
+        }
     }
 
     /// Compute codisp (collusive displacement) for a point
     /// This is the anomaly score - higher means more anomalous
     fn codisp(&self, point: &[f64]) -> f64 {
-        if self.root.is_none() || self.points.is_empty() {
-            return 0.0;
+        match self.root {
+            Some(root) if !self.points.is_empty() => self.compute_codisp(root, point),
+            _ => 0.0,
         }
-        compute_codisp(self.root.as_ref(), point)
     }
 
     /// Get number of points in tree
     fn size(&self) -> usize {
         self.points.len()
     }
-}
 
-/// Recursive insertion with proper bounding box updates
-fn insert_recursive(node: Option<RcNode>, point_id: u64, point: Arc<[f64]>) -> RcNode {
-    match node {
-        None => RcNode::Leaf { point, point_id },
-        Some(RcNode::Leaf {
-            point: existing_point,
-            point_id: existing_id,
-        }) => {
-            // Split leaf into internal node
-            split_leaf(existing_point, existing_id, point, point_id)
+    fn leaf_slot(&self, point_id: u64) -> usize {
+        (point_id % self.leaves.len() as u64) as usize
+    }
+
+    /// Place `node` in a free slot, growing the arena only when none is left
+    fn alloc(&mut self, node: RcNode, parent: Option<usize>) -> usize {
+        let point_id = match node {
+            RcNode::Leaf { point_id } => Some(point_id),
+            RcNode::Internal { .. } => None,
+        };
+        let idx = if let Some(idx) = self.free.pop() {
+            self.nodes[idx] = node;
+            self.parents[idx] = parent;
+            idx
+        } else {
+            self.nodes.push(node);
+            self.parents.push(parent);
+            self.bbox.extend(std::iter::repeat_n(0.0, 2 * self.dims));
+            self.nodes.len() - 1
+        };
+        if let Some(point_id) = point_id {
+            let slot = self.leaf_slot(point_id);
+            self.leaves[slot] = idx;
         }
-        Some(RcNode::Internal {
-            cut_dim,
-            cut_value,
-            left,
-            right,
-            mut bbox_min,
-            mut bbox_max,
-            num_points,
-        }) => {
-            // Update bounding box to include new point
-            for (i, &v) in point.iter().enumerate() {
-                if i < bbox_min.len() {
+        idx
+    }
+
+    fn bbox(&self, idx: usize) -> (&[f64], &[f64]) {
+        let start = idx * 2 * self.dims;
+        self.bbox[start..start + 2 * self.dims].split_at(self.dims)
+    }
+
+    fn bbox_mut(&mut self, idx: usize) -> (&mut [f64], &mut [f64]) {
+        let start = idx * 2 * self.dims;
+        self.bbox[start..start + 2 * self.dims].split_at_mut(self.dims)
+    }
+
+    /// Insert below `idx`, returning the index that replaces it in its parent
+    fn insert_at(&mut self, idx: usize, store: &PointStore, point_id: u64, point: &[f64]) -> usize {
+        match self.nodes[idx] {
+            RcNode::Leaf {
+                point_id: existing_id,
+            } => {
+                // Split leaf into internal node
+                self.split_leaf(idx, store.get(existing_id), point_id, point)
+            }
+            RcNode::Internal {
+                cut_dim,
+                cut_value,
+                left,
+                right,
+                num_points,
+            } => {
+                // Update bounding box to include new point
+                let (bbox_min, bbox_max) = self.bbox_mut(idx);
+                for (i, &v) in point.iter().enumerate() {
                     bbox_min[i] = bbox_min[i].min(v);
                     bbox_max[i] = bbox_max[i].max(v);
                 }
-            }
 
-            if point.get(cut_dim).copied().unwrap_or(0.0) <= cut_value {
-                let new_left = Box::new(insert_recursive(Some(*left), point_id, point));
-                RcNode::Internal {
-                    cut_dim,
-                    cut_value,
-                    left: new_left,
-                    right,
-                    bbox_min,
-                    bbox_max,
-                    num_points: num_points + 1,
-                }
-            } else {
-                let new_right = Box::new(insert_recursive(Some(*right), point_id, point));
-                RcNode::Internal {
+                let (left, right) = if point[cut_dim] <= cut_value {
+                    (self.insert_at(left, store, point_id, point), right)
+                } else {
+                    (left, self.insert_at(right, store, point_id, point))
+                };
+                self.nodes[idx] = RcNode::Internal {
                     cut_dim,
                     cut_value,
                     left,
-                    right: new_right,
-                    bbox_min,
-                    bbox_max,
+                    right,
                     num_points: num_points + 1,
-                }
+                };
+                idx
             }
         }
     }
-}
 
-/// Split a leaf node into an internal node with random cut
-fn split_leaf(p1: Arc<[f64]>, id1: u64, p2: Arc<[f64]>, id2: u64) -> RcNode {
-    let dims = p1.len();
-    if dims == 0 {
-        return RcNode::Leaf {
-            point: p1,
-            point_id: id1,
+    /// Split leaf `leaf` (holding `p1`) with a random cut separating it from
+    /// the new point `p2`, returning the new internal node
+    fn split_leaf(&mut self, leaf: usize, p1: &[f64], id2: u64, p2: &[f64]) -> usize {
+        let dims = self.dims;
+
+        // Choose dimension with probability proportional to range
+        let range = |i: usize| p1[i].max(p2[i]) - p1[i].min(p2[i]);
+        let total_range: f64 = (0..dims).map(range).sum();
+        let cut_dim = if total_range > 1e-10 {
+            let mut r = rand::rng().random::<f64>() * total_range;
+            // Rounding can leave `r` positive; fall back to the widest range
+            let mut chosen = (0..dims)
+                .max_by(|&a, &b| range(a).partial_cmp(&range(b)).unwrap())
+                .unwrap_or(0);
+            for dim in 0..dims {
+                r -= range(dim);
+                if r <= 0.0 {
+                    chosen = dim;
+                    break;
+                }
+            }
+            chosen
+        } else {
+            // All dimensions are equal, pick random
+            rand::rng().random_range(0..dims)
         };
-    }
 
-    // Calculate ranges for each dimension
-    let mut ranges: Vec<(usize, f64)> = (0..dims)
-        .map(|i| {
-            let min = p1[i].min(p2[i]);
-            let max = p1[i].max(p2[i]);
-            (i, max - min)
-        })
-        .collect();
-
-    // Sort by range (largest first) for better cuts
-    ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-
-    // Choose dimension with probability proportional to range
-    let total_range: f64 = ranges.iter().map(|(_, r)| r).sum();
-    let cut_dim = if total_range > 1e-10 {
-        let mut r = rand::rng().random::<f64>() * total_range;
-        let mut chosen = ranges[0].0;
-        for (dim, range) in &ranges {
-            r -= range;
-            if r <= 0.0 {
-                chosen = *dim;
-                break;
-            }
-        }
-        chosen
-    } else {
-        // All dimensions are equal, pick random
-        rand::rng().random_range(0..dims)
-    };
-
-    // Random cut value between the two points
-    let min_val = p1[cut_dim].min(p2[cut_dim]);
-    let max_val = p1[cut_dim].max(p2[cut_dim]);
-    let cut_value = if (max_val - min_val).abs() < 1e-10 {
-        min_val
-    } else {
-        min_val + rand::rng().random::<f64>() * (max_val - min_val)
-    };
-
-    // Create bounding box
-    let mut bbox_min = Vec::with_capacity(dims);
-    let mut bbox_max = Vec::with_capacity(dims);
-    for i in 0..dims {
-        bbox_min.push(p1[i].min(p2[i]));
-        bbox_max.push(p1[i].max(p2[i]));
-    }
-
-    // Create children based on cut
-    let (left_leaf, right_leaf) = if p1[cut_dim] <= cut_value {
-        (
-            RcNode::Leaf {
-                point: p1,
-                point_id: id1,
-            },
-            RcNode::Leaf {
-                point: p2,
-                point_id: id2,
-            },
-        )
-    } else {
-        (
-            RcNode::Leaf {
-                point: p2,
-                point_id: id2,
-            },
-            RcNode::Leaf {
-                point: p1,
-                point_id: id1,
+        // Random cut value between the two points
+        let min_val = p1[cut_dim].min(p2[cut_dim]);
+        let max_val = p1[cut_dim].max(p2[cut_dim]);
+        let cut_value = if (max_val - min_val).abs() < 1e-10 {
+            min_val
+        } else {
+            min_val + rand::rng().random::<f64>() * (max_val - min_val)
+        };
+
+        // The new internal node takes the leaf's place under its parent
+        let parent = self.parents[leaf];
+        let internal = self.alloc(
+            RcNode::Internal {
+                cut_dim,
+                cut_value,
+                left: leaf,
+                right: leaf,
+                num_points: 2,
             },
-        )
-    };
-
-    RcNode::Internal {
-        cut_dim,
-        cut_value,
-        left: Box::new(left_leaf),
-        right: Box::new(right_leaf),
-        bbox_min: bbox_min.into_boxed_slice(),
-        bbox_max: bbox_max.into_boxed_slice(),
-        num_points: 2,
-    }
-}
+            parent,
+        );
+        let new_leaf = self.alloc(RcNode::Leaf { point_id: id2 }, Some(internal));
+        self.parents[leaf] = Some(internal);
 
-/// Recursive deletion
-fn delete_recursive(node: Option<RcNode>, point: &[f64]) -> Option<RcNode> {
-    match node {
-        None => None,
-        Some(RcNode::Leaf {
-            point: leaf_point,
-            point_id,
-        }) => {
-            // Check if this is the point to delete (approximate match)
-            let is_match = leaf_point
-                .iter()
-                .zip(point.iter())
-                .all(|(a, b)| (a - b).abs() < 1e-10);
-            if is_match {
-                None
-            } else {
-                Some(RcNode::Leaf {
-                    point: leaf_point,
-                    point_id,
-                })
-            }
-        }
-        Some(RcNode::Internal {
-            cut_dim,
-            cut_value,
-            left,
-            right,
-            bbox_min,
-            bbox_max,
-            num_points,
-        }) => {
-            // Determine which subtree to delete from
-            let go_left = point.get(cut_dim).copied().unwrap_or(0.0) <= cut_value;
-
-            let (new_left, new_right) = if go_left {
-                (delete_recursive(Some(*left), point), Some(*right))
+        // Create children based on cut
+        if let RcNode::Internal { left, right, .. } = &mut self.nodes[internal] {
+            if p1[cut_dim] <= cut_value {
+                *right = new_leaf;
             } else {
-                (Some(*left), delete_recursive(Some(*right), point))
-            };
-
-            // If one child is None, return the other (tree collapse)
-            match (new_left, new_right) {
-                (None, None) => None,
-                (Some(l), None) => Some(l),
-                (None, Some(r)) => Some(r),
-                (Some(l), Some(r)) => {
-                    let new_count = num_points.saturating_sub(1);
-                    Some(RcNode::Internal {
-                        cut_dim,
-                        cut_value,
-                        left: Box::new(l),
-                        right: Box::new(r),
-                        bbox_min,
-                        bbox_max,
-                        num_points: new_count,
-                    })
-                }
+                *left = new_leaf;
             }
         }
-    }
-}
 
-/// Compute CoDisp (Collusive Displacement) score for a point
-/// Higher score = more anomalous
-fn compute_codisp(node: Option<&RcNode>, point: &[f64]) -> f64 {
-    match node {
-        None => 0.0,
-        Some(RcNode::Leaf { .. }) => {
-            // Reached a leaf - base case
-            1.0
+        // Create bounding box
+        let (bbox_min, bbox_max) = self.bbox_mut(internal);
+        for i in 0..dims {
+            bbox_min[i] = p1[i].min(p2[i]);
+            bbox_max[i] = p1[i].max(p2[i]);
         }
-        Some(RcNode::Internal {
-            cut_dim,
-            cut_value,
-            left,
-            right,
-            num_points,
-            bbox_min,
-            bbox_max,
-            ..
-        }) => {
-            let point_val = point.get(*cut_dim).copied().unwrap_or(0.0);
-            let _n = *num_points as f64;
-
-            // Check if point would displace sibling subtree
-            let (next_node, sibling) = if point_val <= *cut_value {
-                (left.as_ref(), right.as_ref())
-            } else {
-                (right.as_ref(), left.as_ref())
-            };
-
-            // Check if point is outside the bounding box
-            let mut is_outside = false;
-            for (i, &v) in point.iter().enumerate() {
-                if i < bbox_min.len() && (v < bbox_min[i] - 1e-10 || v > bbox_max[i] + 1e-10) {
-                    is_outside = true;
-                    break;
+        internal
+    }
+
+    /// Compute CoDisp (Collusive Displacement) score for a point
+    /// Higher score = more anomalous
+    fn compute_codisp(&self, root: usize, point: &[f64]) -> f64 {
+        let mut score = 0.0;
+        let mut idx = root;
+        loop {
+            match self.nodes[idx] {
+                // Reached a leaf - base case
+                RcNode::Leaf { .. } => return score + 1.0,
+                RcNode::Internal {
+                    cut_dim,
+                    cut_value,
+                    left,
+                    right,
+                    ..
+                } => {
+                    // Check if point would displace sibling subtree
+                    let (next, sibling) = if point[cut_dim] <= cut_value {
+                        (left, right)
+                    } else {
+                        (right, left)
+                    };
+
+                    // Point outside the bounding box displaces the sibling
+                    let (bbox_min, bbox_max) = self.bbox(idx);
+                    let is_outside = point
+                        .iter()
+                        .zip(bbox_min.iter().zip(bbox_max))
+                        .any(|(&v, (&lo, &hi))| v < lo - 1e-10 || v > hi + 1e-10);
+                    if is_outside {
+                        score += self.subtree_size(sibling) as f64;
+                    }
+                    idx = next;
                 }
             }
-
-            if is_outside {
-                // Point is outside bbox - high displacement
-                let sibling_size = get_subtree_size(sibling) as f64;
-                // Displacement score: sibling size / depth
-                sibling_size + compute_codisp(Some(next_node), point)
-            } else {
-                // Point is inside bbox - continue recursively
-                compute_codisp(Some(next_node), point)
-            }
         }
     }
-}
 
-/// Get the size of a subtree
-fn get_subtree_size(node: &RcNode) -> usize {
-    match node {
-        RcNode::Leaf { .. } => 1,
-        RcNode::Internal { num_points, .. } => *num_points,
+    /// Get the size of a subtree
+    fn subtree_size(&self, idx: usize) -> usize {
+        match self.nodes[idx] {
+            RcNode::Leaf { .. } => 1,
+            RcNode::Internal { num_points, .. } => num_points,
+        }
     }
 }
 
@@ -524,15 +428,17 @@ impl StreamingRRCF {
         let t_size = tree_size.max(16).min(1024);
         let shingle = shingle_size.max(1);
 
-        let trees: Vec<RcTree> = (0..n_trees).map(|_| RcTree::new(t_size)).collect();
+        let dims = dimensions.max(1);
+        let trees: Vec<RcTree> = (0..n_trees).map(|_| RcTree::new(t_size, dims)).collect();
 
         Self {
             trees,
+            store: PointStore::new(dims, t_size),
             window_size: t_size * 2,
             shingle_buffer: VecDeque::with_capacity(shingle),
             shingle_size: shingle,
             next_point_id: 1,
-            dimensions: dimensions.max(1),
+            dimensions: dims,
             num_trees: n_trees,
             tree_size: t_size,
             baseline_codisp: 0.0,
@@ -564,39 +470,43 @@ impl StreamingRRCF {
             return (0.0, false);
         }
 
-        // Flatten shingle into Arc<[f64]>
-        let point: Arc<[f64]> = self
-            .shingle_buffer
-            .iter()
-            .copied()
-            .collect::<Vec<_>>()
-            .into();
-
-        self.update_multivariate_arc(point)
+        let point_id = self.next_point_id();
+        self.store
+            .put(point_id, self.shingle_buffer.iter().copied());
+        self.score_and_insert(point_id)
     }
 
     /// Update with new vector (multivariate)
-    pub fn update_multivariate(&mut self, point: Vec<f64>) -> (f64, bool) {
-        self.update_multivariate_arc(point.into())
+    ///
+    /// Vectors are truncated or zero-padded to the forest's dimensionality.
+    pub fn update_multivariate(&mut self, point: impl IntoIterator<Item = f64>) -> (f64, bool) {
+        let point_id = self.next_point_id();
+        self.store.put(point_id, point);
+        self.score_and_insert(point_id)
     }
 
-    /// Update with shared Arc (zero-allocation for trees)
-    pub fn update_multivariate_arc(&mut self, point: Arc<[f64]>) -> (f64, bool) {
+    fn next_point_id(&mut self) -> u64 {
         let point_id = self.next_point_id;
         self.next_point_id += 1;
+        point_id
+    }
+
+    /// Score a stored point against the forest, then insert it into every tree
+    fn score_and_insert(&mut self, point_id: u64) -> (f64, bool) {
         self.sample_count += 1;
+        let point = self.store.get(point_id);
 
         // Compute codisp before insertion (anomaly score)
-        let codisp_sum: f64 = self.trees.iter().map(|tree| tree.codisp(&point)).sum();
+        let codisp_sum: f64 = self.trees.iter().map(|tree| tree.codisp(point)).sum();
         let avg_codisp = if self.num_trees > 0 {
             codisp_sum / self.num_trees as f64
         } else {
             0.0
         };
 
-        // Insert into all trees (Arc cloning is cheap)
+        // Insert into all trees (they share the stored point)
         for tree in &mut self.trees {
-            tree.insert(point_id, point.clone());
+            tree.insert(&self.store, point_id);
         }
 
         // Update baseline using EWMA (for adaptive thresholding)
@@ -634,7 +544,7 @@ impl StreamingRRCF {
     /// Reset the forest
     pub fn reset(&mut self) {
         self.trees = (0..self.num_trees)
-            .map(|_| RcTree::new(self.tree_size))
+            .map(|_| RcTree::new(self.tree_size, self.dimensions))
             .collect();
         self.shingle_buffer.clear();
         self.next_point_id = 1;
@@ -644,6 +554,7 @@ impl StreamingRRCF {
 }
 
 /// RRCF-based detector for integration with engine
+#[derive(Serialize, Deserialize, Clone)]
 pub struct RRCFDetector {
     rrcf: StreamingRRCF,
     threshold: f64,
@@ -669,7 +580,7 @@ impl RRCFDetector {
         (score, score > self.threshold)
     }
 
-    pub fn update_vector(&mut self, vector: Vec<f64>) -> (f64, bool) {
+    pub fn update_vector(&mut self, vector: impl IntoIterator<Item = f64>) -> (f64, bool) {
         let (score, _) = self.rrcf.update_multivariate(vector);
         (score, score > self.threshold)
     }
@@ -739,6 +650,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_eviction_keeps_trees_consistent() {
+        let mut rrcf = StreamingRRCF::univariate(4, 32, 4);
+
+        // Repeated values exercise splits between identical points
+        for i in 0..500 {
+            rrcf.update_univariate(100.0 + (i % 7 / 3) as f64);
+        }
+
+        for tree in &rrcf.trees {
+            let root = tree.root.unwrap();
+            assert_eq!(tree.size(), 32);
+            assert_eq!(tree.subtree_size(root), 32);
+            // 32 leaves and 31 internal nodes, with freed slots recycled
+            assert_eq!(tree.nodes.len() - tree.free.len(), 63);
+            assert!(tree.nodes.len() <= 64);
+        }
+    }
+
     #[test]
     fn test_detector_wrapper() {
         let mut detector = RRCFDetector::new_univariate(4);
@@ -7,17 +7,36 @@
 //! Key advantages:
 //! - Zero hyperparameters (fully automatic)
 //! - Works on any time series without tuning
-//! - FFT-based, O(n log n) complexity using Cooley-Tukey algorithm
+//! - FFT-based, O(n log n) complexity
 //! - Robust to noise and seasonality
 //!
 //! Performance optimizations:
-//! - Cooley-Tukey radix-2 FFT: O(n log n) vs naive DFT O(n²)
-//! - Power-of-2 padding for cache-friendly memory access
-//! - Pre-computed twiddle factors for repeated FFTs
-//! - In-place butterfly operations to minimize allocations
+//! - `rustfft` plans for the exact window length (no padding, so the
+//!   spectrum matches a plain DFT), shared across detectors of the same size
+//! - Transform and scratch buffers reused, so steady-state updates do not
+//!   allocate
+//! - SIMD kernels (AVX/SSE/NEON, picked at runtime) with feature `simd`
+//! - [`SpectralResidual::update_batch`] runs one transform per batch
+//!
+//! [`FftContext`] and [`FastSpectralResidual`] predate the planner and are
+//! kept as thin wrappers over it.
 
+use once_cell::sync::Lazy;
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Process-wide planner, so detectors with equal windows share one plan
+static FFT_PLANNER: Lazy<Mutex<FftPlanner<f64>>> = Lazy::new(|| Mutex::new(FftPlanner::new()));
+
+fn plan_fft(len: usize) -> Arc<dyn Fft<f64>> {
+    FFT_PLANNER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .plan_fft_forward(len)
+}
 
 /// Spectral Residual Anomaly Detection
 #[derive(Serialize, Deserialize, Clone)]
@@ -41,6 +60,17 @@ pub struct SpectralResidual {
     min_score_seen: f64,
     max_score_seen: f64,
     sample_count: u64,
+
+    // FFT plan (planned on first use, also after a restore) and reused
+    // transform buffers, so steady-state updates do not allocate
+    #[serde(skip)]
+    fft: Option<Arc<dyn Fft<f64>>>,
+    #[serde(skip)]
+    fft_buf: Vec<Complex<f64>>,
+    #[serde(skip)]
+    fft_scratch: Vec<Complex<f64>>,
+    #[serde(skip)]
+    spectrum_buf: Vec<f64>,
 }
 
 impl SpectralResidual {
@@ -64,6 +94,10 @@ impl SpectralResidual {
             min_score_seen: f64::MAX,
             max_score_seen: f64::MIN,
             sample_count: 0,
+            fft: None,
+            fft_buf: Vec::with_capacity(ws),
+            fft_scratch: Vec::new(),
+            spectrum_buf: Vec::with_capacity(ws / 2 + 1),
         }
     }
 
@@ -73,7 +107,33 @@ impl SpectralResidual {
     /// - anomaly_score: 0.0 (normal) to 1.0+ (anomalous), higher = more anomalous
     /// - is_anomaly: true if exceeds adaptive threshold
     pub fn update(&mut self, value: f64) -> (f64, bool) {
-        // Add value to window
+        self.push(value);
+
+        // Performance: Only run full spectral analysis every N events unless it's the first window
+        // This amortizes the transform cost without losing much signal
+        if self.sample_count > self.window_size as u64 && !self.sample_count.is_multiple_of(5) {
+            return (0.0, false);
+        }
+
+        self.score_window()
+    }
+
+    /// Add several values and score only the window ending at the last one
+    ///
+    /// One transform for the whole batch instead of one per fifth event, for
+    /// hosts that receive values in chunks and only act on the latest state.
+    /// An empty batch scores nothing.
+    pub fn update_batch(&mut self, values: &[f64]) -> (f64, bool) {
+        if values.is_empty() {
+            return (0.0, false);
+        }
+        for &value in values {
+            self.push(value);
+        }
+        self.score_window()
+    }
+
+    fn push(&mut self, value: f64) {
         self.window.push_back(value);
         self.sample_count += 1;
 
@@ -81,13 +141,10 @@ impl SpectralResidual {
         while self.window.len() > self.window_size {
             self.window.pop_front();
         }
+    }
 
-        // Performance: Only run full spectral analysis every N events unless it's the first window
-        // This amortizes the O(N^2) cost without losing much signal
-        if self.sample_count > self.window_size as u64 && self.sample_count % 5 != 0 {
-            return (0.0, false);
-        }
-
+    /// Score the current window and adapt the threshold to it
+    fn score_window(&mut self) -> (f64, bool) {
         // Wait for full window
         if self.window.len() < self.window_size {
             return (0.0, false);
@@ -120,18 +177,16 @@ impl SpectralResidual {
     }
 
     /// Core spectral residual computation
-    fn compute_spectral_residual(&self) -> f64 {
+    fn compute_spectral_residual(&mut self) -> f64 {
         let n = self.window.len();
         if n < 4 {
             return 0.0;
         }
 
-        // Convert window to vector for FFT
-        let signal: Vec<f64> = self.window.iter().copied().collect();
-
         // Calculate signal statistics for normalization
-        let signal_mean = signal.iter().sum::<f64>() / n as f64;
-        let signal_std = (signal
+        let signal_mean = self.window.iter().sum::<f64>() / n as f64;
+        let signal_std = (self
+            .window
             .iter()
             .map(|&x| (x - signal_mean).powi(2))
             .sum::<f64>()
@@ -139,49 +194,29 @@ impl SpectralResidual {
             .sqrt()
             .max(1e-10);
 
-        // Normalize signal (zero mean, unit variance)
-        let normalized_signal: Vec<f64> = signal
-            .iter()
-            .map(|&x| (x - signal_mean) / signal_std)
-            .collect();
-
-        // Compute FFT
-        let fft_result = self.real_fft(&normalized_signal);
-
-        // Compute log amplitude spectrum
-        let log_amplitude: Vec<f64> = fft_result
-            .iter()
-            .map(|&(re, im)| {
-                let mag = (re * re + im * im).sqrt();
-                (mag + 1e-10).ln() // Add epsilon to avoid log(0)
-            })
-            .collect();
+        // Normalized signal (zero mean, unit variance) into the FFT buffer
+        self.fft_buf.clear();
+        self.fft_buf.extend(
+            self.window
+                .iter()
+                .map(|&x| Complex::new((x - signal_mean) / signal_std, 0.0)),
+        );
 
-        // Apply spectral residual transformation
-        // 1. Smooth the log amplitude (moving average)
-        let smoothed = self.moving_average(&log_amplitude, 3);
+        // Compute FFT log amplitude spectrum
+        self.log_amplitude_spectrum();
+        let log_amplitude = &self.spectrum_buf;
 
-        // 2. Compute spectral residual: log_amp - smoothed_log_amp
-        let spectral_residual: Vec<f64> = log_amplitude
-            .iter()
-            .zip(smoothed.iter())
-            .map(|(log_amp, smooth)| log_amp - smooth)
-            .collect();
+        // Apply spectral residual transformation:
+        // log_amp - smoothed_log_amp (moving average of width 3)
+        let residual = |i: usize| log_amplitude[i] - smoothed_at(log_amplitude, i, 3);
 
-        // 3. Get the saliency (use last coefficient as anomaly indicator)
+        // Get the saliency (use last coefficient as anomaly indicator)
         // Higher absolute residual = more anomalous
-        let last_idx = spectral_residual.len().saturating_sub(1);
-        let saliency = spectral_residual
-            .get(last_idx)
-            .copied()
-            .unwrap_or(0.0)
-            .abs();
+        let saliency = residual(log_amplitude.len() - 1).abs();
 
         // Also check the low-frequency components
-        let low_freq_saliency: f64 = spectral_residual
-            .iter()
-            .take(3)
-            .map(|x| x.abs())
+        let low_freq_saliency: f64 = (0..log_amplitude.len().min(3))
+            .map(|i| residual(i).abs())
             .sum::<f64>()
             / 3.0;
 
@@ -190,48 +225,24 @@ impl SpectralResidual {
 
         // Apply sensitivity adjustment
         // Higher sensitivity = lower threshold for detection
-        let adjusted_score = combined * (1.0 + self.sensitivity);
-
-        adjusted_score
-    }
-
-    /// Simple real FFT implementation using DFT
-    fn real_fft(&self, signal: &[f64]) -> Vec<(f64, f64)> {
-        let n = signal.len();
-        let mut result = Vec::with_capacity(n / 2 + 1);
-
-        // DC component (k=0)
-        let dc = signal.iter().sum::<f64>() / n as f64;
-        result.push((dc, 0.0));
-
-        // Other frequencies (k=1 to n/2)
-        for k in 1..=n / 2 {
-            let (mut re, mut im) = (0.0, 0.0);
-            for (i, &x) in signal.iter().enumerate() {
-                let angle = -2.0 * std::f64::consts::PI * (k as f64) * (i as f64) / (n as f64);
-                re += x * angle.cos();
-                im += x * angle.sin();
-            }
-            result.push((re / n as f64, im / n as f64));
-        }
-
-        result
+        combined * (1.0 + self.sensitivity)
     }
 
-    /// Simple moving average for smoothing
-    fn moving_average(&self, data: &[f64], window: usize) -> Vec<f64> {
-        let w = window.max(1);
-        let n = data.len();
-        let mut result = Vec::with_capacity(n);
-
-        for i in 0..n {
-            let start = i.saturating_sub(w / 2);
-            let end = (i + w / 2 + 1).min(n);
-            let avg = data[start..end].iter().sum::<f64>() / (end - start) as f64;
-            result.push(avg);
-        }
-
-        result
+    /// Transform `fft_buf` in place and write the log amplitude of bins
+    /// 0..=n/2 (scaled by 1/n) to `spectrum_buf`
+    fn log_amplitude_spectrum(&mut self) {
+        let n = self.fft_buf.len();
+        let fft = self.fft.get_or_insert_with(|| plan_fft(n));
+        self.fft_scratch
+            .resize(fft.get_inplace_scratch_len(), Complex::default());
+        fft.process_with_scratch(&mut self.fft_buf, &mut self.fft_scratch);
+
+        self.spectrum_buf.clear();
+        self.spectrum_buf.extend(
+            self.fft_buf[..=n / 2]
+                .iter()
+                .map(|c| (c.norm() / n as f64 + 1e-10).ln()), // Add epsilon to avoid log(0)
+        );
     }
 
     /// Update adaptive threshold using EWMA and EWMVar
@@ -266,188 +277,104 @@ impl SpectralResidual {
     }
 }
 
+/// Centered moving average of `data` at index `i`
+fn smoothed_at(data: &[f64], i: usize, window: usize) -> f64 {
+    let w = window.max(1);
+    let start = i.saturating_sub(w / 2);
+    let end = (i + w / 2 + 1).min(data.len());
+    data[start..end].iter().sum::<f64>() / (end - start) as f64
+}
+
+/// Forward FFT of a power-of-two size on the shared planner, with its own
+/// reused buffers
 #[derive(Serialize, Deserialize, Clone)]
 pub struct FftContext {
+    // Twiddle tables of the former radix-2 transform, kept so serialized
+    // contexts keep their shape
     twiddles_re: Vec<f64>,
     twiddles_im: Vec<f64>,
     size: usize,
+
+    // Planned on first use, also after a restore
+    #[serde(skip)]
+    fft: Option<Arc<dyn Fft<f64>>>,
+    #[serde(skip)]
+    buf: Vec<Complex<f64>>,
+    #[serde(skip)]
+    scratch: Vec<Complex<f64>>,
 }
 
 impl FftContext {
-    fn new(size: usize) -> Self {
+    /// Context for `size` rounded up to a power of two
+    pub fn new(size: usize) -> Self {
         let n = size.next_power_of_two();
-        let half_n = n / 2;
-        let mut twiddles_re = Vec::with_capacity(half_n);
-        let mut twiddles_im = Vec::with_capacity(half_n);
-
-        for k in 0..half_n {
-            let angle = -2.0 * std::f64::consts::PI * (k as f64) / (n as f64);
-            twiddles_re.push(angle.cos());
-            twiddles_im.push(angle.sin());
-        }
-
+        let (twiddles_re, twiddles_im) = (0..n / 2)
+            .map(|k| {
+                let angle = -2.0 * std::f64::consts::PI * (k as f64) / (n as f64);
+                (angle.cos(), angle.sin())
+            })
+            .unzip();
         Self {
             twiddles_re,
             twiddles_im,
             size: n,
+            fft: Some(plan_fft(n)),
+            buf: Vec::with_capacity(n),
+            scratch: Vec::new(),
         }
     }
 
-    fn fft(&self, re: &mut [f64], im: &mut [f64]) {
-        let n = re.len();
-        debug_assert!(n.is_power_of_two());
-        debug_assert_eq!(re.len(), im.len());
-
-        let mut j = 0usize;
-        for i in 1..n {
-            let mut m = n >> 1;
-            while j >= m {
-                j -= m;
-                m >>= 1;
-            }
-            j += m;
-            if i < j {
-                re.swap(i, j);
-                im.swap(i, j);
-            }
-        }
-
-        let mut len = 2;
-        while len <= n {
-            let half_len = len / 2;
-            let step = n / len;
-
-            for i in (0..n).step_by(len) {
-                for j in 0..half_len {
-                    let twiddle_idx = j * step;
-                    let tw_re = self.twiddles_re.get(twiddle_idx).copied().unwrap_or(1.0);
-                    let tw_im = self.twiddles_im.get(twiddle_idx).copied().unwrap_or(0.0);
-
-                    let idx1 = i + j;
-                    let idx2 = i + j + half_len;
-
-                    let t_re = re[idx2] * tw_re - im[idx2] * tw_im;
-                    let t_im = re[idx2] * tw_im + im[idx2] * tw_re;
+    pub fn size(&self) -> usize {
+        self.size
+    }
 
-                    re[idx2] = re[idx1] - t_re;
-                    im[idx2] = im[idx1] - t_im;
-                    re[idx1] = re[idx1] + t_re;
-                    im[idx1] = im[idx1] + t_im;
-                }
-            }
-            len <<= 1;
+    /// Transform `re` + i`im` (both of length `size`) in place
+    pub fn fft(&mut self, re: &mut [f64], im: &mut [f64]) {
+        debug_assert_eq!(re.len(), self.size);
+        debug_assert_eq!(re.len(), im.len());
+        let fft = self.fft.get_or_insert_with(|| plan_fft(self.size));
+        self.buf.clear();
+        self.buf.extend(
+            re.iter()
+                .zip(im.iter())
+                .map(|(&re, &im)| Complex::new(re, im)),
+        );
+        self.scratch
+            .resize(fft.get_inplace_scratch_len(), Complex::default());
+        fft.process_with_scratch(&mut self.buf, &mut self.scratch);
+        for ((re, im), c) in re.iter_mut().zip(im.iter_mut()).zip(&self.buf) {
+            *re = c.re;
+            *im = c.im;
         }
     }
 }
 
 impl SpectralResidual {
+    /// Detector plus an [`FftContext`] for its window rounded up to a power
+    /// of two; the detector plans its own transform, so the context is only
+    /// needed by callers that run transforms themselves
     pub fn new_with_fft(window_size: usize, sensitivity: f64) -> (Self, FftContext) {
         let ws = window_size.max(8);
-        let fft_size = ws.next_power_of_two();
-        let detector = Self::new(ws, sensitivity);
-        let ctx = FftContext::new(fft_size);
-        (detector, ctx)
-    }
-
-    fn compute_spectral_residual_fft(&self, ctx: &FftContext) -> f64 {
-        let n = self.window.len();
-        if n < 4 {
-            return 0.0;
-        }
-
-        let fft_size = ctx.size;
-        let signal: Vec<f64> = self.window.iter().copied().collect();
-
-        let signal_mean = signal.iter().sum::<f64>() / n as f64;
-        let signal_std = (signal
-            .iter()
-            .map(|&x| (x - signal_mean).powi(2))
-            .sum::<f64>()
-            / n as f64)
-            .sqrt()
-            .max(1e-10);
-
-        let mut re: Vec<f64> = signal
-            .iter()
-            .map(|&x| (x - signal_mean) / signal_std)
-            .chain(std::iter::repeat(0.0))
-            .take(fft_size)
-            .collect();
-        let mut im = vec![0.0; fft_size];
-
-        ctx.fft(&mut re, &mut im);
-
-        let half_n = fft_size / 2 + 1;
-        let log_amplitude: Vec<f64> = (0..half_n)
-            .map(|k| {
-                let mag = (re[k] * re[k] + im[k] * im[k]).sqrt();
-                (mag / fft_size as f64 + 1e-10).ln()
-            })
-            .collect();
-
-        let smoothed = self.moving_average(&log_amplitude, 3);
-
-        let spectral_residual: Vec<f64> = log_amplitude
-            .iter()
-            .zip(smoothed.iter())
-            .map(|(log_amp, smooth)| log_amp - smooth)
-            .collect();
-
-        let last_idx = spectral_residual.len().saturating_sub(1);
-        let saliency = spectral_residual
-            .get(last_idx)
-            .copied()
-            .unwrap_or(0.0)
-            .abs();
-
-        let low_freq_saliency: f64 = spectral_residual
-            .iter()
-            .take(3)
-            .map(|x| x.abs())
-            .sum::<f64>()
-            / 3.0;
-
-        let combined = (saliency + low_freq_saliency) / 2.0;
-        combined * (1.0 + self.sensitivity)
+        (Self::new(ws, sensitivity), FftContext::new(ws))
     }
 }
 
-/// High-performance Spectral Residual detector with pre-computed FFT context
+/// [`SpectralResidual`] under its earlier name; every detector now uses the
+/// planned FFT
 #[derive(Serialize, Deserialize, Clone)]
 pub struct FastSpectralResidual {
     detector: SpectralResidual,
-    #[serde(skip)]
-    fft_context: Option<FftContext>,
-    use_fft: bool,
 }
 
 impl FastSpectralResidual {
     pub fn new(window_size: usize, sensitivity: f64) -> Self {
-        let ws = window_size.max(8);
-        let fft_size = ws.next_power_of_two();
-        let detector = SpectralResidual::new(ws, sensitivity);
-
         Self {
-            detector,
-            fft_context: Some(FftContext::new(fft_size)),
-            use_fft: true,
+            detector: SpectralResidual::new(window_size, sensitivity),
         }
     }
 
     pub fn update(&mut self, value: f64) -> (f64, bool) {
-        let result = self.detector.update(value);
-
-        if self.detector.sample_count > self.detector.window_size as u64
-            && self.detector.sample_count % 5 == 0
-            && self.detector.window.len() >= self.detector.window_size
-            && self.use_fft
-        {
-            if let Some(ref ctx) = self.fft_context {
-                let _fft_score = self.detector.compute_spectral_residual_fft(ctx);
-            }
-        }
-
-        result
+        self.detector.update(value)
     }
 
     pub fn get_threshold(&self) -> f64 {
@@ -529,6 +456,63 @@ mod tests {
         assert!(threshold_after < 100.0, "Threshold should not explode");
     }
 
+    #[test]
+    fn test_spectrum_matches_dft() {
+        let mut detector = SpectralResidual::new(24, 0.5);
+        let signal: Vec<f64> = (0..24)
+            .map(|i| (i as f64 * 0.7).sin() + 0.1 * i as f64)
+            .collect();
+        detector.fft_buf = signal.iter().map(|&x| Complex::new(x, 0.0)).collect();
+        detector.log_amplitude_spectrum();
+
+        // Reference: direct DFT, O(n²)
+        let n = signal.len();
+        for k in 0..=n / 2 {
+            let (mut re, mut im) = (0.0, 0.0);
+            for (i, &x) in signal.iter().enumerate() {
+                let angle = -2.0 * std::f64::consts::PI * (k * i) as f64 / n as f64;
+                re += x * angle.cos();
+                im += x * angle.sin();
+            }
+            let expected = ((re * re + im * im).sqrt() / n as f64 + 1e-10).ln();
+            assert!(
+                (detector.spectrum_buf[k] - expected).abs() < 1e-9,
+                "bin {}: {} vs {}",
+                k,
+                detector.spectrum_buf[k],
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_update_batch_scores_last_window() {
+        let values: Vec<f64> = (0..40).map(|i| 100.0 + (i as f64).sin() * 10.0).collect();
+        let mut single = SpectralResidual::new(16, 0.5);
+        let mut batched = SpectralResidual::new(16, 0.5);
+
+        // Same history, then the same final window scored once
+        for &value in &values[..30] {
+            single.update(value);
+            batched.update(value);
+        }
+        for &value in &values[30..39] {
+            single.push(value);
+        }
+        single.push(values[39]);
+        let expected = single.score_window();
+        assert_eq!(batched.update_batch(&values[30..]), expected);
+        assert_eq!(batched.get_stats(), single.get_stats());
+        assert_eq!(batched.update_batch(&[]), (0.0, false));
+
+        // A restored detector plans its FFT again
+        let json = serde_json::to_string(&batched).unwrap();
+        let mut restored: SpectralResidual = serde_json::from_str(&json).unwrap();
+        let (restored_score, _) = restored.update_batch(&[1.0]);
+        let (score, _) = batched.update_batch(&[1.0]);
+        assert!((restored_score - score).abs() < 1e-6);
+    }
+
     #[test]
     fn test_fft_context_creation() {
         let ctx = FftContext::new(32);
@@ -539,7 +523,7 @@ mod tests {
 
     #[test]
     fn test_fft_correctness() {
-        let ctx = FftContext::new(8);
+        let mut ctx = FftContext::new(8);
 
         let mut re = vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
         let mut im = vec![0.0; 8];
@@ -0,0 +1,388 @@
+//! SPOT / DSPOT: Streaming Peaks-Over-Threshold (Siffer et al., KDD 2017)
+//!
+//! Extreme value theory says the excesses over a high threshold `t` follow a
+//! Generalized Pareto Distribution whatever the underlying distribution. SPOT
+//! calibrates `t` as an empirical quantile of the first values, fits the GPD
+//! to the excesses ("peaks"), and derives the alarm threshold `z_q` that a
+//! normal value exceeds with probability `q` (the risk). Afterwards each
+//! value is either normal, a new peak (refitting the GPD and moving `z_q`),
+//! or an alarm, which is kept out of the model.
+//!
+//! DSPOT (`with_drift`) runs the same on `x - M`, where `M` is the mean of
+//! the last `depth` non-alarm values, so a slowly drifting level does not
+//! move the tail.
+//!
+//! The GPD is fitted by the method of moments over a bounded window of
+//! recent peaks, kept as running sums, so an update is O(1) and allocation
+//! free once calibrated. Grimshaw's maximum-likelihood fit from the paper is
+//! more accurate for very heavy tails (shape >= 0.5) but needs a root search
+//! per peak.
+//!
+//! Unlike the paper, alarms only stay out of the fit when they are far
+//! beyond `z_q`. Excluding every alarm truncates the tail the fit sees,
+//! which lowers `z_q`, which truncates more: the threshold ratchets down
+//! until normal tail values alarm.
+
+use serde::{Deserialize, Serialize};
+
+/// Shapes closer to zero than this use the exponential-tail formulas
+const SHAPE_EPSILON: f64 = 1e-6;
+/// Peaks needed before the GPD fit is trusted
+const MIN_PEAKS: usize = 5;
+/// Alarms whose excess over `t` is within this multiple of the alarm
+/// threshold's still join the fit
+const ALARM_FIT_FACTOR: f64 = 2.0;
+
+/// What [`Spot::update`] did with a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpotOutcome {
+    /// Still collecting calibration values
+    Calibrating,
+    /// Below the initial threshold
+    Normal,
+    /// Between the initial and alarm thresholds; added to the tail model
+    Peak,
+    /// Beyond the alarm threshold
+    Alarm,
+}
+
+/// Moving mean of the last `depth` non-alarm values (DSPOT)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct DriftWindow {
+    values: Vec<f64>,
+    depth: usize,
+    cursor: usize,
+    sum: f64,
+}
+
+impl DriftWindow {
+    fn new(depth: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(depth),
+            depth,
+            cursor: 0,
+            sum: 0.0,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.values.len() == self.depth
+    }
+
+    fn mean(&self) -> f64 {
+        if self.values.is_empty() {
+            0.0
+        } else {
+            self.sum / self.values.len() as f64
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        if self.is_full() {
+            self.sum += value - self.values[self.cursor];
+            self.values[self.cursor] = value;
+            self.cursor = (self.cursor + 1) % self.depth;
+            if self.cursor == 0 {
+                // Re-sum once per lap so rounding errors do not accumulate
+                self.sum = self.values.iter().sum();
+            }
+        } else {
+            self.values.push(value);
+            self.sum += value;
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Spot {
+    /// Probability of a normal value exceeding the alarm threshold
+    risk: f64,
+    /// Quantile of the calibration values used as the initial threshold
+    level: f64,
+    calibration_len: usize,
+    calibration: Vec<f64>,
+    /// Initial threshold `t`
+    init_threshold: f64,
+    /// Alarm threshold `z_q` (on detrended values under DSPOT)
+    alarm_threshold: f64,
+    /// Excesses over `t` of the most recent peaks (ring buffer)
+    peaks: Vec<f64>,
+    max_peaks: usize,
+    peak_cursor: usize,
+    peak_sum: f64,
+    peak_sum_sq: f64,
+    /// Peaks seen since calibration started
+    total_peaks: u64,
+    /// Non-alarm values seen since calibration started
+    count: u64,
+    shape: f64,
+    scale: f64,
+    calibrated: bool,
+    drift: Option<DriftWindow>,
+}
+
+impl Spot {
+    /// SPOT flagging values a normal stream exceeds with probability `risk`
+    /// (e.g. 1e-4), calibrated on the first `calibration_len` values with the
+    /// initial threshold at their `level` quantile (e.g. 0.98)
+    pub fn new(risk: f64, level: f64, calibration_len: usize, max_peaks: usize) -> Self {
+        let calibration_len = calibration_len.max(2 * MIN_PEAKS);
+        let max_peaks = max_peaks.max(MIN_PEAKS);
+        Self {
+            risk: risk.clamp(1e-12, 0.5),
+            level: level.clamp(0.5, 0.999),
+            calibration_len,
+            calibration: Vec::with_capacity(calibration_len),
+            init_threshold: 0.0,
+            alarm_threshold: f64::INFINITY,
+            peaks: Vec::with_capacity(max_peaks),
+            max_peaks,
+            peak_cursor: 0,
+            peak_sum: 0.0,
+            peak_sum_sq: 0.0,
+            total_peaks: 0,
+            count: 0,
+            shape: 0.0,
+            scale: 0.0,
+            calibrated: false,
+            drift: None,
+        }
+    }
+
+    /// Use DSPOT: model values relative to the mean of the last `depth`
+    /// non-alarm values
+    pub fn with_drift(mut self, depth: usize) -> Self {
+        self.drift = Some(DriftWindow::new(depth.max(1)));
+        self
+    }
+
+    pub fn update(&mut self, value: f64) -> SpotOutcome {
+        if let Some(drift) = &mut self.drift
+            && !drift.is_full()
+        {
+            drift.push(value);
+            return SpotOutcome::Calibrating;
+        }
+        let local = self.local_mean();
+        let x = value - local;
+
+        if !self.calibrated {
+            self.calibration.push(x);
+            self.push_drift(value);
+            if self.calibration.len() >= self.calibration_len {
+                self.calibrate();
+            }
+            return SpotOutcome::Calibrating;
+        }
+
+        if x > self.alarm_threshold {
+            let alarm_excess = self.alarm_threshold - self.init_threshold;
+            if x - self.init_threshold <= ALARM_FIT_FACTOR * alarm_excess {
+                // Near-threshold alarms still shape the tail (see module doc)
+                self.count += 1;
+                self.add_peak(x - self.init_threshold);
+                self.fit();
+            }
+            return SpotOutcome::Alarm;
+        }
+
+        self.count += 1;
+        self.push_drift(value);
+        if x > self.init_threshold {
+            self.add_peak(x - self.init_threshold);
+            self.fit();
+            SpotOutcome::Peak
+        } else {
+            SpotOutcome::Normal
+        }
+    }
+
+    pub fn is_calibrated(&self) -> bool {
+        self.calibrated
+    }
+
+    /// Current alarm threshold in the units of the input, `None` until
+    /// calibrated
+    pub fn alarm_threshold(&self) -> Option<f64> {
+        self.calibrated
+            .then(|| self.alarm_threshold + self.local_mean())
+    }
+
+    /// Initial (peak) threshold in the units of the input
+    pub fn init_threshold(&self) -> Option<f64> {
+        self.calibrated
+            .then(|| self.init_threshold + self.local_mean())
+    }
+
+    /// Fitted GPD shape (> 0: heavy tail, 0: exponential, < 0: bounded)
+    pub fn shape(&self) -> f64 {
+        self.shape
+    }
+
+    /// Fitted GPD scale
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Estimated probability of a normal value at least as large as `value`
+    ///
+    /// Values below the initial threshold report the peak rate.
+    pub fn tail_probability(&self, value: f64) -> f64 {
+        if !self.calibrated || self.count == 0 {
+            return 1.0;
+        }
+        let peak_rate = self.total_peaks as f64 / self.count as f64;
+        let excess = (value - self.local_mean() - self.init_threshold).max(0.0);
+        if self.scale <= 0.0 {
+            return if excess > 0.0 { 0.0 } else { peak_rate };
+        }
+        let survival = if self.shape.abs() < SHAPE_EPSILON {
+            (-excess / self.scale).exp()
+        } else {
+            let base = 1.0 + self.shape * excess / self.scale;
+            if base <= 0.0 {
+                0.0
+            } else {
+                base.powf(-1.0 / self.shape)
+            }
+        };
+        (peak_rate * survival).clamp(0.0, 1.0)
+    }
+
+    fn local_mean(&self) -> f64 {
+        self.drift.as_ref().map_or(0.0, DriftWindow::mean)
+    }
+
+    fn push_drift(&mut self, value: f64) {
+        if let Some(drift) = &mut self.drift {
+            drift.push(value);
+        }
+    }
+
+    /// Set `t` from the calibration values, seed the peaks and fit
+    fn calibrate(&mut self) {
+        let mut values = std::mem::take(&mut self.calibration);
+        values.sort_unstable_by(|a, b| a.total_cmp(b));
+        let rank = ((values.len() - 1) as f64 * self.level).round() as usize;
+        self.init_threshold = values[rank];
+
+        self.count = values.len() as u64;
+        for &x in &values[rank + 1..] {
+            if x > self.init_threshold {
+                self.add_peak(x - self.init_threshold);
+            }
+        }
+        self.calibrated = true;
+        self.fit();
+    }
+
+    fn add_peak(&mut self, excess: f64) {
+        self.total_peaks += 1;
+        if self.peaks.len() < self.max_peaks {
+            self.peaks.push(excess);
+        } else {
+            let old = self.peaks[self.peak_cursor];
+            self.peaks[self.peak_cursor] = excess;
+            self.peak_sum -= old;
+            self.peak_sum_sq -= old * old;
+            self.peak_cursor = (self.peak_cursor + 1) % self.max_peaks;
+        }
+        self.peak_sum += excess;
+        self.peak_sum_sq += excess * excess;
+        if self.peak_cursor == 0 && self.peaks.len() == self.max_peaks {
+            self.peak_sum = self.peaks.iter().sum();
+            self.peak_sum_sq = self.peaks.iter().map(|p| p * p).sum();
+        }
+    }
+
+    /// Method-of-moments GPD fit over the peak window, then the alarm
+    /// threshold for the configured risk
+    fn fit(&mut self) {
+        let k = self.peaks.len();
+        if k < MIN_PEAKS {
+            // Too few peaks for a tail model: only values beyond every
+            // calibration value are extreme
+            self.alarm_threshold = self.init_threshold
+                + self
+                    .peaks
+                    .iter()
+                    .copied()
+                    .fold(0.0, f64::max)
+                    .max(f64::EPSILON);
+            return;
+        }
+
+        let mean = self.peak_sum / k as f64;
+        let variance = (self.peak_sum_sq / k as f64 - mean * mean).max(0.0);
+        if variance <= f64::EPSILON * mean * mean {
+            self.shape = 0.0;
+            self.scale = mean;
+        } else {
+            let ratio = mean * mean / variance;
+            self.shape = 0.5 * (1.0 - ratio);
+            self.scale = 0.5 * mean * (ratio + 1.0);
+        }
+
+        let r = self.risk * self.count as f64 / self.total_peaks as f64;
+        self.alarm_threshold = if self.shape.abs() < SHAPE_EPSILON {
+            self.init_threshold - self.scale * r.ln()
+        } else {
+            self.init_threshold + self.scale / self.shape * (r.powf(-self.shape) - 1.0)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic Exp(1) samples
+    fn exponential(n: usize) -> impl Iterator<Item = f64> {
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        (0..n).map(move |_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let u = (state >> 11) as f64 / (1u64 << 53) as f64;
+            -(1.0 - u).ln()
+        })
+    }
+
+    #[test]
+    fn test_spot_calibrates_exponential_tail() {
+        let mut spot = Spot::new(1e-4, 0.98, 1_000, 500);
+        let mut alarms = 0;
+        for x in exponential(50_000) {
+            if spot.update(x) == SpotOutcome::Alarm {
+                alarms += 1;
+            }
+        }
+
+        // Exp(1) exceeds ln(10^4) ~ 9.2 with probability 1e-4
+        let z = spot.alarm_threshold().unwrap();
+        assert!((z - 1e4f64.ln()).abs() < 1.0, "z = {}", z);
+        assert!(spot.shape().abs() < 0.1, "shape = {}", spot.shape());
+        assert!(alarms < 20, "{} alarms", alarms);
+
+        assert_eq!(spot.update(50.0), SpotOutcome::Alarm);
+        assert!(spot.tail_probability(50.0) < 1e-6);
+    }
+
+    #[test]
+    fn test_dspot_follows_level_drift() {
+        let mut plain = Spot::new(1e-4, 0.98, 500, 200);
+        let mut dspot = Spot::new(1e-4, 0.98, 500, 200).with_drift(50);
+        let mut plain_alarms = 0;
+        let mut dspot_alarms = 0;
+        for (i, noise) in exponential(5_000).enumerate() {
+            // Level climbs from 100 to 150 after calibration
+            let level = 100.0 + (i.saturating_sub(1_000) as f64 / 80.0).min(50.0);
+            let x = level + noise;
+            plain_alarms += (plain.update(x) == SpotOutcome::Alarm) as usize;
+            dspot_alarms += (dspot.update(x) == SpotOutcome::Alarm) as usize;
+        }
+        assert!(plain_alarms > 1_000, "{} plain alarms", plain_alarms);
+        assert!(dspot_alarms < 20, "{} dspot alarms", dspot_alarms);
+        assert_eq!(dspot.update(200.0), SpotOutcome::Alarm);
+    }
+}
@@ -0,0 +1,62 @@
+//! Heap Allocation Counting
+//!
+//! [`CountingAllocator`] wraps the system allocator and counts allocations
+//! per thread, so a caller can measure exactly what one call allocates even
+//! while other threads run. Feature `alloc-counter` installs it as the global
+//! allocator; without it, [`count_allocations`] returns a count of 0
+//! unless the binary installs the allocator itself.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// System allocator that counts `alloc`/`realloc` calls on the calling thread
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record();
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record();
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record();
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+fn record() {
+    // `try_with`: the slot is gone while the thread is being torn down
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+/// Allocations made by the current thread so far
+pub fn thread_allocations() -> u64 {
+    ALLOCATIONS.with(Cell::get)
+}
+
+/// Run `f`, returning its result and the allocations it made on this thread
+pub fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, u64) {
+    let before = thread_allocations();
+    let result = f();
+    (result, thread_allocations() - before)
+}
+
+/// Whether [`CountingAllocator`] is the global allocator of this build
+pub const ENABLED: bool = cfg!(feature = "alloc-counter");
+
+#[cfg(feature = "alloc-counter")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
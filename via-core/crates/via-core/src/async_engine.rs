@@ -0,0 +1,394 @@
+//! Tokio-Native Detection Engine
+//!
+//! [`AsyncDetectionEngine`] lets async services embed the detector without
+//! running CPU-heavy profile updates on their executor. It shards entities
+//! across dedicated worker threads, each owning a [`ProfileRegistry`], so
+//! one entity's events are always processed in order by the same worker.
+//!
+//! - [`AsyncDetectionEngine::process`] awaits the event's [`AnomalySignal`].
+//! - [`IngestSender`] is the fire-and-forget path; anomalies of ingested
+//!   events come out of [`AsyncDetectionEngine::take_anomalies`].
+//!
+//! Each worker reads from a bounded queue of `queue_capacity` jobs. A full
+//! queue makes `process` and [`IngestSender::send`] wait for room
+//! (backpressure) and [`IngestSender::try_send`] fail with
+//! [`EngineError::Full`]. The anomaly stream does not push back on the
+//! workers: anomalies it has no room for are dropped and counted in
+//! [`EngineStats::dropped_anomalies`].
+
+use crate::engine::{AnomalyProfile, ProfileConfig};
+use crate::feedback::FeedbackEvent;
+use crate::registry::{ProfileRegistry, RegistryConfig, RegistryStats};
+use crate::signal::AnomalySignal;
+use serde::Serialize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use tokio::sync::{mpsc, oneshot};
+
+/// Configuration for [`AsyncDetectionEngine`]
+#[derive(Debug, Clone)]
+pub struct AsyncEngineConfig {
+    /// Worker threads, each with its own registry
+    pub workers: usize,
+    /// Jobs queued per worker before senders wait
+    pub queue_capacity: usize,
+    /// Anomalies of ingested events buffered for `take_anomalies`
+    pub anomaly_capacity: usize,
+    /// Per-worker registry; `max_profiles` and `max_entities` apply to each
+    /// worker
+    pub registry: RegistryConfig,
+    /// Configuration of the profiles the workers create
+    pub profile: ProfileConfig,
+}
+
+impl Default for AsyncEngineConfig {
+    fn default() -> Self {
+        Self {
+            workers: thread::available_parallelism().map_or(4, |n| n.get()),
+            queue_capacity: 4_096,
+            anomaly_capacity: 4_096,
+            registry: RegistryConfig::default(),
+            profile: ProfileConfig::default(),
+        }
+    }
+}
+
+/// One event for an entity
+#[derive(Debug, Clone, Copy)]
+pub struct DetectionEvent {
+    /// Nanoseconds
+    pub timestamp: u64,
+    pub entity_hash: u64,
+    pub value: f64,
+}
+
+/// Why the engine did not take or process an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineError {
+    /// The worker's queue is full (`try_send` only)
+    Full,
+    /// New entity turned away at the registry's `max_entities`
+    Rejected,
+    /// The engine was shut down
+    Closed,
+}
+
+impl std::fmt::Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full => write!(f, "Worker queue is full"),
+            Self::Rejected => write!(f, "Entity rejected at the registry's entity cap"),
+            Self::Closed => write!(f, "Detection engine is shut down"),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+/// Registry stats of every worker
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EngineStats {
+    pub workers: Vec<RegistryStats>,
+    /// Anomalies of ingested events lost to a full anomaly stream
+    pub dropped_anomalies: u64,
+}
+
+enum Job {
+    Process(
+        DetectionEvent,
+        oneshot::Sender<Result<AnomalySignal, EngineError>>,
+    ),
+    Ingest(DetectionEvent),
+    Feedback(FeedbackEvent, oneshot::Sender<bool>),
+    Stats(oneshot::Sender<RegistryStats>),
+}
+
+/// Detection on worker threads, driven from async code
+pub struct AsyncDetectionEngine {
+    shards: Arc<[mpsc::Sender<Job>]>,
+    workers: Vec<thread::JoinHandle<ProfileRegistry<AnomalyProfile>>>,
+    anomalies: Option<mpsc::Receiver<AnomalySignal>>,
+    dropped_anomalies: Arc<AtomicU64>,
+}
+
+impl AsyncDetectionEngine {
+    /// Start the workers; no runtime is needed to create the engine
+    pub fn new(config: AsyncEngineConfig) -> Self {
+        let (anomaly_tx, anomaly_rx) = mpsc::channel(config.anomaly_capacity.max(1));
+        let dropped_anomalies = Arc::new(AtomicU64::new(0));
+        let (shards, workers) = (0..config.workers.max(1))
+            .map(|id| {
+                let (tx, rx) = mpsc::channel(config.queue_capacity.max(1));
+                let worker = Worker {
+                    rx,
+                    registry: ProfileRegistry::with_config(config.registry.clone()),
+                    profile_config: config.profile.clone(),
+                    anomalies: anomaly_tx.clone(),
+                    dropped_anomalies: Arc::clone(&dropped_anomalies),
+                };
+                let handle = thread::Builder::new()
+                    .name(format!("via-engine-{}", id))
+                    .spawn(move || worker.run())
+                    .expect("Failed to spawn engine worker");
+                (tx, handle)
+            })
+            .unzip::<_, _, Vec<_>, _>();
+
+        Self {
+            shards: shards.into(),
+            workers,
+            anomalies: Some(anomaly_rx),
+            dropped_anomalies,
+        }
+    }
+
+    pub fn workers(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Process one event on its entity's worker and return its signal
+    pub async fn process(&self, event: DetectionEvent) -> Result<AnomalySignal, EngineError> {
+        let (tx, rx) = oneshot::channel();
+        send(&self.shards, event.entity_hash, Job::Process(event, tx)).await?;
+        rx.await.map_err(|_| EngineError::Closed)?
+    }
+
+    /// Cloneable ingestion handle for events whose signals are not awaited
+    pub fn ingest_sender(&self) -> IngestSender {
+        IngestSender {
+            shards: Arc::clone(&self.shards),
+        }
+    }
+
+    /// Stream of anomalies raised by ingested events; `None` after the
+    /// first call
+    pub fn take_anomalies(&mut self) -> Option<mpsc::Receiver<AnomalySignal>> {
+        self.anomalies.take()
+    }
+
+    /// Apply Tier-2 feedback to its entity's profile; false if the worker
+    /// does not track the entity
+    pub async fn feedback(&self, event: FeedbackEvent) -> Result<bool, EngineError> {
+        let (tx, rx) = oneshot::channel();
+        send(&self.shards, event.entity_hash, Job::Feedback(event, tx)).await?;
+        rx.await.map_err(|_| EngineError::Closed)
+    }
+
+    /// Registry stats of every worker, after the jobs queued before the call
+    pub async fn stats(&self) -> Result<EngineStats, EngineError> {
+        let mut workers = Vec::with_capacity(self.shards.len());
+        for shard in self.shards.iter() {
+            let (tx, rx) = oneshot::channel();
+            shard
+                .send(Job::Stats(tx))
+                .await
+                .map_err(|_| EngineError::Closed)?;
+            workers.push(rx.await.map_err(|_| EngineError::Closed)?);
+        }
+        Ok(EngineStats {
+            workers,
+            dropped_anomalies: self.dropped_anomalies.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Stop taking events, let the workers drain their queues, and return
+    /// their registries (for checkpointing)
+    ///
+    /// Waits for every [`IngestSender`] to be dropped as well.
+    pub async fn shutdown(self) -> Vec<ProfileRegistry<AnomalyProfile>> {
+        let Self {
+            shards, workers, ..
+        } = self;
+        drop(shards);
+        tokio::task::spawn_blocking(move || {
+            workers
+                .into_iter()
+                .filter_map(|worker| worker.join().ok())
+                .collect()
+        })
+        .await
+        .unwrap_or_default()
+    }
+}
+
+/// Fire-and-forget ingestion into an [`AsyncDetectionEngine`]
+#[derive(Clone)]
+pub struct IngestSender {
+    shards: Arc<[mpsc::Sender<Job>]>,
+}
+
+impl IngestSender {
+    /// Queue an event, waiting while its worker's queue is full
+    pub async fn send(&self, event: DetectionEvent) -> Result<(), EngineError> {
+        send(&self.shards, event.entity_hash, Job::Ingest(event)).await
+    }
+
+    /// Queue an event without waiting
+    pub fn try_send(&self, event: DetectionEvent) -> Result<(), EngineError> {
+        shard(&self.shards, event.entity_hash)
+            .try_send(Job::Ingest(event))
+            .map_err(|e| match e {
+                mpsc::error::TrySendError::Full(_) => EngineError::Full,
+                mpsc::error::TrySendError::Closed(_) => EngineError::Closed,
+            })
+    }
+}
+
+fn shard(shards: &[mpsc::Sender<Job>], entity_hash: u64) -> &mpsc::Sender<Job> {
+    &shards[(entity_hash % shards.len() as u64) as usize]
+}
+
+async fn send(shards: &[mpsc::Sender<Job>], entity_hash: u64, job: Job) -> Result<(), EngineError> {
+    shard(shards, entity_hash)
+        .send(job)
+        .await
+        .map_err(|_| EngineError::Closed)
+}
+
+struct Worker {
+    rx: mpsc::Receiver<Job>,
+    registry: ProfileRegistry<AnomalyProfile>,
+    profile_config: ProfileConfig,
+    anomalies: mpsc::Sender<AnomalySignal>,
+    dropped_anomalies: Arc<AtomicU64>,
+}
+
+impl Worker {
+    fn run(mut self) -> ProfileRegistry<AnomalyProfile> {
+        while let Some(job) = self.rx.blocking_recv() {
+            match job {
+                Job::Process(event, reply) => {
+                    // The caller may have stopped waiting
+                    let _ = reply.send(self.process(event).ok_or(EngineError::Rejected));
+                }
+                Job::Ingest(event) => {
+                    if let Some(signal) = self.process(event).filter(|s| s.is_anomaly)
+                        && self.anomalies.try_send(signal).is_err()
+                    {
+                        self.dropped_anomalies.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                Job::Feedback(event, reply) => {
+                    let known = match self.registry.get_mut(event.entity_hash) {
+                        Some(profile) => {
+                            profile.apply_feedback(std::slice::from_ref(&event));
+                            true
+                        }
+                        None => false,
+                    };
+                    let _ = reply.send(known);
+                }
+                Job::Stats(reply) => {
+                    let mut stats = self.registry.stats().clone();
+                    stats.total_profiles = self.registry.len();
+                    let _ = reply.send(stats);
+                }
+            }
+        }
+        self.registry
+    }
+
+    fn process(&mut self, event: DetectionEvent) -> Option<AnomalySignal> {
+        let config = &self.profile_config;
+        let profile = self.registry.try_get_or_create(event.entity_hash, || {
+            AnomalyProfile::with_config(config.clone())
+        })?;
+        Some(profile.process_with_hash(event.timestamp, event.entity_hash, event.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(workers: usize) -> AsyncEngineConfig {
+        AsyncEngineConfig {
+            workers,
+            queue_capacity: 16,
+            anomaly_capacity: 16,
+            ..Default::default()
+        }
+    }
+
+    fn event(i: u64, entity_hash: u64, value: f64) -> DetectionEvent {
+        DetectionEvent {
+            timestamp: (i + 1) * 10_000_000,
+            entity_hash,
+            value,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_and_ingest() {
+        let mut engine = AsyncDetectionEngine::new(AsyncEngineConfig {
+            anomaly_capacity: 1_024,
+            ..config(2)
+        });
+        let mut anomalies = engine.take_anomalies().unwrap();
+        assert!(engine.take_anomalies().is_none());
+
+        for i in 0..300u64 {
+            let signal = engine.process(event(i, 1, 100.0)).await.unwrap();
+            assert_eq!(signal.sequence, i + 1);
+        }
+
+        // Ingestion waits for room in the 16-slot queues
+        let ingest = engine.ingest_sender();
+        for i in 0..300u64 {
+            ingest
+                .send(event(i, 2, 100.0 + (i % 3) as f64))
+                .await
+                .unwrap();
+        }
+        ingest.send(event(300, 2, 50_000.0)).await.unwrap();
+
+        // Stats are answered after the queued events
+        let stats = engine.stats().await.unwrap();
+        let mut spiked = false;
+        while let Ok(signal) = anomalies.try_recv() {
+            assert_eq!(signal.entity_hash, 2);
+            spiked |= signal.raw_value == 50_000.0;
+        }
+        assert!(spiked);
+        assert_eq!(stats.dropped_anomalies, 0);
+        let profiles: usize = stats.workers.iter().map(|s| s.total_profiles).sum();
+        assert_eq!(profiles, 2);
+
+        drop(ingest);
+        let registries = engine.shutdown().await;
+        assert_eq!(registries.len(), 2);
+        let events: u64 = registries
+            .iter()
+            .flat_map(|r| r.iter())
+            .map(|(_, p)| p.event_count())
+            .sum();
+        assert_eq!(events, 601);
+    }
+
+    #[tokio::test]
+    async fn test_backpressure_and_entity_cap() {
+        let engine = AsyncDetectionEngine::new(AsyncEngineConfig {
+            registry: RegistryConfig {
+                max_entities: Some(1),
+                ..Default::default()
+            },
+            ..config(1)
+        });
+        assert!(engine.process(event(0, 1, 1.0)).await.is_ok());
+        assert_eq!(
+            engine.process(event(0, 2, 1.0)).await.unwrap_err(),
+            EngineError::Rejected
+        );
+
+        // A non-waiting sender sees the bounded queue fill up
+        let ingest = engine.ingest_sender();
+        let full = (0..10_000u64)
+            .map(|i| ingest.try_send(event(i + 1, 1, 1.0)))
+            .any(|r| r == Err(EngineError::Full));
+        assert!(full);
+
+        let stats = engine.stats().await.unwrap();
+        assert_eq!(stats.workers[0].total_rejections, 1);
+    }
+}
@@ -0,0 +1,249 @@
+//! Portable Learned Baselines
+//!
+//! `AnomalyProfile::export_baseline` captures what a profile has learned
+//! about normal behavior as plain JSON:
+//!
+//! ```text
+//! { "version": 1, "events": 20000,
+//!   "value": { "mean": 101.2, "std": 4.8 },
+//!   "frequency": 19.7,
+//!   "volume": { "level": 19.6, "trend": 0.0, "seasonals": [...] },
+//!   "histogram": { "min": 0.1, "max": 10000.0, "bins": [...], "total_weight": 998.1 } }
+//! ```
+//!
+//! `AnomalyProfile::from_baseline` starts a new profile from one ("golden
+//! baseline") past its warmup, and a registry can pin one for every profile
+//! it creates. Unlike a checkpoint, a baseline leaves out the detectors'
+//! internal state (forests, sketches, drift windows, ensemble weights),
+//! which re-learn from live traffic; in exchange it can be read, edited and
+//! merged across entities or environments with [`ProfileBaseline::merge`].
+
+use serde::{Deserialize, Serialize};
+
+/// Version written by `export_baseline`
+pub const BASELINE_VERSION: u32 = 1;
+
+/// A profile's learned notion of normal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileBaseline {
+    pub version: u32,
+    /// Events the baseline was learned from, summed over merged baselines
+    pub events: u64,
+    pub value: ValueStats,
+    /// Smoothed event rate (events per second)
+    pub frequency: f64,
+    /// Holt-Winters state of the Volume detector's event rate; `None` until
+    /// its first season was seen
+    pub volume: Option<SeasonalBaseline>,
+    /// Fading histogram of the Distribution detector
+    pub histogram: HistogramBaseline,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ValueStats {
+    pub mean: f64,
+    pub std: f64,
+}
+
+/// Holt-Winters level, trend and one seasonal component per phase
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeasonalBaseline {
+    pub level: f64,
+    pub trend: f64,
+    pub seasonals: Vec<f64>,
+}
+
+/// Log-spaced bins over `[min, max]` with their decayed counts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistogramBaseline {
+    pub min: f64,
+    pub max: f64,
+    pub bins: Vec<f64>,
+    pub total_weight: f64,
+}
+
+/// Why a baseline could not be merged or applied
+#[derive(Debug, Clone, PartialEq)]
+pub enum BaselineError {
+    UnsupportedVersion {
+        found: u32,
+        max_supported: u32,
+    },
+    /// The layouts differ in this field (histogram range or bins, season
+    /// length), so the states do not line up
+    Incompatible(&'static str),
+}
+
+impl std::fmt::Display for BaselineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedVersion {
+                found,
+                max_supported,
+            } => write!(
+                f,
+                "Baseline version {} is newer than supported {}",
+                found, max_supported
+            ),
+            Self::Incompatible(field) => write!(f, "Baseline layout differs in {}", field),
+        }
+    }
+}
+
+impl std::error::Error for BaselineError {}
+
+impl ProfileBaseline {
+    pub fn check_version(&self) -> Result<(), BaselineError> {
+        if self.version > BASELINE_VERSION {
+            return Err(BaselineError::UnsupportedVersion {
+                found: self.version,
+                max_supported: BASELINE_VERSION,
+            });
+        }
+        Ok(())
+    }
+
+    /// Combine two baselines, weighting each by the events it was learned
+    /// from
+    ///
+    /// Both need the same histogram layout; a side without a Volume season
+    /// yields to the other, while two different season lengths are
+    /// incompatible.
+    pub fn merge(&self, other: &ProfileBaseline) -> Result<ProfileBaseline, BaselineError> {
+        self.check_version()?;
+        other.check_version()?;
+        let (a, b) = (&self.histogram, &other.histogram);
+        if a.bins.len() != b.bins.len() {
+            return Err(BaselineError::Incompatible("histogram.bins"));
+        }
+        if a.min != b.min || a.max != b.max {
+            return Err(BaselineError::Incompatible("histogram range"));
+        }
+
+        let events = self.events + other.events;
+        let w = if events == 0 {
+            0.5
+        } else {
+            self.events as f64 / events as f64
+        };
+        let mix = |x: f64, y: f64| w * x + (1.0 - w) * y;
+
+        let mean = mix(self.value.mean, other.value.mean);
+        let second_moment = mix(
+            self.value.std.powi(2) + self.value.mean.powi(2),
+            other.value.std.powi(2) + other.value.mean.powi(2),
+        );
+
+        let volume = match (&self.volume, &other.volume) {
+            (Some(x), Some(y)) => {
+                if x.seasonals.len() != y.seasonals.len() {
+                    return Err(BaselineError::Incompatible("volume.seasonals"));
+                }
+                Some(SeasonalBaseline {
+                    level: mix(x.level, y.level),
+                    trend: mix(x.trend, y.trend),
+                    seasonals: x
+                        .seasonals
+                        .iter()
+                        .zip(&y.seasonals)
+                        .map(|(s, t)| mix(*s, *t))
+                        .collect(),
+                })
+            }
+            (x, y) => x.clone().or_else(|| y.clone()),
+        };
+
+        Ok(ProfileBaseline {
+            version: BASELINE_VERSION,
+            events,
+            value: ValueStats {
+                mean,
+                std: (second_moment - mean * mean).max(0.0).sqrt(),
+            },
+            frequency: mix(self.frequency, other.frequency),
+            volume,
+            histogram: HistogramBaseline {
+                min: a.min,
+                max: a.max,
+                bins: a
+                    .bins
+                    .iter()
+                    .zip(&b.bins)
+                    .map(|(x, y)| mix(*x, *y))
+                    .collect(),
+                total_weight: mix(a.total_weight, b.total_weight),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline(events: u64, mean: f64, bins: Vec<f64>) -> ProfileBaseline {
+        ProfileBaseline {
+            version: BASELINE_VERSION,
+            events,
+            value: ValueStats { mean, std: 1.0 },
+            frequency: 10.0,
+            volume: Some(SeasonalBaseline {
+                level: 10.0,
+                trend: 0.0,
+                seasonals: vec![1.0, -1.0],
+            }),
+            histogram: HistogramBaseline {
+                min: 0.1,
+                max: 100.0,
+                total_weight: bins.iter().sum(),
+                bins,
+            },
+        }
+    }
+
+    #[test]
+    fn test_merge_weights_by_events() {
+        let a = baseline(300, 10.0, vec![3.0, 0.0]);
+        let b = baseline(100, 20.0, vec![0.0, 1.0]);
+        let merged = a.merge(&b).unwrap();
+
+        assert_eq!(merged.events, 400);
+        assert!((merged.value.mean - 12.5).abs() < 1e-12);
+        // Pooled spread includes the gap between the means
+        let expected_var: f64 = 0.75 * 101.0 + 0.25 * 401.0 - 12.5 * 12.5;
+        assert!((merged.value.std - expected_var.sqrt()).abs() < 1e-9);
+        assert_eq!(merged.histogram.bins, vec![2.25, 0.25]);
+        assert_eq!(merged.volume, a.volume);
+
+        let mut young = baseline(10, 10.0, vec![1.0, 1.0]);
+        young.volume = None;
+        assert_eq!(young.merge(&a).unwrap().volume, a.volume);
+    }
+
+    #[test]
+    fn test_merge_rejects_mismatched_layouts() {
+        let a = baseline(1, 10.0, vec![1.0, 1.0]);
+        let b = baseline(1, 10.0, vec![1.0, 1.0, 1.0]);
+        assert_eq!(
+            a.merge(&b),
+            Err(BaselineError::Incompatible("histogram.bins"))
+        );
+
+        let mut c = a.clone();
+        c.volume.as_mut().unwrap().seasonals.push(0.0);
+        assert_eq!(
+            a.merge(&c),
+            Err(BaselineError::Incompatible("volume.seasonals"))
+        );
+
+        let mut future = a.clone();
+        future.version = BASELINE_VERSION + 1;
+        assert!(matches!(
+            a.merge(&future),
+            Err(BaselineError::UnsupportedVersion { .. })
+        ));
+
+        let json = serde_json::to_string(&a).unwrap();
+        assert_eq!(serde_json::from_str::<ProfileBaseline>(&json).unwrap(), a);
+    }
+}
@@ -4,12 +4,16 @@
 //! Tier-2 (Bun) owns the storage; Tier-1 just serializes/deserializes.
 
 use crate::policy::runtime as policy_runtime;
-use crate::registry::ProfileRegistry;
+use crate::registry::{ProfileEntry, ProfileRegistry};
 use crate::signal::NUM_DETECTORS;
 use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
 
 /// Version for checkpoint format migrations
-pub const CHECKPOINT_VERSION: u32 = 1;
+///
+/// Version 2 profile checkpoints carry full detector state; version 1 held
+/// only ensemble weights and bandit parameters.
+pub const CHECKPOINT_VERSION: u32 = 2;
 
 /// Serialized state for adaptive ensemble weights
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +39,10 @@ impl Default for EnsembleCheckpoint {
     }
 }
 
+/// `DetectorCheckpoint::detector_id` of the entry holding a profile's full
+/// [`Checkpointable`] state
+pub const PROFILE_STATE_ID: u8 = 0;
+
 /// Serialized state for a single detector
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DetectorCheckpoint {
@@ -100,7 +108,46 @@ pub struct PolicyCheckpoint {
     pub policy_checksum: u64,
 }
 
+impl ProfileCheckpoint {
+    /// Capture a registry entry's full profile state
+    pub fn capture<P: Checkpointable>(hash: u64, entry: &ProfileEntry<P>) -> Self {
+        Self {
+            entity_hash: hash,
+            event_count: entry.meta.event_count,
+            priority: entry.meta.priority,
+            ensemble: EnsembleCheckpoint::default(), // Per-profile ensemble if needed
+            detectors: vec![DetectorCheckpoint {
+                detector_id: PROFILE_STATE_ID,
+                state: entry.profile.to_checkpoint(),
+            }],
+            created_at: 0, // TODO: track creation time
+            last_access: 0,
+        }
+    }
+}
+
 impl FullCheckpoint {
+    /// Checkpoint of `profiles` stamped with the current time and active policy
+    pub fn new(
+        profiles: Vec<ProfileCheckpoint>,
+        global_ensemble: EnsembleCheckpoint,
+        feedback_stats: FeedbackCheckpoint,
+    ) -> Self {
+        let policy_version = policy_runtime().current_version();
+        Self {
+            version: CHECKPOINT_VERSION,
+            timestamp: now_ns(),
+            profile_count: profiles.len(),
+            profiles,
+            global_ensemble,
+            feedback_stats,
+            policy: PolicyCheckpoint {
+                policy_checksum: xxhash_rust::xxh3::xxh3_64(policy_version.as_bytes()),
+                active_policy_version: policy_version,
+            },
+        }
+    }
+
     /// Create an empty checkpoint
     pub fn empty() -> Self {
         Self {
@@ -135,6 +182,21 @@ impl FullCheckpoint {
         Ok(checkpoint)
     }
 
+    /// Rebuild every checkpointed profile, keyed by entity hash
+    pub fn restore_profiles<P: Checkpointable>(&self) -> Result<Vec<(u64, P)>, CheckpointError> {
+        self.profiles
+            .iter()
+            .map(|checkpoint| {
+                let state = checkpoint
+                    .detectors
+                    .iter()
+                    .find(|d| d.detector_id == PROFILE_STATE_ID)
+                    .ok_or(CheckpointError::ProfileNotFound(checkpoint.entity_hash))?;
+                Ok((checkpoint.entity_hash, P::from_checkpoint(&state.state)?))
+            })
+            .collect()
+    }
+
     /// Get size in bytes (approximate)
     pub fn size_bytes(&self) -> usize {
         self.to_bytes().map(|b| b.len()).unwrap_or(0)
@@ -149,6 +211,7 @@ pub enum CheckpointError {
     UnsupportedVersion { found: u32, max_supported: u32 },
     ProfileNotFound(u64),
     InvalidState(String),
+    Io(String),
 }
 
 impl std::fmt::Display for CheckpointError {
@@ -168,12 +231,21 @@ impl std::fmt::Display for CheckpointError {
             }
             Self::ProfileNotFound(h) => write!(f, "Profile not found: {}", h),
             Self::InvalidState(e) => write!(f, "Invalid state: {}", e),
+            Self::Io(e) => write!(f, "Checkpoint I/O failed: {}", e),
         }
     }
 }
 
 impl std::error::Error for CheckpointError {}
 
+/// Wall-clock time in nanoseconds since the Unix epoch
+pub(crate) fn now_ns() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
 /// Request to create a checkpoint (sent to Tier-2)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckpointRequest {
@@ -241,49 +313,27 @@ impl CheckpointManager {
     }
 
     /// Create a checkpoint (returns bytes to send to Tier-2)
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(
+            name = "checkpoint",
+            level = "debug",
+            skip_all,
+            fields(profiles = registry.len())
+        )
+    )]
     pub fn create_checkpoint<P: Checkpointable>(
         &mut self,
         registry: &ProfileRegistry<P>,
         global_ensemble: EnsembleCheckpoint,
         feedback_stats: FeedbackCheckpoint,
     ) -> Result<CheckpointRequest, CheckpointError> {
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_nanos() as u64)
-            .unwrap_or(0);
-
-        let mut profiles = Vec::with_capacity(registry.len());
-
-        for (&hash, entry) in registry.iter_entries() {
-            let state = entry.profile.to_checkpoint();
-            profiles.push(ProfileCheckpoint {
-                entity_hash: hash,
-                event_count: entry.meta.event_count,
-                priority: entry.meta.priority,
-                ensemble: EnsembleCheckpoint::default(), // Per-profile ensemble if needed
-                detectors: vec![DetectorCheckpoint {
-                    detector_id: 0,
-                    state,
-                }],
-                created_at: 0, // TODO: track creation time
-                last_access: 0,
-            });
-        }
-
-        let full = FullCheckpoint {
-            version: CHECKPOINT_VERSION,
-            timestamp,
-            profile_count: profiles.len(),
-            profiles,
-            global_ensemble,
-            feedback_stats,
-            policy: PolicyCheckpoint {
-                active_policy_version: policy_runtime().current_version(),
-                policy_checksum: xxhash_rust::xxh3::xxh3_64(
-                    policy_runtime().current_version().as_bytes(),
-                ),
-            },
-        };
+        let profiles = registry
+            .iter_entries()
+            .map(|(&hash, entry)| ProfileCheckpoint::capture(hash, entry))
+            .collect();
+        let full = FullCheckpoint::new(profiles, global_ensemble, feedback_stats);
+        let timestamp = full.timestamp;
 
         let data = full.to_bytes()?;
         let uncompressed_size = data.len();
@@ -303,12 +353,7 @@ impl CheckpointManager {
     /// Record successful checkpoint
     pub fn record_success(&mut self, checkpoint_id: u64) {
         self.last_checkpoint_id = Some(checkpoint_id);
-        self.last_checkpoint_time = Some(
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map(|d| d.as_nanos() as u64)
-                .unwrap_or(0),
-        );
+        self.last_checkpoint_time = Some(now_ns());
     }
 
     /// Get last successful checkpoint info
@@ -326,9 +371,125 @@ impl Default for CheckpointManager {
     }
 }
 
+// ============================================================================
+// REGISTRY BLOB
+// ============================================================================
+
+/// Leading bytes of a registry blob
+pub const REGISTRY_BLOB_MAGIC: [u8; 4] = *b"VIAR";
+/// Layout version of the registry blob framing
+pub const REGISTRY_BLOB_VERSION: u32 = 1;
+/// Largest single-profile checkpoint written or restored, in bytes; guards
+/// against corrupt lengths
+pub const MAX_PROFILE_CHECKPOINT_BYTES: u32 = 256 << 20;
+
+/// Write every profile in `registry` as a length-prefixed binary blob
+///
+/// Layout (little endian): magic `VIAR`, blob version `u32`, profile count
+/// `u64`, then per profile its hash `u64`, priority `u8`, event count `u64`,
+/// state length `u32` and the [`Checkpointable`] state. Profiles are
+/// serialized one at a time, so memory use is bounded by the largest profile
+/// rather than the registry. Returns the number of profiles written.
+pub fn write_registry<P: Checkpointable, W: Write>(
+    registry: &ProfileRegistry<P>,
+    out: &mut W,
+) -> Result<usize, CheckpointError> {
+    let io = |e: std::io::Error| CheckpointError::Io(e.to_string());
+    out.write_all(&REGISTRY_BLOB_MAGIC).map_err(io)?;
+    out.write_all(&REGISTRY_BLOB_VERSION.to_le_bytes())
+        .map_err(io)?;
+    out.write_all(&(registry.len() as u64).to_le_bytes())
+        .map_err(io)?;
+
+    for (&hash, entry) in registry.iter_entries() {
+        let state = entry.profile.to_checkpoint();
+        let len = u32::try_from(state.len())
+            .ok()
+            .filter(|&len| len <= MAX_PROFILE_CHECKPOINT_BYTES)
+            .ok_or_else(|| {
+                CheckpointError::SerializationFailed(format!(
+                    "profile {} checkpoint is {} bytes",
+                    hash,
+                    state.len()
+                ))
+            })?;
+        out.write_all(&hash.to_le_bytes()).map_err(io)?;
+        out.write_all(&[entry.meta.priority]).map_err(io)?;
+        out.write_all(&entry.meta.event_count.to_le_bytes())
+            .map_err(io)?;
+        out.write_all(&len.to_le_bytes()).map_err(io)?;
+        out.write_all(&state).map_err(io)?;
+    }
+    out.flush().map_err(io)?;
+    Ok(registry.len())
+}
+
+/// Restore profiles from a [`write_registry`] blob into `registry`,
+/// replacing profiles with the same hash
+///
+/// Reads one frame at a time. Returns the number of profiles restored; on
+/// error, frames before the bad one stay restored.
+pub fn read_registry<P: Checkpointable, R: Read>(
+    input: &mut R,
+    registry: &mut ProfileRegistry<P>,
+) -> Result<usize, CheckpointError> {
+    let mut magic = [0u8; 4];
+    read_exact(input, &mut magic)?;
+    if magic != REGISTRY_BLOB_MAGIC {
+        return Err(CheckpointError::DeserializationFailed(
+            "not a registry checkpoint".to_string(),
+        ));
+    }
+    let version = u32::from_le_bytes(read_array(input)?);
+    if version > REGISTRY_BLOB_VERSION {
+        return Err(CheckpointError::UnsupportedVersion {
+            found: version,
+            max_supported: REGISTRY_BLOB_VERSION,
+        });
+    }
+
+    let count = u64::from_le_bytes(read_array(input)?);
+    let mut state = Vec::new();
+    for _ in 0..count {
+        let hash = u64::from_le_bytes(read_array(input)?);
+        let [priority] = read_array(input)?;
+        let event_count = u64::from_le_bytes(read_array(input)?);
+        let len = u32::from_le_bytes(read_array(input)?);
+        if len > MAX_PROFILE_CHECKPOINT_BYTES {
+            return Err(CheckpointError::DeserializationFailed(format!(
+                "profile {} frame of {} bytes exceeds the limit",
+                hash, len
+            )));
+        }
+        state.resize(len as usize, 0);
+        read_exact(input, &mut state)?;
+
+        let mut entry = ProfileEntry::new(P::from_checkpoint(&state)?).with_priority(priority);
+        entry.meta.event_count = event_count;
+        registry.insert_entry(hash, entry);
+    }
+    Ok(count as usize)
+}
+
+fn read_exact<R: Read>(input: &mut R, buf: &mut [u8]) -> Result<(), CheckpointError> {
+    input.read_exact(buf).map_err(|e| match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => {
+            CheckpointError::DeserializationFailed("truncated registry checkpoint".to_string())
+        }
+        _ => CheckpointError::Io(e.to_string()),
+    })
+}
+
+fn read_array<R: Read, const N: usize>(input: &mut R) -> Result<[u8; N], CheckpointError> {
+    let mut buf = [0u8; N];
+    read_exact(input, &mut buf)?;
+    Ok(buf)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::engine::AnomalyProfile;
 
     #[test]
     fn test_checkpoint_serialization() {
@@ -349,6 +510,75 @@ mod tests {
         assert_eq!(restored.timestamp, 1234567890);
     }
 
+    #[test]
+    fn test_full_checkpoint_restores_registry() {
+        let mut registry = ProfileRegistry::new();
+        for entity in 1..=3u64 {
+            let profile = registry.get_or_create(entity, AnomalyProfile::default);
+            for i in 0..(50 * entity) {
+                let _ = profile.process_with_hash(i * 1_000_000, entity, 100.0);
+            }
+        }
+
+        let request = CheckpointManager::new()
+            .create_checkpoint(
+                &registry,
+                EnsembleCheckpoint::default(),
+                FeedbackCheckpoint::default(),
+            )
+            .unwrap();
+        let checkpoint = FullCheckpoint::from_bytes(&request.data).unwrap();
+        assert_eq!(checkpoint.version, CHECKPOINT_VERSION);
+
+        let mut restored: Vec<(u64, AnomalyProfile)> = checkpoint.restore_profiles().unwrap();
+        restored.sort_by_key(|(hash, _)| *hash);
+        let counts: Vec<(u64, u64)> = restored
+            .iter()
+            .map(|(hash, p)| (*hash, p.event_count()))
+            .collect();
+        assert_eq!(counts, vec![(1, 50), (2, 100), (3, 150)]);
+    }
+
+    #[test]
+    fn test_registry_blob_roundtrip() {
+        let mut registry = ProfileRegistry::new();
+        for entity in 1..=3u64 {
+            let profile = registry.get_or_create(entity, AnomalyProfile::default);
+            for i in 0..(40 * entity) {
+                let _ = profile.process_with_hash(i * 1_000_000, entity, 100.0);
+            }
+        }
+        registry.set_priority(2, 7);
+
+        let mut blob = Vec::new();
+        assert_eq!(write_registry(&registry, &mut blob).unwrap(), 3);
+        assert_eq!(&blob[..4], b"VIAR");
+
+        let mut restored = ProfileRegistry::<AnomalyProfile>::new();
+        assert_eq!(
+            read_registry(&mut blob.as_slice(), &mut restored).unwrap(),
+            3
+        );
+        for entity in 1..=3u64 {
+            let (before, after) = (
+                registry.get_meta(entity).unwrap(),
+                restored.get_meta(entity).unwrap(),
+            );
+            assert_eq!(after.priority, before.priority);
+            assert_eq!(after.event_count, before.event_count);
+        }
+        assert_eq!(restored.get(3).unwrap().event_count(), 120);
+
+        // A truncated blob fails instead of restoring garbage
+        let mut partial = ProfileRegistry::<AnomalyProfile>::new();
+        let result = read_registry(&mut &blob[..blob.len() - 1], &mut partial);
+        assert!(matches!(
+            result,
+            Err(CheckpointError::DeserializationFailed(_))
+        ));
+        assert!(read_registry(&mut &b"nope"[..], &mut partial).is_err());
+    }
+
     #[test]
     fn test_version_check() {
         let mut checkpoint = FullCheckpoint::empty();
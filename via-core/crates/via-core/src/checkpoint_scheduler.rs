@@ -0,0 +1,395 @@
+//! Scheduled Checkpoints to Disk
+//!
+//! [`CheckpointScheduler`] snapshots every profile in a [`ProfileRegistry`]
+//! into a directory on a fixed interval. Every `full_every`-th snapshot is
+//! full; the ones in between carry only the profiles touched since the
+//! previous snapshot plus the hashes that left the registry, so the steady
+//! state cost follows activity rather than registry size.
+//!
+//! A full snapshot and the incrementals on top of it form a chain; only the
+//! newest `retain_full` chains are kept. [`load_latest`] folds the newest
+//! chain back into a single [`FullCheckpoint`].
+//!
+//! The scheduler is driven by whoever owns the registry (a gatekeeper shard
+//! calls [`CheckpointScheduler::tick`] from its event loop), so snapshots
+//! never contend with event processing for a lock.
+
+use crate::checkpoint::{
+    CHECKPOINT_VERSION, CheckpointError, Checkpointable, EnsembleCheckpoint, FeedbackCheckpoint,
+    FullCheckpoint, ProfileCheckpoint,
+};
+use crate::registry::ProfileRegistry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const FULL_SUFFIX: &str = "-full.ckpt";
+const INCREMENTAL_SUFFIX: &str = "-incr.ckpt";
+
+/// Configuration for the checkpoint scheduler
+#[derive(Debug, Clone)]
+pub struct CheckpointSchedulerConfig {
+    /// Directory snapshots are written to (created if missing)
+    pub dir: PathBuf,
+    /// Time between snapshots
+    pub interval: Duration,
+    /// Write a full snapshot every this many snapshots; the rest are incremental
+    pub full_every: u32,
+    /// Number of chains (full snapshot plus its incrementals) kept on disk
+    pub retain_full: usize,
+}
+
+impl Default for CheckpointSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("checkpoints"),
+            interval: Duration::from_secs(60),
+            full_every: 10,
+            retain_full: 3,
+        }
+    }
+}
+
+/// One snapshot file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Position in the directory's snapshot sequence
+    pub sequence: u64,
+    /// Sequence of the full snapshot this one applies on top of (its own
+    /// sequence when full)
+    pub base: u64,
+    /// Hashes that left the registry since the previous snapshot
+    pub removed: Vec<u64>,
+    /// Profiles captured by this snapshot (changed ones only when incremental)
+    pub checkpoint: FullCheckpoint,
+}
+
+impl Snapshot {
+    pub fn is_full(&self) -> bool {
+        self.sequence == self.base
+    }
+}
+
+/// Summary of a written snapshot
+#[derive(Debug, Clone)]
+pub struct SnapshotInfo {
+    pub sequence: u64,
+    pub full: bool,
+    /// Profiles written
+    pub profiles: usize,
+    /// Profiles recorded as removed
+    pub removed: usize,
+    pub bytes: usize,
+    pub path: PathBuf,
+}
+
+/// Writes full and incremental registry snapshots on an interval
+pub struct CheckpointScheduler {
+    config: CheckpointSchedulerConfig,
+    next_sequence: u64,
+    /// Full snapshot the current chain builds on; `None` until one is written
+    base: Option<u64>,
+    /// Incrementals written since `base`
+    since_full: u32,
+    /// `(created_at, event_count)` of each profile as of the last snapshot
+    written: HashMap<u64, (Instant, u64)>,
+    last_run: Instant,
+}
+
+impl CheckpointScheduler {
+    /// Create the snapshot directory and continue its sequence numbering
+    ///
+    /// The first snapshot is always full, since nothing is known about what
+    /// an earlier process wrote.
+    pub fn new(config: CheckpointSchedulerConfig) -> Result<Self, CheckpointError> {
+        std::fs::create_dir_all(&config.dir).map_err(|e| io_error(&config.dir, e))?;
+        let next_sequence = list_snapshots(&config.dir)?
+            .last()
+            .map_or(1, |(sequence, _, _)| sequence + 1);
+
+        Ok(Self {
+            config,
+            next_sequence,
+            base: None,
+            since_full: 0,
+            written: HashMap::new(),
+            last_run: Instant::now(),
+        })
+    }
+
+    pub fn config(&self) -> &CheckpointSchedulerConfig {
+        &self.config
+    }
+
+    /// Snapshot the registry if the interval has elapsed since the last one
+    pub fn tick<P: Checkpointable>(
+        &mut self,
+        registry: &ProfileRegistry<P>,
+    ) -> Result<Option<SnapshotInfo>, CheckpointError> {
+        if self.last_run.elapsed() < self.config.interval {
+            return Ok(None);
+        }
+        self.snapshot(registry).map(Some)
+    }
+
+    /// Snapshot the registry now
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(
+            name = "checkpoint",
+            level = "debug",
+            skip_all,
+            fields(sequence = self.next_sequence, profiles = registry.len())
+        )
+    )]
+    pub fn snapshot<P: Checkpointable>(
+        &mut self,
+        registry: &ProfileRegistry<P>,
+    ) -> Result<SnapshotInfo, CheckpointError> {
+        self.last_run = Instant::now();
+        let sequence = self.next_sequence;
+        let base = match self.base {
+            Some(base) if self.since_full + 1 < self.config.full_every.max(1) => base,
+            _ => sequence,
+        };
+        let full = base == sequence;
+
+        let mut written = HashMap::with_capacity(registry.len());
+        let mut profiles = Vec::new();
+        for (&hash, entry) in registry.iter_entries() {
+            let key = (entry.meta.created_at, entry.meta.event_count);
+            if full || self.written.get(&hash) != Some(&key) {
+                profiles.push(ProfileCheckpoint::capture(hash, entry));
+            }
+            written.insert(hash, key);
+        }
+        let removed: Vec<u64> = if full {
+            Vec::new()
+        } else {
+            self.written
+                .keys()
+                .filter(|hash| !written.contains_key(hash))
+                .copied()
+                .collect()
+        };
+
+        let snapshot = Snapshot {
+            sequence,
+            base,
+            removed,
+            checkpoint: FullCheckpoint::new(
+                profiles,
+                EnsembleCheckpoint::default(),
+                FeedbackCheckpoint::default(),
+            ),
+        };
+        let bytes = bincode::serialize(&snapshot)
+            .map_err(|e| CheckpointError::SerializationFailed(e.to_string()))?;
+
+        let suffix = if full {
+            FULL_SUFFIX
+        } else {
+            INCREMENTAL_SUFFIX
+        };
+        let path = self.config.dir.join(format!("{:010}{}", sequence, suffix));
+        // Write then rename so a crash never leaves a truncated snapshot
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, &bytes).map_err(|e| io_error(&tmp, e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| io_error(&path, e))?;
+
+        self.next_sequence += 1;
+        self.base = Some(base);
+        self.since_full = if full { 0 } else { self.since_full + 1 };
+        self.written = written;
+        if full {
+            self.prune()?;
+        }
+
+        Ok(SnapshotInfo {
+            sequence,
+            full,
+            profiles: snapshot.checkpoint.profile_count,
+            removed: snapshot.removed.len(),
+            bytes: bytes.len(),
+            path,
+        })
+    }
+
+    /// Delete chains older than the newest `retain_full` full snapshots
+    fn prune(&self) -> Result<(), CheckpointError> {
+        let snapshots = list_snapshots(&self.config.dir)?;
+        let Some(&(cutoff, _, _)) = snapshots
+            .iter()
+            .filter(|(_, full, _)| *full)
+            .nth_back(self.config.retain_full.max(1) - 1)
+        else {
+            return Ok(());
+        };
+        for (_, _, path) in snapshots.iter().filter(|(seq, _, _)| *seq < cutoff) {
+            std::fs::remove_file(path).map_err(|e| io_error(path, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Fold the newest chain in `dir` into one checkpoint
+///
+/// Returns `None` when the directory holds no full snapshot.
+pub fn load_latest(dir: &Path) -> Result<Option<FullCheckpoint>, CheckpointError> {
+    let snapshots = list_snapshots(dir)?;
+    let Some(start) = snapshots.iter().rposition(|(_, full, _)| *full) else {
+        return Ok(None);
+    };
+
+    let mut base = read_snapshot(&snapshots[start].2)?;
+    let mut profiles: HashMap<u64, ProfileCheckpoint> = by_hash(&mut base.checkpoint);
+    let mut latest = base.checkpoint;
+
+    for (_, _, path) in &snapshots[start + 1..] {
+        let mut snapshot = read_snapshot(path)?;
+        if snapshot.base != base.sequence {
+            return Err(CheckpointError::InvalidState(format!(
+                "{} does not build on snapshot {}",
+                path.display(),
+                base.sequence
+            )));
+        }
+        for hash in &snapshot.removed {
+            profiles.remove(hash);
+        }
+        profiles.extend(by_hash(&mut snapshot.checkpoint));
+        latest = snapshot.checkpoint;
+    }
+
+    latest.profiles = profiles.into_values().collect();
+    latest.profile_count = latest.profiles.len();
+    Ok(Some(latest))
+}
+
+/// Move a checkpoint's profiles out, keyed by entity hash
+fn by_hash(checkpoint: &mut FullCheckpoint) -> HashMap<u64, ProfileCheckpoint> {
+    std::mem::take(&mut checkpoint.profiles)
+        .into_iter()
+        .map(|p| (p.entity_hash, p))
+        .collect()
+}
+
+fn read_snapshot(path: &Path) -> Result<Snapshot, CheckpointError> {
+    let bytes = std::fs::read(path).map_err(|e| io_error(path, e))?;
+    let snapshot: Snapshot = bincode::deserialize(&bytes).map_err(|e| {
+        CheckpointError::DeserializationFailed(format!("{}: {}", path.display(), e))
+    })?;
+    if snapshot.checkpoint.version > CHECKPOINT_VERSION {
+        return Err(CheckpointError::UnsupportedVersion {
+            found: snapshot.checkpoint.version,
+            max_supported: CHECKPOINT_VERSION,
+        });
+    }
+    Ok(snapshot)
+}
+
+/// `(sequence, full, path)` of every snapshot in `dir`, oldest first
+fn list_snapshots(dir: &Path) -> Result<Vec<(u64, bool, PathBuf)>, CheckpointError> {
+    let entries = std::fs::read_dir(dir).map_err(|e| io_error(dir, e))?;
+    let mut snapshots = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| io_error(dir, e))?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let (sequence, full) = if let Some(seq) = name.strip_suffix(FULL_SUFFIX) {
+            (seq, true)
+        } else if let Some(seq) = name.strip_suffix(INCREMENTAL_SUFFIX) {
+            (seq, false)
+        } else {
+            continue;
+        };
+        if let Ok(sequence) = sequence.parse::<u64>() {
+            snapshots.push((sequence, full, path));
+        }
+    }
+    snapshots.sort_by_key(|(sequence, _, _)| *sequence);
+    Ok(snapshots)
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> CheckpointError {
+    CheckpointError::Io(format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::AnomalyProfile;
+
+    fn feed(registry: &mut ProfileRegistry<AnomalyProfile>, entity: u64, events: u64) {
+        for i in 0..events {
+            registry
+                .get_or_create(entity, AnomalyProfile::default)
+                .process_with_hash(i * 1_000_000, entity, 100.0);
+        }
+    }
+
+    fn files(dir: &Path) -> Vec<String> {
+        list_snapshots(dir)
+            .unwrap()
+            .into_iter()
+            .map(|(_, _, p)| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_incremental_snapshots_restore_registry() {
+        let dir = std::env::temp_dir().join(format!("via-core-ckpt-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut scheduler = CheckpointScheduler::new(CheckpointSchedulerConfig {
+            dir: dir.clone(),
+            interval: Duration::from_secs(3600),
+            full_every: 3,
+            retain_full: 1,
+        })
+        .unwrap();
+
+        let mut registry = ProfileRegistry::new();
+        for entity in 1..=3 {
+            feed(&mut registry, entity, 20);
+        }
+        assert!(scheduler.tick(&registry).unwrap().is_none());
+        let first = scheduler.snapshot(&registry).unwrap();
+        assert!(first.full);
+        assert_eq!(first.profiles, 3);
+
+        // Only touched, new and removed profiles go into the incremental
+        feed(&mut registry, 2, 10);
+        feed(&mut registry, 4, 20);
+        registry.remove(3);
+        let second = scheduler.snapshot(&registry).unwrap();
+        assert!(!second.full);
+        assert_eq!((second.profiles, second.removed), (2, 1));
+
+        let mut restored: Vec<(u64, AnomalyProfile)> = load_latest(&dir)
+            .unwrap()
+            .unwrap()
+            .restore_profiles()
+            .unwrap();
+        restored.sort_by_key(|(hash, _)| *hash);
+        let counts: Vec<(u64, u64)> = restored
+            .iter()
+            .map(|(hash, p)| (*hash, p.event_count()))
+            .collect();
+        assert_eq!(counts, vec![(1, 20), (2, 30), (4, 20)]);
+
+        // A new full snapshot retires the previous chain
+        assert_eq!(scheduler.snapshot(&registry).unwrap().profiles, 0);
+        assert!(scheduler.snapshot(&registry).unwrap().full);
+        assert_eq!(files(&dir), vec!["0000000004-full.ckpt"]);
+
+        // A restarted scheduler continues the sequence with a full snapshot
+        let mut restarted = CheckpointScheduler::new(scheduler.config().clone()).unwrap();
+        let info = restarted.snapshot(&registry).unwrap();
+        assert_eq!((info.sequence, info.full, info.profiles), (5, true, 3));
+        assert_eq!(load_latest(&dir).unwrap().unwrap().profile_count, 3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,7 +1,7 @@
 //! VIA-Core Detection Engine v2
 //!
 //! Two-stage pipeline architecture:
-//! 1. Detection Stage: Run all 10 detectors independently
+//! 1. Detection Stage: Run all 13 detectors independently
 //! 2. Decision Stage: Combine with AdaptiveEnsemble, produce rich signals
 //!
 //! This engine produces `AnomalySignal` with full detector breakdown and attribution.
@@ -11,22 +11,40 @@ use crate::algo::{
     adaptive_ensemble::{AdaptiveEnsemble, DetectorOutput},
     adaptive_threshold::presets,
     behavioral_fingerprint::BehavioralFingerprintDetector,
+    calibration::ScoreCalibrator,
+    ddsketch::DDSketch,
+    decomposition::SeasonalDecomposition,
     drift_detector::{DriftType, EnsembleDriftDetector},
     enhanced_cusum::EnhancedCUSUM,
     ewma::EWMA,
     histogram::FadingHistogram,
     hll::HyperLogLog,
-    holtwinters::HoltWinters,
+    holtwinters::{HoltWinters, PeriodEstimator},
+    matrix_profile::StreamingMatrixProfile,
     multi_scale::MultiScaleDetector,
     rrcf::RRCFDetector,
     spectral_residual::SpectralResidual,
+    spot::{Spot, SpotOutcome},
 };
-use crate::checkpoint::{CheckpointError, Checkpointable, EnsembleCheckpoint};
+use crate::baseline::{
+    BASELINE_VERSION, BaselineError, HistogramBaseline, ProfileBaseline, SeasonalBaseline,
+    ValueStats,
+};
+use crate::checkpoint::{CHECKPOINT_VERSION, CheckpointError, Checkpointable};
+use crate::explain::{ConditionCheck, DecisionBranch, DecisionExplanation};
 use crate::feedback::{FeedbackEvent, LearningUpdate};
+use crate::lifecycle::{LifecycleEventKind, LifecycleLog};
 use crate::policy::runtime as policy_runtime;
+use crate::registry::ProfileRegistry;
 use crate::signal::{
-    AnomalySignal, Attribution, BaselineSummary, DetectorId, DetectorScore, NUM_DETECTORS, Severity,
+    AnomalySignal, Attribution, BaselineSummary, DetectorId, DetectorScore, FiredDetector,
+    NUM_DETECTORS, NormalRange, Severity, SignalExplanation, WeightContribution,
 };
+use crate::view::{PUBLISH_INTERVAL, ProfileView, SharedState, StatsSnapshot};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 
 // ============================================================================
 // CORE ABSTRACTIONS
@@ -38,8 +56,153 @@ pub struct SignalContext {
     pub timestamp: u64,
     pub unique_id_hash: u64,
     pub value: f64,
+    /// All feature channels of the event; a single channel equal to `value`
+    /// for scalar events
+    pub features: FeatureVector,
     pub is_warmup: bool,
     pub sequence: u64,
+    pub exogenous: ExogenousContext,
+}
+
+/// Number of named feature channels (see [`FeatureChannel`])
+pub const NUM_FEATURES: usize = 3;
+
+/// Named channels of a multivariate event (see [`AnomalyProfile::process_vector`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum FeatureChannel {
+    /// Request latency (ms); also serves as the event's scalar value
+    Latency = 0,
+    /// Request or response payload size (bytes)
+    PayloadSize = 1,
+    /// Status class, e.g. 2 for 2xx through 5 for 5xx
+    StatusClass = 2,
+}
+
+impl FeatureChannel {
+    pub const ALL: [FeatureChannel; NUM_FEATURES] = [
+        FeatureChannel::Latency,
+        FeatureChannel::PayloadSize,
+        FeatureChannel::StatusClass,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FeatureChannel::Latency => "latency",
+            FeatureChannel::PayloadSize => "payload_size",
+            FeatureChannel::StatusClass => "status_class",
+        }
+    }
+}
+
+/// Feature values of one event, indexed by [`FeatureChannel`]
+///
+/// Fixed-size so [`SignalContext`] stays `Copy` and the hot path stays
+/// allocation-free.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FeatureVector {
+    values: [f64; NUM_FEATURES],
+    len: u8,
+}
+
+impl FeatureVector {
+    /// A scalar event: the latency channel only
+    pub fn scalar(value: f64) -> Self {
+        Self::from_slice(&[value])
+    }
+
+    /// Channels in [`FeatureChannel`] order; values past [`NUM_FEATURES`]
+    /// are ignored
+    pub fn from_slice(features: &[f64]) -> Self {
+        let len = features.len().min(NUM_FEATURES);
+        let mut values = [0.0; NUM_FEATURES];
+        values[..len].copy_from_slice(&features[..len]);
+        Self {
+            values,
+            len: len as u8,
+        }
+    }
+
+    pub fn get(&self, channel: FeatureChannel) -> Option<f64> {
+        self.as_slice().get(channel as usize).copied()
+    }
+
+    pub fn as_slice(&self) -> &[f64] {
+        &self.values[..self.len as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// More than the latency channel is present
+    pub fn is_multivariate(&self) -> bool {
+        self.len > 1
+    }
+
+    /// Replace a channel's value; absent channels stay absent
+    pub fn set(&mut self, channel: FeatureChannel, value: f64) {
+        if (channel as usize) < self.len() {
+            self.values[channel as usize] = value;
+        }
+    }
+}
+
+/// Score multiplier for level-shift detectors while a deploy is rolling out
+const DEPLOY_SENSITIVITY: f64 = 0.5;
+/// Score multiplier for traffic-shape detectors outside business hours
+const OFF_HOURS_SENSITIVITY: f64 = 0.7;
+/// Decision floor multiplier while a deploy is rolling out
+const DEPLOY_FLOOR_SCALE: f64 = 1.5;
+
+/// Host-supplied facts about the event's surroundings
+///
+/// Shifts that are expected given the context (a new release changing the
+/// value distribution, traffic swings at night) count for less, so they do
+/// not page on their own. The default context leaves detection unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExogenousContext {
+    /// Whether the event falls in business hours, if the host knows
+    pub business_hours: Option<bool>,
+    /// A deploy or config rollout is in progress
+    pub deploy_in_progress: bool,
+}
+
+impl ExogenousContext {
+    /// Multiplier on `detector`'s score under this context
+    pub fn detector_sensitivity(&self, detector: DetectorId) -> f64 {
+        let mut sensitivity = 1.0;
+        if self.deploy_in_progress
+            && matches!(
+                detector,
+                DetectorId::ChangePoint
+                    | DetectorId::Drift
+                    | DetectorId::Distribution
+                    | DetectorId::Quantile
+            )
+        {
+            sensitivity *= DEPLOY_SENSITIVITY;
+        }
+        if self.business_hours == Some(false)
+            && matches!(detector, DetectorId::Volume | DetectorId::Burst)
+        {
+            sensitivity *= OFF_HOURS_SENSITIVITY;
+        }
+        sensitivity
+    }
+
+    /// Multiplier on the detector and ensemble score floors of the decision
+    pub fn decision_floor_scale(&self) -> f64 {
+        if self.deploy_in_progress {
+            DEPLOY_FLOOR_SCALE
+        } else {
+            1.0
+        }
+    }
 }
 
 /// Internal detection result from a single detector
@@ -50,10 +213,171 @@ pub struct DetectionResult {
     pub signal_type: u8,
     pub expected: f64,
     pub confidence: f64,
-    pub reason: String,
+    pub reason: DetectionReason,
+    /// Values of this event that would not have fired, for detectors that
+    /// judge the value against a learned bound; timing, entity and shape
+    /// detectors leave it `None`
+    pub normal_range: Option<NormalRange>,
+}
+
+/// Why a detector fired, rendered with `Display`
+///
+/// Kept as data so a firing detector does not allocate on the hot path; the
+/// text is only built when someone displays it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DetectionReason {
+    Volume {
+        spike: bool,
+        expected_rps: f64,
+        observed_rps: f64,
+    },
+    Distribution {
+        value: f64,
+        rarity: f64,
+    },
+    Cardinality {
+        new_entities: f64,
+        velocity: f64,
+    },
+    Burst {
+        iat_ms: f64,
+        baseline_ms: f64,
+    },
+    Spectral {
+        trend: &'static str,
+        residual: f64,
+    },
+    TrendChange {
+        increase: bool,
+        severity: f64,
+    },
+    Rrcf {
+        codisp: f64,
+    },
+    MultiScale {
+        scales_triggered: usize,
+    },
+    Drift {
+        kind: &'static str,
+        severity: f64,
+    },
+    Quantile {
+        quantile: f64,
+        baseline: f64,
+        observed: f64,
+    },
+    Evt {
+        value: f64,
+        threshold: f64,
+        tail_probability: f64,
+    },
+    Discord {
+        window: usize,
+        distance: f64,
+        typical: f64,
+    },
+    Text(String),
+}
+
+impl std::fmt::Display for DetectionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Volume {
+                spike,
+                expected_rps,
+                observed_rps,
+            } => write!(
+                f,
+                "Volume {}: expected {:.1} RPS, observed {:.1} RPS",
+                if *spike { "spike" } else { "drop" },
+                expected_rps,
+                observed_rps
+            ),
+            Self::Distribution { value, rarity } => write!(
+                f,
+                "Distribution shift: value {:.2} has rarity score {:.1}",
+                value, rarity
+            ),
+            Self::Cardinality {
+                new_entities,
+                velocity,
+            } => write!(
+                f,
+                "New unique entities: {:.0} new (velocity: {:.1}/event)",
+                new_entities, velocity
+            ),
+            Self::Burst {
+                iat_ms,
+                baseline_ms,
+            } => write!(
+                f,
+                "Burst detected: IAT {:.2}ms (baseline: {:.2}ms)",
+                iat_ms, baseline_ms
+            ),
+            Self::Spectral { trend, residual } => write!(
+                f,
+                "Spectral anomaly: {} (FFT residual: {:.2})",
+                trend, residual
+            ),
+            Self::TrendChange { increase, severity } => write!(
+                f,
+                "Trend change: sustained {} (severity: {:.0}%)",
+                if *increase { "increase" } else { "decrease" },
+                severity * 100.0
+            ),
+            Self::Rrcf { codisp } => {
+                write!(f, "RRCF anomaly: co-displacement score {:.2}", codisp)
+            }
+            Self::MultiScale { scales_triggered } => write!(
+                f,
+                "Multi-scale anomaly: {} resolution(s) triggered",
+                scales_triggered
+            ),
+            Self::Drift { kind, severity } => write!(
+                f,
+                "Concept drift: {} (severity: {:.0}%)",
+                kind,
+                severity * 100.0
+            ),
+            Self::Quantile {
+                quantile,
+                baseline,
+                observed,
+            } => write!(
+                f,
+                "p{:.0} shifted: {:.2} -> {:.2} (baseline -> recent)",
+                quantile * 100.0,
+                baseline,
+                observed
+            ),
+            Self::Evt {
+                value,
+                threshold,
+                tail_probability,
+            } => write!(
+                f,
+                "Extreme value: {:.2} beyond EVT threshold {:.2} (tail probability {:.1e})",
+                value, threshold, tail_probability
+            ),
+            Self::Discord {
+                window,
+                distance,
+                typical,
+            } => write!(
+                f,
+                "Pattern break: last {} values {:.2} from nearest past match (typical {:.2})",
+                window, distance, typical
+            ),
+            Self::Text(text) => f.write_str(text),
+        }
+    }
 }
 
 /// Trait for all detectors
+///
+/// Detectors see every event through [`SignalContext`]: `value` is the
+/// scalar stream, `features` carries the other channels of multivariate
+/// events for detectors that model them jointly (RRCF).
 pub trait Detector: Send + Sync {
     fn name(&self) -> &str;
     fn id(&self) -> DetectorId;
@@ -68,14 +392,26 @@ pub trait Detector: Send + Sync {
 // ============================================================================
 
 /// Volume Detector (Holt-Winters + Adaptive Threshold)
+#[derive(Serialize, Deserialize)]
 pub struct VolumeDetectorV2 {
     hw: HoltWinters,
     rate_estimator: EWMA,
     adaptive_threshold: AdaptiveThreshold,
     last_timestamp: u64,
     warmup_count: usize,
+    /// Estimates the season of the rate series when auto-detection is on
+    period_estimator: Option<PeriodEstimator>,
 }
 
+/// Recent rates the period estimator looks at (events)
+const PERIOD_WINDOW: usize = 512;
+/// Events between two period estimates
+const PERIOD_INTERVAL: usize = 256;
+/// Relative change of the estimated period that re-seeds Holt-Winters
+const MATERIAL_PERIOD_CHANGE: f64 = 0.1;
+/// Intervals the Volume detector learns before scoring
+const VOLUME_WARMUP: usize = 100;
+
 impl VolumeDetectorV2 {
     pub fn new(alpha: f64, beta: f64, gamma: f64, period: usize) -> Self {
         Self {
@@ -84,6 +420,65 @@ impl VolumeDetectorV2 {
             adaptive_threshold: presets::volume_threshold(),
             last_timestamp: 0,
             warmup_count: 0,
+            period_estimator: None,
+        }
+    }
+
+    /// Estimate the season from the rate series instead of trusting the
+    /// configured `period`, re-seeding Holt-Winters when it moves materially
+    pub fn with_period_detection(mut self) -> Self {
+        self.period_estimator = Some(PeriodEstimator::new(PERIOD_WINDOW, PERIOD_INTERVAL));
+        self.hw.reserve_period(PERIOD_WINDOW / 2);
+        self
+    }
+
+    /// Season length the estimator settled on, if any
+    pub fn detected_period(&self) -> Option<usize> {
+        self.period_estimator.as_ref()?.period()
+    }
+
+    /// Learned Holt-Winters state of the rate, once a season was seen
+    pub fn seasonal_baseline(&self) -> Option<SeasonalBaseline> {
+        let (level, trend) = self.hw.components()?;
+        Some(SeasonalBaseline {
+            level,
+            trend,
+            seasonals: self.hw.get_seasonality().to_vec(),
+        })
+    }
+
+    /// Start from a learned rate model, scoring from the first interval
+    pub fn seed(&mut self, baseline: &SeasonalBaseline) {
+        self.hw
+            .seed(baseline.level, baseline.trend, &baseline.seasonals);
+        self.rate_estimator.seed(baseline.level);
+        self.warmup_count = self.warmup_count.max(VOLUME_WARMUP);
+    }
+
+    /// Add the rate model of another shard of the same stream
+    pub fn merge(&mut self, other: &VolumeDetectorV2) -> Result<(), BaselineError> {
+        self.hw
+            .merge_sum(&other.hw)
+            .map_err(|_| BaselineError::Incompatible("volume.seasonals"))?;
+        self.rate_estimator
+            .seed(self.rate_estimator.value() + other.rate_estimator.value());
+        self.last_timestamp = self.last_timestamp.max(other.last_timestamp);
+        self.warmup_count = self.warmup_count.max(other.warmup_count);
+        Ok(())
+    }
+
+    fn track_period(&mut self, rate: f64) {
+        let Some(estimator) = self.period_estimator.as_mut() else {
+            return;
+        };
+        if !estimator.update(rate) {
+            return;
+        }
+        if let Some(period) = estimator.period() {
+            let current = self.hw.period() as f64;
+            if (period as f64 - current).abs() > current * MATERIAL_PERIOD_CHANGE {
+                self.hw.reseed(period, estimator.history());
+            }
         }
     }
 }
@@ -116,8 +511,9 @@ impl Detector for VolumeDetectorV2 {
         self.warmup_count += 1;
 
         let (predicted, deviation) = self.hw.update(smoothed_rps);
+        self.track_period(smoothed_rps);
 
-        if ctx.is_warmup || self.warmup_count < 100 {
+        if ctx.is_warmup || self.warmup_count < VOLUME_WARMUP {
             return None;
         }
 
@@ -140,12 +536,12 @@ impl Detector for VolumeDetectorV2 {
                 signal_type: DetectorId::Volume as u8,
                 expected: predicted,
                 confidence,
-                reason: format!(
-                    "Volume {}: expected {:.1} RPS, observed {:.1} RPS",
-                    if deviation > 0.0 { "spike" } else { "drop" },
-                    predicted,
-                    smoothed_rps
-                ),
+                normal_range: None,
+                reason: DetectionReason::Volume {
+                    spike: deviation > 0.0,
+                    expected_rps: predicted,
+                    observed_rps: smoothed_rps,
+                },
             })
         } else {
             None
@@ -154,14 +550,25 @@ impl Detector for VolumeDetectorV2 {
 
     fn get_stats(&self) -> String {
         let (mean, std, thresh, count) = self.adaptive_threshold.get_stats();
+        let source = if self.detected_period().is_some() {
+            "detected"
+        } else {
+            "configured"
+        };
         format!(
-            "VolumeV2: μ={:.2}, σ={:.2}, thresh={:.2}, n={}",
-            mean, std, thresh, count
+            "VolumeV2: μ={:.2}, σ={:.2}, thresh={:.2}, n={}, period={} ({})",
+            mean,
+            std,
+            thresh,
+            count,
+            self.hw.period(),
+            source
         )
     }
 }
 
 /// Distribution Detector (Fading Histogram)
+#[derive(Serialize, Deserialize)]
 pub struct DistributionDetectorV2 {
     hist: FadingHistogram,
     adaptive_threshold: AdaptiveThreshold,
@@ -174,6 +581,34 @@ impl DistributionDetectorV2 {
             adaptive_threshold: presets::distribution_threshold(),
         }
     }
+
+    pub fn histogram_baseline(&self) -> HistogramBaseline {
+        let (min, max) = self.hist.range();
+        HistogramBaseline {
+            min,
+            max,
+            bins: self.hist.bins().to_vec(),
+            total_weight: self.hist.total_weight(),
+        }
+    }
+
+    /// Start from learned counts; the layout has to match this histogram's
+    pub fn seed(&mut self, baseline: &HistogramBaseline) -> Result<(), BaselineError> {
+        if baseline.bins.len() != self.hist.bins().len() {
+            return Err(BaselineError::Incompatible("histogram.bins"));
+        }
+        if (baseline.min, baseline.max) != self.hist.range() {
+            return Err(BaselineError::Incompatible("histogram range"));
+        }
+        self.hist.seed(&baseline.bins, baseline.total_weight);
+        Ok(())
+    }
+
+    pub fn merge(&mut self, other: &DistributionDetectorV2) -> Result<(), BaselineError> {
+        self.hist
+            .merge(&other.hist)
+            .map_err(|_| BaselineError::Incompatible("histogram layout"))
+    }
 }
 
 impl Detector for DistributionDetectorV2 {
@@ -201,16 +636,25 @@ impl Detector for DistributionDetectorV2 {
         };
 
         if score > 0.0 {
+            let (_, _, threshold, _) = self.adaptive_threshold.get_stats();
+            let normal_range =
+                self.hist
+                    .nearest_common_span(ctx.value, threshold)
+                    .map(|(low, high)| NormalRange {
+                        low: low.is_finite().then_some(low),
+                        high: high.is_finite().then_some(high),
+                    });
             Some(DetectionResult {
                 score,
                 weight: 0.8,
                 signal_type: DetectorId::Distribution as u8,
                 expected: 0.0,
                 confidence,
-                reason: format!(
-                    "Distribution shift: value {:.2} has rarity score {:.1}",
-                    ctx.value, anomaly_likelihood
-                ),
+                normal_range,
+                reason: DetectionReason::Distribution {
+                    value: ctx.value,
+                    rarity: anomaly_likelihood,
+                },
             })
         } else {
             None
@@ -219,6 +663,7 @@ impl Detector for DistributionDetectorV2 {
 }
 
 /// Cardinality Detector (HLL Velocity)
+#[derive(Serialize, Deserialize)]
 pub struct CardinalityDetectorV2 {
     hll: HyperLogLog,
     velocity_tracker: EWMA,
@@ -237,6 +682,16 @@ impl CardinalityDetectorV2 {
             last_velocity: 0.0,
         }
     }
+
+    /// Union of both sketches; `weight` is this side's share of the events
+    pub fn merge(&mut self, other: &CardinalityDetectorV2, weight: f64) {
+        if self.hll.merge(&other.hll).is_ok() {
+            // Measure the next delta from the union, not this side's count
+            self.last_count = self.hll.count();
+        }
+        self.velocity_tracker.merge(&other.velocity_tracker, weight);
+        self.last_velocity = self.velocity_tracker.value();
+    }
 }
 
 impl Default for CardinalityDetectorV2 {
@@ -279,10 +734,11 @@ impl Detector for CardinalityDetectorV2 {
                 signal_type: DetectorId::Cardinality as u8,
                 expected: self.last_velocity,
                 confidence,
-                reason: format!(
-                    "New unique entities: {:.0} new (velocity: {:.1}/event)",
-                    delta, velocity
-                ),
+                normal_range: None,
+                reason: DetectionReason::Cardinality {
+                    new_entities: delta,
+                    velocity,
+                },
             })
         } else {
             None
@@ -291,6 +747,7 @@ impl Detector for CardinalityDetectorV2 {
 }
 
 /// Burst Detector (Enhanced CUSUM)
+#[derive(Serialize, Deserialize)]
 pub struct BurstDetectorV2 {
     cusum: EnhancedCUSUM,
     iat_tracker: EWMA,
@@ -308,6 +765,18 @@ impl BurstDetectorV2 {
             warmup_remaining: 50, // Wait for IAT to stabilize (shorter)
         }
     }
+
+    /// Pool with another shard; `weight` is this side's share of the events
+    pub fn merge(&mut self, other: &BurstDetectorV2, weight: f64) {
+        // Interleaving two streams adds their rates
+        let (a, b) = (self.iat_tracker.value(), other.iat_tracker.value());
+        if a > 0.0 && b > 0.0 {
+            self.iat_tracker.seed(1.0 / (1.0 / a + 1.0 / b));
+        }
+        self.cusum.merge(&other.cusum, weight);
+        self.last_timestamp = self.last_timestamp.max(other.last_timestamp);
+        self.warmup_remaining = self.warmup_remaining.min(other.warmup_remaining);
+    }
 }
 
 impl Default for BurstDetectorV2 {
@@ -363,10 +832,11 @@ impl Detector for BurstDetectorV2 {
                 signal_type: DetectorId::Burst as u8,
                 expected: baseline_iat,
                 confidence: 0.75,
-                reason: format!(
-                    "Burst detected: IAT {:.2}ms (baseline: {:.2}ms)",
-                    delta_ms, baseline_iat
-                ),
+                normal_range: None,
+                reason: DetectionReason::Burst {
+                    iat_ms: delta_ms,
+                    baseline_ms: baseline_iat,
+                },
             })
         } else {
             None
@@ -375,6 +845,7 @@ impl Detector for BurstDetectorV2 {
 }
 
 /// Spectral Detector (FFT Residual)
+#[derive(Serialize, Deserialize)]
 pub struct SpectralDetector {
     spectral: SpectralResidual,
     last_values: Vec<f64>,
@@ -428,7 +899,11 @@ impl Detector for SpectralDetector {
                 signal_type: DetectorId::Spectral as u8,
                 expected: 0.0,
                 confidence: 0.85,
-                reason: format!("Spectral anomaly: {} (FFT residual: {:.2})", trend, score),
+                normal_range: None,
+                reason: DetectionReason::Spectral {
+                    trend,
+                    residual: score,
+                },
             })
         } else {
             None
@@ -437,6 +912,7 @@ impl Detector for SpectralDetector {
 }
 
 /// Change Point Detector (Trend CUSUM)
+#[derive(Serialize, Deserialize)]
 pub struct ChangePointDetector {
     cusum: EnhancedCUSUM,
     trend_ewma: EWMA,
@@ -451,6 +927,11 @@ impl ChangePointDetector {
             last_value: 0.0,
         }
     }
+
+    pub fn merge(&mut self, other: &ChangePointDetector, weight: f64) {
+        self.cusum.merge(&other.cusum, weight);
+        self.trend_ewma.merge(&other.trend_ewma, weight);
+    }
 }
 
 impl Default for ChangePointDetector {
@@ -490,15 +971,11 @@ impl Detector for ChangePointDetector {
                 signal_type: DetectorId::ChangePoint as u8,
                 expected: 0.0,
                 confidence: 0.8,
-                reason: format!(
-                    "Trend change: sustained {} (severity: {:.0}%)",
-                    if alarm_type > 0 {
-                        "increase"
-                    } else {
-                        "decrease"
-                    },
-                    severity * 100.0
-                ),
+                normal_range: None,
+                reason: DetectionReason::TrendChange {
+                    increase: alarm_type > 0,
+                    severity,
+                },
             })
         } else {
             None
@@ -506,10 +983,21 @@ impl Detector for ChangePointDetector {
     }
 }
 
+/// Smoothing factor of the per-channel magnitude used to normalize features
+const FEATURE_SCALE_ALPHA: f64 = 0.01;
+
 /// RRCF Detector (Random Cut Forest)
+///
+/// Scalar events feed a shingled univariate forest. Multivariate events feed
+/// a second forest over all feature channels, each divided by its running
+/// mean magnitude so payload bytes do not dominate latency milliseconds.
+#[derive(Serialize, Deserialize)]
 pub struct RRCFDetectorV2 {
     rrcf: RRCFDetector,
     warmup_count: usize,
+    /// Created on the first multivariate event
+    multivariate: Option<RRCFDetector>,
+    feature_scale: [f64; NUM_FEATURES],
 }
 
 impl RRCFDetectorV2 {
@@ -517,7 +1005,29 @@ impl RRCFDetectorV2 {
         Self {
             rrcf: RRCFDetector::new_univariate(10),
             warmup_count: 0,
+            multivariate: None,
+            feature_scale: [0.0; NUM_FEATURES],
+        }
+    }
+
+    fn update_features(&mut self, features: &FeatureVector) -> (f64, bool) {
+        for (scale, value) in self.feature_scale.iter_mut().zip(features.as_slice()) {
+            *scale = if *scale == 0.0 {
+                value.abs()
+            } else {
+                *scale + FEATURE_SCALE_ALPHA * (value.abs() - *scale)
+            };
         }
+        let scale = &self.feature_scale;
+        self.multivariate
+            .get_or_insert_with(|| RRCFDetector::new_multivariate(NUM_FEATURES))
+            .update_vector(
+                features
+                    .as_slice()
+                    .iter()
+                    .zip(scale)
+                    .map(|(value, scale)| value / scale.max(1e-9)),
+            )
     }
 }
 
@@ -537,7 +1047,11 @@ impl Detector for RRCFDetectorV2 {
     }
 
     fn update(&mut self, ctx: &SignalContext) -> Option<DetectionResult> {
-        let (score, is_anomaly) = self.rrcf.update(ctx.value);
+        let (score, is_anomaly) = if ctx.features.is_multivariate() {
+            self.update_features(&ctx.features)
+        } else {
+            self.rrcf.update(ctx.value)
+        };
         self.warmup_count += 1;
 
         if self.warmup_count > 20 && is_anomaly && score > 0.4 {
@@ -548,7 +1062,8 @@ impl Detector for RRCFDetectorV2 {
                 signal_type: DetectorId::RRCF as u8,
                 expected: 0.0,
                 confidence: (score * 0.9).min(0.95),
-                reason: format!("RRCF anomaly: co-displacement score {:.2}", score),
+                normal_range: None,
+                reason: DetectionReason::Rrcf { codisp: score },
             })
         } else {
             None
@@ -557,6 +1072,7 @@ impl Detector for RRCFDetectorV2 {
 }
 
 /// Multi-Scale Detector
+#[derive(Serialize, Deserialize)]
 pub struct MultiScaleDetectorV2 {
     multi_scale: MultiScaleDetector,
 }
@@ -600,10 +1116,8 @@ impl Detector for MultiScaleDetectorV2 {
                 signal_type: DetectorId::MultiScale as u8,
                 expected: 0.0,
                 confidence: 0.75 + (scales_triggered as f64 * 0.05).min(0.2),
-                reason: format!(
-                    "Multi-scale anomaly: {} resolution(s) triggered",
-                    scales_triggered
-                ),
+                normal_range: None,
+                reason: DetectionReason::MultiScale { scales_triggered },
             })
         } else {
             None
@@ -612,6 +1126,7 @@ impl Detector for MultiScaleDetectorV2 {
 }
 
 /// Behavioral Fingerprint Detector
+#[derive(Serialize, Deserialize)]
 pub struct BehavioralFingerprintDetectorV2 {
     behavioral: BehavioralFingerprintDetector,
 }
@@ -640,7 +1155,7 @@ impl Detector for BehavioralFingerprintDetectorV2 {
     }
 
     fn update(&mut self, ctx: &SignalContext) -> Option<DetectionResult> {
-        let (score, is_anomaly, reason) = self.behavioral.process(
+        let (score, is_anomaly) = self.behavioral.observe(
             ctx.unique_id_hash,
             ctx.timestamp,
             ctx.value.abs(),
@@ -654,7 +1169,8 @@ impl Detector for BehavioralFingerprintDetectorV2 {
                 signal_type: DetectorId::Behavioral as u8,
                 expected: 0.0,
                 confidence: (score * 0.85).min(0.95),
-                reason,
+                normal_range: None,
+                reason: DetectionReason::Text(self.behavioral.explain(ctx.unique_id_hash, true)),
             })
         } else {
             None
@@ -663,6 +1179,7 @@ impl Detector for BehavioralFingerprintDetectorV2 {
 }
 
 /// Drift Detector (Concept Drift)
+#[derive(Serialize, Deserialize)]
 pub struct DriftDetectorV2 {
     drift: EnsembleDriftDetector,
     sample_count: u64,
@@ -716,11 +1233,278 @@ impl Detector for DriftDetectorV2 {
                 signal_type: DetectorId::Drift as u8,
                 expected: 0.0,
                 confidence: 0.7 + (severity * 0.25),
-                reason: format!(
-                    "Concept drift: {} (severity: {:.0}%)",
-                    drift_name,
-                    severity * 100.0
-                ),
+                normal_range: None,
+                reason: DetectionReason::Drift {
+                    kind: drift_name,
+                    severity,
+                },
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Quantiles whose shifts the quantile detector watches
+const TAIL_QUANTILES: [f64; 2] = [0.95, 0.99];
+/// Events before the quantile detector scores
+const QUANTILE_WARMUP: u64 = 200;
+
+/// Quantile Detector (DDSketch tail latency)
+///
+/// Compares p95/p99 of a short-memory sketch with those of a long-memory
+/// baseline. The log-ratio of each pair runs through its own adaptive band,
+/// so SLO-style tail regressions fire even when the median is unchanged.
+/// Unlike the fading histogram the sketch needs no value range.
+#[derive(Serialize, Deserialize)]
+pub struct QuantileDetector {
+    baseline: DDSketch,
+    recent: DDSketch,
+    bands: [AdaptiveThreshold; TAIL_QUANTILES.len()],
+    sample_count: u64,
+}
+
+impl QuantileDetector {
+    pub fn new() -> Self {
+        Self {
+            baseline: DDSketch::new(0.01, 512).with_half_life(5000.0),
+            recent: DDSketch::new(0.01, 512).with_half_life(50.0),
+            bands: [
+                presets::distribution_threshold(),
+                presets::distribution_threshold(),
+            ],
+            sample_count: 0,
+        }
+    }
+
+    pub fn merge(&mut self, other: &QuantileDetector) -> Result<(), BaselineError> {
+        self.baseline
+            .merge(&other.baseline)
+            .and_then(|_| self.recent.merge(&other.recent))
+            .map_err(|_| BaselineError::Incompatible("quantile sketch"))?;
+        self.sample_count += other.sample_count;
+        Ok(())
+    }
+}
+
+impl Default for QuantileDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Detector for QuantileDetector {
+    fn name(&self) -> &str {
+        "Quantile/Tail"
+    }
+
+    fn id(&self) -> DetectorId {
+        DetectorId::Quantile
+    }
+
+    fn update(&mut self, ctx: &SignalContext) -> Option<DetectionResult> {
+        self.baseline.add(ctx.value);
+        self.recent.add(ctx.value);
+        self.sample_count += 1;
+
+        let mut best: Option<(f64, f64, f64, f64, f64)> = None;
+        for (band, &q) in self.bands.iter_mut().zip(TAIL_QUANTILES.iter()) {
+            let (Some(baseline), Some(observed)) =
+                (self.baseline.quantile(q), self.recent.quantile(q))
+            else {
+                continue;
+            };
+            let shift = (observed.max(1e-9) / baseline.max(1e-9)).ln().abs();
+            let _ = band.update(shift);
+            let score = band.anomaly_score(shift);
+            if score > best.map_or(0.0, |(s, ..)| s) {
+                let (_, _, max_shift, _) = band.get_stats();
+                best = Some((score, q, baseline, observed, max_shift));
+            }
+        }
+
+        if self.sample_count < QUANTILE_WARMUP {
+            return None;
+        }
+
+        // Values inside the band cannot push the recent quantile out of it
+        best.map(
+            |(score, quantile, baseline, observed, max_shift)| DetectionResult {
+                score,
+                weight: 1.0,
+                signal_type: DetectorId::Quantile as u8,
+                expected: baseline,
+                confidence: 0.6 + score * 0.35,
+                normal_range: Some(NormalRange::between(
+                    baseline * (-max_shift).exp(),
+                    baseline * max_shift.exp(),
+                )),
+                reason: DetectionReason::Quantile {
+                    quantile,
+                    baseline,
+                    observed,
+                },
+            },
+        )
+    }
+
+    fn get_stats(&self) -> String {
+        let q = |sketch: &DDSketch, q| sketch.quantile(q).unwrap_or(0.0);
+        format!(
+            "p95={:.2}/{:.2}, p99={:.2}/{:.2} (baseline/recent)",
+            q(&self.baseline, 0.95),
+            q(&self.recent, 0.95),
+            q(&self.baseline, 0.99),
+            q(&self.recent, 0.99)
+        )
+    }
+}
+
+/// Probability of a normal value beyond the EVT alarm threshold
+const EVT_RISK: f64 = 1e-4;
+
+/// EVT Detector (DSPOT peaks-over-threshold)
+///
+/// Fits a Generalized Pareto tail above the 98th percentile of the first
+/// events and flags values a normal stream exceeds with probability
+/// `EVT_RISK`, relative to a moving mean so level drift does not alarm.
+/// The threshold calibrates itself, which suits heavy-tailed latency where
+/// sigma bands under-fire or over-fire.
+#[derive(Serialize, Deserialize)]
+pub struct EvtDetector {
+    spot: Spot,
+}
+
+impl EvtDetector {
+    pub fn new() -> Self {
+        Self {
+            spot: Spot::new(EVT_RISK, 0.98, 500, 500).with_drift(100),
+        }
+    }
+}
+
+impl Default for EvtDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Detector for EvtDetector {
+    fn name(&self) -> &str {
+        "EVT/SPOT"
+    }
+
+    fn id(&self) -> DetectorId {
+        DetectorId::Evt
+    }
+
+    fn update(&mut self, ctx: &SignalContext) -> Option<DetectionResult> {
+        // Thresholds from before the value, which an alarm may refit
+        let thresholds = self.spot.alarm_threshold().zip(self.spot.init_threshold());
+        let tail_probability = self.spot.tail_probability(ctx.value);
+        if self.spot.update(ctx.value) != SpotOutcome::Alarm {
+            return None;
+        }
+        let (threshold, peak_threshold) = thresholds?;
+
+        let overshoot = (ctx.value - threshold) / (threshold - peak_threshold).max(1e-9);
+        let score = 0.5 + 0.5 * overshoot.min(1.0);
+        Some(DetectionResult {
+            score,
+            weight: 0.9,
+            signal_type: DetectorId::Evt as u8,
+            expected: threshold,
+            confidence: 0.7 + score * 0.25,
+            normal_range: Some(NormalRange::at_most(threshold)),
+            reason: DetectionReason::Evt {
+                value: ctx.value,
+                threshold,
+                tail_probability,
+            },
+        })
+    }
+
+    fn get_stats(&self) -> String {
+        match self.spot.alarm_threshold() {
+            Some(threshold) => format!(
+                "threshold={:.2}, shape={:.3}, scale={:.3}",
+                threshold,
+                self.spot.shape(),
+                self.spot.scale()
+            ),
+            None => "calibrating".to_string(),
+        }
+    }
+}
+
+/// Matrix profile subsequence length (events)
+const DISCORD_WINDOW: usize = 16;
+/// Past subsequences searched for a match
+const DISCORD_HISTORY: usize = 128;
+
+/// Discord Detector (Matrix Profile)
+///
+/// Scores how far the shape of the last `DISCORD_WINDOW` values is from its
+/// nearest match among the previous `DISCORD_HISTORY` subsequences.
+/// Repeating patterns (batch cycles, polling loops) match closely; a break
+/// in the pattern has no match even when every value is in range and the
+/// spectrum barely moves.
+#[derive(Serialize, Deserialize)]
+pub struct DiscordDetector {
+    profile: StreamingMatrixProfile,
+    adaptive_threshold: AdaptiveThreshold,
+    typical_distance: EWMA,
+}
+
+impl DiscordDetector {
+    pub fn new() -> Self {
+        Self {
+            profile: StreamingMatrixProfile::new(DISCORD_WINDOW, DISCORD_HISTORY),
+            adaptive_threshold: presets::distribution_threshold(),
+            typical_distance: EWMA::new(100.0),
+        }
+    }
+}
+
+impl Default for DiscordDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Detector for DiscordDetector {
+    fn name(&self) -> &str {
+        "MatrixProfile/Discord"
+    }
+
+    fn id(&self) -> DetectorId {
+        DetectorId::Discord
+    }
+
+    fn update(&mut self, ctx: &SignalContext) -> Option<DetectionResult> {
+        let distance = self.profile.update(ctx.value)?;
+        if !self.profile.is_saturated() {
+            return None;
+        }
+
+        let typical = self.typical_distance.value();
+        let _ = self.adaptive_threshold.update(distance);
+        let score = self.adaptive_threshold.anomaly_score(distance);
+        self.typical_distance.update(distance);
+
+        if score > 0.0 {
+            Some(DetectionResult {
+                score,
+                weight: 0.8,
+                signal_type: DetectorId::Discord as u8,
+                expected: 0.0,
+                confidence: 0.6 + score * 0.3,
+                normal_range: None,
+                reason: DetectionReason::Discord {
+                    window: self.profile.window(),
+                    distance,
+                    typical,
+                },
             })
         } else {
             None
@@ -732,8 +1516,11 @@ impl Detector for DriftDetectorV2 {
 // ENHANCED ANOMALY PROFILE WITH ADAPTIVE ENSEMBLE
 // ============================================================================
 
+/// Feedback labels after which an old label counts half in the calibration
+const CALIBRATION_HALF_LIFE: f64 = 500.0;
+
 /// Configuration for the anomaly profile
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProfileConfig {
     pub hw_alpha: f64,
     pub hw_beta: f64,
@@ -748,33 +1535,269 @@ pub struct ProfileConfig {
     pub min_detector_score_for_anomaly: f64,
     pub min_ensemble_score_for_anomaly: f64,
     pub use_adaptive_ensemble_threshold: bool,
+    /// Estimate the Volume detector's season instead of using `period`
+    pub auto_period: bool,
+    /// Floor for [`AnomalyProfile::process_filtered`]
+    pub emission: EmissionFloor,
+    /// Hysteresis and cooldown applied to the anomaly decision
+    pub suppression: AlertSuppression,
+    /// Remove a wall-clock seasonal cycle from values before detection
+    pub decomposition: Option<DecompositionConfig>,
+    /// Derive severity from the feedback-calibrated probability instead of
+    /// the raw score, once the profile is calibrated
+    pub calibrated_severity: bool,
+    /// Built-in detectors that are neither run nor weighted by the ensemble;
+    /// their scores stay at the default (not fired)
+    pub disabled_detectors: Vec<DetectorId>,
+    /// Recent values kept per profile for [`SignalExplanation::history`];
+    /// 0 leaves anomalous signals without an explanation
+    pub explain_history: usize,
 }
 
-impl Default for ProfileConfig {
-    fn default() -> Self {
+/// Season of the optional decomposition stage
+///
+/// With the stage on, detectors see each value minus its learned seasonal
+/// component (`trend + residual`): daily or weekly swings stop looking like
+/// level shifts, while real shifts still show and value-range detectors keep
+/// their units. The components are reported in [`BaselineSummary`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DecompositionConfig {
+    /// Length of the seasonal cycle
+    pub season_secs: u64,
+    /// Slots the cycle is cut into
+    pub buckets: usize,
+}
+
+impl DecompositionConfig {
+    /// Daily cycle in 15-minute slots
+    pub fn daily() -> Self {
         Self {
-            hw_alpha: 0.3,
-            hw_beta: 0.1,
-            hw_gamma: 0.1,
-            period: 24,
-            hist_bins: 50,
-            min_val: 0.0,
-            max_val: 10000.0,
-            hist_decay: 0.999,
-            confidence_threshold: 0.5,
-            warmup_events: 100,
-            min_detector_score_for_anomaly: 0.10,
-            min_ensemble_score_for_anomaly: 0.10,
-            use_adaptive_ensemble_threshold: true,
+            season_secs: 86_400,
+            buckets: 96,
+        }
+    }
+
+    /// Weekly cycle in hourly slots
+    pub fn weekly() -> Self {
+        Self {
+            season_secs: 7 * 86_400,
+            buckets: 168,
         }
     }
+
+    fn build(&self) -> SeasonalDecomposition {
+        SeasonalDecomposition::new(self.season_secs.saturating_mul(1_000_000_000), self.buckets)
+    }
 }
 
-/// Enhanced Anomaly Profile with Adaptive Ensemble
-pub struct AnomalyProfile {
-    // Detectors (Static Dispatch: No vtable overhead)
-    v_volume: VolumeDetectorV2,
-    v_dist: DistributionDetectorV2,
+/// Severity and score an anomaly must reach to be emitted as a full signal
+///
+/// The default emits every anomaly; raising either floor lets hosts skip
+/// materializing and shipping signals they would drop anyway.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct EmissionFloor {
+    pub min_severity: Severity,
+    pub min_score: f64,
+}
+
+impl EmissionFloor {
+    pub fn admits(&self, signal: &AnomalySignal) -> bool {
+        self.admits_decision(signal.is_anomaly, signal.severity, signal.ensemble_score)
+    }
+
+    fn admits_decision(&self, is_anomaly: bool, severity: Severity, score: f64) -> bool {
+        is_anomaly && severity >= self.min_severity && score >= self.min_score
+    }
+}
+
+/// Duplicate-alert suppression for the anomaly decision
+///
+/// An anomalous event is only raised when at least `required_hits` of the
+/// last `window_events` events (itself included) were anomalous, and not
+/// within `cooldown_ms` of the previous raised anomaly; those inside the
+/// cooldown carry [`AnomalySignal::suppressed_by_cooldown`]. The default
+/// (1 of 1, no cooldown) raises every anomalous event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertSuppression {
+    /// Anomalous events needed in the window; clamped to `1..=window_events`
+    pub required_hits: u32,
+    /// Events the hysteresis looks back over; clamped to `1..=64`
+    pub window_events: u32,
+    pub cooldown_ms: u64,
+}
+
+impl Default for AlertSuppression {
+    fn default() -> Self {
+        Self {
+            required_hits: 1,
+            window_events: 1,
+            cooldown_ms: 0,
+        }
+    }
+}
+
+/// Why [`AnomalyProfile::update_config`] rejected a configuration
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// Fields baked into detector state; the profile has to be re-created
+    Structural(Vec<&'static str>),
+    /// A hot-reloadable field is outside `[0, 1]`
+    OutOfRange { field: &'static str, value: f64 },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Structural(fields) => write!(
+                f,
+                "Not hot-reloadable (re-create the profile): {}",
+                fields.join(", ")
+            ),
+            Self::OutOfRange { field, value } => {
+                write!(f, "{} must be within [0, 1], got {}", field, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl ProfileConfig {
+    /// Fields that size or seed detector state and differ from `other`
+    ///
+    /// Everything else (decision thresholds, the adaptive threshold switch,
+    /// the emission floor) only feeds the decision stage and can change on a
+    /// live profile.
+    pub fn structural_changes(&self, other: &ProfileConfig) -> Vec<&'static str> {
+        let fields = [
+            ("hw_alpha", self.hw_alpha == other.hw_alpha),
+            ("hw_beta", self.hw_beta == other.hw_beta),
+            ("hw_gamma", self.hw_gamma == other.hw_gamma),
+            ("period", self.period == other.period),
+            ("hist_bins", self.hist_bins == other.hist_bins),
+            ("min_val", self.min_val == other.min_val),
+            ("max_val", self.max_val == other.max_val),
+            ("hist_decay", self.hist_decay == other.hist_decay),
+            ("warmup_events", self.warmup_events == other.warmup_events),
+            ("auto_period", self.auto_period == other.auto_period),
+            ("decomposition", self.decomposition == other.decomposition),
+            (
+                "disabled_detectors",
+                self.disabled_detectors == other.disabled_detectors,
+            ),
+        ];
+        fields
+            .into_iter()
+            .filter(|(_, same)| !same)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Hot-reloadable fields that differ from `other`
+    pub fn decision_changes(&self, other: &ProfileConfig) -> Vec<&'static str> {
+        let fields = [
+            (
+                "confidence_threshold",
+                self.confidence_threshold == other.confidence_threshold,
+            ),
+            (
+                "min_detector_score_for_anomaly",
+                self.min_detector_score_for_anomaly == other.min_detector_score_for_anomaly,
+            ),
+            (
+                "min_ensemble_score_for_anomaly",
+                self.min_ensemble_score_for_anomaly == other.min_ensemble_score_for_anomaly,
+            ),
+            (
+                "use_adaptive_ensemble_threshold",
+                self.use_adaptive_ensemble_threshold == other.use_adaptive_ensemble_threshold,
+            ),
+            ("emission", self.emission == other.emission),
+            ("suppression", self.suppression == other.suppression),
+            (
+                "calibrated_severity",
+                self.calibrated_severity == other.calibrated_severity,
+            ),
+            (
+                "explain_history",
+                self.explain_history == other.explain_history,
+            ),
+        ];
+        fields
+            .into_iter()
+            .filter(|(_, same)| !same)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Check the hot-reloadable thresholds are usable
+    pub fn validate_decision(&self) -> Result<(), ConfigError> {
+        let thresholds = [
+            ("confidence_threshold", self.confidence_threshold),
+            (
+                "min_detector_score_for_anomaly",
+                self.min_detector_score_for_anomaly,
+            ),
+            (
+                "min_ensemble_score_for_anomaly",
+                self.min_ensemble_score_for_anomaly,
+            ),
+            ("emission.min_score", self.emission.min_score),
+        ];
+        for (field, value) in thresholds {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(ConfigError::OutOfRange { field, value });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProfileConfig {
+    fn default() -> Self {
+        Self {
+            hw_alpha: 0.3,
+            hw_beta: 0.1,
+            hw_gamma: 0.1,
+            period: 24,
+            hist_bins: 50,
+            min_val: 0.0,
+            max_val: 10000.0,
+            hist_decay: 0.999,
+            confidence_threshold: 0.5,
+            warmup_events: 100,
+            min_detector_score_for_anomaly: 0.10,
+            min_ensemble_score_for_anomaly: 0.10,
+            use_adaptive_ensemble_threshold: true,
+            auto_period: true,
+            emission: EmissionFloor::default(),
+            suppression: AlertSuppression::default(),
+            decomposition: None,
+            calibrated_severity: false,
+            disabled_detectors: Vec::new(),
+            explain_history: 32,
+        }
+    }
+}
+
+/// Stage-1 output of the built-in detectors for one event
+#[derive(Default)]
+struct DetectorRun {
+    scores: [DetectorScore; NUM_DETECTORS],
+    results: [Option<DetectionResult>; NUM_DETECTORS],
+    outputs: [DetectorOutput; NUM_DETECTORS],
+    output_count: usize,
+}
+
+/// Enhanced Anomaly Profile with Adaptive Ensemble
+///
+/// Serializes its full detection state (see [`Checkpointable`]); the
+/// lifecycle log and policy generation are process-local and start fresh.
+#[derive(Serialize, Deserialize)]
+pub struct AnomalyProfile {
+    // Detectors (Static Dispatch: No vtable overhead)
+    v_volume: VolumeDetectorV2,
+    v_dist: DistributionDetectorV2,
     v_card: CardinalityDetectorV2,
     v_burst: BurstDetectorV2,
     v_spectral: SpectralDetector,
@@ -783,9 +1806,16 @@ pub struct AnomalyProfile {
     v_ms: MultiScaleDetectorV2,
     v_behavioral: BehavioralFingerprintDetectorV2,
     v_drift: DriftDetectorV2,
+    v_quantile: QuantileDetector,
+    v_evt: EvtDetector,
+    v_discord: DiscordDetector,
 
+    /// Optional seasonal preprocessing (see [`DecompositionConfig`])
+    decomposition: Option<SeasonalDecomposition>,
     /// Adaptive ensemble for weight learning
     ensemble: AdaptiveEnsemble,
+    /// Ensemble score to anomaly probability, learned from feedback
+    calibrator: ScoreCalibrator,
     /// Event counter
     event_count: u64,
     /// Configuration
@@ -795,6 +1825,35 @@ pub struct AnomalyProfile {
     value_sum_sq: f64,
     last_timestamp: u64,
     frequency_ewma: EWMA,
+    /// Lifecycle events; behind a lock so `&self` paths (checkpoints) can log
+    #[serde(skip)]
+    lifecycle: Mutex<LifecycleLog>,
+    /// Drift detector fired on the previous event
+    drift_active: bool,
+    /// Policy runtime generation last seen
+    #[serde(skip)]
+    policy_generation: u64,
+    /// Host-reported degraded tier
+    degraded: bool,
+    /// Exogenous context of the last processed event
+    exogenous: ExogenousContext,
+    /// Pre-suppression decisions of recent events, newest in bit 0
+    recent_hits: u64,
+    /// No anomaly is raised before this timestamp
+    cooldown_until: u64,
+    /// The last event was anomalous but short of the hysteresis
+    hysteresis_pending: bool,
+    /// Host-supplied detectors run after the built-in ones (not checkpointed)
+    #[serde(skip)]
+    extra_detectors: Vec<Box<dyn Detector>>,
+    /// Scores of the extra detectors on the last event
+    #[serde(skip)]
+    extra_scores: Vec<DetectorScore>,
+    /// Last `explain_history` values, oldest first
+    recent_values: VecDeque<f64>,
+    /// Counters and snapshots published to [`ProfileView`]s
+    #[serde(skip)]
+    shared: Arc<SharedState>,
 }
 
 impl AnomalyProfile {
@@ -805,12 +1864,15 @@ impl AnomalyProfile {
 
     /// Create with custom configuration
     pub fn with_config(config: ProfileConfig) -> Self {
-        let v_volume = VolumeDetectorV2::new(
+        let mut v_volume = VolumeDetectorV2::new(
             config.hw_alpha,
             config.hw_beta,
             config.hw_gamma,
             config.period,
         );
+        if config.auto_period {
+            v_volume = v_volume.with_period_detection();
+        }
         let v_dist = DistributionDetectorV2::new(
             config.hist_bins,
             config.min_val,
@@ -825,6 +1887,9 @@ impl AnomalyProfile {
         let v_ms = MultiScaleDetectorV2::new();
         let v_behavioral = BehavioralFingerprintDetectorV2::new();
         let v_drift = DriftDetectorV2::new();
+        let v_quantile = QuantileDetector::new();
+        let v_evt = EvtDetector::new();
+        let v_discord = DiscordDetector::new();
 
         let detector_names = vec![
             v_volume.name().to_string(),
@@ -837,11 +1902,14 @@ impl AnomalyProfile {
             v_ms.name().to_string(),
             v_behavioral.name().to_string(),
             v_drift.name().to_string(),
+            v_quantile.name().to_string(),
+            v_evt.name().to_string(),
+            v_discord.name().to_string(),
         ];
 
         let ensemble = AdaptiveEnsemble::default_ensemble(detector_names);
 
-        Self {
+        let profile = Self {
             v_volume,
             v_dist,
             v_card,
@@ -852,14 +1920,61 @@ impl AnomalyProfile {
             v_ms,
             v_behavioral,
             v_drift,
+            v_quantile,
+            v_evt,
+            v_discord,
+            decomposition: config
+                .decomposition
+                .as_ref()
+                .map(DecompositionConfig::build),
             ensemble,
+            calibrator: ScoreCalibrator::new().with_half_life(CALIBRATION_HALF_LIFE),
             event_count: 0,
             config,
             value_sum: 0.0,
             value_sum_sq: 0.0,
             last_timestamp: 0,
             frequency_ewma: EWMA::new(100.0),
+            lifecycle: Mutex::new(LifecycleLog::default()),
+            drift_active: false,
+            policy_generation: 0,
+            degraded: false,
+            exogenous: ExogenousContext::default(),
+            recent_hits: 0,
+            cooldown_until: 0,
+            hysteresis_pending: false,
+            extra_detectors: Vec::new(),
+            extra_scores: Vec::new(),
+            recent_values: VecDeque::new(),
+            shared: Arc::default(),
+        };
+        profile.log_event(LifecycleEventKind::Created);
+        profile
+    }
+
+    /// Add custom detectors to the ensemble (builder)
+    ///
+    /// Each extra detector runs after the built-in ones on every event and
+    /// becomes one more ensemble arm with its own learned weight, so it moves
+    /// `ensemble_score` and the adaptive and score-floor decisions. The fixed
+    /// per-detector arrays of [`AnomalySignal`], and with them attribution,
+    /// the detector floor and feedback, cover the built-in detectors only;
+    /// read the extras' scores with
+    /// [`extra_detector_scores`](Self::extra_detector_scores). Their
+    /// [`Detector::id`] is not used.
+    ///
+    /// Extra detectors are not checkpointed. A restored profile keeps their
+    /// ensemble weights; call this again with the same detectors in the same
+    /// order to reattach them.
+    pub fn with_extra_detectors(mut self, detectors: Vec<Box<dyn Detector>>) -> Self {
+        for detector in detectors {
+            if self.ensemble.num_detectors() <= NUM_DETECTORS + self.extra_detectors.len() {
+                self.ensemble.add_detector(detector.name().to_string());
+            }
+            self.extra_detectors.push(detector);
+            self.extra_scores.push(DetectorScore::default());
         }
+        self
     }
 
     /// Legacy constructor for backward compatibility
@@ -899,7 +2014,91 @@ impl AnomalyProfile {
         unique_id_hash: u64,
         value: f64,
     ) -> AnomalySignal {
+        self.process_with_context(
+            timestamp,
+            unique_id_hash,
+            value,
+            ExogenousContext::default(),
+        )
+    }
+
+    /// Process an event with host-supplied context modulating sensitivity
+    /// (see [`ExogenousContext`])
+    pub fn process_with_context(
+        &mut self,
+        timestamp: u64,
+        unique_id_hash: u64,
+        value: f64,
+        exogenous: ExogenousContext,
+    ) -> AnomalySignal {
+        self.process_features(
+            timestamp,
+            unique_id_hash,
+            FeatureVector::scalar(value),
+            exogenous,
+        )
+    }
+
+    /// Process a multivariate event whose values follow [`FeatureChannel`]
+    /// order (latency, payload size, status class)
+    ///
+    /// The latency channel drives the scalar detectors and the baseline, as
+    /// `value` does in [`process_with_hash`](Self::process_with_hash); RRCF
+    /// scores all channels jointly. Extra values are ignored and an empty
+    /// slice is treated as a zero latency.
+    pub fn process_vector(
+        &mut self,
+        timestamp: u64,
+        unique_id_hash: u64,
+        features: &[f64],
+    ) -> AnomalySignal {
+        let features = if features.is_empty() {
+            FeatureVector::scalar(0.0)
+        } else {
+            FeatureVector::from_slice(features)
+        };
+        self.process_features(
+            timestamp,
+            unique_id_hash,
+            features,
+            ExogenousContext::default(),
+        )
+    }
+
+    fn process_features(
+        &mut self,
+        timestamp: u64,
+        unique_id_hash: u64,
+        features: FeatureVector,
+        exogenous: ExogenousContext,
+    ) -> AnomalySignal {
+        self.detect(timestamp, unique_id_hash, features, exogenous, None)
+            .expect("without a floor every event yields a signal")
+    }
+
+    /// Run detection on one event; with a `floor`, a signal is only built
+    /// for an event that clears it, so normal events skip the attribution
+    /// ranges and explanation entirely
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(
+            name = "process_event",
+            level = "debug",
+            skip_all,
+            fields(entity = unique_id_hash, timestamp)
+        )
+    )]
+    fn detect(
+        &mut self,
+        timestamp: u64,
+        unique_id_hash: u64,
+        features: FeatureVector,
+        exogenous: ExogenousContext,
+        floor: Option<EmissionFloor>,
+    ) -> Option<AnomalySignal> {
+        let value = features.as_slice()[0];
         self.event_count += 1;
+        self.exogenous = exogenous;
 
         // Update baseline tracking
         self.value_sum += value;
@@ -915,20 +2114,41 @@ impl AnomalyProfile {
         }
         self.last_timestamp = timestamp;
 
+        if self.config.explain_history > 0 {
+            while self.recent_values.len() >= self.config.explain_history {
+                self.recent_values.pop_front();
+            }
+            self.recent_values.push_back(value);
+        } else {
+            self.recent_values.clear();
+        }
+
         let is_warmup = self.event_count < self.config.warmup_events as u64;
+        if self.event_count == self.config.warmup_events as u64 {
+            self.log_event(LifecycleEventKind::WarmupComplete);
+        }
+
+        // Detectors see the seasonally adjusted value when decomposing
+        let decomposition = self
+            .decomposition
+            .as_mut()
+            .map(|d| d.update(timestamp, value));
+        let detector_value = decomposition.map_or(value, |d| d.adjusted());
+        let mut features = features;
+        features.set(FeatureChannel::Latency, detector_value);
 
         let ctx = SignalContext {
             timestamp,
             unique_id_hash,
-            value,
+            value: detector_value,
+            features,
             is_warmup,
             sequence: self.event_count,
+            exogenous,
         };
 
         // === STAGE 1: Run all detectors ===
-        let mut detector_outputs = [DetectorOutput::default(); NUM_DETECTORS];
-        let mut output_count = 0usize;
-        let mut detector_scores = [DetectorScore::default(); NUM_DETECTORS];
+        let mut run = DetectorRun::default();
 
         let n = self.event_count as f64;
         let avg = self.value_sum / n.max(1.0);
@@ -938,93 +2158,131 @@ impl AnomalyProfile {
         let uncertainty_score = self.compute_uncertainty(value, avg, std);
         let use_fast_path = uncertainty_score < 0.3 && !is_warmup;
 
-        // Run all 10 detectors with static dispatch
-        // Note: We ALWAYS run all detectors to maintain state consistency
+        // Run all enabled built-in detectors with static dispatch
+        // Note: We ALWAYS run every enabled detector to maintain state consistency
         // The uncertainty gate only affects the combine path complexity
         Self::run_detector(
             &mut self.v_volume,
             &ctx,
             use_fast_path,
-            &mut detector_scores,
-            &mut detector_outputs,
-            &mut output_count,
+            &self.config.disabled_detectors,
+            &mut run,
         );
         Self::run_detector(
             &mut self.v_dist,
             &ctx,
             use_fast_path,
-            &mut detector_scores,
-            &mut detector_outputs,
-            &mut output_count,
+            &self.config.disabled_detectors,
+            &mut run,
         );
         Self::run_detector(
             &mut self.v_card,
             &ctx,
             use_fast_path,
-            &mut detector_scores,
-            &mut detector_outputs,
-            &mut output_count,
+            &self.config.disabled_detectors,
+            &mut run,
         );
         Self::run_detector(
             &mut self.v_burst,
             &ctx,
             use_fast_path,
-            &mut detector_scores,
-            &mut detector_outputs,
-            &mut output_count,
+            &self.config.disabled_detectors,
+            &mut run,
         );
         Self::run_detector(
             &mut self.v_spectral,
             &ctx,
             use_fast_path,
-            &mut detector_scores,
-            &mut detector_outputs,
-            &mut output_count,
+            &self.config.disabled_detectors,
+            &mut run,
         );
         Self::run_detector(
             &mut self.v_cp,
             &ctx,
             use_fast_path,
-            &mut detector_scores,
-            &mut detector_outputs,
-            &mut output_count,
+            &self.config.disabled_detectors,
+            &mut run,
         );
         Self::run_detector(
             &mut self.v_rrcf,
             &ctx,
             use_fast_path,
-            &mut detector_scores,
-            &mut detector_outputs,
-            &mut output_count,
+            &self.config.disabled_detectors,
+            &mut run,
         );
         Self::run_detector(
             &mut self.v_ms,
             &ctx,
             use_fast_path,
-            &mut detector_scores,
-            &mut detector_outputs,
-            &mut output_count,
+            &self.config.disabled_detectors,
+            &mut run,
         );
         Self::run_detector(
             &mut self.v_behavioral,
             &ctx,
             use_fast_path,
-            &mut detector_scores,
-            &mut detector_outputs,
-            &mut output_count,
+            &self.config.disabled_detectors,
+            &mut run,
         );
         Self::run_detector(
             &mut self.v_drift,
             &ctx,
             use_fast_path,
-            &mut detector_scores,
-            &mut detector_outputs,
-            &mut output_count,
+            &self.config.disabled_detectors,
+            &mut run,
+        );
+        Self::run_detector(
+            &mut self.v_quantile,
+            &ctx,
+            use_fast_path,
+            &self.config.disabled_detectors,
+            &mut run,
+        );
+        Self::run_detector(
+            &mut self.v_evt,
+            &ctx,
+            use_fast_path,
+            &self.config.disabled_detectors,
+            &mut run,
+        );
+        Self::run_detector(
+            &mut self.v_discord,
+            &ctx,
+            use_fast_path,
+            &self.config.disabled_detectors,
+            &mut run,
         );
 
+        let DetectorRun {
+            scores: mut detector_scores,
+            mut results,
+            outputs: mut detector_outputs,
+            output_count,
+        } = run;
+
+        if exogenous != ExogenousContext::default() {
+            for output in &mut detector_outputs[..output_count] {
+                let sensitivity = DetectorId::from_u8(output.detector_id as u8)
+                    .map_or(1.0, |d| exogenous.detector_sensitivity(d));
+                output.score *= sensitivity;
+                detector_scores[output.detector_id].score *= sensitivity as f32;
+            }
+        }
+
+        let drift = detector_scores[DetectorId::Drift as usize];
+        if drift.fired && !self.drift_active {
+            self.log_event(LifecycleEventKind::DriftRelearn {
+                severity: drift.score as f64,
+            });
+        }
+        self.drift_active = drift.fired;
+
+        let mut outputs: SmallVec<[DetectorOutput; 2 * NUM_DETECTORS]> =
+            SmallVec::from_slice(&detector_outputs[..output_count]);
+        self.run_extra_detectors(&ctx, &mut outputs);
+
         // === STAGE 2: Combine with AdaptiveEnsemble ===
-        let (ensemble_score, ensemble_confidence) =
-            self.ensemble.combine(&detector_outputs[..output_count]);
+        let (ensemble_score, ensemble_confidence) = self.ensemble.combine(&outputs);
 
         // Convert weights to fixed array
         let mut weight_array = [0.1f32; NUM_DETECTORS];
@@ -1044,6 +2302,10 @@ impl AnomalyProfile {
             std_value: std as f32,
             avg_frequency: self.frequency_ewma.get_value() as f32,
             profile_age: self.event_count as u32,
+            detected_period: self.v_volume.detected_period().unwrap_or(0) as u32,
+            trend: decomposition.map_or(0.0, |d| d.trend) as f32,
+            seasonal: decomposition.map_or(0.0, |d| d.seasonal) as f32,
+            residual: decomposition.map_or(0.0, |d| d.residual) as f32,
             is_warmup,
         };
 
@@ -1058,6 +2320,13 @@ impl AnomalyProfile {
         let attribution = Attribution::compute(&detector_scores, &weights_f64);
 
         // Apply Tier-2 compiled runtime policy (if any)
+        let policy_generation = policy_runtime().generation();
+        if policy_generation != self.policy_generation {
+            self.policy_generation = policy_generation;
+            self.log_event(LifecycleEventKind::PolicyApplied {
+                version: policy_runtime().current_version(),
+            });
+        }
         let policy_effect = policy_runtime().evaluate(
             unique_id_hash,
             attribution.primary_detector,
@@ -1068,33 +2337,415 @@ impl AnomalyProfile {
             (ensemble_confidence * policy_effect.confidence_scale).clamp(0.0, 1.0);
 
         // Build the signal
-        let severity = Severity::from_score(adjusted_score);
+        let calibrated_probability = self.calibrator.probability(adjusted_score);
+        let severity = match calibrated_probability {
+            Some(probability) if self.config.calibrated_severity => {
+                Severity::from_score(probability)
+            }
+            _ => Severity::from_score(adjusted_score),
+        };
 
         // Hybrid decision: detector floor + ensemble score floor + adaptive ensemble threshold.
+        let floor_scale = exogenous.decision_floor_scale();
+        let detector_floor = self.config.min_detector_score_for_anomaly * floor_scale;
         let any_detector_fired = detector_scores
             .iter()
-            .any(|s| s.fired && (s.score as f64) >= self.config.min_detector_score_for_anomaly);
+            .any(|s| s.fired && (s.score as f64) >= detector_floor);
         let adaptive_trigger = self.config.use_adaptive_ensemble_threshold
             && self.ensemble.is_anomaly(adjusted_score)
             && adjusted_confidence >= self.config.confidence_threshold;
-        let score_floor_trigger = adjusted_score >= self.config.min_ensemble_score_for_anomaly;
+        let score_floor_trigger =
+            adjusted_score >= self.config.min_ensemble_score_for_anomaly * floor_scale;
 
         let is_anomaly = !policy_effect.suppress
             && (any_detector_fired || adaptive_trigger || score_floor_trigger);
+        let (is_anomaly, suppressed_by_cooldown) = self.apply_suppression(timestamp, is_anomaly);
+
+        #[cfg(feature = "instrument")]
+        if is_anomaly || suppressed_by_cooldown {
+            let fired: Vec<&str> = (0..NUM_DETECTORS)
+                .filter(|&i| detector_scores[i].fired)
+                .filter_map(|i| DetectorId::from_u8(i as u8).map(|id| id.name()))
+                .collect();
+            tracing::debug!(
+                score = adjusted_score,
+                confidence = adjusted_confidence,
+                detector_trigger = any_detector_fired,
+                adaptive_trigger,
+                score_floor_trigger,
+                suppressed = suppressed_by_cooldown,
+                fired = %fired.join(","),
+                "anomaly"
+            );
+        }
+
+        if let Some(floor) = floor
+            && !floor.admits_decision(is_anomaly, severity, adjusted_score)
+        {
+            self.publish(timestamp, is_anomaly);
+            return None;
+        }
+
+        // Detectors judged the seasonally adjusted value; report in raw units
+        let normal_range = if is_anomaly || suppressed_by_cooldown {
+            let mut ranges = results.iter().flatten().filter_map(|r| r.normal_range);
+            ranges
+                .next()
+                .and_then(|first| ranges.try_fold(first, |acc, r| acc.intersect(&r)))
+                .map(|range| range.shifted(value - detector_value))
+        } else {
+            None
+        };
+
+        let explanation = ((is_anomaly || suppressed_by_cooldown)
+            && self.config.explain_history > 0)
+            .then(|| {
+                Box::new(SignalExplanation {
+                    history: self.recent_values.iter().copied().collect(),
+                    fired: results
+                        .iter_mut()
+                        .enumerate()
+                        .filter_map(|(i, result)| {
+                            let result = result.take()?;
+                            let score = &detector_scores[i];
+                            Some(FiredDetector {
+                                detector: DetectorId::from_u8(i as u8)?,
+                                score: score.score,
+                                expected: score.expected,
+                                observed: score.observed,
+                                threshold: detector_floor,
+                                reason: result.reason.to_string(),
+                                normal_range: result.normal_range,
+                            })
+                        })
+                        .collect(),
+                    contributions: WeightContribution::compute(&detector_scores, &weights_f64),
+                })
+            });
 
-        AnomalySignal {
+        let signal = AnomalySignal {
             entity_hash: unique_id_hash,
             timestamp,
             sequence: self.event_count,
             is_anomaly,
+            suppressed_by_cooldown,
             severity,
             ensemble_score: adjusted_score,
             confidence: adjusted_confidence,
+            calibrated_probability: calibrated_probability.map(|p| p as f32),
             detector_scores,
             detector_weights: weight_array,
             attribution,
+            normal_range,
             baseline,
             raw_value: value,
+            explanation,
+        };
+        self.publish(timestamp, is_anomaly);
+        Some(signal)
+    }
+
+    /// Hysteresis and cooldown on the hybrid decision; returns whether to
+    /// raise the event and whether the cooldown held it back
+    fn apply_suppression(&mut self, timestamp: u64, anomalous: bool) -> (bool, bool) {
+        let suppression = self.config.suppression;
+        let window = suppression.window_events.clamp(1, 64);
+        let mask = u64::MAX >> (64 - window);
+        self.recent_hits = ((self.recent_hits << 1) | anomalous as u64) & mask;
+
+        let held = anomalous
+            && self.recent_hits.count_ones() >= suppression.required_hits.clamp(1, window);
+        self.hysteresis_pending = anomalous && !held;
+        if !held {
+            return (false, false);
+        }
+        if timestamp < self.cooldown_until {
+            return (false, true);
+        }
+        self.cooldown_until =
+            timestamp.saturating_add(suppression.cooldown_ms.saturating_mul(1_000_000));
+        (true, false)
+    }
+
+    /// Process an event, returning a signal only when it clears the
+    /// configured [`EmissionFloor`]; `None` means a normal event
+    pub fn process_filtered(
+        &mut self,
+        timestamp: u64,
+        unique_id_hash: u64,
+        value: f64,
+        exogenous: ExogenousContext,
+    ) -> Option<AnomalySignal> {
+        let floor = self.config.emission;
+        self.detect(
+            timestamp,
+            unique_id_hash,
+            FeatureVector::scalar(value),
+            exogenous,
+            Some(floor),
+        )
+    }
+
+    /// Process `(timestamp, unique_id_hash, value)` events in order, one
+    /// signal per event; equivalent to calling
+    /// [`process_with_hash`](Self::process_with_hash) on each
+    pub fn process_batch(&mut self, events: &[(u64, u64, f64)]) -> Vec<AnomalySignal> {
+        let mut signals = Vec::with_capacity(events.len());
+        self.process_batch_into(events, &mut signals, false);
+        signals
+    }
+
+    /// Process events in order, appending their signals to `out`
+    ///
+    /// Hosts that keep `out` across calls pay for its allocation once. With
+    /// `filtered`, only signals clearing the [`EmissionFloor`] are appended,
+    /// as with [`process_filtered`](Self::process_filtered); an event's
+    /// position in the batch is its `sequence` minus the first event's.
+    /// Returns the number of signals appended.
+    pub fn process_batch_into(
+        &mut self,
+        events: &[(u64, u64, f64)],
+        out: &mut Vec<AnomalySignal>,
+        filtered: bool,
+    ) -> usize {
+        let start = out.len();
+        if !filtered {
+            out.reserve(events.len());
+        }
+        let floor = filtered.then_some(self.config.emission);
+        for &(timestamp, unique_id_hash, value) in events {
+            let features = FeatureVector::scalar(value);
+            let exogenous = ExogenousContext::default();
+            if let Some(signal) = self.detect(timestamp, unique_id_hash, features, exogenous, floor)
+            {
+                out.push(signal);
+            }
+        }
+        out.len() - start
+    }
+
+    /// Change the floor used by [`process_filtered`](Self::process_filtered)
+    pub fn set_emission_floor(&mut self, floor: EmissionFloor) {
+        self.config.emission = floor;
+    }
+
+    pub fn config(&self) -> &ProfileConfig {
+        &self.config
+    }
+
+    /// Check whether `config` could replace this profile's configuration
+    /// (see [`update_config`](Self::update_config))
+    pub fn check_config(&self, config: &ProfileConfig) -> Result<(), ConfigError> {
+        let structural = self.config.structural_changes(config);
+        if !structural.is_empty() {
+            return Err(ConfigError::Structural(structural));
+        }
+        config.validate_decision()
+    }
+
+    /// Apply new decision settings to a live profile, keeping every learned
+    /// baseline, detector state and ensemble weight
+    ///
+    /// All-or-nothing: a change to a structural field (see
+    /// [`ProfileConfig::structural_changes`]) or an out-of-range threshold
+    /// rejects the whole update and leaves the profile untouched.
+    pub fn update_config(&mut self, config: ProfileConfig) -> Result<(), ConfigError> {
+        self.check_config(&config)?;
+        let changed = self.config.decision_changes(&config);
+        if !changed.is_empty() {
+            self.config = config;
+            self.log_event(LifecycleEventKind::ConfigReloaded {
+                fields: changed.into_iter().map(String::from).collect(),
+            });
+        }
+        Ok(())
+    }
+
+    /// What the profile learned about normal behavior, as a portable
+    /// [`ProfileBaseline`]
+    pub fn export_baseline(&self) -> ProfileBaseline {
+        let n = self.event_count as f64;
+        let mean = self.value_sum / n.max(1.0);
+        let variance = self.value_sum_sq / n.max(1.0) - mean * mean;
+        ProfileBaseline {
+            version: BASELINE_VERSION,
+            events: self.event_count,
+            value: ValueStats {
+                mean,
+                std: variance.max(0.0).sqrt(),
+            },
+            frequency: self.frequency_ewma.get_value(),
+            volume: self.v_volume.seasonal_baseline(),
+            histogram: self.v_dist.histogram_baseline(),
+        }
+    }
+
+    /// A new profile starting from a learned ("golden") baseline
+    ///
+    /// The profile counts the baseline's events as its own, so it is past
+    /// warmup when the baseline was; the Volume and Distribution detectors
+    /// start from the learned rate model and histogram. The other detectors
+    /// learn from live traffic. The histogram layout (`hist_bins`,
+    /// `min_val`, `max_val`) has to match `config`.
+    pub fn from_baseline(
+        config: ProfileConfig,
+        baseline: &ProfileBaseline,
+    ) -> Result<Self, BaselineError> {
+        baseline.check_version()?;
+        let mut profile = Self::with_config(config);
+        profile.v_dist.seed(&baseline.histogram)?;
+        if let Some(volume) = &baseline.volume {
+            profile.v_volume.seed(volume);
+        }
+
+        let n = baseline.events as f64;
+        profile.event_count = baseline.events;
+        profile.value_sum = baseline.value.mean * n;
+        profile.value_sum_sq = (baseline.value.std.powi(2) + baseline.value.mean.powi(2)) * n;
+        profile.frequency_ewma.seed(baseline.frequency);
+        Ok(profile)
+    }
+
+    /// Fold in a profile that tracked the same entity on another node
+    ///
+    /// For detection sharded by partition and consolidated later: each side
+    /// saw a disjoint part of the entity's events. Mergeable state is
+    /// combined exactly (cardinality registers, value histogram, quantile
+    /// sketches, event count and value sums). The event rate of the merged
+    /// stream is the sum of both, so the Volume detector's Holt-Winters
+    /// components and the frequency estimate add up, with seasonal phases
+    /// aligned, and the Burst detector's inter-arrival time follows. CUSUM
+    /// statistics and smoothed trends are averaged, weighted by each side's
+    /// events. Forests, windows, the ensemble and the calibrator stay this
+    /// profile's and keep learning from the merged stream.
+    ///
+    /// The histogram layout and any learned season length have to match;
+    /// otherwise nothing is changed.
+    pub fn merge(&mut self, other: &AnomalyProfile) -> Result<(), BaselineError> {
+        let (a, b) = (
+            self.v_dist.histogram_baseline(),
+            other.v_dist.histogram_baseline(),
+        );
+        if a.bins.len() != b.bins.len() {
+            return Err(BaselineError::Incompatible("histogram.bins"));
+        }
+        if (a.min, a.max) != (b.min, b.max) {
+            return Err(BaselineError::Incompatible("histogram range"));
+        }
+        if let (Some(x), Some(y)) = (
+            self.v_volume.seasonal_baseline(),
+            other.v_volume.seasonal_baseline(),
+        ) && x.seasonals.len() != y.seasonals.len()
+        {
+            return Err(BaselineError::Incompatible("volume.seasonals"));
+        }
+
+        let events = self.event_count + other.event_count;
+        let weight = if events == 0 {
+            0.5
+        } else {
+            self.event_count as f64 / events as f64
+        };
+
+        self.v_volume.merge(&other.v_volume)?;
+        self.v_dist.merge(&other.v_dist)?;
+        self.v_quantile.merge(&other.v_quantile)?;
+        self.v_card.merge(&other.v_card, weight);
+        self.v_burst.merge(&other.v_burst, weight);
+        self.v_cp.merge(&other.v_cp, weight);
+
+        self.event_count = events;
+        self.value_sum += other.value_sum;
+        self.value_sum_sq += other.value_sum_sq;
+        let frequency = self.frequency_ewma.get_value() + other.frequency_ewma.get_value();
+        if frequency > 0.0 {
+            self.frequency_ewma.seed(frequency);
+        }
+        self.last_timestamp = self.last_timestamp.max(other.last_timestamp);
+        Ok(())
+    }
+
+    /// Explain which branch of the hybrid decision produced `signal`.
+    ///
+    /// Detector and score floors are evaluated from the signal itself. The
+    /// adaptive threshold and hysteresis state are the profile's current
+    /// ones, so explain right after processing. Policy suppression is not
+    /// recorded on the signal; it is inferred when a branch held but the
+    /// signal is not anomalous and neither cooldown nor hysteresis held it.
+    pub fn explain_decision(&self, signal: &AnomalySignal) -> DecisionExplanation {
+        let strongest = signal
+            .detector_scores
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.fired)
+            .max_by(|a, b| a.1.score.total_cmp(&b.1.score));
+        let strongest_score = strongest.map(|(_, s)| s.score as f64).unwrap_or(0.0);
+        let adaptive_enabled = self.config.use_adaptive_ensemble_threshold;
+        // Floors as applied to the last event, which is the one being explained
+        let floor_scale = self.exogenous.decision_floor_scale();
+
+        let mut detector_floor = ConditionCheck::at_least(
+            DecisionBranch::DetectorFloor,
+            "strongest fired detector score >= min_detector_score",
+            strongest_score,
+            self.config.min_detector_score_for_anomaly * floor_scale,
+        );
+        detector_floor.met &= strongest.is_some();
+
+        let mut conditions = vec![detector_floor];
+        conditions.push(ConditionCheck::above(
+            DecisionBranch::AdaptiveThreshold,
+            "ensemble_score > adaptive_threshold",
+            signal.ensemble_score,
+            self.ensemble.get_threshold(),
+        ));
+        conditions.push(ConditionCheck::at_least(
+            DecisionBranch::AdaptiveThreshold,
+            "confidence >= confidence_threshold",
+            signal.confidence,
+            self.config.confidence_threshold,
+        ));
+        conditions.push(ConditionCheck::at_least(
+            DecisionBranch::ScoreFloor,
+            "ensemble_score >= min_ensemble_score",
+            signal.ensemble_score,
+            self.config.min_ensemble_score_for_anomaly * floor_scale,
+        ));
+
+        let branch_met = |branch: DecisionBranch| {
+            conditions
+                .iter()
+                .filter(|c| c.branch == branch)
+                .all(|c| c.met)
+        };
+        let mut triggered: Vec<DecisionBranch> = [
+            DecisionBranch::DetectorFloor,
+            DecisionBranch::AdaptiveThreshold,
+            DecisionBranch::ScoreFloor,
+        ]
+        .into_iter()
+        .filter(|&b| b != DecisionBranch::AdaptiveThreshold || adaptive_enabled)
+        .filter(|&b| branch_met(b))
+        .collect();
+
+        let suppressed = !signal.is_anomaly && !triggered.is_empty();
+        if suppressed {
+            triggered.push(if signal.suppressed_by_cooldown {
+                DecisionBranch::Cooldown
+            } else if self.hysteresis_pending {
+                DecisionBranch::Hysteresis
+            } else {
+                DecisionBranch::PolicySuppression
+            });
+        }
+
+        DecisionExplanation {
+            is_anomaly: signal.is_anomaly,
+            triggered,
+            suppressed,
+            strongest_detector: strongest.and_then(|(i, _)| DetectorId::from_u8(i as u8)),
+            adaptive_enabled,
+            conditions,
         }
     }
 
@@ -1104,17 +2755,20 @@ impl AnomalyProfile {
         detector: &mut D,
         ctx: &SignalContext,
         _fast_path: bool,
-        scores: &mut [DetectorScore; NUM_DETECTORS],
-        outputs: &mut [DetectorOutput; NUM_DETECTORS],
-        output_count: &mut usize,
+        disabled: &[DetectorId],
+        run: &mut DetectorRun,
     ) {
+        // Disabled detectors keep no state and add no ensemble arm
+        if disabled.contains(&detector.id()) {
+            return;
+        }
         let detector_id = detector.id() as usize;
 
         // IMPORTANT: Always run detector.update() to maintain state consistency
         // Fast path only affects output complexity, not detector state
 
         if let Some(result) = detector.update(ctx) {
-            scores[detector_id] = DetectorScore::new(
+            run.scores[detector_id] = DetectorScore::new(
                 result.score,
                 result.confidence,
                 true,
@@ -1122,21 +2776,58 @@ impl AnomalyProfile {
                 ctx.value,
             );
 
-            outputs[*output_count] = DetectorOutput {
+            run.outputs[run.output_count] = DetectorOutput {
                 detector_id,
                 score: result.score,
                 confidence: result.confidence,
                 signal_type: result.signal_type,
             };
-            *output_count += 1;
+            run.output_count += 1;
+            run.results[detector_id] = Some(result);
         } else {
-            outputs[*output_count] = DetectorOutput {
+            run.outputs[run.output_count] = DetectorOutput {
                 detector_id,
                 score: 0.0,
                 confidence: 1.0,
                 signal_type: 0,
             };
-            *output_count += 1;
+            run.output_count += 1;
+        }
+    }
+
+    /// Run the extra detectors, appending their outputs as ensemble arms
+    /// `NUM_DETECTORS..`
+    fn run_extra_detectors(
+        &mut self,
+        ctx: &SignalContext,
+        outputs: &mut SmallVec<[DetectorOutput; 2 * NUM_DETECTORS]>,
+    ) {
+        let extras = self.extra_detectors.iter_mut().zip(&mut self.extra_scores);
+        for (i, (detector, score)) in extras.enumerate() {
+            let detector_id = NUM_DETECTORS + i;
+            if let Some(result) = detector.update(ctx) {
+                *score = DetectorScore::new(
+                    result.score,
+                    result.confidence,
+                    true,
+                    result.expected,
+                    ctx.value,
+                );
+                outputs.push(DetectorOutput {
+                    detector_id,
+                    score: result.score,
+                    confidence: result.confidence,
+                    signal_type: result.signal_type,
+                });
+            } else {
+                *score = DetectorScore::default();
+                outputs.push(DetectorOutput {
+                    detector_id,
+                    score: 0.0,
+                    confidence: 1.0,
+                    signal_type: 0,
+                });
+            }
         }
     }
 
@@ -1167,12 +2858,23 @@ impl AnomalyProfile {
         (value_uncertainty + frequency_deviation) / 2.0
     }
 
-    /// Apply feedback to update ensemble weights
+    /// Apply feedback to update ensemble weights and, for events carrying
+    /// the signal's ensemble score, the score calibration
     pub fn apply_feedback(&mut self, events: &[FeedbackEvent]) {
         if events.is_empty() {
             return;
         }
 
+        for event in events {
+            if let Some(score) = event.ensemble_score {
+                self.calibrator.observe(
+                    score as f64,
+                    event.was_true_positive,
+                    event.feedback_confidence.clamp(0.1, 1.0) as f64,
+                );
+            }
+        }
+
         let update = LearningUpdate::from_batch(events);
 
         if !update.is_significant() {
@@ -1201,6 +2903,11 @@ impl AnomalyProfile {
         }
     }
 
+    /// Score calibration learned from feedback
+    pub fn calibrator(&self) -> &ScoreCalibrator {
+        &self.calibrator
+    }
+
     /// Get current ensemble weights
     pub fn get_weights(&self) -> Vec<f64> {
         self.ensemble.current_weights().to_vec()
@@ -1225,73 +2932,225 @@ impl AnomalyProfile {
                 self.v_behavioral.get_stats(),
             ),
             (self.v_drift.name().to_string(), self.v_drift.get_stats()),
+            (
+                self.v_quantile.name().to_string(),
+                self.v_quantile.get_stats(),
+            ),
+            (self.v_evt.name().to_string(), self.v_evt.get_stats()),
+            (
+                self.v_discord.name().to_string(),
+                self.v_discord.get_stats(),
+            ),
         ]
+        .into_iter()
+        .chain(
+            self.extra_detectors
+                .iter()
+                .map(|d| (d.name().to_string(), d.get_stats())),
+        )
+        .collect()
     }
 
-    /// Reset the profile
+    /// Scores of the detectors added with
+    /// [`with_extra_detectors`](Self::with_extra_detectors) on the last event
+    pub fn extra_detector_scores(&self) -> &[DetectorScore] {
+        &self.extra_scores
+    }
+
+    /// Reset the profile (the lifecycle log is kept)
     pub fn reset(&mut self) {
         self.event_count = 0;
         self.value_sum = 0.0;
         self.value_sum_sq = 0.0;
         self.last_timestamp = 0;
         self.ensemble.reset();
+        self.calibrator = ScoreCalibrator::new().with_half_life(CALIBRATION_HALF_LIFE);
+        self.shared.record_event(0, 0, false);
+        self.log_event(LifecycleEventKind::Reset);
+    }
+
+    /// Read-only handle other threads can poll while this profile keeps
+    /// processing (see [`crate::view`])
+    ///
+    /// The view starts with a snapshot of the current state; after that the
+    /// processing thread republishes every [`PUBLISH_INTERVAL`] events.
+    pub fn view(&self) -> ProfileView {
+        self.shared
+            .record_event(self.event_count, self.last_timestamp, false);
+        self.shared.publish_stats(self.stats_snapshot());
+        ProfileView::new(Arc::clone(&self.shared))
+    }
+
+    fn stats_snapshot(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            event_count: self.event_count,
+            weights: self.get_weights(),
+            detector_stats: self.get_detector_stats(),
+        }
+    }
+
+    /// Hand the processed event to any views
+    fn publish(&self, timestamp: u64, is_anomaly: bool) {
+        self.shared
+            .record_event(self.event_count, timestamp, is_anomaly);
+        if Arc::strong_count(&self.shared) == 1 {
+            return;
+        }
+        if self.event_count.is_multiple_of(PUBLISH_INTERVAL) {
+            self.shared.publish_stats(self.stats_snapshot());
+        }
+        if self.shared.take_checkpoint_request() {
+            self.shared
+                .publish_checkpoint(self.event_count, self.to_checkpoint());
+        }
     }
 
     /// Get event count
     pub fn event_count(&self) -> u64 {
         self.event_count
     }
+
+    /// Record a host-reported tier change; repeated reports of the same state
+    /// are ignored
+    pub fn record_tier_change(&mut self, degraded: bool, reason: &str) {
+        if degraded != self.degraded {
+            self.degraded = degraded;
+            self.log_event(LifecycleEventKind::TierChange {
+                degraded,
+                reason: reason.to_string(),
+            });
+        }
+    }
+
+    /// Lifecycle events recorded at or after `since_unix_ms`
+    pub fn lifecycle(&self, since_unix_ms: u64) -> LifecycleLog {
+        self.lifecycle.lock().unwrap().since(since_unix_ms)
+    }
+
+    fn log_event(&self, kind: LifecycleEventKind) {
+        #[cfg(feature = "instrument")]
+        tracing::debug!(event = ?kind, events = self.event_count, "lifecycle");
+        self.lifecycle
+            .lock()
+            .unwrap()
+            .record(self.event_count, self.last_timestamp, kind);
+    }
 }
 
+impl ProfileRegistry<AnomalyProfile> {
+    /// [`AnomalyProfile::update_config`] for every held profile
+    ///
+    /// Every profile is checked before any is changed, so a rejection leaves
+    /// the whole registry on its old configuration. Returns the number of
+    /// profiles updated.
+    pub fn update_config(&mut self, config: &ProfileConfig) -> Result<usize, ConfigError> {
+        for (_, profile) in self.iter() {
+            profile.check_config(config)?;
+        }
+        let mut updated = 0;
+        for (_, profile) in self.iter_mut() {
+            profile.update_config(config.clone())?;
+            updated += 1;
+        }
+        Ok(updated)
+    }
+}
+
+/// Detectors in the ensemble when version-1 checkpoints were written
+const V1_NUM_DETECTORS: usize = 10;
+/// Size of a version-1 checkpoint, which held only an
+/// [`EnsembleCheckpoint`](crate::checkpoint::EnsembleCheckpoint) of the
+/// first ten detectors
+const V1_CHECKPOINT_LEN: usize = 3 * V1_NUM_DETECTORS * 8 + 8;
+
+/// Version-1 weights, bandit alphas, bandit betas and sample count
+type V1Ensemble = (
+    [f64; V1_NUM_DETECTORS],
+    [f64; V1_NUM_DETECTORS],
+    [f64; V1_NUM_DETECTORS],
+    u64,
+);
+
 impl Checkpointable for AnomalyProfile {
+    /// Serialize every detector, the ensemble learner, and baseline tracking
+    /// behind a `CHECKPOINT_VERSION` prefix, so a restored profile continues
+    /// exactly where this one stopped
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(name = "checkpoint", level = "debug", skip_all)
+    )]
     fn to_checkpoint(&self) -> Vec<u8> {
-        // Serialize ensemble state
-        let weights = self.get_weights();
-        let (alphas, betas) = self.ensemble.bandit_params();
-        let checkpoint = EnsembleCheckpoint {
-            weights: {
-                let mut arr = [0.1; NUM_DETECTORS];
-                for (i, w) in weights.iter().enumerate().take(NUM_DETECTORS) {
-                    arr[i] = *w;
-                }
-                arr
-            },
-            alpha: {
-                let mut arr = [1.0; NUM_DETECTORS];
-                for (i, a) in alphas.iter().enumerate().take(NUM_DETECTORS) {
-                    arr[i] = *a;
-                }
-                arr
-            },
-            beta: {
-                let mut arr = [1.0; NUM_DETECTORS];
-                for (i, b) in betas.iter().enumerate().take(NUM_DETECTORS) {
-                    arr[i] = *b;
-                }
-                arr
-            },
-            total_samples: self.event_count,
+        let bytes = bincode::serialize(&(CHECKPOINT_VERSION, self)).unwrap_or_default();
+        self.log_event(LifecycleEventKind::Checkpoint { bytes: bytes.len() });
+        bytes
+    }
+
+    /// Restore a full-state checkpoint; version-1 checkpoints restore only the
+    /// ensemble weights and need a re-warmup
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(
+            name = "restore",
+            level = "debug",
+            skip_all,
+            fields(bytes = data.len())
+        )
+    )]
+    fn from_checkpoint(data: &[u8]) -> Result<Self, CheckpointError> {
+        let version = data
+            .get(..4)
+            .map(|prefix| u32::from_le_bytes(prefix.try_into().unwrap()));
+
+        let profile = match version {
+            Some(CHECKPOINT_VERSION) => {
+                let (_, profile): (u32, AnomalyProfile) = bincode::deserialize(data)
+                    .map_err(|e| CheckpointError::DeserializationFailed(e.to_string()))?;
+                // The lifecycle log is not checkpointed; start it like `with_config`
+                profile.log_event(LifecycleEventKind::Created);
+                profile
+            }
+            _ if data.len() == V1_CHECKPOINT_LEN => Self::from_ensemble_checkpoint(data)?,
+            Some(found) if found > CHECKPOINT_VERSION => {
+                return Err(CheckpointError::UnsupportedVersion {
+                    found,
+                    max_supported: CHECKPOINT_VERSION,
+                });
+            }
+            _ => {
+                return Err(CheckpointError::DeserializationFailed(format!(
+                    "unrecognized checkpoint ({} bytes)",
+                    data.len()
+                )));
+            }
         };
+        profile.log_event(LifecycleEventKind::Restore {
+            total_samples: profile.event_count,
+        });
 
-        bincode::serialize(&checkpoint).unwrap_or_default()
+        Ok(profile)
     }
+}
 
-    fn from_checkpoint(data: &[u8]) -> Result<Self, CheckpointError> {
-        let checkpoint: EnsembleCheckpoint = bincode::deserialize(data)
+impl AnomalyProfile {
+    fn from_ensemble_checkpoint(data: &[u8]) -> Result<Self, CheckpointError> {
+        let (v1_weights, v1_alpha, v1_beta, total_samples): V1Ensemble = bincode::deserialize(data)
             .map_err(|e| CheckpointError::DeserializationFailed(e.to_string()))?;
 
+        // Detectors added since start from an average weight and a flat prior
+        let mean_weight = v1_weights.iter().sum::<f64>() / V1_NUM_DETECTORS as f64;
+        let mut weights = [mean_weight; NUM_DETECTORS];
+        let mut alpha = [1.0; NUM_DETECTORS];
+        let mut beta = [1.0; NUM_DETECTORS];
+        weights[..V1_NUM_DETECTORS].copy_from_slice(&v1_weights);
+        alpha[..V1_NUM_DETECTORS].copy_from_slice(&v1_alpha);
+        beta[..V1_NUM_DETECTORS].copy_from_slice(&v1_beta);
+
         let mut profile = AnomalyProfile::default();
-        profile.event_count = checkpoint.total_samples;
+        profile.event_count = total_samples;
         profile
             .ensemble
-            .restore_state(
-                &checkpoint.weights,
-                &checkpoint.alpha,
-                &checkpoint.beta,
-                checkpoint.total_samples,
-            )
+            .restore_state(&weights, &alpha, &beta, total_samples)
             .map_err(|e| CheckpointError::InvalidState(e.to_string()))?;
-
         Ok(profile)
     }
 }
@@ -1337,6 +3196,8 @@ impl AnomalyProfile {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::explain::DecisionBranch;
+    use crate::feedback::FeedbackSource;
     use crate::policy::{
         PatternRule, PolicyAction, PolicyDefaults, PolicySnapshot, runtime as policy_runtime,
     };
@@ -1379,6 +3240,814 @@ mod tests {
         assert!(signal.detector_scores[DetectorId::Distribution as usize].score > 0.0);
     }
 
+    #[test]
+    fn test_explain_decision_reports_branches_and_margins() {
+        let mut profile = AnomalyProfile::default();
+        for i in 0..150 {
+            profile.process_with_hash(i * 50_000_000, 777, 100.0);
+        }
+
+        let spike = profile.process_with_hash(150 * 50_000_000, 777, 10000.0);
+        let explanation = profile.explain_decision(&spike);
+        assert_eq!(explanation.is_anomaly, spike.is_anomaly);
+        assert_eq!(explanation.is_anomaly, !explanation.triggered.is_empty());
+        assert!(!explanation.suppressed);
+        assert_eq!(explanation.conditions.len(), 4);
+        for c in &explanation.conditions {
+            assert!((c.margin - (c.value - c.threshold)).abs() < 1e-12);
+        }
+
+        // A normal value afterwards: every unmet condition carries a negative margin
+        let normal = profile.process_with_hash(151 * 50_000_000, 777, 100.0);
+        let explanation = profile.explain_decision(&normal);
+        if !explanation.is_anomaly {
+            assert!(explanation.triggered.is_empty());
+            let miss = explanation
+                .closest_miss()
+                .expect("some condition was unmet");
+            assert!(miss.margin <= 0.0);
+        }
+        assert!(
+            explanation
+                .conditions
+                .iter()
+                .filter(|c| !c.met)
+                .all(|c| c.margin <= 0.0)
+        );
+    }
+
+    #[test]
+    fn test_fired_value_detectors_report_normal_range() {
+        let mut profile = AnomalyProfile::default();
+        for i in 0..600u64 {
+            let signal =
+                profile.process_with_hash(i * 50_000_000, 777, 100.0 + (i % 7) as f64 * 2.0);
+            if !signal.is_anomaly && !signal.suppressed_by_cooldown {
+                assert!(signal.normal_range.is_none());
+            }
+        }
+
+        let spike = profile.process_with_hash(600 * 50_000_000, 777, 10_000.0);
+        assert!(spike.is_anomaly);
+        let range = spike.normal_range.expect("value detectors fired");
+        assert!(!range.contains(10_000.0), "{}", range);
+        assert!(range.contains(105.0), "{}", range);
+        assert!(range.nearest(10_000.0) < 10_000.0);
+        assert!(spike.reason().contains(&format!("normal: {}", range)));
+
+        let explanation = spike.explanation().unwrap();
+        let bounded: Vec<_> = explanation
+            .fired
+            .iter()
+            .filter_map(|f| f.normal_range.map(|r| (f.detector, r)))
+            .collect();
+        assert!(!bounded.is_empty());
+        for (detector, detector_range) in &bounded {
+            assert!(!detector_range.contains(10_000.0), "{:?}", detector);
+            // The aggregate satisfies every detector reporting a range
+            assert_eq!(detector_range.intersect(&range), Some(range));
+        }
+        assert!(bounded.iter().all(|(d, _)| matches!(
+            d,
+            DetectorId::Distribution | DetectorId::Quantile | DetectorId::Evt
+        )));
+    }
+
+    #[test]
+    fn test_anomalous_signal_carries_explanation() {
+        let mut profile = AnomalyProfile::with_config(ProfileConfig {
+            explain_history: 8,
+            ..Default::default()
+        });
+        for i in 0..150 {
+            let normal = profile.process_with_hash(i * 50_000_000, 777, 100.0 + (i % 3) as f64);
+            if !normal.is_anomaly && !normal.suppressed_by_cooldown {
+                assert!(normal.explanation().is_none());
+            }
+        }
+
+        let spike = profile.process_with_hash(150 * 50_000_000, 777, 10_000.0);
+        assert!(spike.is_anomaly);
+        let explanation = spike.explanation().expect("anomalies are explained");
+        assert_eq!(explanation.history.len(), 8);
+        assert_eq!(explanation.history.last(), Some(&10_000.0));
+        assert!(!explanation.fired.is_empty());
+        for fired in &explanation.fired {
+            assert!(spike.detector_fired(fired.detector));
+            assert!(!fired.reason.is_empty());
+            assert_eq!(
+                fired.threshold,
+                profile.config().min_detector_score_for_anomaly
+            );
+        }
+        let top = explanation.top_contributions(1)[0];
+        assert_eq!(top.detector as u8, spike.attribution.primary_detector);
+        let shares: f32 = explanation.contributions.iter().map(|c| c.share).sum();
+        assert!((shares - 1.0).abs() < 1e-4);
+
+        let json = serde_json::to_value(&spike).unwrap();
+        assert_eq!(json["explanation"]["history"].as_array().unwrap().len(), 8);
+
+        let mut quiet = AnomalyProfile::with_config(ProfileConfig {
+            explain_history: 0,
+            ..Default::default()
+        });
+        for i in 0..150 {
+            quiet.process_with_hash(i * 50_000_000, 777, 100.0 + (i % 3) as f64);
+        }
+        let spike = quiet.process_with_hash(150 * 50_000_000, 777, 10_000.0);
+        assert!(spike.is_anomaly && spike.explanation().is_none());
+        assert!(
+            serde_json::to_value(&spike)
+                .unwrap()
+                .get("explanation")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_hysteresis_and_cooldown_collapse_duplicate_alerts() {
+        let suppression = AlertSuppression {
+            required_hits: 3,
+            window_events: 5,
+            cooldown_ms: 5_000,
+        };
+        let mut plain = AnomalyProfile::default();
+        let mut damped = AnomalyProfile::with_config(ProfileConfig {
+            suppression,
+            ..Default::default()
+        });
+        for i in 0..150 {
+            plain.process_with_hash(i * 50_000_000, 777, 100.0);
+            damped.process_with_hash(i * 50_000_000, 777, 100.0);
+        }
+
+        // One isolated spike: anomalous, but short of 3 in 5
+        let spike = damped.process_with_hash(150 * 50_000_000, 777, 10_000.0);
+        assert!(!spike.is_anomaly && !spike.suppressed_by_cooldown);
+        assert!(
+            damped
+                .explain_decision(&spike)
+                .triggered
+                .contains(&DecisionBranch::Hysteresis)
+        );
+        for i in 151..400 {
+            plain.process_with_hash(i * 50_000_000, 777, 100.0);
+            damped.process_with_hash(i * 50_000_000, 777, 100.0);
+        }
+
+        // A sustained incident: raised once, then held by the 100-event cooldown
+        let (mut plain_raised, mut raised, mut cooled) = (0, 0, 0);
+        for i in 400..440 {
+            let ts = i * 50_000_000;
+            plain_raised += plain
+                .process_with_hash(ts, 777, 10_000.0 + i as f64)
+                .is_anomaly as usize;
+            let signal = damped.process_with_hash(ts, 777, 10_000.0 + i as f64);
+            raised += signal.is_anomaly as usize;
+            if signal.suppressed_by_cooldown {
+                cooled += 1;
+                assert_eq!(
+                    damped.explain_decision(&signal).triggered.last(),
+                    Some(&DecisionBranch::Cooldown)
+                );
+            }
+        }
+        assert!(
+            plain_raised > 3,
+            "{} raised without suppression",
+            plain_raised
+        );
+        assert_eq!(raised, 1);
+        assert!(cooled > 0);
+    }
+
+    #[test]
+    fn test_feedback_calibrates_severity() {
+        let mut profile = AnomalyProfile::with_config(ProfileConfig {
+            calibrated_severity: true,
+            ..Default::default()
+        });
+        for i in 0..150 {
+            profile.process_with_hash(i * 50_000_000, 777, 100.0);
+        }
+        let before = profile.process_with_hash(150 * 50_000_000, 777, 10_000.0);
+        assert_eq!(before.calibrated_probability, None);
+        assert_eq!(before.severity, Severity::from_score(before.ensemble_score));
+
+        // Tier-2 confirms only one in ten alerts, whatever their score; events
+        // without a score leave the calibration alone
+        profile.apply_feedback(&[FeedbackEvent::true_positive(
+            777,
+            0,
+            [0.0; NUM_DETECTORS],
+            FeedbackSource::HumanReview,
+            1.0,
+        )]);
+        assert_eq!(profile.calibrator().label_weight(), 0.0);
+        for i in 0..200 {
+            let scores = [0.0; NUM_DETECTORS];
+            let event = if i % 10 == 0 {
+                FeedbackEvent::true_positive(777, i, scores, FeedbackSource::HumanReview, 1.0)
+            } else {
+                FeedbackEvent::false_positive(777, i, scores, FeedbackSource::HumanReview, 1.0)
+            };
+            profile.apply_feedback(&[event.with_ensemble_score((i % 20) as f64 / 20.0)]);
+        }
+
+        let after = profile.process_with_hash(151 * 50_000_000, 777, 10_000.0);
+        let probability = after.calibrated_probability.unwrap();
+        assert!(probability > 0.0 && probability < 0.25, "{}", probability);
+        assert_eq!(after.severity, Severity::None);
+    }
+
+    #[test]
+    fn test_process_vector_scores_channels_jointly() {
+        let mut profile = AnomalyProfile::default();
+        for i in 0..300 {
+            let jitter = (i % 7) as f64;
+            let features = [100.0 + jitter, 2_000.0 + jitter * 10.0, 2.0];
+            let signal = profile.process_vector(i * 50_000_000, 42, &features);
+            assert_eq!(signal.raw_value, features[0]);
+        }
+        assert!(profile.v_rrcf.multivariate.is_some());
+
+        // Latency stays normal; only payload size and status class move
+        let signal = profile.process_vector(300 * 50_000_000, 42, &[103.0, 40_000.0, 5.0]);
+        assert!(signal.detector_scores[DetectorId::RRCF as usize].score > 0.0);
+        assert!((signal.baseline.avg_value - 103.0).abs() < 1.0);
+
+        let features = FeatureVector::from_slice(&[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(features.len(), NUM_FEATURES);
+        assert_eq!(features.get(FeatureChannel::StatusClass), Some(3.0));
+        assert_eq!(
+            FeatureVector::scalar(1.0).get(FeatureChannel::PayloadSize),
+            None
+        );
+    }
+
+    #[test]
+    fn test_golden_baseline_skips_warmup() {
+        let value = |i: u64| 100.0 + (i % 5) as f64 * 3.0;
+        let mut learned = AnomalyProfile::default();
+        for i in 0..2_000u64 {
+            learned.process_with_hash(i * 50_000_000, 7, value(i));
+        }
+        let baseline = learned.export_baseline();
+        assert_eq!(baseline.events, 2_000);
+        assert!((baseline.value.mean - 106.0).abs() < 1e-9);
+        assert!((baseline.frequency - 20.0).abs() < 0.5);
+        assert!(baseline.volume.is_some());
+
+        let json = serde_json::to_string_pretty(&baseline).unwrap();
+        let baseline: ProfileBaseline = serde_json::from_str(&json).unwrap();
+        let mut golden =
+            AnomalyProfile::from_baseline(ProfileConfig::default(), &baseline).unwrap();
+        assert_eq!(golden.export_baseline(), baseline);
+
+        // Volume scores rate changes right away instead of after warmup
+        let mut fresh = AnomalyProfile::default();
+        let mut ts = 10_000 * 50_000_000u64;
+        let (mut golden_volume, mut fresh_volume) = (false, false);
+        for i in 0..60u64 {
+            ts += if i < 30 { 50_000_000 } else { 1_000_000 };
+            let signal = golden.process_with_hash(ts, 7, value(i));
+            assert!(!signal.baseline.is_warmup);
+            golden_volume |= signal.detector_fired(DetectorId::Volume);
+            let signal = fresh.process_with_hash(ts, 7, value(i));
+            assert!(signal.baseline.is_warmup);
+            fresh_volume |= signal.detector_fired(DetectorId::Volume);
+        }
+        assert!(golden_volume && !fresh_volume);
+
+        let spike = golden.process_with_hash(ts + 50_000_000, 7, 5_000.0);
+        assert!(spike.is_anomaly);
+        assert!(spike.detector_fired(DetectorId::Distribution));
+
+        let other_layout = ProfileConfig {
+            hist_bins: 20,
+            ..Default::default()
+        };
+        assert_eq!(
+            AnomalyProfile::from_baseline(other_layout, &baseline).err(),
+            Some(BaselineError::Incompatible("histogram.bins"))
+        );
+    }
+
+    #[test]
+    fn test_view_reads_while_processing() {
+        let profile = Arc::new(Mutex::new(AnomalyProfile::default()));
+        let view = profile.lock().unwrap().view();
+        assert_eq!(view.snapshot().weights.len(), NUM_DETECTORS);
+
+        let worker = {
+            let profile = Arc::clone(&profile);
+            std::thread::spawn(move || {
+                for i in 0..2_000u64 {
+                    profile.lock().unwrap().process_with_hash(
+                        i * 10_000_000,
+                        7,
+                        100.0 + (i % 5) as f64,
+                    );
+                }
+            })
+        };
+        // Reads go to the view, never to the profile's lock
+        let mut last_seen = 0;
+        while !worker.is_finished() {
+            let snapshot = view.snapshot();
+            assert!(snapshot.event_count >= last_seen);
+            assert!(view.event_count() >= snapshot.event_count);
+            last_seen = snapshot.event_count;
+        }
+        worker.join().unwrap();
+
+        assert_eq!(view.event_count(), 2_000);
+        assert_eq!(view.last_timestamp(), 1_999 * 10_000_000);
+        let snapshot = view.snapshot();
+        assert_eq!(
+            snapshot.event_count,
+            2_000 / PUBLISH_INTERVAL * PUBLISH_INTERVAL
+        );
+        assert_eq!(snapshot.detector_stats.len(), 13);
+
+        assert!(view.checkpoint().is_none());
+        view.request_checkpoint();
+        profile
+            .lock()
+            .unwrap()
+            .process_with_hash(20_000_000_000, 7, 100.0);
+        let checkpoint = view.checkpoint().unwrap();
+        assert_eq!(checkpoint.event_count, 2_001);
+        let restored = AnomalyProfile::from_checkpoint(&checkpoint.bytes).unwrap();
+        assert_eq!(restored.event_count(), 2_001);
+
+        profile.lock().unwrap().reset();
+        assert_eq!(view.event_count(), 0);
+    }
+
+    #[test]
+    fn test_merge_sharded_profiles() {
+        let value = |i: u64| 100.0 + (i % 5) as f64 * 3.0;
+        let user = |i: u64| (i % 1_500).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let mut whole = AnomalyProfile::default();
+        let mut shards = [AnomalyProfile::default(), AnomalyProfile::default()];
+        for i in 0..4_000u64 {
+            let ts = i * 50_000_000;
+            whole.process_with_hash(ts, user(i), value(i));
+            shards[(i % 2) as usize].process_with_hash(ts, user(i), value(i));
+        }
+        let [mut merged, other] = shards;
+        assert!((merged.export_baseline().frequency - 10.0).abs() < 0.5);
+        merged.merge(&other).unwrap();
+
+        let (m, w) = (merged.export_baseline(), whole.export_baseline());
+        assert_eq!(merged.event_count(), 4_000);
+        assert!((m.value.mean - w.value.mean).abs() < 1e-9);
+        assert!((m.value.std - w.value.std).abs() < 1e-9);
+        assert!((m.frequency - 20.0).abs() < 0.5);
+        let level = m.volume.unwrap().level;
+        assert!(
+            (level - w.volume.unwrap().level).abs() < 1.0,
+            "level {level}"
+        );
+        // Shards saw disjoint halves of each user's events, not of the users
+        let users = merged.v_card.hll.count();
+        assert!(
+            (users - whole.v_card.hll.count()).abs() < 1.0,
+            "users {users}"
+        );
+        let share = |h: &HistogramBaseline, i: usize| h.bins[i] / h.total_weight;
+        for i in 0..m.histogram.bins.len() {
+            assert!((share(&m.histogram, i) - share(&w.histogram, i)).abs() < 0.02);
+        }
+
+        // Continues on the whole stream without a rate alarm
+        for i in 4_000..4_200u64 {
+            let signal = merged.process_with_hash(i * 50_000_000, user(i), value(i));
+            assert!(!signal.detector_fired(DetectorId::Volume), "event {i}");
+        }
+
+        let mut other_layout = AnomalyProfile::with_config(ProfileConfig {
+            hist_bins: 20,
+            ..Default::default()
+        });
+        assert_eq!(
+            other_layout.merge(&merged),
+            Err(BaselineError::Incompatible("histogram.bins"))
+        );
+        assert_eq!(other_layout.event_count(), 0);
+    }
+
+    #[test]
+    fn test_update_config_keeps_learned_state() {
+        let mut profile = AnomalyProfile::default();
+        for i in 0..200 {
+            profile.process_with_hash(i * 50_000_000, 5, 100.0 + (i % 5) as f64);
+        }
+        let weights = profile.get_weights();
+
+        let mut config = profile.config().clone();
+        config.min_detector_score_for_anomaly = 0.4;
+        config.emission.min_severity = Severity::High;
+        profile.update_config(config.clone()).unwrap();
+        assert_eq!(profile.event_count(), 200);
+        assert_eq!(profile.get_weights(), weights);
+        assert_eq!(profile.config().min_detector_score_for_anomaly, 0.4);
+        let reloaded = profile.lifecycle(0).events().last().unwrap().kind.clone();
+        assert_eq!(
+            reloaded,
+            LifecycleEventKind::ConfigReloaded {
+                fields: vec![
+                    "min_detector_score_for_anomaly".to_string(),
+                    "emission".to_string()
+                ]
+            }
+        );
+
+        // Rejections are all-or-nothing
+        let mut structural = config.clone();
+        structural.confidence_threshold = 0.9;
+        structural.hist_bins = 20;
+        structural.period = 12;
+        assert_eq!(
+            profile.update_config(structural),
+            Err(ConfigError::Structural(vec!["period", "hist_bins"]))
+        );
+        let mut out_of_range = config.clone();
+        out_of_range.min_ensemble_score_for_anomaly = 1.5;
+        assert!(matches!(
+            profile.update_config(out_of_range),
+            Err(ConfigError::OutOfRange {
+                field: "min_ensemble_score_for_anomaly",
+                ..
+            })
+        ));
+        assert_eq!(profile.config().confidence_threshold, 0.5);
+        assert_eq!(profile.config().min_ensemble_score_for_anomaly, 0.10);
+    }
+
+    #[test]
+    fn test_registry_update_config_is_atomic() {
+        let mut registry = ProfileRegistry::new();
+        for hash in 0..3 {
+            registry.insert(hash, AnomalyProfile::default());
+        }
+        let odd = ProfileConfig {
+            max_val: 500.0,
+            ..Default::default()
+        };
+        registry.insert(3, AnomalyProfile::with_config(odd));
+
+        let config = ProfileConfig {
+            confidence_threshold: 0.8,
+            ..Default::default()
+        };
+        assert_eq!(
+            registry.update_config(&config),
+            Err(ConfigError::Structural(vec!["max_val"]))
+        );
+        assert!(
+            registry
+                .iter()
+                .all(|(_, p)| p.config().confidence_threshold == 0.5)
+        );
+
+        registry.remove(3);
+        assert_eq!(registry.update_config(&config), Ok(3));
+        assert!(
+            registry
+                .iter()
+                .all(|(_, p)| p.config().confidence_threshold == 0.8)
+        );
+    }
+
+    /// Fires at full score whenever the value crosses a fixed limit
+    struct LimitDetector {
+        limit: f64,
+        seen: u64,
+    }
+
+    impl Detector for LimitDetector {
+        fn name(&self) -> &str {
+            "Custom/Limit"
+        }
+
+        fn id(&self) -> DetectorId {
+            DetectorId::Volume
+        }
+
+        fn update(&mut self, ctx: &SignalContext) -> Option<DetectionResult> {
+            self.seen += 1;
+            (ctx.value > self.limit).then(|| DetectionResult {
+                score: 1.0,
+                weight: 1.0,
+                signal_type: 0,
+                expected: self.limit,
+                confidence: 0.9,
+                normal_range: None,
+                reason: DetectionReason::Text(format!("above {}", self.limit)),
+            })
+        }
+
+        fn get_stats(&self) -> String {
+            format!("seen={}", self.seen)
+        }
+    }
+
+    fn limit_detector() -> Box<dyn Detector> {
+        Box::new(LimitDetector {
+            limit: 1_000.0,
+            seen: 0,
+        })
+    }
+
+    #[test]
+    fn test_extra_detectors_join_ensemble() {
+        let mut profile = AnomalyProfile::default().with_extra_detectors(vec![limit_detector()]);
+        assert_eq!(profile.get_weights().len(), NUM_DETECTORS + 1);
+
+        for i in 0..150 {
+            profile.process_with_hash(i * 50_000_000, 3, 100.0 + (i % 5) as f64);
+        }
+        assert!(!profile.extra_detector_scores()[0].fired);
+
+        let spike = profile.process_with_hash(150 * 50_000_000, 3, 5_000.0);
+        let extra = profile.extra_detector_scores()[0];
+        assert!(extra.fired && extra.score == 1.0);
+        assert!(spike.ensemble_score > 0.0);
+        let stats = profile.get_detector_stats();
+        assert_eq!(
+            stats.last().unwrap(),
+            &("Custom/Limit".to_string(), "seen=151".to_string())
+        );
+
+        // Weights survive a checkpoint; reattaching does not add another arm
+        let restored = AnomalyProfile::from_checkpoint(&profile.to_checkpoint())
+            .unwrap()
+            .with_extra_detectors(vec![limit_detector()]);
+        assert_eq!(restored.get_weights(), profile.get_weights());
+        assert_eq!(restored.extra_detector_scores().len(), 1);
+    }
+
+    #[test]
+    fn test_disabled_detectors_do_not_score() {
+        let disabled = [DetectorId::Distribution, DetectorId::ChangePoint];
+        let config = ProfileConfig {
+            disabled_detectors: disabled.to_vec(),
+            ..Default::default()
+        };
+        let mut ablated = AnomalyProfile::with_config(config.clone());
+        let mut full = AnomalyProfile::default();
+
+        let mut full_fired = 0;
+        for i in 0..400u64 {
+            let value = if i == 350 {
+                5_000.0
+            } else {
+                100.0 + (i % 7) as f64
+            };
+            let signal = ablated.process_with_hash(i * 50_000_000, 1, value);
+            for id in disabled {
+                let score = signal.detector_scores[id as usize];
+                assert!(!score.fired && score.score == 0.0);
+            }
+            let signal = full.process_with_hash(i * 50_000_000, 1, value);
+            full_fired += disabled
+                .iter()
+                .filter(|&&id| signal.detector_scores[id as usize].score > 0.0)
+                .count();
+        }
+        assert!(full_fired >= 2);
+
+        // Toggling a detector changes what state exists
+        assert_eq!(
+            config.structural_changes(&ProfileConfig::default()),
+            vec!["disabled_detectors"]
+        );
+    }
+
+    #[test]
+    fn test_process_batch_matches_single_events() {
+        let events: Vec<(u64, u64, f64)> = (0..400u64)
+            .map(|i| {
+                let value = if i == 350 {
+                    5_000.0
+                } else {
+                    100.0 + (i % 7) as f64
+                };
+                (i * 50_000_000, i % 5, value)
+            })
+            .collect();
+
+        let mut single = AnomalyProfile::default();
+        let expected: Vec<AnomalySignal> = events
+            .iter()
+            .map(|&(ts, hash, value)| single.process_with_hash(ts, hash, value))
+            .collect();
+        let mut batched = AnomalyProfile::default();
+        let mut signals = batched.process_batch(&events[..200]);
+        signals.extend(batched.process_batch(&events[200..]));
+
+        assert_eq!(signals.len(), events.len());
+        for (a, b) in signals.iter().zip(&expected) {
+            assert_eq!((a.sequence, a.is_anomaly), (b.sequence, b.is_anomaly));
+            assert_eq!(a.ensemble_score, b.ensemble_score);
+        }
+
+        // Filtered batches only append what the emission floor admits
+        let mut filtered = AnomalyProfile::default();
+        let mut out = Vec::new();
+        let appended = filtered.process_batch_into(&events, &mut out, true);
+        assert_eq!(appended, out.len());
+        let admitted: Vec<u64> = expected
+            .iter()
+            .filter(|s| s.is_anomaly)
+            .map(|s| s.sequence)
+            .collect();
+        assert!(!admitted.is_empty());
+        assert_eq!(out.iter().map(|s| s.sequence).collect::<Vec<_>>(), admitted);
+    }
+
+    #[test]
+    fn test_volume_detects_seasonal_period() {
+        let mut profile = AnomalyProfile::default();
+        let mut ts = 0u64;
+        let mut signal = None;
+        for i in 0..1_200 {
+            // Arrival rate swings over a 48-event cycle
+            let phase = 2.0 * std::f64::consts::PI * i as f64 / 48.0;
+            ts += (50_000_000.0 * (1.0 + 0.5 * phase.sin())) as u64;
+            signal = Some(profile.process_with_hash(ts, 8, 100.0));
+        }
+
+        assert_eq!(profile.v_volume.detected_period(), Some(48));
+        assert_eq!(signal.unwrap().baseline.detected_period, 48);
+        assert!(
+            profile
+                .v_volume
+                .get_stats()
+                .contains("period=48 (detected)")
+        );
+
+        let fixed = AnomalyProfile::with_config(ProfileConfig {
+            auto_period: false,
+            ..Default::default()
+        });
+        assert_eq!(fixed.v_volume.detected_period(), None);
+    }
+
+    #[test]
+    fn test_quantile_detector_fires_on_tail_shift() {
+        let mut detector = QuantileDetector::new();
+        let mut observe = |i: u64, value: f64| {
+            detector.update(&SignalContext {
+                timestamp: i * 1_000_000,
+                unique_id_hash: 1,
+                value,
+                features: FeatureVector::scalar(value),
+                is_warmup: false,
+                sequence: i,
+                exogenous: ExogenousContext::default(),
+            })
+        };
+        for i in 0..2_000 {
+            let _ = observe(i, 50.0 + (i % 10) as f64);
+        }
+
+        // Median unchanged, but one event in ten now takes 5s, far beyond
+        // any fixed histogram range
+        let fired = (2_000..2_100)
+            .filter_map(|i| {
+                let value = if i % 10 == 0 {
+                    5_000.0
+                } else {
+                    50.0 + (i % 10) as f64
+                };
+                observe(i, value)
+            })
+            .next()
+            .expect("tail shift should fire");
+
+        assert!(fired.score > 0.0);
+        assert_eq!(fired.signal_type, DetectorId::Quantile as u8);
+        assert!((fired.expected - 59.0).abs() < 1.5, "{}", fired.expected);
+        let reason = fired.reason.to_string();
+        assert!(reason.starts_with("p95 shifted") || reason.starts_with("p99 shifted"));
+    }
+
+    #[test]
+    fn test_evt_detector_calibrates_heavy_tail() {
+        let mut detector = EvtDetector::new();
+        let mut observe = |value: f64| {
+            detector.update(&SignalContext {
+                timestamp: 0,
+                unique_id_hash: 1,
+                value,
+                features: FeatureVector::scalar(value),
+                is_warmup: false,
+                sequence: 0,
+                exogenous: ExogenousContext::default(),
+            })
+        };
+
+        // Pareto latencies (tail index 3): a 3-sigma band would fire often
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut fired = 0;
+        for _ in 0..10_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let u = (state >> 11) as f64 / (1u64 << 53) as f64;
+            let latency = 50.0 * (1.0 - u).powf(-1.0 / 3.0);
+            fired += observe(latency).is_some() as usize;
+        }
+        assert!(fired < 10, "{} alarms on normal traffic", fired);
+
+        let spike = observe(5_000.0).expect("extreme latency should fire");
+        assert_eq!(spike.signal_type, DetectorId::Evt as u8);
+        assert!(spike.expected < 5_000.0);
+        assert!(
+            spike
+                .reason
+                .to_string()
+                .starts_with("Extreme value: 5000.00")
+        );
+    }
+
+    #[test]
+    fn test_discord_detector_flags_pattern_break() {
+        let mut detector = DiscordDetector::new();
+        let mut observe = |value: f64| {
+            detector.update(&SignalContext {
+                timestamp: 0,
+                unique_id_hash: 1,
+                value,
+                features: FeatureVector::scalar(value),
+                is_warmup: false,
+                sequence: 0,
+                exogenous: ExogenousContext::default(),
+            })
+        };
+
+        // Batch job: a latency bump every 12 events, with jitter
+        let cycle = |i: usize| if i % 12 < 3 { 180.0 } else { 100.0 } + (i * 7 % 5) as f64;
+        let mut fired = 0;
+        for i in 0..1_000 {
+            fired += observe(cycle(i)).is_some() as usize;
+        }
+        assert!(fired < 5, "{} firings on the regular cycle", fired);
+
+        // The bump lands mid-cycle once: every value is still in range
+        let result = (1_000..1_024)
+            .filter_map(|i| observe(cycle(i + 6)))
+            .next()
+            .expect("pattern break should fire");
+        assert_eq!(result.signal_type, DetectorId::Discord as u8);
+        assert!(
+            result
+                .reason
+                .to_string()
+                .starts_with("Pattern break: last 16 values")
+        );
+    }
+
+    #[test]
+    fn test_decomposition_removes_daily_cycle() {
+        let run = |decomposition: Option<DecompositionConfig>| {
+            let mut profile = AnomalyProfile::with_config(ProfileConfig {
+                decomposition,
+                ..Default::default()
+            });
+            let mut fired = 0;
+            let mut last = None;
+            // Six days at one event per minute; latency triples by noon
+            for minute in 0..6 * 1_440u64 {
+                let phase = 2.0 * std::f64::consts::PI * (minute % 1_440) as f64 / 1_440.0;
+                let value = 100.0 - 50.0 * phase.cos() + (minute * 7 % 5) as f64;
+                let signal = profile.process_with_hash(minute * 60_000_000_000, 3, value);
+                if minute >= 4 * 1_440 {
+                    fired += signal.detector_fired(DetectorId::ChangePoint) as usize;
+                    fired += signal.detector_fired(DetectorId::Drift) as usize;
+                }
+                last = Some(signal);
+            }
+            (fired, last.unwrap().baseline)
+        };
+
+        let (raw_fired, raw_baseline) = run(None);
+        let (fired, baseline) = run(Some(DecompositionConfig::daily()));
+        assert_eq!(raw_baseline.seasonal, 0.0);
+        assert!(baseline.seasonal < -40.0, "{:?}", baseline);
+        assert!((baseline.trend - 100.0).abs() < 10.0, "{:?}", baseline);
+        assert!(
+            3 * fired < 2 * raw_fired,
+            "{} firings with decomposition, {} without",
+            fired,
+            raw_fired
+        );
+    }
+
     #[test]
     fn test_legacy_compatibility() {
         let mut profile = AnomalyProfile::default();
@@ -1415,6 +4084,119 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_full_checkpoint_restores_detection_state() {
+        // Periodic values across several entities so every detector builds state
+        let value = |i: u64| 100.0 + 20.0 * ((i % 24) as f64 / 24.0 * std::f64::consts::TAU).sin();
+        let mut profile = AnomalyProfile::default();
+        for i in 0..400 {
+            let _ = profile.process_with_hash(i * 50_000_000, i % 7, value(i));
+        }
+
+        let mut restored = AnomalyProfile::from_checkpoint(&profile.to_checkpoint()).unwrap();
+        assert_eq!(restored.event_count(), profile.event_count());
+
+        // Same input afterwards, including a spike, scores identically. RRCF
+        // draws random cuts on insert, so only its score may differ.
+        for i in 400..460 {
+            let v = if i == 430 { 5000.0 } else { value(i) };
+            let a = profile.process_with_hash(i * 50_000_000, i % 7, v);
+            let b = restored.process_with_hash(i * 50_000_000, i % 7, v);
+            assert_eq!(a.sequence, b.sequence);
+            for (id, (x, y)) in a.detector_scores.iter().zip(&b.detector_scores).enumerate() {
+                if id == DetectorId::RRCF as usize {
+                    continue;
+                }
+                assert_eq!(
+                    x.score.to_bits(),
+                    y.score.to_bits(),
+                    "detector {} at {}",
+                    id,
+                    i
+                );
+                assert_eq!(x.fired, y.fired);
+            }
+            assert_eq!(a.baseline.avg_value, b.baseline.avg_value);
+        }
+
+        // Version-1 (ensemble-only) checkpoints still restore
+        let legacy = bincode::serialize(&(
+            [0.1; V1_NUM_DETECTORS],
+            [1.0; V1_NUM_DETECTORS],
+            [1.0; V1_NUM_DETECTORS],
+            77u64,
+        ))
+        .unwrap();
+        assert_eq!(legacy.len(), V1_CHECKPOINT_LEN);
+        assert_eq!(
+            AnomalyProfile::from_checkpoint(&legacy)
+                .unwrap()
+                .event_count(),
+            77
+        );
+
+        let mut future = profile.to_checkpoint();
+        future[..4].copy_from_slice(&(CHECKPOINT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            AnomalyProfile::from_checkpoint(&future),
+            Err(CheckpointError::UnsupportedVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_deploy_context_damps_level_shift_detectors() {
+        let deploy = ExogenousContext {
+            business_hours: Some(true),
+            deploy_in_progress: true,
+        };
+        let mut plain = AnomalyProfile::default();
+        let mut deploying = AnomalyProfile::default();
+        for i in 0..300 {
+            let v = 100.0 + (i % 5) as f64;
+            let _ = plain.process_with_hash(i * 50_000_000, 9, v);
+            let _ = deploying.process_with_hash(i * 50_000_000, 9, v);
+        }
+
+        // A release shifts the level; detectors see the same values either way
+        let mut damped = 0;
+        for i in 300..340 {
+            let a = plain.process_with_hash(i * 50_000_000, 9, 400.0);
+            let b = deploying.process_with_context(i * 50_000_000, 9, 400.0, deploy);
+            for id in [
+                DetectorId::ChangePoint,
+                DetectorId::Distribution,
+                DetectorId::Drift,
+            ] {
+                let (x, y) = (
+                    a.detector_scores[id as usize],
+                    b.detector_scores[id as usize],
+                );
+                assert_eq!(x.fired, y.fired);
+                assert_eq!(
+                    y.score,
+                    x.score * DEPLOY_SENSITIVITY as f32,
+                    "{:?} at {}",
+                    id,
+                    i
+                );
+                damped += x.fired as usize;
+            }
+            // Business hours leave traffic-shape detectors alone
+            let volume = DetectorId::Volume as usize;
+            assert_eq!(
+                a.detector_scores[volume].score,
+                b.detector_scores[volume].score
+            );
+        }
+        assert!(damped > 0, "level shift should fire a damped detector");
+
+        // The explanation reports the raised floors
+        let last = deploying.process_with_context(340 * 50_000_000, 9, 400.0, deploy);
+        let floor = deploying.explain_decision(&last).conditions[0].threshold;
+        let expected = ProfileConfig::default().min_detector_score_for_anomaly * DEPLOY_FLOOR_SCALE;
+        assert!((floor - expected).abs() < 1e-12);
+    }
+
     #[test]
     fn test_policy_suppresses_detected_anomaly() {
         policy_runtime().install_snapshot(PolicySnapshot {
@@ -1440,6 +4222,12 @@ mod tests {
             !signal.is_anomaly,
             "policy should suppress anomaly decision"
         );
+        let explanation = profile.explain_decision(&signal);
+        assert!(explanation.suppressed);
+        assert_eq!(
+            explanation.triggered.last(),
+            Some(&DecisionBranch::PolicySuppression)
+        );
 
         policy_runtime().install_snapshot(PolicySnapshot::default());
     }
@@ -0,0 +1,102 @@
+//! Decision Introspection
+//!
+//! `AnomalyProfile::explain_decision` breaks an `AnomalySignal` back down into
+//! the hybrid decision rule that produced it:
+//!
+//! ```text
+//! is_anomaly = !policy_suppress
+//!     && (detector_floor || adaptive_threshold || score_floor)
+//!     && hysteresis_met && !in_cooldown
+//! ```
+//!
+//! Each condition is reported with its value, threshold and margin, so a
+//! missed detection shows how close every branch came to firing.
+
+use crate::signal::DetectorId;
+use serde::Serialize;
+
+/// A branch of the decision rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecisionBranch {
+    /// A detector fired with a score at or above the per-detector floor
+    DetectorFloor,
+    /// Ensemble score above the learned threshold with enough confidence
+    AdaptiveThreshold,
+    /// Ensemble score at or above the fixed floor
+    ScoreFloor,
+    /// A Tier-2 policy suppressed an otherwise anomalous decision
+    PolicySuppression,
+    /// Too few recent events were anomalous to raise this one
+    Hysteresis,
+    /// Within the cooldown after the last raised anomaly
+    Cooldown,
+}
+
+/// One comparison inside a branch
+#[derive(Debug, Clone, Serialize)]
+pub struct ConditionCheck {
+    pub branch: DecisionBranch,
+    /// What was compared, e.g. `"ensemble_score > adaptive_threshold"`
+    pub condition: &'static str,
+    pub value: f64,
+    pub threshold: f64,
+    /// `value - threshold`; negative means the condition fell short by that much
+    pub margin: f64,
+    pub met: bool,
+}
+
+impl ConditionCheck {
+    pub(crate) fn at_least(
+        branch: DecisionBranch,
+        condition: &'static str,
+        value: f64,
+        threshold: f64,
+    ) -> Self {
+        Self {
+            branch,
+            condition,
+            value,
+            threshold,
+            margin: value - threshold,
+            met: value >= threshold,
+        }
+    }
+
+    pub(crate) fn above(
+        branch: DecisionBranch,
+        condition: &'static str,
+        value: f64,
+        threshold: f64,
+    ) -> Self {
+        Self {
+            met: value > threshold,
+            ..Self::at_least(branch, condition, value, threshold)
+        }
+    }
+}
+
+/// Why a signal was (or was not) flagged
+#[derive(Debug, Clone, Serialize)]
+pub struct DecisionExplanation {
+    pub is_anomaly: bool,
+    /// Branches whose conditions all held
+    pub triggered: Vec<DecisionBranch>,
+    /// Set when a policy overrode triggered branches
+    pub suppressed: bool,
+    /// Highest-scoring fired detector considered by the detector floor
+    pub strongest_detector: Option<DetectorId>,
+    /// Whether the adaptive branch is enabled in the profile config
+    pub adaptive_enabled: bool,
+    pub conditions: Vec<ConditionCheck>,
+}
+
+impl DecisionExplanation {
+    /// The unmet condition that came closest to its threshold
+    pub fn closest_miss(&self) -> Option<&ConditionCheck> {
+        self.conditions
+            .iter()
+            .filter(|c| !c.met)
+            .max_by(|a, b| a.margin.total_cmp(&b.margin))
+    }
+}
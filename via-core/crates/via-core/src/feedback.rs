@@ -47,6 +47,9 @@ pub struct FeedbackEvent {
     /// End-to-end latency from signal to feedback.
     #[serde(default)]
     pub feedback_latency_ms: u64,
+    /// Ensemble score of the original signal; feeds the score calibration
+    #[serde(default)]
+    pub ensemble_score: Option<f32>,
 }
 
 /// Source of the feedback
@@ -83,6 +86,7 @@ impl FeedbackEvent {
             label_class: FeedbackLabelClass::Uncertain,
             pattern_id: None,
             feedback_latency_ms: 0,
+            ensemble_score: None,
         }
     }
 
@@ -105,6 +109,7 @@ impl FeedbackEvent {
             label_class: FeedbackLabelClass::Uncertain,
             pattern_id: None,
             feedback_latency_ms: 0,
+            ensemble_score: None,
         }
     }
 
@@ -127,9 +132,16 @@ impl FeedbackEvent {
             label_class: FeedbackLabelClass::Uncertain,
             pattern_id: None,
             feedback_latency_ms: 0,
+            ensemble_score: None,
         }
     }
 
+    /// Attach the original signal's ensemble score (builder)
+    pub fn with_ensemble_score(mut self, score: f64) -> Self {
+        self.ensemble_score = Some(score as f32);
+        self
+    }
+
     /// Calculate which detectors were correct
     pub fn correct_detectors(&self) -> [bool; NUM_DETECTORS] {
         let mut correct = [false; NUM_DETECTORS];
@@ -403,7 +415,9 @@ mod tests {
         let event = FeedbackEvent::true_positive(
             12345,
             1000000,
-            [0.8, 0.6, 0.3, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            [
+                0.8, 0.6, 0.3, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+            ],
             FeedbackSource::LLMAnalysis,
             0.95,
         );
@@ -425,7 +439,9 @@ mod tests {
         let event = FeedbackEvent::true_positive(
             12345,
             1000000,
-            [0.8, 0.6, 0.3, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            [
+                0.8, 0.6, 0.3, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+            ],
             FeedbackSource::HumanReview,
             1.0,
         );
@@ -443,14 +459,18 @@ mod tests {
             FeedbackEvent::true_positive(
                 1,
                 1000,
-                [0.8, 0.6, 0.3, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+                [
+                    0.8, 0.6, 0.3, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+                ],
                 FeedbackSource::LLMAnalysis,
                 1.0,
             ),
             FeedbackEvent::false_positive(
                 2,
                 2000,
-                [0.9, 0.2, 0.1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+                [
+                    0.9, 0.2, 0.1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+                ],
                 FeedbackSource::HumanReview,
                 0.8,
             ),
@@ -25,6 +25,8 @@ pub struct Tier1SignalV1 {
     pub primary_detector: u8,
     pub detectors_fired: u8,
     pub confidence: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub calibrated_probability: Option<f32>,
     pub detector_scores: Vec<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attributes: Option<serde_json::Value>,
@@ -47,6 +49,7 @@ impl From<AnomalySignal> for Tier1SignalV1 {
             primary_detector: signal.attribution.primary_detector,
             detectors_fired: signal.attribution.detectors_fired,
             confidence: signal.confidence,
+            calibrated_probability: signal.calibrated_probability,
             detector_scores: signal.detector_scores.map(|s| s.score).to_vec(),
             attributes: None,
         }
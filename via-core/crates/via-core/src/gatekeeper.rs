@@ -22,19 +22,21 @@ use once_cell::sync::Lazy;
 use prometheus::{Counter, Encoder, Gauge, Histogram, TextEncoder};
 use serde::{Deserialize, Serialize};
 use std::io::Write;
-use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
 use std::thread;
 use tokio::net::TcpListener;
 use tracing::{error, info, warn};
 
 use via_core::{
+    checkpoint_scheduler::{CheckpointScheduler, CheckpointSchedulerConfig},
     engine::AnomalyProfile,
     feedback::{FeedbackEvent, FeedbackLabelClass, FeedbackSource},
     forwarder::{ForwarderConfig, Tier2Forwarder},
     policy::{PolicySnapshot, runtime as policy_runtime},
     registry::{ProfileRegistry, RegistryConfig},
     signal::{AnomalySignal, NUM_DETECTORS},
+    spill::DirectoryStore,
 };
 
 const GATEKEEPER_VERSION: &str = "2.2.0";
@@ -142,6 +144,26 @@ pub static EVICTIONS_TOTAL: Lazy<Counter> = Lazy::new(|| {
     c
 });
 
+pub static REJECTIONS_TOTAL: Lazy<Counter> = Lazy::new(|| {
+    let c = Counter::new(
+        "via_entity_rejections_total",
+        "Events for new entities rejected at the per-shard entity cap",
+    )
+    .unwrap();
+    prometheus::register(Box::new(c.clone())).unwrap();
+    c
+});
+
+pub static EXPIRATIONS_TOTAL: Lazy<Counter> = Lazy::new(|| {
+    let c = Counter::new(
+        "via_profile_expirations_total",
+        "Profiles dropped after their idle TTL",
+    )
+    .unwrap();
+    prometheus::register(Box::new(c.clone())).unwrap();
+    c
+});
+
 pub static FEEDBACK_RECEIVED: Lazy<Counter> = Lazy::new(|| {
     let c = Counter::new("via_feedback_received", "Total feedback events received").unwrap();
     prometheus::register(Box::new(c.clone())).unwrap();
@@ -231,7 +253,7 @@ impl OTelAnyValue {
             _ => None,
         }
     }
-    
+
     pub fn as_str(&self) -> Option<&str> {
         match self {
             OTelAnyValue::StringValue(s) => Some(s),
@@ -378,6 +400,7 @@ struct ShardWorker {
     persistence_tx: Sender<AnomalyOutput>,
     feedback_rx: Receiver<InternalFeedback>,
     forwarder: Option<Arc<Tier2Forwarder>>,
+    checkpoints: Option<CheckpointScheduler>,
 }
 
 impl ShardWorker {
@@ -388,17 +411,32 @@ impl ShardWorker {
         feedback_rx: Receiver<InternalFeedback>,
         registry_config: RegistryConfig,
         forwarder: Option<Arc<Tier2Forwarder>>,
+        checkpoint_config: Option<CheckpointSchedulerConfig>,
+        spill_dir: Option<std::path::PathBuf>,
     ) -> thread::JoinHandle<()> {
         thread::Builder::new()
             .name(format!("via-shard-{}", id))
             .spawn(move || {
+                let checkpoints = checkpoint_config.and_then(|config| {
+                    CheckpointScheduler::new(config)
+                        .map_err(|e| error!(shard = id, error = %e, "Checkpoints disabled"))
+                        .ok()
+                });
+                let mut registry = ProfileRegistry::with_config(registry_config);
+                if let Some(dir) = spill_dir {
+                    match DirectoryStore::open(dir) {
+                        Ok(store) => registry.set_spill_store(Some(Box::new(store))),
+                        Err(e) => error!(shard = id, error = %e, "Spill tier disabled"),
+                    }
+                }
                 let mut worker = ShardWorker {
                     id,
                     rx,
-                    registry: ProfileRegistry::with_config(registry_config),
+                    registry,
                     persistence_tx: p_tx,
                     feedback_rx,
                     forwarder,
+                    checkpoints,
                 };
                 worker.run();
                 info!(shard = id, "Shard worker stopped.");
@@ -417,25 +455,46 @@ impl ShardWorker {
                 self.process_feedback(feedback);
             }
 
+            // Scheduled checkpoint (no-op until the interval elapses)
+            if let Some(scheduler) = self.checkpoints.as_mut() {
+                match scheduler.tick(&self.registry) {
+                    Ok(Some(snapshot)) => info!(
+                        shard = self.id,
+                        sequence = snapshot.sequence,
+                        full = snapshot.full,
+                        profiles = snapshot.profiles,
+                        bytes = snapshot.bytes,
+                        "Checkpoint written"
+                    ),
+                    Ok(None) => {}
+                    Err(e) => warn!(shard = self.id, error = %e, "Checkpoint failed"),
+                }
+            }
+
             // Process events
             match self.rx.recv_timeout(std::time::Duration::from_millis(100)) {
                 Ok(event) => {
                     let timer = PROCESSING_LATENCY.start_timer();
 
-                    let initial_len = self.registry.len();
+                    let initial_evictions = self.registry.stats().total_evictions;
 
-                    // Get or create profile
-                    let profile = self
+                    // Get or create profile, unless the shard is at its entity cap
+                    let Some(profile) = self
                         .registry
-                        .get_or_create(event.uid_hash, || AnomalyProfile::default());
+                        .try_get_or_create(event.uid_hash, AnomalyProfile::default)
+                    else {
+                        REJECTIONS_TOTAL.inc();
+                        timer.observe_duration();
+                        continue;
+                    };
 
                     // Process event and get rich signal
                     let signal = profile.process_with_hash(event.ts, event.uid_hash, event.val);
 
                     // Track evictions
-                    let new_len = self.registry.len();
-                    if new_len < initial_len {
-                        EVICTIONS_TOTAL.inc_by((initial_len - new_len) as f64);
+                    let evictions = self.registry.stats().total_evictions - initial_evictions;
+                    if evictions > 0 {
+                        EVICTIONS_TOTAL.inc_by(evictions as f64);
                     }
 
                     // Handle anomalies
@@ -474,6 +533,10 @@ impl ShardWorker {
                     // Periodic stats update
                     event_counter += 1;
                     if event_counter % 10000 == 0 {
+                        let expired = self.registry.expire_idle();
+                        if expired > 0 {
+                            EXPIRATIONS_TOTAL.inc_by(expired as f64);
+                        }
                         ACTIVE_PROFILES.set(self.registry.len() as f64);
                     }
                 }
@@ -705,50 +768,62 @@ async fn ingest_batch(
     StatusCode::ACCEPTED
 }
 
-async fn ingest_otel(
-    State(state): State<AppState>,
-    Json(batch): Json<OTelLogBatch>,
-) -> StatusCode {
+async fn ingest_otel(State(state): State<AppState>, Json(batch): Json<OTelLogBatch>) -> StatusCode {
     let mut total_events = 0u64;
-    
+
     for resource_log in batch.resourceLogs {
         for scope_log in resource_log.scopeLogs {
             for record in scope_log.logRecords {
                 total_events += 1;
-                
+
                 // Extract entity ID from attributes (service.name or custom entity.id)
-                let entity_id = record.attributes.iter()
+                let entity_id = record
+                    .attributes
+                    .iter()
                     .find(|kv| kv.key == "service.name" || kv.key == "entity.id")
                     .and_then(|kv| kv.value.as_str())
                     .unwrap_or("unknown")
                     .to_string();
-                
+
                 // Extract metric value from attributes
-                let metric_value = record.attributes.iter()
-                    .find(|kv| matches!(kv.key.as_str(), 
-                        "http.duration_ms" | "latency_ms" | 
-                        "process.memory.usage" | "process.cpu.utilization" |
-                        "http.status_code" | "value"))
+                let metric_value = record
+                    .attributes
+                    .iter()
+                    .find(|kv| {
+                        matches!(
+                            kv.key.as_str(),
+                            "http.duration_ms"
+                                | "latency_ms"
+                                | "process.memory.usage"
+                                | "process.cpu.utilization"
+                                | "http.status_code"
+                                | "value"
+                        )
+                    })
                     .and_then(|kv| kv.value.as_f64())
                     .unwrap_or(0.0);
-                
+
                 // Parse timestamp
                 let timestamp_ns: u64 = record.timeUnixNano.parse().unwrap_or(0);
-                
+
                 let event = IngestEvent {
                     u: entity_id,
                     v: metric_value,
                     t: timestamp_ns,
                 };
-                
-                if state.ingest_tx.try_send(IngestPacket::Single(event)).is_err() {
+
+                if state
+                    .ingest_tx
+                    .try_send(IngestPacket::Single(event))
+                    .is_err()
+                {
                     DROPPED_INGEST_QUEUE.inc();
                     DROPPED_TOTAL.inc();
                 }
             }
         }
     }
-    
+
     INGEST_TOTAL.inc_by(total_events as f64);
     StatusCode::ACCEPTED
 }
@@ -928,6 +1003,8 @@ async fn run() -> Result<(), String> {
     let _ = &*PROCESSING_LATENCY;
     let _ = &*ACTIVE_PROFILES;
     let _ = &*EVICTIONS_TOTAL;
+    let _ = &*REJECTIONS_TOTAL;
+    let _ = &*EXPIRATIONS_TOTAL;
     let _ = &*FEEDBACK_RECEIVED;
     let _ = &*FEEDBACK_PROFILE_MISS;
 
@@ -937,13 +1014,24 @@ async fn run() -> Result<(), String> {
     info!(shards = shard_count, "Configuring hardware parallelism.");
 
     // Registry config: 100K profiles per shard = ~1.6M total for 16 shards
+    let env_number = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|&n| n > 0)
+    };
     let registry_config = RegistryConfig {
         max_profiles: 100_000,
         min_events_for_eviction: 10,
         enable_lru: true,
+        idle_ttl: env_number("VIA_IDLE_TTL_SECS").map(std::time::Duration::from_secs),
+        max_entities: env_number("VIA_MAX_ENTITIES").map(|n| n as usize),
+        pinned_priority: None,
     };
     info!(
         max_profiles_per_shard = registry_config.max_profiles,
+        idle_ttl = ?registry_config.idle_ttl,
+        max_entities_per_shard = ?registry_config.max_entities,
         "Memory-bounded profile registry configured."
     );
 
@@ -962,6 +1050,29 @@ async fn run() -> Result<(), String> {
         Arc::new(Tier2Forwarder::new(config))
     });
 
+    // Scheduled checkpoints (optional, enabled via environment variable)
+    let checkpoint_dir = std::env::var("VIA_CHECKPOINT_DIR").ok();
+    let checkpoint_config = checkpoint_dir.map(|dir| {
+        let interval_secs = std::env::var("VIA_CHECKPOINT_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(60);
+        info!(dir = %dir, interval_secs, "Scheduled checkpoints enabled");
+        CheckpointSchedulerConfig {
+            dir: dir.into(),
+            interval: std::time::Duration::from_secs(interval_secs),
+            ..Default::default()
+        }
+    });
+
+    // Spill tier for evicted profiles (optional, enabled via environment variable)
+    let spill_dir = std::env::var("VIA_SPILL_DIR")
+        .ok()
+        .map(std::path::PathBuf::from);
+    if let Some(dir) = &spill_dir {
+        info!(dir = %dir.display(), "Evicted profiles spill to disk");
+    }
+
     // Shard workers
     let mut txs = Vec::new();
     let mut feedback_txs = Vec::new();
@@ -979,6 +1090,15 @@ async fn run() -> Result<(), String> {
             feedback_rx,
             registry_config.clone(),
             forwarder.clone(),
+            checkpoint_config
+                .as_ref()
+                .map(|config| CheckpointSchedulerConfig {
+                    dir: config.dir.join(format!("shard-{}", i)),
+                    ..config.clone()
+                }),
+            spill_dir
+                .as_ref()
+                .map(|dir| dir.join(format!("shard-{}", i))),
         ));
     }
 
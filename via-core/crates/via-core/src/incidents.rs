@@ -0,0 +1,353 @@
+//! Incident Aggregation
+//!
+//! Groups anomalous signals into incidents so consumers see one ongoing
+//! problem instead of a stream of per-event alerts. A signal joins the open
+//! incident for its entity and primary detector when it arrives within
+//! `merge_gap_ms` of that incident's latest signal; otherwise it opens a new
+//! incident. Signals held back by the alert cooldown extend an open incident
+//! but never open one.
+//!
+//! An incident closes once no signal has joined it for `merge_gap_ms` of
+//! stream time (the latest timestamp observed across all entities). The most
+//! recent closed incidents are kept in a bounded list.
+
+use crate::signal::{AnomalySignal, DetectorId, Severity};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IncidentConfig {
+    /// Longest quiet period (ms) between signals of one incident
+    pub merge_gap_ms: u64,
+    /// Open incidents tracked; beyond this the stalest one is closed early
+    pub max_open: usize,
+    /// Closed incidents kept for retrieval
+    pub max_closed: usize,
+}
+
+impl Default for IncidentConfig {
+    fn default() -> Self {
+        Self {
+            merge_gap_ms: 60_000,
+            max_open: 1024,
+            max_closed: 256,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Incident {
+    /// Increasing per tracker, in order of opening
+    pub id: u64,
+    pub entity_hash: u64,
+    pub primary_detector: DetectorId,
+    /// Timestamps (ns) of the first and latest contributing signal
+    pub start: u64,
+    pub end: u64,
+    pub signal_count: u32,
+    pub peak_severity: Severity,
+    pub peak_score: f64,
+    /// Detectors that fired in any contributing signal, in id order
+    pub contributing_detectors: Vec<DetectorId>,
+    pub is_open: bool,
+}
+
+impl Incident {
+    fn open(id: u64, primary_detector: DetectorId, signal: &AnomalySignal) -> Self {
+        let mut incident = Self {
+            id,
+            entity_hash: signal.entity_hash,
+            primary_detector,
+            start: signal.timestamp,
+            end: signal.timestamp,
+            signal_count: 0,
+            peak_severity: Severity::None,
+            peak_score: 0.0,
+            contributing_detectors: Vec::new(),
+            is_open: true,
+        };
+        incident.absorb(signal);
+        incident
+    }
+
+    fn absorb(&mut self, signal: &AnomalySignal) {
+        self.start = self.start.min(signal.timestamp);
+        self.end = self.end.max(signal.timestamp);
+        self.signal_count = self.signal_count.saturating_add(1);
+        self.peak_severity = self.peak_severity.max(signal.severity);
+        self.peak_score = self.peak_score.max(signal.ensemble_score);
+
+        for (idx, score) in signal.detector_scores.iter().enumerate() {
+            if !score.fired {
+                continue;
+            }
+            let Some(detector) = DetectorId::from_u8(idx as u8) else {
+                continue;
+            };
+            if let Err(pos) = self
+                .contributing_detectors
+                .binary_search_by_key(&(detector as u8), |d| *d as u8)
+            {
+                self.contributing_detectors.insert(pos, detector);
+            }
+        }
+    }
+}
+
+/// Correlates signals from many entities into incidents
+#[derive(Debug, Clone)]
+pub struct IncidentTracker {
+    config: IncidentConfig,
+    /// Open incidents keyed by (entity hash, primary detector)
+    open: HashMap<(u64, u8), Incident>,
+    closed: VecDeque<Incident>,
+    next_id: u64,
+    /// Latest signal timestamp seen (ns)
+    now: u64,
+    /// No open incident can go stale up to this timestamp (ns)
+    next_expiry: u64,
+}
+
+impl IncidentTracker {
+    pub fn new(config: IncidentConfig) -> Self {
+        let config = IncidentConfig {
+            max_open: config.max_open.max(1),
+            ..config
+        };
+        Self {
+            config,
+            open: HashMap::new(),
+            closed: VecDeque::with_capacity(config.max_closed),
+            next_id: 1,
+            now: 0,
+            next_expiry: u64::MAX,
+        }
+    }
+
+    pub fn config(&self) -> IncidentConfig {
+        self.config
+    }
+
+    /// Change the merge gap; applies to open incidents too
+    pub fn set_merge_gap_ms(&mut self, merge_gap_ms: u64) {
+        self.config.merge_gap_ms = merge_gap_ms;
+        self.next_expiry = 0;
+        self.expire(self.now);
+    }
+
+    /// Fold a signal into the incidents; returns the id of the incident it
+    /// joined or opened, if any
+    pub fn observe(&mut self, signal: &AnomalySignal) -> Option<u64> {
+        self.expire(signal.timestamp);
+        if !signal.is_anomaly && !signal.suppressed_by_cooldown {
+            return None;
+        }
+        let primary = signal.attribution.primary_detector;
+        let detector = DetectorId::from_u8(primary)?;
+        let key = (signal.entity_hash, primary);
+        let gap_ns = self.gap_ns();
+
+        if let Some(incident) = self.open.get_mut(&key) {
+            incident.absorb(signal);
+            self.next_expiry = self.next_expiry.min(incident.end.saturating_add(gap_ns));
+            return Some(incident.id);
+        }
+        if !signal.is_anomaly {
+            return None;
+        }
+
+        if self.open.len() >= self.config.max_open {
+            let stalest = self
+                .open
+                .iter()
+                .min_by_key(|(_, incident)| (incident.end, incident.id))
+                .map(|(key, _)| *key);
+            if let Some(stalest) = stalest {
+                self.close(stalest);
+            }
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        let incident = Incident::open(id, detector, signal);
+        self.next_expiry = self.next_expiry.min(incident.end.saturating_add(gap_ns));
+        self.open.insert(key, incident);
+        Some(id)
+    }
+
+    /// Close incidents that have been quiet for the merge gap as of `now`
+    /// (ns); returns how many closed
+    pub fn expire(&mut self, now: u64) -> usize {
+        self.now = self.now.max(now);
+        if self.now <= self.next_expiry {
+            return 0;
+        }
+
+        let gap_ns = self.gap_ns();
+        let now = self.now;
+        let stale: Vec<(u64, u8)> = self
+            .open
+            .iter()
+            .filter(|(_, incident)| now.saturating_sub(incident.end) > gap_ns)
+            .map(|(key, _)| *key)
+            .collect();
+        for key in &stale {
+            self.close(*key);
+        }
+        self.next_expiry = self
+            .open
+            .values()
+            .map(|incident| incident.end.saturating_add(gap_ns))
+            .min()
+            .unwrap_or(u64::MAX);
+        stale.len()
+    }
+
+    /// Open incidents, oldest first
+    pub fn open_incidents(&self) -> Vec<&Incident> {
+        let mut open: Vec<&Incident> = self.open.values().collect();
+        open.sort_unstable_by_key(|incident| incident.id);
+        open
+    }
+
+    /// Retained closed incidents, in the order they closed
+    pub fn closed_incidents(&self) -> impl Iterator<Item = &Incident> {
+        self.closed.iter()
+    }
+
+    pub fn open_count(&self) -> usize {
+        self.open.len()
+    }
+
+    fn close(&mut self, key: (u64, u8)) {
+        let Some(mut incident) = self.open.remove(&key) else {
+            return;
+        };
+        incident.is_open = false;
+        if self.config.max_closed == 0 {
+            return;
+        }
+        if self.closed.len() == self.config.max_closed {
+            self.closed.pop_front();
+        }
+        self.closed.push_back(incident);
+    }
+
+    fn gap_ns(&self) -> u64 {
+        self.config.merge_gap_ms.saturating_mul(1_000_000)
+    }
+}
+
+impl Default for IncidentTracker {
+    fn default() -> Self {
+        Self::new(IncidentConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::DetectorScore;
+
+    const SECOND: u64 = 1_000_000_000;
+
+    fn anomaly(
+        entity: u64,
+        seconds: u64,
+        primary: DetectorId,
+        fired: &[DetectorId],
+    ) -> AnomalySignal {
+        let mut signal = AnomalySignal {
+            entity_hash: entity,
+            timestamp: seconds * SECOND,
+            is_anomaly: true,
+            severity: Severity::Medium,
+            ensemble_score: 0.65,
+            ..Default::default()
+        };
+        signal.attribution.primary_detector = primary as u8;
+        for &detector in fired {
+            signal.detector_scores[detector as usize] =
+                DetectorScore::new(0.9, 1.0, true, 0.0, 0.0);
+        }
+        signal
+    }
+
+    #[test]
+    fn test_groups_related_signals() {
+        let mut tracker = IncidentTracker::new(IncidentConfig {
+            merge_gap_ms: 10_000,
+            ..Default::default()
+        });
+
+        let first = tracker.observe(&anomaly(1, 0, DetectorId::Volume, &[DetectorId::Volume]));
+        let mut peak = anomaly(
+            1,
+            5,
+            DetectorId::Volume,
+            &[DetectorId::Burst, DetectorId::Volume],
+        );
+        peak.severity = Severity::Critical;
+        peak.ensemble_score = 0.95;
+        assert_eq!(tracker.observe(&peak), first);
+
+        // A cooldown-suppressed signal extends it, a normal one is ignored
+        let mut held = anomaly(1, 12, DetectorId::Volume, &[]);
+        held.is_anomaly = false;
+        held.suppressed_by_cooldown = true;
+        assert_eq!(tracker.observe(&held), first);
+        let mut normal = anomaly(1, 13, DetectorId::Volume, &[]);
+        normal.is_anomaly = false;
+        assert_eq!(tracker.observe(&normal), None);
+
+        // Other entity or other primary detector: separate incidents
+        let other_entity = tracker.observe(&anomaly(2, 13, DetectorId::Volume, &[]));
+        let other_detector = tracker.observe(&anomaly(1, 13, DetectorId::Drift, &[]));
+        assert!(other_entity != first && other_detector != first);
+        assert_eq!(tracker.open_count(), 3);
+
+        let open = tracker.open_incidents();
+        let incident = open[0];
+        assert_eq!(incident.id, first.unwrap());
+        assert_eq!((incident.start, incident.end), (0, 12 * SECOND));
+        assert_eq!(incident.signal_count, 3);
+        assert_eq!(incident.peak_severity, Severity::Critical);
+        assert_eq!(incident.peak_score, 0.95);
+        assert_eq!(
+            incident.contributing_detectors,
+            vec![DetectorId::Volume, DetectorId::Burst]
+        );
+
+        let json = serde_json::to_value(incident).unwrap();
+        assert_eq!(json["primary_detector"], "volume");
+        assert_eq!(json["is_open"], true);
+    }
+
+    #[test]
+    fn test_quiet_incidents_close() {
+        let mut tracker = IncidentTracker::new(IncidentConfig {
+            merge_gap_ms: 10_000,
+            max_open: 2,
+            max_closed: 2,
+        });
+        let first = tracker.observe(&anomaly(1, 0, DetectorId::Volume, &[]));
+        tracker.observe(&anomaly(2, 8, DetectorId::Volume, &[]));
+
+        // Stream time from any entity closes the first incident
+        assert_eq!(tracker.expire(15 * SECOND), 1);
+        let closed: Vec<_> = tracker.closed_incidents().collect();
+        assert_eq!(closed.len(), 1);
+        assert_eq!(Some(closed[0].id), first);
+        assert!(!closed[0].is_open);
+
+        // The same entity after the gap opens a new incident
+        let reopened = tracker.observe(&anomaly(1, 16, DetectorId::Volume, &[]));
+        assert!(reopened.unwrap() > first.unwrap());
+
+        // Over capacity, the stalest open incident closes early
+        tracker.observe(&anomaly(3, 17, DetectorId::Volume, &[]));
+        assert_eq!(tracker.open_count(), 2);
+        let ids: Vec<_> = tracker.closed_incidents().map(|i| i.entity_hash).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+}
@@ -1,40 +1,86 @@
 //! VIA-Core: SOTA Anomaly Detection Engine
 //!
 //! High-performance Tier-1 detection engine with:
-//! - 10 SOTA detectors (Volume, Distribution, Cardinality, Burst, Spectral, ChangePoint, RRCF, MultiScale, Behavioral, Drift)
+//! - 13 SOTA detectors (Volume, Distribution, Cardinality, Burst, Spectral, ChangePoint, RRCF, MultiScale, Behavioral, Drift, Quantile, EVT, Discord)
+//! - Custom detectors plugged into the ensemble alongside the built-in ones
 //! - Adaptive Ensemble with Thompson Sampling weight learning
-//! - Rich AnomalySignal output with full attribution
+//! - Rich AnomalySignal output with full attribution, and an explanation
+//!   (recent values, fired detectors' reasons, weight shares) on anomalies
 //! - Feedback loop for continuous improvement
-//! - Memory-bounded profile registry with LRU eviction
+//! - Severity calibration: ensemble scores mapped to anomaly probabilities
+//!   learned from feedback
+//! - Memory-bounded profile registry with LRU eviction, an eviction
+//!   callback, and an optional spill-to-disk tier for evicted profiles
+//! - Registry policies: idle TTL, hard entity cap with rejection, and
+//!   pinned (never evicted) priorities
+//! - Sharded registry with a lock per shard, shared across threads
+//! - Tokio-native engine: sharded worker threads behind an awaitable
+//!   `process` and a bounded ingestion channel with backpressure
 //! - Checkpoint/recovery for Bun-managed persistence
+//! - Portable, mergeable JSON baselines; new profiles can start from a
+//!   pinned golden baseline instead of warming up
+//! - Merging profiles of one entity tracked on several nodes
+//! - Lock-free profile views: counters, double-buffered weights and
+//!   detector stats, and on-request checkpoints readable from other threads
+//!   while the profile keeps processing
+//! - Scheduled full and incremental checkpoints to disk
 //! - Tier-2 HTTP forwarding for anomaly signals
+//! - Bounded lifecycle event log per profile and registry
+//! - Incident aggregation of related anomaly signals across entities
+//! - Tracing spans and events for detection and checkpoints (feature
+//!   `instrument`), with a subscriber hosts install over FFI
 #![allow(clippy::not_unsafe_ptr_arg_deref)]
 
 use std::ffi::{CStr, CString};
-use std::os::raw::{c_char, c_double, c_ulonglong};
+use std::os::raw::{c_char, c_double, c_int, c_longlong, c_ulonglong, c_void};
 
 // Core modules
 pub mod algo;
+pub mod alloc_counter;
+pub mod async_engine;
+pub mod baseline;
 pub mod checkpoint;
+pub mod checkpoint_scheduler;
 pub mod engine;
+pub mod explain;
 pub mod feedback;
 pub mod forwarder;
+pub mod incidents;
+pub mod lifecycle;
 pub mod policy;
 pub mod registry;
 pub mod signal;
+pub mod spill;
+pub mod telemetry;
+pub mod view;
 
 // Re-exports
+pub use async_engine::{
+    AsyncDetectionEngine, AsyncEngineConfig, DetectionEvent, EngineError, EngineStats, IngestSender,
+};
+pub use baseline::{BaselineError, ProfileBaseline};
 pub use checkpoint::{CheckpointError, CheckpointManager, CheckpointRequest, FullCheckpoint};
-pub use engine::{AnomalyProfile, AnomalyResult, ProfileConfig, SignalContext};
+pub use checkpoint_scheduler::{CheckpointScheduler, CheckpointSchedulerConfig};
+pub use engine::{
+    AlertSuppression, AnomalyProfile, AnomalyResult, ConfigError, DecompositionConfig,
+    DetectionReason, DetectionResult, Detector, EmissionFloor, ExogenousContext, FeatureChannel,
+    FeatureVector, NUM_FEATURES, ProfileConfig, SignalContext,
+};
+pub use explain::{ConditionCheck, DecisionBranch, DecisionExplanation};
 pub use feedback::{
     FeedbackChannel, FeedbackEvent, FeedbackLabelClass, FeedbackSource, FeedbackStats,
 };
 pub use forwarder::{ForwarderConfig, ForwarderStats, Tier1SignalV1, Tier2Forwarder};
+pub use incidents::{Incident, IncidentConfig, IncidentTracker};
+pub use lifecycle::{LifecycleEvent, LifecycleEventKind, LifecycleLog};
 pub use policy::{PolicySnapshot, runtime as policy_runtime};
-pub use registry::{ProfileRegistry, RegistryConfig};
+pub use registry::{ProfileRegistry, RegistryConfig, ShardedRegistry};
 pub use signal::{
-    AnomalySignal, Attribution, BaselineSummary, DetectorId, DetectorScore, NUM_DETECTORS, Severity,
+    AnomalySignal, Attribution, BaselineSummary, CAnomalySignalFlat, DetectorId, DetectorScore,
+    FiredDetector, NUM_DETECTORS, NormalRange, Severity, SignalExplanation, WeightContribution,
 };
+pub use spill::{DirectoryStore, SpillStore};
+pub use view::{ProfileView, PublishedCheckpoint, StatsSnapshot};
 
 // ============================================================================
 // FFI INTERFACE
@@ -125,6 +171,272 @@ pub extern "C" fn via_process_event(
     Box::into_raw(Box::new(signal))
 }
 
+/// Process an event, writing the signal into caller memory at `out`
+///
+/// Allocates nothing per event, unlike `via_process_event`. Returns false
+/// and leaves `*out` untouched for a null profile or `out`.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_process_event_into(
+    ptr: *mut AnomalyProfile,
+    timestamp: c_ulonglong,
+    unique_id_hash: c_ulonglong,
+    value: c_double,
+    out: *mut CAnomalySignalFlat,
+) -> bool {
+    if ptr.is_null() || out.is_null() {
+        return false;
+    }
+
+    let profile = unsafe { &mut *ptr };
+    let signal = profile.process_with_hash(timestamp, unique_id_hash, value);
+
+    unsafe { out.write(CAnomalySignalFlat::from(&signal)) };
+    true
+}
+
+/// Size in bytes of `CAnomalySignalFlat`, for hosts to check their layout
+#[unsafe(no_mangle)]
+pub extern "C" fn via_signal_flat_size() -> usize {
+    std::mem::size_of::<CAnomalySignalFlat>()
+}
+
+/// Process a multivariate event: `len` values at `features` in channel order
+/// (latency, payload size, status class; see `FeatureChannel`)
+///
+/// Returns null for a null profile, or null `features` with a non-zero
+/// `len`. Otherwise returns a signal that must be freed with `via_free_signal`.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_process_vector(
+    ptr: *mut AnomalyProfile,
+    timestamp: c_ulonglong,
+    unique_id_hash: c_ulonglong,
+    features: *const c_double,
+    len: usize,
+) -> *mut AnomalySignal {
+    if ptr.is_null() || (features.is_null() && len > 0) {
+        return std::ptr::null_mut();
+    }
+
+    let features = if len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(features, len) }
+    };
+    let profile = unsafe { &mut *ptr };
+    let signal = profile.process_vector(timestamp, unique_id_hash, features);
+
+    Box::into_raw(Box::new(signal))
+}
+
+/// Process an event with exogenous context (see `ExogenousContext`)
+///
+/// `business_hours` is 1 inside business hours, 0 outside, and negative when
+/// unknown. Returns a signal that must be freed with `via_free_signal`.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_process_event_with_context(
+    ptr: *mut AnomalyProfile,
+    timestamp: c_ulonglong,
+    unique_id_hash: c_ulonglong,
+    value: c_double,
+    business_hours: c_int,
+    deploy_in_progress: bool,
+) -> *mut AnomalySignal {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let exogenous = exogenous_context(business_hours, deploy_in_progress);
+    let profile = unsafe { &mut *ptr };
+    let signal = profile.process_with_context(timestamp, unique_id_hash, value, exogenous);
+
+    Box::into_raw(Box::new(signal))
+}
+
+/// Process an event, materializing a signal only when it clears the
+/// profile's emission floor (see `via_set_emission_floor`)
+///
+/// Returns 0 for a normal event and leaves `*out_signal` null. Otherwise
+/// returns 1 + severity and, when `out_signal` is non-null, stores a signal
+/// there that must be freed with `via_free_signal`. Context arguments are as
+/// for `via_process_event_with_context`.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_process_event_filtered(
+    ptr: *mut AnomalyProfile,
+    timestamp: c_ulonglong,
+    unique_id_hash: c_ulonglong,
+    value: c_double,
+    business_hours: c_int,
+    deploy_in_progress: bool,
+    out_signal: *mut *mut AnomalySignal,
+) -> u8 {
+    if !out_signal.is_null() {
+        unsafe { *out_signal = std::ptr::null_mut() };
+    }
+    if ptr.is_null() {
+        return 0;
+    }
+
+    let exogenous = exogenous_context(business_hours, deploy_in_progress);
+    let profile = unsafe { &mut *ptr };
+    match profile.process_filtered(timestamp, unique_id_hash, value, exogenous) {
+        Some(signal) => {
+            let code = 1 + signal.severity as u8;
+            if !out_signal.is_null() {
+                unsafe { *out_signal = Box::into_raw(Box::new(signal)) };
+            }
+            code
+        }
+        None => 0,
+    }
+}
+
+/// Process `len` events given as parallel arrays, in order
+///
+/// Signals are filtered against the profile's emission floor as in
+/// `via_process_event_filtered`. When `out_codes` is non-null it receives
+/// one code per event: 0 for a normal event, else 1 + severity. Returns the
+/// number of events that cleared the floor, or -1 for a null profile or null
+/// input arrays with a non-zero `len`.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_process_batch(
+    ptr: *mut AnomalyProfile,
+    timestamps: *const c_ulonglong,
+    unique_id_hashes: *const c_ulonglong,
+    values: *const c_double,
+    len: usize,
+    out_codes: *mut u8,
+) -> c_longlong {
+    if ptr.is_null() {
+        return -1;
+    }
+    if len == 0 {
+        return 0;
+    }
+    if timestamps.is_null() || unique_id_hashes.is_null() || values.is_null() {
+        return -1;
+    }
+
+    let (timestamps, hashes, values) = unsafe {
+        (
+            std::slice::from_raw_parts(timestamps, len),
+            std::slice::from_raw_parts(unique_id_hashes, len),
+            std::slice::from_raw_parts(values, len),
+        )
+    };
+    let mut codes =
+        (!out_codes.is_null()).then(|| unsafe { std::slice::from_raw_parts_mut(out_codes, len) });
+
+    let profile = unsafe { &mut *ptr };
+    let mut emitted = 0;
+    for (i, ((&timestamp, &hash), &value)) in timestamps.iter().zip(hashes).zip(values).enumerate()
+    {
+        let code =
+            match profile.process_filtered(timestamp, hash, value, ExogenousContext::default()) {
+                Some(signal) => {
+                    emitted += 1;
+                    1 + signal.severity as u8
+                }
+                None => 0,
+            };
+        if let Some(codes) = codes.as_mut() {
+            codes[i] = code;
+        }
+    }
+    emitted
+}
+
+/// Set the severity (0 = None .. 4 = Critical) and score an anomaly must
+/// reach for `via_process_event_filtered` to emit it
+///
+/// Returns false for a null profile or an unknown severity.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_set_emission_floor(
+    ptr: *mut AnomalyProfile,
+    min_severity: u8,
+    min_score: c_double,
+) -> bool {
+    let min_severity = match min_severity {
+        0 => Severity::None,
+        1 => Severity::Low,
+        2 => Severity::Medium,
+        3 => Severity::High,
+        4 => Severity::Critical,
+        _ => return false,
+    };
+    if ptr.is_null() {
+        return false;
+    }
+
+    let profile = unsafe { &mut *ptr };
+    profile.set_emission_floor(EmissionFloor {
+        min_severity,
+        min_score,
+    });
+    true
+}
+
+const CONFIG_MALFORMED: c_int = -1;
+const CONFIG_OUT_OF_RANGE: c_int = -2;
+const CONFIG_STRUCTURAL: c_int = -3;
+
+/// Hot-reload decision settings on a live profile without resetting what it
+/// has learned (see `AnomalyProfile::update_config`)
+///
+/// `config_json` is a JSON object holding any subset of `ProfileConfig`
+/// fields; the rest keep their current value. Returns 0 on success, -1 for a
+/// null profile or malformed JSON, -2 for a threshold outside [0, 1] and -3
+/// for a field that needs the profile re-created. On error nothing changes.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_update_config(ptr: *mut AnomalyProfile, config_json: *const c_char) -> c_int {
+    if ptr.is_null() {
+        return CONFIG_MALFORMED;
+    }
+
+    let profile = unsafe { &mut *ptr };
+    let Some(config) = patched_config(profile.config(), config_json) else {
+        return CONFIG_MALFORMED;
+    };
+    match profile.update_config(config) {
+        Ok(()) => 0,
+        Err(e) => config_error_code(&e),
+    }
+}
+
+/// `base` with the fields of the JSON object at `json` overlaid
+fn patched_config(base: &ProfileConfig, json: *const c_char) -> Option<ProfileConfig> {
+    if json.is_null() {
+        return None;
+    }
+    let json = unsafe { CStr::from_ptr(json) }.to_str().ok()?;
+    let serde_json::Value::Object(patch) = serde_json::from_str(json).ok()? else {
+        return None;
+    };
+
+    let mut config = serde_json::to_value(base).ok()?;
+    let fields = config.as_object_mut()?;
+    for (field, value) in patch {
+        if !fields.contains_key(&field) {
+            return None;
+        }
+        fields.insert(field, value);
+    }
+    serde_json::from_value(config).ok()
+}
+
+fn config_error_code(error: &ConfigError) -> c_int {
+    match error {
+        ConfigError::OutOfRange { .. } => CONFIG_OUT_OF_RANGE,
+        ConfigError::Structural(_) => CONFIG_STRUCTURAL,
+    }
+}
+
+fn exogenous_context(business_hours: c_int, deploy_in_progress: bool) -> ExogenousContext {
+    ExogenousContext {
+        business_hours: (business_hours >= 0).then_some(business_hours > 0),
+        deploy_in_progress,
+    }
+}
+
 /// Free an AnomalySignal
 #[unsafe(no_mangle)]
 pub extern "C" fn via_free_signal(ptr: *mut AnomalySignal) {
@@ -145,6 +457,15 @@ pub extern "C" fn via_signal_is_anomaly(ptr: *const AnomalySignal) -> bool {
     unsafe { (*ptr).is_anomaly }
 }
 
+/// Whether the cooldown after a previous anomaly held this one back
+#[unsafe(no_mangle)]
+pub extern "C" fn via_signal_suppressed_by_cooldown(ptr: *const AnomalySignal) -> bool {
+    if ptr.is_null() {
+        return false;
+    }
+    unsafe { (*ptr).suppressed_by_cooldown }
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn via_signal_severity(ptr: *const AnomalySignal) -> u8 {
     if ptr.is_null() {
@@ -169,6 +490,16 @@ pub extern "C" fn via_signal_confidence(ptr: *const AnomalySignal) -> c_double {
     unsafe { (*ptr).confidence }
 }
 
+/// Feedback-calibrated anomaly probability, or -1.0 while the profile is
+/// uncalibrated
+#[unsafe(no_mangle)]
+pub extern "C" fn via_signal_calibrated_probability(ptr: *const AnomalySignal) -> c_double {
+    if ptr.is_null() {
+        return -1.0;
+    }
+    unsafe { (*ptr).calibrated_probability }.map_or(-1.0, |p| p as c_double)
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn via_signal_primary_detector(ptr: *const AnomalySignal) -> u8 {
     if ptr.is_null() {
@@ -203,6 +534,30 @@ pub extern "C" fn via_signal_detector_weight(ptr: *const AnomalySignal, detector
     unsafe { (*ptr).detector_weights[detector_idx as usize] }
 }
 
+/// Write the values that would not have fired (`AnomalySignal::normal_range`)
+/// to `low` and `high`, an open bound as -/+infinity
+///
+/// Returns false, leaving both untouched, when the signal has no range or a
+/// pointer is null.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_signal_normal_range(
+    ptr: *const AnomalySignal,
+    low: *mut c_double,
+    high: *mut c_double,
+) -> bool {
+    if ptr.is_null() || low.is_null() || high.is_null() {
+        return false;
+    }
+    let Some(range) = (unsafe { (*ptr).normal_range }) else {
+        return false;
+    };
+    unsafe {
+        low.write(range.low.unwrap_or(f64::NEG_INFINITY));
+        high.write(range.high.unwrap_or(f64::INFINITY));
+    }
+    true
+}
+
 /// Serialize signal to JSON (returns null-terminated string, must free with via_free_string)
 #[unsafe(no_mangle)]
 pub extern "C" fn via_signal_to_json(ptr: *const AnomalySignal) -> *mut c_char {
@@ -220,6 +575,99 @@ pub extern "C" fn via_signal_to_json(ptr: *const AnomalySignal) -> *mut c_char {
     }
 }
 
+/// The signal's explanation as JSON (must free with via_free_string)
+///
+/// Null when the signal carries none: it was not anomalous, or the profile
+/// has `explain_history` set to 0.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_signal_explanation_json(ptr: *const AnomalySignal) -> *mut c_char {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let signal = unsafe { &*ptr };
+    let Some(explanation) = signal.explanation() else {
+        return std::ptr::null_mut();
+    };
+    match serde_json::to_string(explanation) {
+        Ok(json) => match CString::new(json) {
+            Ok(c_str) => c_str.into_raw(),
+            Err(_) => std::ptr::null_mut(),
+        },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Explain a signal's decision as JSON (must free with via_free_string)
+///
+/// Call right after `via_process_event` on the same profile; see
+/// `AnomalyProfile::explain_decision`.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_explain_decision(
+    profile_ptr: *const AnomalyProfile,
+    signal_ptr: *const AnomalySignal,
+) -> *mut c_char {
+    if profile_ptr.is_null() || signal_ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let profile = unsafe { &*profile_ptr };
+    let signal = unsafe { &*signal_ptr };
+    match serde_json::to_string(&profile.explain_decision(signal)) {
+        Ok(json) => match CString::new(json) {
+            Ok(c_str) => c_str.into_raw(),
+            Err(_) => std::ptr::null_mut(),
+        },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Lifecycle events recorded at or after `since_unix_ms` as JSON
+/// (`{"capacity", "dropped", "events": [...]}`; must free with via_free_string)
+#[unsafe(no_mangle)]
+pub extern "C" fn via_profile_lifecycle(
+    profile_ptr: *const AnomalyProfile,
+    since_unix_ms: c_ulonglong,
+) -> *mut c_char {
+    if profile_ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let profile = unsafe { &*profile_ptr };
+    match serde_json::to_string(&profile.lifecycle(since_unix_ms)) {
+        Ok(json) => match CString::new(json) {
+            Ok(c_str) => c_str.into_raw(),
+            Err(_) => std::ptr::null_mut(),
+        },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Report that the host moved a profile into (or out of) a degraded tier.
+/// `reason` may be null.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_record_tier_change(
+    profile_ptr: *mut AnomalyProfile,
+    degraded: bool,
+    reason: *const c_char,
+) -> bool {
+    if profile_ptr.is_null() {
+        return false;
+    }
+
+    let reason = if reason.is_null() {
+        ""
+    } else {
+        match unsafe { CStr::from_ptr(reason) }.to_str() {
+            Ok(s) => s,
+            Err(_) => return false,
+        }
+    };
+    let profile = unsafe { &mut *profile_ptr };
+    profile.record_tier_change(degraded, reason);
+    true
+}
+
 /// Reset a profile
 #[unsafe(no_mangle)]
 pub extern "C" fn reset_profile(ptr: *mut AnomalyProfile) {
@@ -294,6 +742,47 @@ pub extern "C" fn via_send_feedback(
     true
 }
 
+/// Send a JSON `FeedbackEvent` to a profile
+///
+/// Unlike `via_send_feedback` this carries every field, including the
+/// original `ensemble_score` that trains the score calibration.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_send_feedback_json(
+    profile_ptr: *mut AnomalyProfile,
+    event_json: *const c_char,
+) -> bool {
+    if profile_ptr.is_null() || event_json.is_null() {
+        return false;
+    }
+
+    let Ok(json) = unsafe { CStr::from_ptr(event_json) }.to_str() else {
+        return false;
+    };
+    let Ok(event) = serde_json::from_str::<FeedbackEvent>(json) else {
+        return false;
+    };
+    unsafe { &mut *profile_ptr }.apply_feedback(&[event]);
+    true
+}
+
+/// Score calibration curve as JSON (`{"method", "label_weight", "points"}`;
+/// must free with via_free_string)
+#[unsafe(no_mangle)]
+pub extern "C" fn via_calibration_curve(profile_ptr: *const AnomalyProfile) -> *mut c_char {
+    if profile_ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let profile = unsafe { &*profile_ptr };
+    match serde_json::to_string(&profile.calibrator().curve()) {
+        Ok(json) => match CString::new(json) {
+            Ok(c_str) => c_str.into_raw(),
+            Err(_) => std::ptr::null_mut(),
+        },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
 // ============================================================================
 // CHECKPOINT FFI
 // ============================================================================
@@ -342,45 +831,771 @@ pub extern "C" fn via_restore_from_checkpoint(
     }
 }
 
-// ============================================================================
-// UTILITY FUNCTIONS
-// ============================================================================
-
-/// Hash a string (for pre-hashing on the Bun side)
+/// Create a checkpoint from a profile as raw bytes, skipping the base64 copy
+///
+/// Stores the length in `*out_len` and returns the bytes, which must be freed
+/// with `via_free_bytes`. Returns null if the checkpoint exceeds
+/// `checkpoint::MAX_PROFILE_CHECKPOINT_BYTES`.
 #[unsafe(no_mangle)]
-pub extern "C" fn via_hash_string(s: *const c_char) -> c_ulonglong {
-    if s.is_null() {
-        return 0;
+pub extern "C" fn via_create_checkpoint_bytes(
+    profile_ptr: *const AnomalyProfile,
+    out_len: *mut usize,
+) -> *mut u8 {
+    if profile_ptr.is_null() || out_len.is_null() {
+        return std::ptr::null_mut();
     }
 
-    let c_str = unsafe { CStr::from_ptr(s) };
-    match c_str.to_str() {
-        Ok(str_slice) => xxhash_rust::xxh3::xxh3_64(str_slice.as_bytes()),
-        Err(_) => 0,
+    let profile = unsafe { &*profile_ptr };
+    let checkpoint_data = profile.to_checkpoint();
+    if checkpoint_data.len() > checkpoint::MAX_PROFILE_CHECKPOINT_BYTES as usize {
+        return std::ptr::null_mut();
     }
+    into_raw_bytes(checkpoint_data, out_len)
 }
 
-/// Get detector name by index
+/// Restore a profile from `len` raw checkpoint bytes at `data`
+///
+/// The bytes are only borrowed for the call. Returns null for oversized or
+/// malformed input.
 #[unsafe(no_mangle)]
-pub extern "C" fn via_detector_name(idx: u8) -> *const c_char {
-    static NAMES: [&str; NUM_DETECTORS] = [
-        "Volume/RPS\0",
-        "Distribution/Value\0",
-        "Cardinality/Velocity\0",
-        "Burst/IAT\0",
-        "Spectral/FFT\0",
-        "ChangePoint/Trend\0",
-        "RRCF/Isolation\0",
-        "MultiScale/Temporal\0",
-        "Behavioral/Fingerprint\0",
-        "Drift/Concept\0",
-    ];
+pub extern "C" fn via_restore_from_checkpoint_bytes(
+    data: *const u8,
+    len: usize,
+) -> *mut AnomalyProfile {
+    if data.is_null() || len > checkpoint::MAX_PROFILE_CHECKPOINT_BYTES as usize {
+        return std::ptr::null_mut();
+    }
 
-    if idx >= NUM_DETECTORS as u8 {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    match AnomalyProfile::from_checkpoint(data) {
+        Ok(profile) => Box::into_raw(Box::new(profile)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Free a byte buffer returned by `via_create_checkpoint_bytes` or
+/// `via_registry_checkpoint_all`
+#[unsafe(no_mangle)]
+pub extern "C" fn via_free_bytes(ptr: *mut u8, len: usize) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let _ = Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len));
+    }
+}
+
+fn into_raw_bytes(data: Vec<u8>, out_len: *mut usize) -> *mut u8 {
+    let data = data.into_boxed_slice();
+    unsafe { *out_len = data.len() };
+    Box::into_raw(data) as *mut u8
+}
+
+// ============================================================================
+// BASELINE FFI
+// ============================================================================
+
+fn parse_baseline(json: *const c_char) -> Option<ProfileBaseline> {
+    if json.is_null() {
+        return None;
+    }
+    let json = unsafe { CStr::from_ptr(json) }.to_str().ok()?;
+    serde_json::from_str(json).ok()
+}
+
+fn baseline_json(baseline: &ProfileBaseline) -> *mut c_char {
+    match serde_json::to_string_pretty(baseline) {
+        Ok(json) => match CString::new(json) {
+            Ok(c_str) => c_str.into_raw(),
+            Err(_) => std::ptr::null_mut(),
+        },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// The profile's learned baseline as pretty-printed JSON (see
+/// `baseline::ProfileBaseline`; must free with via_free_string)
+#[unsafe(no_mangle)]
+pub extern "C" fn via_profile_export_baseline(ptr: *const AnomalyProfile) -> *mut c_char {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    baseline_json(&unsafe { &*ptr }.export_baseline())
+}
+
+/// Create a default-configured profile starting from a baseline (see
+/// `AnomalyProfile::from_baseline`)
+///
+/// Returns null for malformed JSON or a baseline whose histogram layout does
+/// not match the default configuration.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_create_profile_from_baseline(
+    baseline_json: *const c_char,
+) -> *mut AnomalyProfile {
+    let Some(baseline) = parse_baseline(baseline_json) else {
+        return std::ptr::null_mut();
+    };
+    match AnomalyProfile::from_baseline(ProfileConfig::default(), &baseline) {
+        Ok(profile) => Box::into_raw(Box::new(profile)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Merge two baselines, weighted by the events behind each (see
+/// `ProfileBaseline::merge`; must free with via_free_string)
+///
+/// Returns null for malformed JSON or incompatible layouts.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_baseline_merge(a_json: *const c_char, b_json: *const c_char) -> *mut c_char {
+    let (Some(a), Some(b)) = (parse_baseline(a_json), parse_baseline(b_json)) else {
+        return std::ptr::null_mut();
+    };
+    match a.merge(&b) {
+        Ok(merged) => baseline_json(&merged),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Fold `src`, a profile of the same entity from another node, into `dst`
+/// (see `AnomalyProfile::merge`); `src` is left as it was
+///
+/// Returns 0 on success, -1 for a null pointer or `src == dst`, and -2 when
+/// the histogram layouts or season lengths differ (`dst` is unchanged).
+#[unsafe(no_mangle)]
+pub extern "C" fn via_profile_merge(dst: *mut AnomalyProfile, src: *const AnomalyProfile) -> c_int {
+    if dst.is_null() || src.is_null() || std::ptr::eq(dst, src) {
+        return -1;
+    }
+    match unsafe { &mut *dst }.merge(unsafe { &*src }) {
+        Ok(()) => 0,
+        Err(_) => -2,
+    }
+}
+
+// ============================================================================
+// VIEW FFI
+// ============================================================================
+
+/// Read-only view of a profile for a monitoring thread; the profile keeps
+/// processing on its own thread and is never locked by view calls. Free
+/// with `via_view_free` (the view may outlive the profile)
+#[unsafe(no_mangle)]
+pub extern "C" fn via_profile_view(ptr: *const AnomalyProfile) -> *mut ProfileView {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(unsafe { &*ptr }.view()))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn via_view_free(view: *mut ProfileView) {
+    if !view.is_null() {
+        unsafe {
+            let _ = Box::from_raw(view);
+        }
+    }
+}
+
+/// Counters and the latest published snapshot as JSON:
+/// `{"event_count", "anomaly_count", "last_timestamp", "snapshot": {...}}`
+#[unsafe(no_mangle)]
+pub extern "C" fn via_view_stats_json(view: *const ProfileView) -> *mut c_char {
+    if view.is_null() {
+        return std::ptr::null_mut();
+    }
+    let view = unsafe { &*view };
+    let json = serde_json::json!({
+        "event_count": view.event_count(),
+        "anomaly_count": view.anomaly_count(),
+        "last_timestamp": view.last_timestamp(),
+        "snapshot": *view.snapshot(),
+    });
+    match CString::new(json.to_string()) {
+        Ok(c_str) => c_str.into_raw(),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Ask for a checkpoint, published with the profile's next event
+#[unsafe(no_mangle)]
+pub extern "C" fn via_view_request_checkpoint(view: *const ProfileView) {
+    if !view.is_null() {
+        unsafe { &*view }.request_checkpoint();
+    }
+}
+
+/// Latest checkpoint published on request, in the `via_create_checkpoint_bytes`
+/// format; null until one was published. Free with `via_free_bytes`
+#[unsafe(no_mangle)]
+pub extern "C" fn via_view_checkpoint(view: *const ProfileView, out_len: *mut usize) -> *mut u8 {
+    if view.is_null() || out_len.is_null() {
+        return std::ptr::null_mut();
+    }
+    match unsafe { &*view }.checkpoint() {
+        Some(checkpoint) => into_raw_bytes(checkpoint.bytes.to_vec(), out_len),
+        None => std::ptr::null_mut(),
+    }
+}
+
+// ============================================================================
+// REGISTRY FFI
+// ============================================================================
+
+/// Registry of profiles keyed by entity hash, as handed to the host
+pub struct AnomalyRegistry {
+    profiles: ProfileRegistry<AnomalyProfile>,
+    /// Configuration for profiles the registry creates from here on
+    profile_config: ProfileConfig,
+    /// Incidents built from the signals the registry emits
+    incidents: IncidentTracker,
+    /// Baseline new profiles start from (see `via_registry_pin_baseline`)
+    golden_baseline: Option<ProfileBaseline>,
+}
+
+/// Sink for `via_registry_checkpoint_stream`; returns false to abort
+pub type ViaWriteCallback = extern "C" fn(ctx: *mut c_void, data: *const u8, len: usize) -> bool;
+
+/// Source for `via_registry_restore_stream`; fills up to `cap` bytes and
+/// returns how many were written, 0 at end of input or negative on error
+pub type ViaReadCallback = extern "C" fn(ctx: *mut c_void, buf: *mut u8, cap: usize) -> isize;
+
+/// Create an empty registry holding at most `max_profiles` (0 = default)
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_create(max_profiles: c_ulonglong) -> *mut AnomalyRegistry {
+    let mut config = RegistryConfig::default();
+    if max_profiles > 0 {
+        config.max_profiles = max_profiles as usize;
+    }
+    Box::into_raw(Box::new(AnomalyRegistry {
+        profiles: ProfileRegistry::with_config(config),
+        profile_config: ProfileConfig::default(),
+        incidents: IncidentTracker::default(),
+        golden_baseline: None,
+    }))
+}
+
+/// Free a registry and every profile in it
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_free(ptr: *mut AnomalyRegistry) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let _ = Box::from_raw(ptr);
+    }
+}
+
+/// Number of profiles currently held in memory by the registry (spilled
+/// ones not included)
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_len(ptr: *const AnomalyRegistry) -> c_ulonglong {
+    if ptr.is_null() {
+        return 0;
+    }
+    unsafe { &*ptr }.profiles.len() as c_ulonglong
+}
+
+/// Process an event against the entity's profile, creating it on first use
+///
+/// Returns a signal that must be freed with `via_free_signal`, or null when
+/// the entity is new and the registry is at its entity cap (see
+/// `via_registry_set_policy`).
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_process_event(
+    ptr: *mut AnomalyRegistry,
+    timestamp: c_ulonglong,
+    unique_id_hash: c_ulonglong,
+    value: c_double,
+) -> *mut AnomalySignal {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let AnomalyRegistry {
+        profiles,
+        profile_config,
+        incidents,
+        golden_baseline,
+    } = unsafe { &mut *ptr };
+    let Some(profile) = profiles.try_get_or_create(unique_id_hash, || {
+        // A baseline a later config change made incompatible is skipped
+        golden_baseline
+            .as_ref()
+            .and_then(|b| AnomalyProfile::from_baseline(profile_config.clone(), b).ok())
+            .unwrap_or_else(|| AnomalyProfile::with_config(profile_config.clone()))
+    }) else {
+        return std::ptr::null_mut();
+    };
+    let signal = profile.process_with_hash(timestamp, unique_id_hash, value);
+    incidents.observe(&signal);
+
+    Box::into_raw(Box::new(signal))
+}
+
+/// Checkpoint every profile in the registry as one length-prefixed binary
+/// blob (see `checkpoint::write_registry`)
+///
+/// Stores the blob length in `*out_len` and returns the blob, which must be
+/// freed with `via_free_bytes`. Returns null on error.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_checkpoint_all(
+    ptr: *const AnomalyRegistry,
+    out_len: *mut usize,
+) -> *mut u8 {
+    if ptr.is_null() || out_len.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let registry = unsafe { &*ptr };
+    let mut blob = Vec::new();
+    if checkpoint::write_registry(&registry.profiles, &mut blob).is_err() {
+        return std::ptr::null_mut();
+    }
+
+    into_raw_bytes(blob, out_len)
+}
+
+/// Restore profiles from a `via_registry_checkpoint_all` blob, replacing
+/// profiles with the same entity hash
+///
+/// Returns the number of profiles restored, or -1 on a malformed blob.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_restore_all(
+    ptr: *mut AnomalyRegistry,
+    data: *const u8,
+    len: usize,
+) -> c_longlong {
+    if ptr.is_null() || data.is_null() {
+        return -1;
+    }
+
+    let registry = unsafe { &mut *ptr };
+    let mut blob = unsafe { std::slice::from_raw_parts(data, len) };
+    match checkpoint::read_registry(&mut blob, &mut registry.profiles) {
+        Ok(count) => count as c_longlong,
+        Err(_) => -1,
+    }
+}
+
+/// Streaming variant of `via_registry_checkpoint_all`: hands the blob to
+/// `write` in chunks so the host never holds it in one allocation
+///
+/// Returns the number of profiles written, or -1 on error or abort.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_checkpoint_stream(
+    ptr: *const AnomalyRegistry,
+    write: Option<ViaWriteCallback>,
+    ctx: *mut c_void,
+) -> c_longlong {
+    let (Some(write), false) = (write, ptr.is_null()) else {
+        return -1;
+    };
+
+    let registry = unsafe { &*ptr };
+    let mut sink = std::io::BufWriter::with_capacity(64 << 10, CallbackWriter { write, ctx });
+    match checkpoint::write_registry(&registry.profiles, &mut sink) {
+        Ok(count) => count as c_longlong,
+        Err(_) => -1,
+    }
+}
+
+/// Streaming variant of `via_registry_restore_all`: pulls the blob from
+/// `read` in chunks
+///
+/// Returns the number of profiles restored, or -1 on error.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_restore_stream(
+    ptr: *mut AnomalyRegistry,
+    read: Option<ViaReadCallback>,
+    ctx: *mut c_void,
+) -> c_longlong {
+    let (Some(read), false) = (read, ptr.is_null()) else {
+        return -1;
+    };
+
+    let registry = unsafe { &mut *ptr };
+    let mut source = std::io::BufReader::with_capacity(64 << 10, CallbackReader { read, ctx });
+    match checkpoint::read_registry(&mut source, &mut registry.profiles) {
+        Ok(count) => count as c_longlong,
+        Err(_) => -1,
+    }
+}
+
+/// Hot-reload decision settings on every profile in the registry and on
+/// profiles it creates later (see `via_update_config` for `config_json`)
+///
+/// Returns the number of profiles updated, or the negative
+/// `via_update_config` code; on error no profile changes.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_update_config(
+    ptr: *mut AnomalyRegistry,
+    config_json: *const c_char,
+) -> c_longlong {
+    if ptr.is_null() {
+        return CONFIG_MALFORMED as c_longlong;
+    }
+
+    let registry = unsafe { &mut *ptr };
+    let Some(config) = patched_config(&registry.profile_config, config_json) else {
+        return CONFIG_MALFORMED as c_longlong;
+    };
+    // Checked up front as well, so an empty registry rejects what a profile would
+    let check = match registry.profile_config.structural_changes(&config) {
+        fields if fields.is_empty() => config.validate_decision(),
+        fields => Err(ConfigError::Structural(fields)),
+    };
+    match check.and_then(|()| registry.profiles.update_config(&config)) {
+        Ok(updated) => {
+            registry.profile_config = config;
+            updated as c_longlong
+        }
+        Err(e) => config_error_code(&e) as c_longlong,
+    }
+}
+
+/// Pin a golden baseline: every profile the registry creates from here on
+/// starts from it instead of warming up; profiles it already holds keep
+/// their state
+///
+/// A null `baseline_json` unpins. Returns 0 on success, -1 for a null
+/// registry or malformed JSON and -2 for a baseline that does not fit the
+/// registry's profile configuration; on error the pin is unchanged.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_pin_baseline(
+    ptr: *mut AnomalyRegistry,
+    baseline_json: *const c_char,
+) -> c_int {
+    if ptr.is_null() {
+        return -1;
+    }
+    let registry = unsafe { &mut *ptr };
+    if baseline_json.is_null() {
+        registry.golden_baseline = None;
+        return 0;
+    }
+    let Some(baseline) = parse_baseline(baseline_json) else {
+        return -1;
+    };
+    if AnomalyProfile::from_baseline(registry.profile_config.clone(), &baseline).is_err() {
+        return -2;
+    }
+    registry.golden_baseline = Some(baseline);
+    0
+}
+
+/// Called for each profile the registry evicts or expires with the host's
+/// context, the entity hash, the profile's event count and whether it was
+/// spilled (false: discarded)
+pub type ViaEvictionCallback =
+    extern "C" fn(ctx: *mut c_void, hash: c_ulonglong, event_count: c_ulonglong, spilled: bool);
+
+/// Host eviction callback with its context pointer
+#[derive(Clone, Copy)]
+struct EvictionSink {
+    callback: ViaEvictionCallback,
+    ctx: *mut c_void,
+}
+
+// The host owns `ctx` and promises the callback may run on any thread
+unsafe impl Send for EvictionSink {}
+
+impl EvictionSink {
+    fn notify(&self, hash: u64, profile: &AnomalyProfile, spilled: bool) {
+        (self.callback)(self.ctx, hash, profile.event_count(), spilled);
+    }
+}
+
+/// Spill evicted profiles into `dir` (created if missing) and reload them
+/// when their entity shows up again, instead of discarding them
+///
+/// Profiles spilled there by an earlier registry are picked up too. A null
+/// `dir` goes back to discarding. Returns 0 on success, -1 for a null
+/// registry or a path that is not UTF-8, and -2 if the directory cannot be
+/// created.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_enable_spill(
+    ptr: *mut AnomalyRegistry,
+    dir: *const c_char,
+) -> c_int {
+    if ptr.is_null() {
+        return -1;
+    }
+    let registry = unsafe { &mut *ptr };
+    if dir.is_null() {
+        registry.profiles.set_spill_store(None);
+        return 0;
+    }
+    let Ok(dir) = unsafe { CStr::from_ptr(dir) }.to_str() else {
+        return -1;
+    };
+    match DirectoryStore::open(dir) {
+        Ok(store) => {
+            registry.profiles.set_spill_store(Some(Box::new(store)));
+            0
+        }
+        Err(_) => -2,
+    }
+}
+
+/// Call `callback` for every profile the registry evicts or expires from
+/// here on; a null `callback` removes it
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_set_eviction_callback(
+    ptr: *mut AnomalyRegistry,
+    callback: Option<ViaEvictionCallback>,
+    ctx: *mut c_void,
+) {
+    if ptr.is_null() {
+        return;
+    }
+    let callback = callback.map(|callback| {
+        let sink = EvictionSink { callback, ctx };
+        Box::new(move |hash, profile: &AnomalyProfile, spilled| sink.notify(hash, profile, spilled))
+            as registry::EvictionCallback<AnomalyProfile>
+    });
+    unsafe { &mut *ptr }
+        .profiles
+        .set_eviction_callback(callback);
+}
+
+/// Set the registry's retention policies; 0 (or a negative
+/// `pinned_priority`) turns each off
+///
+/// - `idle_ttl_ms`: profiles not accessed for this long are dropped
+/// - `max_entities`: new entities beyond this many tracked profiles are
+///   rejected (`via_registry_process_event` returns null)
+/// - `pinned_priority`: profiles with at least this priority (see
+///   `via_registry_set_priority`) are never evicted or expired
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_set_policy(
+    ptr: *mut AnomalyRegistry,
+    idle_ttl_ms: c_ulonglong,
+    max_entities: c_ulonglong,
+    pinned_priority: c_int,
+) -> bool {
+    if ptr.is_null() {
+        return false;
+    }
+    let profiles = &mut unsafe { &mut *ptr }.profiles;
+    let config = RegistryConfig {
+        idle_ttl: (idle_ttl_ms > 0).then(|| std::time::Duration::from_millis(idle_ttl_ms)),
+        max_entities: (max_entities > 0).then_some(max_entities as usize),
+        pinned_priority: u8::try_from(pinned_priority).ok(),
+        ..profiles.config().clone()
+    };
+    profiles.set_config(config);
+    true
+}
+
+/// Set the priority hint of the entity's profile (higher is evicted later;
+/// at the pinned priority never); false if the registry holds no profile for
+/// it
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_set_priority(
+    ptr: *mut AnomalyRegistry,
+    entity_hash: c_ulonglong,
+    priority: u8,
+) -> bool {
+    if ptr.is_null() {
+        return false;
+    }
+    let profiles = &mut unsafe { &mut *ptr }.profiles;
+    if profiles.get(entity_hash).is_none() {
+        return false;
+    }
+    profiles.set_priority(entity_hash, priority);
+    true
+}
+
+/// Drop every profile idle past the TTL now, instead of on its next access;
+/// returns how many were dropped
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_expire_idle(ptr: *mut AnomalyRegistry) -> c_ulonglong {
+    if ptr.is_null() {
+        return 0;
+    }
+    unsafe { &mut *ptr }.profiles.expire_idle() as c_ulonglong
+}
+
+/// Registry counters (creations, evictions, spills, expirations, rejections)
+/// as JSON (must free with via_free_string)
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_stats_json(ptr: *const AnomalyRegistry) -> *mut c_char {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    let profiles = &unsafe { &*ptr }.profiles;
+    let mut stats = profiles.stats().clone();
+    stats.total_profiles = profiles.len();
+    match serde_json::to_string(&stats) {
+        Ok(json) => match CString::new(json) {
+            Ok(c_str) => c_str.into_raw(),
+            Err(_) => std::ptr::null_mut(),
+        },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Open incidents as a JSON array, oldest first (must free with
+/// via_free_string)
+///
+/// Incidents group the registry's anomalous signals by entity and primary
+/// detector; see `incidents::IncidentTracker`.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_open_incidents(ptr: *const AnomalyRegistry) -> *mut c_char {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let registry = unsafe { &*ptr };
+    match serde_json::to_string(&registry.incidents.open_incidents()) {
+        Ok(json) => match CString::new(json) {
+            Ok(c_str) => c_str.into_raw(),
+            Err(_) => std::ptr::null_mut(),
+        },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Retained closed incidents with an id above `after_id` as a JSON array, in
+/// the order they closed (must free with via_free_string)
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_closed_incidents(
+    ptr: *const AnomalyRegistry,
+    after_id: c_ulonglong,
+) -> *mut c_char {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let registry = unsafe { &*ptr };
+    let closed: Vec<&Incident> = registry
+        .incidents
+        .closed_incidents()
+        .filter(|incident| incident.id > after_id)
+        .collect();
+    match serde_json::to_string(&closed) {
+        Ok(json) => match CString::new(json) {
+            Ok(c_str) => c_str.into_raw(),
+            Err(_) => std::ptr::null_mut(),
+        },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Set how long (ms) an entity may go without anomalies before its incident
+/// closes
+#[unsafe(no_mangle)]
+pub extern "C" fn via_registry_set_incident_gap(
+    ptr: *mut AnomalyRegistry,
+    merge_gap_ms: c_ulonglong,
+) -> bool {
+    if ptr.is_null() {
+        return false;
+    }
+    unsafe { &mut *ptr }
+        .incidents
+        .set_merge_gap_ms(merge_gap_ms);
+    true
+}
+
+struct CallbackWriter {
+    write: ViaWriteCallback,
+    ctx: *mut c_void,
+}
+
+impl std::io::Write for CallbackWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if (self.write)(self.ctx, buf.as_ptr(), buf.len()) {
+            Ok(buf.len())
+        } else {
+            Err(std::io::Error::other("checkpoint sink aborted"))
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+struct CallbackReader {
+    read: ViaReadCallback,
+    ctx: *mut c_void,
+}
+
+impl std::io::Read for CallbackReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = (self.read)(self.ctx, buf.as_mut_ptr(), buf.len());
+        if n < 0 || n as usize > buf.len() {
+            return Err(std::io::Error::other("checkpoint source failed"));
+        }
+        Ok(n as usize)
+    }
+}
+
+// ============================================================================
+// TRACING
+// ============================================================================
+
+/// Install a process-wide tracing subscriber (see `telemetry`)
+///
+/// `filter` holds comma-separated `target=level` directives such as
+/// `via_core=debug` (null or empty for `telemetry::DEFAULT_FILTER`).
+/// `callback` receives each formatted line with `ctx` and may be called from
+/// any thread; null writes to stderr. Spans and detection events need a build
+/// with feature `instrument`. Returns 0 on success, -1 for an invalid filter
+/// and -2 when a subscriber is already installed.
+#[unsafe(no_mangle)]
+pub extern "C" fn via_tracing_init(
+    filter: *const c_char,
+    callback: Option<telemetry::ViaLogCallback>,
+    ctx: *mut c_void,
+) -> c_int {
+    let filter = if filter.is_null() {
+        ""
+    } else {
+        match unsafe { CStr::from_ptr(filter) }.to_str() {
+            Ok(filter) => filter,
+            Err(_) => return -1,
+        }
+    };
+    let sink = match callback {
+        Some(callback) => telemetry::LogSink::Callback(callback, ctx),
+        None => telemetry::LogSink::Stderr,
+    };
+    match telemetry::install(filter, sink) {
+        Ok(()) => 0,
+        Err(telemetry::TelemetryError::InvalidFilter(_)) => -1,
+        Err(telemetry::TelemetryError::AlreadyInstalled) => -2,
+    }
+}
+
+// ============================================================================
+// UTILITY FUNCTIONS
+// ============================================================================
+
+/// Hash a string (for pre-hashing on the Bun side)
+#[unsafe(no_mangle)]
+pub extern "C" fn via_hash_string(s: *const c_char) -> c_ulonglong {
+    if s.is_null() {
+        return 0;
+    }
+
+    let c_str = unsafe { CStr::from_ptr(s) };
+    match c_str.to_str() {
+        Ok(str_slice) => xxhash_rust::xxh3::xxh3_64(str_slice.as_bytes()),
+        Err(_) => 0,
+    }
+}
+
+/// Get detector name by index
+#[unsafe(no_mangle)]
+pub extern "C" fn via_detector_name(idx: u8) -> *const c_char {
+    if idx >= NUM_DETECTORS as u8 {
         return std::ptr::null();
     }
 
-    NAMES[idx as usize].as_ptr() as *const c_char
+    signal::DETECTOR_NAMES[idx as usize].as_ptr() as *const c_char
 }
 
 /// Get the number of detectors
@@ -483,10 +1698,678 @@ mod tests {
         free_profile(profile);
     }
 
+    #[test]
+    fn test_ffi_signal_explanation_and_normal_range() {
+        let profile = via_create_profile();
+        for i in 0..300u64 {
+            via_free_signal(via_process_event(profile, i * 50_000_000, 7, 100.0));
+        }
+        let (mut low, mut high) = (0.0, 0.0);
+
+        let normal = via_process_event(profile, 300 * 50_000_000, 7, 100.0);
+        assert!(via_signal_explanation_json(normal).is_null());
+        assert!(!via_signal_normal_range(normal, &mut low, &mut high));
+        via_free_signal(normal);
+
+        let spike = via_process_event(profile, 301 * 50_000_000, 7, 10_000.0);
+        assert!(via_signal_is_anomaly(spike));
+        let json = via_signal_explanation_json(spike);
+        let parsed: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(json) }.to_str().unwrap()).unwrap();
+        assert!(!parsed["fired"].as_array().unwrap().is_empty());
+        via_free_string(json);
+        assert!(via_signal_normal_range(spike, &mut low, &mut high));
+        assert!(low <= high && high < 10_000.0);
+        via_free_signal(spike);
+
+        assert!(via_signal_explanation_json(std::ptr::null()).is_null());
+        free_profile(profile);
+    }
+
+    #[test]
+    fn test_ffi_baseline_export_merge_and_pin() {
+        let profile = via_create_profile();
+        for i in 0..500u64 {
+            via_free_signal(via_process_event(profile, i * 50_000_000, 7, 100.0));
+        }
+        let exported = via_profile_export_baseline(profile);
+        let json = unsafe { CStr::from_ptr(exported) }.to_owned();
+        via_free_string(exported);
+        let baseline: ProfileBaseline = serde_json::from_str(json.to_str().unwrap()).unwrap();
+        assert_eq!(baseline.events, 500);
+
+        let golden = via_create_profile_from_baseline(json.as_ptr());
+        assert!(!golden.is_null());
+        let signal = via_process_event(golden, 600 * 50_000_000, 7, 100.0);
+        assert!(!unsafe { &*signal }.baseline.is_warmup);
+        via_free_signal(signal);
+
+        let merged = via_baseline_merge(json.as_ptr(), json.as_ptr());
+        let merged_baseline: ProfileBaseline =
+            serde_json::from_str(unsafe { CStr::from_ptr(merged) }.to_str().unwrap()).unwrap();
+        assert_eq!(merged_baseline.events, 1_000);
+        via_free_string(merged);
+
+        let mut narrow = baseline.clone();
+        narrow.histogram.bins.truncate(10);
+        let narrow = CString::new(serde_json::to_string(&narrow).unwrap()).unwrap();
+        assert!(via_create_profile_from_baseline(narrow.as_ptr()).is_null());
+        assert!(via_baseline_merge(json.as_ptr(), narrow.as_ptr()).is_null());
+
+        assert_eq!(via_profile_merge(golden, profile), 0);
+        assert_eq!(unsafe { &*golden }.event_count(), 1_001);
+        assert_eq!(via_profile_merge(golden, golden), -1);
+        assert_eq!(via_profile_merge(golden, std::ptr::null()), -1);
+
+        let registry = via_registry_create(0);
+        let garbage = CString::new("{").unwrap();
+        assert_eq!(via_registry_pin_baseline(registry, garbage.as_ptr()), -1);
+        assert_eq!(via_registry_pin_baseline(registry, narrow.as_ptr()), -2);
+        assert_eq!(via_registry_pin_baseline(registry, json.as_ptr()), 0);
+        let signal = via_registry_process_event(registry, 600 * 50_000_000, 9, 100.0);
+        assert!(!unsafe { &*signal }.baseline.is_warmup);
+        via_free_signal(signal);
+        assert_eq!(via_registry_pin_baseline(registry, std::ptr::null()), 0);
+        let signal = via_registry_process_event(registry, 600 * 50_000_000, 10, 100.0);
+        assert!(unsafe { &*signal }.baseline.is_warmup);
+        via_free_signal(signal);
+
+        via_registry_free(registry);
+        free_profile(golden);
+        free_profile(profile);
+    }
+
+    #[test]
+    fn test_ffi_process_vector() {
+        let profile = via_create_profile();
+        let features = [120.0, 4_096.0, 2.0];
+
+        let signal = via_process_vector(profile, 1_000_000, 7, features.as_ptr(), features.len());
+        assert!(!signal.is_null());
+        assert_eq!(unsafe { (*signal).raw_value }, 120.0);
+        via_free_signal(signal);
+
+        assert!(via_process_vector(profile, 2_000_000, 7, std::ptr::null(), 3).is_null());
+        let empty = via_process_vector(profile, 2_000_000, 7, std::ptr::null(), 0);
+        assert!(!empty.is_null());
+        via_free_signal(empty);
+
+        free_profile(profile);
+    }
+
+    #[test]
+    fn test_ffi_process_event_into() {
+        let boxed = via_create_profile();
+        let flat = via_create_profile();
+        let mut out = CAnomalySignalFlat::default();
+        for i in 0..300u64 {
+            let value = if i >= 290 {
+                5_000.0
+            } else {
+                100.0 + (i % 7) as f64
+            };
+            let ts = i * 100_000_000;
+            let signal = via_process_event(boxed, ts, i % 5, value);
+            assert!(via_process_event_into(flat, ts, i % 5, value, &mut out));
+
+            let expected = unsafe { &*signal };
+            assert_eq!(out.sequence, expected.sequence);
+            assert_eq!(out.raw_value, value);
+            assert_eq!(out.is_anomaly, expected.is_anomaly, "event {}", i);
+            assert_eq!(out.severity, expected.severity as u8);
+            assert_eq!(out.calibrated_probability, -1.0);
+            assert_eq!(
+                out.attribution.detectors_fired,
+                expected.attribution.detectors_fired
+            );
+            via_free_signal(signal);
+        }
+        assert!(out.is_anomaly);
+
+        assert!(!via_process_event_into(
+            flat,
+            0,
+            1,
+            1.0,
+            std::ptr::null_mut()
+        ));
+        assert!(!via_process_event_into(
+            std::ptr::null_mut(),
+            0,
+            1,
+            1.0,
+            &mut out
+        ));
+        assert_eq!(via_signal_flat_size(), std::mem::size_of_val(&out));
+
+        free_profile(flat);
+        free_profile(boxed);
+    }
+
+    #[test]
+    fn test_ffi_update_config() {
+        let profile = via_create_profile();
+        let update = |p: *mut AnomalyProfile, json: &str| {
+            let json = CString::new(json).unwrap();
+            via_update_config(p, json.as_ptr())
+        };
+
+        assert_eq!(update(profile, r#"{"confidence_threshold": 0.7}"#), 0);
+        assert_eq!(unsafe { &*profile }.config().confidence_threshold, 0.7);
+        assert_eq!(update(profile, r#"{"confidence_threshold": 2.0}"#), -2);
+        assert_eq!(update(profile, r#"{"hist_bins": 10}"#), -3);
+        assert_eq!(update(profile, r#"{"no_such_field": 1}"#), -1);
+        assert_eq!(update(profile, "[1, 2]"), -1);
+        assert_eq!(update(std::ptr::null_mut(), "{}"), -1);
+        assert_eq!(unsafe { &*profile }.config().confidence_threshold, 0.7);
+        free_profile(profile);
+
+        let registry = via_registry_create(0);
+        via_free_signal(via_registry_process_event(registry, 1_000_000, 1, 10.0));
+        let json = CString::new(r#"{"min_ensemble_score_for_anomaly": 0.3}"#).unwrap();
+        assert_eq!(via_registry_update_config(registry, json.as_ptr()), 1);
+        let json = CString::new(r#"{"period": 12}"#).unwrap();
+        assert_eq!(via_registry_update_config(registry, json.as_ptr()), -3);
+
+        // Profiles created after the reload start from the new settings
+        via_free_signal(via_registry_process_event(registry, 2_000_000, 2, 10.0));
+        let registry_ref = unsafe { &*registry };
+        assert!(
+            registry_ref
+                .profiles
+                .iter()
+                .all(|(_, p)| p.config().min_ensemble_score_for_anomaly == 0.3)
+        );
+        assert_eq!(registry_ref.profiles.len(), 2);
+        via_registry_free(registry);
+    }
+
+    #[test]
+    fn test_ffi_lifecycle_log() {
+        let profile = via_create_profile();
+        for i in 0..100u64 {
+            via_free_signal(via_process_event(profile, (i + 1) * 1_000_000, i, 100.0));
+        }
+        let reason = CString::new("tier2 unreachable").unwrap();
+        assert!(via_record_tier_change(profile, true, reason.as_ptr()));
+        assert!(via_record_tier_change(profile, true, std::ptr::null()));
+        assert!(via_record_tier_change(profile, false, std::ptr::null()));
+
+        let checkpoint = via_create_checkpoint(profile);
+        let restored = via_restore_from_checkpoint(checkpoint);
+        assert!(!restored.is_null());
+
+        let lifecycle = |p: *const AnomalyProfile| -> serde_json::Value {
+            let json = via_profile_lifecycle(p, 0);
+            let value = serde_json::from_str(unsafe { CStr::from_ptr(json) }.to_str().unwrap());
+            via_free_string(json);
+            value.unwrap()
+        };
+        let kinds = |log: &serde_json::Value| -> Vec<String> {
+            log["events"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|e| e["kind"].as_str().unwrap().to_string())
+                .filter(|k| k != "policy_applied")
+                .collect()
+        };
+
+        let original = lifecycle(profile);
+        assert_eq!(
+            kinds(&original),
+            vec![
+                "created",
+                "warmup_complete",
+                "tier_change",
+                "tier_change",
+                "checkpoint"
+            ]
+        );
+        let warmup = original["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|e| e["kind"] == "warmup_complete")
+            .unwrap();
+        assert_eq!(warmup["sequence"], 100);
+        assert_eq!(warmup["event_timestamp"], 100_000_000);
+        assert_eq!(kinds(&lifecycle(restored)), vec!["created", "restore"]);
+        assert!(via_profile_lifecycle(std::ptr::null(), 0).is_null());
+
+        via_free_string(checkpoint);
+        free_profile(restored);
+        free_profile(profile);
+    }
+
+    #[test]
+    fn test_ffi_filtered_emission() {
+        let profile = via_create_profile();
+        let byte_only = via_create_profile();
+        for p in [profile, byte_only] {
+            assert!(via_set_emission_floor(p, Severity::Medium as u8, 0.0));
+        }
+        assert!(!via_set_emission_floor(profile, 9, 0.0));
+
+        let (mut emitted, mut flagged) = (0, 0);
+        for i in 0..600u64 {
+            let value = if i >= 580 {
+                5_000.0
+            } else {
+                100.0 + (i % 7) as f64
+            };
+            let (ts, hash) = (i * 100_000_000, i % 5);
+            let mut signal: *mut AnomalySignal = std::ptr::dangling_mut();
+            let code = via_process_event_filtered(profile, ts, hash, value, -1, false, &mut signal);
+            if code != 0 {
+                emitted += 1;
+                assert!(via_signal_is_anomaly(signal));
+                assert_eq!(code, 1 + via_signal_severity(signal));
+                assert!(via_signal_severity(signal) >= Severity::Medium as u8);
+                via_free_signal(signal);
+            } else {
+                assert!(signal.is_null());
+            }
+
+            // Without an out pointer only the byte crosses the boundary
+            let code = via_process_event_filtered(
+                byte_only,
+                ts,
+                hash,
+                value,
+                -1,
+                false,
+                std::ptr::null_mut(),
+            );
+            flagged += (code != 0) as usize;
+        }
+        assert!(emitted > 0 && emitted < 100, "emitted {}", emitted);
+        assert!(flagged > 0 && flagged < 100, "flagged {}", flagged);
+
+        free_profile(byte_only);
+        free_profile(profile);
+    }
+
+    #[test]
+    fn test_ffi_process_batch() {
+        use std::ptr::{null, null_mut};
+        let single = via_create_profile();
+        let batched = via_create_profile();
+        let (mut timestamps, mut hashes, mut values) = (Vec::new(), Vec::new(), Vec::new());
+        let mut expected = Vec::new();
+        for i in 0..600u64 {
+            let value = if i >= 580 {
+                5_000.0
+            } else {
+                100.0 + (i % 7) as f64
+            };
+            let (ts, hash) = (i * 100_000_000, i % 5);
+            expected.push(via_process_event_filtered(
+                single,
+                ts,
+                hash,
+                value,
+                -1,
+                false,
+                null_mut(),
+            ));
+            timestamps.push(ts);
+            hashes.push(hash);
+            values.push(value);
+        }
+
+        let mut codes = vec![u8::MAX; 600];
+        let emitted = via_process_batch(
+            batched,
+            timestamps.as_ptr(),
+            hashes.as_ptr(),
+            values.as_ptr(),
+            600,
+            codes.as_mut_ptr(),
+        );
+        assert_eq!(codes, expected);
+        assert!(emitted > 0);
+        assert_eq!(
+            emitted,
+            codes.iter().filter(|&&c| c != 0).count() as c_longlong
+        );
+
+        assert_eq!(
+            via_process_batch(batched, null(), null(), null(), 0, null_mut()),
+            0
+        );
+        assert_eq!(
+            via_process_batch(batched, null(), null(), null(), 3, null_mut()),
+            -1
+        );
+        assert_eq!(
+            via_process_batch(null_mut(), null(), null(), null(), 0, null_mut()),
+            -1
+        );
+
+        free_profile(batched);
+        free_profile(single);
+    }
+
+    #[test]
+    fn test_ffi_registry_incidents() {
+        let incidents = |ptr: *mut c_char| {
+            let json = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_owned();
+            via_free_string(ptr);
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()
+        };
+
+        let registry = via_registry_create(0);
+        let mut anomalies = 0;
+        for i in 0..600u64 {
+            let value = if i >= 580 {
+                5_000.0
+            } else {
+                100.0 + (i % 7) as f64
+            };
+            let signal = via_registry_process_event(registry, i * 100_000_000, 7, value);
+            anomalies += via_signal_is_anomaly(signal) as u64;
+            via_free_signal(signal);
+        }
+
+        // Alerts collapse into a few incidents that account for all of them
+        let open = incidents(via_registry_open_incidents(registry));
+        let open = open.as_array().unwrap();
+        assert!(!open.is_empty() && (open.len() as u64) < anomalies / 4);
+        let grouped: u64 = open
+            .iter()
+            .map(|i| i["signal_count"].as_u64().unwrap())
+            .sum();
+        assert_eq!(grouped, anomalies);
+        assert!(open.iter().all(|i| i["entity_hash"] == 7));
+        let ids: Vec<u64> = open.iter().map(|i| i["id"].as_u64().unwrap()).collect();
+
+        // Any entity's later event advances stream time past the gap
+        assert!(via_registry_set_incident_gap(registry, 1_000));
+        via_free_signal(via_registry_process_event(
+            registry,
+            70_000_000_000,
+            8,
+            100.0,
+        ));
+        assert!(
+            incidents(via_registry_open_incidents(registry))
+                .as_array()
+                .unwrap()
+                .is_empty()
+        );
+        let closed = incidents(via_registry_closed_incidents(registry, 0));
+        let closed = closed.as_array().unwrap();
+        assert_eq!(closed.len(), ids.len());
+        assert!(closed.iter().all(|i| i["is_open"] == false));
+        let last = *ids.iter().max().unwrap();
+        let after = incidents(via_registry_closed_incidents(registry, last));
+        assert!(after.as_array().unwrap().is_empty());
+
+        assert!(via_registry_open_incidents(std::ptr::null()).is_null());
+        via_registry_free(registry);
+    }
+
+    #[test]
+    fn test_ffi_feedback_json_trains_calibration() {
+        let profile = via_create_profile();
+        let curve = || {
+            let ptr = via_calibration_curve(profile);
+            let json = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_owned();
+            via_free_string(ptr);
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()
+        };
+        assert_eq!(curve()["method"], "uncalibrated");
+
+        for i in 0..40u64 {
+            let event = FeedbackEvent::false_positive(
+                1,
+                i,
+                [0.0; NUM_DETECTORS],
+                FeedbackSource::HumanReview,
+                1.0,
+            )
+            .with_ensemble_score(0.5);
+            let event = FeedbackEvent {
+                was_true_positive: i % 4 == 0,
+                ..event
+            };
+            let json = CString::new(serde_json::to_string(&event).unwrap()).unwrap();
+            assert!(via_send_feedback_json(profile, json.as_ptr()));
+        }
+        let malformed = CString::new("{").unwrap();
+        assert!(!via_send_feedback_json(profile, malformed.as_ptr()));
+
+        let curve = curve();
+        assert_eq!(curve["method"], "platt");
+        // Older labels have started to fade
+        let weight = curve["label_weight"].as_f64().unwrap();
+        assert!(weight > 35.0 && weight < 40.0, "{}", weight);
+
+        let signal = via_process_event(profile, 1_000_000, 1, 100.0);
+        let probability = via_signal_calibrated_probability(signal);
+        assert!(probability > 0.0 && probability < 1.0);
+        via_free_signal(signal);
+        assert_eq!(via_signal_calibrated_probability(std::ptr::null()), -1.0);
+        free_profile(profile);
+    }
+
+    #[test]
+    fn test_ffi_checkpoint_bytes() {
+        let profile = via_create_profile();
+        for i in 0..100u64 {
+            via_free_signal(via_process_event(profile, (i + 1) * 1_000_000, i, 100.0));
+        }
+
+        let mut len = 0usize;
+        let bytes = via_create_checkpoint_bytes(profile, &mut len);
+        assert!(!bytes.is_null());
+
+        // Same payload as the base64 path, minus the encoding
+        let b64 = via_create_checkpoint(profile);
+        let decoded = base64_decode(unsafe { CStr::from_ptr(b64) }.to_str().unwrap()).unwrap();
+        via_free_string(b64);
+        assert_eq!(unsafe { std::slice::from_raw_parts(bytes, len) }, decoded);
+
+        let restored = via_restore_from_checkpoint_bytes(bytes, len);
+        assert!(!restored.is_null());
+        assert_eq!(unsafe { &*restored }.event_count(), 100);
+        assert!(via_restore_from_checkpoint_bytes(bytes, len / 2).is_null());
+        assert!(via_restore_from_checkpoint_bytes(bytes, usize::MAX).is_null());
+        assert!(via_create_checkpoint_bytes(profile, std::ptr::null_mut()).is_null());
+
+        via_free_bytes(bytes, len);
+        free_profile(restored);
+        free_profile(profile);
+    }
+
+    #[test]
+    fn test_ffi_profile_view() {
+        let profile = via_create_profile();
+        let view = via_profile_view(profile);
+        assert!(!view.is_null());
+
+        let mut len = 0usize;
+        assert!(via_view_checkpoint(view, &mut len).is_null());
+        via_view_request_checkpoint(view);
+        for i in 0..128u64 {
+            via_free_signal(via_process_event(profile, (i + 1) * 1_000_000, i, 100.0));
+        }
+
+        let json = via_view_stats_json(view);
+        let stats: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(json) }.to_str().unwrap()).unwrap();
+        via_free_string(json);
+        assert_eq!(stats["event_count"], 128);
+        assert_eq!(stats["snapshot"]["event_count"], 128);
+        assert_eq!(
+            stats["snapshot"]["detector_stats"]
+                .as_array()
+                .unwrap()
+                .len(),
+            13
+        );
+
+        let bytes = via_view_checkpoint(view, &mut len);
+        assert!(!bytes.is_null());
+        let restored = via_restore_from_checkpoint_bytes(bytes, len);
+        assert_eq!(unsafe { &*restored }.event_count(), 1);
+        via_free_bytes(bytes, len);
+        free_profile(restored);
+
+        // The view outlives its profile
+        free_profile(profile);
+        let json = via_view_stats_json(view);
+        assert!(!json.is_null());
+        via_free_string(json);
+        via_view_free(view);
+        assert!(via_profile_view(std::ptr::null()).is_null());
+    }
+
+    #[test]
+    fn test_ffi_registry_spill() {
+        extern "C" fn record(ctx: *mut c_void, hash: u64, events: u64, spilled: bool) {
+            unsafe { &mut *(ctx as *mut Vec<(u64, u64, bool)>) }.push((hash, events, spilled));
+        }
+
+        let dir = std::env::temp_dir().join(format!("via-core-ffi-spill-{}", std::process::id()));
+        let dir_c = CString::new(dir.to_str().unwrap()).unwrap();
+        let registry = via_registry_create(2);
+        assert_eq!(via_registry_enable_spill(registry, dir_c.as_ptr()), 0);
+        let mut evicted: Vec<(u64, u64, bool)> = Vec::new();
+        via_registry_set_eviction_callback(
+            registry,
+            Some(record),
+            &mut evicted as *mut _ as *mut c_void,
+        );
+
+        // Entity 1 is the least recently used when entity 3 arrives
+        for entity in 1..=3u64 {
+            for i in 0..300u64 {
+                let ts = (i + 1) * 50_000_000;
+                via_free_signal(via_registry_process_event(registry, ts, entity, 100.0));
+            }
+        }
+        assert_eq!(evicted, vec![(1, 300, true)]);
+        assert_eq!(via_registry_len(registry), 2);
+
+        // It comes back with its learned state instead of warming up again
+        let signal = via_registry_process_event(registry, 301 * 50_000_000, 1, 100.0);
+        assert!(!unsafe { &*signal }.baseline.is_warmup);
+        via_free_signal(signal);
+        assert_eq!(evicted.len(), 2);
+
+        via_registry_set_eviction_callback(registry, None, std::ptr::null_mut());
+        assert_eq!(via_registry_enable_spill(registry, std::ptr::null()), 0);
+        via_registry_free(registry);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_ffi_registry_policy() {
+        let registry = via_registry_create(0);
+        assert!(via_registry_set_policy(registry, 0, 1, 5));
+        let signal = via_registry_process_event(registry, 1_000_000, 1, 100.0);
+        assert!(!signal.is_null());
+        via_free_signal(signal);
+        assert!(via_registry_process_event(registry, 2_000_000, 2, 100.0).is_null());
+        assert!(via_registry_set_priority(registry, 1, 5));
+        assert!(!via_registry_set_priority(registry, 2, 5));
+        assert_eq!(via_registry_expire_idle(registry), 0);
+
+        let json = via_registry_stats_json(registry);
+        let stats: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(json) }.to_str().unwrap()).unwrap();
+        via_free_string(json);
+        assert_eq!(stats["total_rejections"], 1);
+        assert_eq!(stats["total_profiles"], 1);
+
+        // Lifting the cap admits the entity
+        assert!(via_registry_set_policy(registry, 60_000, 0, -1));
+        let signal = via_registry_process_event(registry, 2_000_000, 2, 100.0);
+        assert!(!signal.is_null());
+        via_free_signal(signal);
+        via_registry_free(registry);
+    }
+
+    #[test]
+    fn test_ffi_registry_checkpoint() {
+        extern "C" fn collect(ctx: *mut c_void, data: *const u8, len: usize) -> bool {
+            let out = unsafe { &mut *(ctx as *mut Vec<u8>) };
+            out.extend_from_slice(unsafe { std::slice::from_raw_parts(data, len) });
+            true
+        }
+        extern "C" fn drain(ctx: *mut c_void, buf: *mut u8, cap: usize) -> isize {
+            let input = unsafe { &mut *(ctx as *mut &[u8]) };
+            let n = input.len().min(cap).min(7);
+            unsafe { std::ptr::copy_nonoverlapping(input.as_ptr(), buf, n) };
+            *input = &input[n..];
+            n as isize
+        }
+
+        let registry = via_registry_create(0);
+        for i in 0..200u64 {
+            via_free_signal(via_registry_process_event(
+                registry,
+                (i + 1) * 1_000_000,
+                i % 25,
+                100.0,
+            ));
+        }
+        assert_eq!(via_registry_len(registry), 25);
+
+        let mut len = 0usize;
+        let blob = via_registry_checkpoint_all(registry, &mut len);
+        assert!(!blob.is_null());
+
+        let mut streamed = Vec::new();
+        let written = via_registry_checkpoint_stream(
+            registry,
+            Some(collect),
+            &mut streamed as *mut Vec<u8> as *mut c_void,
+        );
+        assert_eq!(written, 25);
+        assert_eq!(streamed.len(), len);
+
+        let restored = via_registry_create(0);
+        assert_eq!(via_registry_restore_all(restored, blob, len), 25);
+        assert_eq!(via_registry_restore_all(restored, blob, len - 1), -1);
+        via_free_bytes(blob, len);
+
+        let from_stream = via_registry_create(0);
+        let mut input = streamed.as_slice();
+        let read = via_registry_restore_stream(
+            from_stream,
+            Some(drain),
+            &mut input as *mut &[u8] as *mut c_void,
+        );
+        assert_eq!(read, 25);
+        assert_eq!(via_registry_len(from_stream), 25);
+        assert_eq!(
+            unsafe { &mut *from_stream }
+                .profiles
+                .get(3)
+                .unwrap()
+                .event_count(),
+            8
+        );
+
+        assert_eq!(
+            via_registry_checkpoint_stream(registry, None, std::ptr::null_mut()),
+            -1
+        );
+        for ptr in [registry, restored, from_stream] {
+            via_registry_free(ptr);
+        }
+    }
+
     #[test]
     fn test_detector_names() {
         assert!(!via_detector_name(0).is_null());
         assert!(via_detector_name(100).is_null());
-        assert_eq!(via_num_detectors(), 10);
+        assert!(!via_detector_name(12).is_null());
+        let name = unsafe { CStr::from_ptr(via_detector_name(6)) };
+        assert_eq!(name.to_str(), Ok(DetectorId::RRCF.name()));
+        assert_eq!(via_num_detectors(), 13);
     }
 }
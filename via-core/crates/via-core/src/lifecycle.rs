@@ -0,0 +1,192 @@
+//! Profile Lifecycle Log
+//!
+//! A bounded, timestamped record of the moments a profile's (or registry's)
+//! detection behavior can change: creation, end of warmup, drift relearns,
+//! checkpoints and restores, policy installs and tier degradation. Operators
+//! read it back (e.g. via `via_profile_lifecycle`) to explain why detection
+//! changed at a given time.
+//!
+//! When full, the oldest events are dropped and counted in `dropped`.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Events kept per log
+pub const DEFAULT_LIFECYCLE_CAPACITY: usize = 128;
+
+/// What happened
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LifecycleEventKind {
+    Created,
+    /// `warmup_events` reached; detectors and thresholds go live
+    WarmupComplete,
+    /// Concept drift detected; the drift detector reset its reference window
+    DriftRelearn {
+        severity: f64,
+    },
+    Checkpoint {
+        bytes: usize,
+    },
+    Restore {
+        total_samples: u64,
+    },
+    /// A new Tier-2 policy snapshot took effect for this profile
+    PolicyApplied {
+        version: String,
+    },
+    /// Host-reported switch into or out of a degraded tier
+    TierChange {
+        degraded: bool,
+        reason: String,
+    },
+    Reset,
+    /// Decision settings hot-reloaded; lists the fields that changed
+    ConfigReloaded {
+        fields: Vec<String>,
+    },
+    /// Registry: a profile was inserted
+    ProfileCreated {
+        hash: u64,
+    },
+    /// Registry: a profile was evicted to stay within capacity, to the
+    /// spill tier or discarded
+    ProfileEvicted {
+        hash: u64,
+        spilled: bool,
+    },
+    /// Registry: a spilled profile was loaded back on access
+    ProfileReloaded {
+        hash: u64,
+    },
+    /// Registry: a profile was dropped after its idle TTL
+    ProfileExpired {
+        hash: u64,
+    },
+    /// Registry: a profile was removed explicitly
+    ProfileRemoved {
+        hash: u64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LifecycleEvent {
+    /// Wall-clock time (ms since the Unix epoch)
+    pub unix_ms: u64,
+    /// Events processed by the profile at the time (0 for registry events)
+    pub sequence: u64,
+    /// Timestamp of the last processed event (ns, 0 before the first)
+    pub event_timestamp: u64,
+    #[serde(flatten)]
+    pub kind: LifecycleEventKind,
+}
+
+/// Bounded lifecycle event log
+#[derive(Debug, Clone, Serialize)]
+pub struct LifecycleLog {
+    capacity: usize,
+    /// Events discarded to stay within capacity
+    dropped: u64,
+    events: VecDeque<LifecycleEvent>,
+}
+
+impl LifecycleLog {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            dropped: 0,
+            events: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Append an event stamped with the current wall-clock time
+    pub fn record(&mut self, sequence: u64, event_timestamp: u64, kind: LifecycleEventKind) {
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+        self.events.push_back(LifecycleEvent {
+            unix_ms: now_unix_ms(),
+            sequence,
+            event_timestamp,
+            kind,
+        });
+    }
+
+    /// Events in the order they happened
+    pub fn events(&self) -> impl Iterator<Item = &LifecycleEvent> {
+        self.events.iter()
+    }
+
+    /// Copy holding only events at or after `unix_ms`
+    pub fn since(&self, unix_ms: u64) -> Self {
+        Self {
+            capacity: self.capacity,
+            dropped: self.dropped,
+            events: self
+                .events
+                .iter()
+                .filter(|e| e.unix_ms >= unix_ms)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl Default for LifecycleLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_LIFECYCLE_CAPACITY)
+    }
+}
+
+fn now_unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_log_drops_oldest() {
+        let mut log = LifecycleLog::new(2);
+        log.record(0, 0, LifecycleEventKind::Created);
+        log.record(100, 5, LifecycleEventKind::WarmupComplete);
+        log.record(150, 7, LifecycleEventKind::Reset);
+
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.dropped(), 1);
+        let kinds: Vec<_> = log.events().map(|e| e.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                LifecycleEventKind::WarmupComplete,
+                LifecycleEventKind::Reset
+            ]
+        );
+
+        assert!(log.since(u64::MAX).is_empty());
+        assert_eq!(log.since(0).len(), 2);
+
+        let json = serde_json::to_value(&log).unwrap();
+        assert_eq!(json["events"][0]["kind"], "warmup_complete");
+        assert_eq!(json["events"][0]["sequence"], 100);
+    }
+}
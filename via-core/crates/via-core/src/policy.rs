@@ -12,6 +12,7 @@ use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 const DEFAULT_HISTORY_LIMIT: usize = 16;
@@ -268,6 +269,8 @@ pub struct PolicyRuntime {
     indexed: RwLock<Option<IndexedPolicySnapshot>>,
     history: RwLock<Vec<PolicySnapshot>>,
     history_limit: usize,
+    /// Incremented on every install, so profiles can notice policy changes
+    generation: AtomicU64,
 }
 
 impl PolicyRuntime {
@@ -277,6 +280,7 @@ impl PolicyRuntime {
             indexed: RwLock::new(None),
             history: RwLock::new(Vec::new()),
             history_limit: DEFAULT_HISTORY_LIMIT,
+            generation: AtomicU64::new(0),
         }
     }
 
@@ -301,6 +305,12 @@ impl PolicyRuntime {
         let indexed = IndexedPolicySnapshot::from_snapshot(&snapshot);
         *self.indexed.write().unwrap() = Some(indexed);
         *active = snapshot;
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+
+    /// Number of snapshots installed so far (including rollbacks)
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
     }
 
     pub fn rollback_to_version(&self, version: &str) -> bool {
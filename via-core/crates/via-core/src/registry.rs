@@ -2,9 +2,23 @@
 //!
 //! This module manages the collection of AnomalyProfile instances with
 //! configurable memory bounds. Uses LRU eviction to prevent unbounded growth.
+//! With a spill tier (see [`crate::spill`]) evicted profiles are checkpointed
+//! instead of discarded and reloaded on their next access.
+//!
+//! Beyond LRU, [`RegistryConfig`] sets an idle TTL, a hard cap on tracked
+//! entities (new ones are rejected rather than evicting others) and a
+//! priority at which profiles are pinned: never evicted or expired.
+//!
+//! [`ShardedRegistry`] splits one registry into independently locked shards
+//! by hash, for detection shared across threads.
 
+use crate::checkpoint::{CheckpointError, Checkpointable};
+use crate::lifecycle::{LifecycleEventKind, LifecycleLog};
+use crate::spill::{SpillStore, decode_entry, encode_entry};
+use serde::Serialize;
 use std::collections::HashMap;
-use std::time::Instant;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
 /// Configuration for the profile registry
 #[derive(Debug, Clone)]
@@ -15,6 +29,16 @@ pub struct RegistryConfig {
     pub min_events_for_eviction: u64,
     /// Whether to track access order for LRU
     pub enable_lru: bool,
+    /// Profiles not accessed for this long are dropped, on their next
+    /// access or by [`ProfileRegistry::expire_idle`]
+    pub idle_ttl: Option<Duration>,
+    /// Hard cap on tracked entities (in memory plus spilled); beyond it
+    /// [`ProfileRegistry::try_get_or_create`] rejects new entities instead
+    /// of evicting others
+    pub max_entities: Option<usize>,
+    /// Profiles with at least this priority are never evicted or expired;
+    /// with only those left the registry grows past `max_profiles`
+    pub pinned_priority: Option<u8>,
 }
 
 impl Default for RegistryConfig {
@@ -23,6 +47,9 @@ impl Default for RegistryConfig {
             max_profiles: 100_000,
             min_events_for_eviction: 10,
             enable_lru: true,
+            idle_ttl: None,
+            max_entities: None,
+            pinned_priority: None,
         }
     }
 }
@@ -96,13 +123,36 @@ impl<P> ProfileEntry<P> {
 }
 
 /// Statistics about the registry
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct RegistryStats {
     pub total_profiles: usize,
     pub total_evictions: u64,
     pub total_creations: u64,
     pub total_accesses: u64,
     pub capacity: usize,
+    /// Evicted profiles written to the spill tier
+    pub total_spills: u64,
+    /// Spilled profiles brought back on access
+    pub total_reloads: u64,
+    /// Spill writes or reloads that failed; those profiles are lost
+    pub spill_failures: u64,
+    /// Profiles this registry spilled that are still in the spill tier
+    pub spilled_profiles: usize,
+    /// Profiles dropped after `idle_ttl` without access
+    pub total_expirations: u64,
+    /// New entities turned away at `max_entities`
+    pub total_rejections: u64,
+}
+
+/// Called with each evicted or expired profile's hash, the profile, and
+/// whether it was spilled (false: discarded)
+pub type EvictionCallback<P> = Box<dyn FnMut(u64, &P, bool) + Send>;
+
+/// Spill store plus the codec for the registry's profile type
+struct SpillTier<P> {
+    store: Box<dyn SpillStore>,
+    encode: fn(&ProfileEntry<P>) -> Vec<u8>,
+    decode: fn(&[u8]) -> Result<ProfileEntry<P>, CheckpointError>,
 }
 
 /// Memory-bounded profile registry with LRU eviction
@@ -117,6 +167,11 @@ pub struct ProfileRegistry<P> {
     /// Using a simple Vec as a circular buffer
     access_order: Vec<u64>,
     access_head: usize,
+    /// Profile creations, evictions and removals
+    lifecycle: LifecycleLog,
+    /// Where evicted profiles go instead of being discarded
+    spill: Option<SpillTier<P>>,
+    on_evict: Option<EvictionCallback<P>>,
 }
 
 impl<P> ProfileRegistry<P> {
@@ -137,9 +192,40 @@ impl<P> ProfileRegistry<P> {
             config,
             access_order: Vec::with_capacity(capacity),
             access_head: 0,
+            lifecycle: LifecycleLog::default(),
+            spill: None,
+            on_evict: None,
         }
     }
 
+    /// Call `callback` for every profile evicted from here on (`None`
+    /// removes it)
+    pub fn set_eviction_callback(&mut self, callback: Option<EvictionCallback<P>>) {
+        self.on_evict = callback;
+    }
+
+    /// Whether evicted profiles are spilled instead of discarded
+    pub fn has_spill_tier(&self) -> bool {
+        self.spill.is_some()
+    }
+
+    pub fn config(&self) -> &RegistryConfig {
+        &self.config
+    }
+
+    /// Apply new policies, evicting down to a lowered `max_profiles`;
+    /// returns the evicted profiles
+    pub fn set_config(&mut self, config: RegistryConfig) -> Vec<(u64, P)> {
+        self.stats.capacity = config.max_profiles;
+        self.config = config;
+        self.evict_to_size(self.config.max_profiles)
+    }
+
+    /// Profiles tracked, in memory or spilled by this registry
+    pub fn entity_count(&self) -> usize {
+        self.profiles.len() + self.stats.spilled_profiles
+    }
+
     /// Get profile count
     pub fn len(&self) -> usize {
         self.profiles.len()
@@ -160,8 +246,11 @@ impl<P> ProfileRegistry<P> {
         self.profiles.len() >= self.config.max_profiles
     }
 
-    /// Get a profile (if exists), updating access time
+    /// Get a profile (if exists, reloading it if spilled), updating access
+    /// time
     pub fn get(&mut self, hash: u64) -> Option<&P> {
+        self.expire_if_idle(hash);
+        self.reload(hash);
         if let Some(entry) = self.profiles.get_mut(&hash) {
             entry.meta.touch();
             self.stats.total_accesses += 1;
@@ -171,8 +260,11 @@ impl<P> ProfileRegistry<P> {
         }
     }
 
-    /// Get mutable reference to profile (if exists), updating access time
+    /// Get mutable reference to profile (if exists, reloading it if
+    /// spilled), updating access time
     pub fn get_mut(&mut self, hash: u64) -> Option<&mut P> {
+        self.expire_if_idle(hash);
+        self.reload(hash);
         if let Some(entry) = self.profiles.get_mut(&hash) {
             entry.meta.touch();
             self.stats.total_accesses += 1;
@@ -182,11 +274,25 @@ impl<P> ProfileRegistry<P> {
         }
     }
 
-    /// Check if profile exists
+    /// Check if profile exists, in memory or spilled
     pub fn contains(&self, hash: u64) -> bool {
+        self.is_resident(hash) || self.is_spilled(hash)
+    }
+
+    /// Check if profile is held in memory
+    pub fn is_resident(&self, hash: u64) -> bool {
         self.profiles.contains_key(&hash)
     }
 
+    /// Check if profile was evicted to the spill tier
+    pub fn is_spilled(&self, hash: u64) -> bool {
+        !self.is_resident(hash)
+            && self
+                .spill
+                .as_ref()
+                .is_some_and(|tier| tier.store.contains(hash))
+    }
+
     /// Insert a new profile, evicting if necessary
     pub fn insert(&mut self, hash: u64, profile: P) -> Option<(u64, P)> {
         self.insert_with_priority(hash, profile, 0)
@@ -199,6 +305,27 @@ impl<P> ProfileRegistry<P> {
         profile: P,
         priority: u8,
     ) -> Option<(u64, P)> {
+        self.insert_entry(hash, ProfileEntry::new(profile).with_priority(priority))
+    }
+
+    /// Insert an entry with its metadata (e.g. restored from a checkpoint),
+    /// replacing any spilled copy
+    pub fn insert_entry(&mut self, hash: u64, entry: ProfileEntry<P>) -> Option<(u64, P)> {
+        if let Some(tier) = self.spill.as_mut() {
+            // Best effort: a stale record only matters if it is reloaded
+            // after this entry is evicted again, which overwrites it
+            if tier.store.contains(hash) && tier.store.remove(hash).is_ok() {
+                self.stats.spilled_profiles = self.stats.spilled_profiles.saturating_sub(1);
+            }
+        }
+        let evicted = self.admit(hash, entry);
+        self.stats.total_creations += 1;
+        self.log_event(LifecycleEventKind::ProfileCreated { hash });
+        evicted
+    }
+
+    /// Store an entry, evicting first if at capacity
+    fn admit(&mut self, hash: u64, entry: ProfileEntry<P>) -> Option<(u64, P)> {
         let mut evicted = None;
 
         // Evict if at capacity
@@ -206,9 +333,7 @@ impl<P> ProfileRegistry<P> {
             evicted = self.evict_one();
         }
 
-        let entry = ProfileEntry::new(profile).with_priority(priority);
         self.profiles.insert(hash, entry);
-        self.stats.total_creations += 1;
 
         // Track in access order
         if self.access_order.len() < self.config.max_profiles {
@@ -222,6 +347,9 @@ impl<P> ProfileRegistry<P> {
     }
 
     /// Get or create a profile
+    ///
+    /// Always yields a profile, so `max_entities` does not apply; see
+    /// [`Self::try_get_or_create`].
     pub fn get_or_create<F>(&mut self, hash: u64, create: F) -> &mut P
     where
         F: FnOnce() -> P,
@@ -234,34 +362,107 @@ impl<P> ProfileRegistry<P> {
     where
         F: FnOnce() -> P,
     {
-        // Check if exists
-        if self.profiles.contains_key(&hash) {
+        // Check if exists (or was spilled)
+        self.expire_if_idle(hash);
+        if self.profiles.contains_key(&hash) || self.reload(hash) {
             let entry = self.profiles.get_mut(&hash).unwrap();
             entry.meta.touch();
             self.stats.total_accesses += 1;
             return &mut entry.profile;
         }
 
-        // Need to create - evict first if necessary
-        if self.is_full() {
-            self.evict_one();
+        // Create and insert, evicting first if necessary
+        let entry = ProfileEntry::new(create()).with_priority(priority);
+        self.admit(hash, entry);
+        self.stats.total_creations += 1;
+        self.log_event(LifecycleEventKind::ProfileCreated { hash });
+
+        &mut self.profiles.get_mut(&hash).unwrap().profile
+    }
+
+    /// Get or create a profile, unless creating it would exceed
+    /// `max_entities`; rejections are counted in the stats
+    pub fn try_get_or_create<F>(&mut self, hash: u64, create: F) -> Option<&mut P>
+    where
+        F: FnOnce() -> P,
+    {
+        self.try_get_or_create_with_priority(hash, 0, create)
+    }
+
+    /// [`Self::try_get_or_create`] with priority
+    pub fn try_get_or_create_with_priority<F>(
+        &mut self,
+        hash: u64,
+        priority: u8,
+        create: F,
+    ) -> Option<&mut P>
+    where
+        F: FnOnce() -> P,
+    {
+        self.expire_if_idle(hash);
+        let at_cap = self
+            .config
+            .max_entities
+            .is_some_and(|cap| self.entity_count() >= cap);
+        if at_cap && !self.profiles.contains_key(&hash) && !self.is_spilled(hash) {
+            self.stats.total_rejections += 1;
+            return None;
         }
+        Some(self.get_or_create_with_priority(hash, priority, create))
+    }
 
-        // Create and insert
-        let profile = create();
-        let entry = ProfileEntry::new(profile).with_priority(priority);
-        self.profiles.insert(hash, entry);
-        self.stats.total_creations += 1;
+    fn is_pinned(&self, meta: &ProfileMeta) -> bool {
+        self.config
+            .pinned_priority
+            .is_some_and(|pinned| meta.priority >= pinned)
+    }
 
-        // Track access order
-        if self.access_order.len() < self.config.max_profiles {
-            self.access_order.push(hash);
-        } else {
-            self.access_order[self.access_head] = hash;
-            self.access_head = (self.access_head + 1) % self.access_order.len();
+    fn is_expired(&self, meta: &ProfileMeta) -> bool {
+        self.config
+            .idle_ttl
+            .is_some_and(|ttl| meta.last_access.elapsed() > ttl)
+            && !self.is_pinned(meta)
+    }
+
+    /// Drop every profile idle for longer than `idle_ttl`; returns how many
+    pub fn expire_idle(&mut self) -> usize {
+        if self.config.idle_ttl.is_none() {
+            return 0;
+        }
+        let expired: Vec<u64> = self
+            .profiles
+            .iter()
+            .filter(|(_, e)| self.is_expired(&e.meta))
+            .map(|(&h, _)| h)
+            .collect();
+        for &hash in &expired {
+            self.expire(hash);
+        }
+        expired.len()
+    }
+
+    /// Drop the profile for `hash` if it outlived `idle_ttl`
+    fn expire_if_idle(&mut self, hash: u64) {
+        if self.config.idle_ttl.is_none() {
+            return;
+        }
+        if self
+            .profiles
+            .get(&hash)
+            .is_some_and(|e| self.is_expired(&e.meta))
+        {
+            self.expire(hash);
         }
+    }
 
-        &mut self.profiles.get_mut(&hash).unwrap().profile
+    fn expire(&mut self, hash: u64) {
+        if let Some(entry) = self.profiles.remove(&hash) {
+            self.stats.total_expirations += 1;
+            self.log_event(LifecycleEventKind::ProfileExpired { hash });
+            if let Some(callback) = self.on_evict.as_mut() {
+                callback(hash, &entry.profile, false);
+            }
+        }
     }
 
     /// Evict one profile based on LRU/score
@@ -275,18 +476,85 @@ impl<P> ProfileRegistry<P> {
 
         if let Some(entry) = self.profiles.remove(&candidate) {
             self.stats.total_evictions += 1;
+            let spilled = self.spill_entry(candidate, &entry);
+            self.log_event(LifecycleEventKind::ProfileEvicted {
+                hash: candidate,
+                spilled,
+            });
+            if let Some(callback) = self.on_evict.as_mut() {
+                callback(candidate, &entry.profile, spilled);
+            }
             Some((candidate, entry.profile))
         } else {
             None
         }
     }
 
-    /// Find the best candidate for eviction
+    /// Write an evicted entry to the spill tier; false if there is none or
+    /// the write failed
+    fn spill_entry(&mut self, hash: u64, entry: &ProfileEntry<P>) -> bool {
+        let Some(tier) = self.spill.as_mut() else {
+            return false;
+        };
+        match tier.store.put(hash, &(tier.encode)(entry)) {
+            Ok(()) => {
+                self.stats.total_spills += 1;
+                self.stats.spilled_profiles += 1;
+                true
+            }
+            Err(_e) => {
+                #[cfg(feature = "instrument")]
+                tracing::warn!(hash, error = %_e, "spill failed");
+                self.stats.spill_failures += 1;
+                false
+            }
+        }
+    }
+
+    /// Remove a spilled entry from the spill tier
+    fn take_spilled(&mut self, hash: u64) -> Option<ProfileEntry<P>> {
+        let tier = self.spill.as_mut()?;
+        let entry = tier.store.take(hash).and_then(|record| {
+            let Some(record) = record else {
+                return Ok(None);
+            };
+            self.stats.spilled_profiles = self.stats.spilled_profiles.saturating_sub(1);
+            (tier.decode)(&record).map(Some)
+        });
+        match entry {
+            Ok(entry) => entry,
+            Err(_e) => {
+                #[cfg(feature = "instrument")]
+                tracing::warn!(hash, error = %_e, "reload failed");
+                self.stats.spill_failures += 1;
+                None
+            }
+        }
+    }
+
+    /// Bring a spilled profile back into memory; true if it is resident now
+    fn reload(&mut self, hash: u64) -> bool {
+        if self.profiles.contains_key(&hash) {
+            return true;
+        }
+        let Some(entry) = self.take_spilled(hash) else {
+            return false;
+        };
+        self.admit(hash, entry);
+        self.stats.total_reloads += 1;
+        self.log_event(LifecycleEventKind::ProfileReloaded { hash });
+        true
+    }
+
+    /// Find the best candidate for eviction; pinned profiles never are
     fn find_eviction_candidate(&self) -> Option<u64> {
-        if !self.config.enable_lru {
-            return self
-                .profiles
+        let evictable = || {
+            self.profiles
                 .iter()
+                .filter(|(_, e)| !self.is_pinned(&e.meta))
+        };
+        if !self.config.enable_lru {
+            return evictable()
                 .min_by(|a, b| a.1.meta.created_at.cmp(&b.1.meta.created_at))
                 .map(|(&h, _)| h);
         }
@@ -294,7 +562,7 @@ impl<P> ProfileRegistry<P> {
         // Simple LRU: find oldest access with low event count
         let mut best_candidate: Option<(u64, f64)> = None;
 
-        for (&hash, entry) in &self.profiles {
+        for (&hash, entry) in evictable() {
             // Don't evict profiles with too few events (still learning)
             if entry.meta.event_count < self.config.min_events_for_eviction {
                 continue;
@@ -313,9 +581,7 @@ impl<P> ProfileRegistry<P> {
 
         // If no candidate met criteria, just pick the oldest
         if best_candidate.is_none() {
-            best_candidate = self
-                .profiles
-                .iter()
+            best_candidate = evictable()
                 .min_by(|a, b| a.1.meta.last_access.cmp(&b.1.meta.last_access))
                 .map(|(&h, e)| (h, e.meta.eviction_score()));
         }
@@ -323,23 +589,34 @@ impl<P> ProfileRegistry<P> {
         best_candidate.map(|(h, _)| h)
     }
 
-    /// Remove a specific profile
+    /// Remove a specific profile, in memory or spilled
     pub fn remove(&mut self, hash: u64) -> Option<P> {
-        self.profiles.remove(&hash).map(|e| e.profile)
+        let entry = match self.profiles.remove(&hash) {
+            Some(entry) => entry,
+            None => self.take_spilled(hash)?,
+        };
+        self.log_event(LifecycleEventKind::ProfileRemoved { hash });
+        Some(entry.profile)
     }
 
-    /// Clear all profiles
+    /// Clear all profiles held in memory; spilled ones stay in the spill
+    /// tier
     pub fn clear(&mut self) {
         self.profiles.clear();
         self.access_order.clear();
         self.access_head = 0;
     }
 
-    /// Iterate over all profiles (read-only)
+    /// Iterate over all profiles held in memory (read-only)
     pub fn iter(&self) -> impl Iterator<Item = (&u64, &P)> {
         self.profiles.iter().map(|(k, v)| (k, &v.profile))
     }
 
+    /// Iterate over all profiles mutably, without touching access metadata
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&u64, &mut P)> {
+        self.profiles.iter_mut().map(|(k, v)| (k, &mut v.profile))
+    }
+
     /// Iterate over all entries with metadata
     pub fn iter_entries(&self) -> impl Iterator<Item = (&u64, &ProfileEntry<P>)> {
         self.profiles.iter()
@@ -370,6 +647,17 @@ impl<P> ProfileRegistry<P> {
         self.profiles.get(&hash).map(|e| &e.meta)
     }
 
+    /// Profile creations, evictions and removals, oldest first
+    pub fn lifecycle(&self) -> &LifecycleLog {
+        &self.lifecycle
+    }
+
+    fn log_event(&mut self, kind: LifecycleEventKind) {
+        #[cfg(feature = "instrument")]
+        tracing::debug!(event = ?kind, profiles = self.profiles.len(), "lifecycle");
+        self.lifecycle.record(0, 0, kind);
+    }
+
     /// Update priority for a profile
     pub fn set_priority(&mut self, hash: u64, priority: u8) {
         if let Some(entry) = self.profiles.get_mut(&hash) {
@@ -378,22 +666,146 @@ impl<P> ProfileRegistry<P> {
     }
 }
 
+impl<P: Checkpointable> ProfileRegistry<P> {
+    /// Spill evicted profiles to `store` and reload them on access (`None`
+    /// discards evicted profiles again; records already spilled stay in the
+    /// old store)
+    ///
+    /// Records already in `store` (e.g. spilled before a restart) count as
+    /// spilled profiles of this registry.
+    pub fn set_spill_store(&mut self, store: Option<Box<dyn SpillStore>>) {
+        self.stats.spilled_profiles = store.as_ref().map_or(0, |store| store.len());
+        self.spill = store.map(|store| SpillTier {
+            store,
+            encode: encode_entry::<P>,
+            decode: decode_entry::<P>,
+        });
+    }
+
+    /// Builder form of [`Self::set_spill_store`]
+    pub fn with_spill_store(mut self, store: impl SpillStore + 'static) -> Self {
+        self.set_spill_store(Some(Box::new(store)));
+        self
+    }
+}
+
 impl<P> Default for ProfileRegistry<P> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Registry shared across threads, split by hash into shards behind their
+/// own locks so threads working on different profiles rarely wait
+pub struct ShardedRegistry<P> {
+    shards: Box<[Mutex<ProfileRegistry<P>>]>,
+}
+
+impl<P> ShardedRegistry<P> {
+    /// `shards` registries splitting `config`'s `max_profiles` and
+    /// `max_entities` between them
+    pub fn new(shards: usize, config: RegistryConfig) -> Self {
+        let shards = shards.max(1);
+        let shard_config = RegistryConfig {
+            max_profiles: config.max_profiles.div_ceil(shards),
+            max_entities: config.max_entities.map(|n| n.div_ceil(shards)),
+            ..config
+        };
+        Self {
+            shards: (0..shards)
+                .map(|_| Mutex::new(ProfileRegistry::with_config(shard_config.clone())))
+                .collect(),
+        }
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Lock the shard holding `hash`
+    pub fn shard(&self, hash: u64) -> MutexGuard<'_, ProfileRegistry<P>> {
+        self.shards[(hash % self.shards.len() as u64) as usize]
+            .lock()
+            .unwrap()
+    }
+
+    /// Run `f` on the profile for `hash`, created by `create` if missing,
+    /// holding only its shard's lock
+    pub fn with_profile<R>(
+        &self,
+        hash: u64,
+        create: impl FnOnce() -> P,
+        f: impl FnOnce(&mut P) -> R,
+    ) -> R {
+        f(self.shard(hash).get_or_create(hash, create))
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|s| s.lock().unwrap().len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Statistics summed over the shards
+    pub fn stats(&self) -> RegistryStats {
+        let mut total = RegistryStats::default();
+        for shard in &self.shards {
+            let shard = shard.lock().unwrap();
+            let stats = shard.stats();
+            total.total_profiles += stats.total_profiles;
+            total.total_evictions += stats.total_evictions;
+            total.total_creations += stats.total_creations;
+            total.total_accesses += stats.total_accesses;
+            total.capacity += stats.capacity;
+            total.total_spills += stats.total_spills;
+            total.total_reloads += stats.total_reloads;
+            total.spill_failures += stats.spill_failures;
+            total.spilled_profiles += stats.spilled_profiles;
+            total.total_expirations += stats.total_expirations;
+            total.total_rejections += stats.total_rejections;
+        }
+        total
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_sharded_registry_across_threads() {
+        let registry: ShardedRegistry<u64> = ShardedRegistry::new(
+            4,
+            RegistryConfig {
+                max_profiles: 64,
+                ..Default::default()
+            },
+        );
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for hash in 0..32u64 {
+                        registry.with_profile(hash, || 0, |count| *count += 1);
+                    }
+                });
+            }
+        });
+        assert_eq!(registry.len(), 32);
+        assert_eq!(*registry.shard(5).get(5).unwrap(), 4);
+        let stats = registry.stats();
+        assert_eq!(stats.capacity, 64);
+        assert_eq!(stats.total_creations, 32);
+    }
+
     #[test]
     fn test_basic_operations() {
         let mut registry: ProfileRegistry<String> = ProfileRegistry::with_config(RegistryConfig {
             max_profiles: 10,
             min_events_for_eviction: 1,
             enable_lru: true,
+            ..Default::default()
         });
 
         // Insert
@@ -419,6 +831,7 @@ mod tests {
             max_profiles: 3,
             min_events_for_eviction: 0,
             enable_lru: true,
+            ..Default::default()
         });
 
         // Fill to capacity
@@ -433,6 +846,15 @@ mod tests {
 
         assert_eq!(registry.len(), 3);
         assert!(registry.stats().total_evictions >= 1);
+
+        // Lifecycle: four creations with the eviction logged before the fourth
+        let kinds: Vec<_> = registry.lifecycle().events().map(|e| &e.kind).collect();
+        assert_eq!(kinds.len(), 5);
+        assert!(matches!(
+            kinds[3],
+            LifecycleEventKind::ProfileEvicted { .. }
+        ));
+        assert_eq!(kinds[4], &LifecycleEventKind::ProfileCreated { hash: 4 });
     }
 
     #[test]
@@ -456,6 +878,7 @@ mod tests {
             max_profiles: 3,
             min_events_for_eviction: 0,
             enable_lru: true,
+            ..Default::default()
         });
 
         // Insert with different priorities
@@ -479,4 +902,167 @@ mod tests {
             "High priority should survive eviction"
         );
     }
+
+    #[test]
+    fn test_entity_cap_rejects_new_entities() {
+        let mut registry: ProfileRegistry<u32> = ProfileRegistry::with_config(RegistryConfig {
+            max_entities: Some(2),
+            ..Default::default()
+        });
+        assert!(registry.try_get_or_create(1, || 1).is_some());
+        assert!(registry.try_get_or_create(2, || 2).is_some());
+        assert!(registry.try_get_or_create(3, || 3).is_none());
+        assert_eq!(registry.try_get_or_create(1, || 9).copied(), Some(1));
+        assert_eq!(registry.stats().total_rejections, 1);
+        assert_eq!(registry.stats().total_evictions, 0);
+
+        // get_or_create always yields a profile
+        registry.get_or_create(3, || 3);
+        assert_eq!(registry.len(), 3);
+    }
+
+    #[test]
+    fn test_idle_ttl_and_pinned_priority() {
+        let mut registry: ProfileRegistry<u32> = ProfileRegistry::with_config(RegistryConfig {
+            max_profiles: 2,
+            min_events_for_eviction: 0,
+            idle_ttl: Some(Duration::from_millis(20)),
+            pinned_priority: Some(5),
+            ..Default::default()
+        });
+        registry.insert_with_priority(1, 1, 5);
+        registry.insert(2, 2);
+        std::thread::sleep(Duration::from_millis(30));
+
+        // Pinned profiles outlive the TTL and are never the eviction victim
+        assert_eq!(registry.get(2), None);
+        assert_eq!(registry.get(1), Some(&1));
+        registry.insert(3, 3);
+        registry.insert(4, 4);
+        assert!(registry.is_resident(1) && registry.is_resident(4));
+        assert!(!registry.contains(3));
+        assert_eq!(registry.stats().total_expirations, 1);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(registry.expire_idle(), 1);
+        assert_eq!(registry.hashes(), vec![1]);
+        assert!(
+            registry
+                .lifecycle()
+                .events()
+                .any(|e| e.kind == LifecycleEventKind::ProfileExpired { hash: 2 })
+        );
+
+        // Lowering the capacity evicts down to it, pinned profiles excepted
+        registry.insert_with_priority(5, 5, 9);
+        registry.insert(6, 6);
+        let evicted = registry.set_config(RegistryConfig {
+            max_profiles: 1,
+            ..registry.config().clone()
+        });
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(evicted[0].0, 6);
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Counter(u64);
+
+    impl Checkpointable for Counter {
+        fn to_checkpoint(&self) -> Vec<u8> {
+            self.0.to_le_bytes().to_vec()
+        }
+
+        fn from_checkpoint(data: &[u8]) -> Result<Self, CheckpointError> {
+            let bytes = data
+                .try_into()
+                .map_err(|_| CheckpointError::InvalidState("counter".to_string()))?;
+            Ok(Counter(u64::from_le_bytes(bytes)))
+        }
+    }
+
+    #[test]
+    fn test_spill_and_reload() {
+        use crate::spill::DirectoryStore;
+        use std::sync::{Arc, Mutex};
+
+        let dir = std::env::temp_dir().join(format!("via-core-spill-{}", std::process::id()));
+        let mut registry: ProfileRegistry<Counter> = ProfileRegistry::with_config(RegistryConfig {
+            max_profiles: 2,
+            min_events_for_eviction: 0,
+            enable_lru: true,
+            ..Default::default()
+        })
+        .with_spill_store(DirectoryStore::open(&dir).unwrap());
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let log = evicted.clone();
+        registry.set_eviction_callback(Some(Box::new(move |hash, profile: &Counter, spilled| {
+            log.lock().unwrap().push((hash, profile.0, spilled));
+        })));
+
+        registry.insert_with_priority(1, Counter(10), 1);
+        for _ in 0..4 {
+            registry.get_mut(1).unwrap().0 += 1;
+        }
+        registry.insert_with_priority(2, Counter(20), 10);
+        registry.insert_with_priority(3, Counter(30), 10);
+        assert_eq!(evicted.lock().unwrap()[0], (1, 14, true));
+        assert!(registry.is_spilled(1) && registry.contains(1));
+        assert!(!registry.is_resident(1));
+        assert_eq!(registry.len(), 2);
+
+        // Reloading evicts another profile to make room
+        assert_eq!(registry.get(1), Some(&Counter(14)));
+        assert!(registry.is_resident(1));
+        assert_eq!(registry.stats().total_reloads, 1);
+        assert_eq!(registry.stats().total_spills, 2);
+        assert_eq!(registry.get_meta(1).unwrap().priority, 1);
+        assert_eq!(registry.get_meta(1).unwrap().event_count, 5);
+        assert!(
+            registry
+                .lifecycle()
+                .events()
+                .any(|e| e.kind == LifecycleEventKind::ProfileReloaded { hash: 1 })
+        );
+
+        // Removing a spilled profile returns it and forgets it
+        let spilled_hash = evicted.lock().unwrap()[1].0;
+        assert!(registry.remove(spilled_hash).is_some());
+        assert!(!registry.contains(spilled_hash));
+
+        registry.set_spill_store(None);
+        registry.insert(4, Counter(40));
+        assert!(!evicted.lock().unwrap()[2].2, "discarded without a tier");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_spilled_profiles_survive_restart() {
+        use crate::spill::DirectoryStore;
+
+        let dir =
+            std::env::temp_dir().join(format!("via-core-spill-restart-{}", std::process::id()));
+        let config = RegistryConfig {
+            max_profiles: 1,
+            min_events_for_eviction: 0,
+            ..Default::default()
+        };
+        let mut registry: ProfileRegistry<Counter> = ProfileRegistry::with_config(config.clone())
+            .with_spill_store(DirectoryStore::open(&dir).unwrap());
+        registry.insert(1, Counter(10));
+        registry.insert(2, Counter(20));
+        registry.insert(3, Counter(30));
+        assert_eq!(registry.stats().spilled_profiles, 2);
+        drop(registry);
+
+        // A new process finds the records and counts them
+        let mut registry: ProfileRegistry<Counter> = ProfileRegistry::with_config(config)
+            .with_spill_store(DirectoryStore::open(&dir).unwrap());
+        assert_eq!(registry.stats().spilled_profiles, 2);
+        assert_eq!(registry.entity_count(), 2);
+        assert!(registry.is_spilled(1) && !registry.is_spilled(3));
+        assert_eq!(registry.get(2), Some(&Counter(20)));
+        assert_eq!(registry.stats().spilled_profiles, 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -7,11 +7,21 @@
 use serde::{Deserialize, Serialize};
 
 /// Number of detectors in the ensemble (compile-time constant)
-pub const NUM_DETECTORS: usize = 10;
+pub const NUM_DETECTORS: usize = 13;
 
 /// Detector identifiers for attribution
+///
+/// Numeric values are stable across versions: they index `detector_scores`,
+/// cross the FFI and are written in checkpoints, so a detector keeps its
+/// number and new detectors take the next one. The lowercase [`key`]
+/// (`volume`, `rrcf`, ...) is stable likewise; it is what `Display` prints,
+/// what [`from_name`] parses and what JSON and TOML hold, while binary
+/// formats keep the number.
+///
+/// [`key`]: DetectorId::key
+/// [`from_name`]: DetectorId::from_name
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DetectorId {
     Volume = 0,
     Distribution = 1,
@@ -23,41 +33,178 @@ pub enum DetectorId {
     MultiScale = 7,
     Behavioral = 8,
     Drift = 9,
+    Quantile = 10,
+    Evt = 11,
+    Discord = 12,
 }
 
+/// Variant names, as binary formats saw them before keys
+const VARIANTS: [&str; NUM_DETECTORS] = [
+    "Volume",
+    "Distribution",
+    "Cardinality",
+    "Burst",
+    "Spectral",
+    "ChangePoint",
+    "RRCF",
+    "MultiScale",
+    "Behavioral",
+    "Drift",
+    "Quantile",
+    "Evt",
+    "Discord",
+];
+
+/// Display names in numeric order, NUL-terminated so the FFI can hand them
+/// out as C strings (see [`DetectorId::name`])
+pub const DETECTOR_NAMES: [&str; NUM_DETECTORS] = [
+    "Volume/RPS\0",
+    "Distribution/Value\0",
+    "Cardinality/Velocity\0",
+    "Burst/IAT\0",
+    "Spectral/FFT\0",
+    "ChangePoint/Trend\0",
+    "RRCF/Isolation\0",
+    "MultiScale/Temporal\0",
+    "Behavioral/Fingerprint\0",
+    "Drift/Concept\0",
+    "Quantile/Tail\0",
+    "EVT/SPOT\0",
+    "MatrixProfile/Discord\0",
+];
+
 impl DetectorId {
+    /// Every detector, in numeric order
+    pub const ALL: [DetectorId; NUM_DETECTORS] = [
+        Self::Volume,
+        Self::Distribution,
+        Self::Cardinality,
+        Self::Burst,
+        Self::Spectral,
+        Self::ChangePoint,
+        Self::RRCF,
+        Self::MultiScale,
+        Self::Behavioral,
+        Self::Drift,
+        Self::Quantile,
+        Self::Evt,
+        Self::Discord,
+    ];
+
     pub fn from_u8(v: u8) -> Option<Self> {
-        match v {
-            0 => Some(Self::Volume),
-            1 => Some(Self::Distribution),
-            2 => Some(Self::Cardinality),
-            3 => Some(Self::Burst),
-            4 => Some(Self::Spectral),
-            5 => Some(Self::ChangePoint),
-            6 => Some(Self::RRCF),
-            7 => Some(Self::MultiScale),
-            8 => Some(Self::Behavioral),
-            9 => Some(Self::Drift),
-            _ => None,
+        Self::ALL.get(v as usize).copied()
+    }
+
+    /// Stable lowercase name for config files, CLI filters and JSON
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Volume => "volume",
+            Self::Distribution => "distribution",
+            Self::Cardinality => "cardinality",
+            Self::Burst => "burst",
+            Self::Spectral => "spectral",
+            Self::ChangePoint => "changepoint",
+            Self::RRCF => "rrcf",
+            Self::MultiScale => "multiscale",
+            Self::Behavioral => "behavioral",
+            Self::Drift => "drift",
+            Self::Quantile => "quantile",
+            Self::Evt => "evt",
+            Self::Discord => "discord",
         }
     }
 
+    /// Detector by its key, variant or display name, ignoring case, `-` and
+    /// `_`: `rrcf`, `RRCF` and `RRCF/Isolation` all give `RRCF`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase().replace(['-', '_'], "");
+        Self::ALL
+            .into_iter()
+            .find(|id| id.key() == name || id.name().eq_ignore_ascii_case(&name))
+    }
+
+    /// Display name (`RRCF/Isolation`), from [`DETECTOR_NAMES`]
     pub fn name(&self) -> &'static str {
-        match self {
-            Self::Volume => "Volume/RPS",
-            Self::Distribution => "Distribution/Value",
-            Self::Cardinality => "Cardinality/Velocity",
-            Self::Burst => "Burst/IAT",
-            Self::Spectral => "Spectral/FFT",
-            Self::ChangePoint => "ChangePoint/Trend",
-            Self::RRCF => "RRCF/Isolation",
-            Self::MultiScale => "MultiScale/Temporal",
-            Self::Behavioral => "Behavioral/Fingerprint",
-            Self::Drift => "Drift/Concept",
+        let name = DETECTOR_NAMES[*self as usize];
+        &name[..name.len() - 1]
+    }
+}
+
+impl std::fmt::Display for DetectorId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl std::str::FromStr for DetectorId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_name(s).ok_or_else(|| {
+            let keys: Vec<&str> = Self::ALL.iter().map(|id| id.key()).collect();
+            format!("unknown detector '{s}' (expected {})", keys.join(", "))
+        })
+    }
+}
+
+impl TryFrom<u8> for DetectorId {
+    type Error = String;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        Self::from_u8(v).ok_or_else(|| format!("unknown detector number {v}"))
+    }
+}
+
+impl Serialize for DetectorId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(self.key())
+        } else {
+            let index = *self as u32;
+            serializer.serialize_unit_variant("DetectorId", index, VARIANTS[index as usize])
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DetectorId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(DetectorIdVisitor)
+        } else {
+            deserializer.deserialize_enum("DetectorId", &VARIANTS, DetectorIdVisitor)
         }
     }
 }
 
+/// Accepts a name (see `from_name`), a number, or an enum variant
+struct DetectorIdVisitor;
+
+impl<'de> serde::de::Visitor<'de> for DetectorIdVisitor {
+    type Value = DetectorId;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a detector name or number")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<DetectorId, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<DetectorId, E> {
+        u8::try_from(v)
+            .ok()
+            .and_then(DetectorId::from_u8)
+            .ok_or_else(|| E::custom(format!("unknown detector number {v}")))
+    }
+
+    fn visit_enum<A: serde::de::EnumAccess<'de>>(self, data: A) -> Result<DetectorId, A::Error> {
+        use serde::de::VariantAccess;
+        let (id, variant) = data.variant::<DetectorId>()?;
+        variant.unit_variant()?;
+        Ok(id)
+    }
+}
+
 /// Severity levels for anomalies
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
@@ -131,6 +278,13 @@ pub struct BaselineSummary {
     pub avg_frequency: f32,
     /// Total events processed for this profile
     pub profile_age: u32,
+    /// Season length (events) the Volume detector estimated, 0 if none
+    pub detected_period: u32,
+    /// Trend, seasonal and residual components of the value when the
+    /// decomposition stage is on, otherwise 0
+    pub trend: f32,
+    pub seasonal: f32,
+    pub residual: f32,
     /// Whether profile is in warmup period
     pub is_warmup: bool,
 }
@@ -183,6 +337,82 @@ impl Attribution {
     }
 }
 
+/// Values a detector would have accepted, bounds inclusive; a missing bound
+/// is open
+///
+/// Displays as the condition an operator can act on, e.g. `value <= 420.00`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NormalRange {
+    pub low: Option<f64>,
+    pub high: Option<f64>,
+}
+
+impl NormalRange {
+    /// Values at or below `high`
+    pub fn at_most(high: f64) -> Self {
+        Self {
+            low: None,
+            high: Some(high),
+        }
+    }
+
+    /// Values from `low` to `high`
+    pub fn between(low: f64, high: f64) -> Self {
+        Self {
+            low: Some(low),
+            high: Some(high),
+        }
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        self.low.is_none_or(|low| value >= low) && self.high.is_none_or(|high| value <= high)
+    }
+
+    /// The value in the range closest to `value`
+    pub fn nearest(&self, value: f64) -> f64 {
+        match (self.low, self.high) {
+            (Some(low), _) if value < low => low,
+            (_, Some(high)) if value > high => high,
+            _ => value,
+        }
+    }
+
+    /// Values in both ranges, `None` when they do not overlap
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let low = match (self.low, other.low) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let high = match (self.high, other.high) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        match (low, high) {
+            (Some(low), Some(high)) if low > high => None,
+            _ => Some(Self { low, high }),
+        }
+    }
+
+    /// The range moved by `delta`
+    pub fn shifted(&self, delta: f64) -> Self {
+        Self {
+            low: self.low.map(|low| low + delta),
+            high: self.high.map(|high| high + delta),
+        }
+    }
+}
+
+impl std::fmt::Display for NormalRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.low, self.high) {
+            (Some(low), Some(high)) => write!(f, "{:.2} <= value <= {:.2}", low, high),
+            (Some(low), None) => write!(f, "value >= {:.2}", low),
+            (None, Some(high)) => write!(f, "value <= {:.2}", high),
+            (None, None) => write!(f, "any value"),
+        }
+    }
+}
+
 /// Full anomaly signal for Tier-2 consumption
 #[repr(C)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -198,15 +428,21 @@ pub struct AnomalySignal {
     // === Primary Decision ===
     /// Whether this is classified as an anomaly
     pub is_anomaly: bool,
+    /// Anomalous, but within the cooldown after the last raised anomaly, so
+    /// `is_anomaly` is false (see `ProfileConfig::suppression`)
+    pub suppressed_by_cooldown: bool,
     /// Severity level
     pub severity: Severity,
     /// Combined ensemble score (0.0 - 1.0)
     pub ensemble_score: f64,
     /// Overall confidence in the decision
     pub confidence: f64,
+    /// Empirical probability that an event with this score is anomalous,
+    /// learned from Tier-2 feedback; `None` until enough labels arrived
+    pub calibrated_probability: Option<f32>,
 
     // === Full Detector Breakdown ===
-    /// Individual scores from all 10 detectors
+    /// Individual scores from all built-in detectors
     pub detector_scores: [DetectorScore; NUM_DETECTORS],
     /// Current ensemble weights for each detector
     pub detector_weights: [f32; NUM_DETECTORS],
@@ -214,12 +450,23 @@ pub struct AnomalySignal {
     // === Attribution ===
     /// Which detectors contributed most
     pub attribution: Attribution,
+    /// On anomalous and cooldown-suppressed signals, the values that would
+    /// have fired none of the fired detectors reporting a
+    /// [`FiredDetector::normal_range`]; `None` when none reports one or
+    /// their ranges do not overlap
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normal_range: Option<NormalRange>,
 
     // === Context ===
     /// Baseline behavior for this entity
     pub baseline: BaselineSummary,
     /// Raw value that was processed
     pub raw_value: f64,
+
+    // === Explanation ===
+    /// Why the event was flagged; see [`explanation`](Self::explanation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<Box<SignalExplanation>>,
 }
 
 impl Default for AnomalySignal {
@@ -229,14 +476,18 @@ impl Default for AnomalySignal {
             timestamp: 0,
             sequence: 0,
             is_anomaly: false,
+            suppressed_by_cooldown: false,
             severity: Severity::None,
             ensemble_score: 0.0,
             confidence: 1.0,
+            calibrated_probability: None,
             detector_scores: [DetectorScore::default(); NUM_DETECTORS],
             detector_weights: [0.1; NUM_DETECTORS], // Equal weights initially
             attribution: Attribution::default(),
+            normal_range: None,
             baseline: BaselineSummary::default(),
             raw_value: 0.0,
+            explanation: None,
         }
     }
 }
@@ -271,6 +522,13 @@ impl AnomalySignal {
         self.detector_scores[detector as usize].score
     }
 
+    /// Detail behind the decision, present on anomalous and
+    /// cooldown-suppressed signals from a profile with a non-zero
+    /// `ProfileConfig::explain_history`
+    pub fn explanation(&self) -> Option<&SignalExplanation> {
+        self.explanation.as_deref()
+    }
+
     /// Generate a compact reason string
     pub fn reason(&self) -> String {
         if !self.is_anomaly {
@@ -280,9 +538,13 @@ impl AnomalySignal {
         let primary = self.primary_detector_name();
         let secondary = self.secondary_detector_name();
         let fired = self.attribution.detectors_fired;
+        let normal = self
+            .normal_range
+            .map(|range| format!("; normal: {}", range))
+            .unwrap_or_default();
 
         format!(
-            "{} anomaly (score: {:.2}, confidence: {:.0}%) - Primary: {} ({:.0}%), Secondary: {} ({:.0}%), {} detectors triggered",
+            "{} anomaly (score: {:.2}, confidence: {:.0}%) - Primary: {} ({:.0}%), Secondary: {} ({:.0}%), {} detectors triggered{}",
             match self.severity {
                 Severity::Critical => "CRITICAL",
                 Severity::High => "HIGH",
@@ -296,11 +558,96 @@ impl AnomalySignal {
             self.attribution.primary_contribution * 100.0,
             secondary,
             self.attribution.secondary_contribution * 100.0,
-            fired
+            fired,
+            normal
         )
     }
 }
 
+/// Why a signal was flagged
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignalExplanation {
+    /// Last values seen for the entity, oldest first, ending with this event's
+    pub history: Vec<f64>,
+    /// Built-in detectors that fired on this event
+    pub fired: Vec<FiredDetector>,
+    /// Built-in detectors with a non-zero weighted score, largest share first
+    pub contributions: Vec<WeightContribution>,
+}
+
+impl SignalExplanation {
+    /// The `k` detectors contributing most to the ensemble score
+    pub fn top_contributions(&self, k: usize) -> &[WeightContribution] {
+        &self.contributions[..k.min(self.contributions.len())]
+    }
+}
+
+/// A detector that fired, as it saw the event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FiredDetector {
+    pub detector: DetectorId,
+    pub score: f32,
+    pub expected: f32,
+    pub observed: f32,
+    /// Score a fired detector needed to trigger the anomaly on its own
+    /// (`min_detector_score_for_anomaly`, scaled by the exogenous context)
+    pub threshold: f64,
+    /// The detector's own account, e.g. `Volume spike: expected 10.0 rps, got 55.0 rps`
+    pub reason: String,
+    /// Values that would not have fired it, in the units the detector saw
+    /// (seasonally adjusted when decomposing; see
+    /// [`DetectionResult::normal_range`](crate::engine::DetectionResult::normal_range))
+    pub normal_range: Option<NormalRange>,
+}
+
+/// One detector's part of the ensemble score
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WeightContribution {
+    pub detector: DetectorId,
+    /// Ensemble weight at the time of the event
+    pub weight: f32,
+    pub score: f32,
+    /// Share of the summed `score * confidence * weight` (0.0 - 1.0)
+    pub share: f32,
+}
+
+impl WeightContribution {
+    /// Every detector's share of the weighted score, largest first, skipping
+    /// detectors that contributed nothing
+    pub fn compute(
+        scores: &[DetectorScore; NUM_DETECTORS],
+        weights: &[f64; NUM_DETECTORS],
+    ) -> Vec<Self> {
+        let total: f64 = scores
+            .iter()
+            .zip(weights)
+            .map(|(s, w)| s.weighted_contribution(*w))
+            .sum();
+        if total <= 0.0 {
+            return Vec::new();
+        }
+        let mut contributions: Vec<Self> = scores
+            .iter()
+            .zip(weights)
+            .enumerate()
+            .filter_map(|(i, (score, weight))| {
+                let contribution = score.weighted_contribution(*weight);
+                if contribution <= 0.0 {
+                    return None;
+                }
+                Some(Self {
+                    detector: DetectorId::from_u8(i as u8)?,
+                    weight: *weight as f32,
+                    score: score.score,
+                    share: (contribution / total) as f32,
+                })
+            })
+            .collect();
+        contributions.sort_by(|a, b| b.share.total_cmp(&a.share));
+        contributions
+    }
+}
+
 /// Builder for constructing AnomalySignal
 pub struct AnomalySignalBuilder {
     signal: AnomalySignal,
@@ -376,6 +723,52 @@ impl AnomalySignalBuilder {
     }
 }
 
+/// [`AnomalySignal`] with C-compatible fields only, for hosts that read the
+/// signal straight out of their own memory (see `via_process_event_into`)
+///
+/// `severity` is the [`Severity`] discriminant and `calibrated_probability`
+/// is -1.0 while the profile is uncalibrated. Hosts can check their layout
+/// against `via_signal_flat_size`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CAnomalySignalFlat {
+    pub entity_hash: u64,
+    pub timestamp: u64,
+    pub sequence: u64,
+    pub ensemble_score: f64,
+    pub confidence: f64,
+    pub raw_value: f64,
+    pub calibrated_probability: f32,
+    pub is_anomaly: bool,
+    pub suppressed_by_cooldown: bool,
+    pub severity: u8,
+    pub detector_scores: [DetectorScore; NUM_DETECTORS],
+    pub detector_weights: [f32; NUM_DETECTORS],
+    pub attribution: Attribution,
+    pub baseline: BaselineSummary,
+}
+
+impl From<&AnomalySignal> for CAnomalySignalFlat {
+    fn from(signal: &AnomalySignal) -> Self {
+        Self {
+            entity_hash: signal.entity_hash,
+            timestamp: signal.timestamp,
+            sequence: signal.sequence,
+            ensemble_score: signal.ensemble_score,
+            confidence: signal.confidence,
+            raw_value: signal.raw_value,
+            calibrated_probability: signal.calibrated_probability.unwrap_or(-1.0),
+            is_anomaly: signal.is_anomaly,
+            suppressed_by_cooldown: signal.suppressed_by_cooldown,
+            severity: signal.severity as u8,
+            detector_scores: signal.detector_scores,
+            detector_weights: signal.detector_weights,
+            attribution: signal.attribution,
+            baseline: signal.baseline,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -411,6 +804,57 @@ mod tests {
         assert!(!signal.detector_fired(DetectorId::Cardinality));
     }
 
+    #[test]
+    fn test_detector_id_names_and_numbers_are_stable() {
+        for (i, id) in DetectorId::ALL.into_iter().enumerate() {
+            assert_eq!(id as usize, i);
+            assert_eq!(DetectorId::from_u8(i as u8), Some(id));
+            assert_eq!(id.to_string().parse::<DetectorId>(), Ok(id));
+        }
+        assert_eq!(DetectorId::from_u8(NUM_DETECTORS as u8), None);
+        assert_eq!(DetectorId::RRCF.name(), "RRCF/Isolation");
+        assert!(
+            DETECTOR_NAMES
+                .iter()
+                .all(|n| n.find('\0') == Some(n.len() - 1))
+        );
+        assert_eq!(DetectorId::from_name("RRCF"), Some(DetectorId::RRCF));
+        assert_eq!(
+            DetectorId::from_name("change-point"),
+            Some(DetectorId::ChangePoint)
+        );
+        assert_eq!(DetectorId::from_name("EVT/SPOT"), Some(DetectorId::Evt));
+        assert!(
+            "fft"
+                .parse::<DetectorId>()
+                .unwrap_err()
+                .contains("volume, distribution")
+        );
+    }
+
+    #[test]
+    fn test_detector_id_serde_uses_keys_and_numbers() {
+        let ids = [DetectorId::ChangePoint, DetectorId::RRCF];
+        let json = serde_json::to_string(&ids).unwrap();
+        assert_eq!(json, r#"["changepoint","rrcf"]"#);
+        // Variant names of earlier output and numbers read back too
+        let parsed: Vec<DetectorId> =
+            serde_json::from_str(r#"["ChangePoint", 6, "rrcf"]"#).unwrap();
+        assert_eq!(
+            parsed,
+            [DetectorId::ChangePoint, DetectorId::RRCF, DetectorId::RRCF]
+        );
+        assert!(serde_json::from_str::<DetectorId>("13").is_err());
+
+        // Binary formats keep the derived encoding: the variant index
+        let bytes = bincode::serialize(&DetectorId::RRCF).unwrap();
+        assert_eq!(bytes, bincode::serialize(&6u32).unwrap());
+        assert_eq!(
+            bincode::deserialize::<DetectorId>(&bytes).unwrap(),
+            DetectorId::RRCF
+        );
+    }
+
     #[test]
     fn test_attribution() {
         let mut scores = [DetectorScore::default(); NUM_DETECTORS];
@@ -418,7 +862,9 @@ mod tests {
         scores[1] = DetectorScore::new(0.7, 0.80, true, 0.0, 0.0); // Distribution
         scores[2] = DetectorScore::new(0.3, 0.70, false, 0.0, 0.0); // Cardinality
 
-        let weights = [0.15, 0.12, 0.10, 0.08, 0.12, 0.10, 0.11, 0.08, 0.08, 0.06];
+        let weights = [
+            0.12, 0.10, 0.09, 0.08, 0.09, 0.08, 0.09, 0.08, 0.07, 0.06, 0.05, 0.05, 0.04,
+        ];
 
         let attr = Attribution::compute(&scores, &weights);
 
@@ -426,4 +872,47 @@ mod tests {
         assert_eq!(attr.secondary_detector, 1); // Distribution secondary
         assert_eq!(attr.detectors_fired, 2);
     }
+
+    #[test]
+    fn test_weight_contributions() {
+        let mut scores = [DetectorScore::default(); NUM_DETECTORS];
+        scores[0] = DetectorScore::new(0.9, 1.0, true, 0.0, 0.0);
+        scores[3] = DetectorScore::new(0.3, 1.0, false, 0.0, 0.0);
+        let weights = [0.5; NUM_DETECTORS];
+
+        let contributions = WeightContribution::compute(&scores, &weights);
+        assert_eq!(contributions.len(), 2);
+        assert_eq!(contributions[0].detector, DetectorId::Volume);
+        assert!((contributions[0].share - 0.75).abs() < 1e-6);
+        assert_eq!(contributions[1].detector, DetectorId::Burst);
+
+        let explanation = SignalExplanation {
+            contributions,
+            ..Default::default()
+        };
+        assert_eq!(explanation.top_contributions(1).len(), 1);
+        assert_eq!(explanation.top_contributions(5).len(), 2);
+        assert!(
+            WeightContribution::compute(&[DetectorScore::default(); NUM_DETECTORS], &weights)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_normal_range() {
+        let below = NormalRange::at_most(420.0);
+        assert!(below.contains(420.0) && !below.contains(421.0));
+        assert_eq!(below.nearest(1_000.0), 420.0);
+        assert_eq!(below.nearest(10.0), 10.0);
+        assert_eq!(below.to_string(), "value <= 420.00");
+
+        let band = NormalRange::between(100.0, 500.0);
+        assert_eq!(band.nearest(50.0), 100.0);
+        assert_eq!(
+            below.intersect(&band),
+            Some(NormalRange::between(100.0, 420.0))
+        );
+        assert_eq!(below.intersect(&NormalRange::between(450.0, 500.0)), None);
+        assert_eq!(band.shifted(-100.0), NormalRange::between(0.0, 400.0));
+    }
 }
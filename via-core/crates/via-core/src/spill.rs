@@ -0,0 +1,159 @@
+//! Spill-to-Disk Tier for Evicted Profiles
+//!
+//! A [`ProfileRegistry`](crate::registry::ProfileRegistry) with a spill
+//! tier checkpoints the profiles it evicts into a [`SpillStore`] instead of
+//! discarding them, and reloads one transparently the next time its entity
+//! is accessed. Long-tail entities keep their learned baselines while only
+//! the active ones stay in memory.
+//!
+//! [`DirectoryStore`] keeps one file per profile. Other backends (an
+//! embedded key-value store, object storage) implement [`SpillStore`].
+//!
+//! Each record holds the profile's priority `u8` and event count `u64`
+//! (little endian) followed by its [`Checkpointable`] state, so eviction
+//! order survives the round trip.
+
+use crate::checkpoint::{CheckpointError, Checkpointable};
+use crate::registry::ProfileEntry;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+const SPILL_SUFFIX: &str = ".spill";
+/// Priority and event count ahead of the state
+const HEADER_LEN: usize = 9;
+
+/// Storage for spilled profile records, keyed by entity hash
+pub trait SpillStore: Send {
+    /// Store `record`, replacing any previous one for `hash`
+    fn put(&mut self, hash: u64, record: &[u8]) -> Result<(), CheckpointError>;
+    /// Remove and return the record for `hash`
+    fn take(&mut self, hash: u64) -> Result<Option<Vec<u8>>, CheckpointError>;
+    /// Drop the record for `hash`, if any
+    fn remove(&mut self, hash: u64) -> Result<(), CheckpointError>;
+    fn contains(&self, hash: u64) -> bool;
+    /// Number of records stored
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// One `<hash>.spill` file per profile in a directory
+///
+/// The hashes on disk are kept in memory, so lookups for entities that were
+/// never spilled don't touch the filesystem.
+pub struct DirectoryStore {
+    dir: PathBuf,
+    hashes: HashSet<u64>,
+}
+
+impl DirectoryStore {
+    /// Use `dir` (created if missing); records already there stay loadable,
+    /// so a restarted process picks up what the previous one spilled
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, CheckpointError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
+        let hashes = std::fs::read_dir(&dir)
+            .map_err(|e| io_error(&dir, e))?
+            .filter_map(Result::ok)
+            .filter_map(|e| {
+                let name = e.file_name();
+                let hash = name.to_str()?.strip_suffix(SPILL_SUFFIX)?;
+                u64::from_str_radix(hash, 16).ok()
+            })
+            .collect();
+        Ok(Self { dir, hashes })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, hash: u64) -> PathBuf {
+        self.dir.join(format!("{:016x}{}", hash, SPILL_SUFFIX))
+    }
+}
+
+impl SpillStore for DirectoryStore {
+    fn put(&mut self, hash: u64, record: &[u8]) -> Result<(), CheckpointError> {
+        let path = self.path(hash);
+        // Write then rename so a crash never leaves a truncated record
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, record).map_err(|e| io_error(&tmp, e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| io_error(&path, e))?;
+        self.hashes.insert(hash);
+        Ok(())
+    }
+
+    fn take(&mut self, hash: u64) -> Result<Option<Vec<u8>>, CheckpointError> {
+        if !self.contains(hash) {
+            return Ok(None);
+        }
+        let path = self.path(hash);
+        match std::fs::read(&path) {
+            Ok(record) => {
+                self.remove(hash)?;
+                Ok(Some(record))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.hashes.remove(&hash);
+                Ok(None)
+            }
+            Err(e) => Err(io_error(&path, e)),
+        }
+    }
+
+    fn remove(&mut self, hash: u64) -> Result<(), CheckpointError> {
+        if !self.contains(hash) {
+            return Ok(());
+        }
+        let path = self.path(hash);
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(&path, e)),
+            _ => {
+                self.hashes.remove(&hash);
+                Ok(())
+            }
+        }
+    }
+
+    fn contains(&self, hash: u64) -> bool {
+        self.hashes.contains(&hash)
+    }
+
+    fn len(&self) -> usize {
+        self.hashes.len()
+    }
+}
+
+/// Serialize an evicted entry into a spill record
+pub fn encode_entry<P: Checkpointable>(entry: &ProfileEntry<P>) -> Vec<u8> {
+    let state = entry.profile.to_checkpoint();
+    let mut record = Vec::with_capacity(HEADER_LEN + state.len());
+    record.push(entry.meta.priority);
+    record.extend_from_slice(&entry.meta.event_count.to_le_bytes());
+    record.extend_from_slice(&state);
+    record
+}
+
+/// Rebuild an entry from a spill record
+pub fn decode_entry<P: Checkpointable>(record: &[u8]) -> Result<ProfileEntry<P>, CheckpointError> {
+    if record.len() < HEADER_LEN {
+        return Err(CheckpointError::DeserializationFailed(format!(
+            "spill record of {} bytes",
+            record.len()
+        )));
+    }
+    let priority = record[0];
+    let mut event_count = [0u8; 8];
+    event_count.copy_from_slice(&record[1..HEADER_LEN]);
+    let mut entry =
+        ProfileEntry::new(P::from_checkpoint(&record[HEADER_LEN..])?).with_priority(priority);
+    entry.meta.event_count = u64::from_le_bytes(event_count);
+    Ok(entry)
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> CheckpointError {
+    CheckpointError::Io(format!("{}: {}", path.display(), e))
+}
@@ -0,0 +1,192 @@
+//! Structured Tracing
+//!
+//! With feature `instrument`, profiles, the registry and checkpoints report
+//! through the `tracing` crate:
+//!
+//! | Name             | Kind  | Level | Fields                                      |
+//! |------------------|-------|-------|---------------------------------------------|
+//! | `process_event`  | span  | debug | `entity`, `timestamp`                       |
+//! | `anomaly`        | event | debug | score, confidence, triggers, fired detectors|
+//! | `lifecycle`      | event | debug | lifecycle event, profile event count        |
+//! | `checkpoint`     | span  | debug | profile count / snapshot sequence           |
+//! | `restore`        | span  | debug | checkpoint bytes                            |
+//!
+//! via-sim adds a `tick` span per `SimulationEngine::tick` (its own
+//! `instrument` feature). Without the feature none of this is compiled in
+//! and the hot path is unchanged.
+//!
+//! Rust hosts install any subscriber. Hosts behind the C ABI call
+//! `via_tracing_init` with a filter such as `via_core=debug` and a callback
+//! that receives each formatted line (or none for stderr); see [`install`].
+
+use std::ffi::c_void;
+use std::io;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::prelude::*;
+
+/// Filter used when the host passes an empty one
+pub const DEFAULT_FILTER: &str = "via_core=debug,via_sim=debug";
+
+/// Receives one formatted line (without the trailing newline)
+pub type ViaLogCallback = extern "C" fn(ctx: *mut c_void, line: *const u8, len: usize);
+
+/// Why a subscriber could not be installed
+#[derive(Debug, Clone, PartialEq)]
+pub enum TelemetryError {
+    /// The filter is not `target=level` directives
+    InvalidFilter(String),
+    /// The process already has a global subscriber
+    AlreadyInstalled,
+}
+
+impl std::fmt::Display for TelemetryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidFilter(e) => write!(f, "invalid tracing filter: {}", e),
+            Self::AlreadyInstalled => write!(f, "a tracing subscriber is already installed"),
+        }
+    }
+}
+
+impl std::error::Error for TelemetryError {}
+
+/// Where formatted lines go
+#[derive(Clone, Copy)]
+pub enum LogSink {
+    Stderr,
+    /// Host callback with its context pointer
+    Callback(ViaLogCallback, *mut c_void),
+}
+
+// The host owns `ctx` and promises the callback may run on any thread
+unsafe impl Send for LogSink {}
+unsafe impl Sync for LogSink {}
+
+/// Install a global `fmt` subscriber writing to `sink`, filtered by
+/// comma-separated `target=level` directives (`DEFAULT_FILTER` when empty)
+pub fn install(filter: &str, sink: LogSink) -> Result<(), TelemetryError> {
+    let filter = if filter.trim().is_empty() {
+        DEFAULT_FILTER
+    } else {
+        filter
+    };
+    let targets: Targets =
+        filter
+            .parse()
+            .map_err(|e: tracing_subscriber::filter::ParseError| {
+                TelemetryError::InvalidFilter(e.to_string())
+            })?;
+    let layer = tracing_subscriber::fmt::layer()
+        .with_ansi(false)
+        .with_writer(sink);
+    tracing_subscriber::registry()
+        .with(layer.with_filter(targets))
+        .try_init()
+        .map_err(|_| TelemetryError::AlreadyInstalled)
+}
+
+impl<'a> MakeWriter<'a> for LogSink {
+    type Writer = LineWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LineWriter {
+            sink: *self,
+            buf: Vec::new(),
+        }
+    }
+}
+
+/// Buffers one event and hands it to the sink when dropped
+pub struct LineWriter {
+    sink: LogSink,
+    buf: Vec<u8>,
+}
+
+impl io::Write for LineWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for LineWriter {
+    fn drop(&mut self) {
+        let line = self.buf.strip_suffix(b"\n").unwrap_or(&self.buf);
+        if line.is_empty() {
+            return;
+        }
+        match self.sink {
+            LogSink::Stderr => {
+                let _ = io::Write::write_all(&mut io::stderr(), &self.buf);
+            }
+            LogSink::Callback(callback, ctx) => callback(ctx, line.as_ptr(), line.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    static LINES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    extern "C" fn collect(_ctx: *mut c_void, line: *const u8, len: usize) {
+        let line = unsafe { std::slice::from_raw_parts(line, len) };
+        LINES
+            .lock()
+            .unwrap()
+            .push(String::from_utf8_lossy(line).into_owned());
+    }
+
+    // The only test installing the process-wide subscriber
+    #[test]
+    fn test_callback_receives_filtered_lines() {
+        assert!(matches!(
+            install("via_core=loud", LogSink::Stderr),
+            Err(TelemetryError::InvalidFilter(_))
+        ));
+        install(
+            "via_core=debug",
+            LogSink::Callback(collect, std::ptr::null_mut()),
+        )
+        .unwrap();
+        assert_eq!(
+            install("", LogSink::Stderr),
+            Err(TelemetryError::AlreadyInstalled)
+        );
+
+        tracing::info!(target: "via_core::test", answer = 42, "hello");
+        tracing::info!(target: "elsewhere", "filtered out");
+        tracing::trace!(target: "via_core::test", "below the level");
+
+        #[cfg(feature = "instrument")]
+        {
+            let mut profile = crate::AnomalyProfile::default();
+            for i in 0..200u64 {
+                profile.process_with_hash(i * 1_000_000_000, 7, 10.0);
+            }
+            profile.process_with_hash(200 * 1_000_000_000, 7, 10_000.0);
+        }
+
+        let lines = LINES.lock().unwrap();
+        assert!(
+            lines.iter().any(|l| l.contains("hello answer=42")),
+            "{lines:?}"
+        );
+        assert!(!lines.iter().any(|l| l.contains("filtered out")));
+        assert!(!lines.iter().any(|l| l.contains("below the level")));
+        #[cfg(feature = "instrument")]
+        {
+            assert!(lines.iter().any(|l| l.contains("WarmupComplete")));
+            let anomaly = lines.iter().find(|l| l.contains(" anomaly ")).unwrap();
+            assert!(anomaly.contains("process_event{entity=7"), "{anomaly}");
+            assert!(anomaly.contains("fired="), "{anomaly}");
+        }
+    }
+}
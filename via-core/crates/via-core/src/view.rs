@@ -0,0 +1,144 @@
+//! Lock-Free Profile Views
+//!
+//! [`AnomalyProfile::view`](crate::AnomalyProfile::view) hands out a
+//! [`ProfileView`]: a cloneable `Send + Sync` handle other threads read
+//! without touching the profile. The thread processing events keeps
+//! `&mut AnomalyProfile` behind whatever per-profile lock the host uses;
+//! monitoring reads go to the view and never contend for that lock.
+//!
+//! - Event count, anomaly count and last timestamp are atomics stored on
+//!   every event.
+//! - Ensemble weights and detector stats are double-buffered: every
+//!   [`PUBLISH_INTERVAL`] events the processing thread fills the back buffer
+//!   and flips it to the front. It only `try_lock`s the back buffer and
+//!   skips the round if a slow reader still holds it, so processing never
+//!   waits on a reader.
+//! - Checkpoints are requested: the next processed event serializes the
+//!   profile and publishes the bytes for the view to pick up.
+//!
+//! While no view exists only the atomics are written.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Events between two published stats snapshots
+pub const PUBLISH_INTERVAL: u64 = 64;
+
+/// Slower-changing profile state as of `event_count`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StatsSnapshot {
+    pub event_count: u64,
+    pub weights: Vec<f64>,
+    /// `(detector name, stats)` as from `get_detector_stats`
+    pub detector_stats: Vec<(String, String)>,
+}
+
+/// Checkpoint the processing thread published on request
+#[derive(Debug, Clone)]
+pub struct PublishedCheckpoint {
+    /// Events the profile had processed when it was taken
+    pub event_count: u64,
+    pub bytes: Arc<Vec<u8>>,
+}
+
+/// State shared between a profile and its views
+#[derive(Default)]
+pub(crate) struct SharedState {
+    events: AtomicU64,
+    anomalies: AtomicU64,
+    last_timestamp: AtomicU64,
+    /// Index of the buffer readers use
+    front: AtomicUsize,
+    buffers: [Mutex<Arc<StatsSnapshot>>; 2],
+    checkpoint_requested: AtomicBool,
+    checkpoint: Mutex<Option<PublishedCheckpoint>>,
+}
+
+impl SharedState {
+    pub(crate) fn record_event(&self, event_count: u64, timestamp: u64, anomaly: bool) {
+        self.events.store(event_count, Ordering::Relaxed);
+        self.last_timestamp.store(timestamp, Ordering::Relaxed);
+        if anomaly {
+            self.anomalies.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Fill the back buffer and flip it to the front; false (nothing
+    /// published) if a reader still holds the back buffer
+    pub(crate) fn publish_stats(&self, snapshot: StatsSnapshot) -> bool {
+        let back = 1 - self.front.load(Ordering::Acquire);
+        let Ok(mut slot) = self.buffers[back].try_lock() else {
+            return false;
+        };
+        *slot = Arc::new(snapshot);
+        drop(slot);
+        self.front.store(back, Ordering::Release);
+        true
+    }
+
+    /// Whether a view asked for a checkpoint; clears the request
+    pub(crate) fn take_checkpoint_request(&self) -> bool {
+        self.checkpoint_requested.load(Ordering::Relaxed)
+            && self.checkpoint_requested.swap(false, Ordering::AcqRel)
+    }
+
+    /// Hand a requested checkpoint to the views; when a reader holds the
+    /// slot the request is re-armed for the next event
+    pub(crate) fn publish_checkpoint(&self, event_count: u64, bytes: Vec<u8>) {
+        match self.checkpoint.try_lock() {
+            Ok(mut slot) => {
+                *slot = Some(PublishedCheckpoint {
+                    event_count,
+                    bytes: Arc::new(bytes),
+                })
+            }
+            Err(_) => self.checkpoint_requested.store(true, Ordering::Release),
+        }
+    }
+}
+
+/// Read-only handle on a profile's published state
+#[derive(Clone)]
+pub struct ProfileView {
+    shared: Arc<SharedState>,
+}
+
+impl ProfileView {
+    pub(crate) fn new(shared: Arc<SharedState>) -> Self {
+        Self { shared }
+    }
+
+    pub fn event_count(&self) -> u64 {
+        self.shared.events.load(Ordering::Relaxed)
+    }
+
+    /// Anomalies raised since the view's profile was created or restored
+    pub fn anomaly_count(&self) -> u64 {
+        self.shared.anomalies.load(Ordering::Relaxed)
+    }
+
+    /// Timestamp of the last processed event
+    pub fn last_timestamp(&self) -> u64 {
+        self.shared.last_timestamp.load(Ordering::Relaxed)
+    }
+
+    /// Latest published weights and detector stats (at most
+    /// [`PUBLISH_INTERVAL`] events old while events flow)
+    pub fn snapshot(&self) -> Arc<StatsSnapshot> {
+        let front = self.shared.front.load(Ordering::Acquire);
+        self.shared.buffers[front].lock().unwrap().clone()
+    }
+
+    /// Ask the processing thread to publish a checkpoint with its next event
+    pub fn request_checkpoint(&self) {
+        self.shared
+            .checkpoint_requested
+            .store(true, Ordering::Release);
+    }
+
+    /// Most recent checkpoint published on request, if any
+    pub fn checkpoint(&self) -> Option<PublishedCheckpoint> {
+        self.shared.checkpoint.lock().unwrap().clone()
+    }
+}
@@ -0,0 +1,107 @@
+//! Steady-state allocation guarantee for the detection hot path
+//!
+//! Installs the counting allocator for this test binary (the `alloc-counter`
+//! feature installs it crate-wide instead) and checks that, once a profile
+//! has warmed up on normal traffic, `process_with_hash` makes no heap
+//! allocations for events it does not flag, and neither does a filtered FFI
+//! batch of such events.
+
+#[cfg(not(feature = "alloc-counter"))]
+use via_core::alloc_counter::CountingAllocator;
+use via_core::alloc_counter::count_allocations;
+use via_core::engine::AnomalyProfile;
+use via_core::via_process_batch;
+
+#[cfg(not(feature = "alloc-counter"))]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Latency-like stream: ~100ms values with jitter, ~20 events/s across a
+/// fixed pool of 50 users
+struct Traffic {
+    state: u64,
+    timestamp: u64,
+}
+
+impl Traffic {
+    fn next(&mut self) -> (u64, u64, f64) {
+        // xorshift64
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        let jitter = (self.state % 1000) as f64 / 100.0;
+        self.timestamp += 49_500_000 + self.state % 1_000_000;
+        (self.timestamp, self.state % 50, 95.0 + jitter)
+    }
+}
+
+#[test]
+fn test_steady_state_process_is_allocation_free() {
+    let mut profile = AnomalyProfile::default();
+    let mut traffic = Traffic {
+        state: 0x9E37_79B9_7F4A_7C15,
+        timestamp: 1_700_000_000_000_000_000,
+    };
+
+    // Warm up well past the profile's warmup and every detector's buffers
+    for _ in 0..5_000 {
+        let (ts, user, value) = traffic.next();
+        profile.process_with_hash(ts, user, value);
+    }
+
+    let mut checked = 0;
+    for _ in 0..2_000 {
+        let (ts, user, value) = traffic.next();
+        let (signal, allocations) =
+            count_allocations(|| profile.process_with_hash(ts, user, value));
+        if signal.is_anomaly {
+            continue;
+        }
+        assert_eq!(
+            allocations, 0,
+            "event {} allocated {} times",
+            signal.sequence, allocations
+        );
+        checked += 1;
+    }
+    assert!(checked > 1_500, "only {} events were non-anomalous", checked);
+}
+
+#[test]
+fn test_filtered_batch_of_normal_events_is_allocation_free() {
+    let mut profile = AnomalyProfile::default();
+    let mut traffic = Traffic {
+        state: 0x2545_F491_4F6C_DD1D,
+        timestamp: 1_700_000_000_000_000_000,
+    };
+    for _ in 0..5_000 {
+        let (ts, user, value) = traffic.next();
+        profile.process_with_hash(ts, user, value);
+    }
+
+    const BATCH: usize = 16;
+    let (mut timestamps, mut users, mut values) = ([0u64; BATCH], [0u64; BATCH], [0.0; BATCH]);
+    let mut codes = [0u8; BATCH];
+    let mut checked = 0;
+    for _ in 0..100 {
+        for i in 0..BATCH {
+            (timestamps[i], users[i], values[i]) = traffic.next();
+        }
+        let (emitted, allocations) = count_allocations(|| {
+            via_process_batch(
+                &mut profile,
+                timestamps.as_ptr(),
+                users.as_ptr(),
+                values.as_ptr(),
+                BATCH,
+                codes.as_mut_ptr(),
+            )
+        });
+        if emitted > 0 {
+            continue;
+        }
+        assert_eq!(allocations, 0, "batch allocated {} times", allocations);
+        checked += 1;
+    }
+    assert!(checked > 50, "only {} batches were all normal", checked);
+}
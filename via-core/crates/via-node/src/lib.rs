@@ -0,0 +1,434 @@
+//! VIA-Node: Node-API binding for VIA-Core
+//!
+//! Exposes the engine to Node and Bun as JS classes instead of raw pointers:
+//! - `Profile`: one `AnomalyProfile`
+//! - `Registry`: memory-bounded per-entity profiles (`ProfileRegistry`)
+//! - `Simulation`: the via-sim `SimulationEngine`, for tests and demos
+//!
+//! Each class owns its Rust value and drops it when the JS object is
+//! collected, so there is nothing to free by hand. Timestamps and entity
+//! hashes are `bigint` since nanosecond timestamps and 64-bit hashes do not
+//! fit a JS number. The C ABI in via-core stays for other hosts.
+//!
+//! Build with `napi build --release` (from `@napi-rs/cli`) in this crate.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use via_core::checkpoint::{self, Checkpointable};
+use via_core::engine::{AnomalyProfile, ProfileConfig};
+use via_core::registry::{ProfileRegistry, RegistryConfig};
+use via_core::signal::AnomalySignal;
+use via_sim::SimulationEngine;
+
+/// Signal returned to JS, flattened from `AnomalySignal`
+#[napi(object)]
+pub struct Signal {
+    pub entity_hash: BigInt,
+    pub timestamp: BigInt,
+    pub sequence: i64,
+    pub is_anomaly: bool,
+    pub suppressed_by_cooldown: bool,
+    /// 0 = None .. 4 = Critical
+    pub severity: u32,
+    pub ensemble_score: f64,
+    pub confidence: f64,
+    /// Feedback-calibrated probability, absent while uncalibrated
+    pub calibrated_probability: Option<f64>,
+    pub primary_detector: String,
+    pub detectors_fired: u32,
+    /// Per-detector scores in `DetectorId` order
+    pub detector_scores: Vec<f64>,
+    pub raw_value: f64,
+    /// Values that would not have fired (see `AnomalySignal::normal_range`);
+    /// an absent bound is open
+    pub normal_low: Option<f64>,
+    pub normal_high: Option<f64>,
+    /// Human-readable summary (see `AnomalySignal::reason`)
+    pub reason: String,
+}
+
+impl From<AnomalySignal> for Signal {
+    fn from(signal: AnomalySignal) -> Self {
+        Self {
+            entity_hash: BigInt::from(signal.entity_hash),
+            timestamp: BigInt::from(signal.timestamp),
+            sequence: signal.sequence as i64,
+            is_anomaly: signal.is_anomaly,
+            suppressed_by_cooldown: signal.suppressed_by_cooldown,
+            severity: signal.severity as u32,
+            ensemble_score: signal.ensemble_score,
+            confidence: signal.confidence,
+            calibrated_probability: signal.calibrated_probability.map(f64::from),
+            primary_detector: signal.primary_detector_name().to_string(),
+            detectors_fired: signal.attribution.detectors_fired as u32,
+            detector_scores: signal
+                .detector_scores
+                .iter()
+                .map(|s| s.score as f64)
+                .collect(),
+            raw_value: signal.raw_value,
+            normal_low: signal.normal_range.and_then(|r| r.low),
+            normal_high: signal.normal_range.and_then(|r| r.high),
+            reason: signal.reason(),
+        }
+    }
+}
+
+/// `bigint` to `u64`, rejecting negative or wider values
+fn to_u64(value: &BigInt, what: &str) -> Result<u64> {
+    let (negative, value, lossless) = value.get_u64();
+    if negative || !lossless {
+        return Err(Error::new(
+            Status::InvalidArg,
+            format!("{what} must fit in an unsigned 64-bit integer"),
+        ));
+    }
+    Ok(value)
+}
+
+/// Entity hash of a string id, as `via_hash_string` computes it
+fn hash_id(id: &str) -> u64 {
+    xxhash_rust::xxh3::xxh3_64(id.as_bytes())
+}
+
+/// `base` with the fields of the JSON object `json` replaced, as
+/// `via_update_config` does
+fn parse_config(base: &ProfileConfig, json: &str) -> Result<ProfileConfig> {
+    let invalid = |msg: String| Error::new(Status::InvalidArg, msg);
+    let patch: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(json).map_err(|e| invalid(format!("config: {e}")))?;
+    let mut config = serde_json::to_value(base).map_err(|e| invalid(e.to_string()))?;
+    let fields = config
+        .as_object_mut()
+        .ok_or_else(|| invalid("config is not an object".to_string()))?;
+    for (field, value) in patch {
+        if !fields.contains_key(&field) {
+            return Err(invalid(format!("unknown ProfileConfig field '{field}'")));
+        }
+        fields.insert(field, value);
+    }
+    let config: ProfileConfig =
+        serde_json::from_value(config).map_err(|e| invalid(format!("config: {e}")))?;
+    config
+        .validate_decision()
+        .map_err(|e| invalid(e.to_string()))?;
+    Ok(config)
+}
+
+// ============================================================================
+// PROFILE
+// ============================================================================
+
+/// One anomaly profile
+#[napi]
+pub struct Profile {
+    inner: AnomalyProfile,
+}
+
+#[napi]
+impl Profile {
+    /// Create a profile, optionally overriding `ProfileConfig` fields with a
+    /// JSON object
+    #[napi(constructor)]
+    pub fn new(config_json: Option<String>) -> Result<Self> {
+        let inner = match config_json {
+            Some(json) => {
+                AnomalyProfile::with_config(parse_config(&ProfileConfig::default(), &json)?)
+            }
+            None => AnomalyProfile::default(),
+        };
+        Ok(Self { inner })
+    }
+
+    /// Restore a profile from `checkpoint()` bytes
+    #[napi(factory)]
+    pub fn from_checkpoint(data: Buffer) -> Result<Self> {
+        AnomalyProfile::from_checkpoint(&data)
+            .map(|inner| Self { inner })
+            .map_err(|e| Error::new(Status::InvalidArg, e.to_string()))
+    }
+
+    /// Process an event for the entity `unique_id`
+    #[napi]
+    pub fn process(&mut self, timestamp: BigInt, unique_id: String, value: f64) -> Result<Signal> {
+        let timestamp = to_u64(&timestamp, "timestamp")?;
+        Ok(self
+            .inner
+            .process_with_hash(timestamp, hash_id(&unique_id), value)
+            .into())
+    }
+
+    /// Process an event for an already hashed entity
+    #[napi]
+    pub fn process_hash(
+        &mut self,
+        timestamp: BigInt,
+        unique_id_hash: BigInt,
+        value: f64,
+    ) -> Result<Signal> {
+        let timestamp = to_u64(&timestamp, "timestamp")?;
+        let hash = to_u64(&unique_id_hash, "uniqueIdHash")?;
+        Ok(self.inner.process_with_hash(timestamp, hash, value).into())
+    }
+
+    /// Hot-reload decision settings from a JSON object of `ProfileConfig`
+    /// fields; structural changes and out-of-range values throw
+    #[napi]
+    pub fn update_config(&mut self, config_json: String) -> Result<()> {
+        let config = parse_config(self.inner.config(), &config_json)?;
+        self.inner
+            .update_config(config)
+            .map_err(|e| Error::new(Status::InvalidArg, e.to_string()))
+    }
+
+    /// Binary checkpoint of the profile
+    #[napi]
+    pub fn checkpoint(&self) -> Buffer {
+        self.inner.to_checkpoint().into()
+    }
+
+    #[napi(getter)]
+    pub fn event_count(&self) -> i64 {
+        self.inner.event_count() as i64
+    }
+}
+
+// ============================================================================
+// REGISTRY
+// ============================================================================
+
+/// Per-entity profiles with LRU eviction
+#[napi]
+pub struct Registry {
+    profiles: ProfileRegistry<AnomalyProfile>,
+    /// Configuration for profiles the registry creates
+    profile_config: ProfileConfig,
+}
+
+#[napi]
+impl Registry {
+    /// Create a registry holding at most `max_profiles` (default when absent
+    /// or 0), creating profiles from the optional JSON config
+    #[napi(constructor)]
+    pub fn new(max_profiles: Option<u32>, config_json: Option<String>) -> Result<Self> {
+        let mut config = RegistryConfig::default();
+        if let Some(max) = max_profiles.filter(|&max| max > 0) {
+            config.max_profiles = max as usize;
+        }
+        let profile_config = match config_json {
+            Some(json) => parse_config(&ProfileConfig::default(), &json)?,
+            None => ProfileConfig::default(),
+        };
+        Ok(Self {
+            profiles: ProfileRegistry::with_config(config),
+            profile_config,
+        })
+    }
+
+    /// Process an event against the profile keyed by `entity_id`, creating it
+    /// on first use; the signal's entity is the same id
+    #[napi]
+    pub fn process(&mut self, timestamp: BigInt, entity_id: String, value: f64) -> Result<Signal> {
+        let timestamp = to_u64(&timestamp, "timestamp")?;
+        let hash = hash_id(&entity_id);
+        let profile_config = &self.profile_config;
+        let profile = self
+            .profiles
+            .get_or_create(hash, || AnomalyProfile::with_config(profile_config.clone()));
+        Ok(profile.process_with_hash(timestamp, hash, value).into())
+    }
+
+    /// Number of profiles held, like `Map.size`
+    #[napi(getter)]
+    pub fn size(&self) -> u32 {
+        self.profiles.len() as u32
+    }
+
+    /// Checkpoint every profile as one blob (see
+    /// `via_registry_checkpoint_all`)
+    #[napi]
+    pub fn checkpoint(&self) -> Result<Buffer> {
+        let mut blob = Vec::new();
+        checkpoint::write_registry(&self.profiles, &mut blob)
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+        Ok(blob.into())
+    }
+
+    /// Restore profiles from a `checkpoint()` blob, returning how many
+    #[napi]
+    pub fn restore(&mut self, data: Buffer) -> Result<u32> {
+        let mut blob: &[u8] = &data;
+        checkpoint::read_registry(&mut blob, &mut self.profiles)
+            .map(|count| count as u32)
+            .map_err(|e| Error::new(Status::InvalidArg, e.to_string()))
+    }
+}
+
+// ============================================================================
+// SIMULATION
+// ============================================================================
+
+/// Simulated OTel log stream with ground truth
+#[napi]
+pub struct Simulation {
+    inner: SimulationEngine,
+}
+
+#[napi]
+impl Simulation {
+    /// Create a simulation, reproducible when `seed` is given
+    #[napi(constructor)]
+    pub fn new(seed: Option<BigInt>) -> Result<Self> {
+        let inner = match seed {
+            Some(seed) => SimulationEngine::new_deterministic(to_u64(&seed, "seed")?),
+            None => SimulationEngine::new(),
+        };
+        Ok(Self { inner })
+    }
+
+    /// Start with `baseline_scenario` as normal traffic
+    #[napi]
+    pub fn start(&mut self, baseline_scenario: String) {
+        self.inner.start(&baseline_scenario);
+    }
+
+    #[napi]
+    pub fn stop(&mut self) {
+        self.inner.stop();
+    }
+
+    /// Add a scenario by name; false if it is unknown
+    #[napi]
+    pub fn add_scenario(&mut self, name: String) -> bool {
+        self.inner.add_scenario_by_name(&name)
+    }
+
+    /// Inject an anomaly scenario for `duration_ms`, returning its id
+    #[napi]
+    pub fn inject_anomaly(&mut self, scenario_name: String, duration_ms: u32) -> Option<String> {
+        self.inner
+            .inject_anomaly(&scenario_name, duration_ms as u64)
+    }
+
+    /// Scale every scenario's event rate
+    #[napi]
+    pub fn set_rate_scale(&mut self, factor: f64) {
+        self.inner.set_rate_scale(factor);
+    }
+
+    /// Advance simulated time and return the batch (logs, ground truth and
+    /// metadata) as a plain object
+    #[napi]
+    pub fn tick(&mut self, delta_ms: u32) -> Result<serde_json::Value> {
+        let batch = self.inner.tick_ms(delta_ms as u64);
+        serde_json::to_value(&batch).map_err(|e| Error::from_reason(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use via_core::signal::NUM_DETECTORS;
+
+    #[test]
+    fn test_to_u64_rejects_negative_and_wide_values() {
+        assert_eq!(
+            to_u64(&BigInt::from(u64::MAX), "timestamp").unwrap(),
+            u64::MAX
+        );
+
+        let negative = BigInt::from(-1i64);
+        let wide = BigInt {
+            sign_bit: false,
+            words: vec![0, 1],
+        };
+        for value in [negative, wide] {
+            let err = to_u64(&value, "timestamp").unwrap_err();
+            assert_eq!(err.status, Status::InvalidArg);
+            assert!(
+                err.reason.starts_with("timestamp must fit"),
+                "{}",
+                err.reason
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_id_matches_the_c_abi() {
+        assert_eq!(
+            hash_id("checkout"),
+            via_core::via_hash_string(c"checkout".as_ptr())
+        );
+    }
+
+    #[test]
+    fn test_parse_config_patches_known_fields() {
+        let base = ProfileConfig::default();
+        let config = parse_config(&base, r#"{"confidence_threshold": 0.9}"#).unwrap();
+        assert_eq!(config.confidence_threshold, 0.9);
+        assert_eq!(config.hw_alpha, base.hw_alpha);
+
+        for (json, message) in [
+            (
+                r#"{"hw_alphaa": 0.3}"#,
+                "unknown ProfileConfig field 'hw_alphaa'",
+            ),
+            (r#"{"period": "daily"}"#, "config: "),
+            ("[1, 2]", "config: "),
+            (r#"{"confidence_threshold": 1.5}"#, "confidence_threshold"),
+        ] {
+            let err = parse_config(&base, json).unwrap_err();
+            assert_eq!(err.status, Status::InvalidArg, "{json}");
+            assert!(err.reason.contains(message), "{json}: {}", err.reason);
+        }
+        assert!(Profile::new(Some("{".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_signal_shape() {
+        let mut profile = Profile::new(None).unwrap();
+        let signal = profile
+            .process(BigInt::from(1_000u64), "checkout".to_string(), 42.0)
+            .unwrap();
+        assert_eq!(signal.entity_hash.get_u64().1, hash_id("checkout"));
+        assert_eq!(signal.timestamp.get_u64().1, 1_000);
+        assert_eq!(signal.detector_scores.len(), NUM_DETECTORS);
+        assert_eq!(signal.raw_value, 42.0);
+        assert!(signal.severity <= 4);
+        assert_eq!(profile.event_count(), 1);
+
+        let Err(err) = profile.process_hash(BigInt::from(1u64), BigInt::from(-5i64), 1.0) else {
+            panic!("a negative hash is accepted");
+        };
+        assert!(err.reason.starts_with("uniqueIdHash"), "{}", err.reason);
+    }
+
+    #[test]
+    fn test_registry_keeps_a_profile_per_entity() {
+        let mut registry = Registry::new(None, None).unwrap();
+        for (i, entity) in ["a", "b", "a"].into_iter().enumerate() {
+            let signal = registry
+                .process(BigInt::from(i as u64), entity.to_string(), 1.0)
+                .unwrap();
+            assert_eq!(signal.entity_hash.get_u64().1, hash_id(entity));
+        }
+        assert_eq!(registry.size(), 2);
+
+        // A bad profile config fails the constructor
+        let err = Registry::new(None, Some(r#"{"nope": 1}"#.to_string())).err();
+        assert!(err.unwrap().reason.contains("unknown ProfileConfig field"));
+    }
+
+    #[test]
+    fn test_simulation_tick_is_a_plain_object() {
+        let mut sim = Simulation::new(Some(BigInt::from(7u64))).unwrap();
+        sim.start("normal_traffic".to_string());
+        assert!(!sim.add_scenario("no_such_scenario".to_string()));
+        let batch = sim.tick(100).unwrap();
+        for key in ["logs", "ground_truth", "metadata"] {
+            assert!(batch.get(key).is_some(), "{key}");
+        }
+        assert_eq!(batch["metadata"]["tick_ns"], 100_000_000);
+        assert!(Simulation::new(Some(BigInt::from(-1i64))).is_err());
+    }
+}
@@ -0,0 +1,345 @@
+//! VIA-Py: Python bindings for via-sim and via-bench
+//!
+//! Lets notebooks drive the simulator and benchmark runner directly instead
+//! of shelling out to the CLI and parsing JSON:
+//! - `Simulation`: the via-sim `SimulationEngine`; `tick()` returns numpy
+//!   arrays of timestamps, values and ground-truth labels
+//! - `run_benchmark()`: one `BenchmarkRunner` run, returning the summary
+//!   next to per-event numpy arrays of scores, predictions and labels
+//!
+//! Build with `maturin develop --release` in this crate.
+
+use numpy::{PyArray1, PyArrayMethods};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use via_bench::pipeline::scenario_by_name;
+use via_bench::{BenchmarkConfig, BenchmarkRunner};
+use via_core::signal::NUM_DETECTORS;
+use via_sim::{SimulationBatch, SimulationEngine};
+
+/// `json.loads(text)`
+fn json_loads<'py>(py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyAny>> {
+    py.import("json")?.call_method1("loads", (text,))
+}
+
+/// `config` with the fields of `overrides` replaced; unknown fields are
+/// rejected like `via_update_config` does
+fn apply_overrides(
+    config: BenchmarkConfig,
+    overrides: &Bound<'_, PyDict>,
+) -> PyResult<BenchmarkConfig> {
+    let patch: String = overrides
+        .py()
+        .import("json")?
+        .call_method1("dumps", (overrides,))?
+        .extract()?;
+    patch_config(config, &patch).map_err(PyValueError::new_err)
+}
+
+/// `config` with the fields of the JSON object `patch` replaced
+fn patch_config(config: BenchmarkConfig, patch: &str) -> Result<BenchmarkConfig, String> {
+    let patch: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(patch).map_err(|e| format!("overrides: {e}"))?;
+
+    let mut value = serde_json::to_value(config).map_err(|e| e.to_string())?;
+    let fields = value
+        .as_object_mut()
+        .ok_or_else(|| "benchmark config is not an object".to_string())?;
+    for (field, v) in patch {
+        if !fields.contains_key(&field) {
+            return Err(format!("unknown BenchmarkConfig field '{field}'"));
+        }
+        fields.insert(field, v);
+    }
+    serde_json::from_value(value).map_err(|e| format!("benchmark config: {e}"))
+}
+
+/// A batch's logs as columns, one entry per log
+#[derive(Default)]
+struct LogColumns {
+    timestamps: Vec<u64>,
+    values: Vec<f64>,
+    labels: Vec<bool>,
+    services: Vec<String>,
+    anomaly_ids: Vec<Option<String>>,
+}
+
+impl LogColumns {
+    fn from_batch(batch: &SimulationBatch) -> Self {
+        let mut columns = Self::default();
+        for log in batch.records() {
+            columns
+                .timestamps
+                .push(log.timeUnixNano.parse::<u64>().unwrap_or(0));
+            columns.values.push(log.metric_value());
+            columns.labels.push(log.isGroundTruthAnomaly);
+            columns
+                .services
+                .push(log.service_name().unwrap_or("unknown").to_string());
+            columns.anomaly_ids.push(log.anomalyId.clone());
+        }
+        columns
+    }
+}
+
+/// A benchmark run: the `--output` summary plus one entry per scored event
+#[derive(Default)]
+struct RunColumns {
+    summary: String,
+    timestamps: Vec<u64>,
+    scores: Vec<f64>,
+    predictions: Vec<bool>,
+    labels: Vec<bool>,
+    severity: Vec<u8>,
+    /// Events x detectors, row-major
+    detector_scores: Vec<f32>,
+}
+
+impl RunColumns {
+    fn run(config: BenchmarkConfig) -> Result<Self, String> {
+        let mut runner = BenchmarkRunner::new();
+        let results = runner.run(config)?;
+        let mut columns = Self {
+            summary: runner.export_json(&results),
+            ..Default::default()
+        };
+        for (signal, label) in runner.scored_events() {
+            columns.timestamps.push(signal.timestamp);
+            columns.scores.push(signal.ensemble_score);
+            columns.predictions.push(signal.is_anomaly);
+            columns.labels.push(label);
+            columns.severity.push(signal.severity as u8);
+            columns
+                .detector_scores
+                .extend(signal.detector_scores.iter().map(|s| s.score));
+        }
+        Ok(columns)
+    }
+}
+
+/// Simulated OTel log stream with ground truth
+#[pyclass(name = "Simulation", unsendable)]
+struct Simulation {
+    inner: SimulationEngine,
+}
+
+#[pymethods]
+impl Simulation {
+    /// Create a simulation, reproducible when `seed` is given
+    #[new]
+    #[pyo3(signature = (seed = None))]
+    fn new(seed: Option<u64>) -> Self {
+        let inner = match seed {
+            Some(seed) => SimulationEngine::new_deterministic(seed),
+            None => SimulationEngine::new(),
+        };
+        Self { inner }
+    }
+
+    /// Start with `baseline_scenario` as normal traffic
+    #[pyo3(signature = (baseline_scenario = "normal_traffic"))]
+    fn start(&mut self, baseline_scenario: &str) {
+        self.inner.start(baseline_scenario);
+    }
+
+    fn stop(&mut self) {
+        self.inner.stop();
+    }
+
+    /// Add a background scenario by name; False if it is unknown
+    fn add_scenario(&mut self, name: &str) -> bool {
+        self.inner.add_scenario_by_name(name)
+    }
+
+    /// Schedule an anomaly scenario `start_offset_ms` from now, returning
+    /// its anomaly id
+    fn schedule_anomaly(
+        &mut self,
+        scenario_name: &str,
+        start_offset_ms: u64,
+        duration_ms: u64,
+    ) -> PyResult<String> {
+        self.inner
+            .schedule_anomaly(
+                scenario_name,
+                start_offset_ms * 1_000_000,
+                duration_ms * 1_000_000,
+            )
+            .ok_or_else(|| PyValueError::new_err(format!("unknown scenario '{scenario_name}'")))
+    }
+
+    /// Start an anomaly scenario now, returning its anomaly id
+    fn inject_anomaly(&mut self, scenario_name: &str, duration_ms: u64) -> PyResult<String> {
+        self.inner
+            .inject_anomaly(scenario_name, duration_ms)
+            .ok_or_else(|| PyValueError::new_err(format!("unknown scenario '{scenario_name}'")))
+    }
+
+    /// Scale every scenario's event rate
+    fn set_rate_scale(&mut self, factor: f64) {
+        self.inner.set_rate_scale(factor);
+    }
+
+    /// Current simulated time (ns since epoch)
+    #[getter]
+    fn time_ns(&self) -> u64 {
+        self.inner.current_time()
+    }
+
+    /// Advance simulated time by `delta_ms` and return the batch's logs as
+    /// columns: `timestamps` (uint64 ns), `values` (float64), `labels`
+    /// (bool ground truth), `services` and `anomaly_ids` (lists)
+    fn tick<'py>(&mut self, py: Python<'py>, delta_ms: u64) -> PyResult<Bound<'py, PyDict>> {
+        let logs = LogColumns::from_batch(&self.inner.tick_ms(delta_ms));
+
+        let columns = PyDict::new(py);
+        columns.set_item("timestamps", PyArray1::from_vec(py, logs.timestamps))?;
+        columns.set_item("values", PyArray1::from_vec(py, logs.values))?;
+        columns.set_item("labels", PyArray1::from_vec(py, logs.labels))?;
+        columns.set_item("services", PyList::new(py, logs.services)?)?;
+        columns.set_item("anomaly_ids", PyList::new(py, logs.anomaly_ids)?)?;
+        Ok(columns)
+    }
+
+    /// Advance simulated time by `delta_ms` and return the full batch as
+    /// OTLP JSON
+    fn tick_json(&mut self, delta_ms: u64) -> String {
+        self.inner.tick_json(delta_ms * 1_000_000)
+    }
+}
+
+/// Run a via-bench scenario (`quick`, `mixed`, `security`, ... as on the
+/// CLI; unknown names run `quick`) with optional `BenchmarkConfig` field
+/// overrides
+///
+/// Returns a dict with `results` (the summary `--output` writes) and one
+/// entry per event in detection order: `timestamps` (uint64 ns), `scores`
+/// (ensemble score), `predictions` and `labels` (bool), `severity` (uint8)
+/// and `detector_scores` (events x detectors, float32).
+#[pyfunction]
+#[pyo3(signature = (scenario = "quick", overrides = None))]
+fn run_benchmark<'py>(
+    py: Python<'py>,
+    scenario: &str,
+    overrides: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let mut config = scenario_by_name(scenario);
+    if let Some(overrides) = overrides {
+        config = apply_overrides(config, overrides)?;
+    }
+    config.quiet = true;
+    config.spill_events = true;
+
+    let run = py
+        .detach(move || RunColumns::run(config))
+        .map_err(PyValueError::new_err)?;
+
+    let events = run.timestamps.len();
+    let columns = PyDict::new(py);
+    columns.set_item("results", json_loads(py, &run.summary)?)?;
+    columns.set_item("timestamps", PyArray1::from_vec(py, run.timestamps))?;
+    columns.set_item("scores", PyArray1::from_vec(py, run.scores))?;
+    columns.set_item("predictions", PyArray1::from_vec(py, run.predictions))?;
+    columns.set_item("labels", PyArray1::from_vec(py, run.labels))?;
+    columns.set_item("severity", PyArray1::from_vec(py, run.severity))?;
+    columns.set_item(
+        "detector_scores",
+        PyArray1::from_vec(py, run.detector_scores).reshape([events, NUM_DETECTORS])?,
+    )?;
+    Ok(columns)
+}
+
+/// Names of the detectors, in `detector_scores` column order
+#[pyfunction]
+fn detector_names() -> Vec<&'static str> {
+    via_core::signal::DetectorId::ALL
+        .iter()
+        .map(|d| d.name())
+        .collect()
+}
+
+#[pymodule]
+fn via_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Simulation>()?;
+    m.add_function(wrap_pyfunction!(run_benchmark, m)?)?;
+    m.add_function(wrap_pyfunction!(detector_names, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_config_replaces_known_fields() {
+        let config = patch_config(
+            BenchmarkConfig::default(),
+            r#"{"duration_secs": 7, "per_service": true}"#,
+        )
+        .unwrap();
+        assert_eq!(config.duration_secs, 7);
+        assert!(config.per_service);
+
+        for (patch, message) in [
+            (
+                r#"{"duration": 7}"#,
+                "unknown BenchmarkConfig field 'duration'",
+            ),
+            (r#"{"duration_secs": "long"}"#, "benchmark config: "),
+            ("[7]", "overrides: "),
+        ] {
+            let err = patch_config(BenchmarkConfig::default(), patch).unwrap_err();
+            assert!(err.contains(message), "{patch}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_log_columns_line_up() {
+        let mut sim = SimulationEngine::new_deterministic(5);
+        sim.start("normal_traffic");
+        let anomaly = sim.inject_anomaly("traffic_spike", 1_000).unwrap();
+        let batch = sim.tick_ms(100);
+        let logs = LogColumns::from_batch(&batch);
+
+        let n = batch.record_count();
+        assert!(n > 0);
+        assert_eq!(logs.values.len(), n);
+        assert_eq!(logs.labels.len(), n);
+        assert_eq!(logs.services.len(), n);
+        assert_eq!(logs.anomaly_ids.len(), n);
+        let end = batch.metadata.timestamp_ns;
+        assert!(logs.timestamps.iter().all(|&t| t <= end));
+        assert!(logs.timestamps.iter().any(|&t| t > 0));
+        for (label, id) in logs.labels.iter().zip(&logs.anomaly_ids) {
+            assert_eq!(*label, id.as_deref() == Some(anomaly.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_run_columns_shape_and_errors() {
+        let config = BenchmarkConfig {
+            duration_secs: 10,
+            quiet: true,
+            spill_events: true,
+            ..Default::default()
+        };
+        let run = RunColumns::run(config.clone()).unwrap();
+        let events = run.timestamps.len();
+        assert!(events > 0);
+        assert_eq!(run.scores.len(), events);
+        assert_eq!(run.predictions.len(), events);
+        assert_eq!(run.labels.len(), events);
+        assert_eq!(run.severity.len(), events);
+        assert_eq!(run.detector_scores.len(), events * NUM_DETECTORS);
+        let summary: serde_json::Value = serde_json::from_str(&run.summary).unwrap();
+        assert!(summary.is_object());
+
+        let bad = BenchmarkConfig {
+            profile_overrides: [("hw_alphaa".to_string(), 0.3)].into(),
+            ..config
+        };
+        let err = RunColumns::run(bad).err().unwrap();
+        assert!(err.contains("hw_alphaa"), "{err}");
+    }
+}
@@ -5,12 +5,18 @@
 //! - Inject anomalies
 //! - Get status and metrics
 //! - Dashboard data streaming
+//! - Live anomaly signal feed (`/signals`, SSE on `/signals/stream`)
+//! - Rate-limited ticks (see `pacing`), however many clients drive them
 
 use crate::core::{GroundTruth, SimulationBatch};
 use crate::engine::{EngineState, SimulationEngine};
+use crate::pacing::{Pacer, PacingConfig};
 use crate::scenarios;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
+use via_core::engine::AnomalyProfile;
+use via_core::signal::{AnomalySignal, DetectorId, NUM_DETECTORS, Severity};
 
 /// HTTP API Server Configuration
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -23,6 +29,9 @@ pub struct ApiConfig {
     pub cors_enabled: bool,
     /// Tick interval in milliseconds (default: 100)
     pub tick_interval_ms: u64,
+    /// Limits on the logs and simulated time a tick may produce
+    #[serde(default)]
+    pub pacing: PacingConfig,
 }
 
 impl Default for ApiConfig {
@@ -32,6 +41,7 @@ impl Default for ApiConfig {
             port: 8080,
             cors_enabled: true,
             tick_interval_ms: 100,
+            pacing: PacingConfig::default(),
         }
     }
 }
@@ -41,16 +51,171 @@ pub struct SimulationState {
     pub engine: SimulationEngine,
     pub config: ApiConfig,
     pub tick_count: u64,
+    /// Most recent batch produced by a tick, shown on the dashboard
+    pub last_batch: Option<SimulationBatch>,
+    /// Tier-1 detection over every ticked batch, feeding `/signals`
+    pub detection: LiveDetectionEngine,
+    /// Rate limit shared by the ticker and every client's `/tick`
+    pub pacer: Pacer,
 }
 
 impl SimulationState {
     pub fn new(config: ApiConfig) -> Self {
         Self {
             engine: SimulationEngine::new(),
+            pacer: Pacer::new(config.pacing.clone()),
             config,
             tick_count: 0,
+            last_batch: None,
+            detection: LiveDetectionEngine::new(),
         }
     }
+
+    /// Advance the engine by `delta_ns`, run detection over the new logs, and
+    /// keep the batch for the dashboard
+    ///
+    /// The pacer clamps `delta_ns` and drops logs over the rate limit; the
+    /// batch's `dropped_events` says how many.
+    pub fn tick(&mut self, delta_ns: u64) -> &SimulationBatch {
+        let mut batch = self.engine.tick(self.pacer.clamp_delta(delta_ns));
+        self.pacer.admit(&mut batch);
+        self.detection.observe(&batch);
+        self.tick_count += 1;
+        self.last_batch.insert(batch)
+    }
+}
+
+/// Recent anomalous signals kept for clients to catch up from
+const SIGNAL_BUFFER_SIZE: usize = 1024;
+
+/// Runs simulated logs through a Tier-1 profile as they are generated
+///
+/// Anomalous signals are numbered from 1 and buffered so SSE clients can
+/// resume from the last id they saw (`Last-Event-ID` or `?after=`).
+pub struct LiveDetectionEngine {
+    profile: AnomalyProfile,
+    signals: VecDeque<(u64, AnomalySignal)>,
+    last_id: u64,
+}
+
+impl Default for LiveDetectionEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiveDetectionEngine {
+    pub fn new() -> Self {
+        Self {
+            profile: AnomalyProfile::default(),
+            signals: VecDeque::with_capacity(SIGNAL_BUFFER_SIZE),
+            last_id: 0,
+        }
+    }
+
+    /// Process every log in `batch`, returning how many were anomalous
+    pub fn observe(&mut self, batch: &SimulationBatch) -> usize {
+        let mut anomalies = 0;
+        for resource_log in &batch.logs.resourceLogs {
+            for scope_log in &resource_log.scopeLogs {
+                for log in &scope_log.logRecords {
+                    let timestamp = log.timeUnixNano.parse().unwrap_or(0);
+                    let signal = self
+                        .profile
+                        .process(timestamp, &log.traceId, log.metric_value());
+                    if signal.is_anomaly {
+                        self.push(signal);
+                        anomalies += 1;
+                    }
+                }
+            }
+        }
+        anomalies
+    }
+
+    fn push(&mut self, signal: AnomalySignal) {
+        if self.signals.len() == SIGNAL_BUFFER_SIZE {
+            self.signals.pop_front();
+        }
+        self.last_id += 1;
+        self.signals.push_back((self.last_id, signal));
+    }
+
+    /// Id of the newest signal (0 before the first one)
+    pub fn last_id(&self) -> u64 {
+        self.last_id
+    }
+
+    /// Buffered signals newer than `after` that pass `filter`, oldest first
+    pub fn signals_after<'a>(
+        &'a self,
+        after: u64,
+        filter: &'a SignalFilter,
+    ) -> impl Iterator<Item = &'a (u64, AnomalySignal)> + 'a {
+        self.signals
+            .iter()
+            .skip_while(move |(id, _)| *id <= after)
+            .filter(move |(_, signal)| filter.matches(signal))
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// Signal feed filter, parsed from `?severity=high&detector=volume`
+#[derive(Debug, Clone, Default)]
+pub struct SignalFilter {
+    /// Minimum severity to pass
+    pub min_severity: Severity,
+    /// Only signals where this detector fired
+    pub detector: Option<DetectorId>,
+}
+
+impl SignalFilter {
+    /// Parse `severity` (`low`..`critical`) and `detector` (e.g. `burst`,
+    /// `changepoint`) from a query string; other keys are ignored
+    pub fn from_query(query: &str) -> Result<Self, String> {
+        let mut filter = Self::default();
+        for (key, value) in query_pairs(query) {
+            match key {
+                "severity" => {
+                    filter.min_severity = match value.to_ascii_lowercase().as_str() {
+                        "none" => Severity::None,
+                        "low" => Severity::Low,
+                        "medium" => Severity::Medium,
+                        "high" => Severity::High,
+                        "critical" => Severity::Critical,
+                        _ => return Err(format!("Unknown severity: {}", value)),
+                    }
+                }
+                "detector" => {
+                    let detector = (0..NUM_DETECTORS as u8)
+                        .filter_map(DetectorId::from_u8)
+                        .find(|d| format!("{:?}", d).eq_ignore_ascii_case(value))
+                        .ok_or_else(|| format!("Unknown detector: {}", value))?;
+                    filter.detector = Some(detector);
+                }
+                _ => {}
+            }
+        }
+        Ok(filter)
+    }
+
+    pub fn matches(&self, signal: &AnomalySignal) -> bool {
+        signal.severity >= self.min_severity
+            && self.detector.is_none_or(|d| signal.detector_fired(d))
+    }
+}
+
+pub(crate) fn query_pairs(query: &str) -> impl Iterator<Item = (&str, &str)> {
+    query.split('&').filter_map(|pair| pair.split_once('='))
+}
+
+/// Format one signal as a Server-Sent Events message
+pub fn sse_event(id: u64, signal: &AnomalySignal) -> String {
+    let data = serde_json::to_string(signal).unwrap_or_else(|_| "{}".to_string());
+    format!("id: {}\nevent: anomaly\ndata: {}\n\n", id, data)
 }
 
 /// Thread-safe handle to simulation state
@@ -292,21 +457,68 @@ pub fn handle_get_status(state: &SharedState) -> ApiResponse<SimulationStatus> {
 /// Handle GET /dashboard - get full dashboard state
 pub fn handle_get_dashboard(state: &SharedState) -> ApiResponse<DashboardState> {
     let mut state = state.lock().unwrap();
-    // Generate a quick tick to get current data
-    let batch = state.engine.tick(0);
-    let dashboard = DashboardState::from_batch(&batch, &state.engine);
+    // Prefer the latest ticked batch; otherwise generate a quick tick
+    let dashboard = match &state.last_batch {
+        Some(batch) => DashboardState::from_batch(batch, &state.engine),
+        None => {
+            let batch = state.engine.tick(0);
+            DashboardState::from_batch(&batch, &state.engine)
+        }
+    };
     ApiResponse::success(dashboard)
 }
 
 /// Handle POST /tick - advance simulation by one tick (for manual control)
 pub fn handle_tick(state: &SharedState, delta_ms: u64) -> ApiResponse<SimulationBatch> {
     let mut state = state.lock().unwrap();
-    let batch = state.engine.tick_ms(delta_ms);
-    state.tick_count += 1;
+    let batch = state.tick(delta_ms * 1_000_000).clone();
 
     ApiResponse::success(batch)
 }
 
+/// Signals returned by GET /signals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalsResponse {
+    /// Pass as `after` to fetch only newer signals
+    pub last_id: u64,
+    pub signals: Vec<AnomalySignal>,
+}
+
+/// Handle GET /signals - buffered anomaly signals newer than `after`
+pub fn handle_get_signals(
+    state: &SharedState,
+    after: u64,
+    filter: &SignalFilter,
+) -> ApiResponse<SignalsResponse> {
+    let state = state.lock().unwrap();
+    let detection = &state.detection;
+    ApiResponse::success(SignalsResponse {
+        last_id: detection.last_id(),
+        signals: detection
+            .signals_after(after, filter)
+            .map(|(_, signal)| signal.clone())
+            .collect(),
+    })
+}
+
+/// SSE messages for buffered signals newer than `after`, and the id to
+/// resume from next time
+pub fn signal_events(state: &SharedState, after: u64, filter: &SignalFilter) -> (u64, String) {
+    let state = state.lock().unwrap();
+    let detection = &state.detection;
+    // Ids restart after a reset; replay the new buffer from the beginning
+    let after = if after > detection.last_id() {
+        0
+    } else {
+        after
+    };
+    let events = detection
+        .signals_after(after, filter)
+        .map(|(id, signal)| sse_event(*id, signal))
+        .collect();
+    (detection.last_id(), events)
+}
+
 /// Handle POST /rate - change simulation speed (placeholder - rate not implemented yet)
 pub fn handle_change_rate(
     state: &SharedState,
@@ -323,11 +535,87 @@ pub fn handle_reset(state: &SharedState) -> ApiResponse<SimulationStatus> {
     let mut state = state.lock().unwrap();
 
     state.engine.reset();
+    state.detection.reset();
+    state.last_batch = None;
 
     let status = SimulationStatus::from_engine(&state.engine);
     ApiResponse::success(status)
 }
 
+/// Route a request to its handler, returning the HTTP status and JSON body
+///
+/// `url` may carry a query string (`/tick?delta_ms=100`); POST bodies are
+/// JSON. Shared by the `server` feature and any other HTTP front end.
+pub fn dispatch(state: &SharedState, method: &str, url: &str, body: &str) -> (u16, String) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+
+    match (method, path) {
+        ("GET", "/scenarios") => respond(handle_list_scenarios()),
+        ("GET", "/status") => respond(handle_get_status(state)),
+        ("GET", "/dashboard") => respond(handle_get_dashboard(state)),
+        ("GET", "/signals") => {
+            let after = query_pairs(query)
+                .find(|(key, _)| *key == "after")
+                .map_or(Ok(0), |(_, v)| v.parse::<u64>());
+            match (after, SignalFilter::from_query(query)) {
+                (Ok(after), Ok(filter)) => respond(handle_get_signals(state, after, &filter)),
+                (Err(_), _) => bad_request("after must be a non-negative integer"),
+                (_, Err(e)) => bad_request(&e),
+            }
+        }
+        ("POST", "/start") => match serde_json::from_str(body) {
+            Ok(request) => respond(handle_start(state, request)),
+            Err(e) => bad_request(&format!("Invalid start request: {}", e)),
+        },
+        ("POST", "/stop") => respond(handle_stop(state)),
+        ("POST", "/pause") => respond(handle_pause(state)),
+        ("POST", "/resume") => respond(handle_resume(state)),
+        ("POST", "/inject") => match serde_json::from_str(body) {
+            Ok(request) => respond(handle_inject_anomaly(state, request)),
+            Err(e) => bad_request(&format!("Invalid inject request: {}", e)),
+        },
+        ("POST", "/tick") => {
+            let delta_ms = query_pairs(query)
+                .find(|(key, _)| *key == "delta_ms")
+                .map(|(_, v)| v.parse::<u64>());
+            match delta_ms {
+                Some(Ok(ms)) => respond(handle_tick(state, ms)),
+                Some(Err(_)) => bad_request("delta_ms must be a non-negative integer"),
+                None => {
+                    let ms = state.lock().unwrap().config.tick_interval_ms;
+                    respond(handle_tick(state, ms))
+                }
+            }
+        }
+        ("POST", "/reset") => respond(handle_reset(state)),
+        _ => (
+            404,
+            serialize(&ApiResponse::<()>::error(&format!(
+                "No route for {} {}",
+                method, path
+            ))),
+        ),
+    }
+}
+
+fn respond<T: Serialize>(response: ApiResponse<T>) -> (u16, String) {
+    let status = if response.success { 200 } else { 400 };
+    (status, serialize(&response))
+}
+
+fn bad_request(msg: &str) -> (u16, String) {
+    (400, serialize(&ApiResponse::<()>::error(msg)))
+}
+
+fn serialize<T: Serialize>(response: &ApiResponse<T>) -> String {
+    serde_json::to_string(response).unwrap_or_else(|e| {
+        format!(
+            r#"{{"success":false,"error":"serialization failed: {}"}}"#,
+            e
+        )
+    })
+}
+
 // ============================================================================
 // API Documentation
 // ============================================================================
@@ -338,6 +626,8 @@ pub fn get_api_routes() -> Vec<(&'static str, &'static str, &'static str)> {
         ("GET", "/scenarios", "List all available scenarios"),
         ("GET", "/status", "Get current simulation status"),
         ("GET", "/dashboard", "Get full dashboard state with metrics"),
+        ("GET", "/signals", "Buffered anomaly signals (?after=)"),
+        ("GET", "/signals/stream", "SSE anomaly signal feed"),
         ("POST", "/start", "Start simulation with scenario"),
         ("POST", "/stop", "Stop the simulation"),
         ("POST", "/pause", "Pause the simulation"),
@@ -359,7 +649,7 @@ pub fn print_api_docs(config: &ApiConfig) {
     println!("╠──────────────────────────────────────────────────────────────╣");
 
     for (method, path, desc) in get_api_routes() {
-        println!("║ {:6} {:12} - {:40} ║", method, path, desc);
+        println!("║ {:6} {:15} - {:37} ║", method, path, desc);
     }
 
     println!("╠══════════════════════════════════════════════════════════════╣");
@@ -453,4 +743,150 @@ mod tests {
         );
         assert!(inject_response.success);
     }
+
+    #[test]
+    fn test_dispatch_routes() {
+        let state = create_shared_state(ApiConfig::default());
+
+        let (status, body) = dispatch(
+            &state,
+            "POST",
+            "/start",
+            r#"{"scenario": "checkout_funnel", "seed": 7}"#,
+        );
+        assert_eq!(status, 200, "{body}");
+
+        let (status, body) = dispatch(&state, "POST", "/tick?delta_ms=500", "");
+        assert_eq!(status, 200);
+        let tick: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert!(tick["data"]["metadata"]["log_count"].as_u64().unwrap() > 0);
+
+        // Dashboard reflects the last tick instead of generating a new one
+        let (_, body) = dispatch(&state, "GET", "/dashboard", "");
+        let dashboard: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            dashboard["data"]["total_events"],
+            tick["data"]["metadata"]["log_count"]
+        );
+
+        let (status, _) = dispatch(
+            &state,
+            "POST",
+            "/inject",
+            r#"{"anomaly_type": "no_such_anomaly"}"#,
+        );
+        assert_eq!(status, 400);
+        assert_eq!(dispatch(&state, "POST", "/start", "{").0, 400);
+        assert_eq!(dispatch(&state, "POST", "/tick?delta_ms=-1", "").0, 400);
+        assert_eq!(dispatch(&state, "GET", "/nope", "").0, 404);
+    }
+
+    #[test]
+    fn test_tick_is_rate_limited() {
+        let state = create_shared_state(ApiConfig {
+            pacing: PacingConfig {
+                max_events_per_sec: 1,
+                burst: 50,
+                max_tick_ms: 1_000,
+            },
+            ..Default::default()
+        });
+        dispatch(
+            &state,
+            "POST",
+            "/start",
+            r#"{"scenario": "checkout_funnel"}"#,
+        );
+
+        // An hour-long tick is cut to a second of simulated time
+        let (_, body) = dispatch(&state, "POST", "/tick?delta_ms=3600000", "");
+        let tick: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(tick["data"]["metadata"]["elapsed_ns"], 1_000_000_000u64);
+
+        let mut dropped = 0;
+        for _ in 0..20 {
+            let state = &mut *state.lock().unwrap();
+            let batch = state.tick(1_000_000_000);
+            let kept: usize = batch
+                .logs
+                .resourceLogs
+                .iter()
+                .flat_map(|r| &r.scopeLogs)
+                .map(|s| s.logRecords.len())
+                .sum();
+            assert!(kept <= 50);
+            dropped += batch.metadata.dropped_events;
+        }
+        assert!(dropped > 0);
+        assert_eq!(
+            state.lock().unwrap().pacer.dropped_total(),
+            dropped + tick["data"]["metadata"]["dropped_events"].as_u64().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_signal_feed_filters_and_resumes() {
+        let state = create_shared_state(ApiConfig::default());
+        dispatch(
+            &state,
+            "POST",
+            "/start",
+            r#"{"scenario": "checkout_funnel"}"#,
+        );
+        for _ in 0..300 {
+            dispatch(&state, "POST", "/tick?delta_ms=200", "");
+        }
+        dispatch(
+            &state,
+            "POST",
+            "/inject",
+            r#"{"anomaly_type": "payment_outage", "duration_ms": 20000}"#,
+        );
+        for _ in 0..100 {
+            dispatch(&state, "POST", "/tick?delta_ms=200", "");
+        }
+
+        let fetch = |url: &str| -> SignalsResponse {
+            let (status, body) = dispatch(&state, "GET", url, "");
+            assert_eq!(status, 200, "{body}");
+            let response: ApiResponse<SignalsResponse> = serde_json::from_str(&body).unwrap();
+            response.data.unwrap()
+        };
+        let all = fetch("/signals");
+        assert!(!all.signals.is_empty());
+        assert!(all.signals.iter().all(|s| s.is_anomaly));
+
+        let high = fetch("/signals?severity=high");
+        assert!(high.signals.len() <= all.signals.len());
+        assert!(high.signals.iter().all(|s| s.severity >= Severity::High));
+        let detector = DetectorId::from_u8(all.signals[0].attribution.primary_detector).unwrap();
+        let by_detector = fetch(&format!("/signals?detector={:?}", detector).to_lowercase());
+        assert!(!by_detector.signals.is_empty());
+        assert!(
+            by_detector
+                .signals
+                .iter()
+                .all(|s| s.detector_fired(detector))
+        );
+
+        // Resuming from the newest id yields nothing until the next tick
+        let (last_id, events) = signal_events(&state, 0, &SignalFilter::default());
+        assert_eq!(last_id, all.last_id);
+        assert!(events.starts_with("id: ") && events.contains("event: anomaly\ndata: {"));
+        assert!(
+            fetch(&format!("/signals?after={}", last_id))
+                .signals
+                .is_empty()
+        );
+
+        assert_eq!(dispatch(&state, "GET", "/signals?severity=huge", "").0, 400);
+        assert_eq!(
+            dispatch(&state, "GET", "/signals?detector=magic", "").0,
+            400
+        );
+        assert_eq!(dispatch(&state, "GET", "/signals?after=x", "").0, 400);
+
+        dispatch(&state, "POST", "/reset", "");
+        assert_eq!(fetch("/signals").last_id, 0);
+    }
 }
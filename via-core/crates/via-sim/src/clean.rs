@@ -0,0 +1,238 @@
+//! Certified-Clean Generation
+//!
+//! Baselines learned from training data are only as good as the data is
+//! normal. `SimulationEngine::set_clean` refuses every scenario that is not
+//! a baseline (`Scenario::is_baseline`) and audits the logs it emits with a
+//! `CleanAudit`: a count of logs carrying the ground-truth flag, which must
+//! stay zero, and a SHA-256 digest of the stream.
+//!
+//! The digest covers each log as one line of JSON, exactly as
+//! `generate --format jsonl` writes it, so `sha256sum` of an uncompressed
+//! JSONL output matches the certificate. `generate --clean --output
+//! logs.jsonl` writes the certificate to `logs.clean.json`, signed with
+//! HMAC-SHA256 when `VIA_SIM_SIGNING_KEY` is set.
+
+use crate::compression;
+use crate::core::LogRecord;
+use ring::{digest, hmac};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Suffix replacing the log file's extension
+pub const CLEAN_SUFFIX: &str = "clean.json";
+
+/// Environment variable holding the certificate signing key
+pub const SIGNING_KEY_ENV: &str = "VIA_SIM_SIGNING_KEY";
+
+/// Running audit of a clean stream
+#[derive(Clone)]
+pub struct CleanAudit {
+    digest: digest::Context,
+    log_count: u64,
+    anomaly_log_count: u64,
+    start_time_ns: Option<u64>,
+    end_time_ns: u64,
+}
+
+impl Default for CleanAudit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CleanAudit {
+    pub fn new() -> Self {
+        Self {
+            digest: digest::Context::new(&digest::SHA256),
+            log_count: 0,
+            anomaly_log_count: 0,
+            start_time_ns: None,
+            end_time_ns: 0,
+        }
+    }
+
+    pub fn record(&mut self, log: &LogRecord) {
+        let line = serde_json::to_string(log).expect("log records serialize");
+        self.digest.update(line.as_bytes());
+        self.digest.update(b"\n");
+
+        let ts: u64 = log.timeUnixNano.parse().unwrap_or(0);
+        self.start_time_ns = Some(self.start_time_ns.map_or(ts, |start| start.min(ts)));
+        self.end_time_ns = self.end_time_ns.max(ts);
+        self.log_count += 1;
+        if log.isGroundTruthAnomaly {
+            self.anomaly_log_count += 1;
+        }
+    }
+
+    pub fn log_count(&self) -> u64 {
+        self.log_count
+    }
+
+    /// Logs carrying the ground-truth flag; a clean run has none
+    pub fn anomaly_log_count(&self) -> u64 {
+        self.anomaly_log_count
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.anomaly_log_count == 0
+    }
+
+    /// Hex SHA-256 of the stream recorded so far
+    pub fn sha256(&self) -> String {
+        hex(self.digest.clone().finish().as_ref())
+    }
+
+    /// Certificate for the stream so far, signed when `key` is given
+    pub fn certificate(&self, scenario: &str, seed: u64, key: Option<&[u8]>) -> CleanCertificate {
+        let mut certificate = CleanCertificate {
+            scenario: scenario.to_string(),
+            seed,
+            start_time_ns: self.start_time_ns.unwrap_or(0),
+            end_time_ns: self.end_time_ns,
+            log_count: self.log_count,
+            anomaly_log_count: self.anomaly_log_count,
+            certified: self.is_clean(),
+            sha256: self.sha256(),
+            signature: None,
+        };
+        if let Some(key) = key {
+            certificate.sign(key);
+        }
+        certificate
+    }
+}
+
+/// Contents of a `*.clean.json` certificate
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CleanCertificate {
+    /// Baseline scenario
+    pub scenario: String,
+    pub seed: u64,
+    /// Simulated span of the logs
+    pub start_time_ns: u64,
+    pub end_time_ns: u64,
+    pub log_count: u64,
+    /// Logs carrying the ground-truth flag
+    pub anomaly_log_count: u64,
+    /// Whether the stream had no anomaly logs
+    pub certified: bool,
+    /// Hex SHA-256 of the logs as JSON lines
+    pub sha256: String,
+    /// Hex HMAC-SHA256 of the other fields, as compact JSON in this order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl CleanCertificate {
+    /// Bytes the signature covers
+    fn signed_payload(&self) -> Vec<u8> {
+        let unsigned = Self {
+            signature: None,
+            ..self.clone()
+        };
+        serde_json::to_vec(&unsigned).expect("certificates serialize")
+    }
+
+    pub fn sign(&mut self, key: &[u8]) {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+        let tag = hmac::sign(&key, &self.signed_payload());
+        self.signature = Some(hex(tag.as_ref()));
+    }
+
+    /// Whether the certificate carries a valid signature under `key`
+    pub fn verify(&self, key: &[u8]) -> bool {
+        let Some(tag) = self.signature.as_deref().and_then(unhex) else {
+            return false;
+        };
+        let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+        hmac::verify(&key, &self.signed_payload(), &tag).is_ok()
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// Certificate path for a log file: `logs.jsonl` or `logs.jsonl.zst` ->
+/// `logs.clean.json`
+pub fn certificate_path(output: &Path) -> PathBuf {
+    compression::strip_extension(output).with_extension(CLEAN_SUFFIX)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unhex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulationEngine;
+
+    #[test]
+    fn test_clean_engine_certifies_its_stream() {
+        let mut engine = SimulationEngine::new_deterministic(3);
+        engine.set_clean(true);
+        engine.start("normal_traffic");
+        assert!(!engine.add_scenario_by_name("ddos"));
+        assert!(engine.add_scenario_by_name("checkout_funnel"));
+        assert_eq!(
+            engine.schedule_anomaly("memory_leak", 0, 1_000_000_000),
+            None
+        );
+
+        let mut written = String::new();
+        for _ in 0..20 {
+            let batch = engine.tick(100_000_000);
+            for log in &batch.logs.resourceLogs[0].scopeLogs[0].logRecords {
+                written.push_str(&serde_json::to_string(log).unwrap());
+                written.push('\n');
+            }
+        }
+        let audit = engine.clean_audit().unwrap();
+        assert!(audit.log_count() > 0);
+        assert!(audit.is_clean());
+        let expected = digest::digest(&digest::SHA256, written.as_bytes());
+        assert_eq!(audit.sha256(), hex(expected.as_ref()));
+
+        let certificate = audit.certificate("normal_traffic", 3, Some(b"secret"));
+        assert!(certificate.certified);
+        assert!(certificate.verify(b"secret"));
+        assert!(!certificate.verify(b"other"));
+        let mut forged = certificate.clone();
+        forged.log_count += 1;
+        assert!(!forged.verify(b"secret"));
+
+        // A flagged log voids the certificate
+        let mut audit = audit.clone();
+        let mut log = LogRecord::default();
+        log.mark_anomalous("a".into());
+        audit.record(&log);
+        assert!(!audit.certificate("normal_traffic", 3, None).certified);
+    }
+
+    #[test]
+    fn test_certificate_path_replaces_extension() {
+        assert_eq!(
+            certificate_path(Path::new("out/logs.jsonl.zst")),
+            Path::new("out/logs.clean.json")
+        );
+    }
+}
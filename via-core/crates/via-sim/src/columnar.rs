@@ -0,0 +1,305 @@
+//! Columnar Log Output (feature `parquet`)
+//!
+//! `via-sim generate --format parquet|arrow --output <file>` writes logs as
+//! Arrow record batches, in a Parquet file or an Arrow IPC file, so million
+//! event runs re-read quickly and load straight into DuckDB or Polars.
+//!
+//! Every file has the schema of [`log_schema`], one row per log:
+//!
+//! | Column            | Type    | Source                                     |
+//! |-------------------|---------|--------------------------------------------|
+//! | `time_unix_nano`  | uint64  | `timeUnixNano`                             |
+//! | `service_name`    | utf8?   | `service.name` attribute                   |
+//! | `severity_number` | uint32  | `severityNumber`                           |
+//! | `severity_text`   | utf8    | `severityText`                             |
+//! | `trace_id`        | utf8    | `traceId`                                  |
+//! | `span_id`         | utf8    | `spanId`                                   |
+//! | `body`            | utf8    | body as text                               |
+//! | `value`           | float64 | `LogRecord::metric_value`                  |
+//! | `attributes`      | utf8    | attributes as a flat JSON object           |
+//! | `is_anomaly`      | bool    | `isGroundTruthAnomaly`                     |
+//! | `anomaly_id`      | utf8?   | `anomalyId`                                |
+//!
+//! Columns are only ever appended; `SCHEMA_VERSION` in the schema metadata
+//! counts the changes.
+
+use crate::core::{AnyValue, LogRecord};
+use arrow_array::RecordBatch;
+use arrow_array::builder::{
+    BooleanBuilder, Float64Builder, StringBuilder, UInt32Builder, UInt64Builder,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+/// Version of the column layout, in the schema metadata as `via_sim.schema_version`
+pub const SCHEMA_VERSION: &str = "1";
+
+/// Rows buffered before a record batch is written
+const BATCH_ROWS: usize = 65_536;
+
+static SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    let fields = vec![
+        Field::new("time_unix_nano", DataType::UInt64, false),
+        Field::new("service_name", DataType::Utf8, true),
+        Field::new("severity_number", DataType::UInt32, false),
+        Field::new("severity_text", DataType::Utf8, false),
+        Field::new("trace_id", DataType::Utf8, false),
+        Field::new("span_id", DataType::Utf8, false),
+        Field::new("body", DataType::Utf8, false),
+        Field::new("value", DataType::Float64, false),
+        Field::new("attributes", DataType::Utf8, false),
+        Field::new("is_anomaly", DataType::Boolean, false),
+        Field::new("anomaly_id", DataType::Utf8, true),
+    ];
+    let metadata = HashMap::from([(
+        "via_sim.schema_version".to_string(),
+        SCHEMA_VERSION.to_string(),
+    )]);
+    Arc::new(Schema::new_with_metadata(fields, metadata))
+});
+
+/// Schema of every columnar file
+pub fn log_schema() -> SchemaRef {
+    SCHEMA.clone()
+}
+
+/// Column builders for `log_schema`
+#[derive(Default)]
+pub struct LogColumns {
+    time_unix_nano: UInt64Builder,
+    service_name: StringBuilder,
+    severity_number: UInt32Builder,
+    severity_text: StringBuilder,
+    trace_id: StringBuilder,
+    span_id: StringBuilder,
+    body: StringBuilder,
+    value: Float64Builder,
+    attributes: StringBuilder,
+    is_anomaly: BooleanBuilder,
+    anomaly_id: StringBuilder,
+    rows: usize,
+}
+
+impl LogColumns {
+    pub fn append(&mut self, log: &LogRecord) {
+        self.time_unix_nano
+            .append_value(log.timeUnixNano.parse().unwrap_or(0));
+        self.service_name.append_option(log.service_name());
+        self.severity_number.append_value(log.severityNumber);
+        self.severity_text.append_value(&log.severityText);
+        self.trace_id.append_value(&log.traceId);
+        self.span_id.append_value(&log.spanId);
+        self.body.append_value(log.body.as_text());
+        self.value.append_value(log.metric_value());
+        let attributes: serde_json::Map<String, serde_json::Value> = log
+            .attributes
+            .iter()
+            .map(|kv| (kv.key.clone(), plain_json(&kv.value)))
+            .collect();
+        self.attributes
+            .append_value(serde_json::Value::Object(attributes).to_string());
+        self.is_anomaly.append_value(log.isGroundTruthAnomaly);
+        self.anomaly_id.append_option(log.anomalyId.as_deref());
+        self.rows += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// The appended rows as a batch, leaving the builders empty
+    pub fn finish(&mut self) -> Result<RecordBatch, ArrowError> {
+        self.rows = 0;
+        RecordBatch::try_new(
+            log_schema(),
+            vec![
+                Arc::new(self.time_unix_nano.finish()),
+                Arc::new(self.service_name.finish()),
+                Arc::new(self.severity_number.finish()),
+                Arc::new(self.severity_text.finish()),
+                Arc::new(self.trace_id.finish()),
+                Arc::new(self.span_id.finish()),
+                Arc::new(self.body.finish()),
+                Arc::new(self.value.finish()),
+                Arc::new(self.attributes.finish()),
+                Arc::new(self.is_anomaly.finish()),
+                Arc::new(self.anomaly_id.finish()),
+            ],
+        )
+    }
+}
+
+/// `logs` as one record batch
+pub fn to_record_batch<'a>(
+    logs: impl IntoIterator<Item = &'a LogRecord>,
+) -> Result<RecordBatch, ArrowError> {
+    let mut columns = LogColumns::default();
+    for log in logs {
+        columns.append(log);
+    }
+    columns.finish()
+}
+
+/// Attribute value without its OTLP type wrapper
+fn plain_json(value: &AnyValue) -> serde_json::Value {
+    match value {
+        AnyValue::String { stringValue } => stringValue.clone().into(),
+        AnyValue::Int { intValue } => (*intValue).into(),
+        AnyValue::Bool { boolValue } => (*boolValue).into(),
+        AnyValue::Double { doubleValue } => (*doubleValue).into(),
+    }
+}
+
+/// Columnar file layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnarFormat {
+    /// Snappy-compressed Parquet
+    Parquet,
+    /// Arrow IPC file (Feather v2)
+    Arrow,
+}
+
+enum Sink {
+    Parquet(ArrowWriter<BufWriter<File>>),
+    Arrow(arrow_ipc::writer::FileWriter<BufWriter<File>>),
+}
+
+/// Streams logs into a columnar file
+pub struct ColumnarWriter {
+    sink: Sink,
+    columns: LogColumns,
+    rows: u64,
+}
+
+impl ColumnarWriter {
+    pub fn create(path: &Path, format: ColumnarFormat) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let out = BufWriter::new(file);
+        let schema = log_schema();
+        let sink = match format {
+            ColumnarFormat::Parquet => {
+                let props = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                Sink::Parquet(
+                    ArrowWriter::try_new(out, schema, Some(props)).map_err(|e| e.to_string())?,
+                )
+            }
+            ColumnarFormat::Arrow => Sink::Arrow(
+                arrow_ipc::writer::FileWriter::try_new(out, &schema).map_err(|e| e.to_string())?,
+            ),
+        };
+        Ok(Self {
+            sink,
+            columns: LogColumns::default(),
+            rows: 0,
+        })
+    }
+
+    pub fn write(&mut self, log: &LogRecord) -> Result<(), String> {
+        self.columns.append(log);
+        self.rows += 1;
+        if self.columns.len() >= BATCH_ROWS {
+            self.flush_batch()?;
+        }
+        Ok(())
+    }
+
+    fn flush_batch(&mut self) -> Result<(), String> {
+        if self.columns.is_empty() {
+            return Ok(());
+        }
+        let batch = self.columns.finish().map_err(|e| e.to_string())?;
+        match &mut self.sink {
+            Sink::Parquet(writer) => writer.write(&batch).map_err(|e| e.to_string()),
+            Sink::Arrow(writer) => writer.write(&batch).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Write the remaining rows and the file footer, returning the row count
+    pub fn finish(mut self) -> Result<u64, String> {
+        self.flush_batch()?;
+        match self.sink {
+            Sink::Parquet(writer) => writer.close().map(|_| ()).map_err(|e| e.to_string())?,
+            Sink::Arrow(mut writer) => writer.finish().map_err(|e| e.to_string())?,
+        }
+        Ok(self.rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulationEngine;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::UInt64Type;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    fn logs() -> Vec<LogRecord> {
+        let mut engine = SimulationEngine::new_deterministic(7);
+        engine.start("normal_traffic");
+        engine.schedule_anomaly("ddos", 0, 1_000_000_000);
+        let batch = engine.tick(1_000_000_000);
+        batch.logs.resourceLogs[0].scopeLogs[0].logRecords.clone()
+    }
+
+    #[test]
+    fn test_record_batch_matches_schema() {
+        let logs = logs();
+        let batch = to_record_batch(&logs).unwrap();
+        assert_eq!(batch.schema(), log_schema());
+        assert_eq!(batch.num_rows(), logs.len());
+
+        let anomalies = batch
+            .column_by_name("is_anomaly")
+            .unwrap()
+            .as_boolean()
+            .true_count();
+        let expected = logs.iter().filter(|l| l.isGroundTruthAnomaly).count();
+        assert!(expected > 0);
+        assert_eq!(anomalies, expected);
+        let attributes = batch
+            .column_by_name("attributes")
+            .unwrap()
+            .as_string::<i32>();
+        let first: serde_json::Value = serde_json::from_str(attributes.value(0)).unwrap();
+        assert!(first.is_object());
+    }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let logs = logs();
+        let path = std::env::temp_dir().join(format!("via-sim-{}.parquet", std::process::id()));
+        let mut writer = ColumnarWriter::create(&path, ColumnarFormat::Parquet).unwrap();
+        for log in &logs {
+            writer.write(log).unwrap();
+        }
+        assert_eq!(writer.finish().unwrap(), logs.len() as u64);
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+        let rows: usize = batches.iter().map(RecordBatch::num_rows).sum();
+        assert_eq!(rows, logs.len());
+        let times = batches[0]
+            .column_by_name("time_unix_nano")
+            .unwrap()
+            .as_primitive::<UInt64Type>();
+        assert_eq!(times.value(0).to_string(), logs[0].timeUnixNano);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+//! Compressed Files
+//!
+//! Files ending in `.gz` are gzip and files ending in `.zst` / `.zstd` are
+//! zstd; anything else is plain. `create` and `write` compress and
+//! `read_to_string` decompresses by that rule, so `generate --output
+//! logs.jsonl.zst`, via-bench `--output results.json.gz` and replaying or
+//! comparing those files need no separate step.
+
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// zstd level: the library default, fast enough for live generation
+const ZSTD_LEVEL: i32 = 3;
+
+/// Compression picked from a file name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => Self::Gzip,
+            Some("zst" | "zstd") => Self::Zstd,
+            _ => Self::None,
+        }
+    }
+}
+
+/// `path` without a compression extension: `logs.jsonl.zst` -> `logs.jsonl`
+pub fn strip_extension(path: &Path) -> PathBuf {
+    match Compression::from_path(path) {
+        Compression::None => path.to_path_buf(),
+        _ => path.with_extension(""),
+    }
+}
+
+/// Buffered writer compressing by file name; call `finish` to write the
+/// trailer and surface errors a drop would swallow
+pub enum FileWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl FileWriter {
+    pub fn finish(self) -> io::Result<()> {
+        let mut inner = match self {
+            Self::Plain(w) => w,
+            Self::Gzip(w) => w.finish()?,
+            Self::Zstd(w) => w.finish()?,
+        };
+        inner.flush()
+    }
+}
+
+impl Write for FileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(w) => w.write(buf),
+            Self::Gzip(w) => w.write(buf),
+            Self::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(w) => w.flush(),
+            Self::Gzip(w) => w.flush(),
+            Self::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+/// Create `path`, compressing by its extension
+pub fn create(path: &Path) -> io::Result<FileWriter> {
+    let file = BufWriter::new(File::create(path)?);
+    Ok(match Compression::from_path(path) {
+        Compression::None => FileWriter::Plain(file),
+        Compression::Gzip => FileWriter::Gzip(GzEncoder::new(file, flate2::Compression::default())),
+        Compression::Zstd => FileWriter::Zstd(zstd::Encoder::new(file, ZSTD_LEVEL)?),
+    })
+}
+
+/// `std::fs::write`, compressing by extension
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let mut writer = create(path.as_ref())?;
+    writer.write_all(contents.as_ref())?;
+    writer.finish()
+}
+
+/// `std::fs::read_to_string`, decompressing by extension
+pub fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+    let path = path.as_ref();
+    let file = File::open(path)?;
+    let mut reader: Box<dyn Read> = match Compression::from_path(path) {
+        Compression::None => Box::new(file),
+        Compression::Gzip => Box::new(MultiGzDecoder::new(file)),
+        Compression::Zstd => Box::new(zstd::Decoder::new(file)?),
+    };
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_by_extension() {
+        let dir = std::env::temp_dir().join(format!("via-sim-compression-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let text = "{\"severityText\":\"INFO\"}\n".repeat(1000);
+        for name in ["logs.jsonl", "logs.jsonl.gz", "logs.jsonl.zst"] {
+            let path = dir.join(name);
+            write(&path, &text).unwrap();
+            assert_eq!(read_to_string(&path).unwrap(), text, "{name}");
+            let size = std::fs::metadata(&path).unwrap().len() as usize;
+            assert_eq!(size < text.len() / 10, name != "logs.jsonl", "{name}");
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_strip_extension() {
+        assert_eq!(
+            strip_extension(Path::new("out/logs.jsonl.zst")),
+            Path::new("out/logs.jsonl")
+        );
+        assert_eq!(
+            strip_extension(Path::new("results.json")),
+            Path::new("results.json")
+        );
+    }
+}
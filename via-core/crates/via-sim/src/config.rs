@@ -0,0 +1,238 @@
+//! Scenario Config Files
+//!
+//! `via-sim generate --config <file>` reads a TOML [`ScenarioConfig`] with an
+//! explicit anomaly schedule; `via-sim init` writes a commented example
+//! ([`SCENARIO_TEMPLATE`]) to start from.
+
+use crate::delivery::DeliveryConfig;
+use crate::entity::EntityKey;
+use crate::sampling::SamplingConfig;
+use crate::schedule::{CronSchedule, Recurrence};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Commented example scenario config
+pub const SCENARIO_TEMPLATE: &str = include_str!("../templates/scenario.toml");
+/// File name `via-sim init` writes the template to
+pub const SCENARIO_FILE: &str = "via-sim.toml";
+
+/// Settings for one `generate` run
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScenarioConfig {
+    /// Run length such as `30s`, `5m` or `1h`
+    pub duration: String,
+    /// Baseline scenario name
+    pub scenario: String,
+    #[serde(default = "default_tick_ms")]
+    pub tick_ms: u64,
+    #[serde(default = "default_seed")]
+    pub seed: u64,
+    /// `json`, `json-lines` or `pretty` (`parquet`, `arrow` with feature `parquet`)
+    #[serde(default = "default_format")]
+    pub format: String,
+    #[serde(default)]
+    pub anomalies: Vec<ScheduledAnomaly>,
+    /// Log file (stdout when absent); gets a `*.truth.json` sidecar
+    #[serde(default)]
+    pub output: Option<String>,
+    /// Key of the entities listed per window in the sidecar
+    #[serde(default)]
+    pub entity_key: Option<EntityKey>,
+    /// Skew, reordering and duplication applied on output
+    #[serde(default)]
+    pub delivery: DeliveryConfig,
+    /// Fractions of normal and anomaly logs written
+    #[serde(default)]
+    pub sampling: SamplingConfig,
+}
+
+/// Anomaly injected at a fixed offset, or recurring from it with at most
+/// one of `every_sec`, `cron` and `budget`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScheduledAnomaly {
+    pub scenario: String,
+    /// Offset from the start of the run (seconds); the earliest instance of
+    /// a recurring anomaly
+    #[serde(default)]
+    pub start_time_sec: u64,
+    pub duration_sec: u64,
+    /// Recur with this period (seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub every_sec: Option<u64>,
+    /// Recur at each minute matching this cron expression
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cron: Option<String>,
+    /// Recur at random, covering this fraction of the run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<f64>,
+}
+
+impl ScheduledAnomaly {
+    /// How the anomaly repeats; `None` for a single instance
+    pub fn recurrence(&self) -> Result<Option<Recurrence>, String> {
+        match (self.every_sec, &self.cron, self.budget) {
+            (None, None, None) => Ok(None),
+            (Some(0), None, None) => Err(format!("{}: every_sec must be positive", self.scenario)),
+            (Some(every), None, None) => Ok(Some(Recurrence::Every {
+                period_ns: every * 1_000_000_000,
+            })),
+            (None, Some(cron), None) => cron
+                .parse::<CronSchedule>()
+                .map(|cron| Some(Recurrence::Cron(cron)))
+                .map_err(|e| format!("{}: {}", self.scenario, e)),
+            (None, None, Some(rate)) if rate > 0.0 && rate <= 1.0 => {
+                Ok(Some(Recurrence::Budget { rate }))
+            }
+            (None, None, Some(_)) => Err(format!("{}: budget must be in (0, 1]", self.scenario)),
+            _ => Err(format!(
+                "{}: set only one of every_sec, cron and budget",
+                self.scenario
+            )),
+        }
+    }
+}
+
+fn default_tick_ms() -> u64 {
+    100
+}
+
+fn default_seed() -> u64 {
+    42
+}
+
+fn default_format() -> String {
+    "json".to_string()
+}
+
+impl ScenarioConfig {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config {}: {}", path, e))?;
+        Self::parse(&content).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let config: Self =
+            toml::from_str(content).map_err(|e| format!("Invalid scenario config: {}", e))?;
+        for anomaly in &config.anomalies {
+            anomaly
+                .recurrence()
+                .map_err(|e| format!("Invalid scenario config: {}", e))?;
+        }
+        Ok(config)
+    }
+}
+
+/// Write `(file name, content)` pairs into `dir`, creating it if needed.
+///
+/// Refuses to overwrite existing files unless `force` is set, so a re-run
+/// never clobbers edited configs.
+pub fn write_scaffold(
+    dir: &Path,
+    files: &[(&str, &str)],
+    force: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let paths: Vec<PathBuf> = files.iter().map(|(name, _)| dir.join(name)).collect();
+    if !force && let Some(existing) = paths.iter().find(|p| p.exists()) {
+        return Err(format!(
+            "{} already exists (use --force to overwrite)",
+            existing.display()
+        ));
+    }
+
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    for (path, (_, content)) in paths.iter().zip(files) {
+        std::fs::write(path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenarios::create_scenario;
+
+    #[test]
+    fn test_template_parses_with_known_scenarios() {
+        let config = ScenarioConfig::parse(SCENARIO_TEMPLATE).unwrap();
+        assert!(create_scenario(&config.scenario).is_some());
+        assert!(!config.anomalies.is_empty());
+        for anomaly in &config.anomalies {
+            assert!(create_scenario(&anomaly.scenario).is_some());
+        }
+
+        let minimal = ScenarioConfig::parse("duration = \"30s\"\nscenario = \"iot\"").unwrap();
+        assert_eq!(minimal.tick_ms, 100);
+        assert!(minimal.anomalies.is_empty());
+        assert!(!minimal.delivery.is_enabled());
+        assert!(!minimal.sampling.is_enabled());
+        assert!(ScenarioConfig::parse("scenario = \"iot\"").is_err());
+
+        // The commented delivery and sampling blocks are valid once
+        // uncommented
+        let delivery: String = SCENARIO_TEMPLATE
+            .lines()
+            .skip_while(|l| *l != "# [delivery]")
+            .map(|l| match l.strip_prefix("# ") {
+                Some(setting) if setting.starts_with('[') || setting.contains(" = ") => {
+                    format!("{}\n", setting)
+                }
+                _ => format!("{}\n", l),
+            })
+            .collect();
+        let messy = ScenarioConfig::parse(&format!(
+            "duration = \"30s\"\nscenario = \"iot\"\n{}",
+            delivery
+        ))
+        .unwrap();
+        assert_eq!(messy.delivery.max_skew_ms, 500);
+        assert_eq!(messy.delivery.max_delay_ticks, 5);
+        assert_eq!(messy.sampling.normal_rate, 0.1);
+        assert_eq!(messy.sampling.anomaly_rate, 1.0);
+    }
+
+    #[test]
+    fn test_recurring_anomalies_parse() {
+        let config = ScenarioConfig::parse(SCENARIO_TEMPLATE).unwrap();
+        let recurring: Vec<_> = config
+            .anomalies
+            .iter()
+            .filter_map(|a| a.recurrence().unwrap())
+            .collect();
+        assert!(
+            matches!(recurring[0], Recurrence::Every { period_ns } if period_ns == 600_000_000_000)
+        );
+
+        let head = "duration = \"1h\"\nscenario = \"normal\"\n[[anomalies]]\nscenario = \"ddos\"\nduration_sec = 60\n";
+        let cron = ScenarioConfig::parse(&format!("{}cron = \"*/15 * * * *\"", head)).unwrap();
+        assert_eq!(cron.anomalies[0].start_time_sec, 0);
+        assert!(matches!(
+            cron.anomalies[0].recurrence(),
+            Ok(Some(Recurrence::Cron(_)))
+        ));
+        assert!(ScenarioConfig::parse(&format!("{}cron = \"bad\"", head)).is_err());
+        assert!(ScenarioConfig::parse(&format!("{}budget = 1.5", head)).is_err());
+        assert!(ScenarioConfig::parse(&format!("{}every_sec = 60\nbudget = 0.1", head)).is_err());
+    }
+
+    #[test]
+    fn test_scaffold_does_not_overwrite() {
+        let dir = std::env::temp_dir().join(format!("via-sim-init-{}", std::process::id()));
+        let files = [(SCENARIO_FILE, SCENARIO_TEMPLATE)];
+
+        let written = write_scaffold(&dir, &files, false).unwrap();
+        assert_eq!(written, vec![dir.join(SCENARIO_FILE)]);
+        std::fs::write(&written[0], "edited").unwrap();
+
+        assert!(write_scaffold(&dir, &files, false).is_err());
+        assert_eq!(std::fs::read_to_string(&written[0]).unwrap(), "edited");
+        write_scaffold(&dir, &files, true).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&written[0]).unwrap(),
+            SCENARIO_TEMPLATE
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
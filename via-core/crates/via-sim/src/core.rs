@@ -4,6 +4,24 @@
 //! Types are co-located here as the single source of truth.
 
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use via_core::engine::ExogenousContext;
+
+/// Log attribute: whether the log was emitted in business hours
+pub const BUSINESS_HOURS_ATTR: &str = "context.business_hours";
+/// Log attribute: whether a deploy was rolling out when the log was emitted
+pub const DEPLOY_IN_PROGRESS_ATTR: &str = "deploy.in_progress";
+
+/// Monday to Friday, 09:00 to 17:00 UTC
+pub fn is_business_hours(timestamp_ns: u64) -> bool {
+    let secs = timestamp_ns / 1_000_000_000;
+    let days = secs / 86_400;
+    // 1970-01-01 was a Thursday; shift so Monday is 0
+    let weekday = (days + 3) % 7;
+    let hour = (secs % 86_400) / 3_600;
+    weekday < 5 && (9..17).contains(&hour)
+}
 
 // ============================================================================
 // OTel Log Types (OTLP JSON format - camelCase for serialization)
@@ -35,7 +53,10 @@ pub struct ScopeLog {
 }
 
 /// Individual log record - primary unit of simulation
+///
+/// Missing fields deserialize to their defaults, as OTLP JSON omits empty ones.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 #[allow(non_snake_case)]
 pub struct LogRecord {
     pub timeUnixNano: String,
@@ -78,6 +99,18 @@ impl LogRecord {
             .map(|kv| &kv.value)
     }
 
+    /// Field by path: `traceId`, `spanId`, `severityText`, `body`, or else an
+    /// attribute key; non-string values as text
+    pub fn field(&self, path: &str) -> Option<Cow<'_, str>> {
+        match path {
+            "traceId" => Some(Cow::Borrowed(&self.traceId)),
+            "spanId" => Some(Cow::Borrowed(&self.spanId)),
+            "severityText" => Some(Cow::Borrowed(&self.severityText)),
+            "body" => Some(self.body.as_text()),
+            key => self.get_attribute(key).map(AnyValue::as_text),
+        }
+    }
+
     /// Get service name from attributes
     pub fn service_name(&self) -> Option<&str> {
         self.get_attribute("service.name").and_then(|v| v.as_str())
@@ -91,6 +124,7 @@ impl LogRecord {
             "process.memory.usage",
             "process.cpu.utilization",
             "http.status_code",
+            "sensor.temperature_c",
         ] {
             if let Some(v) = self.get_attribute(key) {
                 if let Some(n) = v.as_f64() {
@@ -101,6 +135,20 @@ impl LogRecord {
         1.0
     }
 
+    /// Exogenous context from the `context.business_hours` and
+    /// `deploy.in_progress` attributes; absent attributes leave it unknown
+    pub fn exogenous_context(&self) -> ExogenousContext {
+        ExogenousContext {
+            business_hours: self
+                .get_attribute(BUSINESS_HOURS_ATTR)
+                .and_then(AnyValue::as_bool),
+            deploy_in_progress: self
+                .get_attribute(DEPLOY_IN_PROGRESS_ATTR)
+                .and_then(AnyValue::as_bool)
+                .unwrap_or(false),
+        }
+    }
+
     /// Mark this log as part of a ground truth anomaly
     pub fn mark_anomalous(&mut self, anomaly_id: String) {
         self.isGroundTruthAnomaly = true;
@@ -172,6 +220,16 @@ impl AnyValue {
         }
     }
 
+    /// The value as text, numbers and booleans formatted
+    pub fn as_text(&self) -> Cow<'_, str> {
+        match self {
+            AnyValue::String { stringValue } => Cow::Borrowed(stringValue),
+            AnyValue::Int { intValue } => Cow::Owned(intValue.to_string()),
+            AnyValue::Bool { boolValue } => Cow::Owned(boolValue.to_string()),
+            AnyValue::Double { doubleValue } => Cow::Owned(doubleValue.to_string()),
+        }
+    }
+
     pub fn as_i64(&self) -> Option<i64> {
         match self {
             AnyValue::Int { intValue } => Some(*intValue),
@@ -186,6 +244,13 @@ impl AnyValue {
             _ => None,
         }
     }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            AnyValue::Bool { boolValue } => Some(*boolValue),
+            _ => None,
+        }
+    }
 }
 
 // ============================================================================
@@ -207,6 +272,17 @@ pub struct GroundTruth {
     pub target_services: Vec<String>,
     /// Number of logs generated during this anomaly
     pub log_count: u64,
+    /// Sorted `EntityKey::hash`es of the anomaly's logs, recorded when the
+    /// engine has an entity key (see `SimulationEngine::set_entity_key`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub target_entities: Vec<u64>,
+    /// Missing-data anomaly (see `Scenario::is_absence`): the window is
+    /// positive even though it has few or no logs of its own
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub absence: bool,
+    /// Baseline logs the anomaly kept from being written
+    #[serde(default)]
+    pub suppressed_log_count: u64,
 }
 
 impl GroundTruth {
@@ -218,6 +294,9 @@ impl GroundTruth {
             anomaly_type: anomaly_type.into(),
             target_services: Vec::new(),
             log_count: 0,
+            target_entities: Vec::new(),
+            absence: false,
+            suppressed_log_count: 0,
         }
     }
 
@@ -226,6 +305,11 @@ impl GroundTruth {
         timestamp_ns >= self.start_time_ns && timestamp_ns <= self.end_time_ns
     }
 
+    /// Whether the entity with `hash` is among the recorded targets
+    pub fn involves_entity(&self, hash: u64) -> bool {
+        self.target_entities.binary_search(&hash).is_ok()
+    }
+
     /// Check if a log matches this ground truth (time + service)
     pub fn matches_log(&self, log: &LogRecord) -> bool {
         let ts: u64 = log.timeUnixNano.parse().unwrap_or(0);
@@ -268,6 +352,149 @@ pub struct BatchMetadata {
     pub anomaly_log_count: u64,
     /// Active scenarios
     pub active_scenarios: Vec<String>,
+    /// True generating-process parameters of each active scenario for this tick
+    #[serde(default)]
+    pub process_states: Vec<ProcessState>,
+    /// Logs generated this tick but dropped by the live API's rate limit
+    #[serde(default)]
+    pub dropped_events: u64,
+    /// Simulated time the batch covers (nanoseconds)
+    #[serde(default)]
+    pub tick_ns: u64,
+    /// Logs in this batch per second of simulated time
+    #[serde(default)]
+    pub events_per_second: f64,
+    /// Logs each active scenario generated this tick, keyed as in
+    /// `active_scenarios` (before delivery and rate limiting)
+    #[serde(default)]
+    pub scenario_counts: BTreeMap<String, u64>,
+    /// Logs in this batch per `service.name` (`unknown` without one)
+    #[serde(default)]
+    pub service_counts: BTreeMap<String, u64>,
+    /// Logs in this batch per ground-truth anomaly id
+    #[serde(default)]
+    pub anomaly_counts: BTreeMap<String, u64>,
+    /// Logs generated this tick before sampling and delivery
+    #[serde(default)]
+    pub generated_log_count: u64,
+    /// Ground-truth anomaly logs generated this tick before sampling and
+    /// delivery
+    #[serde(default)]
+    pub generated_anomaly_log_count: u64,
+}
+
+impl SimulationBatch {
+    /// Every log record in the batch
+    pub fn records(&self) -> impl Iterator<Item = &LogRecord> {
+        self.logs
+            .resourceLogs
+            .iter()
+            .flat_map(|r| &r.scopeLogs)
+            .flat_map(|s| &s.logRecords)
+    }
+
+    pub fn record_count(&self) -> usize {
+        self.logs
+            .resourceLogs
+            .iter()
+            .flat_map(|r| &r.scopeLogs)
+            .map(|s| s.logRecords.len())
+            .sum()
+    }
+
+    /// Recompute the metadata derived from the batch's logs (anomaly,
+    /// service and per-anomaly counts, and events per second over
+    /// `tick_ns`), e.g. after logs were added or removed
+    pub fn recount(&mut self) {
+        let mut anomaly_log_count = 0;
+        let mut service_counts = BTreeMap::new();
+        let mut anomaly_counts = BTreeMap::new();
+        for log in self.records() {
+            let service = log.service_name().unwrap_or("unknown");
+            *service_counts.entry(service.to_string()).or_insert(0) += 1;
+            if log.isGroundTruthAnomaly {
+                anomaly_log_count += 1;
+            }
+            if let Some(id) = &log.anomalyId {
+                *anomaly_counts.entry(id.clone()).or_insert(0) += 1;
+            }
+        }
+        let count = self.record_count();
+
+        let metadata = &mut self.metadata;
+        metadata.anomaly_log_count = anomaly_log_count;
+        metadata.service_counts = service_counts;
+        metadata.anomaly_counts = anomaly_counts;
+        metadata.events_per_second = if metadata.tick_ns > 0 {
+            count as f64 / (metadata.tick_ns as f64 / 1_000_000_000.0)
+        } else {
+            0.0
+        };
+    }
+}
+
+// ============================================================================
+// Generating Process State
+// ============================================================================
+
+/// Distribution a scenario samples latency values from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LatencyModel {
+    /// `ln(latency_ms) ~ Normal(mu, sigma)`
+    LogNormal { mu: f64, sigma: f64 },
+    /// Gaussian latency in milliseconds
+    Normal { mean: f64, std_dev: f64 },
+    /// Uniform latency in milliseconds over `[min, max)`
+    Uniform { min: f64, max: f64 },
+}
+
+impl LatencyModel {
+    /// Expected latency in milliseconds
+    pub fn mean(&self) -> f64 {
+        match *self {
+            LatencyModel::LogNormal { mu, sigma } => (mu + sigma * sigma / 2.0).exp(),
+            LatencyModel::Normal { mean, .. } => mean,
+            LatencyModel::Uniform { min, max } => (min + max) / 2.0,
+        }
+    }
+}
+
+/// True parameters a scenario generated its logs from during one tick.
+///
+/// Detectors only see the sampled logs; these values let benchmarks compare
+/// estimates (rate, error ratio, latency) against the process that produced them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ProcessState {
+    /// Scenario name
+    pub scenario: String,
+    /// Anomaly id when the scenario is an injected anomaly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anomaly_id: Option<String>,
+    /// Expected log emission rate (logs/sec)
+    pub rps: f64,
+    /// Expected fraction of emitted logs that are failures (4xx/5xx or error level)
+    pub error_rate: f64,
+    /// Latency distribution, if the scenario emits latency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencyModel>,
+}
+
+impl ProcessState {
+    pub fn new(scenario: impl Into<String>, rps: f64, error_rate: f64) -> Self {
+        Self {
+            scenario: scenario.into(),
+            anomaly_id: None,
+            rps,
+            error_rate,
+            latency: None,
+        }
+    }
+
+    pub fn with_latency(mut self, latency: LatencyModel) -> Self {
+        self.latency = Some(latency);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -293,6 +520,9 @@ mod tests {
             anomaly_type: "Test".to_string(),
             target_services: vec![],
             log_count: 0,
+            target_entities: vec![],
+            absence: false,
+            suppressed_log_count: 0,
         };
 
         let mut log = LogRecord::default();
@@ -303,6 +533,26 @@ mod tests {
         assert!(!gt.matches_log(&log));
     }
 
+    #[test]
+    fn test_latency_model_mean() {
+        let ln = LatencyModel::LogNormal {
+            mu: 4.0,
+            sigma: 0.5,
+        };
+        assert!((ln.mean() - 61.87).abs() < 0.01);
+
+        let u = LatencyModel::Uniform {
+            min: 100.0,
+            max: 300.0,
+        };
+        assert_eq!(u.mean(), 200.0);
+
+        let json = serde_json::to_string(&ln).unwrap();
+        assert!(json.contains("\"kind\":\"log_normal\""));
+        let back: LatencyModel = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, ln);
+    }
+
     #[test]
     fn test_any_value_conversions() {
         let s = AnyValue::string("hello");
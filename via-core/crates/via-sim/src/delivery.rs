@@ -0,0 +1,227 @@
+//! Messy Log Delivery
+//!
+//! Real collectors don't hand logs over in order: each host's clock is a
+//! little off, batches arrive late behind newer ones, and at-least-once
+//! shippers resend records. `Delivery` does that to the engine's output so
+//! detectors can be scored on how well they cope. Only delivery changes:
+//! anomaly labels travel with their records (duplicates included) and the
+//! ground-truth windows are recorded when logs are generated, before any of
+//! this happens.
+
+use crate::core::LogRecord;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Delivery faults to inject; the default delivers logs untouched
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct DeliveryConfig {
+    /// Largest clock offset of a source, ahead or behind (ms). Each
+    /// `service.name` gets its own fixed offset within the bound.
+    pub max_skew_ms: u64,
+    /// Chance a tick's batch is held back behind later batches
+    pub reorder_rate: f64,
+    /// Ticks a held-back batch waits, at most
+    pub max_delay_ticks: u32,
+    /// Chance a record is delivered twice
+    pub duplicate_rate: f64,
+}
+
+impl DeliveryConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.max_skew_ms > 0 || self.reorder_rate > 0.0 || self.duplicate_rate > 0.0
+    }
+}
+
+/// Delivery counters
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeliveryStats {
+    /// Records whose timestamp was shifted
+    pub skewed: u64,
+    /// Batches delivered after a later batch
+    pub reordered: u64,
+    /// Extra copies delivered
+    pub duplicated: u64,
+}
+
+/// Applies a `DeliveryConfig` to successive batches
+pub struct Delivery {
+    config: DeliveryConfig,
+    rng: StdRng,
+    /// Clock offset per source (ns)
+    offsets: HashMap<String, i64>,
+    /// Held-back batches with the ticks they still wait
+    held: Vec<(u32, Vec<LogRecord>)>,
+    stats: DeliveryStats,
+}
+
+impl Delivery {
+    pub fn new(config: DeliveryConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: StdRng::seed_from_u64(seed),
+            offsets: HashMap::new(),
+            held: Vec::new(),
+            stats: DeliveryStats::default(),
+        }
+    }
+
+    pub fn config(&self) -> &DeliveryConfig {
+        &self.config
+    }
+
+    pub fn stats(&self) -> &DeliveryStats {
+        &self.stats
+    }
+
+    /// Records to deliver after a tick produced `logs`: this tick's batch,
+    /// unless held back, followed by held batches that are due
+    pub fn deliver(&mut self, mut logs: Vec<LogRecord>) -> Vec<LogRecord> {
+        if self.config.max_skew_ms > 0 {
+            for log in &mut logs {
+                self.skew(log);
+            }
+        }
+        if self.config.duplicate_rate > 0.0 {
+            let mut copies = Vec::new();
+            for log in &logs {
+                if self.rng.random_bool(self.config.duplicate_rate.min(1.0)) {
+                    copies.push(log.clone());
+                }
+            }
+            self.stats.duplicated += copies.len() as u64;
+            // Resent records land shortly after the original, not next to it
+            for copy in copies {
+                let at = self.rng.random_range(0..=logs.len());
+                logs.insert(at, copy);
+            }
+        }
+
+        let mut out = Vec::new();
+        let hold = !logs.is_empty()
+            && self.config.max_delay_ticks > 0
+            && self.rng.random_bool(self.config.reorder_rate.min(1.0));
+        if hold {
+            let ticks = self.rng.random_range(1..=self.config.max_delay_ticks);
+            self.held.push((ticks, logs));
+        } else {
+            out = logs;
+        }
+
+        let mut i = 0;
+        while i < self.held.len() {
+            if self.held[i].0 == 0 {
+                let (_, batch) = self.held.remove(i);
+                self.stats.reordered += 1;
+                out.extend(batch);
+            } else {
+                self.held[i].0 -= 1;
+                i += 1;
+            }
+        }
+        out
+    }
+
+    /// Everything still held back, oldest first (end of a run)
+    pub fn flush(&mut self) -> Vec<LogRecord> {
+        let held = std::mem::take(&mut self.held);
+        self.stats.reordered += held.len() as u64;
+        held.into_iter().flat_map(|(_, batch)| batch).collect()
+    }
+
+    fn skew(&mut self, log: &mut LogRecord) {
+        let bound = self.config.max_skew_ms as i64 * 1_000_000;
+        let source = log.service_name().unwrap_or_default().to_string();
+        let rng = &mut self.rng;
+        let offset = *self
+            .offsets
+            .entry(source)
+            .or_insert_with(|| rng.random_range(-bound..=bound));
+        if offset == 0 {
+            return;
+        }
+        let Ok(time_ns) = log.timeUnixNano.parse::<u64>() else {
+            return;
+        };
+        log.timeUnixNano = time_ns.saturating_add_signed(offset).to_string();
+        self.stats.skewed += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulationEngine;
+
+    fn batches(engine: &mut SimulationEngine, ticks: usize) -> Vec<Vec<LogRecord>> {
+        (0..ticks)
+            .map(|_| {
+                let batch = engine.tick(100_000_000);
+                batch.logs.resourceLogs[0].scopeLogs[0].logRecords.clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_delivery_skews_reorders_and_duplicates_within_bounds() {
+        let mut engine = SimulationEngine::new_deterministic(3);
+        engine.start("normal_traffic");
+        let generated = batches(&mut engine, 100);
+        let total: usize = generated.iter().map(Vec::len).sum();
+
+        let config = DeliveryConfig {
+            max_skew_ms: 250,
+            reorder_rate: 0.2,
+            max_delay_ticks: 5,
+            duplicate_rate: 0.05,
+        };
+        let mut delivery = Delivery::new(config, 1);
+        let mut delivered = Vec::new();
+        for batch in generated.iter().cloned() {
+            delivered.extend(delivery.deliver(batch));
+        }
+        delivered.extend(delivery.flush());
+
+        let stats = delivery.stats().clone();
+        assert_eq!(delivered.len() as u64, total as u64 + stats.duplicated);
+        assert!(stats.duplicated > 0 && stats.reordered > 0 && stats.skewed > 0);
+
+        // Every original is delivered, shifted by at most the skew bound
+        let originals: HashMap<&str, u64> = generated
+            .iter()
+            .flatten()
+            .map(|l| (l.spanId.as_str(), l.timeUnixNano.parse().unwrap()))
+            .collect();
+        let mut seen = HashMap::new();
+        for log in &delivered {
+            let original = originals[log.spanId.as_str()];
+            let time: u64 = log.timeUnixNano.parse().unwrap();
+            assert!(time.abs_diff(original) <= 250_000_000);
+            *seen.entry(log.spanId.as_str()).or_insert(0) += 1;
+        }
+        assert_eq!(seen.len(), originals.len());
+
+        let times: Vec<u64> = delivered
+            .iter()
+            .map(|l| l.timeUnixNano.parse().unwrap())
+            .collect();
+        assert!(!times.is_sorted());
+    }
+
+    #[test]
+    fn test_default_config_delivers_untouched() {
+        let mut engine = SimulationEngine::new_deterministic(3);
+        engine.start("normal_traffic");
+        engine.schedule_anomaly("ddos", 0, 500_000_000);
+        let mut delivery = Delivery::new(DeliveryConfig::default(), 1);
+        assert!(!delivery.config().is_enabled());
+        for batch in batches(&mut engine, 10) {
+            let expected: Vec<_> = batch.iter().map(|l| l.spanId.clone()).collect();
+            let out = delivery.deliver(batch);
+            let got: Vec<_> = out.iter().map(|l| l.spanId.clone()).collect();
+            assert_eq!(got, expected);
+        }
+        assert_eq!(delivery.stats(), &DeliveryStats::default());
+    }
+}
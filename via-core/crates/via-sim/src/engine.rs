@@ -22,12 +22,21 @@
 //! └─────────────────────────────────────────────────────────┘
 //! ```
 
+use crate::clean::CleanAudit;
 use crate::core::{
-    BatchMetadata, GroundTruth, LogRecord, OTelLog, Resource, ResourceLog, ScopeLog,
-    SimulationBatch,
+    BUSINESS_HOURS_ATTR, BatchMetadata, DEPLOY_IN_PROGRESS_ATTR, GroundTruth, KeyValue, LogRecord,
+    OTelLog, ProcessState, Resource, ResourceLog, ScopeLog, SimulationBatch, is_business_hours,
 };
+use crate::delivery::{Delivery, DeliveryConfig, DeliveryStats};
+use crate::entity::EntityKey;
+use crate::sampling::{Sampler, SamplingConfig, SamplingStats};
 use crate::scenarios::{self, Scenario};
-use std::collections::HashMap;
+use crate::schedule::Recurrence;
+use std::collections::{BTreeMap, HashMap};
+
+/// Distinct entities recorded per ground-truth window, bounding the copy each
+/// batch carries
+const MAX_TARGET_ENTITIES: usize = 1024;
 
 #[derive(Debug, Clone, Copy)]
 pub struct DeterminismConfig {
@@ -71,6 +80,20 @@ pub struct SimulationEngine {
     stats: EngineStats,
     /// Determinism controls (for reproducible benchmark runs)
     determinism: DeterminismConfig,
+    /// Emission rate multiplier applied to every scenario the engine creates
+    rate_scale: f64,
+    /// Tag logs with exogenous context attributes (see `LogRecord::exogenous_context`)
+    emit_context: bool,
+    /// Deploy rollouts as `(start_ns, end_ns)`, reported in the context attributes
+    deploys: Vec<(u64, u64)>,
+    /// Key whose hashes ground truth records as `target_entities`
+    entity_key: Option<EntityKey>,
+    /// Skew, reordering and duplication applied to every batch
+    delivery: Option<Delivery>,
+    /// Normal and anomaly log sampling ahead of delivery
+    sampler: Option<Sampler>,
+    /// Audit of every emitted log while only baseline scenarios may run
+    clean: Option<CleanAudit>,
 }
 
 /// Scheduled scenario for future activation
@@ -106,7 +129,15 @@ impl GroundTruthTracker {
         }
     }
 
-    fn start_anomaly(&mut self, id: String, anomaly_type: String, start_ns: u64, end_ns: u64) {
+    fn start_anomaly(
+        &mut self,
+        id: String,
+        anomaly_type: String,
+        target_services: Vec<String>,
+        start_ns: u64,
+        end_ns: u64,
+        absence: bool,
+    ) {
         self.active.insert(
             id.clone(),
             GroundTruth {
@@ -114,15 +145,30 @@ impl GroundTruthTracker {
                 start_time_ns: start_ns,
                 end_time_ns: end_ns,
                 anomaly_type,
-                target_services: Vec::new(),
+                target_services,
                 log_count: 0,
+                target_entities: Vec::new(),
+                absence,
+                suppressed_log_count: 0,
             },
         );
     }
 
-    fn record_log(&mut self, anomaly_id: &str) {
+    fn record_log(&mut self, anomaly_id: &str, entity_hash: Option<u64>) {
         if let Some(gt) = self.active.get_mut(anomaly_id) {
             gt.log_count += 1;
+            if let Some(hash) = entity_hash
+                && gt.target_entities.len() < MAX_TARGET_ENTITIES
+                && let Err(i) = gt.target_entities.binary_search(&hash)
+            {
+                gt.target_entities.insert(i, hash);
+            }
+        }
+    }
+
+    fn record_suppressed(&mut self, anomaly_id: &str) {
+        if let Some(gt) = self.active.get_mut(anomaly_id) {
+            gt.suppressed_log_count += 1;
         }
     }
 
@@ -173,6 +219,13 @@ impl SimulationEngine {
             state: EngineState::Stopped,
             stats: EngineStats::default(),
             determinism: DeterminismConfig::default(),
+            rate_scale: 1.0,
+            emit_context: false,
+            deploys: Vec::new(),
+            entity_key: None,
+            delivery: None,
+            sampler: None,
+            clean: None,
         }
     }
 
@@ -190,17 +243,54 @@ impl SimulationEngine {
         self.determinism = config;
     }
 
+    /// Scale the emission rate of all scenarios (used for load sweeps).
+    ///
+    /// Applies to running scenarios immediately and to scenarios created later.
+    /// Non-positive factors are ignored.
+    pub fn set_rate_scale(&mut self, factor: f64) {
+        if !(factor.is_finite() && factor > 0.0) {
+            return;
+        }
+        let relative = factor / self.rate_scale;
+        if let Some(baseline) = self.baseline.as_mut() {
+            baseline.scale_rate(relative);
+        }
+        for scenario in &mut self.scenarios {
+            scenario.scale_rate(relative);
+        }
+        for scheduled in &mut self.scheduled {
+            scheduled.scenario.scale_rate(relative);
+        }
+        self.rate_scale = factor;
+    }
+
+    pub fn rate_scale(&self) -> f64 {
+        self.rate_scale
+    }
+
+    /// Create a scenario by name with the engine's rate scale applied
+    fn create_scaled(&self, name: &str) -> Option<Box<dyn Scenario>> {
+        let mut scenario = scenarios::create_scenario(name)?;
+        if self.rate_scale != 1.0 {
+            scenario.scale_rate(self.rate_scale);
+        }
+        Some(scenario)
+    }
+
     /// Start the simulation with a baseline scenario
     pub fn start(&mut self, baseline_scenario: &str) {
         self.reset();
         scenarios::configure_determinism(self.determinism.enabled, self.determinism.seed);
 
         // Set baseline scenario
-        if let Some(scenario) = scenarios::create_scenario(baseline_scenario) {
+        if let Some(scenario) = self
+            .create_scaled(baseline_scenario)
+            .filter(|s| self.admits(s.as_ref()))
+        {
             self.baseline = Some(scenario);
         } else {
             // Default to normal traffic
-            self.baseline = scenarios::create_scenario("normal_traffic");
+            self.baseline = self.create_scaled("normal_traffic");
         }
 
         self.start_time_ns = if self.determinism.enabled {
@@ -245,6 +335,16 @@ impl SimulationEngine {
         self.scheduled.clear();
         self.ground_truth.reset();
         self.stats = EngineStats::default();
+        self.deploys.clear();
+        if let Some(delivery) = self.delivery.take() {
+            self.set_delivery(delivery.config().clone());
+        }
+        if let Some(sampler) = self.sampler.take() {
+            self.set_sampling(sampler.config().clone());
+        }
+        if self.clean.is_some() {
+            self.clean = Some(CleanAudit::new());
+        }
     }
 
     /// Clear all active scenarios
@@ -252,29 +352,41 @@ impl SimulationEngine {
         self.scenarios.clear();
     }
 
-    /// Add an immediate scenario (starts now)
-    pub fn add_scenario(&mut self, scenario: Box<dyn Scenario>) {
+    /// Add an immediate scenario (starts now); false if a clean run refused it
+    pub fn add_scenario(&mut self, scenario: Box<dyn Scenario>) -> bool {
+        if !self.admits(scenario.as_ref()) {
+            return false;
+        }
         self.scenarios.push(scenario);
+        true
     }
 
     /// Add a scenario by name
     pub fn add_scenario_by_name(&mut self, name: &str) -> bool {
-        if let Some(scenario) = scenarios::create_scenario(name) {
-            self.scenarios.push(scenario);
-            true
-        } else {
-            false
+        match self.create_scaled(name) {
+            Some(scenario) => self.add_scenario(scenario),
+            None => false,
         }
     }
 
-    /// Schedule an anomaly scenario for later
+    /// Whether the scenario may run: anything but baselines is refused in
+    /// clean runs
+    fn admits(&self, scenario: &dyn Scenario) -> bool {
+        self.clean.is_none() || scenario.is_baseline()
+    }
+
+    /// Schedule an anomaly scenario for later; `None` for unknown scenarios
+    /// and in clean runs
     pub fn schedule_anomaly(
         &mut self,
         scenario_name: &str,
         start_offset_ns: u64,
         duration_ns: u64,
     ) -> Option<String> {
-        let scenario = scenarios::create_scenario(scenario_name)?;
+        if self.clean.is_some() {
+            return None;
+        }
+        let scenario = self.create_scaled(scenario_name)?;
         let anomaly_id = format!("{}_{}", scenario_name, self.scheduled.len());
 
         let start_time_ns = self.current_time_ns + start_offset_ns;
@@ -291,12 +403,147 @@ impl SimulationEngine {
         Some(anomaly_id)
     }
 
+    /// Schedule every instance of a recurring anomaly that starts within
+    /// `horizon_ns` from now, the first `first_offset_ns` from now (see
+    /// `schedule`); ids of the instances, none for unknown scenarios and in
+    /// clean runs
+    pub fn schedule_recurring(
+        &mut self,
+        scenario_name: &str,
+        recurrence: &Recurrence,
+        first_offset_ns: u64,
+        duration_ns: u64,
+        horizon_ns: u64,
+    ) -> Vec<String> {
+        if self.clean.is_some() || scenarios::create_scenario(scenario_name).is_none() {
+            return Vec::new();
+        }
+        let seed = if self.determinism.enabled {
+            let key = format!("{}:{}", scenario_name, self.scheduled.len());
+            self.determinism.seed ^ xxhash_rust::xxh3::xxh3_64(key.as_bytes())
+        } else {
+            rand::random()
+        };
+        recurrence
+            .starts(
+                self.current_time_ns,
+                first_offset_ns,
+                duration_ns,
+                horizon_ns,
+                seed,
+            )
+            .into_iter()
+            .filter_map(|start| self.schedule_anomaly(scenario_name, start, duration_ns))
+            .collect()
+    }
+
+    /// Tag every log with business-hours and deploy context attributes
+    pub fn set_emit_context(&mut self, enabled: bool) {
+        self.emit_context = enabled;
+    }
+
+    /// Record the `key` hash of every anomalous log in its ground truth's
+    /// `target_entities` (up to 1024 distinct per anomaly); `None` stops
+    pub fn set_entity_key(&mut self, key: Option<EntityKey>) {
+        self.entity_key = key;
+    }
+
+    /// Mark a deploy rollout, reported as `deploy.in_progress` on logs inside
+    /// it. Enables context attributes. Not an anomaly: no ground truth.
+    pub fn schedule_deploy(&mut self, start_offset_ns: u64, duration_ns: u64) {
+        let start_time_ns = self.current_time_ns + start_offset_ns;
+        self.deploys
+            .push((start_time_ns, start_time_ns + duration_ns));
+        self.emit_context = true;
+    }
+
+    /// Deliver batches with clock skew, late batches and duplicate records
+    /// (see `delivery`). Ground truth is unaffected. A default config turns
+    /// it off.
+    pub fn set_delivery(&mut self, config: DeliveryConfig) {
+        let seed = if self.determinism.enabled {
+            self.determinism.seed
+        } else {
+            rand::random()
+        };
+        self.delivery = config.is_enabled().then(|| Delivery::new(config, seed));
+    }
+
+    /// Records the delivery model still holds back; call once a run ends
+    pub fn flush_delivery(&mut self) -> Vec<LogRecord> {
+        let logs = self
+            .delivery
+            .as_mut()
+            .map(Delivery::flush)
+            .unwrap_or_default();
+        if let Some(audit) = self.clean.as_mut() {
+            logs.iter().for_each(|log| audit.record(log));
+        }
+        logs
+    }
+
+    pub fn delivery_stats(&self) -> Option<&DeliveryStats> {
+        self.delivery.as_ref().map(Delivery::stats)
+    }
+
+    /// Emit only a fraction of normal and of anomaly logs (see `sampling`);
+    /// batches still report the counts generated. A default config turns it
+    /// off.
+    pub fn set_sampling(&mut self, config: SamplingConfig) {
+        let seed = if self.determinism.enabled {
+            // Independent of the delivery stream on the same seed
+            self.determinism.seed ^ 0x5A4D_504C
+        } else {
+            rand::random()
+        };
+        self.sampler = config.is_enabled().then(|| Sampler::new(config, seed));
+    }
+
+    pub fn sampling_stats(&self) -> Option<&SamplingStats> {
+        self.sampler.as_ref().map(Sampler::stats)
+    }
+
+    /// Run only baseline scenarios and audit every emitted log (see
+    /// `clean`). Enabling drops scheduled anomalies and any running
+    /// non-baseline scenario, and starts a fresh audit.
+    pub fn set_clean(&mut self, enabled: bool) {
+        if !enabled {
+            self.clean = None;
+            return;
+        }
+        self.clean = Some(CleanAudit::new());
+        self.scheduled.clear();
+        self.ground_truth.reset();
+        self.scenarios.retain(|s| s.is_baseline());
+        if self.baseline.as_ref().is_some_and(|b| !b.is_baseline()) {
+            self.baseline = self.create_scaled("normal_traffic");
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.clean.is_some()
+    }
+
+    /// Audit of the logs emitted since clean mode was enabled
+    pub fn clean_audit(&self) -> Option<&CleanAudit> {
+        self.clean.as_ref()
+    }
+
     /// Inject an anomaly immediately (convenience method)
     pub fn inject_anomaly(&mut self, scenario_name: &str, duration_ms: u64) -> Option<String> {
         self.schedule_anomaly(scenario_name, 0, duration_ms * 1_000_000)
     }
 
     /// Advance simulation by delta_ns and return generated logs with ground truth
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(
+            name = "tick",
+            level = "debug",
+            skip(self),
+            fields(time_ns = self.current_time_ns)
+        )
+    )]
     pub fn tick(&mut self, delta_ns: u64) -> SimulationBatch {
         if self.state != EngineState::Running {
             return SimulationBatch::default();
@@ -304,18 +551,26 @@ impl SimulationEngine {
 
         let mut all_logs: Vec<LogRecord> = Vec::new();
         let mut active_scenarios: Vec<String> = Vec::new();
+        let mut process_states: Vec<ProcessState> = Vec::new();
+        let mut scenario_counts: BTreeMap<String, u64> = BTreeMap::new();
 
         // Generate logs from baseline
         if let Some(ref mut baseline) = self.baseline {
             let logs = baseline.tick(self.current_time_ns, delta_ns);
             active_scenarios.push(baseline.name().to_string());
+            process_states.extend(baseline.process_state(delta_ns));
             all_logs.extend(logs);
         }
+        let baseline_count = all_logs.len();
 
         // Generate logs from active scenarios
         for scenario in &mut self.scenarios {
             let logs = scenario.tick(self.current_time_ns, delta_ns);
             active_scenarios.push(scenario.name().to_string());
+            process_states.extend(scenario.process_state(delta_ns));
+            *scenario_counts
+                .entry(scenario.name().to_string())
+                .or_insert(0) += logs.len() as u64;
             all_logs.extend(logs);
         }
 
@@ -333,12 +588,38 @@ impl SimulationEngine {
                 self.ground_truth.start_anomaly(
                     scheduled.anomaly_id.clone(),
                     scheduled.scenario.name().to_string(),
+                    scheduled.scenario.targets(),
                     scheduled.start_time_ns,
                     scheduled.end_time_ns,
+                    scheduled.scenario.is_absence(),
                 );
             }
         }
 
+        // Baseline traffic an active anomaly took down is never written
+        let mut index = 0;
+        let before = all_logs.len();
+        all_logs.retain(|log| {
+            index += 1;
+            if index > baseline_count {
+                return true;
+            }
+            let suppressed_by = self
+                .scheduled
+                .iter()
+                .find(|s| s.activated && current < s.end_time_ns && s.scenario.suppresses(log));
+            if let Some(scheduled) = suppressed_by {
+                self.ground_truth.record_suppressed(&scheduled.anomaly_id);
+            }
+            suppressed_by.is_none()
+        });
+        if let Some(baseline) = &self.baseline {
+            let kept = baseline_count - (before - all_logs.len());
+            *scenario_counts
+                .entry(baseline.name().to_string())
+                .or_insert(0) += kept as u64;
+        }
+
         // Generate logs from active scheduled scenarios
         let mut completed_indices: Vec<usize> = Vec::new();
         for (i, scheduled) in self.scheduled.iter_mut().enumerate() {
@@ -348,10 +629,18 @@ impl SimulationEngine {
                 // Mark logs as ground truth anomalies
                 for log in &mut logs {
                     log.mark_anomalous(scheduled.anomaly_id.clone());
-                    self.ground_truth.record_log(&scheduled.anomaly_id);
+                    let entity_hash = self.entity_key.as_ref().map(|key| key.hash(log));
+                    self.ground_truth
+                        .record_log(&scheduled.anomaly_id, entity_hash);
                 }
 
-                active_scenarios.push(format!("{}(anomaly)", scheduled.scenario.name()));
+                let name = format!("{}(anomaly)", scheduled.scenario.name());
+                *scenario_counts.entry(name.clone()).or_insert(0) += logs.len() as u64;
+                active_scenarios.push(name);
+                if let Some(mut state) = scheduled.scenario.process_state(delta_ns) {
+                    state.anomaly_id = Some(scheduled.anomaly_id.clone());
+                    process_states.push(state);
+                }
                 all_logs.extend(logs);
             } else if scheduled.activated && current >= scheduled.end_time_ns {
                 // Scenario completed
@@ -367,18 +656,44 @@ impl SimulationEngine {
             self.stats.scenarios_completed += 1;
         }
 
+        let generated_log_count = all_logs.len() as u64;
+        let generated_anomaly_log_count =
+            all_logs.iter().filter(|l| l.isGroundTruthAnomaly).count() as u64;
+        if let Some(sampler) = self.sampler.as_mut() {
+            sampler.sample(&mut all_logs);
+        }
+
+        if self.emit_context {
+            for log in &mut all_logs {
+                let timestamp: u64 = log.timeUnixNano.parse().unwrap_or(current);
+                let deploying = self
+                    .deploys
+                    .iter()
+                    .any(|&(start, end)| (start..end).contains(&timestamp));
+                log.attributes.push(KeyValue::bool(
+                    BUSINESS_HOURS_ATTR,
+                    is_business_hours(timestamp),
+                ));
+                log.attributes
+                    .push(KeyValue::bool(DEPLOY_IN_PROGRESS_ATTR, deploying));
+            }
+        }
+
+        if let Some(delivery) = self.delivery.as_mut() {
+            all_logs = delivery.deliver(all_logs);
+        }
+        if let Some(audit) = self.clean.as_mut() {
+            all_logs.iter().for_each(|log| audit.record(log));
+        }
+
         // Update time
         self.current_time_ns = end_time;
         self.stats.tick_count += 1;
 
-        // Count anomaly logs
-        let anomaly_log_count = all_logs.iter().filter(|l| l.isGroundTruthAnomaly).count() as u64;
-
         self.stats.total_logs += all_logs.len() as u64;
-        self.stats.total_anomaly_logs += anomaly_log_count;
 
         // Build output
-        SimulationBatch {
+        let mut batch = SimulationBatch {
             logs: OTelLog {
                 resourceLogs: vec![ResourceLog {
                     resource: Resource { attributes: vec![] },
@@ -392,10 +707,18 @@ impl SimulationEngine {
                 timestamp_ns: self.current_time_ns,
                 elapsed_ns: self.current_time_ns - self.start_time_ns,
                 log_count: self.stats.total_logs,
-                anomaly_log_count,
                 active_scenarios,
+                process_states,
+                tick_ns: delta_ns,
+                scenario_counts,
+                generated_log_count,
+                generated_anomaly_log_count,
+                ..Default::default()
             },
-        }
+        };
+        batch.recount();
+        self.stats.total_anomaly_logs += batch.metadata.anomaly_log_count;
+        batch
     }
 
     /// Get engine state
@@ -513,6 +836,607 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_process_states_reported() {
+        let mut engine = SimulationEngine::new_deterministic(7);
+        engine.start("normal_traffic");
+        let anomaly_id = engine
+            .schedule_anomaly("traffic_spike", 0, 1_000_000_000)
+            .unwrap();
+
+        let batch = engine.tick(100_000_000);
+        let states = &batch.metadata.process_states;
+        assert_eq!(states.len(), 2);
+
+        let baseline = &states[0];
+        assert_eq!(baseline.anomaly_id, None);
+        assert!(baseline.rps > 50.0 && baseline.rps < 150.0);
+        assert!(baseline.latency.is_some());
+
+        // Injected anomaly reports its configured rate and carries its id
+        let spike = &states[1];
+        assert_eq!(spike.anomaly_id.as_deref(), Some(anomaly_id.as_str()));
+        assert_eq!(spike.rps, 1000.0);
+        let emitted = batch.metadata.anomaly_log_count as f64;
+        assert_eq!(emitted, (spike.rps * 0.1).round());
+
+        // Per-batch breakdowns add up to the batch
+        let metadata = &batch.metadata;
+        let logs = batch.record_count() as u64;
+        assert_eq!(metadata.anomaly_counts[&anomaly_id] as f64, emitted);
+        assert_eq!(metadata.scenario_counts.len(), 2);
+        assert_eq!(metadata.scenario_counts.values().sum::<u64>(), logs);
+        assert_eq!(metadata.service_counts.values().sum::<u64>(), logs);
+        assert_eq!(metadata.tick_ns, 100_000_000);
+        assert!((metadata.events_per_second - logs as f64 * 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_batch_metadata_breakdowns_add_up() {
+        let mut engine = SimulationEngine::new_deterministic(3);
+        engine.start("normal_traffic");
+        assert!(engine.add_scenario_by_name("checkout_funnel"));
+        let spike = engine
+            .schedule_anomaly("traffic_spike", 0, 300_000_000)
+            .unwrap();
+
+        for tick_ns in [100_000_000, 150_000_000, 1_000_000_000] {
+            let batch = engine.tick(tick_ns);
+            let metadata = &batch.metadata;
+            let logs = batch.record_count() as u64;
+            assert!(logs > 0);
+
+            let sum = |counts: &BTreeMap<String, u64>| counts.values().sum::<u64>();
+            assert_eq!(sum(&metadata.scenario_counts), logs);
+            assert_eq!(sum(&metadata.service_counts), logs);
+            assert!(metadata.service_counts.len() > 1);
+            assert!(
+                metadata
+                    .scenario_counts
+                    .keys()
+                    .all(|name| metadata.active_scenarios.contains(name))
+            );
+
+            // Every anomaly log carries its id
+            assert_eq!(sum(&metadata.anomaly_counts), metadata.anomaly_log_count);
+            assert!(metadata.anomaly_counts[&spike] > 0);
+
+            let expected_eps = logs as f64 / (tick_ns as f64 / 1e9);
+            assert_eq!(metadata.tick_ns, tick_ns);
+            assert!((metadata.events_per_second - expected_eps).abs() < 1e-6);
+        }
+        // The spike has ended; nothing is counted against it any more
+        let batch = engine.tick(100_000_000);
+        assert!(batch.metadata.anomaly_counts.is_empty());
+        assert_eq!(batch.metadata.anomaly_log_count, 0);
+    }
+
+    #[test]
+    fn test_checkout_funnel_payment_outage() {
+        let mut engine = SimulationEngine::new_deterministic(5);
+        engine.start("checkout_funnel");
+        engine.schedule_anomaly("payment_outage", 0, 1_000_000_000);
+
+        let batch = engine.tick(1_000_000_000);
+        let logs: Vec<&LogRecord> = batch
+            .logs
+            .resourceLogs
+            .iter()
+            .flat_map(|r| r.scopeLogs.iter())
+            .flat_map(|s| s.logRecords.iter())
+            .collect();
+
+        // Every order passes cart, fraud check and payment
+        for service in ["cart-service", "fraud-check", "payment-service"] {
+            assert!(logs.iter().any(|l| l.service_name() == Some(service)));
+        }
+
+        let failure_ratio = |l: &LogRecord| {
+            l.get_attribute("payment.failure_ratio")
+                .and_then(|v| v.as_f64())
+        };
+        let outage: Vec<_> = logs.iter().filter(|l| l.isGroundTruthAnomaly).collect();
+        assert_eq!(outage.len() as u64, batch.metadata.anomaly_log_count);
+        assert!(!outage.is_empty());
+        for log in &outage {
+            let status = log
+                .get_attribute("http.status_code")
+                .and_then(|v| v.as_i64());
+            assert!(matches!(status, Some(503 | 504)));
+            assert_eq!(failure_ratio(log), Some(1.0));
+        }
+
+        // Healthy payments report the baseline business metrics
+        let healthy = logs
+            .iter()
+            .filter(|l| !l.isGroundTruthAnomaly)
+            .filter_map(|l| failure_ratio(l))
+            .collect::<Vec<_>>();
+        assert!(!healthy.is_empty());
+        assert!(healthy.iter().all(|r| *r < 0.5));
+    }
+
+    #[test]
+    fn test_iot_fleet_device_level_ground_truth() {
+        let mut engine = SimulationEngine::new_deterministic(3);
+        engine.start("iot_fleet");
+        engine.schedule_anomaly("firmware_regression", 0, 2_000_000_000);
+
+        // Every device reports exactly once per interval
+        let batch = engine.tick(1_000_000_000);
+        let logs: Vec<&LogRecord> = batch
+            .logs
+            .resourceLogs
+            .iter()
+            .flat_map(|r| r.scopeLogs.iter())
+            .flat_map(|s| s.logRecords.iter())
+            .collect();
+        let baseline = logs.iter().filter(|l| !l.isGroundTruthAnomaly).count();
+        assert_eq!(baseline, 1_000);
+
+        let gt = &batch.ground_truth[0];
+        assert!(gt.target_services.len() > 50 && gt.target_services.len() < 150);
+        for log in logs.iter().filter(|l| l.isGroundTruthAnomaly) {
+            assert!(gt.matches_log(log));
+        }
+        // Untouched devices fall outside the anomaly
+        let untouched = logs
+            .iter()
+            .find(|l| {
+                !gt.target_services
+                    .iter()
+                    .any(|t| Some(t.as_str()) == l.service_name())
+            })
+            .unwrap();
+        assert!(!gt.matches_log(untouched));
+    }
+
+    #[test]
+    fn test_ground_truth_records_entity_hashes() {
+        let key: EntityKey = "service.name + source.ip".parse().unwrap();
+        let mut engine = SimulationEngine::new_deterministic(5);
+        engine.set_entity_key(Some(key.clone()));
+        engine.start("normal_traffic");
+        engine.schedule_anomaly("credential_stuffing", 0, 2_000_000_000);
+
+        let batch = engine.tick(1_000_000_000);
+        let gt = &batch.ground_truth[0];
+        assert!(!gt.target_entities.is_empty());
+        assert!(gt.target_entities.is_sorted());
+        for log in batch.logs.resourceLogs[0].scopeLogs[0].logRecords.iter() {
+            // Detection hashing the same key agrees with ground truth
+            assert_eq!(gt.involves_entity(key.hash(log)), log.isGroundTruthAnomaly);
+        }
+    }
+
+    #[test]
+    fn test_entity_churn_workers_expire() {
+        use std::collections::HashSet;
+
+        let mut engine = SimulationEngine::new_deterministic(9);
+        engine.start("entity_churn");
+        let workers = |batch: &SimulationBatch| -> HashSet<String> {
+            logs(batch)
+                .filter_map(|l| l.service_name())
+                .filter(|s| s.starts_with("worker-"))
+                .map(str::to_string)
+                .collect()
+        };
+
+        // Default population: 1,000 new workers per minute, 20 s lifetime
+        let mut seen = HashSet::new();
+        let mut at_30s = HashSet::new();
+        for second in 0..60 {
+            let batch = engine.tick(1_000_000_000);
+            let live = workers(&batch);
+            if second == 30 {
+                at_30s = live.clone();
+            }
+            seen.extend(live);
+        }
+        assert!(
+            (950..=1_050).contains(&seen.len()),
+            "{} workers",
+            seen.len()
+        );
+        assert!(at_30s.len() > 250 && at_30s.len() < 400);
+
+        // Workers alive at 30 s are gone 25 s later; the core stays
+        engine.schedule_anomaly("core_latency_regression", 0, 1_000_000_000);
+        let batch = engine.tick(1_000_000_000);
+        assert!(workers(&batch).is_disjoint(&at_30s));
+        let gt = &batch.ground_truth[0];
+        assert_eq!(gt.target_services.len(), 20);
+        for log in logs(&batch).filter(|l| l.isGroundTruthAnomaly) {
+            assert!(gt.matches_log(log));
+            assert!(log.service_name().unwrap().starts_with("core-svc-"));
+        }
+    }
+
+    #[test]
+    fn test_user_sessions_share_traces_and_churn_users() {
+        use std::collections::{HashMap, HashSet};
+
+        let mut engine = SimulationEngine::new_deterministic(11);
+        engine.start("user_sessions");
+        let attr = |log: &LogRecord, key: &str| -> Option<String> {
+            log.attributes
+                .iter()
+                .find(|kv| kv.key == key)
+                .map(|kv| kv.value.as_text().into_owned())
+        };
+
+        let mut traces: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut users = HashSet::new();
+        let mut logins = 0;
+        for _ in 0..600 {
+            let batch = engine.tick(1_000_000_000);
+            for log in logs(&batch) {
+                let session = attr(log, "session.id").unwrap();
+                traces
+                    .entry(log.traceId.clone())
+                    .or_default()
+                    .insert(session);
+                users.insert(attr(log, "user.id").unwrap());
+                if attr(log, "http.route").as_deref() == Some("/login") {
+                    logins += 1;
+                }
+            }
+        }
+
+        // One trace per session, many logs per trace
+        assert!(traces.values().all(|sessions| sessions.len() == 1));
+        let total = engine.stats().total_logs as usize;
+        assert!(
+            total > traces.len() * 5,
+            "{total} logs, {} traces",
+            traces.len()
+        );
+        // ~3,000 sessions over 10 minutes: regulars return, churn adds new users
+        assert!((2_700..=3_300).contains(&logins), "{logins} logins");
+        assert!(users.len() < logins, "{} users", users.len());
+        assert!(users.iter().any(|u| u.as_str() >= "user-0005000"));
+    }
+
+    #[test]
+    fn test_ransomware_escalates_on_one_host() {
+        let mut engine = SimulationEngine::new_deterministic(4);
+        engine.start("normal_traffic");
+        engine.schedule_anomaly("ransomware", 0, 20_000_000_000);
+
+        let mut per_second = Vec::new();
+        let mut ground_truth = Vec::new();
+        for _ in 0..20 {
+            let batch = engine.tick(1_000_000_000);
+            let attack: Vec<&LogRecord> = logs(&batch).filter(|l| l.isGroundTruthAnomaly).collect();
+            for log in &attack {
+                assert_eq!(log.service_name(), Some("file-server-01"));
+            }
+            let entropy: Vec<f64> = attack
+                .iter()
+                .filter(|l| l.body.as_text().starts_with("File written"))
+                .filter_map(|l| l.attributes.iter().find(|kv| kv.key == "file.entropy"))
+                .map(|kv| kv.value.as_text().parse().unwrap())
+                .collect();
+            assert!(entropy.iter().all(|e| *e > 7.9));
+            per_second.push(attack.len());
+            ground_truth = batch.ground_truth;
+        }
+        assert!(per_second[19] > per_second[1] * 20, "{per_second:?}");
+        let gt = &ground_truth[0];
+        assert_eq!(gt.target_services, ["file-server-01"]);
+        assert_eq!(gt.log_count as usize, per_second.iter().sum::<usize>());
+    }
+
+    #[test]
+    fn test_api_scraping_rotates_keys_under_rate_limit() {
+        use std::collections::HashMap;
+
+        let mut engine = SimulationEngine::new_deterministic(6);
+        engine.start("normal_traffic");
+        engine.schedule_anomaly("api_scraping", 0, 120_000_000_000);
+
+        let mut per_key: HashMap<String, Vec<u64>> = HashMap::new();
+        let mut ips = std::collections::HashSet::new();
+        let (mut attack, mut total) = (0, 0);
+        for _ in 0..1_200 {
+            let batch = engine.tick(100_000_000);
+            for log in logs(&batch) {
+                total += 1;
+                if !log.isGroundTruthAnomaly {
+                    continue;
+                }
+                attack += 1;
+                let attr = |key: &str| {
+                    let kv = log.attributes.iter().find(|kv| kv.key == key).unwrap();
+                    kv.value.as_text().into_owned()
+                };
+                ips.insert(attr("net.peer.ip"));
+                per_key
+                    .entry(attr("api.key"))
+                    .or_default()
+                    .push(log.timeUnixNano.parse().unwrap());
+            }
+        }
+
+        // Four rotations of ten keys, each behind its own IP
+        assert_eq!(per_key.len(), 40);
+        assert_eq!(ips.len(), 40);
+        // Quiet next to the baseline, and no key ever above 1 request/sec
+        assert!(attack * 10 < total, "{attack} of {total}");
+        for times in per_key.values() {
+            assert!(times.len() >= 25);
+            assert!(times.windows(2).all(|w| w[1] - w[0] >= 1_000_000_000));
+        }
+    }
+
+    #[test]
+    fn test_beaconing_is_periodic() {
+        let mut engine = SimulationEngine::new_deterministic(10);
+        engine.start("normal_traffic");
+        engine.schedule_anomaly("beaconing", 0, 300_000_000_000);
+
+        let mut beacons = Vec::new();
+        for _ in 0..3_000 {
+            let batch = engine.tick(100_000_000);
+            for log in logs(&batch).filter(|l| l.isGroundTruthAnomaly) {
+                assert_eq!(log.service_name(), Some("workstation-042"));
+                let name = log.get_attribute("dns.question.name").unwrap();
+                assert!(name.as_str().unwrap().ends_with(".cdn-metrics-sync.net"));
+                let kind = log.get_attribute("dns.question.type").unwrap();
+                let time: u64 = log.timeUnixNano.parse().unwrap();
+                beacons.push((time, kind.as_str().unwrap().to_string()));
+            }
+        }
+
+        // One lookup every 5s ± 2%, every fourth an upload
+        assert!((58..=62).contains(&beacons.len()), "{}", beacons.len());
+        for pair in beacons.windows(2) {
+            let gap = pair[1].0 - pair[0].0;
+            assert!((4_900_000_000..=5_100_000_000).contains(&gap), "{gap}");
+        }
+        for (i, (_, kind)) in beacons.iter().enumerate() {
+            assert_eq!(kind, if i % 4 == 3 { "TXT" } else { "A" });
+        }
+    }
+
+    #[test]
+    fn test_regional_outage_shifts_traffic() {
+        let mut engine = SimulationEngine::new_deterministic(11);
+        engine.start("normal_traffic");
+        engine.schedule_anomaly("regional_outage", 60_000_000_000, 120_000_000_000);
+
+        let region = |log: &LogRecord| {
+            log.get_attribute("cloud.region")
+                .and_then(|v| v.as_str())
+                .unwrap()
+                .to_string()
+        };
+        let (mut before, mut during) = (HashMap::new(), HashMap::new());
+        let (mut early_errors, mut late_errors) = (0, 0);
+        for second in 0..180 {
+            let batch = engine.tick(1_000_000_000);
+            for log in logs(&batch) {
+                let counts = if second < 60 {
+                    &mut before
+                } else if second >= 70 {
+                    &mut during
+                } else {
+                    continue;
+                };
+                *counts.entry(region(log)).or_insert(0usize) += 1;
+                if second >= 70 && region(log) == "us-east-1" {
+                    // Only the outage's own failures remain in the lost region
+                    assert!(log.isGroundTruthAnomaly && log.severityText == "ERROR");
+                }
+                if log.isGroundTruthAnomaly && region(log) != "us-east-1" {
+                    let error = (log.severityText == "ERROR") as usize;
+                    if (60..90).contains(&second) {
+                        early_errors += error;
+                    } else if second >= 150 {
+                        late_errors += error;
+                    }
+                }
+            }
+        }
+
+        // Per-second volume: ~33 logs per region, then 6 health checks vs ~50
+        let rate = |counts: &HashMap<String, usize>, region: &str, secs: f64| {
+            counts.get(region).copied().unwrap_or(0) as f64 / secs
+        };
+        for region in ["us-east-1", "us-west-2", "eu-west-1"] {
+            assert!((25.0..42.0).contains(&rate(&before, region, 60.0)));
+        }
+        assert!(rate(&during, "us-east-1", 110.0) < 8.0);
+        for region in ["us-west-2", "eu-west-1"] {
+            let rate = rate(&during, region, 110.0);
+            assert!((40.0..62.0).contains(&rate), "{region}: {rate}");
+        }
+        assert!(
+            early_errors > late_errors * 3,
+            "{early_errors} -> {late_errors}"
+        );
+    }
+
+    #[test]
+    fn test_service_silence_is_absence() {
+        let mut engine = SimulationEngine::new_deterministic(12);
+        engine.start("normal_traffic");
+        engine.schedule_anomaly("service_silence", 30_000_000_000, 30_000_000_000);
+
+        let mut per_window = [0usize; 3];
+        let mut ground_truth = Vec::new();
+        for second in 0..90 {
+            let batch = engine.tick(1_000_000_000);
+            per_window[second / 30] += logs(&batch)
+                .filter(|l| l.service_name() == Some("inventory-service"))
+                .count();
+            assert!(logs(&batch).all(|l| !l.isGroundTruthAnomaly));
+            ground_truth = batch.ground_truth;
+        }
+
+        assert!(per_window[0] > 0 && per_window[2] > 0, "{per_window:?}");
+        assert_eq!(per_window[1], 0);
+        let gt = &ground_truth[0];
+        assert!(gt.absence);
+        assert_eq!(gt.log_count, 0);
+        assert!(gt.suppressed_log_count > 0);
+    }
+
+    #[test]
+    fn test_canary_regression_drifts_gradually() {
+        let mut engine = SimulationEngine::new_deterministic(8);
+        engine.start("normal_traffic");
+        engine.schedule_anomaly("canary_regression", 0, 1_500_000_000_000);
+
+        // Default ramp: 20 minutes; compare the first and the last 3 minutes
+        let (mut early, mut late) = (Vec::new(), Vec::new());
+        let (mut early_errors, mut late_errors) = (0, 0);
+        for second in 0..1_380 {
+            let batch = engine.tick(1_000_000_000);
+            for log in logs(&batch).filter(|l| l.isGroundTruthAnomaly) {
+                assert_eq!(log.service_name(), Some("payment-service"));
+                let latency = log.metric_value();
+                let error = log.severityText == "ERROR";
+                if second < 180 {
+                    early.push(latency);
+                    early_errors += error as usize;
+                } else if second >= 1_200 {
+                    late.push(latency);
+                    late_errors += error as usize;
+                }
+            }
+        }
+        let median = |v: &mut Vec<f64>| {
+            v.sort_by(f64::total_cmp);
+            v[v.len() / 2]
+        };
+        let (early_median, late_median) = (median(&mut early), median(&mut late));
+        assert!((40.0..75.0).contains(&early_median), "{early_median}");
+        assert!(
+            late_median > early_median * 2.0,
+            "{early_median} -> {late_median}"
+        );
+        assert!(
+            late_errors > early_errors * 3,
+            "{early_errors} -> {late_errors}"
+        );
+    }
+
+    #[test]
+    fn test_consumer_lag_ramps_then_recovers() {
+        let mut engine = SimulationEngine::new_deterministic(9);
+        engine.start("normal_traffic");
+        engine.schedule_anomaly("consumer_lag", 0, 900_000_000_000);
+
+        // Default: 5 minutes degraded at 40% of 500 msg/s, then 1.5x catch-up
+        let mut lag_by_second = Vec::new();
+        let (mut slow_stalled, mut slow_after) = (0, 0);
+        let mut caught_up_at = None;
+        for second in 0..900 {
+            let batch = engine.tick(1_000_000_000);
+            let mut lag = 0;
+            for log in logs(&batch).filter(|l| l.isGroundTruthAnomaly) {
+                assert_eq!(log.service_name(), Some("stream-processor"));
+                if let Some(value) = log.get_attribute("messaging.kafka.consumer.lag") {
+                    lag += value.as_i64().unwrap();
+                }
+                let slow = log.get_attribute("process.duration_ms").is_some()
+                    && log.severityText == "WARN";
+                if second < 300 {
+                    slow_stalled += slow as usize;
+                } else if second > 360 {
+                    slow_after += slow as usize;
+                }
+                if log.get_attribute("event").and_then(|v| v.as_str()) == Some("consumer_caught_up")
+                {
+                    caught_up_at.get_or_insert(second);
+                }
+            }
+            lag_by_second.push(lag);
+        }
+
+        // Lag climbs steadily while stalled, peaking near 90k messages
+        let peak = lag_by_second[299];
+        assert!((80_000..100_000).contains(&peak), "{peak}");
+        assert!(lag_by_second[60] < lag_by_second[150]);
+        assert!(lag_by_second[150] < lag_by_second[240]);
+        assert!(lag_by_second[330] < peak);
+        // ...and drains at 250 msg/s: about six minutes of catching up
+        let caught_up_at = caught_up_at.expect("consumer never caught up");
+        assert!((600..720).contains(&caught_up_at), "{caught_up_at}");
+        assert_eq!(lag_by_second[800], 0);
+        assert!(slow_stalled > 100 && slow_after < slow_stalled / 20);
+    }
+
+    #[test]
+    fn test_deploy_window_context_attributes() {
+        let mut engine = SimulationEngine::new_deterministic(5);
+        engine.start("normal_traffic");
+        let plain = engine.tick(1_000_000_000);
+        assert!(logs(&plain).all(|l| l.get_attribute(DEPLOY_IN_PROGRESS_ATTR).is_none()));
+
+        engine.schedule_deploy(1_000_000_000, 2_000_000_000);
+        let before = engine.tick(1_000_000_000);
+        let during = engine.tick(2_000_000_000);
+        let after = engine.tick(1_000_000_000);
+
+        assert!(logs(&before).all(|l| !l.exogenous_context().deploy_in_progress));
+        assert!(logs(&during).count() > 0);
+        assert!(logs(&during).all(|l| l.exogenous_context().deploy_in_progress));
+        assert!(logs(&after).all(|l| !l.exogenous_context().deploy_in_progress));
+        // Deterministic runs start at the epoch: Thursday 00:00 UTC
+        assert!(logs(&during).all(|l| l.exogenous_context().business_hours == Some(false)));
+        assert_eq!(during.metadata.anomaly_log_count, 0);
+
+        // Thursday 1970-01-01 10:00 UTC, and the Saturday after
+        assert!(is_business_hours(10 * 3_600 * 1_000_000_000));
+        assert!(!is_business_hours(
+            (2 * 86_400 + 10 * 3_600) * 1_000_000_000
+        ));
+    }
+
+    fn logs(batch: &SimulationBatch) -> impl Iterator<Item = &LogRecord> {
+        batch
+            .logs
+            .resourceLogs
+            .iter()
+            .flat_map(|r| &r.scopeLogs)
+            .flat_map(|s| &s.logRecords)
+    }
+
+    #[test]
+    fn test_rate_scale_multiplies_emission() {
+        let mut base = SimulationEngine::new_deterministic(11);
+        base.start("normal_traffic");
+        base.schedule_anomaly("traffic_spike", 0, 10_000_000_000);
+        let b1 = base.tick(1_000_000_000);
+
+        let mut scaled = SimulationEngine::new_deterministic(11);
+        scaled.set_rate_scale(4.0);
+        scaled.start("normal_traffic");
+        scaled.schedule_anomaly("traffic_spike", 0, 10_000_000_000);
+        let b4 = scaled.tick(1_000_000_000);
+
+        // Anomaly rate is exact; baseline rate carries jitter
+        assert_eq!(
+            b4.metadata.anomaly_log_count,
+            4 * b1.metadata.anomaly_log_count
+        );
+        let normal = |b: &SimulationBatch| b.metadata.log_count - b.metadata.anomaly_log_count;
+        let ratio = normal(&b4) as f64 / normal(&b1) as f64;
+        assert!(ratio > 3.0 && ratio < 5.0, "baseline ratio {ratio}");
+        assert_eq!(
+            b4.metadata.process_states[0].rps,
+            4.0 * b1.metadata.process_states[0].rps
+        );
+
+        // Rescaling a running engine is relative to the current scale
+        scaled.set_rate_scale(1.0);
+        let b = scaled.tick(1_000_000_000);
+        assert_eq!(b.metadata.process_states[1].rps, 1000.0);
+    }
+
     #[test]
     fn test_deterministic_replay_same_seed() {
         let mut e1 = SimulationEngine::new_deterministic(42);
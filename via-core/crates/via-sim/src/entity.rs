@@ -0,0 +1,229 @@
+//! Entity Keys
+//!
+//! Names the entity a log belongs to from one or more of its fields, so
+//! detection can run per source IP, per user, or per service and IP pair.
+//! A key is a field path (`client.ip`), several joined with `+`
+//! (`service.name + client.ip`), or a template with `{field}` placeholders
+//! (`{service.name}/{client.ip}`). Field paths are those of
+//! `LogRecord::field`.
+//!
+//! The engine records ground-truth entities with `EntityKey::hash` and
+//! via-bench keys detection with it too, so both sides hash the same text.
+
+use crate::core::LogRecord;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+/// Rendered key of logs that have none of the key's fields
+pub const UNKNOWN_ENTITY: &str = "unknown";
+
+/// Separator between fields of a `a + b` key
+const JOIN_SEPARATOR: &str = "|";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Field(String),
+}
+
+/// Template naming a log's entity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct EntityKey {
+    template: String,
+    parts: Vec<Part>,
+}
+
+impl EntityKey {
+    /// Key of a single field
+    pub fn field(path: impl Into<String>) -> Self {
+        let path = path.into();
+        Self {
+            template: path.clone(),
+            parts: vec![Part::Field(path)],
+        }
+    }
+
+    /// Field paths the key reads, in order
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Field(path) => Some(path.as_str()),
+            Part::Literal(_) => None,
+        })
+    }
+
+    /// The key's text for `log`, missing fields rendered empty; `None` when
+    /// the log has none of the fields
+    pub fn render<'a>(&self, log: &'a LogRecord) -> Option<Cow<'a, str>> {
+        if let [Part::Field(path)] = self.parts.as_slice() {
+            return log.field(path);
+        }
+        let mut found = false;
+        let mut text = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(literal) => text.push_str(literal),
+                Part::Field(path) => {
+                    if let Some(value) = log.field(path) {
+                        found = true;
+                        text.push_str(&value);
+                    }
+                }
+            }
+        }
+        found.then_some(Cow::Owned(text))
+    }
+
+    /// `render`, or `UNKNOWN_ENTITY` for logs without the key
+    pub fn entity<'a>(&self, log: &'a LogRecord) -> Cow<'a, str> {
+        self.render(log).unwrap_or(Cow::Borrowed(UNKNOWN_ENTITY))
+    }
+
+    /// Entity hash of `log`, as `via_hash_string` computes it over `entity`
+    pub fn hash(&self, log: &LogRecord) -> u64 {
+        xxhash_rust::xxh3::xxh3_64(self.entity(log).as_bytes())
+    }
+}
+
+impl Default for EntityKey {
+    /// One entity per trace
+    fn default() -> Self {
+        Self::field("traceId")
+    }
+}
+
+impl FromStr for EntityKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let template = s.trim();
+        let parts = if template.contains(['{', '}']) {
+            parse_template(template)?
+        } else {
+            let mut parts = Vec::new();
+            for (i, path) in template.split('+').map(str::trim).enumerate() {
+                if path.is_empty() {
+                    return Err(format!("empty field in entity key '{s}'"));
+                }
+                if i > 0 {
+                    parts.push(Part::Literal(JOIN_SEPARATOR.to_string()));
+                }
+                parts.push(Part::Field(path.to_string()));
+            }
+            parts
+        };
+        Ok(Self {
+            template: template.to_string(),
+            parts,
+        })
+    }
+}
+
+/// Literal text with `{field}` placeholders
+fn parse_template(template: &str) -> Result<Vec<Part>, String> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find(['{', '}']) {
+        if rest[open..].starts_with('}') {
+            return Err(format!("unmatched '}}' in entity key '{template}'"));
+        }
+        if open > 0 {
+            parts.push(Part::Literal(rest[..open].to_string()));
+        }
+        let close = rest[open..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed '{{' in entity key '{template}'"))?;
+        let path = rest[open + 1..open + close].trim();
+        if path.is_empty() || path.contains('{') {
+            return Err(format!("invalid placeholder in entity key '{template}'"));
+        }
+        parts.push(Part::Field(path.to_string()));
+        rest = &rest[open + close + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Literal(rest.to_string()));
+    }
+    if !parts.iter().any(|part| matches!(part, Part::Field(_))) {
+        return Err(format!("entity key '{template}' names no field"));
+    }
+    Ok(parts)
+}
+
+impl TryFrom<String> for EntityKey {
+    type Error = String;
+
+    fn try_from(template: String) -> Result<Self, Self::Error> {
+        template.parse()
+    }
+}
+
+impl From<EntityKey> for String {
+    fn from(key: EntityKey) -> Self {
+        key.template
+    }
+}
+
+impl fmt::Display for EntityKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.template)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::KeyValue;
+
+    fn log() -> LogRecord {
+        LogRecord {
+            traceId: "trace-1".to_string(),
+            attributes: vec![
+                KeyValue::string("service.name", "auth-service"),
+                KeyValue::string("client.ip", "10.0.0.7"),
+                KeyValue::int("http.status_code", 401),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_single_field_hashes_like_the_raw_value() {
+        let log = log();
+        let key = EntityKey::default();
+        assert_eq!(key.hash(&log), xxhash_rust::xxh3::xxh3_64(b"trace-1"));
+        let status: EntityKey = "http.status_code".parse().unwrap();
+        assert_eq!(status.entity(&log), "401");
+        let missing: EntityKey = "user.id".parse().unwrap();
+        assert_eq!(missing.entity(&log), UNKNOWN_ENTITY);
+    }
+
+    #[test]
+    fn test_composite_keys() {
+        let log = log();
+        let joined: EntityKey = "service.name + client.ip".parse().unwrap();
+        assert_eq!(joined.entity(&log), "auth-service|10.0.0.7");
+        assert_eq!(
+            joined.fields().collect::<Vec<_>>(),
+            ["service.name", "client.ip"]
+        );
+
+        let template: EntityKey = "{service.name}/{client.ip}:{user.id}".parse().unwrap();
+        assert_eq!(template.entity(&log), "auth-service/10.0.0.7:");
+        assert_eq!(template.to_string(), "{service.name}/{client.ip}:{user.id}");
+
+        for bad in ["", "a + ", "{a", "a}", "{}", "ip-{}"] {
+            assert!(bad.parse::<EntityKey>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let key: EntityKey = "service.name + client.ip".parse().unwrap();
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(json, "\"service.name + client.ip\"");
+        assert_eq!(serde_json::from_str::<EntityKey>(&json).unwrap(), key);
+        assert!(serde_json::from_str::<EntityKey>("\"{oops\"").is_err());
+    }
+}
@@ -0,0 +1,397 @@
+//! Benchmark Dataset Export
+//!
+//! `via-sim export` renders a generated run in the layouts public anomaly
+//! detection benchmarks ship, so detectors can be scored on via-sim data with
+//! the same harnesses and compared against published results:
+//! - `nab`: Numenta Anomaly Benchmark, one `data/<scenario>/<service>.csv`
+//!   (`timestamp,value`) per service plus `labels/combined_windows.json` and
+//!   `labels/combined_labels.json`
+//! - `swat`: one SWaT-style CSV with a column per service and a
+//!   `Normal/Attack` label per row
+//!
+//! Each service becomes a regularly sampled series: its logs are bucketed by
+//! the interval and a bucket holds their mean metric value (or their count).
+//! A bucket is labeled anomalous when any of its logs is ground truth.
+
+use crate::compression;
+use crate::core::{GroundTruth, LogRecord};
+use crate::truth::{self, TruthFile, TruthRecorder};
+use chrono::DateTime;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Timestamp format of NAB data files and labels
+const NAB_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+/// Timestamp format of NAB label windows
+const NAB_WINDOW_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.6f";
+/// Timestamp format of SWaT rows
+const SWAT_TIME_FORMAT: &str = "%d/%m/%Y %I:%M:%S %p";
+
+/// What a bucket of logs is reduced to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SeriesValue {
+    /// Mean `LogRecord::metric_value`; empty buckets repeat the last value
+    #[default]
+    Mean,
+    /// Number of logs
+    Count,
+}
+
+impl FromStr for SeriesValue {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "mean" => Ok(Self::Mean),
+            "count" => Ok(Self::Count),
+            other => Err(format!("expected mean or count, got '{other}'")),
+        }
+    }
+}
+
+impl fmt::Display for SeriesValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mean => f.write_str("mean"),
+            Self::Count => f.write_str("count"),
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+struct Bucket {
+    sum: f64,
+    count: u64,
+    anomalous: bool,
+}
+
+/// One service's samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub service: String,
+    pub values: Vec<f64>,
+    /// Ground truth per sample
+    pub labels: Vec<bool>,
+}
+
+/// Buckets logs into per-service series while a run is generated
+pub struct SeriesExporter {
+    start_ns: u64,
+    interval_ns: u64,
+    value: SeriesValue,
+    services: BTreeMap<String, Vec<Bucket>>,
+    truth: TruthRecorder,
+}
+
+impl SeriesExporter {
+    /// Series sampled every `interval_secs` from `start_ns`, the engine's
+    /// time before the first tick
+    pub fn new(start_ns: u64, interval_secs: u64, value: SeriesValue) -> Self {
+        Self {
+            start_ns,
+            interval_ns: interval_secs.max(1) * 1_000_000_000,
+            value,
+            services: BTreeMap::new(),
+            truth: TruthRecorder::new(None),
+        }
+    }
+
+    pub fn record(&mut self, log: &LogRecord) {
+        self.truth.record(log);
+        let ts: u64 = log.timeUnixNano.parse().unwrap_or(0);
+        let Some(offset) = ts.checked_sub(self.start_ns) else {
+            return;
+        };
+        let index = (offset / self.interval_ns) as usize;
+        let buckets = self
+            .services
+            .entry(log.service_name().unwrap_or("unknown").to_string())
+            .or_default();
+        if buckets.len() <= index {
+            buckets.resize(index + 1, Bucket::default());
+        }
+        let bucket = &mut buckets[index];
+        bucket.sum += log.metric_value();
+        bucket.count += 1;
+        bucket.anomalous |= log.isGroundTruthAnomaly;
+    }
+
+    /// The dataset, every series padded to the longest
+    pub fn finish(self, scenario: &str, seed: u64, ground_truth: &[GroundTruth]) -> Dataset {
+        let len = self.services.values().map(Vec::len).max().unwrap_or(0);
+        let series = self
+            .services
+            .into_iter()
+            .map(|(service, mut buckets)| {
+                buckets.resize(len, Bucket::default());
+                let mut last = 0.0;
+                let values = buckets
+                    .iter()
+                    .map(|b| match self.value {
+                        SeriesValue::Count => b.count as f64,
+                        SeriesValue::Mean => {
+                            if b.count > 0 {
+                                last = b.sum / b.count as f64;
+                            }
+                            last
+                        }
+                    })
+                    .collect();
+                let labels = buckets.iter().map(|b| b.anomalous).collect();
+                Series {
+                    service,
+                    values,
+                    labels,
+                }
+            })
+            .collect();
+
+        Dataset {
+            scenario: scenario.to_string(),
+            start_ns: self.start_ns,
+            interval_ns: self.interval_ns,
+            series,
+            truth: self.truth.finish(scenario, seed, ground_truth),
+        }
+    }
+}
+
+/// A run as aligned per-service series with its ground truth
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    /// Baseline scenario, the NAB data category
+    pub scenario: String,
+    pub start_ns: u64,
+    pub interval_ns: u64,
+    /// Series by service name
+    pub series: Vec<Series>,
+    pub truth: TruthFile,
+}
+
+impl Dataset {
+    /// Samples per series
+    pub fn len(&self) -> usize {
+        self.series.first().map_or(0, |s| s.values.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn timestamp(&self, index: usize, format: &str) -> String {
+        let ns = self.start_ns + index as u64 * self.interval_ns;
+        DateTime::from_timestamp_nanos(ns as i64)
+            .format(format)
+            .to_string()
+    }
+
+    /// NAB file key of a series, relative to `data/`
+    fn nab_key(&self, series: &Series) -> String {
+        format!(
+            "{}/{}.csv",
+            file_name(&self.scenario),
+            file_name(&series.service)
+        )
+    }
+
+    /// `combined_windows.json`: per file, the anomaly windows whose logs
+    /// include the series' service, clipped to the series
+    pub fn nab_windows(&self) -> BTreeMap<String, Vec<[String; 2]>> {
+        let last = self.len().saturating_sub(1) as u64;
+        let end_ns = self.start_ns + last * self.interval_ns;
+        self.series
+            .iter()
+            .map(|series| {
+                let windows = self
+                    .truth
+                    .windows
+                    .iter()
+                    .filter(|w| w.services.contains(&series.service))
+                    .filter(|w| w.start_time_ns <= end_ns && w.end_time_ns >= self.start_ns)
+                    .map(|w| {
+                        let start = self.index_of(w.start_time_ns);
+                        let end = self.index_of(w.end_time_ns);
+                        [
+                            self.timestamp(start, NAB_WINDOW_FORMAT),
+                            self.timestamp(end, NAB_WINDOW_FORMAT),
+                        ]
+                    })
+                    .collect();
+                (self.nab_key(series), windows)
+            })
+            .collect()
+    }
+
+    /// Sample holding `ts_ns`, clamped to the series
+    fn index_of(&self, ts_ns: u64) -> usize {
+        let index = ts_ns.saturating_sub(self.start_ns) / self.interval_ns;
+        (index as usize).min(self.len().saturating_sub(1))
+    }
+
+    /// Write the NAB layout under `dir`, returning the files written
+    pub fn write_nab(&self, dir: &Path) -> Result<Vec<PathBuf>, String> {
+        let data_dir = dir.join("data").join(file_name(&self.scenario));
+        let labels_dir = dir.join("labels");
+        for d in [&data_dir, &labels_dir] {
+            std::fs::create_dir_all(d)
+                .map_err(|e| format!("Failed to create {}: {}", d.display(), e))?;
+        }
+
+        let mut written = Vec::new();
+        for series in &self.series {
+            let mut csv = String::from("timestamp,value\n");
+            for (i, value) in series.values.iter().enumerate() {
+                csv.push_str(&format!(
+                    "{},{}\n",
+                    self.timestamp(i, NAB_TIME_FORMAT),
+                    value
+                ));
+            }
+            let path = dir.join("data").join(self.nab_key(series));
+            write_file(&path, &csv)?;
+            written.push(path);
+        }
+
+        let windows = self.nab_windows();
+        // NAB's labelers mark a point per anomaly; windows are grown around it
+        let labels: BTreeMap<&String, Vec<String>> = windows
+            .iter()
+            .map(|(key, windows)| {
+                let points = windows.iter().map(|[start, _]| start[..19].to_string());
+                (key, points.collect())
+            })
+            .collect();
+        for (name, json) in [
+            (
+                "combined_windows.json",
+                serde_json::to_string_pretty(&windows),
+            ),
+            (
+                "combined_labels.json",
+                serde_json::to_string_pretty(&labels),
+            ),
+        ] {
+            let path = labels_dir.join(name);
+            write_file(&path, &json.map_err(|e| e.to_string())?)?;
+            written.push(path);
+        }
+
+        let sidecar = labels_dir.join(format!("via-sim.{}", truth::TRUTH_SUFFIX));
+        self.truth.write(&sidecar)?;
+        written.push(sidecar);
+        Ok(written)
+    }
+
+    /// Write one SWaT-style CSV to `path`, with the ground-truth sidecar
+    /// next to it; returns the files written
+    pub fn write_swat(&self, path: &Path) -> Result<Vec<PathBuf>, String> {
+        let mut csv = String::from("Timestamp");
+        for series in &self.series {
+            csv.push(',');
+            csv.push_str(&file_name(&series.service));
+        }
+        csv.push_str(",Normal/Attack\n");
+        for i in 0..self.len() {
+            csv.push_str(&self.timestamp(i, SWAT_TIME_FORMAT));
+            for series in &self.series {
+                csv.push_str(&format!(",{}", series.values[i]));
+            }
+            let attack = self.series.iter().any(|s| s.labels[i]);
+            csv.push_str(if attack { ",Attack\n" } else { ",Normal\n" });
+        }
+        write_file(path, &csv)?;
+
+        let sidecar = truth::sidecar_path(path);
+        self.truth.write(&sidecar)?;
+        Ok(vec![path.to_path_buf(), sidecar])
+    }
+}
+
+/// `name` with characters unsafe in file names and CSV headers replaced
+fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    compression::write(path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulationEngine;
+
+    fn dataset(value: SeriesValue) -> Dataset {
+        let mut engine = SimulationEngine::new_deterministic(7);
+        engine.start("normal_traffic");
+        engine.schedule_anomaly("credential_stuffing", 3_000_000_000, 4_000_000_000);
+
+        let mut exporter = SeriesExporter::new(engine.current_time(), 1, value);
+        let mut ground_truth = Vec::new();
+        for _ in 0..100 {
+            let batch = engine.tick(100_000_000);
+            for log in &batch.logs.resourceLogs[0].scopeLogs[0].logRecords {
+                exporter.record(log);
+            }
+            ground_truth = batch.ground_truth;
+        }
+        exporter.finish("normal_traffic", 7, &ground_truth)
+    }
+
+    #[test]
+    fn test_series_are_aligned_and_labeled() {
+        let data = dataset(SeriesValue::Count);
+        assert_eq!(data.len(), 10);
+        for series in &data.series {
+            assert_eq!(series.values.len(), 10);
+            assert_eq!(series.labels.len(), 10);
+        }
+        let auth = data
+            .series
+            .iter()
+            .find(|s| s.service == "auth-service")
+            .unwrap();
+        assert!(auth.labels[4]);
+        assert!(!auth.labels[0]);
+        let logs: f64 = data.series.iter().flat_map(|s| &s.values).sum();
+        assert_eq!(logs as u64, data.truth.total_logs);
+    }
+
+    #[test]
+    fn test_nab_layout() {
+        let data = dataset(SeriesValue::Mean);
+        let dir = std::env::temp_dir().join(format!("via-sim-nab-{}", std::process::id()));
+        let written = data.write_nab(&dir).unwrap();
+        assert_eq!(written.len(), data.series.len() + 3);
+
+        let csv =
+            std::fs::read_to_string(dir.join("data/normal_traffic/auth-service.csv")).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("timestamp,value"));
+        assert!(lines.next().unwrap().starts_with("1970-01-01 00:00:00,"));
+
+        let windows: BTreeMap<String, Vec<[String; 2]>> = serde_json::from_str(
+            &std::fs::read_to_string(dir.join("labels/combined_windows.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            windows["normal_traffic/auth-service.csv"],
+            [[
+                "1970-01-01 00:00:03.000000".to_string(),
+                "1970-01-01 00:00:07.000000".to_string()
+            ]]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
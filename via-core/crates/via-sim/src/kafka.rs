@@ -0,0 +1,214 @@
+//! Kafka Source and Sink (feature `kafka`)
+//!
+//! `KafkaSink` publishes generated batches to a topic, one JSON `LogRecord`
+//! per message keyed by `service.name`, so partitions keep per-service order.
+//! Ground-truth fields travel with each record.
+//!
+//! `KafkaSource` consumes a topic into `SimulationBatch`es for the benchmark
+//! runner. Payloads are parsed like replay captures: a record, an OTLP JSON
+//! document, or JSON lines of either. Ground-truth windows are built from the
+//! labels as records arrive.
+
+use crate::core::{LogRecord, SimulationBatch};
+use crate::replay::{GroundTruthTracker, ReplayConfig, parse_logs, single_batch};
+use rdkafka::Message;
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::producer::{BaseProducer, BaseRecord, Producer};
+use std::time::Duration;
+
+/// Connection settings shared by source and sink
+#[derive(Debug, Clone)]
+pub struct KafkaConfig {
+    /// `bootstrap.servers`
+    pub brokers: String,
+    pub topic: String,
+    /// Consumer group (source only)
+    pub group_id: String,
+    /// Label attribute for records without `isGroundTruthAnomaly` (source only)
+    pub label_attribute: Option<String>,
+}
+
+impl Default for KafkaConfig {
+    fn default() -> Self {
+        Self {
+            brokers: "localhost:9092".to_string(),
+            topic: "via-logs".to_string(),
+            group_id: "via-bench".to_string(),
+            label_attribute: None,
+        }
+    }
+}
+
+/// Publishes simulation batches to a topic
+pub struct KafkaSink {
+    producer: BaseProducer,
+    topic: String,
+}
+
+impl KafkaSink {
+    pub fn new(config: &KafkaConfig) -> Result<Self, String> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", &config.brokers)
+            .create()
+            .map_err(|e| format!("Failed to create Kafka producer: {}", e))?;
+        Ok(Self {
+            producer,
+            topic: config.topic.clone(),
+        })
+    }
+
+    /// Queue every record of the batch, returning the number of messages.
+    ///
+    /// Blocks while the local producer queue is full.
+    pub fn publish(&self, batch: &SimulationBatch) -> Result<usize, String> {
+        let mut sent = 0;
+        for resource_log in &batch.logs.resourceLogs {
+            for scope_log in &resource_log.scopeLogs {
+                for log in &scope_log.logRecords {
+                    let (key, payload) = encode(log)?;
+                    let mut record = BaseRecord::to(&self.topic).key(key).payload(&payload);
+                    loop {
+                        match self.producer.send(record) {
+                            Ok(()) => break,
+                            Err((
+                                KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull),
+                                r,
+                            )) => {
+                                record = r;
+                                self.producer.poll(Duration::from_millis(100));
+                            }
+                            Err((e, _)) => return Err(format!("Kafka send failed: {}", e)),
+                        }
+                    }
+                    sent += 1;
+                }
+            }
+        }
+        self.producer.poll(Duration::ZERO);
+        Ok(sent)
+    }
+
+    /// Wait for queued messages to be delivered
+    pub fn flush(&self, timeout: Duration) -> Result<(), String> {
+        self.producer
+            .flush(timeout)
+            .map_err(|e| format!("Kafka flush failed: {}", e))
+    }
+}
+
+/// Consumes a topic into simulation batches
+pub struct KafkaSource {
+    consumer: BaseConsumer,
+    replay: ReplayConfig,
+    tracker: GroundTruthTracker,
+    first_ts: Option<u64>,
+    received: u64,
+}
+
+impl KafkaSource {
+    /// Subscribe to the topic, starting from the earliest offset for a new group
+    pub fn new(config: &KafkaConfig) -> Result<Self, String> {
+        let consumer: BaseConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &config.brokers)
+            .set("group.id", &config.group_id)
+            .set("auto.offset.reset", "earliest")
+            .create()
+            .map_err(|e| format!("Failed to create Kafka consumer: {}", e))?;
+        consumer
+            .subscribe(&[&config.topic])
+            .map_err(|e| format!("Failed to subscribe to {}: {}", config.topic, e))?;
+        Ok(Self {
+            consumer,
+            replay: ReplayConfig {
+                label_attribute: config.label_attribute.clone(),
+            },
+            tracker: GroundTruthTracker::default(),
+            first_ts: None,
+            received: 0,
+        })
+    }
+
+    /// Up to `max_messages` messages as one batch, waiting at most `timeout`
+    /// for the first. `Ok(None)` when nothing arrived in time.
+    pub fn next_batch(
+        &mut self,
+        max_messages: usize,
+        timeout: Duration,
+    ) -> Result<Option<SimulationBatch>, String> {
+        let mut logs = Vec::new();
+        let mut wait = timeout;
+        for _ in 0..max_messages.max(1) {
+            let Some(message) = self.consumer.poll(wait) else {
+                break;
+            };
+            wait = Duration::ZERO;
+            let message = message.map_err(|e| format!("Kafka receive failed: {}", e))?;
+            let Some(payload) = message.payload() else {
+                continue;
+            };
+            logs.extend(decode(payload, &self.replay)?);
+        }
+        if logs.is_empty() {
+            return Ok(None);
+        }
+
+        let mut last_ts = 0u64;
+        for log in logs.iter_mut() {
+            self.tracker.observe(log);
+            last_ts = last_ts.max(log.timeUnixNano.parse().unwrap_or(0));
+        }
+        let first_ts = *self.first_ts.get_or_insert(last_ts);
+        self.received += logs.len() as u64;
+        Ok(Some(single_batch(
+            logs,
+            self.tracker.windows.clone(),
+            last_ts,
+            last_ts.saturating_sub(first_ts),
+            self.received,
+            "kafka",
+        )))
+    }
+}
+
+fn encode(log: &LogRecord) -> Result<(&str, String), String> {
+    let payload =
+        serde_json::to_string(log).map_err(|e| format!("Failed to encode log record: {}", e))?;
+    Ok((log.service_name().unwrap_or(""), payload))
+}
+
+fn decode(payload: &[u8], config: &ReplayConfig) -> Result<Vec<LogRecord>, String> {
+    let text =
+        std::str::from_utf8(payload).map_err(|e| format!("Kafka payload is not UTF-8: {}", e))?;
+    parse_logs(text, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::KeyValue;
+
+    #[test]
+    fn test_published_records_decode_with_ground_truth() {
+        let mut log = LogRecord {
+            timeUnixNano: "1000".to_string(),
+            attributes: vec![
+                KeyValue::string("service.name", "payments"),
+                KeyValue::double("latency_ms", 250.0),
+            ],
+            ..Default::default()
+        };
+        log.mark_anomalous("memory_leak-1".to_string());
+
+        let (key, payload) = encode(&log).unwrap();
+        assert_eq!(key, "payments");
+        let decoded = decode(payload.as_bytes(), &ReplayConfig::default()).unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert!(decoded[0].isGroundTruthAnomaly);
+        assert_eq!(decoded[0].anomalyId.as_deref(), Some("memory_leak-1"));
+        assert_eq!(decoded[0].metric_value(), 250.0);
+
+        assert!(decode(&[0xff, 0xfe], &ReplayConfig::default()).is_err());
+    }
+}
@@ -73,18 +73,35 @@
 //! | Category    | Scenario               | Description                           |
 //! |-------------|------------------------|---------------------------------------|
 //! | Traffic     | `normal_traffic`       | Baseline realistic traffic            |
+//! |             | `seasonal_traffic`     | Daily / weekly load curves, bursts    |
 //! |             | `traffic_spike`        | Sudden traffic burst                  |
 //! | Security    | `credential_stuffing`  | Brute force login attempts            |
 //! |             | `sql_injection`        | SQL injection probes                  |
 //! |             | `port_scan`            | Network port scanning                 |
+//! |             | `ransomware`           | Escalating file-encryption burst      |
 //! | Performance | `memory_leak`          | Gradual memory increase → OOM         |
 //! |             | `cpu_spike`            | High CPU causing timeouts             |
 //! |             | `infinite_loop`        | Stack overflow simulation             |
+//! |             | `canary_regression`    | Gradual drift on canary instances     |
+//! |             | `consumer_lag`         | Stream consumer lag ramp and recovery |
 //! | Distributed | `ddos`                 | Multi-source DDoS attack              |
 //! |             | `cascade_failure`      | Service failure propagation           |
 //! |             | `data_exfiltration`    | Large suspicious data transfers       |
+//! |             | `api_scraping`         | Low-and-slow scraping, rotating keys  |
+//! |             | `beaconing`            | Periodic DNS tunneling from one host  |
+//! |             | `regional_outage`      | Region drops out, others spike        |
+//! |             | `service_silence`      | Service stops logging (absence)       |
 //! |             | `slow_queries`         | Database performance degradation      |
 //! |             | `error_spike`          | Sudden error rate increase            |
+//! | E-commerce  | `checkout_funnel`      | Checkout flow with business metrics   |
+//! |             | `payment_outage`       | Payment provider timeouts / 503s      |
+//! |             | `fraud_burst`          | Card-testing order burst              |
+//! | IoT         | `iot_fleet`            | Device fleet telemetry (per-device)   |
+//! |             | `firmware_regression`  | Bad firmware rollout cohort           |
+//! |             | `sensor_drift`         | Sensors drifting out of calibration   |
+//! | Churn       | `entity_churn`         | Short-lived workers around a core     |
+//! |             | `core_latency_regression` | Core services slow down under churn |
+//! | Sessions    | `user_sessions`        | Users log in, browse, buy and churn   |
 
 // Core types - single source of truth
 pub mod core;
@@ -95,36 +112,117 @@ pub mod scenarios;
 // Unified simulation engine
 pub mod engine;
 
+// Entity key templates shared by ground truth and detection
+pub mod entity;
+
+// Service dependency graph requests are walked through
+pub mod topology;
+
+// Daily / weekly load curves and arrival burstiness of baseline traffic
+pub mod seasonality;
+
 // HTTP Control API
 pub mod api;
 
+// Token-bucket pacing of live ticks
+pub mod pacing;
+
+// HTTP server for interactive mode
+#[cfg(feature = "server")]
+pub mod server;
+
+// TOML scenario configs and `init` scaffolding
+pub mod config;
+
+// Replay of recorded OTel logs
+pub mod replay;
+
+// Ground-truth sidecar files for generated datasets
+pub mod truth;
+
+// Clock skew, late batches and duplicates on delivery
+pub mod delivery;
+
+// Normal and anomaly log sampling
+pub mod sampling;
+
+// Recurring, cron and budgeted anomaly schedules
+pub mod schedule;
+
+// Anomaly-free runs with a signed stream certificate
+pub mod clean;
+
+// gzip / zstd file output and input by extension
+pub mod compression;
+
+// NAB / SWaT-style benchmark dataset export
+pub mod export;
+
+// Kafka source/sink
+#[cfg(feature = "kafka")]
+pub mod kafka;
+
+// Parquet / Arrow IPC log files
+#[cfg(feature = "parquet")]
+pub mod columnar;
+
 // Re-exports for convenience
 pub use core::{
-    AnyValue, BatchMetadata, GroundTruth, KeyValue, LogRecord, OTelLog, Resource, ResourceLog,
-    ScopeLog, SimulationBatch,
+    AnyValue, BUSINESS_HOURS_ATTR, BatchMetadata, DEPLOY_IN_PROGRESS_ATTR, GroundTruth, KeyValue,
+    LatencyModel, LogRecord, OTelLog, ProcessState, Resource, ResourceLog, ScopeLog,
+    SimulationBatch, is_business_hours,
 };
 
+pub use clean::{CleanAudit, CleanCertificate};
+pub use config::{ScenarioConfig, ScheduledAnomaly};
+pub use delivery::{Delivery, DeliveryConfig, DeliveryStats};
+pub use export::{Dataset, Series, SeriesExporter, SeriesValue};
+pub use pacing::{Pacer, PacingConfig, TokenBucket};
+pub use replay::{ReplayConfig, ReplaySource};
+pub use sampling::{Sampler, SamplingConfig, SamplingStats};
+pub use schedule::{CronSchedule, Recurrence};
+pub use truth::{TruthFile, TruthRecorder, TruthWindow};
+
+#[cfg(feature = "kafka")]
+pub use kafka::{KafkaConfig, KafkaSink, KafkaSource};
+
+#[cfg(feature = "parquet")]
+pub use columnar::{ColumnarFormat, ColumnarWriter};
+
 pub use engine::{DeterminismConfig, EngineState, EngineStats, SimulationEngine};
+pub use entity::EntityKey;
+pub use seasonality::{Burstiness, Seasonality};
+pub use topology::{Hop, HopError, Request, Service, Topology};
 
 pub use scenarios::{
     Scenario,
+    // Churn
+    churn::{CoreLatencyRegression, EntityChurn, Population},
     create_scenario,
     // Distributed
     distributed::{
-        CascadeFailure, DDoSAttack, DataExfiltration, ErrorRateSpike, SlowQueries, TrafficSpike,
+        ApiScraping, Beaconing, CascadeFailure, DDoSAttack, DataExfiltration, ErrorRateSpike,
+        RegionalOutage, ServiceSilence, SlowQueries, TrafficSpike,
     },
+    // E-commerce
+    ecommerce::{CheckoutFunnel, FraudBurst, PaymentProviderOutage},
+    // IoT
+    iot::{FirmwareRegression, Fleet, FleetTelemetry, SensorDrift},
     list_scenarios,
     // Performance
-    performance::{CpuSpike, InfiniteLoop, MemoryLeak},
+    performance::{CanaryRegression, ConsumerLag, CpuSpike, InfiniteLoop, MemoryLeak},
     // Security
-    security::{CredentialStuffing, PortScan, SqlInjection},
+    security::{CredentialStuffing, PortScan, Ransomware, SqlInjection},
+    // Sessions
+    sessions::{UserBase, UserSessions},
     // Traffic
     traffic::NormalTraffic,
 };
 
 pub use api::{
-    ApiConfig, ApiResponse, InjectAnomalyRequest, SharedState, SimulationState, StartRequest,
-    create_shared_state, handle_change_rate, handle_get_dashboard, handle_get_status,
-    handle_inject_anomaly, handle_list_scenarios, handle_pause, handle_resume, handle_start,
-    handle_stop, handle_tick, print_api_docs,
+    ApiConfig, ApiResponse, InjectAnomalyRequest, LiveDetectionEngine, SharedState, SignalFilter,
+    SimulationState, StartRequest, create_shared_state, dispatch, handle_change_rate,
+    handle_get_dashboard, handle_get_signals, handle_get_status, handle_inject_anomaly,
+    handle_list_scenarios, handle_pause, handle_resume, handle_start, handle_stop, handle_tick,
+    print_api_docs, signal_events,
 };
@@ -3,11 +3,26 @@
 //! Usage:
 //!   via-sim generate --duration 5m --scenario normal_traffic
 //!   via-sim generate --duration 1m --anomalies memory_leak,ddos
-//!   via-sim interactive --port 8080
+//!   via-sim generate --config via-sim.toml
+//!   via-sim generate --anomalies ddos --output logs.jsonl      (+ logs.truth.json)
+//!   via-sim generate --duration 1h --output logs.jsonl.zst      (.gz and .zst compress)
+//!   via-sim generate --duration 10m --realtime                  (or --speed 10x)
+//!   via-sim generate --format parquet --output logs.parquet     (feature `parquet`)
+//!   via-sim generate --skew-ms 500 --reorder-rate 0.1 --duplicate-rate 0.01
+//!   via-sim export --format nab --anomalies ddos --output nab/
+//!   via-sim init
+//!   via-sim interactive --port 8080                             (feature `server`)
+//!   via-sim publish --brokers localhost:9092 --topic via-logs   (feature `kafka`)
 //!   via-sim list
+//!   via-sim completions bash > /etc/bash_completion.d/via-sim
 
-use clap::{Parser, Subcommand, ValueEnum};
-use via_sim::{SimulationEngine, scenarios};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use std::io::Write;
+use std::path::Path;
+use via_sim::{
+    DeliveryConfig, EntityKey, SamplingConfig, ScenarioConfig, ScheduledAnomaly, SeriesExporter,
+    SeriesValue, SimulationEngine, TruthRecorder, clean, compression, config, scenarios, truth,
+};
 
 #[derive(Parser)]
 #[command(name = "via-sim")]
@@ -44,11 +59,160 @@ enum Commands {
         /// Deterministic simulation seed
         #[arg(long, default_value = "42")]
         seed: u64,
+
+        /// TOML scenario config (see `via-sim init`); replaces the flags above
+        #[arg(long)]
+        config: Option<String>,
+
+        /// Write logs to this file instead of stdout, with every anomaly
+        /// window in a companion <name>.truth.json
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// List each window's entities under this key in the truth file
+        /// (e.g. "source.ip" or "service.name + user.id")
+        #[arg(long)]
+        entity_key: Option<EntityKey>,
+
+        /// Emit logs at wall-clock rate instead of as fast as possible
+        #[arg(long)]
+        realtime: bool,
+
+        /// Wall-clock pacing as a multiple of real time (e.g. 10x, 0.5x);
+        /// implies --realtime
+        #[arg(long, value_parser = parse_speed)]
+        speed: Option<f64>,
+
+        /// Offset each service's clock by up to this many ms, ahead or behind
+        #[arg(long)]
+        skew_ms: Option<u64>,
+
+        /// Chance a tick's logs are delivered late, behind newer ones
+        #[arg(long)]
+        reorder_rate: Option<f64>,
+
+        /// Ticks a late batch waits, at most (default 5)
+        #[arg(long)]
+        max_delay_ticks: Option<u32>,
+
+        /// Chance a log is delivered twice
+        #[arg(long)]
+        duplicate_rate: Option<f64>,
+
+        /// Fraction of normal logs written (e.g. 0.01); anomaly logs are
+        /// all kept unless --anomaly-sample-rate says otherwise
+        #[arg(long)]
+        sample_rate: Option<f64>,
+
+        /// Fraction of ground-truth anomaly logs written
+        #[arg(long)]
+        anomaly_sample_rate: Option<f64>,
+
+        /// Certified anomaly-free run: baseline scenarios only, with a
+        /// <name>.clean.json certificate (HMAC-signed when
+        /// VIA_SIM_SIGNING_KEY is set)
+        #[arg(long)]
+        clean: bool,
+    },
+
+    /// Export a generated run as a benchmark dataset (NAB or SWaT layout)
+    Export {
+        /// Dataset layout
+        #[arg(short, long, default_value = "nab")]
+        format: ExportFormat,
+
+        /// Directory (nab) or CSV file (swat) to write
+        #[arg(short, long)]
+        output: String,
+
+        /// Sampling interval of the series (e.g., 1s, 1m)
+        #[arg(long, default_value = "1s")]
+        interval: String,
+
+        /// Sample value: `mean` metric of the bucket's logs or their `count`
+        #[arg(long, default_value = "mean")]
+        value: SeriesValue,
+
+        /// Duration (e.g., 5m, 1h, 30s)
+        #[arg(short, long, default_value = "1m")]
+        duration: String,
+
+        /// Base scenario for background traffic
+        #[arg(short, long, default_value = "normal_traffic")]
+        scenario: String,
+
+        /// Anomalies to inject (comma-separated)
+        #[arg(short, long)]
+        anomalies: Option<String>,
+
+        /// Tick interval in milliseconds
+        #[arg(long, default_value = "100")]
+        tick_ms: u64,
+
+        /// Deterministic simulation seed
+        #[arg(long, default_value = "42")]
+        seed: u64,
+
+        /// TOML scenario config (see `via-sim init`); replaces the run flags
+        #[arg(long)]
+        config: Option<String>,
+    },
+
+    /// Publish generated logs to a Kafka topic
+    #[cfg(feature = "kafka")]
+    Publish {
+        /// Duration (e.g., 5m, 1h, 30s)
+        #[arg(short, long, default_value = "1m")]
+        duration: String,
+
+        /// Base scenario for background traffic
+        #[arg(short, long, default_value = "normal_traffic")]
+        scenario: String,
+
+        /// Anomalies to inject (comma-separated)
+        #[arg(short, long)]
+        anomalies: Option<String>,
+
+        /// Tick interval in milliseconds
+        #[arg(long, default_value = "100")]
+        tick_ms: u64,
+
+        /// Deterministic simulation seed
+        #[arg(long, default_value = "42")]
+        seed: u64,
+
+        /// Kafka bootstrap servers
+        #[arg(long, default_value = "localhost:9092")]
+        brokers: String,
+
+        /// Topic to publish to
+        #[arg(long, default_value = "via-logs")]
+        topic: String,
     },
 
     /// List available scenarios
     List,
 
+    /// Write a commented example scenario config (via-sim.toml)
+    Init {
+        /// Directory to write into
+        #[arg(default_value = ".")]
+        dir: String,
+
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Print shell completions
+    Completions {
+        /// Target shell
+        shell: clap_complete::Shell,
+    },
+
+    /// Print the man page (roff)
+    Man,
+
     /// Interactive mode with HTTP API
     Interactive {
         /// Port to listen on
@@ -58,6 +222,14 @@ enum Commands {
         /// Host to bind to
         #[arg(long, default_value = "127.0.0.1")]
         host: String,
+
+        /// Background tick interval in milliseconds
+        #[arg(long, default_value = "100")]
+        tick_ms: u64,
+
+        /// Logs admitted per second across all ticks (0 = unlimited)
+        #[arg(long, default_value = "100000")]
+        max_events_per_sec: u64,
     },
 
     /// Run throughput benchmark
@@ -77,12 +249,156 @@ enum OutputFormat {
     Json,
     JsonLines,
     Pretty,
+    /// Parquet file (needs --output)
+    #[cfg(feature = "parquet")]
+    Parquet,
+    /// Arrow IPC file (needs --output)
+    #[cfg(feature = "parquet")]
+    Arrow,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    /// Numenta Anomaly Benchmark: data/<scenario>/<service>.csv + labels/
+    Nab,
+    /// One CSV, a column per service and a Normal/Attack label per row
+    Swat,
+}
+
+/// Layout and sampling of an `export` run
+struct ExportOptions {
+    format: ExportFormat,
+    path: String,
+    interval_secs: u64,
+    value: SeriesValue,
+}
+
+/// Where and how `generate` writes logs
+struct GenerateOutput {
+    format: OutputFormat,
+    /// Log file, stdout when absent; files get a ground-truth sidecar
+    path: Option<String>,
+    /// Key rendered into the sidecar's window entities
+    entity_key: Option<EntityKey>,
+    /// Simulated seconds per wall-clock second; unpaced when absent
+    speed: Option<f64>,
+    /// Skew, reordering and duplication applied to written logs
+    delivery: DeliveryConfig,
+    /// Fractions of normal and anomaly logs written
+    sampling: SamplingConfig,
+    /// Refuse anomalies and certify the stream anomaly-free
+    clean: bool,
+}
+
+/// `--skew-ms` and friends layered over `base`
+fn delivery_flags(
+    mut base: DeliveryConfig,
+    skew_ms: Option<u64>,
+    reorder_rate: Option<f64>,
+    max_delay_ticks: Option<u32>,
+    duplicate_rate: Option<f64>,
+) -> DeliveryConfig {
+    base.max_skew_ms = skew_ms.unwrap_or(base.max_skew_ms);
+    base.reorder_rate = reorder_rate.unwrap_or(base.reorder_rate);
+    base.duplicate_rate = duplicate_rate.unwrap_or(base.duplicate_rate);
+    base.max_delay_ticks = match max_delay_ticks {
+        Some(ticks) => ticks,
+        None if base.reorder_rate > 0.0 && base.max_delay_ticks == 0 => 5,
+        None => base.max_delay_ticks,
+    };
+    base
+}
+
+/// `--sample-rate` and `--anomaly-sample-rate` layered over `base`
+fn sampling_flags(
+    mut base: SamplingConfig,
+    sample_rate: Option<f64>,
+    anomaly_sample_rate: Option<f64>,
+) -> SamplingConfig {
+    base.normal_rate = sample_rate.unwrap_or(base.normal_rate);
+    base.anomaly_rate = anomaly_sample_rate.unwrap_or(base.anomaly_rate);
+    base
+}
+
+/// Sleeps between ticks so simulated time tracks the wall clock
+struct WallClockPacer {
+    start: std::time::Instant,
+    speed: f64,
+}
+
+impl WallClockPacer {
+    fn new(speed: f64) -> Self {
+        Self {
+            start: std::time::Instant::now(),
+            speed,
+        }
+    }
+
+    /// Wall-clock time at which `simulated_ns` is due
+    fn due(&self, simulated_ns: u64) -> std::time::Duration {
+        std::time::Duration::from_secs_f64(simulated_ns as f64 / 1e9 / self.speed)
+    }
+
+    /// Sleep until `simulated_ns` is due; returns at once when behind
+    fn wait(&self, simulated_ns: u64) {
+        if let Some(ahead) = self.due(simulated_ns).checked_sub(self.start.elapsed()) {
+            std::thread::sleep(ahead);
+        }
+    }
+
+    /// How far generation fell behind the wall clock at `simulated_ns`
+    fn lag(&self, simulated_ns: u64) -> std::time::Duration {
+        self.start.elapsed().saturating_sub(self.due(simulated_ns))
+    }
 }
 
 fn main() {
     let cli = Cli::parse();
 
     match cli.command {
+        Commands::Generate {
+            config: Some(path),
+            output,
+            entity_key,
+            realtime,
+            speed,
+            skew_ms,
+            reorder_rate,
+            max_delay_ticks,
+            duplicate_rate,
+            sample_rate,
+            anomaly_sample_rate,
+            clean,
+            ..
+        } => {
+            let config = ScenarioConfig::load(&path).unwrap_or_else(|e| exit_with(&e));
+            let format = OutputFormat::from_str(&config.format, true)
+                .unwrap_or_else(|e| exit_with(&format!("{}: format {}", path, e)));
+            let output = GenerateOutput {
+                format,
+                path: output.or(config.output),
+                entity_key: entity_key.or(config.entity_key),
+                speed: speed.or(realtime.then_some(1.0)),
+                delivery: delivery_flags(
+                    config.delivery,
+                    skew_ms,
+                    reorder_rate,
+                    max_delay_ticks,
+                    duplicate_rate,
+                ),
+                sampling: sampling_flags(config.sampling, sample_rate, anomaly_sample_rate),
+                clean,
+            };
+            run_generate(
+                config.duration,
+                config.scenario,
+                None,
+                &config.anomalies,
+                &output,
+                config.tick_ms,
+                config.seed,
+            );
+        }
         Commands::Generate {
             duration,
             scenario,
@@ -90,14 +406,126 @@ fn main() {
             format,
             tick_ms,
             seed,
+            config: None,
+            output,
+            entity_key,
+            realtime,
+            speed,
+            skew_ms,
+            reorder_rate,
+            max_delay_ticks,
+            duplicate_rate,
+            sample_rate,
+            anomaly_sample_rate,
+            clean,
+        } => {
+            let output = GenerateOutput {
+                format,
+                path: output,
+                entity_key,
+                speed: speed.or(realtime.then_some(1.0)),
+                delivery: delivery_flags(
+                    DeliveryConfig::default(),
+                    skew_ms,
+                    reorder_rate,
+                    max_delay_ticks,
+                    duplicate_rate,
+                ),
+                sampling: sampling_flags(
+                    SamplingConfig::default(),
+                    sample_rate,
+                    anomaly_sample_rate,
+                ),
+                clean,
+            };
+            run_generate(duration, scenario, anomalies, &[], &output, tick_ms, seed);
+        }
+        Commands::Export {
+            format,
+            output,
+            interval,
+            value,
+            duration,
+            scenario,
+            anomalies,
+            tick_ms,
+            seed,
+            config,
+        } => {
+            let export = ExportOptions {
+                format,
+                path: output,
+                interval_secs: parse_duration(&interval),
+                value,
+            };
+            match config {
+                Some(path) => {
+                    let config = ScenarioConfig::load(&path).unwrap_or_else(|e| exit_with(&e));
+                    run_export(
+                        config.duration,
+                        config.scenario,
+                        None,
+                        &config.anomalies,
+                        &export,
+                        config.tick_ms,
+                        config.seed,
+                    );
+                }
+                None => run_export(duration, scenario, anomalies, &[], &export, tick_ms, seed),
+            }
+        }
+        #[cfg(feature = "kafka")]
+        Commands::Publish {
+            duration,
+            scenario,
+            anomalies,
+            tick_ms,
+            seed,
+            brokers,
+            topic,
         } => {
-            run_generate(duration, scenario, anomalies, format, tick_ms, seed);
+            let kafka = via_sim::KafkaConfig {
+                brokers,
+                topic,
+                ..Default::default()
+            };
+            run_publish(duration, scenario, anomalies, tick_ms, seed, &kafka);
         }
         Commands::List => {
             run_list();
         }
-        Commands::Interactive { port, host } => {
-            run_interactive(host, port);
+        Commands::Init { dir, force } => {
+            let files = [(config::SCENARIO_FILE, config::SCENARIO_TEMPLATE)];
+            match config::write_scaffold(std::path::Path::new(&dir), &files, force) {
+                Ok(paths) => {
+                    for path in paths {
+                        eprintln!("Wrote {}", path.display());
+                    }
+                    eprintln!("Edit it, then run: via-sim generate --config <file>");
+                }
+                Err(e) => exit_with(&e),
+            }
+        }
+        Commands::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut Cli::command(),
+                "via-sim",
+                &mut std::io::stdout(),
+            );
+        }
+        Commands::Man => {
+            clap_mangen::Man::new(Cli::command())
+                .render(&mut std::io::stdout())
+                .unwrap_or_else(|e| exit_with(&format!("Failed to render man page: {}", e)));
+        }
+        Commands::Interactive {
+            port,
+            host,
+            tick_ms,
+            max_events_per_sec,
+        } => {
+            run_interactive(host, port, tick_ms, max_events_per_sec);
         }
         Commands::Benchmark {
             duration,
@@ -108,11 +536,17 @@ fn main() {
     }
 }
 
+fn exit_with(msg: &str) -> ! {
+    eprintln!("Error: {}", msg);
+    std::process::exit(1);
+}
+
 fn run_generate(
     duration: String,
     scenario: String,
     anomalies: Option<String>,
-    format: OutputFormat,
+    planned: &[ScheduledAnomaly],
+    output: &GenerateOutput,
     tick_ms: u64,
     seed: u64,
 ) {
@@ -121,51 +555,108 @@ fn run_generate(
     eprintln!("╠══════════════════════════════════════════════════════════════╣");
     eprintln!("║ Duration: {:50} ║", duration);
     eprintln!("║ Scenario: {:50} ║", scenario);
-    eprintln!(
-        "║ Anomalies: {:49} ║",
-        anomalies.as_deref().unwrap_or("none")
-    );
+    let anomaly_label = if planned.is_empty() {
+        anomalies.clone().unwrap_or_else(|| "none".to_string())
+    } else {
+        let names: Vec<&str> = planned.iter().map(|a| a.scenario.as_str()).collect();
+        names.join(",")
+    };
+    eprintln!("║ Anomalies: {:49} ║", anomaly_label);
     eprintln!("║ Seed: {:54} ║", seed);
+    if let Some(speed) = output.speed {
+        eprintln!("║ Pacing: {:52} ║", format!("{}x real time", speed));
+    }
+    let delivery = &output.delivery;
+    if delivery.is_enabled() {
+        let label = format!(
+            "skew ±{}ms, reorder {}, duplicate {}",
+            delivery.max_skew_ms, delivery.reorder_rate, delivery.duplicate_rate
+        );
+        eprintln!("║ Delivery: {:50} ║", label);
+    }
+    let sampling = &output.sampling;
+    if sampling.is_enabled() {
+        let label = format!(
+            "normal {}, anomaly {}",
+            sampling.normal_rate, sampling.anomaly_rate
+        );
+        eprintln!("║ Sampling: {:50} ║", label);
+    }
+    if output.clean {
+        eprintln!("║ Clean: {:53} ║", "baseline only, certified");
+    }
     eprintln!("╚══════════════════════════════════════════════════════════════╝");
 
+    if output.clean {
+        if anomalies.is_some() || !planned.is_empty() {
+            exit_with("--clean runs take no anomalies");
+        }
+        if scenarios::create_scenario(&scenario).is_some_and(|s| !s.is_baseline()) {
+            exit_with(&format!(
+                "--clean: '{}' is not a baseline scenario",
+                scenario
+            ));
+        }
+    }
+
     let duration_ns = parse_duration(&duration) * 1_000_000_000;
     let tick_ns = tick_ms * 1_000_000;
 
     let mut engine = SimulationEngine::new_deterministic(seed);
+    engine.set_clean(output.clean);
     engine.start(&scenario);
+    engine.set_delivery(output.delivery.clone());
+    engine.set_sampling(output.sampling.clone());
 
     // Schedule anomalies if provided
     if let Some(anomaly_list) = anomalies {
-        let anomaly_count = anomaly_list.split(',').count();
-        let anomaly_duration_ns = duration_ns / (anomaly_count as u64 + 1);
-        let mut offset_ns = anomaly_duration_ns / 2; // Start anomalies after initial baseline
-
-        for anomaly_name in anomaly_list.split(',') {
-            let name = anomaly_name.trim();
-            if let Some(id) = engine.schedule_anomaly(name, offset_ns, anomaly_duration_ns / 2) {
-                eprintln!(
-                    "Scheduled anomaly '{}' (id: {}) at offset {}ms for {}ms",
-                    name,
-                    id,
-                    offset_ns / 1_000_000,
-                    anomaly_duration_ns / 2 / 1_000_000
-                );
-            } else {
-                eprintln!("Warning: Unknown anomaly type '{}'", name);
-            }
-            offset_ns += anomaly_duration_ns;
-        }
+        schedule_anomalies(&mut engine, &anomaly_list, duration_ns);
     }
+    schedule_planned(&mut engine, planned, duration_ns);
 
     eprintln!("\nGenerating logs...\n");
 
+    #[cfg(feature = "parquet")]
+    let mut columnar = open_columnar(output);
+    // Files are compressed by extension (logs.jsonl.gz, logs.jsonl.zst)
+    let mut file = match &output.path {
+        #[cfg(feature = "parquet")]
+        Some(_) if columnar.is_some() => None,
+        Some(path) => match compression::create(Path::new(path)) {
+            Ok(file) => Some(file),
+            Err(e) => exit_with(&format!("Failed to create {}: {}", path, e)),
+        },
+        None => None,
+    };
+    let mut stdout = std::io::stdout().lock();
+    let out: &mut dyn Write = match file.as_mut() {
+        Some(file) => file,
+        None => &mut stdout,
+    };
+    let mut truth = output
+        .path
+        .as_ref()
+        .map(|_| TruthRecorder::new(output.entity_key.clone()));
+    let mut ground_truth = Vec::new();
+
     let mut total_logs = 0u64;
     let mut total_anomaly_logs = 0u64;
     let mut elapsed_ns = 0u64;
+    let pacer = output.speed.map(WallClockPacer::new);
 
     while elapsed_ns < duration_ns {
-        let batch = engine.tick(tick_ns);
+        let mut batch = engine.tick(tick_ns);
         elapsed_ns += tick_ns;
+        if elapsed_ns >= duration_ns
+            && let Some(scope_log) = batch
+                .logs
+                .resourceLogs
+                .first_mut()
+                .and_then(|r| r.scopeLogs.first_mut())
+        {
+            // Late batches still held back go out with the last tick
+            scope_log.logRecords.extend(engine.flush_delivery());
+        }
 
         // Output logs
         for resource_log in &batch.logs.resourceLogs {
@@ -175,13 +666,16 @@ fn run_generate(
                     if log.isGroundTruthAnomaly {
                         total_anomaly_logs += 1;
                     }
+                    if let Some(truth) = truth.as_mut() {
+                        truth.record(log);
+                    }
 
-                    match format {
+                    let written = match output.format {
                         OutputFormat::Json => {
-                            println!("{}", serde_json::to_string(log).unwrap());
+                            writeln!(out, "{}", serde_json::to_string(log).unwrap())
                         }
                         OutputFormat::JsonLines => {
-                            println!("{}", serde_json::to_string(log).unwrap());
+                            writeln!(out, "{}", serde_json::to_string(log).unwrap())
                         }
                         OutputFormat::Pretty => {
                             let anomaly_marker = if log.isGroundTruthAnomaly {
@@ -189,18 +683,34 @@ fn run_generate(
                             } else {
                                 ""
                             };
-                            println!(
+                            writeln!(
+                                out,
                                 "[{}] {} - {}{}",
                                 log.severityText,
                                 log.service_name().unwrap_or("unknown"),
                                 log.body.as_str().unwrap_or(""),
                                 anomaly_marker
-                            );
+                            )
                         }
-                    }
+                        #[cfg(feature = "parquet")]
+                        OutputFormat::Parquet | OutputFormat::Arrow => columnar
+                            .as_mut()
+                            .expect("columnar writer is open")
+                            .write(log)
+                            .map_err(std::io::Error::other),
+                    };
+                    written.unwrap_or_else(|e| exit_with(&format!("Failed to write logs: {}", e)));
                 }
             }
         }
+        ground_truth = batch.ground_truth;
+
+        if let Some(pacer) = &pacer {
+            // Paced output goes to live consumers, so don't hold it back
+            out.flush()
+                .unwrap_or_else(|e| exit_with(&format!("Failed to write logs: {}", e)));
+            pacer.wait(elapsed_ns);
+        }
 
         // Progress update every ~5 seconds of simulated time
         if elapsed_ns % (5_000_000_000) < tick_ns {
@@ -212,6 +722,38 @@ fn run_generate(
         }
     }
 
+    out.flush()
+        .unwrap_or_else(|e| exit_with(&format!("Failed to write logs: {}", e)));
+    if let Some(file) = file {
+        file.finish()
+            .unwrap_or_else(|e| exit_with(&format!("Failed to write logs: {}", e)));
+    }
+    #[cfg(feature = "parquet")]
+    if let Some(columnar) = columnar {
+        columnar
+            .finish()
+            .unwrap_or_else(|e| exit_with(&format!("Failed to write logs: {}", e)));
+    }
+    if let (Some(path), Some(truth)) = (&output.path, truth) {
+        let sidecar = truth::sidecar_path(Path::new(path));
+        truth
+            .finish(&scenario, seed, &ground_truth)
+            .write(&sidecar)
+            .unwrap_or_else(|e| exit_with(&e));
+        eprintln!("Wrote {} and {}", path, sidecar.display());
+    }
+    let certificate = engine.clean_audit().map(|audit| {
+        let key = std::env::var(clean::SIGNING_KEY_ENV).ok();
+        audit.certificate(&scenario, seed, key.as_deref().map(str::as_bytes))
+    });
+    if let (Some(path), Some(certificate)) = (&output.path, &certificate) {
+        let certificate_path = clean::certificate_path(Path::new(path));
+        certificate
+            .write(&certificate_path)
+            .unwrap_or_else(|e| exit_with(&e));
+        eprintln!("Wrote {}", certificate_path.display());
+    }
+
     eprintln!("\n╔══════════════════════════════════════════════════════════════╗");
     eprintln!("║                     Generation Complete                       ║");
     eprintln!("╠══════════════════════════════════════════════════════════════╣");
@@ -221,7 +763,253 @@ fn run_generate(
         "║ Anomaly ratio: {:42.2}% ║",
         (total_anomaly_logs as f64 / total_logs.max(1) as f64) * 100.0
     );
+    if let Some(pacer) = &pacer {
+        eprintln!(
+            "║ Behind wall clock: {:40.2}s ║",
+            pacer.lag(elapsed_ns).as_secs_f64()
+        );
+    }
+    if let Some(stats) = engine.delivery_stats() {
+        eprintln!(
+            "║ Delivery: {:50} ║",
+            format!(
+                "{} skewed, {} late batches, {} duplicates",
+                stats.skewed, stats.reordered, stats.duplicated
+            )
+        );
+    }
+    if let Some(stats) = engine.sampling_stats() {
+        eprintln!(
+            "║ Sampling: {:50} ║",
+            format!(
+                "{} of {} normal, {} of {} anomaly logs",
+                stats.normal_kept, stats.normal_seen, stats.anomaly_kept, stats.anomaly_seen
+            )
+        );
+    }
+    if let Some(certificate) = &certificate {
+        let label = match (certificate.certified, &certificate.signature) {
+            (false, _) => "FAILED",
+            (true, Some(_)) => "certified, signed",
+            (true, None) => "certified, unsigned",
+        };
+        eprintln!("║ Clean: {:53} ║", label);
+    }
     eprintln!("╚══════════════════════════════════════════════════════════════╝");
+    if let Some(certificate) = &certificate {
+        eprintln!("Stream SHA-256: {}", certificate.sha256);
+    }
+    if certificate.is_some_and(|c| !c.certified) {
+        exit_with("clean run emitted ground-truth anomaly logs");
+    }
+}
+
+/// Spread comma-separated anomalies evenly over the run, each lasting half its slot
+fn schedule_anomalies(engine: &mut SimulationEngine, anomaly_list: &str, duration_ns: u64) {
+    let anomaly_count = anomaly_list.split(',').count();
+    let anomaly_duration_ns = duration_ns / (anomaly_count as u64 + 1);
+    let mut offset_ns = anomaly_duration_ns / 2; // Start anomalies after initial baseline
+
+    for anomaly_name in anomaly_list.split(',') {
+        let name = anomaly_name.trim();
+        if let Some(id) = engine.schedule_anomaly(name, offset_ns, anomaly_duration_ns / 2) {
+            eprintln!(
+                "Scheduled anomaly '{}' (id: {}) at offset {}ms for {}ms",
+                name,
+                id,
+                offset_ns / 1_000_000,
+                anomaly_duration_ns / 2 / 1_000_000
+            );
+        } else {
+            eprintln!("Warning: Unknown anomaly type '{}'", name);
+        }
+        offset_ns += anomaly_duration_ns;
+    }
+}
+
+/// Columnar writer for `--format parquet|arrow`, which write to a file only
+#[cfg(feature = "parquet")]
+fn open_columnar(output: &GenerateOutput) -> Option<via_sim::ColumnarWriter> {
+    let format = match output.format {
+        OutputFormat::Parquet => via_sim::ColumnarFormat::Parquet,
+        OutputFormat::Arrow => via_sim::ColumnarFormat::Arrow,
+        _ => return None,
+    };
+    let Some(path) = &output.path else {
+        exit_with("--format parquet and arrow need --output <file>");
+    };
+    Some(via_sim::ColumnarWriter::create(Path::new(path), format).unwrap_or_else(|e| exit_with(&e)))
+}
+
+/// Schedule a config's anomalies at their offsets, expanding recurring ones
+/// over the run
+fn schedule_planned(engine: &mut SimulationEngine, planned: &[ScheduledAnomaly], duration_ns: u64) {
+    for anomaly in planned {
+        let start_ns = anomaly.start_time_sec * 1_000_000_000;
+        let length_ns = anomaly.duration_sec * 1_000_000_000;
+        if let Some(recurrence) = anomaly.recurrence().unwrap_or_else(|e| exit_with(&e)) {
+            let ids = engine.schedule_recurring(
+                &anomaly.scenario,
+                &recurrence,
+                start_ns,
+                length_ns,
+                duration_ns,
+            );
+            match ids.first() {
+                Some(first) => eprintln!(
+                    "Scheduled anomaly '{}' {} times (first id: {}) for {}s each",
+                    anomaly.scenario,
+                    ids.len(),
+                    first,
+                    anomaly.duration_sec
+                ),
+                None => eprintln!(
+                    "Warning: recurring anomaly '{}' is unknown or outside the run",
+                    anomaly.scenario
+                ),
+            }
+            continue;
+        }
+        match engine.schedule_anomaly(&anomaly.scenario, start_ns, length_ns) {
+            Some(id) => eprintln!(
+                "Scheduled anomaly '{}' (id: {}) at {}s for {}s",
+                anomaly.scenario, id, anomaly.start_time_sec, anomaly.duration_sec
+            ),
+            None => eprintln!("Warning: Unknown anomaly type '{}'", anomaly.scenario),
+        }
+    }
+}
+
+fn run_export(
+    duration: String,
+    scenario: String,
+    anomalies: Option<String>,
+    planned: &[ScheduledAnomaly],
+    export: &ExportOptions,
+    tick_ms: u64,
+    seed: u64,
+) {
+    let layout = match export.format {
+        ExportFormat::Nab => "nab",
+        ExportFormat::Swat => "swat",
+    };
+    eprintln!("╔══════════════════════════════════════════════════════════════╗");
+    eprintln!("║           VIA-SIM Dataset Export                             ║");
+    eprintln!("╠══════════════════════════════════════════════════════════════╣");
+    eprintln!("║ Duration: {:50} ║", duration);
+    eprintln!("║ Scenario: {:50} ║", scenario);
+    eprintln!("║ Format: {:52} ║", layout);
+    eprintln!(
+        "║ Interval: {:50} ║",
+        format!("{}s ({})", export.interval_secs, export.value)
+    );
+    eprintln!("║ Seed: {:54} ║", seed);
+    eprintln!("╚══════════════════════════════════════════════════════════════╝");
+
+    let duration_ns = parse_duration(&duration) * 1_000_000_000;
+    let tick_ns = tick_ms * 1_000_000;
+
+    let mut engine = SimulationEngine::new_deterministic(seed);
+    engine.start(&scenario);
+    if let Some(anomaly_list) = anomalies {
+        schedule_anomalies(&mut engine, &anomaly_list, duration_ns);
+    }
+    schedule_planned(&mut engine, planned, duration_ns);
+
+    let mut exporter =
+        SeriesExporter::new(engine.current_time(), export.interval_secs, export.value);
+    let mut ground_truth = Vec::new();
+    let mut elapsed_ns = 0u64;
+    while elapsed_ns < duration_ns {
+        let batch = engine.tick(tick_ns);
+        elapsed_ns += tick_ns;
+        for resource_log in &batch.logs.resourceLogs {
+            for scope_log in &resource_log.scopeLogs {
+                for log in &scope_log.logRecords {
+                    exporter.record(log);
+                }
+            }
+        }
+        ground_truth = batch.ground_truth;
+    }
+
+    let dataset = exporter.finish(&scenario, seed, &ground_truth);
+    let path = Path::new(&export.path);
+    let written = match export.format {
+        ExportFormat::Nab => dataset.write_nab(path),
+        ExportFormat::Swat => dataset.write_swat(path),
+    }
+    .unwrap_or_else(|e| exit_with(&e));
+    for file in written {
+        eprintln!("Wrote {}", file.display());
+    }
+    eprintln!(
+        "\n{} series x {} samples, {} anomaly windows",
+        dataset.series.len(),
+        dataset.len(),
+        dataset.truth.windows.len()
+    );
+}
+
+#[cfg(feature = "kafka")]
+fn run_publish(
+    duration: String,
+    scenario: String,
+    anomalies: Option<String>,
+    tick_ms: u64,
+    seed: u64,
+    kafka: &via_sim::KafkaConfig,
+) {
+    eprintln!("╔══════════════════════════════════════════════════════════════╗");
+    eprintln!("║           VIA-SIM Kafka Publish                              ║");
+    eprintln!("╠══════════════════════════════════════════════════════════════╣");
+    eprintln!("║ Duration: {:50} ║", duration);
+    eprintln!("║ Scenario: {:50} ║", scenario);
+    eprintln!("║ Brokers: {:51} ║", kafka.brokers);
+    eprintln!("║ Topic: {:53} ║", kafka.topic);
+    eprintln!("╚══════════════════════════════════════════════════════════════╝");
+
+    let sink = match via_sim::KafkaSink::new(kafka) {
+        Ok(sink) => sink,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
+    let duration_ns = parse_duration(&duration) * 1_000_000_000;
+    let tick_ns = tick_ms * 1_000_000;
+
+    let mut engine = SimulationEngine::new_deterministic(seed);
+    engine.start(&scenario);
+    if let Some(anomaly_list) = anomalies {
+        schedule_anomalies(&mut engine, &anomaly_list, duration_ns);
+    }
+
+    let mut total_logs = 0u64;
+    let mut elapsed_ns = 0u64;
+    while elapsed_ns < duration_ns {
+        let batch = engine.tick(tick_ns);
+        elapsed_ns += tick_ns;
+        match sink.publish(&batch) {
+            Ok(sent) => total_logs += sent as u64,
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+
+        if elapsed_ns % (5_000_000_000) < tick_ns {
+            let progress = (elapsed_ns as f64 / duration_ns as f64) * 100.0;
+            eprintln!("Progress: {:.1}% | Published: {}", progress, total_logs);
+        }
+    }
+
+    if let Err(e) = sink.flush(std::time::Duration::from_secs(30)) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+    eprintln!("\nPublished {} logs to {}", total_logs, kafka.topic);
 }
 
 fn run_list() {
@@ -237,23 +1025,36 @@ fn run_list() {
     println!("\nUsage: via-sim generate --scenario <SCENARIO> --anomalies <ANOMALY1,ANOMALY2>");
 }
 
-fn run_interactive(host: String, port: u16) {
-    use via_sim::{ApiConfig, create_shared_state, print_api_docs};
+fn run_interactive(host: String, port: u16, tick_ms: u64, max_events_per_sec: u64) {
+    use via_sim::{ApiConfig, PacingConfig};
 
     let config = ApiConfig {
-        host: host.clone(),
+        host,
         port,
         cors_enabled: true,
-        tick_interval_ms: 100,
+        tick_interval_ms: tick_ms,
+        pacing: PacingConfig {
+            max_events_per_sec,
+            burst: max_events_per_sec.saturating_mul(2),
+            max_tick_ms: 60_000,
+        },
     };
 
-    print_api_docs(&config);
-
-    let _state = create_shared_state(config);
+    #[cfg(not(feature = "server"))]
+    {
+        eprintln!("Interactive mode needs the HTTP server; rebuild with `--features server`.");
+        let _ = config;
+        std::process::exit(1);
+    }
 
-    eprintln!("Interactive HTTP server not yet implemented.");
-    eprintln!("For now, use the library API directly or integrate with a web framework.");
-    eprintln!("\nExample integration with axum/actix/tiny_http would go here.");
+    #[cfg(feature = "server")]
+    {
+        via_sim::print_api_docs(&config);
+        if let Err(e) = via_sim::server::serve(via_sim::create_shared_state(config)) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
 }
 
 fn run_benchmark(duration: String, target_eps: u64) {
@@ -307,6 +1108,18 @@ fn run_benchmark(duration: String, target_eps: u64) {
     eprintln!("╚══════════════════════════════════════════════════════════════╝");
 }
 
+/// `--speed`: a positive multiple of real time, with or without a trailing `x`
+fn parse_speed(s: &str) -> Result<f64, String> {
+    let factor = s.trim().trim_end_matches(['x', 'X']);
+    match factor.parse::<f64>() {
+        Ok(speed) if speed > 0.0 && speed.is_finite() => Ok(speed),
+        _ => Err(format!(
+            "expected a positive speed such as 10x or 0.5x, got '{}'",
+            s
+        )),
+    }
+}
+
 fn parse_duration(s: &str) -> u64 {
     let s = s.trim();
     if s.ends_with("m") {
@@ -319,3 +1132,35 @@ fn parse_duration(s: &str) -> u64 {
         s.parse::<u64>().unwrap_or(60)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_parse_speed() {
+        assert_eq!(parse_speed("10x"), Ok(10.0));
+        assert_eq!(parse_speed(" 0.5X "), Ok(0.5));
+        assert_eq!(parse_speed("3"), Ok(3.0));
+        for bad in ["0x", "-2", "inf", "fast", ""] {
+            assert!(parse_speed(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_wall_clock_pacer_due_and_lag() {
+        let pacer = WallClockPacer::new(4.0);
+        // 2s of simulated time at 4x is due half a second in
+        assert_eq!(pacer.due(2_000_000_000), Duration::from_millis(500));
+        assert_eq!(pacer.lag(60_000_000_000), Duration::ZERO);
+
+        // Two seconds in, generation that only reached 4s is 1s behind
+        let behind = WallClockPacer {
+            start: Instant::now() - Duration::from_secs(2),
+            speed: 4.0,
+        };
+        let lag = behind.lag(4_000_000_000);
+        assert!(lag >= Duration::from_secs(1) && lag < Duration::from_secs(2));
+    }
+}
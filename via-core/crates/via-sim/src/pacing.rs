@@ -0,0 +1,302 @@
+//! Tick Pacing for the Live API
+//!
+//! Every `tick` advances simulated time by whatever delta its caller asks
+//! for, so several clients hammering `POST /tick`, or a ticker configured
+//! with a huge interval, could make the engine generate arbitrarily large
+//! batches. `Pacer` bounds that in two ways:
+//!
+//! - a tick's delta is clamped to `max_tick_ms` before the engine runs, so
+//!   no single batch covers more simulated time than that;
+//! - a wall-clock token bucket admits at most `max_events_per_sec` logs on
+//!   average (`burst` at once). Logs beyond it are thinned out evenly
+//!   across the batch, so every service keeps its share, and counted in
+//!   `BatchMetadata::dropped_events`; the batch's ground truth only counts
+//!   the anomaly logs that were kept.
+//!
+//! Both limits are off by default; callers opt in through `PacingConfig`.
+
+use crate::core::SimulationBatch;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Limits on live ticks; `0` turns a limit off (the default)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct PacingConfig {
+    /// Logs admitted per wall-clock second, on average
+    pub max_events_per_sec: u64,
+    /// Logs admitted at once after an idle period
+    pub burst: u64,
+    /// Largest simulated time one tick may cover (ms)
+    pub max_tick_ms: u64,
+}
+
+/// Wall-clock token bucket
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Start full, refilling `rate` tokens per second up to `capacity`
+    pub fn new(rate: f64, capacity: f64) -> Self {
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Take up to `n` tokens as of `now`, returning how many were granted
+    pub fn take_at(&mut self, n: u64, now: Instant) -> u64 {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.capacity);
+        self.last_refill = self.last_refill.max(now);
+
+        let granted = (n as f64).min(self.tokens.floor());
+        self.tokens -= granted;
+        granted as u64
+    }
+
+    pub fn take(&mut self, n: u64) -> u64 {
+        self.take_at(n, Instant::now())
+    }
+
+    /// Tokens left, as of the last `take`
+    pub fn available(&self) -> u64 {
+        self.tokens as u64
+    }
+}
+
+/// Applies a `PacingConfig` to successive ticks
+#[derive(Debug, Clone)]
+pub struct Pacer {
+    config: PacingConfig,
+    bucket: Option<TokenBucket>,
+    /// Logs dropped since the pacer was created
+    dropped_total: u64,
+    /// Anomaly logs dropped since the pacer was created, per anomaly id
+    dropped_anomaly_logs: HashMap<String, u64>,
+}
+
+impl Pacer {
+    pub fn new(config: PacingConfig) -> Self {
+        let bucket = (config.max_events_per_sec > 0).then(|| {
+            TokenBucket::new(
+                config.max_events_per_sec as f64,
+                config.burst.max(config.max_events_per_sec) as f64,
+            )
+        });
+        Self {
+            config,
+            bucket,
+            dropped_total: 0,
+            dropped_anomaly_logs: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &PacingConfig {
+        &self.config
+    }
+
+    /// `delta_ns` clamped to `max_tick_ms`
+    pub fn clamp_delta(&self, delta_ns: u64) -> u64 {
+        match self.config.max_tick_ms {
+            0 => delta_ns,
+            max_ms => delta_ns.min(max_ms.saturating_mul(1_000_000)),
+        }
+    }
+
+    /// Drop the logs of `batch` the bucket has no tokens for, recording
+    /// them in `dropped_events` and taking them out of the ground truth's
+    /// `log_count`s; returns how many were dropped
+    pub fn admit(&mut self, batch: &mut SimulationBatch) -> u64 {
+        self.admit_at(batch, Instant::now())
+    }
+
+    pub fn admit_at(&mut self, batch: &mut SimulationBatch, now: Instant) -> u64 {
+        let Some(bucket) = self.bucket.as_mut() else {
+            return 0;
+        };
+        let total = batch.record_count();
+        let allowed = bucket.take_at(total as u64, now) as usize;
+        let dropped = (total - allowed) as u64;
+
+        if dropped > 0 {
+            // Keep every (total / allowed)-th log, so each scope and each
+            // service is thinned in proportion rather than the tail of the
+            // batch going missing
+            let (mut seen, mut kept) = (0, 0);
+            for scope in batch
+                .logs
+                .resourceLogs
+                .iter_mut()
+                .flat_map(|r| &mut r.scopeLogs)
+            {
+                scope.logRecords.retain(|log| {
+                    seen += 1;
+                    let keep = seen * allowed / total > kept;
+                    if keep {
+                        kept += 1;
+                    } else if let Some(id) = &log.anomalyId {
+                        *self.dropped_anomaly_logs.entry(id.clone()).or_insert(0) += 1;
+                    }
+                    keep
+                });
+            }
+            batch.recount();
+            self.dropped_total += dropped;
+        }
+        batch.metadata.dropped_events = dropped;
+
+        // Ground truth counts every log the engine generated for an
+        // anomaly, including ones dropped in earlier ticks
+        for gt in &mut batch.ground_truth {
+            if let Some(n) = self.dropped_anomaly_logs.get(&gt.anomaly_id) {
+                gt.log_count = gt.log_count.saturating_sub(*n);
+            }
+        }
+        dropped
+    }
+
+    /// Logs dropped since the pacer was created
+    pub fn dropped_total(&self) -> u64 {
+        self.dropped_total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{GroundTruth, KeyValue, LogRecord, OTelLog, Resource, ResourceLog, ScopeLog};
+    use std::time::Duration;
+
+    fn batch(logs: usize) -> SimulationBatch {
+        let records = (0..logs)
+            .map(|i| {
+                let mut log = LogRecord::default();
+                if i % 2 == 1 {
+                    log.mark_anomalous("a".into());
+                }
+                log
+            })
+            .collect();
+        let mut gt = GroundTruth::new("a", "test");
+        gt.log_count = (logs / 2) as u64;
+        SimulationBatch {
+            logs: OTelLog {
+                resourceLogs: vec![ResourceLog {
+                    resource: Resource { attributes: vec![] },
+                    scopeLogs: vec![ScopeLog {
+                        logRecords: records,
+                    }],
+                }],
+            },
+            ground_truth: vec![gt],
+            ..Default::default()
+        }
+    }
+
+    /// One scope per service with `logs` logs each; `checkout`'s logs all
+    /// belong to anomaly "b", whose ground truth reports `anomaly_logs`
+    fn service_batch(logs: usize, anomaly_logs: u64) -> SimulationBatch {
+        let scope = |service: &str| {
+            let records = (0..logs)
+                .map(|_| {
+                    let mut log = LogRecord::default();
+                    log.attributes
+                        .push(KeyValue::string("service.name", service));
+                    if service == "checkout" {
+                        log.mark_anomalous("b".into());
+                    }
+                    log
+                })
+                .collect();
+            ScopeLog {
+                logRecords: records,
+            }
+        };
+        let mut gt = GroundTruth::new("b", "test");
+        gt.log_count = anomaly_logs;
+        SimulationBatch {
+            logs: OTelLog {
+                resourceLogs: vec![ResourceLog {
+                    resource: Resource { attributes: vec![] },
+                    scopeLogs: vec![scope("frontend"), scope("checkout")],
+                }],
+            },
+            ground_truth: vec![gt],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_token_bucket_refills_at_rate() {
+        let mut bucket = TokenBucket::new(10.0, 20.0);
+        let start = Instant::now();
+        assert_eq!(bucket.take_at(25, start), 20);
+        assert_eq!(bucket.take_at(5, start), 0);
+        assert_eq!(bucket.take_at(5, start + Duration::from_millis(300)), 3);
+        // Refill stops at capacity
+        assert_eq!(bucket.take_at(100, start + Duration::from_secs(60)), 20);
+    }
+
+    #[test]
+    fn test_pacer_drops_and_counts() {
+        let start = Instant::now();
+        let mut pacer = Pacer::new(PacingConfig {
+            max_events_per_sec: 10,
+            burst: 10,
+            max_tick_ms: 1_000,
+        });
+        assert_eq!(pacer.clamp_delta(5_000_000_000), 1_000_000_000);
+
+        let mut first = batch(8);
+        assert_eq!(pacer.admit_at(&mut first, start), 0);
+        assert_eq!(first.metadata.dropped_events, 0);
+
+        let mut second = batch(8);
+        assert_eq!(pacer.admit_at(&mut second, start), 6);
+        assert_eq!(second.metadata.dropped_events, 6);
+        assert_eq!(second.logs.resourceLogs[0].scopeLogs[0].logRecords.len(), 2);
+        assert_eq!(second.metadata.anomaly_log_count, 2);
+        assert_eq!(second.metadata.anomaly_counts["a"], 2);
+        assert_eq!(second.ground_truth[0].log_count, 2);
+        assert_eq!(pacer.dropped_total(), 6);
+
+        let mut unlimited = Pacer::new(PacingConfig::default());
+        let mut big = batch(1_000);
+        assert_eq!(unlimited.admit(&mut big), 0);
+        assert_eq!(unlimited.clamp_delta(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_pacer_thins_every_service() {
+        let mut pacer = Pacer::new(PacingConfig {
+            max_events_per_sec: 6,
+            burst: 6,
+            max_tick_ms: 0,
+        });
+        let start = Instant::now();
+
+        let mut first = service_batch(6, 6);
+        assert_eq!(pacer.admit_at(&mut first, start), 6);
+        assert_eq!(first.metadata.service_counts["frontend"], 3);
+        assert_eq!(first.metadata.service_counts["checkout"], 3);
+        assert_eq!(first.metadata.anomaly_counts["b"], 3);
+        assert_eq!(first.ground_truth[0].log_count, 3);
+
+        // The engine's ground truth is cumulative; earlier drops stay out
+        let mut second = service_batch(6, 12);
+        let later = start + Duration::from_secs(1);
+        assert_eq!(pacer.admit_at(&mut second, later), 6);
+        assert_eq!(second.metadata.service_counts["frontend"], 3);
+        assert_eq!(second.metadata.service_counts["checkout"], 3);
+        assert_eq!(second.ground_truth[0].log_count, 6);
+    }
+}
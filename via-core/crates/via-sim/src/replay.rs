@@ -0,0 +1,467 @@
+//! Replay of Recorded OTel Logs
+//!
+//! `ReplaySource` reads logs captured from a real system and serves them as
+//! `SimulationBatch`es, so the same benchmarking pipeline that consumes
+//! synthetic traffic can run on production captures.
+//!
+//! Accepted input (one file may mix them):
+//! - OTLP JSON documents (`{"resourceLogs": [...]}`), pretty-printed or one per
+//!   line as written by the collector's file exporter
+//! - JSON lines with one `LogRecord` per line
+//!
+//! Resource attributes (notably `service.name`) are copied onto each record.
+//! Ground truth comes from `isGroundTruthAnomaly` (files written by via-sim)
+//! or from an optional label attribute on each record.
+
+use crate::core::{
+    BatchMetadata, GroundTruth, LogRecord, OTelLog, Resource, ResourceLog, ScopeLog,
+    SimulationBatch,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Replay options
+#[derive(Debug, Clone, Default)]
+pub struct ReplayConfig {
+    /// Attribute marking a record as anomalous (`true`, `1`, `"true"`, `"anomaly"`)
+    pub label_attribute: Option<String>,
+}
+
+/// Recorded logs served in time-ordered batches
+#[derive(Debug, Clone)]
+pub struct ReplaySource {
+    records: Vec<(u64, LogRecord)>,
+    ground_truth: Vec<GroundTruth>,
+    next: usize,
+    cursor_ns: u64,
+    served: u64,
+}
+
+impl ReplaySource {
+    /// Load a capture file
+    pub fn load(path: &str, config: &ReplayConfig) -> Result<Self, String> {
+        let content = crate::compression::read_to_string(path)
+            .map_err(|e| format!("Failed to read replay file {}: {}", path, e))?;
+        Self::parse(&content, config).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// Parse capture contents (concatenated JSON values)
+    pub fn parse(content: &str, config: &ReplayConfig) -> Result<Self, String> {
+        Ok(Self::from_records(parse_logs(content, config)?))
+    }
+
+    /// Serve already parsed records, e.g. from a dataset loader; windows come
+    /// from `anomalyId` where set, else from contiguous labelled runs
+    pub fn from_records(records: Vec<LogRecord>) -> Self {
+        let mut records: Vec<(u64, LogRecord)> = records
+            .into_iter()
+            .map(|record| (record.timeUnixNano.parse().unwrap_or(0), record))
+            .collect();
+        records.sort_by_key(|(ts, _)| *ts);
+
+        let mut tracker = GroundTruthTracker::default();
+        for (_, record) in records.iter_mut() {
+            tracker.observe(record);
+        }
+        let cursor_ns = records.first().map(|(ts, _)| *ts).unwrap_or(0);
+        Self {
+            records,
+            ground_truth: tracker.windows,
+            next: 0,
+            cursor_ns,
+            served: 0,
+        }
+    }
+
+    /// Number of records in the capture
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Anomaly windows derived from the labels
+    pub fn ground_truth(&self) -> &[GroundTruth] {
+        &self.ground_truth
+    }
+
+    /// Time span covered by the capture (ns)
+    pub fn span_ns(&self) -> u64 {
+        match (self.records.first(), self.records.last()) {
+            (Some((first, _)), Some((last, _))) => last - first,
+            _ => 0,
+        }
+    }
+
+    /// Next `delta_ns` window of records, `None` once the capture is exhausted.
+    ///
+    /// Stretches of the capture without records are skipped rather than
+    /// returned as empty batches.
+    pub fn next_batch(&mut self, delta_ns: u64) -> Option<SimulationBatch> {
+        let delta_ns = delta_ns.max(1);
+        let (next_ts, _) = self.records.get(self.next)?;
+        if *next_ts >= self.cursor_ns + delta_ns {
+            self.cursor_ns += (next_ts - self.cursor_ns) / delta_ns * delta_ns;
+        }
+        let end_ns = self.cursor_ns + delta_ns;
+
+        let start = self.next;
+        while self.next < self.records.len() && self.records[self.next].0 < end_ns {
+            self.next += 1;
+        }
+        let logs: Vec<LogRecord> = self.records[start..self.next]
+            .iter()
+            .map(|(_, r)| r.clone())
+            .collect();
+        self.cursor_ns = end_ns;
+        self.served += logs.len() as u64;
+
+        let first_ts = self.records.first().map(|(ts, _)| *ts).unwrap_or(0);
+        Some(single_batch(
+            logs,
+            self.ground_truth.clone(),
+            end_ns,
+            end_ns - first_ts,
+            self.served,
+            "replay",
+        ))
+    }
+}
+
+/// Parse concatenated JSON values (OTLP documents, records, or arrays of
+/// either) into records, applying the label attribute
+pub fn parse_logs(content: &str, config: &ReplayConfig) -> Result<Vec<LogRecord>, String> {
+    let mut records = Vec::new();
+    let stream = serde_json::Deserializer::from_str(content).into_iter::<Value>();
+    for (i, value) in stream.enumerate() {
+        let value = value.map_err(|e| format!("invalid JSON value #{}: {}", i + 1, e))?;
+        collect_records(value, &[], &mut records)?;
+    }
+    if let Some(key) = &config.label_attribute {
+        for record in records.iter_mut() {
+            if record.get_attribute(key).is_some_and(is_truthy) {
+                record.isGroundTruthAnomaly = true;
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// Wrap records from an external source into a `SimulationBatch`
+pub(crate) fn single_batch(
+    logs: Vec<LogRecord>,
+    ground_truth: Vec<GroundTruth>,
+    timestamp_ns: u64,
+    elapsed_ns: u64,
+    log_count: u64,
+    source: &str,
+) -> SimulationBatch {
+    let scenario_counts = [(source.to_string(), logs.len() as u64)].into();
+    let mut batch = SimulationBatch {
+        logs: OTelLog {
+            resourceLogs: vec![ResourceLog {
+                resource: Resource { attributes: vec![] },
+                scopeLogs: vec![ScopeLog { logRecords: logs }],
+            }],
+        },
+        ground_truth,
+        metadata: BatchMetadata {
+            timestamp_ns,
+            elapsed_ns,
+            log_count,
+            active_scenarios: vec![source.to_string()],
+            scenario_counts,
+            ..Default::default()
+        },
+    };
+    batch.recount();
+    batch
+}
+
+/// Flatten an OTLP document, a bare record, or an array of either
+fn collect_records(
+    value: Value,
+    resource_attributes: &[Value],
+    out: &mut Vec<LogRecord>,
+) -> Result<(), String> {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                collect_records(item, resource_attributes, out)?;
+            }
+        }
+        Value::Object(mut obj) => {
+            if let Some(Value::Array(resource_logs)) = obj.remove("resourceLogs") {
+                for mut resource_log in resource_logs {
+                    let attributes = match resource_log.pointer_mut("/resource/attributes") {
+                        Some(Value::Array(attrs)) => std::mem::take(attrs),
+                        _ => Vec::new(),
+                    };
+                    let scope_logs = match resource_log.get_mut("scopeLogs") {
+                        Some(Value::Array(scopes)) => std::mem::take(scopes),
+                        _ => Vec::new(),
+                    };
+                    for mut scope_log in scope_logs {
+                        if let Some(Value::Array(records)) = scope_log.get_mut("logRecords") {
+                            for record in std::mem::take(records) {
+                                collect_records(record, &attributes, out)?;
+                            }
+                        }
+                    }
+                }
+            } else {
+                out.push(parse_record(Value::Object(obj), resource_attributes)?);
+            }
+        }
+        other => return Err(format!("expected a JSON object, found {}", other)),
+    }
+    Ok(())
+}
+
+/// Coerce an OTLP JSON log record into `LogRecord`
+fn parse_record(mut record: Value, resource_attributes: &[Value]) -> Result<LogRecord, String> {
+    // int64 timestamps may be encoded as numbers; fall back to the observed time
+    let time = match record.get("timeUnixNano") {
+        Some(Value::String(s)) if s != "0" => Some(s.clone()),
+        Some(Value::Number(n)) if n.as_u64() != Some(0) => Some(n.to_string()),
+        _ => match record.get("observedTimeUnixNano") {
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        },
+    };
+    if let Some(time) = time {
+        record["timeUnixNano"] = Value::String(time);
+    }
+
+    if let Some(body) = record.get_mut("body") {
+        normalize_any_value(body);
+    }
+    if let Some(Value::Array(attributes)) = record.get_mut("attributes") {
+        for kv in attributes.iter_mut() {
+            if let Some(value) = kv.get_mut("value") {
+                normalize_any_value(value);
+            }
+        }
+        let present: Vec<String> = attributes
+            .iter()
+            .filter_map(|kv| kv.get("key").and_then(Value::as_str).map(String::from))
+            .collect();
+        for kv in resource_attributes {
+            let key = kv.get("key").and_then(Value::as_str);
+            if key.is_some_and(|k| !present.iter().any(|p| p == k)) {
+                let mut kv = kv.clone();
+                if let Some(value) = kv.get_mut("value") {
+                    normalize_any_value(value);
+                }
+                attributes.push(kv);
+            }
+        }
+    } else if !resource_attributes.is_empty() {
+        let mut attributes = resource_attributes.to_vec();
+        for kv in attributes.iter_mut() {
+            if let Some(value) = kv.get_mut("value") {
+                normalize_any_value(value);
+            }
+        }
+        record["attributes"] = Value::Array(attributes);
+    }
+
+    serde_json::from_value(record).map_err(|e| format!("invalid log record: {}", e))
+}
+
+/// Map OTLP `AnyValue` encodings onto the four variants `AnyValue` supports.
+///
+/// Protobuf JSON writes int64 as strings; nested values (`kvlistValue`,
+/// `arrayValue`, `bytesValue`) are kept as their JSON text.
+fn normalize_any_value(value: &mut Value) {
+    let Value::Object(obj) = value else {
+        *value = serde_json::json!({ "stringValue": value.to_string() });
+        return;
+    };
+    if let Some(Value::String(s)) = obj.get("intValue")
+        && let Ok(i) = s.parse::<i64>()
+    {
+        *value = serde_json::json!({ "intValue": i });
+        return;
+    }
+    if let Some(Value::String(s)) = obj.get("doubleValue")
+        && let Some(d) = s.parse::<f64>().ok().filter(|d| d.is_finite())
+    {
+        *value = serde_json::json!({ "doubleValue": d });
+        return;
+    }
+    let supported = ["stringValue", "boolValue", "intValue", "doubleValue"]
+        .iter()
+        .any(|k| match obj.get(*k) {
+            Some(Value::String(_)) => *k == "stringValue",
+            Some(Value::Bool(_)) => *k == "boolValue",
+            Some(Value::Number(_)) => *k == "intValue" || *k == "doubleValue",
+            _ => false,
+        });
+    if !supported {
+        let text = match obj.values().next() {
+            Some(Value::String(s)) => s.clone(),
+            Some(inner) => inner.to_string(),
+            None => String::new(),
+        };
+        *value = serde_json::json!({ "stringValue": text });
+    }
+}
+
+fn is_truthy(value: &crate::core::AnyValue) -> bool {
+    if let Some(s) = value.as_str() {
+        return matches!(
+            s.to_ascii_lowercase().as_str(),
+            "true" | "1" | "yes" | "anomaly"
+        );
+    }
+    if let Some(i) = value.as_i64() {
+        return i != 0;
+    }
+    matches!(value, crate::core::AnyValue::Bool { boolValue: true })
+}
+
+/// Builds ground-truth windows from labelled records as they are seen.
+///
+/// Anomalous records without an `anomalyId` get one per contiguous run
+/// (`replay-N`); each id's window spans its earliest to latest record.
+#[derive(Debug, Default)]
+pub(crate) struct GroundTruthTracker {
+    pub(crate) windows: Vec<GroundTruth>,
+    index: HashMap<String, usize>,
+    runs: usize,
+    in_run: bool,
+}
+
+impl GroundTruthTracker {
+    pub(crate) fn observe(&mut self, record: &mut LogRecord) {
+        if !record.isGroundTruthAnomaly {
+            self.in_run = false;
+            return;
+        }
+        if !self.in_run && record.anomalyId.is_none() {
+            self.runs += 1;
+        }
+        self.in_run = true;
+        let id = record
+            .anomalyId
+            .get_or_insert_with(|| format!("replay-{}", self.runs))
+            .clone();
+
+        let ts: u64 = record.timeUnixNano.parse().unwrap_or(0);
+        let windows = &mut self.windows;
+        let i = *self.index.entry(id.clone()).or_insert_with(|| {
+            let mut gt = GroundTruth::new(id, "replay");
+            gt.start_time_ns = ts;
+            gt.end_time_ns = ts;
+            windows.push(gt);
+            windows.len() - 1
+        });
+        let gt = &mut windows[i];
+        gt.start_time_ns = gt.start_time_ns.min(ts);
+        gt.end_time_ns = gt.end_time_ns.max(ts);
+        gt.log_count += 1;
+        if let Some(service) = record.service_name()
+            && !gt.target_services.iter().any(|s| s == service)
+        {
+            gt.target_services.push(service.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OTLP: &str = r#"{
+      "resourceLogs": [{
+        "resource": {"attributes": [{"key": "service.name", "value": {"stringValue": "checkout"}}]},
+        "scopeLogs": [{
+          "scope": {"name": "app"},
+          "logRecords": [
+            {"timeUnixNano": "0", "observedTimeUnixNano": "2000000000",
+             "severityNumber": 17, "severityText": "ERROR",
+             "body": {"kvlistValue": {"values": []}},
+             "attributes": [{"key": "http.status_code", "value": {"intValue": "503"}}]},
+            {"timeUnixNano": "1000000000",
+             "body": {"stringValue": "ok"},
+             "attributes": [{"key": "latency_ms", "value": {"doubleValue": 12.5}}]}
+          ]
+        }]
+      }]
+    }"#;
+
+    #[test]
+    fn test_otlp_document_is_normalized() {
+        let source = ReplaySource::parse(OTLP, &ReplayConfig::default()).unwrap();
+        assert_eq!(source.len(), 2);
+
+        // Sorted by time; the zero timestamp fell back to observed time
+        let (first_ts, first) = &source.records[0];
+        assert_eq!(*first_ts, 1_000_000_000);
+        assert_eq!(first.service_name(), Some("checkout"));
+        assert_eq!(first.metric_value(), 12.5);
+        assert_eq!(first.traceId, "");
+
+        let (second_ts, second) = &source.records[1];
+        assert_eq!(*second_ts, 2_000_000_000);
+        assert_eq!(second.metric_value(), 503.0);
+        assert_eq!(second.severityText, "ERROR");
+        assert!(source.ground_truth().is_empty());
+    }
+
+    #[test]
+    fn test_json_lines_with_label_attribute_build_windows() {
+        let line = |ts: u64, svc: &str, label: &str| {
+            format!(
+                r#"{{"timeUnixNano":"{}","attributes":[{{"key":"service.name","value":{{"stringValue":"{}"}}}},{{"key":"label","value":{}}}]}}"#,
+                ts, svc, label
+            )
+        };
+        let content = [
+            line(1, "api", r#"{"boolValue":false}"#),
+            line(2, "api", r#"{"boolValue":true}"#),
+            line(3, "db", r#"{"stringValue":"anomaly"}"#),
+            line(4, "api", r#"{"intValue":"0"}"#),
+            line(5, "api", r#"{"intValue":1}"#),
+        ]
+        .join("\n");
+
+        let config = ReplayConfig {
+            label_attribute: Some("label".to_string()),
+        };
+        let source = ReplaySource::parse(&content, &config).unwrap();
+        let windows = source.ground_truth();
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].anomaly_id, "replay-1");
+        assert_eq!((windows[0].start_time_ns, windows[0].end_time_ns), (2, 3));
+        assert_eq!(windows[0].log_count, 2);
+        assert_eq!(windows[0].target_services, vec!["api", "db"]);
+        assert_eq!((windows[1].start_time_ns, windows[1].end_time_ns), (5, 5));
+    }
+
+    #[test]
+    fn test_batches_skip_gaps_and_cover_every_record() {
+        let content: String = [0u64, 50, 120, 10_000_000_000, 10_000_000_050]
+            .iter()
+            .map(|ts| format!("{{\"timeUnixNano\":\"{}\"}}\n", ts))
+            .collect();
+        let mut source = ReplaySource::parse(&content, &ReplayConfig::default()).unwrap();
+
+        let mut sizes = Vec::new();
+        while let Some(batch) = source.next_batch(100) {
+            sizes.push(batch.logs.resourceLogs[0].scopeLogs[0].logRecords.len());
+        }
+        assert_eq!(sizes, vec![2, 1, 2]);
+    }
+
+    #[test]
+    fn test_invalid_input_is_reported() {
+        let config = ReplayConfig::default();
+        assert!(ReplaySource::parse("{\"timeUnixNano\": ", &config).is_err());
+        assert!(ReplaySource::parse("42", &config).is_err());
+    }
+}
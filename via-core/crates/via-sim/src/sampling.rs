@@ -0,0 +1,133 @@
+//! Log Sampling
+//!
+//! Long runs at high rates produce far more normal records than anyone
+//! needs to score a detector. `Sampler` keeps a random `normal_rate`
+//! fraction of normal logs and an independent `anomaly_rate` fraction of
+//! ground-truth anomaly logs (all of them by default), so anomalies are
+//! never thinned out along with the background. Batches record how many
+//! logs were generated before sampling in `BatchMetadata`.
+
+use crate::core::LogRecord;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Fractions of logs to keep; the default keeps everything
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct SamplingConfig {
+    /// Fraction of normal logs kept
+    pub normal_rate: f64,
+    /// Fraction of ground-truth anomaly logs kept
+    pub anomaly_rate: f64,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            normal_rate: 1.0,
+            anomaly_rate: 1.0,
+        }
+    }
+}
+
+impl SamplingConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.normal_rate < 1.0 || self.anomaly_rate < 1.0
+    }
+}
+
+/// Sampling counters
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SamplingStats {
+    pub normal_seen: u64,
+    pub normal_kept: u64,
+    pub anomaly_seen: u64,
+    pub anomaly_kept: u64,
+}
+
+/// Applies a `SamplingConfig` to successive batches
+pub struct Sampler {
+    config: SamplingConfig,
+    rng: StdRng,
+    stats: SamplingStats,
+}
+
+impl Sampler {
+    pub fn new(config: SamplingConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: StdRng::seed_from_u64(seed),
+            stats: SamplingStats::default(),
+        }
+    }
+
+    pub fn config(&self) -> &SamplingConfig {
+        &self.config
+    }
+
+    pub fn stats(&self) -> &SamplingStats {
+        &self.stats
+    }
+
+    /// Drop the logs that are not sampled, keeping the order of the rest
+    pub fn sample(&mut self, logs: &mut Vec<LogRecord>) {
+        let normal_rate = self.config.normal_rate.clamp(0.0, 1.0);
+        let anomaly_rate = self.config.anomaly_rate.clamp(0.0, 1.0);
+        let (rng, stats) = (&mut self.rng, &mut self.stats);
+        logs.retain(|log| {
+            if log.isGroundTruthAnomaly {
+                stats.anomaly_seen += 1;
+                let keep = anomaly_rate >= 1.0 || rng.random_bool(anomaly_rate);
+                stats.anomaly_kept += keep as u64;
+                keep
+            } else {
+                stats.normal_seen += 1;
+                let keep = normal_rate >= 1.0 || rng.random_bool(normal_rate);
+                stats.normal_kept += keep as u64;
+                keep
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_anomalies_while_thinning_normal_logs() {
+        let mut logs: Vec<LogRecord> = (0..10_000)
+            .map(|i| {
+                let mut log = LogRecord::default();
+                if i % 100 == 0 {
+                    log.mark_anomalous("a".into());
+                }
+                log
+            })
+            .collect();
+        let mut sampler = Sampler::new(
+            SamplingConfig {
+                normal_rate: 0.1,
+                ..Default::default()
+            },
+            7,
+        );
+        sampler.sample(&mut logs);
+
+        let stats = sampler.stats();
+        assert_eq!((stats.anomaly_seen, stats.anomaly_kept), (100, 100));
+        assert_eq!(stats.normal_seen, 9_900);
+        assert!((800..1_200).contains(&stats.normal_kept));
+        assert_eq!(logs.len() as u64, stats.normal_kept + stats.anomaly_kept);
+
+        let mut none = Sampler::new(
+            SamplingConfig {
+                normal_rate: 0.0,
+                anomaly_rate: 0.0,
+            },
+            7,
+        );
+        none.sample(&mut logs);
+        assert!(logs.is_empty());
+    }
+}
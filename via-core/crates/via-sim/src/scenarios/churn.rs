@@ -0,0 +1,306 @@
+//! Entity Churn Scenarios
+//!
+//! A small core of long-lived services plus a constant stream of short-lived
+//! workers (autoscaled pods, serverless instances, per-session IDs). Each
+//! worker logs under its own `service.name` (`worker-0001234`) for a short
+//! lifetime and is never seen again, so per-service mode creates profiles
+//! faster than they become useful and a bounded registry has to evict. The
+//! anomaly hits the core services only, showing whether churn pushes the
+//! profiles that matter out of the registry.
+//!
+//! - **EntityChurn**: steady core traffic plus worker churn
+//! - **CoreLatencyRegression**: the core services slow down and start failing
+
+use crate::core::{KeyValue, LogRecord, ProcessState};
+use crate::scenarios::traffic::create_log;
+use crate::scenarios::{Scenario, next_trace_and_span_ids, rng_for_tick};
+use rand::prelude::*;
+use rand_distr::{Distribution, Normal};
+
+/// Share of baseline requests that fail
+const CHURN_ERROR_RATE: f64 = 0.005;
+/// Standard deviation of baseline latency, relative to the service's mean
+const LATENCY_NOISE: f64 = 0.1;
+/// Share of regressed core requests that time out
+const REGRESSION_ERROR_RATE: f64 = 0.2;
+
+/// Population shape shared by the baseline and the anomaly targeting it
+#[derive(Debug, Clone)]
+pub struct Population {
+    /// Long-lived services present for the whole run
+    pub core_services: usize,
+    /// Time between two requests of one core service
+    pub core_interval_ns: u64,
+    /// Workers started per simulated minute
+    pub new_per_min: f64,
+    /// How long each worker lives
+    pub lifetime_ns: u64,
+    /// Time between two requests of one worker
+    pub worker_interval_ns: u64,
+}
+
+impl Default for Population {
+    fn default() -> Self {
+        Self::new(20, 50, 1_000.0, 20_000, 1_000)
+    }
+}
+
+impl Population {
+    pub fn new(
+        core_services: usize,
+        core_interval_ms: u64,
+        new_per_min: f64,
+        lifetime_ms: u64,
+        worker_interval_ms: u64,
+    ) -> Self {
+        Self {
+            core_services,
+            core_interval_ns: core_interval_ms.max(1) * 1_000_000,
+            new_per_min,
+            lifetime_ns: lifetime_ms.max(1) * 1_000_000,
+            worker_interval_ns: worker_interval_ms.max(1) * 1_000_000,
+        }
+    }
+
+    pub fn core_id(index: usize) -> String {
+        format!("core-svc-{:02}", index)
+    }
+
+    pub fn worker_id(index: u64) -> String {
+        format!("worker-{:07}", index)
+    }
+
+    /// Workers alive at any moment once churn reaches steady state
+    pub fn live_workers(&self) -> f64 {
+        self.new_per_min * self.lifetime_ns as f64 / 60e9
+    }
+
+    /// Combined core and worker request rate (logs/sec)
+    pub fn requests_per_sec(&self) -> f64 {
+        self.core_services as f64 * 1e9 / self.core_interval_ns as f64
+            + self.live_workers() * 1e9 / self.worker_interval_ns as f64
+    }
+
+    /// Time between two worker starts
+    fn spawn_interval_ns(&self) -> u64 {
+        ((60e9 / self.new_per_min.max(f64::MIN_POSITIVE)) as u64).max(1)
+    }
+
+    /// Workers alive at `elapsed_ns` after churn started; worker `k` starts
+    /// at `k` spawn intervals and lives for `lifetime_ns`
+    fn live_range(&self, elapsed_ns: u64) -> std::ops::RangeInclusive<u64> {
+        let spawn = self.spawn_interval_ns();
+        let first = match elapsed_ns.checked_sub(self.lifetime_ns) {
+            Some(oldest) => oldest / spawn + 1,
+            None => 0,
+        };
+        first..=elapsed_ns / spawn
+    }
+
+    /// Requests entity `index` sends in `[time_ns, time_ns + delta_ns)`,
+    /// phase-shifted so entities spread evenly over the interval
+    fn requests(index: u64, interval: u64, time_ns: u64, delta_ns: u64) -> u64 {
+        let phase = index.wrapping_mul(7_919_000_003) % interval;
+        let start = time_ns.wrapping_add(phase);
+        (start + delta_ns) / interval - start / interval
+    }
+
+    /// Emission rate scales with `factor`; churn (worker starts) does not
+    fn scale_rate(&mut self, factor: f64) {
+        self.core_interval_ns = ((self.core_interval_ns as f64 / factor) as u64).max(1);
+        self.worker_interval_ns = ((self.worker_interval_ns as f64 / factor) as u64).max(1);
+    }
+
+    /// Mean latency of a core service (ms)
+    fn core_latency(index: usize) -> f64 {
+        40.0 + (index % 5) as f64 * 15.0
+    }
+
+    /// Mean latency of a worker (ms)
+    fn worker_latency(index: u64) -> f64 {
+        80.0 + (index % 7) as f64 * 10.0
+    }
+
+    fn request(
+        service: &str,
+        level: &str,
+        latency_ms: f64,
+        time_ns: u64,
+        trace: (&str, &str),
+        mut attrs: Vec<KeyValue>,
+    ) -> LogRecord {
+        let status = if level == "ERROR" { 503 } else { 200 };
+        attrs.extend([
+            KeyValue::double("http.duration_ms", latency_ms),
+            KeyValue::int("http.status_code", status),
+        ]);
+        create_log(
+            level,
+            format!("Request completed: {} in {:.1}ms", status, latency_ms),
+            service,
+            trace.0,
+            trace.1,
+            time_ns,
+            attrs,
+        )
+    }
+}
+
+// ============================================================================
+// Entity Churn (baseline)
+// ============================================================================
+
+/// Steady core traffic plus workers that appear, log briefly and disappear
+pub struct EntityChurn {
+    pub population: Population,
+    started_ns: Option<u64>,
+}
+
+impl EntityChurn {
+    pub fn new(population: Population) -> Self {
+        Self {
+            population,
+            started_ns: None,
+        }
+    }
+}
+
+impl Scenario for EntityChurn {
+    fn name(&self) -> &str {
+        "Entity Churn"
+    }
+
+    fn is_baseline(&self) -> bool {
+        true
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.population.scale_rate(factor);
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("churn/baseline", current_time_ns, delta_ns);
+        let noise = Normal::new(1.0, LATENCY_NOISE).unwrap();
+        let started = *self.started_ns.get_or_insert(current_time_ns);
+        let pop = &self.population;
+        let mut logs = Vec::new();
+
+        let mut emit = |service: &str, mean_ms: f64, attrs: Vec<KeyValue>, rng: &mut StdRng| {
+            let (trace_id, span_id) = next_trace_and_span_ids(rng);
+            let (level, latency) = if rng.random_bool(CHURN_ERROR_RATE) {
+                ("ERROR", mean_ms * 3.0)
+            } else {
+                ("INFO", mean_ms * noise.sample(rng).max(0.1))
+            };
+            logs.push(Population::request(
+                service,
+                level,
+                latency,
+                current_time_ns,
+                (&trace_id, &span_id),
+                attrs,
+            ));
+        };
+
+        for i in 0..pop.core_services {
+            let service = Population::core_id(i);
+            let requests =
+                Population::requests(i as u64, pop.core_interval_ns, current_time_ns, delta_ns);
+            for _ in 0..requests {
+                let attrs = vec![KeyValue::string("entity.kind", "core")];
+                emit(&service, Population::core_latency(i), attrs, &mut rng);
+            }
+        }
+
+        for k in pop.live_range(current_time_ns - started) {
+            let service = Population::worker_id(k);
+            for _ in 0..Population::requests(k, pop.worker_interval_ns, current_time_ns, delta_ns) {
+                let attrs = vec![KeyValue::string("entity.kind", "worker")];
+                emit(&service, Population::worker_latency(k), attrs, &mut rng);
+            }
+        }
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        Some(ProcessState::new(
+            self.name(),
+            self.population.requests_per_sec(),
+            CHURN_ERROR_RATE,
+        ))
+    }
+}
+
+// ============================================================================
+// Core Latency Regression
+// ============================================================================
+
+/// The long-lived core services slow down by `slowdown` and a share of
+/// their requests time out, while worker churn continues around them
+pub struct CoreLatencyRegression {
+    pub population: Population,
+    pub slowdown: f64,
+}
+
+impl CoreLatencyRegression {
+    pub fn new(population: Population, slowdown: f64) -> Self {
+        Self {
+            population,
+            slowdown,
+        }
+    }
+}
+
+impl Scenario for CoreLatencyRegression {
+    fn name(&self) -> &str {
+        "Core Latency Regression"
+    }
+
+    fn targets(&self) -> Vec<String> {
+        (0..self.population.core_services)
+            .map(Population::core_id)
+            .collect()
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.population.scale_rate(factor);
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("churn/core_regression", current_time_ns, delta_ns);
+        let pop = &self.population;
+        let mut logs = Vec::new();
+
+        for i in 0..pop.core_services {
+            let service = Population::core_id(i);
+            let requests =
+                Population::requests(i as u64, pop.core_interval_ns, current_time_ns, delta_ns);
+            for _ in 0..requests {
+                let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+                let slow = Population::core_latency(i) * self.slowdown;
+                let mut attrs = vec![KeyValue::string("entity.kind", "core")];
+                let (level, latency) = if rng.random_bool(REGRESSION_ERROR_RATE) {
+                    attrs.push(KeyValue::string("error.type", "UpstreamTimeout"));
+                    ("ERROR", slow * 2.0)
+                } else {
+                    ("WARN", slow * rng.random_range(0.8..1.2))
+                };
+                logs.push(Population::request(
+                    &service,
+                    level,
+                    latency,
+                    current_time_ns,
+                    (&trace_id, &span_id),
+                    attrs,
+                ));
+            }
+        }
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        let rps =
+            self.population.core_services as f64 * 1e9 / self.population.core_interval_ns as f64;
+        Some(ProcessState::new(self.name(), rps, REGRESSION_ERROR_RATE))
+    }
+}
@@ -5,12 +5,24 @@
 //! - DDoS attacks with multiple sources
 //! - Data exfiltration patterns
 //! - Business logic abuse
+//! - Low-and-slow API scraping
+//! - Periodic DNS beaconing
+//! - Regional outages failing traffic over to other regions
+//! - Services going silent (missing data)
 
-use crate::core::{AnyValue, KeyValue, LogRecord};
-use crate::scenarios::traffic::create_log;
-use crate::scenarios::{Scenario, next_trace_and_span_ids, rng_for_init, rng_for_tick};
+use crate::core::{AnyValue, KeyValue, LatencyModel, LogRecord, ProcessState};
+use crate::scenarios::traffic::{REGIONS, create_log, hop_log};
+use crate::scenarios::{
+    Scenario, next_trace_and_span_ids, per_tick_rate, rng_for_init, rng_for_tick,
+};
+use crate::topology::Topology;
 use rand::prelude::*;
 
+/// Chance per tick of an outbound transfer log
+const EXFIL_TRANSFER_PROBABILITY: f64 = 0.3;
+/// Un-degraded query latency range (ms) before the slowdown multiplier
+const SLOW_QUERY_BASE_MS: (f64, f64) = (50.0, 200.0);
+
 // ============================================================================
 // DDoS Attack Scenario
 // ============================================================================
@@ -52,6 +64,10 @@ impl Scenario for DDoSAttack {
         "DDoS Attack"
     }
 
+    fn scale_rate(&mut self, factor: f64) {
+        self.requests_per_ip *= factor;
+    }
+
     fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
         let mut rng = rng_for_tick("distributed/ddos", current_time_ns, delta_ns);
         let seconds = delta_ns as f64 / 1_000_000_000.0;
@@ -100,35 +116,61 @@ impl Scenario for DDoSAttack {
         }
         logs
     }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        // 429 with p=0.7, then 503 with p=0.3 of the remainder
+        let error_rate = 0.7 + 0.3 * 0.3;
+        Some(ProcessState::new(
+            self.name(),
+            self.requests_per_ip * self.source_ip_count as f64,
+            error_rate,
+        ))
+    }
 }
 
 // ============================================================================
 // Cascade Failure Scenario
 // ============================================================================
 
-/// Cascade failure propagating through service dependencies
+/// Cascade failure propagating from one service to everything that calls it,
+/// directly or through others, one call further per stage
 pub struct CascadeFailure {
     pub initial_service: String,
     pub failure_rate: f64,
-    pub affected_services: Vec<String>,
+    /// The failed service and its dependents with their distance in calls,
+    /// nearest first
+    pub affected_services: Vec<(String, usize)>,
     current_failure_depth: usize,
 }
 
 impl CascadeFailure {
     pub fn new(initial_service: &str, failure_rate: f64) -> Self {
+        Self::with_topology(initial_service, failure_rate, &Topology::default())
+    }
+
+    pub fn with_topology(initial_service: &str, failure_rate: f64, topology: &Topology) -> Self {
+        let mut affected_services = topology.dependents(initial_service);
+        if affected_services.is_empty() {
+            affected_services.push((initial_service.to_string(), 0));
+        }
         Self {
             initial_service: initial_service.to_string(),
             failure_rate,
-            affected_services: vec![
-                "auth-service".to_string(),
-                "payment-service".to_string(),
-                "api-gateway".to_string(),
-                "inventory-service".to_string(),
-                "recommendation-engine".to_string(),
-            ],
+            affected_services,
             current_failure_depth: 0,
         }
     }
+
+    fn max_depth(&self) -> usize {
+        self.affected_services.last().map_or(0, |(_, d)| *d)
+    }
+
+    /// Services failing at the current stage
+    fn failing(&self) -> impl Iterator<Item = &(String, usize)> {
+        self.affected_services
+            .iter()
+            .take_while(|(_, d)| *d <= self.current_failure_depth)
+    }
 }
 
 impl Scenario for CascadeFailure {
@@ -140,21 +182,17 @@ impl Scenario for CascadeFailure {
         let mut rng = rng_for_tick("distributed/cascade_failure", current_time_ns, delta_ns);
         let mut logs = Vec::new();
 
-        // Increment cascade depth over time
+        // Spread one call further over time
         let seconds = delta_ns as f64 / 1_000_000_000.0;
-        if rng.random_bool(0.1 * seconds)
-            && self.current_failure_depth < self.affected_services.len()
+        if rng.random_bool((0.1 * seconds).min(1.0))
+            && self.current_failure_depth < self.max_depth()
         {
             self.current_failure_depth += 1;
         }
 
         // Generate failure logs for affected services
-        for i in 0..=self
-            .current_failure_depth
-            .min(self.affected_services.len() - 1)
-        {
-            let service = &self.affected_services[i];
-
+        for (service, i) in self.failing() {
+            let i = *i;
             if rng.random_bool(self.failure_rate) {
                 let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
 
@@ -197,6 +235,15 @@ impl Scenario for CascadeFailure {
         }
         logs
     }
+
+    fn process_state(&self, delta_ns: u64) -> Option<ProcessState> {
+        let failing = self.failing().count();
+        Some(ProcessState::new(
+            self.name(),
+            per_tick_rate(self.failure_rate, delta_ns) * failing as f64,
+            1.0,
+        ))
+    }
 }
 
 // ============================================================================
@@ -233,7 +280,7 @@ impl Scenario for DataExfiltration {
 
         let mut logs = Vec::new();
 
-        if rng.random_bool(0.3) {
+        if rng.random_bool(EXFIL_TRANSFER_PROBABILITY) {
             let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
 
             // Suspicious external IP
@@ -283,6 +330,14 @@ impl Scenario for DataExfiltration {
         }
         logs
     }
+
+    fn process_state(&self, delta_ns: u64) -> Option<ProcessState> {
+        Some(ProcessState::new(
+            self.name(),
+            per_tick_rate(EXFIL_TRANSFER_PROBABILITY, delta_ns),
+            0.0,
+        ))
+    }
 }
 
 // ============================================================================
@@ -311,6 +366,10 @@ impl Scenario for SlowQueries {
         "Slow Queries"
     }
 
+    fn scale_rate(&mut self, factor: f64) {
+        self.query_rate *= factor;
+    }
+
     fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
         let mut rng = rng_for_tick("distributed/slow_queries", current_time_ns, delta_ns);
         let seconds = delta_ns as f64 / 1_000_000_000.0;
@@ -326,7 +385,7 @@ impl Scenario for SlowQueries {
         for _ in 0..count {
             let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
 
-            let base_latency = rng.random_range(50.0..200.0);
+            let base_latency = rng.random_range(SLOW_QUERY_BASE_MS.0..SLOW_QUERY_BASE_MS.1);
             let slow_latency = base_latency * self.latency_multiplier;
             let query = slow_queries.choose(&mut rng).unwrap();
 
@@ -363,6 +422,19 @@ impl Scenario for SlowQueries {
         }
         logs
     }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        let (min, max) = (
+            SLOW_QUERY_BASE_MS.0 * self.latency_multiplier,
+            SLOW_QUERY_BASE_MS.1 * self.latency_multiplier,
+        );
+        // Queries above 5s are logged at ERROR level
+        let error_rate = ((max - 5000.0) / (max - min)).clamp(0.0, 1.0);
+        Some(
+            ProcessState::new(self.name(), self.query_rate, error_rate)
+                .with_latency(LatencyModel::Uniform { min, max }),
+        )
+    }
 }
 
 // ============================================================================
@@ -391,6 +463,10 @@ impl Scenario for ErrorRateSpike {
         "Error Rate Spike"
     }
 
+    fn scale_rate(&mut self, factor: f64) {
+        self.request_rate *= factor;
+    }
+
     fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
         let mut rng = rng_for_tick("distributed/error_rate_spike", current_time_ns, delta_ns);
         let seconds = delta_ns as f64 / 1_000_000_000.0;
@@ -436,6 +512,15 @@ impl Scenario for ErrorRateSpike {
         }
         logs
     }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        // Only failed requests are logged
+        Some(ProcessState::new(
+            self.name(),
+            self.request_rate * self.error_rate,
+            1.0,
+        ))
+    }
 }
 
 // ============================================================================
@@ -464,6 +549,10 @@ impl Scenario for TrafficSpike {
         "Traffic Spike"
     }
 
+    fn scale_rate(&mut self, factor: f64) {
+        self.base_rps *= factor;
+    }
+
     fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
         let mut rng = rng_for_tick("distributed/traffic_spike", current_time_ns, delta_ns);
         let seconds = delta_ns as f64 / 1_000_000_000.0;
@@ -505,4 +594,523 @@ impl Scenario for TrafficSpike {
         }
         logs
     }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        let load = 1.0 + self.multiplier / 10.0;
+        let (min, max) = (100.0 * load, 500.0 * load);
+        // Requests slower than 2s time out; the rest fail 2% of the time
+        let timeout_rate = ((max - 2000.0) / (max - min)).clamp(0.0, 1.0);
+        let error_rate = timeout_rate + (1.0 - timeout_rate) * 0.02;
+        Some(
+            ProcessState::new(self.name(), self.base_rps * self.multiplier, error_rate)
+                .with_latency(LatencyModel::Uniform { min, max }),
+        )
+    }
+}
+
+// ============================================================================
+// API Scraping Scenario
+// ============================================================================
+
+/// Requests per second one API key may send before the gateway answers 429
+const API_RATE_LIMIT_PER_KEY: f64 = 1.0;
+/// Share of scraper requests that still trip the limiter (clock skew, bursts)
+const SCRAPER_THROTTLED_RATE: f64 = 0.01;
+/// User agents the scraper cycles through
+const SCRAPER_USER_AGENTS: [&str; 4] = [
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 Chrome/124.0",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_4) AppleWebKit/605.1.15 Safari/17.4",
+    "Mozilla/5.0 (X11; Linux x86_64; rv:125.0) Gecko/20100101 Firefox/125.0",
+    "python-requests/2.31.0",
+];
+
+/// Low-and-slow scraping: a few API keys at a time, each behind its own
+/// proxy IP, walk the product catalog just under the per-key rate limit and
+/// hand over to the next keys every `rotation_ns`. Every request looks
+/// normal on its own; only the number of keys and IPs seen over time and
+/// the sequential item ids give it away.
+pub struct ApiScraping {
+    pub target_service: String,
+    /// Keys used at the same time
+    pub active_keys: usize,
+    /// Requests per second per active key, kept under the rate limit
+    pub requests_per_key: f64,
+    /// How long a set of keys is used before the next one takes over
+    pub rotation_ns: u64,
+    /// `(api key, proxy IP)` pairs in rotation order
+    identities: Vec<(String, String)>,
+    elapsed_ns: u64,
+    next_item: u64,
+}
+
+impl ApiScraping {
+    pub fn new(target_service: &str, keys: usize, active_keys: usize, rotation_secs: u64) -> Self {
+        let mut rng = rng_for_init("distributed/api_scraping");
+        let identities = (0..keys.max(1))
+            .map(|_| {
+                let key: u64 = rng.random();
+                let ip = format!(
+                    "{}.{}.{}.{}",
+                    rng.random_range(23..210),
+                    rng.random_range(0..256),
+                    rng.random_range(0..256),
+                    rng.random_range(1..255)
+                );
+                (format!("ak_live_{:012x}", key >> 16), ip)
+            })
+            .collect();
+        Self {
+            target_service: target_service.to_string(),
+            active_keys: active_keys.clamp(1, keys.max(1)),
+            requests_per_key: API_RATE_LIMIT_PER_KEY * 0.9,
+            rotation_ns: rotation_secs.max(1) * 1_000_000_000,
+            identities,
+            elapsed_ns: 0,
+            next_item: 10_000,
+        }
+    }
+
+    /// Identities in use during rotation `n`
+    fn active(&self, n: u64) -> impl Iterator<Item = &(String, String)> {
+        let start = n as usize * self.active_keys;
+        (0..self.active_keys).map(move |i| &self.identities[(start + i) % self.identities.len()])
+    }
+}
+
+impl Scenario for ApiScraping {
+    fn name(&self) -> &str {
+        "API Scraping"
+    }
+
+    fn targets(&self) -> Vec<String> {
+        vec![self.target_service.clone()]
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.requests_per_key *= factor;
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("distributed/api_scraping", current_time_ns, delta_ns);
+        let elapsed = self.elapsed_ns;
+        self.elapsed_ns += delta_ns;
+
+        // Each key fires every `interval`, phase-shifted so keys spread out;
+        // a key's requests stay under the limit at any tick length
+        let interval = (1e9 / self.requests_per_key.max(f64::MIN_POSITIVE)) as u64;
+        let interval = interval.max(1);
+        let identities: Vec<(String, String)> =
+            self.active(elapsed / self.rotation_ns).cloned().collect();
+        let mut logs = Vec::new();
+
+        for (slot, (api_key, ip)) in identities.iter().enumerate() {
+            let start = elapsed + slot as u64 * interval / identities.len() as u64;
+            let requests = (start + delta_ns) / interval - start / interval;
+            for _ in 0..requests {
+                let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+                let item = self.next_item;
+                self.next_item += 1;
+                let latency = rng.random_range(30.0..90.0);
+                let (level, status) = if rng.random_bool(SCRAPER_THROTTLED_RATE) {
+                    ("WARN", 429)
+                } else {
+                    ("INFO", 200)
+                };
+
+                logs.push(create_log(
+                    level,
+                    format!("GET /api/v1/products/{} {}", item, status),
+                    &self.target_service,
+                    &trace_id,
+                    &span_id,
+                    current_time_ns,
+                    vec![
+                        KeyValue::string("http.method", "GET"),
+                        KeyValue::string("http.route", "/api/v1/products/{id}"),
+                        KeyValue::string("url.path", format!("/api/v1/products/{}", item)),
+                        KeyValue::int("http.status_code", status),
+                        KeyValue::double("http.duration_ms", latency),
+                        KeyValue::string("api.key", api_key.clone()),
+                        KeyValue::string("net.peer.ip", ip.clone()),
+                        KeyValue::string(
+                            "user_agent.original",
+                            *SCRAPER_USER_AGENTS.choose(&mut rng).unwrap(),
+                        ),
+                    ],
+                ));
+            }
+        }
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        Some(
+            ProcessState::new(
+                self.name(),
+                self.requests_per_key * self.active_keys as f64,
+                SCRAPER_THROTTLED_RATE,
+            )
+            .with_latency(LatencyModel::Uniform {
+                min: 30.0,
+                max: 90.0,
+            }),
+        )
+    }
+}
+
+// ============================================================================
+// DNS Beaconing Scenario
+// ============================================================================
+
+/// Resolver the implant's lookups go through
+const BEACON_RESOLVER_IP: &str = "10.0.0.53";
+/// Resolution time (ms) of a check-in, answered with a bare A record
+const BEACON_CHECKIN_MS: (f64, f64) = (38.0, 46.0);
+/// Resolution time (ms) of an upload, whose TXT answer carries tasking
+const BEACON_UPLOAD_MS: (f64, f64) = (110.0, 130.0);
+
+/// Implant calling home over DNS. Every `interval_ns`, give or take
+/// `jitter`, it checks in with an A lookup of a rare domain; every
+/// `upload_every`-th check-in is instead a TXT lookup with a few bytes of
+/// stolen data hex-encoded in the subdomain, answered with the next tasking.
+/// Unlike `DataExfiltration` there's nothing large about it: what gives it
+/// away is one host whose lookups repeat on a fixed cycle, in timing and in
+/// value, which is what frequency-domain detectors look for.
+pub struct Beaconing {
+    pub host: String,
+    pub domain: String,
+    pub interval_ns: u64,
+    /// Timing jitter as a share of the interval
+    pub jitter: f64,
+    /// Check-ins per upload
+    pub upload_every: u64,
+    /// Bytes smuggled out per upload
+    pub chunk_bytes: usize,
+    next_beacon_ns: Option<u64>,
+    sequence: u64,
+}
+
+impl Beaconing {
+    pub fn new(host: &str, domain: &str, interval_secs: u64) -> Self {
+        Self {
+            host: host.to_string(),
+            domain: domain.to_string(),
+            interval_ns: interval_secs.max(1) * 1_000_000_000,
+            jitter: 0.02,
+            upload_every: 4,
+            chunk_bytes: 24,
+            next_beacon_ns: None,
+            sequence: 0,
+        }
+    }
+}
+
+impl Scenario for Beaconing {
+    fn name(&self) -> &str {
+        "DNS Beaconing"
+    }
+
+    fn targets(&self) -> Vec<String> {
+        vec![self.host.clone()]
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("distributed/beaconing", current_time_ns, delta_ns);
+        let end = current_time_ns + delta_ns;
+        let mut due = *self.next_beacon_ns.get_or_insert(current_time_ns);
+        let mut logs = Vec::new();
+
+        while due < end {
+            let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+            let upload = self.sequence % self.upload_every.max(1) == self.upload_every.max(1) - 1;
+            let (record_type, name, latency) = if upload {
+                let chunk: String = (0..self.chunk_bytes)
+                    .map(|_| format!("{:02x}", rng.random::<u8>()))
+                    .collect();
+                let name = format!("{}.{}.{}", chunk, self.sequence, self.domain);
+                (
+                    "TXT",
+                    name,
+                    rng.random_range(BEACON_UPLOAD_MS.0..BEACON_UPLOAD_MS.1),
+                )
+            } else {
+                let name = format!("{}.{}", self.sequence, self.domain);
+                (
+                    "A",
+                    name,
+                    rng.random_range(BEACON_CHECKIN_MS.0..BEACON_CHECKIN_MS.1),
+                )
+            };
+            self.sequence += 1;
+
+            logs.push(create_log(
+                "INFO",
+                format!("DNS query {} {} NOERROR", record_type, name),
+                &self.host,
+                &trace_id,
+                &span_id,
+                due,
+                vec![
+                    KeyValue::string("dns.question.name", name.clone()),
+                    KeyValue::string("dns.question.type", record_type),
+                    KeyValue::string("dns.response_code", "NOERROR"),
+                    KeyValue::int("dns.question.size", name.len() as i64),
+                    KeyValue::double("latency_ms", latency),
+                    KeyValue::string("net.peer.ip", BEACON_RESOLVER_IP),
+                    KeyValue::string("threat.category", "dns_tunneling"),
+                ],
+            ));
+
+            let spread = self.interval_ns as f64 * self.jitter;
+            let offset = rng.random_range(-spread..=spread) as i64;
+            due = (due + self.interval_ns).saturating_add_signed(offset);
+        }
+        self.next_beacon_ns = Some(due);
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        let uploads = 1.0 / self.upload_every.max(1) as f64;
+        let mean = |(min, max): (f64, f64)| (min + max) / 2.0;
+        let latency = mean(BEACON_UPLOAD_MS) * uploads + mean(BEACON_CHECKIN_MS) * (1.0 - uploads);
+        Some(
+            ProcessState::new(self.name(), 1e9 / self.interval_ns as f64, 0.0).with_latency(
+                LatencyModel::Normal {
+                    mean: latency,
+                    std_dev: (mean(BEACON_UPLOAD_MS) - latency) * uploads.sqrt(),
+                },
+            ),
+        )
+    }
+}
+
+// ============================================================================
+// Regional Outage Scenario
+// ============================================================================
+
+/// Time in-flight requests of the lost region keep failing
+const OUTAGE_DRAIN_NS: u64 = 5_000_000_000;
+/// Load-balancer health checks of the lost region, per service
+const OUTAGE_HEALTH_CHECK_INTERVAL_NS: u64 = 1_000_000_000;
+
+/// One region goes dark and its traffic fails over to the others. The
+/// region's baseline requests stop (see `Scenario::suppresses`), leaving
+/// only a burst of failed in-flight requests and the load balancer's failed
+/// health checks: a volume drop. The surviving regions take on its share
+/// at once, erroring and slowing down while they scale up over `absorb_ns`:
+/// a simultaneous spike.
+pub struct RegionalOutage {
+    pub region: String,
+    /// Baseline rate across all regions (logs/sec)
+    pub logs_per_sec: f64,
+    /// Error rate of the surviving regions as the traffic arrives
+    pub overload_error_rate: f64,
+    /// Time the surviving regions take to absorb the extra load
+    pub absorb_ns: u64,
+    /// Requests failed over to the surviving regions
+    topology: Topology,
+    /// Logs written past the previous tick's budget by its last request
+    overshoot: usize,
+    elapsed_ns: u64,
+}
+
+impl RegionalOutage {
+    pub fn new(region: &str, logs_per_sec: f64) -> Self {
+        Self {
+            region: region.to_string(),
+            logs_per_sec,
+            overload_error_rate: 0.3,
+            absorb_ns: 60_000_000_000,
+            topology: Topology::default(),
+            overshoot: 0,
+            elapsed_ns: 0,
+        }
+    }
+
+    /// Rate the lost region served before the outage (logs/sec)
+    fn region_rate(&self) -> f64 {
+        self.logs_per_sec / REGIONS.len() as f64
+    }
+
+    /// Overload of the surviving regions, from 1 at the start to 0 once absorbed
+    fn overload(&self) -> f64 {
+        1.0 - (self.elapsed_ns as f64 / self.absorb_ns.max(1) as f64).min(1.0)
+    }
+
+    fn survivors(&self) -> Vec<&'static str> {
+        REGIONS
+            .iter()
+            .copied()
+            .filter(|r| *r != self.region)
+            .collect()
+    }
+}
+
+impl Scenario for RegionalOutage {
+    fn name(&self) -> &str {
+        "Regional Outage"
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.logs_per_sec *= factor;
+    }
+
+    fn suppresses(&self, log: &LogRecord) -> bool {
+        log.get_attribute("cloud.region")
+            .and_then(AnyValue::as_str)
+            .is_some_and(|region| region == self.region)
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("distributed/regional_outage", current_time_ns, delta_ns);
+        let seconds = delta_ns as f64 / 1_000_000_000.0;
+        let elapsed = self.elapsed_ns;
+        let mut logs = Vec::new();
+
+        // In-flight requests of the lost region fail while connections drain
+        if elapsed < OUTAGE_DRAIN_NS {
+            let remaining = 1.0 - elapsed as f64 / OUTAGE_DRAIN_NS as f64;
+            let count = (self.region_rate() * remaining * seconds).round() as u64;
+            for _ in 0..count {
+                let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+                logs.push(create_log(
+                    "ERROR",
+                    format!("Upstream connection reset by {}", self.region),
+                    self.topology.entry(),
+                    &trace_id,
+                    &span_id,
+                    current_time_ns,
+                    vec![
+                        KeyValue::int("http.status_code", 502),
+                        KeyValue::string("error.type", "ConnectionReset"),
+                        KeyValue::string("cloud.region", self.region.clone()),
+                    ],
+                ));
+            }
+        }
+
+        // The load balancer keeps probing the lost region's services
+        let checks = (elapsed + delta_ns) / OUTAGE_HEALTH_CHECK_INTERVAL_NS
+            - elapsed / OUTAGE_HEALTH_CHECK_INTERVAL_NS
+            + u64::from(elapsed == 0);
+        for _ in 0..checks {
+            for service in self.topology.services() {
+                let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+                logs.push(create_log(
+                    "ERROR",
+                    format!(
+                        "Health check failed: {} in {} unreachable",
+                        service.name, self.region
+                    ),
+                    &service.name,
+                    &trace_id,
+                    &span_id,
+                    current_time_ns,
+                    vec![
+                        KeyValue::int("http.status_code", 503),
+                        KeyValue::string("error.type", "RegionUnavailable"),
+                        KeyValue::string("cloud.region", self.region.clone()),
+                    ],
+                ));
+            }
+        }
+
+        // Its share of requests lands on the surviving regions, which error
+        // and slow down until they have scaled up
+        let overload = self.overload();
+        self.topology.restore();
+        if overload > 0.0 {
+            let entry = self.topology.entry().to_string();
+            self.topology.degrade(
+                &entry,
+                self.overload_error_rate * overload,
+                1.0 + 2.0 * overload,
+            );
+        }
+        let survivors = self.survivors();
+        let count = (self.region_rate() * seconds).round() as usize;
+        let budget = count.saturating_sub(self.overshoot);
+        self.overshoot -= count.min(self.overshoot);
+        let start = logs.len();
+        while logs.len() - start < budget && !survivors.is_empty() {
+            let request = self.topology.request(&mut rng);
+            let client_ip = format!(
+                "10.0.{}.{}",
+                rng.random_range(0..255),
+                rng.random_range(0..255)
+            );
+            let region = survivors[rng.random_range(0..survivors.len())];
+            for hop in &request.hops {
+                let mut log = hop_log(&request.trace_id, hop, &client_ip, current_time_ns);
+                for attr in &mut log.attributes {
+                    if attr.key == "cloud.region" {
+                        attr.value = AnyValue::string(region);
+                    }
+                }
+                logs.push(log);
+            }
+        }
+        self.overshoot += (logs.len() - start).saturating_sub(budget);
+
+        self.elapsed_ns += delta_ns;
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        let checks =
+            self.topology.services().len() as f64 * 1e9 / OUTAGE_HEALTH_CHECK_INTERVAL_NS as f64;
+        let rate = self.region_rate() + checks;
+        let errors = checks + self.region_rate() * self.overload_error_rate * self.overload();
+        Some(ProcessState::new(
+            self.name(),
+            rate,
+            errors / rate.max(f64::MIN_POSITIVE),
+        ))
+    }
+}
+
+// ============================================================================
+// Service Silence Scenario
+// ============================================================================
+
+/// A service stops logging altogether: hung process, dead log shipper,
+/// heartbeat gone. Its baseline logs are suppressed and nothing replaces
+/// them, so the anomaly has no logs at all; ground truth marks the window as
+/// an absence to be scored by time.
+pub struct ServiceSilence {
+    pub service_name: String,
+}
+
+impl ServiceSilence {
+    pub fn new(service_name: &str) -> Self {
+        Self {
+            service_name: service_name.to_string(),
+        }
+    }
+}
+
+impl Scenario for ServiceSilence {
+    fn name(&self) -> &str {
+        "Service Silence"
+    }
+
+    fn targets(&self) -> Vec<String> {
+        vec![self.service_name.clone()]
+    }
+
+    fn suppresses(&self, log: &LogRecord) -> bool {
+        log.service_name() == Some(self.service_name.as_str())
+    }
+
+    fn is_absence(&self) -> bool {
+        true
+    }
+
+    fn tick(&mut self, _current_time_ns: u64, _delta_ns: u64) -> Vec<LogRecord> {
+        Vec::new()
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        Some(ProcessState::new(self.name(), 0.0, 0.0))
+    }
 }
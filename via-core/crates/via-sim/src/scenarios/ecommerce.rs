@@ -0,0 +1,367 @@
+//! E-commerce Checkout Funnel Scenarios
+//!
+//! Every order walks the funnel `cart-service` → `fraud-check` →
+//! `payment-service`, one log per step sharing a trace id. Payment logs carry
+//! the tick's business metrics (`checkout.orders_per_minute`,
+//! `payment.failure_ratio`) so detectors see product-level signals next to
+//! latency and status codes.
+//!
+//! - **CheckoutFunnel**: baseline order flow
+//! - **PaymentProviderOutage**: one payment provider times out / 503s
+//! - **FraudBurst**: card-testing bot placing many small orders
+
+use crate::core::{KeyValue, LatencyModel, LogRecord, ProcessState};
+use crate::scenarios::traffic::create_log;
+use crate::scenarios::{Scenario, next_trace_and_span_ids, rng_for_tick};
+use rand::prelude::*;
+use rand_distr::{Distribution, LogNormal, Normal};
+
+/// Payment providers orders are routed to
+const PAYMENT_PROVIDERS: [&str; 3] = ["stripe", "adyen", "paypal"];
+/// Baseline share of payments declined by the issuer
+const CHECKOUT_DECLINE_RATE: f64 = 0.03;
+/// Baseline per-step latency (ms) is LogNormal(mu, sigma), ~33ms median
+const CHECKOUT_LATENCY_MU: f64 = 3.5;
+const CHECKOUT_LATENCY_SIGMA: f64 = 0.4;
+/// Gateway timeout (ms) the payment service gives up after
+const PAYMENT_TIMEOUT_MS: i64 = 30_000;
+/// Share of card-testing attempts the fraud model lets through to payment
+const FRAUD_PASS_THROUGH: f64 = 0.2;
+
+/// Logs emitted per order (cart, fraud check, payment)
+const FUNNEL_STEPS: f64 = 3.0;
+
+/// Business metrics attached to payment logs
+fn business_metrics(orders_per_sec: f64, failure_ratio: f64) -> [KeyValue; 2] {
+    [
+        KeyValue::double("checkout.orders_per_minute", orders_per_sec * 60.0),
+        KeyValue::double("payment.failure_ratio", failure_ratio),
+    ]
+}
+
+// ============================================================================
+// Checkout Funnel (baseline)
+// ============================================================================
+
+/// Healthy checkout traffic
+pub struct CheckoutFunnel {
+    pub orders_per_sec: f64,
+    /// Jittered order rate drawn for the most recent tick
+    current_ops: f64,
+}
+
+impl CheckoutFunnel {
+    pub fn new(orders_per_sec: f64) -> Self {
+        Self {
+            orders_per_sec,
+            current_ops: orders_per_sec,
+        }
+    }
+}
+
+impl Scenario for CheckoutFunnel {
+    fn name(&self) -> &str {
+        "Checkout Funnel"
+    }
+
+    fn is_baseline(&self) -> bool {
+        true
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.orders_per_sec *= factor;
+        self.current_ops *= factor;
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("ecommerce/checkout_funnel", current_time_ns, delta_ns);
+        let seconds = delta_ns as f64 / 1_000_000_000.0;
+
+        let vol_dist = Normal::new(self.orders_per_sec, self.orders_per_sec * 0.1).unwrap();
+        self.current_ops = vol_dist.sample(&mut rng).max(0.0);
+        let orders = (self.current_ops * seconds).round() as usize;
+
+        let declined: Vec<bool> = (0..orders)
+            .map(|_| rng.random_bool(CHECKOUT_DECLINE_RATE))
+            .collect();
+        let failure_ratio = if orders > 0 {
+            declined.iter().filter(|d| **d).count() as f64 / orders as f64
+        } else {
+            0.0
+        };
+
+        let latency_dist = LogNormal::new(CHECKOUT_LATENCY_MU, CHECKOUT_LATENCY_SIGMA).unwrap();
+        let mut logs = Vec::with_capacity(orders * FUNNEL_STEPS as usize);
+
+        for declined in declined {
+            let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+            let items = rng.random_range(1..6);
+            let order_value = (rng.random_range(15.0..250.0f64) * 100.0).round() / 100.0;
+            let provider = *PAYMENT_PROVIDERS.choose(&mut rng).unwrap();
+
+            let cart_ms = latency_dist.sample(&mut rng) as i64;
+            logs.push(create_log(
+                "INFO",
+                format!("Checkout started: {} items, ${:.2}", items, order_value),
+                "cart-service",
+                &trace_id,
+                &span_id,
+                current_time_ns,
+                vec![
+                    KeyValue::string("checkout.step", "cart"),
+                    KeyValue::int("cart.items", items),
+                    KeyValue::double("order.value_usd", order_value),
+                    KeyValue::int("http.status_code", 200),
+                    KeyValue::int("http.duration_ms", cart_ms),
+                ],
+            ));
+
+            let fraud_ms = latency_dist.sample(&mut rng) as i64;
+            let fraud_score = rng.random_range(0.0..0.3f64);
+            logs.push(create_log(
+                "INFO",
+                format!("Fraud check approved (score {:.2})", fraud_score),
+                "fraud-check",
+                &trace_id,
+                &span_id,
+                current_time_ns,
+                vec![
+                    KeyValue::string("checkout.step", "fraud_check"),
+                    KeyValue::double("fraud.score", fraud_score),
+                    KeyValue::string("fraud.decision", "approve"),
+                    KeyValue::int("http.status_code", 200),
+                    KeyValue::int("http.duration_ms", fraud_ms),
+                ],
+            ));
+
+            let payment_ms = latency_dist.sample(&mut rng) as i64;
+            let (level, status, outcome) = if declined {
+                ("WARN", 402, "declined")
+            } else {
+                ("INFO", 200, "authorized")
+            };
+            let mut attrs = vec![
+                KeyValue::string("checkout.step", "payment"),
+                KeyValue::string("payment.provider", provider),
+                KeyValue::string("payment.status", outcome),
+                KeyValue::double("order.value_usd", order_value),
+                KeyValue::int("http.status_code", status),
+                KeyValue::int("http.duration_ms", payment_ms),
+            ];
+            attrs.extend(business_metrics(self.current_ops, failure_ratio));
+            logs.push(create_log(
+                level,
+                format!("Payment {} via {}", outcome, provider),
+                "payment-service",
+                &trace_id,
+                &span_id,
+                current_time_ns,
+                attrs,
+            ));
+        }
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        // Only the payment step of an order can fail
+        Some(
+            ProcessState::new(
+                self.name(),
+                self.current_ops * FUNNEL_STEPS,
+                CHECKOUT_DECLINE_RATE / FUNNEL_STEPS,
+            )
+            .with_latency(LatencyModel::LogNormal {
+                mu: CHECKOUT_LATENCY_MU,
+                sigma: CHECKOUT_LATENCY_SIGMA,
+            }),
+        )
+    }
+}
+
+// ============================================================================
+// Payment Provider Outage
+// ============================================================================
+
+/// A payment provider stops answering: calls hang until the gateway timeout
+/// or fail fast with 503, and the failure ratio jumps
+pub struct PaymentProviderOutage {
+    pub provider: String,
+    /// Orders per second routed to the failing provider
+    pub orders_per_sec: f64,
+}
+
+impl PaymentProviderOutage {
+    pub fn new(provider: &str, orders_per_sec: f64) -> Self {
+        Self {
+            provider: provider.to_string(),
+            orders_per_sec,
+        }
+    }
+}
+
+impl Scenario for PaymentProviderOutage {
+    fn name(&self) -> &str {
+        "Payment Provider Outage"
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.orders_per_sec *= factor;
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("ecommerce/payment_outage", current_time_ns, delta_ns);
+        let seconds = delta_ns as f64 / 1_000_000_000.0;
+        let count = (self.orders_per_sec * seconds).round() as u64;
+        let mut logs = Vec::new();
+
+        for _ in 0..count {
+            let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+
+            // Half the calls hang until the timeout, the rest are refused
+            let (status, duration_ms, error_type) = if rng.random_bool(0.5) {
+                (504, PAYMENT_TIMEOUT_MS, "ProviderTimeout")
+            } else {
+                (503, rng.random_range(5..50), "ProviderUnavailable")
+            };
+
+            let mut attrs = vec![
+                KeyValue::string("checkout.step", "payment"),
+                KeyValue::string("payment.provider", self.provider.clone()),
+                KeyValue::string("payment.status", "provider_error"),
+                KeyValue::string("error.type", error_type),
+                KeyValue::int("http.status_code", status),
+                KeyValue::int("http.duration_ms", duration_ms),
+            ];
+            attrs.extend(business_metrics(self.orders_per_sec, 1.0));
+
+            logs.push(create_log(
+                "ERROR",
+                format!(
+                    "Payment provider {} failed: {} after {}ms",
+                    self.provider, status, duration_ms
+                ),
+                "payment-service",
+                &trace_id,
+                &span_id,
+                current_time_ns,
+                attrs,
+            ));
+        }
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        Some(ProcessState::new(self.name(), self.orders_per_sec, 1.0))
+    }
+}
+
+// ============================================================================
+// Fraud Burst
+// ============================================================================
+
+/// Card-testing bot: a few IPs place many low-value orders with rotating
+/// cards; the fraud model scores them high and most never reach payment
+pub struct FraudBurst {
+    pub attempts_per_sec: f64,
+    source_ips: Vec<String>,
+}
+
+impl FraudBurst {
+    pub fn new(attempts_per_sec: f64) -> Self {
+        Self {
+            attempts_per_sec,
+            source_ips: vec![
+                "185.220.101.34".to_string(),
+                "185.220.101.35".to_string(),
+                "45.155.205.99".to_string(),
+            ],
+        }
+    }
+}
+
+impl Scenario for FraudBurst {
+    fn name(&self) -> &str {
+        "Fraud Burst"
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.attempts_per_sec *= factor;
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("ecommerce/fraud_burst", current_time_ns, delta_ns);
+        let seconds = delta_ns as f64 / 1_000_000_000.0;
+        let count = (self.attempts_per_sec * seconds).round() as u64;
+        let mut logs = Vec::new();
+
+        for _ in 0..count {
+            let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+            let source_ip = self.source_ips.choose(&mut rng).unwrap();
+            let order_value = (rng.random_range(0.5..5.0f64) * 100.0).round() / 100.0;
+            let card_bin = format!("4{:05}", rng.random_range(0..100_000));
+            let fraud_score = rng.random_range(0.75..0.99f64);
+            // Extra velocity and device lookups slow the model down
+            let fraud_ms = rng.random_range(150..400);
+
+            let passed = rng.random_bool(FRAUD_PASS_THROUGH);
+            let decision = if passed { "review" } else { "decline" };
+            logs.push(create_log(
+                if passed { "WARN" } else { "ERROR" },
+                format!(
+                    "Fraud check {} (score {:.2}) for ${:.2} from {}",
+                    decision, fraud_score, order_value, source_ip
+                ),
+                "fraud-check",
+                &trace_id,
+                &span_id,
+                current_time_ns,
+                vec![
+                    KeyValue::string("checkout.step", "fraud_check"),
+                    KeyValue::double("fraud.score", fraud_score),
+                    KeyValue::string("fraud.decision", decision),
+                    KeyValue::string("card.bin", card_bin),
+                    KeyValue::double("order.value_usd", order_value),
+                    KeyValue::string("net.peer.ip", source_ip.clone()),
+                    KeyValue::int("http.status_code", if passed { 200 } else { 403 }),
+                    KeyValue::int("http.duration_ms", fraud_ms),
+                    KeyValue::string("threat.type", "card_testing"),
+                ],
+            ));
+
+            if passed {
+                // Issuers decline stolen cards that slip past the model
+                let mut attrs = vec![
+                    KeyValue::string("checkout.step", "payment"),
+                    KeyValue::string(
+                        "payment.provider",
+                        *PAYMENT_PROVIDERS.choose(&mut rng).unwrap(),
+                    ),
+                    KeyValue::string("payment.status", "declined"),
+                    KeyValue::string("card.bin", format!("4{:05}", rng.random_range(0..100_000))),
+                    KeyValue::int("http.status_code", 402),
+                    KeyValue::int("http.duration_ms", rng.random_range(40..120)),
+                ];
+                attrs.extend(business_metrics(self.attempts_per_sec, 1.0));
+                logs.push(create_log(
+                    "WARN",
+                    format!("Payment declined: card from {}", source_ip),
+                    "payment-service",
+                    &trace_id,
+                    &span_id,
+                    current_time_ns,
+                    attrs,
+                ));
+            }
+        }
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        // Declined attempts log one 403; passed ones a 200 review plus a 402
+        Some(ProcessState::new(
+            self.name(),
+            self.attempts_per_sec * (1.0 + FRAUD_PASS_THROUGH),
+            1.0 / (1.0 + FRAUD_PASS_THROUGH),
+        ))
+    }
+}
@@ -0,0 +1,364 @@
+//! IoT Fleet Telemetry Scenarios
+//!
+//! A fleet of devices reporting temperature and battery readings through
+//! regional gateways. Each device logs under its own `service.name`
+//! (`dev-00042`), so per-service mode keeps one profile per device and the
+//! registry has to hold thousands of entities. Anomalies report the devices
+//! they affect as ground-truth targets.
+//!
+//! - **FleetTelemetry**: baseline readings on a fixed per-device cadence
+//! - **FirmwareRegression**: a rollout cohort overheats, drains, and reboots
+//! - **SensorDrift**: a few devices' readings silently drift upward
+
+use crate::core::{KeyValue, LogRecord, ProcessState};
+use crate::scenarios::traffic::create_log;
+use crate::scenarios::{Scenario, next_trace_and_span_ids, rng_for_tick};
+use rand::prelude::*;
+use rand_distr::{Distribution, Normal};
+
+const REGIONS: [&str; 4] = ["eu-west", "us-east", "us-west", "ap-south"];
+const MODELS: [&str; 3] = ["thermo-s1", "thermo-s2", "env-hub"];
+const FIRMWARE_VERSIONS: [&str; 3] = ["2.2.4", "2.3.0", "2.3.1"];
+/// Firmware the regression rollout installs
+const REGRESSED_FIRMWARE: &str = "2.4.0";
+
+/// Share of baseline readings that fail (sensor read timeout)
+const FLEET_ERROR_RATE: f64 = 0.002;
+/// Standard deviation of baseline temperature noise (°C)
+const TEMPERATURE_NOISE_C: f64 = 0.5;
+/// Share of regressed-firmware reports that are watchdog resets
+const WATCHDOG_RESET_RATE: f64 = 0.3;
+
+/// Fleet shape shared by the baseline and the anomalies targeting it
+#[derive(Debug, Clone)]
+pub struct Fleet {
+    pub devices: usize,
+    /// Time between two reports of one device
+    pub report_interval_ns: u64,
+}
+
+impl Fleet {
+    pub fn new(devices: usize, report_interval_ms: u64) -> Self {
+        Self {
+            devices,
+            report_interval_ns: report_interval_ms.max(1) * 1_000_000,
+        }
+    }
+
+    pub fn device_id(index: usize) -> String {
+        format!("dev-{:05}", index)
+    }
+
+    /// Fleet-wide report rate (logs/sec)
+    pub fn reports_per_sec(&self) -> f64 {
+        self.devices as f64 * 1_000_000_000.0 / self.report_interval_ns as f64
+    }
+
+    /// Devices whose cohort hash falls under `share`; stable across runs
+    pub fn cohort(&self, share: f64, salt: u64) -> Vec<usize> {
+        let cutoff = (share.clamp(0.0, 1.0) * 10_000.0) as u64;
+        (0..self.devices)
+            .filter(|&i| {
+                let key = format!("{}:{}", salt, i);
+                xxhash_rust::xxh3::xxh3_64(key.as_bytes()) % 10_000 < cutoff
+            })
+            .collect()
+    }
+
+    /// Reports device `index` sends in `[time_ns, time_ns + delta_ns)`
+    ///
+    /// Devices are phase-shifted so reports spread evenly over the interval.
+    fn reports(&self, index: usize, time_ns: u64, delta_ns: u64) -> u64 {
+        let interval = self.report_interval_ns;
+        let phase = (index as u64).wrapping_mul(7_919_000_003) % interval;
+        let start = time_ns.wrapping_add(phase);
+        (start + delta_ns) / interval - start / interval
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.report_interval_ns = ((self.report_interval_ns as f64 / factor) as u64).max(1);
+    }
+
+    /// Ambient temperature the device normally reads (°C)
+    fn ambient(index: usize) -> f64 {
+        18.0 + (index % 9) as f64
+    }
+
+    /// Battery level at a given time (%), draining 1% per hour over a 40 h
+    /// charge cycle (so wall-clock start times stay in range)
+    fn battery(index: usize, time_ns: u64) -> f64 {
+        const CYCLE_NS: u64 = 40 * 3_600 * 1_000_000_000;
+        let full = 100.0 - ((index * 37) % 50) as f64;
+        full - (time_ns % CYCLE_NS) as f64 / 3.6e12
+    }
+
+    fn firmware(index: usize) -> &'static str {
+        FIRMWARE_VERSIONS[(index / 7) % FIRMWARE_VERSIONS.len()]
+    }
+
+    /// Telemetry log for one device
+    fn reading(
+        index: usize,
+        level: &str,
+        body: String,
+        time_ns: u64,
+        trace: (&str, &str),
+        mut attrs: Vec<KeyValue>,
+    ) -> LogRecord {
+        attrs.extend([
+            KeyValue::string("device.id", Fleet::device_id(index)),
+            KeyValue::string("device.model", MODELS[index % MODELS.len()]),
+            KeyValue::string("gateway.region", REGIONS[index % REGIONS.len()]),
+        ]);
+        create_log(
+            level,
+            body,
+            &Fleet::device_id(index),
+            trace.0,
+            trace.1,
+            time_ns,
+            attrs,
+        )
+    }
+}
+
+// ============================================================================
+// Fleet Telemetry (baseline)
+// ============================================================================
+
+/// Healthy fleet: every device reports once per interval
+pub struct FleetTelemetry {
+    pub fleet: Fleet,
+}
+
+impl FleetTelemetry {
+    pub fn new(devices: usize, report_interval_ms: u64) -> Self {
+        Self {
+            fleet: Fleet::new(devices, report_interval_ms),
+        }
+    }
+}
+
+impl Scenario for FleetTelemetry {
+    fn name(&self) -> &str {
+        "IoT Fleet Telemetry"
+    }
+
+    fn is_baseline(&self) -> bool {
+        true
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.fleet.scale_rate(factor);
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("iot/fleet", current_time_ns, delta_ns);
+        let noise = Normal::new(0.0, TEMPERATURE_NOISE_C).unwrap();
+        let mut logs = Vec::new();
+
+        for i in 0..self.fleet.devices {
+            for _ in 0..self.fleet.reports(i, current_time_ns, delta_ns) {
+                let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+                let firmware = KeyValue::string("firmware.version", Fleet::firmware(i));
+
+                let log = if rng.random_bool(FLEET_ERROR_RATE) {
+                    Fleet::reading(
+                        i,
+                        "ERROR",
+                        "Sensor read timeout".to_string(),
+                        current_time_ns,
+                        (&trace_id, &span_id),
+                        vec![firmware, KeyValue::string("error.type", "SensorTimeout")],
+                    )
+                } else {
+                    let temperature = Fleet::ambient(i) + noise.sample(&mut rng);
+                    let battery = Fleet::battery(i, current_time_ns);
+                    Fleet::reading(
+                        i,
+                        "INFO",
+                        format!("Telemetry: {:.1}°C, battery {:.0}%", temperature, battery),
+                        current_time_ns,
+                        (&trace_id, &span_id),
+                        vec![
+                            firmware,
+                            KeyValue::double("sensor.temperature_c", temperature),
+                            KeyValue::double("device.battery_pct", battery),
+                        ],
+                    )
+                };
+                logs.push(log);
+            }
+        }
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        Some(ProcessState::new(
+            self.name(),
+            self.fleet.reports_per_sec(),
+            FLEET_ERROR_RATE,
+        ))
+    }
+}
+
+// ============================================================================
+// Firmware Rollout Regression
+// ============================================================================
+
+/// A firmware rollout to a cohort of the fleet ships a busy-loop bug: updated
+/// devices run hot, drain their batteries, and hit watchdog resets
+pub struct FirmwareRegression {
+    pub fleet: Fleet,
+    /// Device indices that received the bad firmware
+    pub cohort: Vec<usize>,
+}
+
+impl FirmwareRegression {
+    pub fn new(fleet: Fleet, rollout_share: f64) -> Self {
+        let cohort = fleet.cohort(rollout_share, 0x0f1e);
+        Self { fleet, cohort }
+    }
+}
+
+impl Scenario for FirmwareRegression {
+    fn name(&self) -> &str {
+        "Firmware Regression"
+    }
+
+    fn targets(&self) -> Vec<String> {
+        self.cohort.iter().map(|&i| Fleet::device_id(i)).collect()
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.fleet.scale_rate(factor);
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("iot/firmware_regression", current_time_ns, delta_ns);
+        let mut logs = Vec::new();
+
+        for &i in &self.cohort {
+            for _ in 0..self.fleet.reports(i, current_time_ns, delta_ns) {
+                let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+                let firmware = KeyValue::string("firmware.version", REGRESSED_FIRMWARE);
+
+                let log = if rng.random_bool(WATCHDOG_RESET_RATE) {
+                    Fleet::reading(
+                        i,
+                        "ERROR",
+                        "Watchdog reset: main loop unresponsive".to_string(),
+                        current_time_ns,
+                        (&trace_id, &span_id),
+                        vec![
+                            firmware,
+                            KeyValue::string("error.type", "WatchdogReset"),
+                            KeyValue::string("device.reboot_reason", "watchdog"),
+                        ],
+                    )
+                } else {
+                    // CPU spinning heats the board and drains the battery
+                    let temperature = Fleet::ambient(i) + rng.random_range(8.0..16.0);
+                    let battery = Fleet::battery(i, current_time_ns) * rng.random_range(0.4..0.7);
+                    Fleet::reading(
+                        i,
+                        "WARN",
+                        format!("Telemetry: {:.1}°C, battery {:.0}%", temperature, battery),
+                        current_time_ns,
+                        (&trace_id, &span_id),
+                        vec![
+                            firmware,
+                            KeyValue::double("sensor.temperature_c", temperature),
+                            KeyValue::double("device.battery_pct", battery),
+                        ],
+                    )
+                };
+                logs.push(log);
+            }
+        }
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        let rps = self.fleet.reports_per_sec() * self.cohort.len() as f64
+            / self.fleet.devices.max(1) as f64;
+        Some(ProcessState::new(self.name(), rps, WATCHDOG_RESET_RATE))
+    }
+}
+
+// ============================================================================
+// Sensor Drift
+// ============================================================================
+
+/// Degrading sensors on a few devices read progressively higher while
+/// everything else about the reports looks healthy
+pub struct SensorDrift {
+    pub fleet: Fleet,
+    /// Devices with drifting sensors
+    pub devices: Vec<usize>,
+    /// Added reading error per simulated minute (°C)
+    pub drift_per_min: f64,
+    started_ns: Option<u64>,
+}
+
+impl SensorDrift {
+    pub fn new(fleet: Fleet, share: f64, drift_per_min: f64) -> Self {
+        let devices = fleet.cohort(share, 0xd41f7);
+        Self {
+            fleet,
+            devices,
+            drift_per_min,
+            started_ns: None,
+        }
+    }
+}
+
+impl Scenario for SensorDrift {
+    fn name(&self) -> &str {
+        "Sensor Drift"
+    }
+
+    fn targets(&self) -> Vec<String> {
+        self.devices.iter().map(|&i| Fleet::device_id(i)).collect()
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.fleet.scale_rate(factor);
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("iot/sensor_drift", current_time_ns, delta_ns);
+        let noise = Normal::new(0.0, TEMPERATURE_NOISE_C).unwrap();
+        let started = *self.started_ns.get_or_insert(current_time_ns);
+        let minutes = (current_time_ns - started) as f64 / 60e9;
+        let offset = self.drift_per_min * minutes;
+        let mut logs = Vec::new();
+
+        for &i in &self.devices {
+            for _ in 0..self.fleet.reports(i, current_time_ns, delta_ns) {
+                let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+                let temperature = Fleet::ambient(i) + offset + noise.sample(&mut rng);
+                let battery = Fleet::battery(i, current_time_ns);
+                logs.push(Fleet::reading(
+                    i,
+                    "INFO",
+                    format!("Telemetry: {:.1}°C, battery {:.0}%", temperature, battery),
+                    current_time_ns,
+                    (&trace_id, &span_id),
+                    vec![
+                        KeyValue::string("firmware.version", Fleet::firmware(i)),
+                        KeyValue::double("sensor.temperature_c", temperature),
+                        KeyValue::double("device.battery_pct", battery),
+                    ],
+                ));
+            }
+        }
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        let rps = self.fleet.reports_per_sec() * self.devices.len() as f64
+            / self.fleet.devices.max(1) as f64;
+        Some(ProcessState::new(self.name(), rps, 0.0))
+    }
+}
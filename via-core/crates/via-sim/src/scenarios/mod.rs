@@ -2,16 +2,27 @@
 //!
 //! Configurable scenarios for generating realistic anomaly patterns:
 //! - **traffic**: Normal and spike traffic patterns
-//! - **security**: Attack patterns (credential stuffing, SQL injection, port scan)
-//! - **performance**: Resource issues (memory leak, CPU spike, slow queries)
-//! - **distributed**: Complex patterns (cascade failure, DDoS, data exfiltration)
+//! - **security**: Attack patterns (credential stuffing, SQL injection, port scan, ransomware)
+//! - **performance**: Resource issues (memory leak, CPU spike, slow queries, canary regression,
+//!   consumer lag)
+//! - **distributed**: Complex patterns (cascade failure, DDoS, data exfiltration, API scraping,
+//!   DNS beaconing, regional outage, service silence)
+//! - **ecommerce**: Checkout funnel (payment-provider outage, fraud burst)
+//! - **iot**: Device fleet telemetry (firmware regression, sensor drift)
+//! - **churn**: Short-lived entities around a stable core (core latency regression)
+//! - **sessions**: Users logging in, browsing and buying, with churn
 
+pub mod churn;
 pub mod distributed;
+pub mod ecommerce;
+pub mod iot;
 pub mod performance;
 pub mod security;
+pub mod sessions;
 pub mod traffic;
 
-use crate::core::LogRecord;
+use crate::core::{LogRecord, ProcessState};
+use crate::seasonality::{Burstiness, Seasonality};
 use rand::{Rng, SeedableRng, rngs::StdRng};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
@@ -37,6 +48,59 @@ pub trait Scenario: Send {
     /// # Returns
     /// Vector of log records generated during this time step
     fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord>;
+
+    /// True generating-process parameters for the most recent tick
+    ///
+    /// Called after `tick` with the same `delta_ns`, so scenarios that emit
+    /// with a per-tick probability can report an equivalent rate.
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        None
+    }
+
+    /// Multiply the scenario's emission rate by `factor`
+    ///
+    /// Scenarios that emit with a fixed per-tick probability ignore this.
+    fn scale_rate(&mut self, _factor: f64) {}
+
+    /// Entities (`service.name`s) an injected anomaly affects; empty = all
+    ///
+    /// Recorded as the ground truth's `target_services`.
+    fn targets(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Whether a baseline log would not have been written while this anomaly
+    /// is active, e.g. traffic of a region that went dark
+    ///
+    /// The engine drops such logs, so anomalies can produce volume drops.
+    fn suppresses(&self, _log: &LogRecord) -> bool {
+        false
+    }
+
+    /// Whether the anomaly is missing data (logs that stop) rather than logs
+    /// that look wrong
+    ///
+    /// Ground truth marks its window `absence`, so benchmarks score it by
+    /// time instead of by per-log labels.
+    fn is_absence(&self) -> bool {
+        false
+    }
+
+    /// Whether the scenario only produces normal traffic
+    ///
+    /// Clean runs (`SimulationEngine::set_clean`) refuse every other
+    /// scenario.
+    fn is_baseline(&self) -> bool {
+        false
+    }
+}
+
+/// Convert a per-tick emission probability into logs/sec
+pub(crate) fn per_tick_rate(probability: f64, delta_ns: u64) -> f64 {
+    if delta_ns == 0 {
+        return 0.0;
+    }
+    probability * 1_000_000_000.0 / delta_ns as f64
 }
 
 pub fn configure_determinism(enabled: bool, seed: u64) {
@@ -84,17 +148,31 @@ pub fn next_trace_and_span_ids<R: Rng + ?Sized>(rng: &mut R) -> (String, String)
 }
 
 // Re-export common scenarios for convenience
+pub use churn::{CoreLatencyRegression, EntityChurn, Population};
 pub use distributed::{
-    CascadeFailure, DDoSAttack, DataExfiltration, ErrorRateSpike, SlowQueries, TrafficSpike,
+    ApiScraping, Beaconing, CascadeFailure, DDoSAttack, DataExfiltration, ErrorRateSpike,
+    RegionalOutage, ServiceSilence, SlowQueries, TrafficSpike,
 };
-pub use performance::{CpuSpike, InfiniteLoop, MemoryLeak};
-pub use security::{CredentialStuffing, PortScan, SqlInjection};
+pub use ecommerce::{CheckoutFunnel, FraudBurst, PaymentProviderOutage};
+pub use iot::{FirmwareRegression, Fleet, FleetTelemetry, SensorDrift};
+pub use performance::{CanaryRegression, ConsumerLag, CpuSpike, InfiniteLoop, MemoryLeak};
+pub use security::{CredentialStuffing, PortScan, Ransomware, SqlInjection};
+pub use sessions::{UserBase, UserSessions};
 pub use traffic::NormalTraffic;
 
+/// Default fleet shared by the IoT baseline and its anomalies
+const IOT_FLEET_DEVICES: usize = 1_000;
+const IOT_REPORT_INTERVAL_MS: u64 = 1_000;
+
 /// Create a scenario by name with default parameters
 pub fn create_scenario(name: &str) -> Option<Box<dyn Scenario>> {
     match name.to_lowercase().as_str() {
         "normal_traffic" | "normal" => Some(Box::new(NormalTraffic::new(100.0))),
+        "seasonal_traffic" | "seasonal" => Some(Box::new(
+            NormalTraffic::new(100.0)
+                .with_seasonality(Seasonality::business())
+                .with_burstiness(Burstiness::Pareto { alpha: 2.5 }),
+        )),
         "credential_stuffing" | "brute_force" => {
             Some(Box::new(CredentialStuffing { attack_rps: 50.0 }))
         }
@@ -103,20 +181,67 @@ pub fn create_scenario(name: &str) -> Option<Box<dyn Scenario>> {
             source_ip: "192.168.1.100".to_string(),
             scan_speed: 100.0,
         })),
+        "ransomware" | "file_encryption" => Some(Box::new(Ransomware::new("file-server-01", 5.0))),
         "memory_leak" => Some(Box::new(MemoryLeak::new("payment-service", 10.0))),
         "cpu_spike" => Some(Box::new(CpuSpike::new("stream-processor", 0.8))),
         "infinite_loop" | "stack_overflow" => Some(Box::new(InfiniteLoop {
             service_name: "recommendation-engine".to_string(),
         })),
+        "canary_regression" | "config_drift" => Some(Box::new(CanaryRegression::new(
+            "payment-service",
+            0.2,
+            20 * 60,
+        ))),
+        "consumer_lag" | "pipeline_lag" => Some(Box::new(ConsumerLag::new(
+            "stream-processor",
+            "orders",
+            500.0,
+            5 * 60,
+        ))),
         "ddos" | "ddos_attack" => Some(Box::new(DDoSAttack::new("api-gateway", 100, 10.0))),
-        "cascade_failure" | "cascade" => Some(Box::new(CascadeFailure::new("auth-service", 0.3))),
+        "cascade_failure" | "cascade" => Some(Box::new(CascadeFailure::new("db-cluster", 0.3))),
         "data_exfiltration" | "exfil" => Some(Box::new(DataExfiltration::new(
             5.0,
             "external-collector.evil.com",
         ))),
+        "api_scraping" | "scraping" => Some(Box::new(ApiScraping::new("api-gateway", 400, 10, 30))),
+        "beaconing" | "dns_tunneling" => Some(Box::new(Beaconing::new(
+            "workstation-042",
+            "cdn-metrics-sync.net",
+            5,
+        ))),
+        "regional_outage" | "region_failover" => {
+            Some(Box::new(RegionalOutage::new("us-east-1", 100.0)))
+        }
+        "service_silence" | "heartbeat_loss" => {
+            Some(Box::new(ServiceSilence::new("inventory-service")))
+        }
         "slow_queries" => Some(Box::new(SlowQueries::new("inventory-service", 5.0, 10.0))),
         "error_spike" => Some(Box::new(ErrorRateSpike::new("payment-service", 0.5, 50.0))),
         "traffic_spike" => Some(Box::new(TrafficSpike::new("api-gateway", 10.0, 100.0))),
+        "checkout_funnel" | "checkout" => Some(Box::new(CheckoutFunnel::new(20.0))),
+        "payment_outage" | "payment_provider_outage" => {
+            Some(Box::new(PaymentProviderOutage::new("stripe", 10.0)))
+        }
+        "fraud_burst" | "card_testing" => Some(Box::new(FraudBurst::new(40.0))),
+        "iot_fleet" | "iot" => Some(Box::new(FleetTelemetry::new(
+            IOT_FLEET_DEVICES,
+            IOT_REPORT_INTERVAL_MS,
+        ))),
+        "firmware_regression" | "firmware_rollout" => Some(Box::new(FirmwareRegression::new(
+            Fleet::new(IOT_FLEET_DEVICES, IOT_REPORT_INTERVAL_MS),
+            0.1,
+        ))),
+        "sensor_drift" => Some(Box::new(SensorDrift::new(
+            Fleet::new(IOT_FLEET_DEVICES, IOT_REPORT_INTERVAL_MS),
+            0.01,
+            3.0,
+        ))),
+        "entity_churn" | "churn" => Some(Box::new(EntityChurn::new(Population::default()))),
+        "core_latency_regression" | "core_regression" => Some(Box::new(
+            CoreLatencyRegression::new(Population::default(), 4.0),
+        )),
+        "user_sessions" | "sessions" => Some(Box::new(UserSessions::new(UserBase::default()))),
         _ => None,
     }
 }
@@ -128,23 +253,82 @@ pub fn list_scenarios() -> Vec<(&'static str, &'static str)> {
             "normal_traffic",
             "Normal baseline traffic with realistic patterns",
         ),
+        (
+            "seasonal_traffic",
+            "Normal traffic on daily / weekly load curves with Pareto bursts",
+        ),
         (
             "credential_stuffing",
             "Brute force login attempts from multiple IPs",
         ),
         ("sql_injection", "SQL injection probe attacks"),
         ("port_scan", "Network port scanning activity"),
+        (
+            "ransomware",
+            "Host encrypting and renaming files at an escalating rate",
+        ),
         ("memory_leak", "Gradual memory consumption leading to OOM"),
         ("cpu_spike", "High CPU utilization causing timeouts"),
         ("infinite_loop", "Stack overflow from infinite recursion"),
+        (
+            "canary_regression",
+            "Bad deploy on some instances drifting slower and flakier",
+        ),
+        (
+            "consumer_lag",
+            "Stream consumer falling behind its topic, then catching up",
+        ),
         ("ddos", "Distributed denial of service attack"),
         (
             "cascade_failure",
             "Service failure propagating through dependencies",
         ),
         ("data_exfiltration", "Suspicious large data transfers"),
+        (
+            "api_scraping",
+            "Scraper rotating API keys and IPs just under the rate limit",
+        ),
+        (
+            "beaconing",
+            "Host calling home over DNS at a fixed interval",
+        ),
+        (
+            "regional_outage",
+            "Region going dark, its traffic failing over to the others",
+        ),
+        (
+            "service_silence",
+            "Service stops logging (missing data, scored by time)",
+        ),
         ("slow_queries", "Database performance degradation"),
         ("error_spike", "Sudden increase in error rates"),
         ("traffic_spike", "Sudden traffic burst"),
+        (
+            "checkout_funnel",
+            "E-commerce checkout flow with business metrics",
+        ),
+        ("payment_outage", "Payment provider timeouts and 503s"),
+        ("fraud_burst", "Card-testing bot placing small orders"),
+        (
+            "iot_fleet",
+            "Device fleet reporting through regional gateways",
+        ),
+        (
+            "firmware_regression",
+            "Firmware rollout cohort overheating and rebooting",
+        ),
+        ("sensor_drift", "Device sensors drifting out of calibration"),
+        (
+            "entity_churn",
+            "Stable core services plus short-lived workers churning through",
+        ),
+        (
+            "core_latency_regression",
+            "Core services slowing down amid worker churn",
+        ),
+        (
+            "user_sessions",
+            "Users logging in, browsing and buying, with churn",
+        ),
     ]
 }
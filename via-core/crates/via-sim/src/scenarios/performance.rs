@@ -1,8 +1,13 @@
-use crate::core::{AnyValue, KeyValue, LogRecord};
+use crate::core::{AnyValue, KeyValue, LatencyModel, LogRecord, ProcessState};
 use crate::scenarios::traffic::create_log;
-use crate::scenarios::{Scenario, next_trace_and_span_ids, rng_for_tick};
+use crate::scenarios::{Scenario, next_trace_and_span_ids, per_tick_rate, rng_for_tick};
 use rand::prelude::*;
-use rand_distr::{Distribution, Normal};
+use rand_distr::{Distribution, LogNormal, Normal};
+
+/// Chance per tick that the leaking service reports its memory usage
+const MEMORY_REPORT_PROBABILITY: f64 = 0.2;
+/// Chance per tick of a stack overflow
+const INFINITE_LOOP_PROBABILITY: f64 = 0.05;
 
 // --- 1. Memory Leak ---
 pub struct MemoryLeak {
@@ -57,7 +62,7 @@ impl Scenario for MemoryLeak {
         let mut logs = Vec::new();
 
         // Generate metric-like logs every second (probabilistically)
-        if rng.random_bool(0.2) {
+        if rng.random_bool(MEMORY_REPORT_PROBABILITY) {
             // not every tick, but frequent
             let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
 
@@ -113,6 +118,14 @@ impl Scenario for MemoryLeak {
 
         logs
     }
+
+    fn process_state(&self, delta_ns: u64) -> Option<ProcessState> {
+        Some(ProcessState::new(
+            self.name(),
+            per_tick_rate(MEMORY_REPORT_PROBABILITY, delta_ns),
+            0.0,
+        ))
+    }
 }
 
 // --- 2. CPU Spike ---
@@ -177,6 +190,16 @@ impl Scenario for CpuSpike {
         }
         logs
     }
+
+    fn process_state(&self, delta_ns: u64) -> Option<ProcessState> {
+        Some(
+            ProcessState::new(self.name(), per_tick_rate(self.intensity, delta_ns), 0.0)
+                .with_latency(LatencyModel::Normal {
+                    mean: 5000.0,
+                    std_dev: 1500.0,
+                }),
+        )
+    }
 }
 
 // --- 3. Infinite Loop (Stack Overflow Simulation) ---
@@ -192,7 +215,7 @@ impl Scenario for InfiniteLoop {
     fn tick(&mut self, current_time_ns: u64, _delta_ns: u64) -> Vec<LogRecord> {
         let mut rng = rng_for_tick("performance/infinite_loop", current_time_ns, _delta_ns);
         // Rare but catastrophic event
-        if rng.random_bool(0.05) {
+        if rng.random_bool(INFINITE_LOOP_PROBABILITY) {
             let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
 
             vec![create_log(
@@ -211,4 +234,386 @@ impl Scenario for InfiniteLoop {
             vec![]
         }
     }
+
+    fn process_state(&self, delta_ns: u64) -> Option<ProcessState> {
+        Some(ProcessState::new(
+            self.name(),
+            per_tick_rate(INFINITE_LOOP_PROBABILITY, delta_ns),
+            1.0,
+        ))
+    }
+}
+
+// --- 4. Canary Regression (gradual drift after a bad deploy) ---
+
+/// Healthy request latency (ms) is LogNormal(mu, sigma), as in normal traffic
+const CANARY_LATENCY_MU: f64 = 4.0;
+const CANARY_LATENCY_SIGMA: f64 = 0.5;
+/// Healthy share of failing requests
+const CANARY_BASE_ERROR_RATE: f64 = 0.01;
+
+/// A bad build rolled out to a fraction of a service's instances. The canary
+/// instances' latency and error rate drift from healthy towards
+/// `latency_factor` and `error_rate` over `ramp_ns`, while the rest of the
+/// service (the baseline traffic) stays healthy: a slow shift in part of the
+/// population rather than a step.
+pub struct CanaryRegression {
+    pub service_name: String,
+    /// Instances of the service
+    pub instances: usize,
+    /// Share of instances running the canary build
+    pub canary_fraction: f64,
+    /// Requests per second the whole service handles
+    pub service_rps: f64,
+    /// Latency multiplier reached at the end of the ramp
+    pub latency_factor: f64,
+    /// Error rate reached at the end of the ramp
+    pub error_rate: f64,
+    /// Time until the regression is fully developed
+    pub ramp_ns: u64,
+    elapsed_ns: u64,
+}
+
+impl CanaryRegression {
+    pub fn new(service_name: &str, canary_fraction: f64, ramp_secs: u64) -> Self {
+        Self {
+            service_name: service_name.to_string(),
+            instances: 10,
+            canary_fraction: canary_fraction.clamp(0.0, 1.0),
+            service_rps: 16.0,
+            latency_factor: 2.5,
+            error_rate: 0.08,
+            ramp_ns: ramp_secs.max(1) * 1_000_000_000,
+            elapsed_ns: 0,
+        }
+    }
+
+    /// Instances running the canary build (at least one)
+    pub fn canary_instances(&self) -> usize {
+        ((self.instances as f64 * self.canary_fraction).round() as usize).max(1)
+    }
+
+    /// Share of the ramp completed, 0 to 1
+    fn progress(&self) -> f64 {
+        (self.elapsed_ns as f64 / self.ramp_ns as f64).min(1.0)
+    }
+
+    fn current_latency_factor(&self) -> f64 {
+        1.0 + (self.latency_factor - 1.0) * self.progress()
+    }
+
+    fn current_error_rate(&self) -> f64 {
+        CANARY_BASE_ERROR_RATE + (self.error_rate - CANARY_BASE_ERROR_RATE) * self.progress()
+    }
+
+    fn canary_rps(&self) -> f64 {
+        self.service_rps * self.canary_instances() as f64 / self.instances.max(1) as f64
+    }
+}
+
+impl Scenario for CanaryRegression {
+    fn name(&self) -> &str {
+        "Canary Regression"
+    }
+
+    fn targets(&self) -> Vec<String> {
+        vec![self.service_name.clone()]
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.service_rps *= factor;
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("performance/canary_regression", current_time_ns, delta_ns);
+        let seconds = delta_ns as f64 / 1_000_000_000.0;
+        let count = (self.canary_rps() * seconds).round() as u64;
+        let latency_factor = self.current_latency_factor();
+        let error_rate = self.current_error_rate();
+        let healthy = LogNormal::new(CANARY_LATENCY_MU, CANARY_LATENCY_SIGMA).unwrap();
+        let mut logs = Vec::new();
+
+        for _ in 0..count {
+            let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+            let instance = rng.random_range(0..self.canary_instances());
+            let latency = (healthy.sample(&mut rng) * latency_factor) as i64;
+            let failed = rng.random_bool(error_rate);
+            let (level, status) = if failed {
+                ("ERROR", 500)
+            } else {
+                ("INFO", 200)
+            };
+
+            let mut attrs = vec![
+                KeyValue::string("http.method", "GET"),
+                KeyValue::int("http.status_code", status),
+                KeyValue::int("http.duration_ms", latency),
+                KeyValue::string(
+                    "service.instance.id",
+                    format!("{}-canary-{}", self.service_name, instance),
+                ),
+                KeyValue::string("service.version", "canary"),
+            ];
+            if failed {
+                attrs.push(KeyValue::string("error.type", "InternalServerError"));
+            }
+            logs.push(create_log(
+                level,
+                format!("Request processed in {}ms", latency),
+                &self.service_name,
+                &trace_id,
+                &span_id,
+                current_time_ns,
+                attrs,
+            ));
+        }
+        self.elapsed_ns += delta_ns;
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        Some(
+            ProcessState::new(self.name(), self.canary_rps(), self.current_error_rate())
+                .with_latency(LatencyModel::LogNormal {
+                    mu: CANARY_LATENCY_MU + self.current_latency_factor().ln(),
+                    sigma: CANARY_LATENCY_SIGMA,
+                }),
+        )
+    }
+}
+
+// --- 5. Consumer Lag (stream consumer falling behind, then catching up) ---
+
+/// Healthy time (ms) to process one polled batch, LogNormal(mu, sigma)
+const BATCH_LATENCY_MU: f64 = 3.7;
+const BATCH_LATENCY_SIGMA: f64 = 0.3;
+/// Messages returned by one poll
+const BATCH_SIZE: f64 = 100.0;
+/// Batches slower than this are logged as warnings
+const SLOW_BATCH_MS: f64 = 100.0;
+/// Lag above this many messages on a partition is reported as a warning
+const LAG_WARN_MESSAGES: f64 = 10_000.0;
+/// Every partition reports its lag once per interval
+const LAG_REPORT_INTERVAL_NS: u64 = 1_000_000_000;
+
+/// A stream consumer falling behind its topic. Producers keep writing at
+/// `produce_rate` while the consumer only manages `degraded_throughput` of
+/// it for `stall_ns`, so lag climbs steadily on every partition; then the
+/// consumer works through the backlog at `catchup_factor` times the produce
+/// rate until lag is back to zero. Each partition reports its lag every
+/// second and every polled batch logs how long it took, so the signal is a
+/// slow ramp and recovery rather than a spike.
+pub struct ConsumerLag {
+    pub service_name: String,
+    pub topic: String,
+    pub consumer_group: String,
+    pub partitions: usize,
+    /// Messages per second produced to the topic
+    pub produce_rate: f64,
+    /// Share of the produce rate consumed while degraded
+    pub degraded_throughput: f64,
+    /// Time the consumer stays degraded
+    pub stall_ns: u64,
+    /// Capacity of the healthy consumer, relative to `produce_rate`; the
+    /// headroom is what drains the backlog
+    pub catchup_factor: f64,
+    /// Messages behind, per partition
+    lag: Vec<f64>,
+    /// Committed offset per partition
+    offsets: Vec<u64>,
+    /// Fractional polls carried over between ticks
+    poll_credit: f64,
+    /// Messages per second consumed during the last tick
+    consumed_rate: f64,
+    elapsed_ns: u64,
+    next_report_ns: u64,
+    caught_up: bool,
+}
+
+impl ConsumerLag {
+    pub fn new(service_name: &str, topic: &str, produce_rate: f64, stall_secs: u64) -> Self {
+        let partitions = 6;
+        Self {
+            service_name: service_name.to_string(),
+            topic: topic.to_string(),
+            consumer_group: format!("{}-group", service_name),
+            partitions,
+            produce_rate,
+            degraded_throughput: 0.4,
+            stall_ns: stall_secs * 1_000_000_000,
+            catchup_factor: 1.5,
+            lag: vec![0.0; partitions],
+            offsets: vec![0; partitions],
+            poll_credit: 0.0,
+            consumed_rate: 0.0,
+            elapsed_ns: 0,
+            next_report_ns: 0,
+            caught_up: false,
+        }
+    }
+
+    /// Messages behind across all partitions
+    pub fn total_lag(&self) -> f64 {
+        self.lag.iter().sum()
+    }
+
+    fn degraded(&self) -> bool {
+        self.elapsed_ns < self.stall_ns
+    }
+
+    /// Messages per second the consumer can process right now
+    fn capacity(&self) -> f64 {
+        if self.degraded() {
+            self.produce_rate * self.degraded_throughput
+        } else {
+            self.produce_rate * self.catchup_factor
+        }
+    }
+
+    /// Batch processing slowdown while degraded
+    fn slowdown(&self) -> f64 {
+        if self.degraded() {
+            1.0 / self.degraded_throughput.max(0.01)
+        } else {
+            1.0
+        }
+    }
+
+    fn messaging_attrs(&self, partition: usize) -> Vec<KeyValue> {
+        vec![
+            KeyValue::string("messaging.system", "kafka"),
+            KeyValue::string("messaging.destination.name", self.topic.clone()),
+            KeyValue::string("messaging.consumer.group.name", self.consumer_group.clone()),
+            KeyValue::string("messaging.destination.partition.id", partition.to_string()),
+        ]
+    }
+}
+
+impl Scenario for ConsumerLag {
+    fn name(&self) -> &str {
+        "Consumer Lag"
+    }
+
+    fn targets(&self) -> Vec<String> {
+        vec![self.service_name.clone()]
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.produce_rate *= factor;
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("performance/consumer_lag", current_time_ns, delta_ns);
+        let seconds = delta_ns as f64 / 1_000_000_000.0;
+        let partitions = self.partitions.max(1);
+        let slowdown = self.slowdown();
+        let batch_latency = LogNormal::new(BATCH_LATENCY_MU, BATCH_LATENCY_SIGMA).unwrap();
+        let mut logs = Vec::new();
+
+        // Producers never slow down; the consumer can't take more than is there
+        let produced = self.produce_rate * seconds / partitions as f64;
+        let capacity = self.capacity() * seconds / partitions as f64;
+        let mut consumed_total = 0.0;
+        for lag in &mut self.lag {
+            let backlog = *lag + produced * rng.random_range(0.8..1.2);
+            let consumed = capacity.min(backlog);
+            *lag = backlog - consumed;
+            consumed_total += consumed;
+        }
+        self.consumed_rate = consumed_total / seconds.max(f64::EPSILON);
+
+        self.poll_credit += consumed_total / BATCH_SIZE;
+        let polls = self.poll_credit.floor();
+        self.poll_credit -= polls;
+        for _ in 0..polls as u64 {
+            let partition = rng.random_range(0..partitions);
+            let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+            let duration = batch_latency.sample(&mut rng) * slowdown;
+            self.offsets[partition] += BATCH_SIZE as u64;
+            let level = if duration > SLOW_BATCH_MS {
+                "WARN"
+            } else {
+                "INFO"
+            };
+            let mut attrs = self.messaging_attrs(partition);
+            attrs.push(KeyValue::int(
+                "messaging.batch.message_count",
+                BATCH_SIZE as i64,
+            ));
+            attrs.push(KeyValue::int(
+                "messaging.kafka.offset",
+                self.offsets[partition] as i64,
+            ));
+            attrs.push(KeyValue::double("process.duration_ms", duration));
+            logs.push(create_log(
+                level,
+                format!(
+                    "Processed batch of {} messages from {}[{}] in {:.0}ms",
+                    BATCH_SIZE, self.topic, partition, duration
+                ),
+                &self.service_name,
+                &trace_id,
+                &span_id,
+                current_time_ns,
+                attrs,
+            ));
+        }
+
+        self.elapsed_ns += delta_ns;
+        while self.next_report_ns < self.elapsed_ns {
+            self.next_report_ns += LAG_REPORT_INTERVAL_NS;
+            for partition in 0..self.lag.len() {
+                let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+                let lag = self.lag[partition].round();
+                let level = if lag > LAG_WARN_MESSAGES {
+                    "WARN"
+                } else {
+                    "INFO"
+                };
+                let mut attrs = self.messaging_attrs(partition);
+                attrs.push(KeyValue::int("messaging.kafka.consumer.lag", lag as i64));
+                logs.push(create_log(
+                    level,
+                    format!(
+                        "Consumer lag on {}[{}]: {} messages",
+                        self.topic, partition, lag
+                    ),
+                    &self.service_name,
+                    &trace_id,
+                    &span_id,
+                    current_time_ns,
+                    attrs,
+                ));
+            }
+        }
+
+        if !self.degraded() && !self.caught_up && self.total_lag() == 0.0 {
+            self.caught_up = true;
+            let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+            logs.push(create_log(
+                "INFO",
+                format!(
+                    "Consumer group {} caught up on {}",
+                    self.consumer_group, self.topic
+                ),
+                &self.service_name,
+                &trace_id,
+                &span_id,
+                current_time_ns,
+                vec![KeyValue::string("event", "consumer_caught_up")],
+            ));
+        }
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        let reports = self.partitions as f64 * 1_000_000_000.0 / LAG_REPORT_INTERVAL_NS as f64;
+        Some(
+            ProcessState::new(self.name(), reports + self.consumed_rate / BATCH_SIZE, 0.0)
+                .with_latency(LatencyModel::LogNormal {
+                    mu: BATCH_LATENCY_MU + self.slowdown().ln(),
+                    sigma: BATCH_LATENCY_SIGMA,
+                }),
+        )
+    }
 }
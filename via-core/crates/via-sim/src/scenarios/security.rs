@@ -1,6 +1,6 @@
-use crate::core::{AnyValue, KeyValue, LogRecord};
+use crate::core::{AnyValue, KeyValue, LatencyModel, LogRecord, ProcessState};
 use crate::scenarios::traffic::create_log;
-use crate::scenarios::{Scenario, next_trace_and_span_ids, rng_for_tick};
+use crate::scenarios::{Scenario, next_trace_and_span_ids, rng_for_init, rng_for_tick};
 use rand::prelude::*;
 
 // --- 1. Credential Stuffing / Brute Force ---
@@ -13,6 +13,10 @@ impl Scenario for CredentialStuffing {
         "Credential Stuffing"
     }
 
+    fn scale_rate(&mut self, factor: f64) {
+        self.attack_rps *= factor;
+    }
+
     fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
         let mut rng = rng_for_tick("security/credential_stuffing", current_time_ns, delta_ns);
         let seconds = delta_ns as f64 / 1_000_000_000.0;
@@ -83,6 +87,18 @@ impl Scenario for CredentialStuffing {
         }
         logs
     }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        // 99% of attempts fail with 401
+        Some(
+            ProcessState::new(self.name(), self.attack_rps, 0.99).with_latency(
+                LatencyModel::Uniform {
+                    min: 300.0,
+                    max: 1000.0,
+                },
+            ),
+        )
+    }
 }
 
 // --- 2. SQL Injection (SQLi) ---
@@ -95,6 +111,10 @@ impl Scenario for SqlInjection {
         "SQL Injection Probe"
     }
 
+    fn scale_rate(&mut self, factor: f64) {
+        self.attack_rps *= factor;
+    }
+
     fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
         let mut rng = rng_for_tick("security/sql_injection", current_time_ns, delta_ns);
         let seconds = delta_ns as f64 / 1_000_000_000.0;
@@ -137,6 +157,10 @@ impl Scenario for SqlInjection {
         }
         logs
     }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        Some(ProcessState::new(self.name(), self.attack_rps, 1.0))
+    }
 }
 
 // --- 3. Port Scanning ---
@@ -150,6 +174,10 @@ impl Scenario for PortScan {
         "Port Scan"
     }
 
+    fn scale_rate(&mut self, factor: f64) {
+        self.scan_speed *= factor;
+    }
+
     fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
         let mut rng = rng_for_tick("security/port_scan", current_time_ns, delta_ns);
         let seconds = delta_ns as f64 / 1_000_000_000.0;
@@ -187,4 +215,194 @@ impl Scenario for PortScan {
         }
         logs
     }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        Some(ProcessState::new(self.name(), self.scan_speed, 0.0))
+    }
+}
+
+// --- 4. Ransomware / File Encryption Burst ---
+
+/// Share directories the ransomware walks, in order
+const RANSOM_DIRS: [&str; 6] = [
+    "/srv/share/finance",
+    "/srv/share/hr",
+    "/srv/share/projects",
+    "/srv/share/legal",
+    "/srv/share/sales",
+    "/srv/share/backups",
+];
+/// Files encrypted per directory before moving on
+const RANSOM_FILES_PER_DIR: u64 = 400;
+const RANSOM_EXTENSIONS: [&str; 5] = ["docx", "xlsx", "pdf", "csv", "pptx"];
+const RANSOM_NOTE: &str = "README_RESTORE_FILES.txt";
+
+/// One host reading, encrypting and renaming files at an escalating rate.
+///
+/// Every file is three logs (read, encrypted write, rename to `.locked`);
+/// writes carry `file.entropy` near 8 bits/byte where the originals sit
+/// at 4-6. The run opens by deleting shadow copies and drops a ransom note
+/// in each directory it enters.
+pub struct Ransomware {
+    pub host: String,
+    /// Files encrypted per second when the burst starts
+    pub initial_files_per_sec: f64,
+    /// Rate multiplier per second of the burst
+    pub escalation: f64,
+    /// Rate the escalation levels off at
+    pub max_files_per_sec: f64,
+    pid: i64,
+    elapsed_ns: u64,
+    files_encrypted: u64,
+    current_fps: f64,
+}
+
+impl Ransomware {
+    pub fn new(host: &str, initial_files_per_sec: f64) -> Self {
+        let mut rng = rng_for_init("security/ransomware");
+        Self {
+            host: host.to_string(),
+            initial_files_per_sec,
+            escalation: 1.25,
+            max_files_per_sec: initial_files_per_sec * 100.0,
+            pid: rng.random_range(2_000..60_000),
+            elapsed_ns: 0,
+            files_encrypted: 0,
+            current_fps: initial_files_per_sec,
+        }
+    }
+
+    fn file_event(
+        &self,
+        action: &str,
+        body: String,
+        path: &str,
+        mut attrs: Vec<KeyValue>,
+        time_ns: u64,
+        rng: &mut StdRng,
+    ) -> LogRecord {
+        let (trace_id, span_id) = next_trace_and_span_ids(rng);
+        attrs.extend([
+            KeyValue::string("event.category", "file"),
+            KeyValue::string("event.action", action),
+            KeyValue::string("file.path", path),
+            KeyValue::string("host.name", self.host.clone()),
+            KeyValue::string("process.name", "svc_updater.exe"),
+            KeyValue::int("process.pid", self.pid),
+            KeyValue::string("user.name", "svc_backup"),
+        ]);
+        // Shadow-copy deletion and ransom notes stand out; file I/O does not
+        let level = if matches!(action, "process_start" | "file_create") {
+            "WARN"
+        } else {
+            "INFO"
+        };
+        create_log(level, body, &self.host, &trace_id, &span_id, time_ns, attrs)
+    }
+}
+
+impl Scenario for Ransomware {
+    fn name(&self) -> &str {
+        "Ransomware"
+    }
+
+    fn targets(&self) -> Vec<String> {
+        vec![self.host.clone()]
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.initial_files_per_sec *= factor;
+        self.max_files_per_sec *= factor;
+        self.current_fps *= factor;
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("security/ransomware", current_time_ns, delta_ns);
+        let mut logs = Vec::new();
+
+        if self.elapsed_ns == 0 {
+            logs.push(self.file_event(
+                "process_start",
+                "Process executed: vssadmin.exe delete shadows /all /quiet".to_string(),
+                "C:\\Windows\\System32\\vssadmin.exe",
+                vec![KeyValue::string(
+                    "process.command_line",
+                    "vssadmin.exe delete shadows /all /quiet",
+                )],
+                current_time_ns,
+                &mut rng,
+            ));
+        }
+
+        let elapsed_secs = self.elapsed_ns as f64 / 1_000_000_000.0;
+        self.current_fps = (self.initial_files_per_sec * self.escalation.powf(elapsed_secs))
+            .min(self.max_files_per_sec);
+        self.elapsed_ns += delta_ns;
+        let seconds = delta_ns as f64 / 1_000_000_000.0;
+        let count = (self.current_fps * seconds).round() as u64;
+
+        for _ in 0..count {
+            let n = self.files_encrypted;
+            self.files_encrypted += 1;
+            let dir_index = (n / RANSOM_FILES_PER_DIR) as usize % RANSOM_DIRS.len();
+            let dir = RANSOM_DIRS[dir_index];
+            if n.is_multiple_of(RANSOM_FILES_PER_DIR) {
+                let note = format!("{}/{}", dir, RANSOM_NOTE);
+                logs.push(self.file_event(
+                    "file_create",
+                    format!("File created: {}", note),
+                    &note,
+                    vec![KeyValue::string("file.extension", "txt")],
+                    current_time_ns,
+                    &mut rng,
+                ));
+            }
+
+            let ext = *RANSOM_EXTENSIONS.choose(&mut rng).unwrap();
+            let path = format!("{}/doc_{:05}.{}", dir, n % RANSOM_FILES_PER_DIR, ext);
+            let size = rng.random_range(8_000..4_000_000_i64);
+            logs.push(self.file_event(
+                "file_read",
+                format!("File read: {}", path),
+                &path,
+                vec![
+                    KeyValue::string("file.extension", ext),
+                    KeyValue::int("file.size", size),
+                    KeyValue::double("file.entropy", rng.random_range(4.0..6.0)),
+                ],
+                current_time_ns,
+                &mut rng,
+            ));
+            logs.push(self.file_event(
+                "file_write",
+                format!("File written: {}", path),
+                &path,
+                vec![
+                    KeyValue::string("file.extension", ext),
+                    KeyValue::int("file.size", size + 512),
+                    KeyValue::double("file.entropy", rng.random_range(7.9..8.0)),
+                ],
+                current_time_ns,
+                &mut rng,
+            ));
+            let locked = format!("{}.locked", path);
+            logs.push(self.file_event(
+                "file_rename",
+                format!("File renamed: {} -> {}", path, locked),
+                &path,
+                vec![
+                    KeyValue::string("file.extension", "locked"),
+                    KeyValue::string("file.target_path", locked),
+                ],
+                current_time_ns,
+                &mut rng,
+            ));
+        }
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        // Read, write and rename per file; nothing fails
+        Some(ProcessState::new(self.name(), self.current_fps * 3.0, 0.0))
+    }
 }
@@ -0,0 +1,390 @@
+//! User Session Scenarios
+//!
+//! A population of users, each with a home country, a device and a client
+//! IP, who come back in sessions: log in, browse a few pages with think time
+//! in between, sometimes buy, then log out or let the session expire. A few
+//! users leave for good after each session and new sign-ups replace them, so
+//! `user.id`, `session.id` and the trace ids (one per session) have the
+//! cardinality and turnover of a real user base rather than one random id
+//! per log.
+//!
+//! - **UserSessions**: session traffic from a churning user base
+
+use crate::core::{KeyValue, LatencyModel, LogRecord, ProcessState};
+use crate::scenarios::traffic::create_log;
+use crate::scenarios::{Scenario, next_trace_and_span_ids, rng_for_init, rng_for_tick};
+use rand::prelude::*;
+use rand_distr::{Distribution, Exp, LogNormal, Normal};
+
+/// `(device.type, share)`
+const DEVICES: [(&str, f64); 3] = [("mobile", 0.55), ("desktop", 0.38), ("tablet", 0.07)];
+/// `(geo.country, share)`
+const COUNTRIES: [(&str, f64); 6] = [
+    ("US", 0.35),
+    ("DE", 0.15),
+    ("GB", 0.15),
+    ("IN", 0.15),
+    ("BR", 0.1),
+    ("JP", 0.1),
+];
+/// Pages a browsing user picks from
+const PAGES: [&str; 4] = ["/products", "/products/{id}", "/search", "/recommendations"];
+
+/// Share of requests failing server-side
+const SESSION_ERROR_RATE: f64 = 0.005;
+/// Share of logins rejected once (wrong password) before succeeding
+const LOGIN_RETRY_RATE: f64 = 0.03;
+/// Request latency (ms) is LogNormal(mu, sigma), ~45ms median
+const SESSION_LATENCY_MU: f64 = 3.8;
+const SESSION_LATENCY_SIGMA: f64 = 0.5;
+/// Share of sessions that end with an explicit logout rather than expiring
+const LOGOUT_RATE: f64 = 0.6;
+
+/// Shape of the user base
+#[derive(Debug, Clone)]
+pub struct UserBase {
+    /// Registered users at any time
+    pub users: usize,
+    /// New sessions started per second
+    pub sessions_per_sec: f64,
+    /// Mean pages viewed per session
+    pub mean_pages: f64,
+    /// Mean time between two actions of a session
+    pub think_time_ns: u64,
+    /// Share of sessions ending in a purchase
+    pub purchase_rate: f64,
+    /// Chance a user never returns after a session
+    pub churn_rate: f64,
+}
+
+impl Default for UserBase {
+    fn default() -> Self {
+        Self::new(5_000, 5.0, 6.0, 3_000, 0.08, 0.02)
+    }
+}
+
+impl UserBase {
+    pub fn new(
+        users: usize,
+        sessions_per_sec: f64,
+        mean_pages: f64,
+        think_time_ms: u64,
+        purchase_rate: f64,
+        churn_rate: f64,
+    ) -> Self {
+        Self {
+            users: users.max(1),
+            sessions_per_sec,
+            mean_pages: mean_pages.max(0.0),
+            think_time_ns: think_time_ms.max(1) * 1_000_000,
+            purchase_rate: purchase_rate.clamp(0.0, 1.0),
+            churn_rate: churn_rate.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn user_id(id: u64) -> String {
+        format!("user-{:07}", id)
+    }
+
+    /// Mean logs per session: login, pages, purchase, logout or expiry
+    pub fn logs_per_session(&self) -> f64 {
+        2.0 + LOGIN_RETRY_RATE + self.mean_pages.max(1.0) + self.purchase_rate
+    }
+}
+
+/// A registered user
+#[derive(Debug, Clone)]
+struct User {
+    id: u64,
+    device: &'static str,
+    country: &'static str,
+    ip: String,
+}
+
+impl User {
+    fn new(id: u64, rng: &mut StdRng) -> Self {
+        Self {
+            id,
+            device: pick(&DEVICES, rng),
+            country: pick(&COUNTRIES, rng),
+            ip: format!(
+                "{}.{}.{}.{}",
+                rng.random_range(11..223),
+                rng.random_range(0..255),
+                rng.random_range(0..255),
+                rng.random_range(1..255)
+            ),
+        }
+    }
+}
+
+fn pick(weighted: &[(&'static str, f64)], rng: &mut StdRng) -> &'static str {
+    weighted
+        .choose_weighted(rng, |(_, share)| *share)
+        .map_or(weighted[0].0, |(name, _)| name)
+}
+
+/// Where a session is in its lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Login,
+    Browse { pages_left: u32 },
+    Purchase,
+    Logout,
+    Expire,
+}
+
+/// One user's visit
+#[derive(Debug, Clone)]
+struct Session {
+    /// Slot of the user in the pool
+    slot: usize,
+    user: User,
+    session_id: String,
+    trace_id: String,
+    stage: Stage,
+    next_action_ns: u64,
+    purchases: bool,
+}
+
+// ============================================================================
+// User Sessions (baseline)
+// ============================================================================
+
+/// Session traffic from a user base with churn
+pub struct UserSessions {
+    pub user_base: UserBase,
+    users: Vec<User>,
+    next_user_id: u64,
+    sessions: Vec<Session>,
+    /// Jittered session rate drawn for the most recent tick
+    current_sps: f64,
+}
+
+impl UserSessions {
+    pub fn new(user_base: UserBase) -> Self {
+        let mut rng = rng_for_init("sessions/users");
+        let users: Vec<User> = (0..user_base.users as u64)
+            .map(|id| User::new(id, &mut rng))
+            .collect();
+        Self {
+            next_user_id: users.len() as u64,
+            users,
+            current_sps: user_base.sessions_per_sec,
+            user_base,
+            sessions: Vec::new(),
+        }
+    }
+
+    /// Registered users right now
+    pub fn users(&self) -> usize {
+        self.users.len()
+    }
+
+    /// Sessions in progress
+    pub fn active_sessions(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Pick a returning user; low slots are the regulars and come back most
+    fn pick_slot(&self, rng: &mut StdRng) -> usize {
+        let u: f64 = rng.random();
+        ((u * u * self.users.len() as f64) as usize).min(self.users.len() - 1)
+    }
+
+    fn start_session(&self, rng: &mut StdRng, now_ns: u64) -> Session {
+        let (trace_id, session_id) = next_trace_and_span_ids(rng);
+        let slot = self.pick_slot(rng);
+        Session {
+            slot,
+            user: self.users[slot].clone(),
+            session_id,
+            trace_id,
+            stage: Stage::Login,
+            next_action_ns: now_ns,
+            purchases: rng.random_bool(self.user_base.purchase_rate),
+        }
+    }
+
+    /// Stage after `stage`, drawing the page count on login
+    fn advance(&self, session: &Session, rng: &mut StdRng) -> Option<Stage> {
+        match session.stage {
+            Stage::Login => {
+                let mean = self.user_base.mean_pages;
+                let pages = Exp::new(1.0 / mean.max(f64::MIN_POSITIVE))
+                    .map_or(0.0, |d| d.sample(rng))
+                    .round() as u32;
+                Some(Stage::Browse {
+                    pages_left: pages.max(1),
+                })
+            }
+            Stage::Browse { pages_left } if pages_left > 1 => Some(Stage::Browse {
+                pages_left: pages_left - 1,
+            }),
+            Stage::Browse { .. } if session.purchases => Some(Stage::Purchase),
+            Stage::Browse { .. } | Stage::Purchase => Some(if rng.random_bool(LOGOUT_RATE) {
+                Stage::Logout
+            } else {
+                Stage::Expire
+            }),
+            Stage::Logout | Stage::Expire => None,
+        }
+    }
+
+    /// Logs of `session`'s current action
+    fn act(session: &Session, rng: &mut StdRng, time_ns: u64) -> Vec<LogRecord> {
+        let user = &session.user;
+        let latency = LogNormal::new(SESSION_LATENCY_MU, SESSION_LATENCY_SIGMA)
+            .unwrap()
+            .sample(rng);
+        let failed = rng.random_bool(SESSION_ERROR_RATE);
+
+        let (service, route, body) = match session.stage {
+            Stage::Login => ("auth-service", "/login", "User logged in".to_string()),
+            Stage::Browse { .. } => {
+                let page = *PAGES.choose(rng).unwrap();
+                let service = if page == "/recommendations" {
+                    "recommendation-engine"
+                } else {
+                    "inventory-service"
+                };
+                (service, page, format!("Served {}", page))
+            }
+            Stage::Purchase => ("payment-service", "/checkout", "Order placed".to_string()),
+            Stage::Logout => ("auth-service", "/logout", "User logged out".to_string()),
+            Stage::Expire => (
+                "auth-service",
+                "/session",
+                "Session expired after inactivity".to_string(),
+            ),
+        };
+
+        let mut logs = Vec::new();
+        let mut request = |level: &str, status: i64, body: String, rng: &mut StdRng| {
+            let (_, span_id) = next_trace_and_span_ids(rng);
+            let mut attrs = vec![
+                KeyValue::string("user.id", UserBase::user_id(user.id)),
+                KeyValue::string("session.id", session.session_id.clone()),
+                KeyValue::string("device.type", user.device),
+                KeyValue::string("geo.country", user.country),
+                KeyValue::string("net.peer.ip", user.ip.clone()),
+                KeyValue::string("http.route", route),
+                KeyValue::int("http.status_code", status),
+                KeyValue::double("http.duration_ms", latency),
+            ];
+            if session.stage == Stage::Purchase && status == 200 {
+                attrs.push(KeyValue::double(
+                    "order.amount",
+                    (rng.random_range(5.0..250.0_f64) * 100.0).round() / 100.0,
+                ));
+            }
+            logs.push(create_log(
+                level,
+                body,
+                service,
+                &session.trace_id,
+                &span_id,
+                time_ns,
+                attrs,
+            ));
+        };
+
+        if session.stage == Stage::Login && rng.random_bool(LOGIN_RETRY_RATE) {
+            request(
+                "WARN",
+                401,
+                "Login failed: invalid password".to_string(),
+                rng,
+            );
+        }
+        if failed {
+            request("ERROR", 500, format!("{} failed", route), rng);
+        } else {
+            request("INFO", 200, body, rng);
+        }
+        logs
+    }
+
+    /// Replace the user in `slot` by a new sign-up
+    fn churn(&mut self, slot: usize, rng: &mut StdRng) {
+        self.users[slot] = User::new(self.next_user_id, rng);
+        self.next_user_id += 1;
+    }
+}
+
+impl Scenario for UserSessions {
+    fn name(&self) -> &str {
+        "User Sessions"
+    }
+
+    fn is_baseline(&self) -> bool {
+        true
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.user_base.sessions_per_sec *= factor;
+        self.current_sps *= factor;
+    }
+
+    fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
+        let mut rng = rng_for_tick("sessions/baseline", current_time_ns, delta_ns);
+        let seconds = delta_ns as f64 / 1_000_000_000.0;
+        let end_ns = current_time_ns + delta_ns;
+
+        let rate = self.user_base.sessions_per_sec;
+        self.current_sps = Normal::new(rate, rate * 0.1)
+            .map_or(rate, |d| d.sample(&mut rng))
+            .max(0.0);
+        let arrivals = (self.current_sps * seconds).round() as usize;
+        for _ in 0..arrivals {
+            let offset = rng.random_range(0..delta_ns.max(1));
+            let session = self.start_session(&mut rng, current_time_ns + offset);
+            self.sessions.push(session);
+        }
+
+        let think = Exp::new(1.0 / self.user_base.think_time_ns as f64).unwrap();
+        let mut logs = Vec::new();
+        let mut sessions = std::mem::take(&mut self.sessions);
+        let mut churned = Vec::new();
+        sessions.retain_mut(|session| {
+            while session.next_action_ns < end_ns {
+                logs.extend(Self::act(session, &mut rng, current_time_ns));
+                match self.advance(session, &mut rng) {
+                    Some(stage) => {
+                        session.stage = stage;
+                        session.next_action_ns += think.sample(&mut rng).max(1.0) as u64;
+                    }
+                    None => {
+                        if rng.random_bool(self.user_base.churn_rate) {
+                            churned.push((session.slot, session.user.id));
+                        }
+                        return false;
+                    }
+                }
+            }
+            true
+        });
+        self.sessions = sessions;
+        for (slot, user_id) in churned {
+            // Another session of the same user may have churned it already
+            if self.users[slot].id == user_id {
+                self.churn(slot, &mut rng);
+            }
+        }
+        logs
+    }
+
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        Some(
+            ProcessState::new(
+                self.name(),
+                self.current_sps * self.user_base.logs_per_session(),
+                SESSION_ERROR_RATE,
+            )
+            .with_latency(LatencyModel::LogNormal {
+                mu: SESSION_LATENCY_MU,
+                sigma: SESSION_LATENCY_SIGMA,
+            }),
+        )
+    }
+}
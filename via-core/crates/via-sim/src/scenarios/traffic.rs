@@ -1,7 +1,8 @@
-use crate::core::{AnyValue, KeyValue, LogRecord};
-use crate::scenarios::{Scenario, next_trace_and_span_ids, rng_for_tick};
+use crate::core::{AnyValue, KeyValue, LatencyModel, LogRecord, ProcessState};
+use crate::scenarios::{Scenario, rng_for_tick};
+use crate::seasonality::{Burstiness, Seasonality};
+use crate::topology::{Hop, Topology};
 use rand::prelude::*;
-use rand_distr::{Distribution, LogNormal, Normal};
 
 // Shared helper for creating logs to reduce duplication
 pub fn create_log(
@@ -40,25 +41,54 @@ pub fn create_log(
     }
 }
 
+/// Baseline requests walked through a service topology: one log per hop,
+/// hops of a request sharing a trace id. `logs_per_sec` is the mean rate;
+/// the rate at a given time follows `seasonality`.
 pub struct NormalTraffic {
     pub logs_per_sec: f64,
-    pub services: Vec<String>,
+    pub topology: Topology,
+    /// Daily / weekly load curve and holidays (flat by default)
+    pub seasonality: Seasonality,
+    /// Spread of each tick's arrivals around the seasonal rate
+    pub burstiness: Burstiness,
+    /// Rate drawn for the most recent tick
+    current_rps: f64,
+    /// Logs written past the previous tick's budget by its last request
+    overshoot: usize,
 }
 
+/// Regions baseline requests are served from, as `cloud.region`
+pub const REGIONS: [&str; 3] = ["us-east-1", "us-west-2", "eu-west-1"];
+
+const NORMAL_ERROR_RATE: f64 = 0.01;
+const NORMAL_LATENCY_MU: f64 = 4.0;
+const NORMAL_LATENCY_SIGMA: f64 = 0.5;
+
 impl NormalTraffic {
     pub fn new(logs_per_sec: f64) -> Self {
+        Self::with_topology(logs_per_sec, Topology::default())
+    }
+
+    pub fn with_topology(logs_per_sec: f64, topology: Topology) -> Self {
         Self {
             logs_per_sec,
-            services: vec![
-                "auth-service".to_string(),
-                "payment-service".to_string(),
-                "api-gateway".to_string(),
-                "db-cluster".to_string(),
-                "inventory-service".to_string(),
-                "recommendation-engine".to_string(),
-            ],
+            topology,
+            seasonality: Seasonality::default(),
+            burstiness: Burstiness::default(),
+            current_rps: logs_per_sec,
+            overshoot: 0,
         }
     }
+
+    pub fn with_seasonality(mut self, seasonality: Seasonality) -> Self {
+        self.seasonality = seasonality;
+        self
+    }
+
+    pub fn with_burstiness(mut self, burstiness: Burstiness) -> Self {
+        self.burstiness = burstiness;
+        self
+    }
 }
 
 impl Scenario for NormalTraffic {
@@ -66,69 +96,167 @@ impl Scenario for NormalTraffic {
         "Normal Traffic"
     }
 
+    fn is_baseline(&self) -> bool {
+        true
+    }
+
+    fn scale_rate(&mut self, factor: f64) {
+        self.logs_per_sec *= factor;
+        self.current_rps *= factor;
+    }
+
     fn tick(&mut self, current_time_ns: u64, delta_ns: u64) -> Vec<LogRecord> {
         let mut rng = rng_for_tick("traffic/normal", current_time_ns, delta_ns);
         let seconds = delta_ns as f64 / 1_000_000_000.0;
 
-        // Add some jitter to the volume (Poisson-like)
-        let vol_dist = Normal::new(self.logs_per_sec, self.logs_per_sec * 0.1).unwrap();
-        let count = (vol_dist.sample(&mut rng) * seconds).max(0.0).round() as u64;
+        let rate = self.logs_per_sec * self.seasonality.factor(current_time_ns);
+        let (rps, count) = self.burstiness.arrivals(rate, seconds, &mut rng);
+        self.current_rps = rps;
+        let budget = count.saturating_sub(self.overshoot);
+        self.overshoot -= count.min(self.overshoot);
 
-        let mut logs = Vec::new();
+        // Whole requests until the tick's log budget is spent
+        let mut logs = Vec::with_capacity(count);
+        while logs.len() < budget {
+            let request = self.topology.request(&mut rng);
+            let client_ip = format!(
+                "10.0.{}.{}",
+                rng.random_range(0..255),
+                rng.random_range(0..255)
+            );
+            for hop in &request.hops {
+                logs.push(hop_log(&request.trace_id, hop, &client_ip, current_time_ns));
+            }
+        }
+        self.overshoot += logs.len().saturating_sub(budget);
+        logs
+    }
 
-        for _ in 0..count {
-            let service = self.services.choose(&mut rng).unwrap();
-            let (trace_id, span_id) = next_trace_and_span_ids(&mut rng);
+    fn process_state(&self, _delta_ns: u64) -> Option<ProcessState> {
+        Some(
+            ProcessState::new(self.name(), self.current_rps, NORMAL_ERROR_RATE).with_latency(
+                LatencyModel::LogNormal {
+                    mu: NORMAL_LATENCY_MU,
+                    sigma: NORMAL_LATENCY_SIGMA,
+                },
+            ),
+        )
+    }
+}
 
-            // LogNormal for realistic latency tail
-            let latency_dist = LogNormal::new(4.0, 0.5).unwrap(); // ~55ms mean, but with tail
-            let latency = latency_dist.sample(&mut rng) as i64;
+/// Region serving the request with `trace_id`; spreads requests evenly
+/// without drawing from the tick's RNG
+pub fn region_of(trace_id: &str) -> &'static str {
+    REGIONS[(xxhash_rust::xxh3::xxh3_64(trace_id.as_bytes()) % REGIONS.len() as u64) as usize]
+}
 
-            let status_code = if rng.random_bool(0.99) { 200 } else { 500 };
-            let level = if status_code == 200 { "INFO" } else { "ERROR" };
+/// Log written by the service of `hop` when its call completes
+pub(crate) fn hop_log(trace_id: &str, hop: &Hop, client_ip: &str, time_ns: u64) -> LogRecord {
+    let latency = hop.latency_ms.round() as i64;
+    let status_code = hop.error.map_or(200, |e| e.status_code());
+    let level = if hop.error.is_none() { "INFO" } else { "ERROR" };
 
-            let mut attrs = vec![
-                KeyValue {
-                    key: "http.method".to_string(),
-                    value: AnyValue::string("GET"),
-                },
-                KeyValue {
-                    key: "http.status_code".to_string(),
-                    value: AnyValue::int(status_code),
-                },
-                KeyValue {
-                    key: "http.duration_ms".to_string(),
-                    value: AnyValue::int(latency),
-                },
-                KeyValue {
-                    key: "net.peer.ip".to_string(),
-                    value: AnyValue::string(format!(
-                        "10.0.{}.{}",
-                        rng.random_range(0..255),
-                        rng.random_range(0..255)
-                    )),
-                },
-            ];
+    let mut attrs = vec![
+        KeyValue {
+            key: "http.method".to_string(),
+            value: AnyValue::string("GET"),
+        },
+        KeyValue {
+            key: "http.status_code".to_string(),
+            value: AnyValue::int(status_code),
+        },
+        KeyValue {
+            key: "http.duration_ms".to_string(),
+            value: AnyValue::int(latency),
+        },
+        KeyValue {
+            key: "net.peer.ip".to_string(),
+            value: AnyValue::string(client_ip),
+        },
+        KeyValue::string("cloud.region", region_of(trace_id)),
+    ];
+    if let Some(caller) = &hop.caller {
+        attrs.push(KeyValue {
+            key: "peer.service".to_string(),
+            value: AnyValue::string(caller.clone()),
+        });
+    }
+    if let Some(parent) = &hop.parent_span_id {
+        attrs.push(KeyValue {
+            key: "parent.span_id".to_string(),
+            value: AnyValue::string(parent.clone()),
+        });
+    }
+    if let Some(error) = hop.error {
+        attrs.push(KeyValue {
+            key: "error.type".to_string(),
+            value: AnyValue::string(error.error_type()),
+        });
+    }
 
-            if status_code == 500 {
-                attrs.push(KeyValue {
-                    key: "error.type".to_string(),
-                    value: AnyValue::string("InternalServerError"),
-                });
-            }
+    create_log(
+        level,
+        format!("Request processed in {}ms", latency),
+        &hop.service,
+        trace_id,
+        &hop.span_id,
+        time_ns,
+        attrs,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            let body = format!("Request processed in {}ms", latency);
-
-            logs.push(create_log(
-                level,
-                body,
-                service,
-                &trace_id,
-                &span_id,
-                current_time_ns,
-                attrs,
-            ));
+    const HOUR_NS: u64 = 3_600 * 1_000_000_000;
+    const DAY_NS: u64 = 24 * HOUR_NS;
+
+    /// Mean logs per second over a minute of 1s ticks from `start_ns`
+    fn mean_rate(traffic: &mut NormalTraffic, start_ns: u64) -> f64 {
+        let ticks = 60;
+        let logs: usize = (0..ticks)
+            .map(|i| {
+                traffic
+                    .tick(start_ns + i * 1_000_000_000, 1_000_000_000)
+                    .len()
+            })
+            .sum();
+        logs as f64 / ticks as f64
+    }
+
+    #[test]
+    fn test_rate_follows_daily_and_weekly_curves() {
+        let mut traffic = NormalTraffic::new(100.0).with_seasonality(Seasonality::business());
+        // Day 6 since the epoch is a Wednesday, day 9 a Saturday
+        let peak = mean_rate(&mut traffic, 6 * DAY_NS + 14 * HOUR_NS);
+        let night = mean_rate(&mut traffic, 6 * DAY_NS + 2 * HOUR_NS);
+        let saturday = mean_rate(&mut traffic, 9 * DAY_NS + 14 * HOUR_NS);
+
+        assert!((peak / 176.0 - 1.0).abs() < 0.05, "{}", peak);
+        assert!((night / 44.0 - 1.0).abs() < 0.05, "{}", night);
+        assert!((saturday / 120.0 - 1.0).abs() < 0.05, "{}", saturday);
+        assert!((traffic.current_rps / 120.0 - 1.0).abs() < 0.3);
+    }
+
+    #[test]
+    fn test_default_traffic_is_flat() {
+        let mut traffic = NormalTraffic::new(100.0);
+        for start_ns in [
+            6 * DAY_NS + 14 * HOUR_NS,
+            6 * DAY_NS + 2 * HOUR_NS,
+            9 * DAY_NS,
+        ] {
+            let rate = mean_rate(&mut traffic, start_ns);
+            assert!((rate / 100.0 - 1.0).abs() < 0.05, "{}", rate);
         }
-        logs
+    }
+
+    #[test]
+    fn test_holidays_dip() {
+        let mut traffic =
+            NormalTraffic::new(100.0).with_seasonality(Seasonality::business().with_holidays([6]));
+        let holiday = mean_rate(&mut traffic, 6 * DAY_NS + 14 * HOUR_NS);
+        assert!((holiday / 64.0 - 1.0).abs() < 0.05, "{}", holiday);
     }
 }
@@ -0,0 +1,255 @@
+//! Recurring Anomaly Schedules
+//!
+//! Soak tests run for hours with the same anomaly coming back again and
+//! again. A [`Recurrence`] expands one anomaly into every instance of a run
+//! up front, so each instance gets its own anomaly id and ground-truth
+//! window:
+//!
+//! - `Every`: a fixed period from the first start (every 10 minutes);
+//! - `Cron`: each minute matching a five-field cron expression, read off the
+//!   simulated clock in UTC (deterministic runs start at the Unix epoch);
+//! - `Budget`: non-overlapping instances placed at random so they cover a
+//!   target fraction of the run.
+
+use rand::prelude::*;
+use std::str::FromStr;
+
+/// Instances one recurring anomaly expands to, at most
+pub const MAX_OCCURRENCES: usize = 10_000;
+
+const NS_PER_SEC: u64 = 1_000_000_000;
+const NS_PER_MIN: u64 = 60 * NS_PER_SEC;
+
+/// How a recurring anomaly repeats
+#[derive(Debug, Clone, PartialEq)]
+pub enum Recurrence {
+    /// Every `period_ns` from the first start
+    Every { period_ns: u64 },
+    /// At each matching minute
+    Cron(CronSchedule),
+    /// Randomly placed, covering `rate` of the run
+    Budget { rate: f64 },
+}
+
+impl Recurrence {
+    /// Start offsets from `now_ns` of the instances, each `duration_ns`
+    /// long, that begin between `first_ns` and `horizon_ns`
+    pub fn starts(
+        &self,
+        now_ns: u64,
+        first_ns: u64,
+        duration_ns: u64,
+        horizon_ns: u64,
+        seed: u64,
+    ) -> Vec<u64> {
+        match self {
+            Recurrence::Every { period_ns } => (first_ns..horizon_ns)
+                .step_by((*period_ns).max(1) as usize)
+                .take(MAX_OCCURRENCES)
+                .collect(),
+            Recurrence::Cron(cron) => {
+                // First whole minute of the simulated clock at or after `first_ns`
+                let first_minute = (now_ns + first_ns).div_ceil(NS_PER_MIN) * NS_PER_MIN;
+                (first_minute..now_ns + horizon_ns)
+                    .step_by(NS_PER_MIN as usize)
+                    .filter(|&t| cron.matches(t / NS_PER_SEC))
+                    .map(|t| t - now_ns)
+                    .take(MAX_OCCURRENCES)
+                    .collect()
+            }
+            Recurrence::Budget { rate } => {
+                let span = horizon_ns.saturating_sub(first_ns);
+                if duration_ns == 0 || span < duration_ns {
+                    return Vec::new();
+                }
+                let fits = span / duration_ns;
+                let wanted = (rate.clamp(0.0, 1.0) * span as f64 / duration_ns as f64).round();
+                let count = (wanted as u64).min(fits).min(MAX_OCCURRENCES as u64);
+                // Spread the idle time as `count + 1` random gaps
+                let idle = span - count * duration_ns;
+                let mut rng = StdRng::seed_from_u64(seed);
+                let mut cuts: Vec<u64> = (0..count).map(|_| rng.random_range(0..=idle)).collect();
+                cuts.sort_unstable();
+                cuts.iter()
+                    .zip(0..)
+                    .map(|(&cut, i)| first_ns + cut + i * duration_ns)
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Five-field cron expression: minute, hour, day of month, month, day of
+/// week (0 or 7 = Sunday)
+///
+/// Fields take `*`, numbers, ranges `a-b`, steps `*/n` or `a-b/n`, and
+/// comma-separated lists of those. As in cron, a time matches when day of
+/// month or day of week does if both are restricted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    /// Whether the minute containing `unix_secs` matches
+    pub fn matches(&self, unix_secs: u64) -> bool {
+        use chrono::{Datelike, Timelike};
+        let Some(time) = chrono::DateTime::from_timestamp(unix_secs as i64, 0) else {
+            return false;
+        };
+        let has = |set: u64, n: u32| set & (1 << n) != 0;
+        let day = has(self.days, time.day());
+        let weekday = has(self.weekdays, time.weekday().num_days_from_sunday());
+        let day_matches = match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        };
+        has(self.minutes, time.minute())
+            && has(self.hours, time.hour())
+            && has(self.months, time.month())
+            && day_matches
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("cron '{}': expected 5 fields", s));
+        };
+        let field = |text: &str, min: u32, max: u32| {
+            parse_field(text, min, max).map_err(|e| format!("cron '{}': {}", s, e))
+        };
+        let mut weekdays = field(weekday, 0, 7)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+        Ok(Self {
+            minutes: field(minute, 0, 59)?,
+            hours: field(hour, 0, 23)?,
+            days: field(day, 1, 31)?,
+            months: field(month, 1, 12)?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+}
+
+/// Bit set of the values a cron field allows
+fn parse_field(text: &str, min: u32, max: u32) -> Result<u64, String> {
+    let number = |n: &str| {
+        n.parse::<u32>()
+            .ok()
+            .filter(|v| (min..=max).contains(v))
+            .ok_or_else(|| format!("'{}' is not in {}-{}", n, min, max))
+    };
+    let mut set = 0u64;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("bad step in '{}'", part)),
+            },
+            None => (part, 1),
+        };
+        let (from, to) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((from, to)) => (number(from)?, number(to)?),
+                None => {
+                    let n = number(range)?;
+                    // `n/step` runs from n to the end of the field
+                    (n, if part.contains('/') { max } else { n })
+                }
+            },
+        };
+        if from > to {
+            return Err(format!("empty range '{}'", range));
+        }
+        for value in (from..=to).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_and_cron_expand_over_the_run() {
+        let hour = 60 * NS_PER_MIN;
+        let every = Recurrence::Every {
+            period_ns: 10 * NS_PER_MIN,
+        };
+        let starts = every.starts(0, NS_PER_MIN, NS_PER_MIN, hour, 0);
+        assert_eq!(starts.len(), 6);
+        assert_eq!(starts[1], 11 * NS_PER_MIN);
+
+        // Half past every hour, from a clock 10 minutes past midnight
+        let cron: CronSchedule = "30 * * * *".parse().unwrap();
+        let now = 10 * NS_PER_MIN;
+        let starts = Recurrence::Cron(cron).starts(now, 0, NS_PER_MIN, 3 * hour, 0);
+        assert_eq!(starts, [20 * NS_PER_MIN, 80 * NS_PER_MIN, 140 * NS_PER_MIN]);
+
+        // 1970-01-01 was a Thursday; dom and dow match either way
+        let thursdays: CronSchedule = "0 0 15 * 4".parse().unwrap();
+        assert!(thursdays.matches(0));
+        assert!(thursdays.matches(14 * 86_400));
+        assert!(!thursdays.matches(86_400));
+        let steps: CronSchedule = "*/15 9-17/4 * * 1-5,7".parse().unwrap();
+        assert!(steps.matches(4 * 86_400 + 13 * 3_600 + 45 * 60));
+        assert!("* * *".parse::<CronSchedule>().is_err());
+        assert!("61 * * * *".parse::<CronSchedule>().is_err());
+        assert!("*/0 * * * *".parse::<CronSchedule>().is_err());
+    }
+
+    #[test]
+    fn test_budget_places_disjoint_instances_at_the_rate() {
+        let run = 24 * 60 * NS_PER_MIN;
+        let budget = Recurrence::Budget { rate: 0.05 };
+        let starts = budget.starts(0, 0, NS_PER_MIN, run, 9);
+        assert_eq!(starts.len(), 72);
+        assert!(starts.windows(2).all(|w| w[1] >= w[0] + NS_PER_MIN));
+        assert!(starts.last().unwrap() + NS_PER_MIN <= run);
+        assert_eq!(starts, budget.starts(0, 0, NS_PER_MIN, run, 9));
+        assert_ne!(starts, budget.starts(0, 0, NS_PER_MIN, run, 10));
+
+        let full = Recurrence::Budget { rate: 1.0 }.starts(0, 0, NS_PER_MIN, 10 * NS_PER_MIN, 1);
+        assert_eq!(full.len(), 10);
+    }
+
+    #[test]
+    fn test_engine_records_every_instance() {
+        let mut engine = crate::SimulationEngine::new_deterministic(5);
+        engine.start("normal_traffic");
+        let every = Recurrence::Every {
+            period_ns: NS_PER_SEC,
+        };
+        let ids = engine.schedule_recurring("cpu_spike", &every, 0, NS_PER_SEC / 2, 3 * NS_PER_SEC);
+        assert_eq!(ids.len(), 3);
+        assert!(
+            engine
+                .schedule_recurring("nope", &every, 0, 1, NS_PER_SEC)
+                .is_empty()
+        );
+
+        let mut ground_truth = Vec::new();
+        for _ in 0..40 {
+            ground_truth = engine.tick(100_000_000).ground_truth;
+        }
+        let mut windows: Vec<_> = ground_truth.iter().map(|gt| gt.start_time_ns).collect();
+        windows.sort_unstable();
+        assert_eq!(windows, [0, NS_PER_SEC, 2 * NS_PER_SEC]);
+    }
+}
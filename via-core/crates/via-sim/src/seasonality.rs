@@ -0,0 +1,193 @@
+//! Traffic Seasonality and Burstiness
+//!
+//! Production request rates follow the clock: a daily curve with quiet
+//! nights and an afternoon peak, a weekly one with lighter weekends, and
+//! dips on holidays. Within a tick, arrivals are bursty rather than evenly
+//! spread. [`Seasonality`] gives the rate multiplier at a simulated UTC
+//! timestamp and [`Burstiness`] draws a tick's arrivals around that rate.
+//!
+//! Both default to flat traffic with a 10% jitter, which is what
+//! `NormalTraffic` generates unless configured otherwise. The
+//! `seasonal_traffic` scenario uses [`Seasonality::business`] with Pareto
+//! bursts. Deterministic runs start at the Unix epoch, a Thursday midnight.
+
+use rand::Rng;
+use rand_distr::{Distribution, Normal, Pareto, Poisson};
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Rate multipliers by time of day, day of week and holiday
+#[derive(Debug, Clone, PartialEq)]
+pub struct Seasonality {
+    /// Multiplier at the start of each UTC hour, interpolated linearly
+    /// within the hour
+    pub daily: [f64; 24],
+    /// Multiplier for each weekday, Monday first
+    pub weekly: [f64; 7],
+    /// Holidays as days since the Unix epoch (day 0 is 1970-01-01)
+    pub holidays: Vec<u64>,
+    /// Multiplier on holidays, in place of the weekday's
+    pub holiday_factor: f64,
+}
+
+impl Default for Seasonality {
+    fn default() -> Self {
+        Self {
+            daily: [1.0; 24],
+            weekly: [1.0; 7],
+            holidays: Vec::new(),
+            holiday_factor: 0.4,
+        }
+    }
+}
+
+impl Seasonality {
+    /// Office-hours load averaging 1 over a week: a daily cosine peaking at
+    /// 1.6 at 14:00 UTC with a 0.4 trough at 02:00, weekdays at 1.1 and
+    /// weekends at 0.75
+    pub fn business() -> Self {
+        let daily = std::array::from_fn(|hour| {
+            let phase = (hour as f64 - 14.0) / 24.0 * std::f64::consts::TAU;
+            1.0 + 0.6 * phase.cos()
+        });
+        Self {
+            daily,
+            weekly: [1.1, 1.1, 1.1, 1.1, 1.1, 0.75, 0.75],
+            ..Default::default()
+        }
+    }
+
+    /// Add holidays, as days since the Unix epoch
+    pub fn with_holidays(mut self, days: impl IntoIterator<Item = u64>) -> Self {
+        self.holidays.extend(days);
+        self
+    }
+
+    /// Rate multiplier at `timestamp_ns` (UTC)
+    pub fn factor(&self, timestamp_ns: u64) -> f64 {
+        let secs = timestamp_ns / 1_000_000_000;
+        let day = secs / SECS_PER_DAY;
+        let hours = (secs % SECS_PER_DAY) as f64 / 3_600.0;
+        let hour = hours as usize;
+        let within = hours - hour as f64;
+        let daily = self.daily[hour] * (1.0 - within) + self.daily[(hour + 1) % 24] * within;
+
+        let day_factor = if self.holidays.contains(&day) {
+            self.holiday_factor
+        } else {
+            // 1970-01-01 was a Thursday; shift so Monday is 0
+            self.weekly[((day + 3) % 7) as usize]
+        };
+        daily * day_factor
+    }
+}
+
+/// How a tick's arrivals scatter around its expected rate
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Burstiness {
+    /// Normal jitter with a standard deviation of 10% of the rate
+    #[default]
+    Jitter,
+    /// Independent arrivals: a Poisson count at the expected rate
+    Poisson,
+    /// Heavy-tailed bursts: the rate scaled by a Pareto factor with mean 1.
+    /// `alpha` (> 1) is the tail index; lower is burstier.
+    Pareto { alpha: f64 },
+}
+
+impl Burstiness {
+    /// Rate drawn for a tick of `seconds` expecting `rate` arrivals per
+    /// second, and the tick's arrival count
+    pub fn arrivals<R: Rng + ?Sized>(&self, rate: f64, seconds: f64, rng: &mut R) -> (f64, usize) {
+        let rate = rate.max(0.0);
+        let drawn = match *self {
+            Burstiness::Jitter => Normal::new(rate, rate * 0.1).unwrap().sample(rng).max(0.0),
+            Burstiness::Poisson => {
+                let count = match Poisson::new(rate * seconds) {
+                    Ok(poisson) => poisson.sample(rng) as usize,
+                    Err(_) => 0,
+                };
+                return (rate, count);
+            }
+            Burstiness::Pareto { alpha } => {
+                let alpha = alpha.max(1.01);
+                let scale = (alpha - 1.0) / alpha;
+                rate * Pareto::new(scale, alpha).unwrap().sample(rng)
+            }
+        };
+        (drawn, (drawn * seconds).round() as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    const HOUR_NS: u64 = 3_600 * 1_000_000_000;
+    const DAY_NS: u64 = 24 * HOUR_NS;
+    /// Day 6 since the epoch is a Wednesday, day 9 a Saturday
+    const WEDNESDAY: u64 = 6 * DAY_NS;
+    const SATURDAY: u64 = 9 * DAY_NS;
+
+    #[test]
+    fn test_default_is_flat() {
+        let flat = Seasonality::default();
+        for hour in 0..24 * 7 {
+            assert_eq!(flat.factor(hour * HOUR_NS + HOUR_NS / 3), 1.0);
+        }
+    }
+
+    #[test]
+    fn test_business_curve_follows_day_and_week() {
+        let business = Seasonality::business();
+        let peak = business.factor(WEDNESDAY + 14 * HOUR_NS);
+        let trough = business.factor(WEDNESDAY + 2 * HOUR_NS);
+        assert!((peak - 1.6 * 1.1).abs() < 1e-9);
+        assert!((trough - 0.4 * 1.1).abs() < 1e-9);
+        // Halfway through an hour is halfway between its neighbours
+        let half = business.factor(WEDNESDAY + 13 * HOUR_NS + HOUR_NS / 2);
+        let expected = (business.factor(WEDNESDAY + 13 * HOUR_NS) + peak) / 2.0;
+        assert!((half - expected).abs() < 1e-9);
+
+        let weekend = business.factor(SATURDAY + 14 * HOUR_NS);
+        assert!((weekend / peak - 0.75 / 1.1).abs() < 1e-9);
+
+        // Averages 1 over a week
+        let minutes = 7 * 24 * 60;
+        let mean = (0..minutes)
+            .map(|m| business.factor(m * 60 * 1_000_000_000))
+            .sum::<f64>()
+            / minutes as f64;
+        assert!((mean - 1.0).abs() < 1e-3, "{}", mean);
+
+        let holiday = Seasonality::business().with_holidays([6]);
+        assert!((holiday.factor(WEDNESDAY + 14 * HOUR_NS) - 1.6 * 0.4).abs() < 1e-9);
+        assert_eq!(holiday.factor(SATURDAY), business.factor(SATURDAY));
+    }
+
+    #[test]
+    fn test_burstiness_keeps_the_mean() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let ticks = 20_000;
+        let stats = |burstiness: Burstiness, rng: &mut StdRng| {
+            let counts: Vec<f64> = (0..ticks)
+                .map(|_| burstiness.arrivals(100.0, 1.0, rng).1 as f64)
+                .collect();
+            let mean = counts.iter().sum::<f64>() / ticks as f64;
+            let var = counts.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / ticks as f64;
+            (mean, var.sqrt() / mean)
+        };
+
+        let (jitter_mean, jitter_cv) = stats(Burstiness::Jitter, &mut rng);
+        let (poisson_mean, poisson_cv) = stats(Burstiness::Poisson, &mut rng);
+        let (pareto_mean, pareto_cv) = stats(Burstiness::Pareto { alpha: 2.5 }, &mut rng);
+        for mean in [jitter_mean, poisson_mean, pareto_mean] {
+            assert!((mean - 100.0).abs() < 2.0, "{}", mean);
+        }
+        assert!((jitter_cv - 0.1).abs() < 0.01);
+        assert!((poisson_cv - 0.1).abs() < 0.01);
+        assert!(pareto_cv > 3.0 * jitter_cv, "{}", pareto_cv);
+    }
+}
@@ -0,0 +1,163 @@
+//! HTTP Server for Interactive Mode (feature `server`)
+//!
+//! Serves the `api` routes over HTTP with `tiny_http` while a background
+//! thread ticks the engine in real time: each tick advances simulated time by
+//! the wall-clock time since the previous one, so clients see a live stream
+//! they can start, pause, and inject anomalies into.
+//!
+//! `GET /signals/stream` is held open on its own thread as a Server-Sent
+//! Events feed of the anomaly signals detection produces each tick.
+
+use crate::api::{SharedState, SignalFilter, dispatch, query_pairs, signal_events};
+use crate::engine::EngineState;
+use std::io::Write;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Comment line sent on idle SSE streams so proxies keep them open
+const SSE_KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// Bind to `config.host:config.port`, start the ticker, and serve until the
+/// process exits
+pub fn serve(state: SharedState) -> Result<(), String> {
+    let (addr, cors) = {
+        let state = state.lock().unwrap();
+        (
+            format!("{}:{}", state.config.host, state.config.port),
+            state.config.cors_enabled,
+        )
+    };
+    let server = Server::http(&addr).map_err(|e| format!("Failed to bind {}: {}", addr, e))?;
+    spawn_ticker(state.clone());
+
+    for mut request in server.incoming_requests() {
+        let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+        if *request.method() == Method::Get && path == "/signals/stream" {
+            match SignalFilter::from_query(query) {
+                Ok(filter) => {
+                    let (state, query) = (state.clone(), query.to_string());
+                    thread::spawn(move || stream_signals(request, state, filter, &query, cors));
+                    continue;
+                }
+                Err(e) => {
+                    let error = serde_json::json!({"success": false, "error": e});
+                    let _ = request.respond(
+                        Response::from_string(error.to_string())
+                            .with_status_code(400)
+                            .with_header(header("Content-Type", "application/json")),
+                    );
+                    continue;
+                }
+            }
+        }
+
+        let mut body = String::new();
+        let (status, json) = if *request.method() == Method::Options {
+            (204, String::new())
+        } else if let Err(e) = request.as_reader().read_to_string(&mut body) {
+            let error = serde_json::json!({"success": false, "error": e.to_string()});
+            (400, error.to_string())
+        } else {
+            let method = request.method().as_str().to_string();
+            dispatch(&state, &method, request.url(), &body)
+        };
+
+        let mut response = Response::from_string(json)
+            .with_status_code(status)
+            .with_header(header("Content-Type", "application/json"));
+        if cors {
+            response.add_header(header("Access-Control-Allow-Origin", "*"));
+            response.add_header(header("Access-Control-Allow-Methods", "GET, POST, OPTIONS"));
+            response.add_header(header("Access-Control-Allow-Headers", "Content-Type"));
+        }
+        // A client hanging up mid-response is not a server error
+        let _ = request.respond(response);
+    }
+    Ok(())
+}
+
+/// Write buffered signals to an SSE client as detection produces them, until
+/// it disconnects
+///
+/// Resumes after `Last-Event-ID` (or `?after=`) when given; otherwise starts
+/// with signals produced from now on.
+fn stream_signals(
+    request: Request,
+    state: SharedState,
+    filter: SignalFilter,
+    query: &str,
+    cors: bool,
+) {
+    let resume = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Last-Event-ID"))
+        .map(|h| h.value.as_str().to_string())
+        .or_else(|| {
+            query_pairs(query)
+                .find(|(key, _)| *key == "after")
+                .map(|(_, v)| v.to_string())
+        });
+    let mut after = match resume.and_then(|id| id.parse::<u64>().ok()) {
+        Some(id) => id,
+        None => state.lock().unwrap().detection.last_id(),
+    };
+
+    let mut writer = request.into_writer();
+    let mut head = String::from(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n",
+    );
+    if cors {
+        head.push_str("Access-Control-Allow-Origin: *\r\n");
+    }
+    head.push_str("\r\n");
+    if writer.write_all(head.as_bytes()).is_err() || writer.flush().is_err() {
+        return;
+    }
+
+    let mut last_write = Instant::now();
+    loop {
+        let interval = state.lock().unwrap().config.tick_interval_ms.max(1);
+        thread::sleep(Duration::from_millis(interval));
+
+        let (last_id, mut events) = signal_events(&state, after, &filter);
+        after = last_id;
+        if events.is_empty() {
+            if last_write.elapsed() < SSE_KEEPALIVE {
+                continue;
+            }
+            events = ": keep-alive\n\n".to_string();
+        }
+        // A write error means the client went away
+        if writer.write_all(events.as_bytes()).is_err() || writer.flush().is_err() {
+            return;
+        }
+        last_write = Instant::now();
+    }
+}
+
+/// Tick the engine every `config.tick_interval_ms` while it is running,
+/// keeping the latest batch for the dashboard
+pub fn spawn_ticker(state: SharedState) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut last = Instant::now();
+        loop {
+            let interval = state.lock().unwrap().config.tick_interval_ms.max(1);
+            thread::sleep(Duration::from_millis(interval));
+
+            let now = Instant::now();
+            let delta_ns = now.duration_since(last).as_nanos() as u64;
+            last = now;
+
+            let mut state = state.lock().unwrap();
+            if state.engine.state() == EngineState::Running {
+                state.tick(delta_ns);
+            }
+        }
+    })
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("static header is valid")
+}
@@ -0,0 +1,426 @@
+//! Service Topology
+//!
+//! A microservice dependency graph that requests are walked through.
+//! `NormalTraffic` sends each request in at the entry service and follows
+//! the call edges, so one request yields one log per hop sharing a trace id,
+//! with per-hop latency that includes the time spent downstream. A failing
+//! or degraded callee fails its callers too, which is how errors show up in
+//! production. `CascadeFailure` follows the same edges backwards from the
+//! failed service.
+//!
+//! ```text
+//!   api-gateway ──► auth-service ──► db-cluster
+//!        │               ▲               ▲
+//!        ├──► payment-service ───────────┤
+//!        ├──► inventory-service ─────────┘
+//!        │               ▲
+//!        └──► recommendation-engine
+//! ```
+
+use crate::scenarios::next_trace_and_span_ids;
+use rand::Rng;
+use rand_distr::{Distribution, LogNormal};
+use std::collections::VecDeque;
+
+/// Calls deeper than this are not followed (guards against cyclic graphs)
+const MAX_DEPTH: usize = 16;
+/// Log-normal spread of a service's own processing time
+const DEFAULT_SIGMA: f64 = 0.5;
+
+/// One service in the graph
+#[derive(Debug, Clone)]
+pub struct Service {
+    pub name: String,
+    /// Median time (ms) spent in the service itself, excluding calls
+    pub median_ms: f64,
+    /// Log-normal sigma of that time
+    pub sigma: f64,
+    /// Chance a call fails in the service itself
+    pub error_rate: f64,
+    /// Extra failure chance while degraded
+    pub degraded_error_rate: f64,
+    /// Latency multiplier while degraded
+    pub degraded_latency: f64,
+}
+
+impl Service {
+    fn new(name: &str, median_ms: f64, error_rate: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            median_ms,
+            sigma: DEFAULT_SIGMA,
+            error_rate,
+            degraded_error_rate: 0.0,
+            degraded_latency: 1.0,
+        }
+    }
+}
+
+/// Call from one service to another
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    to: usize,
+    /// Chance the call is made for a given request
+    probability: f64,
+}
+
+/// How a hop failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HopError {
+    /// The service itself failed
+    Internal,
+    /// A service it called failed
+    Dependency,
+}
+
+impl HopError {
+    pub fn status_code(self) -> i64 {
+        match self {
+            Self::Internal => 500,
+            Self::Dependency => 503,
+        }
+    }
+
+    pub fn error_type(self) -> &'static str {
+        match self {
+            Self::Internal => "InternalServerError",
+            Self::Dependency => "DependencyFailure",
+        }
+    }
+}
+
+/// One service call of a request
+#[derive(Debug, Clone)]
+pub struct Hop {
+    pub service: String,
+    pub span_id: String,
+    /// Span of the calling hop; `None` at the entry service
+    pub parent_span_id: Option<String>,
+    /// Service of the calling hop
+    pub caller: Option<String>,
+    /// Calls from the entry service
+    pub depth: usize,
+    /// Total latency, including the calls made
+    pub latency_ms: f64,
+    pub error: Option<HopError>,
+}
+
+/// A request walked through the graph
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub trace_id: String,
+    /// Hops in completion order: callees before their callers, entry last
+    pub hops: Vec<Hop>,
+}
+
+impl Request {
+    /// The entry service's hop
+    pub fn root(&self) -> &Hop {
+        self.hops.last().expect("a request has an entry hop")
+    }
+}
+
+/// Directed service dependency graph
+#[derive(Debug, Clone)]
+pub struct Topology {
+    services: Vec<Service>,
+    calls: Vec<Vec<Edge>>,
+    entry: usize,
+}
+
+impl Default for Topology {
+    /// The six services every built-in scenario logs as
+    fn default() -> Self {
+        Self::new("api-gateway", 5.0, 0.001)
+            .service("auth-service", 8.0, 0.002)
+            .service("payment-service", 20.0, 0.003)
+            .service("inventory-service", 12.0, 0.002)
+            .service("recommendation-engine", 25.0, 0.002)
+            .service("db-cluster", 6.0, 0.001)
+            .call("api-gateway", "auth-service", 1.0)
+            .call("api-gateway", "inventory-service", 0.5)
+            .call("api-gateway", "recommendation-engine", 0.3)
+            .call("api-gateway", "payment-service", 0.2)
+            .call("payment-service", "auth-service", 1.0)
+            .call("payment-service", "db-cluster", 1.0)
+            .call("inventory-service", "db-cluster", 1.0)
+            .call("recommendation-engine", "inventory-service", 0.5)
+            .call("auth-service", "db-cluster", 0.3)
+    }
+}
+
+impl Topology {
+    /// A graph with only its entry service
+    pub fn new(entry: &str, median_ms: f64, error_rate: f64) -> Self {
+        Self {
+            services: vec![Service::new(entry, median_ms, error_rate)],
+            calls: vec![Vec::new()],
+            entry: 0,
+        }
+    }
+
+    /// Add a service, or replace the latency and error rate of an existing one
+    pub fn service(mut self, name: &str, median_ms: f64, error_rate: f64) -> Self {
+        match self.index(name) {
+            Some(i) => {
+                self.services[i].median_ms = median_ms;
+                self.services[i].error_rate = error_rate;
+            }
+            None => {
+                self.services
+                    .push(Service::new(name, median_ms, error_rate));
+                self.calls.push(Vec::new());
+            }
+        }
+        self
+    }
+
+    /// Make `from` call `to` on `probability` of its requests
+    ///
+    /// Unknown services are added with the entry service's latency and error rate.
+    pub fn call(mut self, from: &str, to: &str, probability: f64) -> Self {
+        let from = self.index_or_insert(from);
+        let to = self.index_or_insert(to);
+        let probability = probability.clamp(0.0, 1.0);
+        match self.calls[from].iter_mut().find(|e| e.to == to) {
+            Some(edge) => edge.probability = probability,
+            None => self.calls[from].push(Edge { to, probability }),
+        }
+        self
+    }
+
+    fn index_or_insert(&mut self, name: &str) -> usize {
+        if let Some(i) = self.index(name) {
+            return i;
+        }
+        let entry = &self.services[self.entry];
+        let service = Service::new(name, entry.median_ms, entry.error_rate);
+        self.services.push(service);
+        self.calls.push(Vec::new());
+        self.services.len() - 1
+    }
+
+    fn index(&self, name: &str) -> Option<usize> {
+        self.services.iter().position(|s| s.name == name)
+    }
+
+    pub fn entry(&self) -> &str {
+        &self.services[self.entry].name
+    }
+
+    pub fn services(&self) -> &[Service] {
+        &self.services
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Service> {
+        self.index(name).map(|i| &self.services[i])
+    }
+
+    /// Services `name` calls
+    pub fn callees(&self, name: &str) -> Vec<&str> {
+        self.index(name)
+            .map(|i| {
+                self.calls[i]
+                    .iter()
+                    .map(|e| self.services[e.to].name.as_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Services calling `name`
+    pub fn callers(&self, name: &str) -> Vec<&str> {
+        let Some(target) = self.index(name) else {
+            return Vec::new();
+        };
+        (0..self.services.len())
+            .filter(|&i| self.calls[i].iter().any(|e| e.to == target))
+            .map(|i| self.services[i].name.as_str())
+            .collect()
+    }
+
+    /// `name` and every service that depends on it, directly or through
+    /// others, with its distance in calls; nearest first, `name` at 0
+    pub fn dependents(&self, name: &str) -> Vec<(String, usize)> {
+        let Some(root) = self.index(name) else {
+            return Vec::new();
+        };
+        let mut distance = vec![None; self.services.len()];
+        distance[root] = Some(0);
+        let mut queue = VecDeque::from([root]);
+        let mut order = Vec::new();
+        while let Some(i) = queue.pop_front() {
+            let d = distance[i].unwrap_or(0);
+            order.push((self.services[i].name.clone(), d));
+            for (caller, calls) in self.calls.iter().enumerate() {
+                if distance[caller].is_none() && calls.iter().any(|e| e.to == i) {
+                    distance[caller] = Some(d + 1);
+                    queue.push_back(caller);
+                }
+            }
+        }
+        order
+    }
+
+    /// Degrade `name`: calls fail with an extra `error_rate` and take
+    /// `latency_factor` times as long. Returns false for unknown services.
+    pub fn degrade(&mut self, name: &str, error_rate: f64, latency_factor: f64) -> bool {
+        let Some(i) = self.index(name) else {
+            return false;
+        };
+        self.services[i].degraded_error_rate = error_rate.clamp(0.0, 1.0);
+        self.services[i].degraded_latency = latency_factor.max(1.0);
+        true
+    }
+
+    /// Clear every degradation
+    pub fn restore(&mut self) {
+        for service in &mut self.services {
+            service.degraded_error_rate = 0.0;
+            service.degraded_latency = 1.0;
+        }
+    }
+
+    /// Walk one request from the entry service
+    pub fn request<R: Rng + ?Sized>(&self, rng: &mut R) -> Request {
+        let (trace_id, span_id) = next_trace_and_span_ids(rng);
+        let mut hops = Vec::new();
+        let mut path = vec![self.entry];
+        self.visit(rng, self.entry, span_id, None, &mut path, &mut hops);
+        Request { trace_id, hops }
+    }
+
+    /// Call `index`, returning its latency and error after its callees' hops
+    fn visit<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        index: usize,
+        span_id: String,
+        parent: Option<(&str, usize)>,
+        path: &mut Vec<usize>,
+        hops: &mut Vec<Hop>,
+    ) -> (f64, Option<HopError>) {
+        let service = &self.services[index];
+        let depth = path.len() - 1;
+        let self_time = LogNormal::new(service.median_ms.max(0.01).ln(), service.sigma)
+            .map(|d| d.sample(rng))
+            .unwrap_or(service.median_ms);
+        let mut latency = self_time * service.degraded_latency;
+        let fail_chance = (service.error_rate + service.degraded_error_rate).min(1.0);
+        let mut error = rng.random_bool(fail_chance).then_some(HopError::Internal);
+
+        // Calls run in order and stop at the first failure
+        if error.is_none() && depth < MAX_DEPTH {
+            for edge in &self.calls[index] {
+                if path.contains(&edge.to) || !rng.random_bool(edge.probability) {
+                    continue;
+                }
+                let (_, child_span) = next_trace_and_span_ids(rng);
+                path.push(edge.to);
+                let (child_latency, child_error) = self.visit(
+                    rng,
+                    edge.to,
+                    child_span,
+                    Some((&span_id, index)),
+                    path,
+                    hops,
+                );
+                path.pop();
+                latency += child_latency;
+                if child_error.is_some() {
+                    error = Some(HopError::Dependency);
+                    break;
+                }
+            }
+        }
+
+        hops.push(Hop {
+            service: service.name.clone(),
+            span_id: span_id.clone(),
+            parent_span_id: parent.map(|(span, _)| span.to_string()),
+            caller: parent.map(|(_, i)| self.services[i].name.clone()),
+            depth,
+            latency_ms: latency,
+            error,
+        });
+        (latency, error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_request_shares_trace_and_links_spans() {
+        let topology = Topology::default();
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut multi_hop = 0;
+        for _ in 0..200 {
+            let request = topology.request(&mut rng);
+            let root = request.root();
+            assert_eq!(root.service, "api-gateway");
+            assert!(root.parent_span_id.is_none());
+            for hop in &request.hops[..request.hops.len() - 1] {
+                let parent = request
+                    .hops
+                    .iter()
+                    .find(|h| Some(&h.span_id) == hop.parent_span_id.as_ref())
+                    .expect("parent hop in the same request");
+                assert_eq!(Some(&parent.service), hop.caller.as_ref());
+                assert_eq!(parent.depth + 1, hop.depth);
+                assert!(parent.latency_ms >= hop.latency_ms);
+            }
+            if request.hops.len() > 2 {
+                multi_hop += 1;
+            }
+        }
+        assert!(multi_hop > 50, "{multi_hop}");
+    }
+
+    #[test]
+    fn test_degraded_service_fails_its_callers() {
+        let mut topology = Topology::default();
+        assert!(topology.degrade("db-cluster", 1.0, 5.0));
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..100 {
+            let request = topology.request(&mut rng);
+            for hop in &request.hops {
+                if hop.service == "db-cluster" {
+                    assert_eq!(hop.error, Some(HopError::Internal));
+                }
+            }
+            let reached_db = request.hops.iter().any(|h| h.service == "db-cluster");
+            if reached_db {
+                assert_eq!(request.root().error, Some(HopError::Dependency));
+            }
+        }
+
+        topology.restore();
+        let failed = (0..200)
+            .filter(|_| topology.request(&mut rng).root().error.is_some())
+            .count();
+        assert!(failed < 20, "{failed}");
+    }
+
+    #[test]
+    fn test_dependents_by_distance() {
+        let topology = Topology::default();
+        let dependents = topology.dependents("db-cluster");
+        assert_eq!(dependents[0], ("db-cluster".to_string(), 0));
+        assert_eq!(dependents.len(), topology.services().len());
+        let distance = |name: &str| dependents.iter().find(|(s, _)| s == name).unwrap().1;
+        assert_eq!(distance("auth-service"), 1);
+        assert_eq!(distance("api-gateway"), 2);
+        assert_eq!(distance("recommendation-engine"), 2);
+        assert!(dependents.windows(2).all(|w| w[0].1 <= w[1].1));
+
+        assert_eq!(
+            topology.callers("auth-service"),
+            ["api-gateway", "payment-service"]
+        );
+        assert!(topology.dependents("unknown").is_empty());
+    }
+}
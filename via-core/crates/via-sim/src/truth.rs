@@ -0,0 +1,223 @@
+//! Ground-Truth Sidecar Files
+//!
+//! `via-sim generate --output logs.jsonl` also writes `logs.truth.json`: every
+//! anomaly window of the run with the services and entities its logs came
+//! from, so detectors outside this repo can be scored offline against the
+//! same dataset without parsing the per-log ground-truth fields.
+
+use crate::compression;
+use crate::core::{GroundTruth, LogRecord};
+use crate::entity::EntityKey;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Suffix replacing the log file's extension
+pub const TRUTH_SUFFIX: &str = "truth.json";
+
+/// Distinct entities listed per window
+const MAX_WINDOW_ENTITIES: usize = 10_000;
+
+/// One injected anomaly
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TruthWindow {
+    pub anomaly_id: String,
+    /// Scenario that produced the anomaly
+    pub scenario: String,
+    pub start_time_ns: u64,
+    pub end_time_ns: u64,
+    /// Logs marked with this anomaly id
+    pub log_count: u64,
+    /// `service.name`s of those logs, sorted
+    pub services: Vec<String>,
+    /// Rendered `entity_key` of those logs, sorted (up to 10,000)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entities: Vec<String>,
+    /// Missing-data window: positive even with no logs of its own
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub absence: bool,
+    /// Baseline logs the anomaly kept from being written
+    #[serde(default)]
+    pub suppressed_log_count: u64,
+}
+
+/// Contents of a `*.truth.json` sidecar
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TruthFile {
+    /// Baseline scenario
+    pub scenario: String,
+    pub seed: u64,
+    /// Simulated span of the logs
+    pub start_time_ns: u64,
+    pub end_time_ns: u64,
+    pub total_logs: u64,
+    pub anomaly_logs: u64,
+    /// Template the windows' `entities` were rendered with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity_key: Option<String>,
+    /// Windows by start time
+    pub windows: Vec<TruthWindow>,
+}
+
+impl TruthFile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// Sidecar path for a log file: `logs.jsonl` or `logs.jsonl.zst` ->
+/// `logs.truth.json`
+pub fn sidecar_path(output: &Path) -> PathBuf {
+    compression::strip_extension(output).with_extension(TRUTH_SUFFIX)
+}
+
+/// Services and entities seen per anomaly
+#[derive(Default)]
+struct Affected {
+    log_count: u64,
+    services: BTreeSet<String>,
+    entities: BTreeSet<String>,
+}
+
+/// Collects a sidecar while logs are written
+pub struct TruthRecorder {
+    entity_key: Option<EntityKey>,
+    affected: BTreeMap<String, Affected>,
+    start_time_ns: Option<u64>,
+    end_time_ns: u64,
+    total_logs: u64,
+    anomaly_logs: u64,
+}
+
+impl TruthRecorder {
+    /// Record windows, rendering `entity_key` for each anomalous log when given
+    pub fn new(entity_key: Option<EntityKey>) -> Self {
+        Self {
+            entity_key,
+            affected: BTreeMap::new(),
+            start_time_ns: None,
+            end_time_ns: 0,
+            total_logs: 0,
+            anomaly_logs: 0,
+        }
+    }
+
+    pub fn record(&mut self, log: &LogRecord) {
+        let ts: u64 = log.timeUnixNano.parse().unwrap_or(0);
+        self.start_time_ns = Some(self.start_time_ns.map_or(ts, |start| start.min(ts)));
+        self.end_time_ns = self.end_time_ns.max(ts);
+        self.total_logs += 1;
+        let Some(id) = log.anomalyId.as_ref().filter(|_| log.isGroundTruthAnomaly) else {
+            return;
+        };
+        self.anomaly_logs += 1;
+
+        let affected = self.affected.entry(id.clone()).or_default();
+        affected.log_count += 1;
+        if let Some(service) = log.service_name()
+            && !affected.services.contains(service)
+        {
+            affected.services.insert(service.to_string());
+        }
+        if let Some(key) = &self.entity_key
+            && affected.entities.len() < MAX_WINDOW_ENTITIES
+        {
+            affected.entities.insert(key.entity(log).into_owned());
+        }
+    }
+
+    /// The sidecar for the run's `ground_truth` windows
+    pub fn finish(mut self, scenario: &str, seed: u64, ground_truth: &[GroundTruth]) -> TruthFile {
+        let mut windows: Vec<TruthWindow> = ground_truth
+            .iter()
+            .map(|gt| {
+                let affected = self.affected.remove(&gt.anomaly_id).unwrap_or_default();
+                TruthWindow {
+                    anomaly_id: gt.anomaly_id.clone(),
+                    scenario: gt.anomaly_type.clone(),
+                    start_time_ns: gt.start_time_ns,
+                    end_time_ns: gt.end_time_ns,
+                    log_count: affected.log_count,
+                    services: affected.services.into_iter().collect(),
+                    entities: affected.entities.into_iter().collect(),
+                    absence: gt.absence,
+                    suppressed_log_count: gt.suppressed_log_count,
+                }
+            })
+            .collect();
+        windows.sort_by(|a, b| {
+            (a.start_time_ns, &a.anomaly_id).cmp(&(b.start_time_ns, &b.anomaly_id))
+        });
+
+        TruthFile {
+            scenario: scenario.to_string(),
+            seed,
+            start_time_ns: self.start_time_ns.unwrap_or(0),
+            end_time_ns: self.end_time_ns,
+            total_logs: self.total_logs,
+            anomaly_logs: self.anomaly_logs,
+            entity_key: self.entity_key.map(|key| key.to_string()),
+            windows,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulationEngine;
+
+    #[test]
+    fn test_sidecar_path_replaces_extension() {
+        assert_eq!(
+            sidecar_path(Path::new("out/logs.jsonl")),
+            Path::new("out/logs.truth.json")
+        );
+        assert_eq!(
+            sidecar_path(Path::new("logs")),
+            Path::new("logs.truth.json")
+        );
+        assert_eq!(
+            sidecar_path(Path::new("logs.jsonl.gz")),
+            Path::new("logs.truth.json")
+        );
+    }
+
+    #[test]
+    fn test_recorder_lists_windows_with_services_and_entities() {
+        let mut engine = SimulationEngine::new_deterministic(7);
+        engine.start("normal_traffic");
+        engine.schedule_anomaly("credential_stuffing", 1_000_000_000, 2_000_000_000);
+        engine.schedule_anomaly("memory_leak", 0, 4_000_000_000);
+
+        let mut recorder = TruthRecorder::new(Some("source.ip".parse().unwrap()));
+        let mut ground_truth = Vec::new();
+        for _ in 0..50 {
+            let batch = engine.tick(100_000_000);
+            for log in &batch.logs.resourceLogs[0].scopeLogs[0].logRecords {
+                recorder.record(log);
+            }
+            ground_truth = batch.ground_truth;
+        }
+        let truth = recorder.finish("normal_traffic", 7, &ground_truth);
+
+        assert_eq!(truth.windows.len(), 2);
+        assert_eq!(truth.windows[0].scenario, "Memory Leak");
+        let stuffing = &truth.windows[1];
+        assert_eq!(stuffing.start_time_ns, 1_000_000_000);
+        assert_eq!(stuffing.services, ["auth-service"]);
+        assert!(!stuffing.entities.is_empty());
+        let window_logs: u64 = truth.windows.iter().map(|w| w.log_count).sum();
+        assert_eq!(window_logs, truth.anomaly_logs);
+
+        let json = serde_json::to_string(&truth).unwrap();
+        assert_eq!(serde_json::from_str::<TruthFile>(&json).unwrap(), truth);
+    }
+}